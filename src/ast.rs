@@ -145,6 +145,8 @@ pub struct FieldDecl {
     pub name: String,
     /// Field type
     pub ty: Type,
+    /// Value used when a record literal omits this field
+    pub default: Option<Expr>,
 }
 
 /// Implementation block that adds methods to a type.
@@ -222,6 +224,8 @@ pub struct FunDecl {
     pub name: String,
     /// Whether this is an async function
     pub is_async: bool,
+    /// Marked `@init`: runs once when the module starts, before `main`
+    pub is_init: bool,
     /// Generic type parameters with bounds: `<T: Display, U: Clone>`
     pub type_params: Vec<TypeParam>,
     /// Temporal constraints (e.g., ~tx within ~db)
@@ -327,27 +331,56 @@ impl NodeId {
     pub const DUMMY: NodeId = NodeId(u32::MAX);
 }
 
-/// Expression node: a stable id plus the expression variant.
+/// Byte range of the source text an expression was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// The text this span covers in `source`, if it lies within it.
+    pub fn slice<'a>(&self, source: &'a str) -> Option<&'a str> {
+        source.get(self.start..self.end)
+    }
+}
+
+/// Expression node: a stable id, its source span, and the expression variant.
 ///
-/// Node ids are identity metadata, not structure. `PartialEq` therefore
-/// compares only `kind`, so structural AST comparisons (e.g. parser
-/// tests) are independent of numbering state.
+/// Node ids and spans are identity metadata, not structure. `PartialEq`
+/// therefore compares only `kind`, so structural AST comparisons (e.g.
+/// parser tests) are independent of numbering state and source layout.
 #[derive(Debug, Clone)]
 pub struct Expr {
     /// Stable node id (`NodeId::DUMMY` until numbering)
     pub id: NodeId,
+    /// Source range within the file passed to `parse_program`; `None` for
+    /// nodes synthesized after parsing
+    pub span: Option<Span>,
     /// The expression variant
     pub kind: ExprKind,
 }
 
 impl Expr {
-    /// Construct an unnumbered expression node.
+    /// Construct an unnumbered expression node without a source span.
     pub fn new(kind: ExprKind) -> Self {
         Expr {
             id: NodeId::DUMMY,
+            span: None,
             kind,
         }
     }
+
+    /// Whether this is a literal zero, possibly negated, such as `0`,
+    /// `-0.0` or `0.0d`.
+    pub fn is_constant_zero(&self) -> bool {
+        match &self.kind {
+            ExprKind::IntLit(0) | ExprKind::DecimalLit(0) => true,
+            ExprKind::FloatLit(value) => *value == 0.0,
+            ExprKind::Unary(unary) if unary.op == UnaryOp::Neg => unary.expr.is_constant_zero(),
+            _ => false,
+        }
+    }
 }
 
 impl PartialEq for Expr {
@@ -356,6 +389,28 @@ impl PartialEq for Expr {
     }
 }
 
+/// Fractional digits kept by the fixed-point `Decimal` type.
+pub const DECIMAL_PLACES: u32 = 4;
+
+/// `Decimal` values are stored as `i64` multiples of `1 / DECIMAL_SCALE`,
+/// so `1.99d` is stored as `19_900`.
+pub const DECIMAL_SCALE: i64 = 10_i64.pow(DECIMAL_PLACES);
+
+/// Render a scaled `Decimal` value as source text without the `d` suffix,
+/// e.g. `19_900` as `1.99`.
+pub fn format_decimal(scaled: i64) -> String {
+    let sign = if scaled < 0 { "-" } else { "" };
+    let magnitude = scaled.unsigned_abs();
+    let scale = DECIMAL_SCALE as u64;
+    let whole = magnitude / scale;
+    let fraction = magnitude % scale;
+    if fraction == 0 {
+        return format!("{}{}", sign, whole);
+    }
+    let digits = format!("{:0width$}", fraction, width = DECIMAL_PLACES as usize);
+    format!("{}{}.{}", sign, whole, digits.trim_end_matches('0'))
+}
+
 /// Expression variants in the AST.
 ///
 /// Expressions are the core computational elements of Restrict Language.
@@ -367,6 +422,8 @@ pub enum ExprKind {
     IntLit(i64),
     /// Floating-point literal (e.g., `3.14`, `2.5e-10`)
     FloatLit(f64),
+    /// Decimal literal (e.g., `1.99d`), scaled by [`DECIMAL_SCALE`]
+    DecimalLit(i64),
     /// String literal (e.g., `"hello"`, `r"raw string"`)
     StringLit(String),
     /// Character literal (e.g., `'a'`, `'\n'`)
@@ -395,6 +452,10 @@ pub enum ExprKind {
     Then(ThenExpr),
     /// While loop
     While(WhileExpr),
+    /// Loop that runs while an Option/Result matches a pattern
+    WhileLet(WhileLetExpr),
+    /// Unconditional loop (`loop { ... }`) left through `break`
+    Loop(BlockExpr),
     /// Pattern matching
     Match(MatchExpr),
 
@@ -429,6 +490,8 @@ pub enum ExprKind {
     // Field access
     /// Field access (e.g., `point.x`)
     FieldAccess(Box<Expr>, String),
+    /// In-place field update on a `mut` record binding (e.g., `point.x <- 5`)
+    FieldUpdate(FieldUpdateExpr),
 
     // List literal
     /// List literal (e.g., `[1, 2, 3]`)
@@ -450,6 +513,9 @@ pub enum ExprKind {
     Ok(Box<Expr>),
     /// Err variant of Result type
     Err(Box<Expr>),
+    /// Postfix `?`: unwraps a `Some`/`Ok`, or returns the `None`/`Err` from
+    /// the enclosing function
+    Try(Box<Expr>),
 
     // Lambda expression
     /// Anonymous function (e.g., `|x| x + 1`)
@@ -582,6 +648,26 @@ pub struct WhileExpr {
     pub body: BlockExpr,
 }
 
+/// Loop that re-evaluates an `Option` or `Result` each iteration and runs
+/// the body while the value matches the pattern.
+///
+/// # Example
+///
+/// ```restrict
+/// stack |> pop while Some(item) {
+///     item |> println
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhileLetExpr {
+    /// Pattern bound on each iteration; the loop exits when it fails to match
+    pub pattern: Pattern,
+    /// Expression evaluated before each iteration
+    pub expr: Box<Expr>,
+    /// Loop body
+    pub body: BlockExpr,
+}
+
 /// Pattern matching expression.
 ///
 /// # Example
@@ -605,6 +691,9 @@ pub struct MatchExpr {
 pub struct MatchArm {
     /// Pattern to match
     pub pattern: Pattern,
+    /// Optional `if` guard; the arm is taken only when the pattern matches
+    /// and the guard evaluates to `true`
+    pub guard: Option<Expr>,
     /// Expression to evaluate if pattern matches
     pub body: BlockExpr,
 }
@@ -645,6 +734,59 @@ pub enum Pattern {
     ListCons(Box<Pattern>, Box<Pattern>),
     /// Exact list pattern `[a, b, c]`
     ListExact(Vec<Box<Pattern>>),
+    /// `name @ pattern`: binds the whole matched value to `name` while also
+    /// matching it against the inner pattern
+    Binding(String, Box<Pattern>),
+    /// Integer range pattern: `start..end` excludes `end`, `start..=end`
+    /// includes it
+    Range {
+        start: i64,
+        end: i64,
+        inclusive: bool,
+    },
+}
+
+impl Pattern {
+    /// The same pattern with every `name @` binding removed, leaving only the
+    /// structure that decides whether a value matches.
+    pub fn without_bindings(&self) -> Pattern {
+        let strip = |pattern: &Pattern| Box::new(pattern.without_bindings());
+        match self {
+            Pattern::Binding(_, inner) => inner.without_bindings(),
+            Pattern::Record(name, fields) => Pattern::Record(
+                name.clone(),
+                fields
+                    .iter()
+                    .map(|(field, pattern)| (field.clone(), pattern.without_bindings()))
+                    .collect(),
+            ),
+            Pattern::RecordDestruct {
+                type_name,
+                fields,
+                rest,
+            } => Pattern::RecordDestruct {
+                type_name: type_name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, pattern)| (field.clone(), pattern.without_bindings()))
+                    .collect(),
+                rest: rest.clone(),
+            },
+            Pattern::Some(inner) => Pattern::Some(strip(inner)),
+            Pattern::Ok(inner) => Pattern::Ok(strip(inner)),
+            Pattern::Err(inner) => Pattern::Err(strip(inner)),
+            Pattern::ListCons(head, tail) => Pattern::ListCons(strip(head), strip(tail)),
+            Pattern::ListExact(patterns) => {
+                Pattern::ListExact(patterns.iter().map(|pattern| strip(pattern)).collect())
+            }
+            Pattern::Wildcard
+            | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::Ident(_)
+            | Pattern::None
+            | Pattern::EmptyList => self.clone(),
+        }
+    }
 }
 
 /// Literal values that can appear in patterns and expressions.
@@ -654,6 +796,8 @@ pub enum Literal {
     Int(i64),
     /// Floating-point literal
     Float(f64),
+    /// Decimal literal, scaled by [`DECIMAL_SCALE`]
+    Decimal(i64),
     /// String literal
     String(String),
     /// Character literal
@@ -671,6 +815,7 @@ pub enum Literal {
 /// ```restrict
 /// (1, 2) add
 /// (list, |x| x * 2) map
+/// ("x") identity<String>
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct CallExpr {
@@ -678,6 +823,8 @@ pub struct CallExpr {
     pub function: Box<Expr>,
     /// Arguments to pass
     pub args: Vec<Box<Expr>>,
+    /// Explicit type arguments written after the callee (e.g., `identity<String>`)
+    pub type_args: Vec<Type>,
 }
 
 /// Binary operation expression.
@@ -699,6 +846,26 @@ pub struct BinaryExpr {
     pub right: Box<Expr>,
 }
 
+/// In-place record field update.
+///
+/// Unlike `clone { ... }`, the update writes through to the existing record
+/// and evaluates to `()`. The record must be reached from a `mut` binding.
+///
+/// # Example
+///
+/// ```restrict
+/// point.x <- point.x + 1
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldUpdateExpr {
+    /// Record whose field is updated (e.g., `point` in `point.x <- 1`)
+    pub object: Box<Expr>,
+    /// Updated field name
+    pub field: String,
+    /// New field value
+    pub value: Box<Expr>,
+}
+
 /// Unary operation expression.
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnaryExpr {
@@ -774,6 +941,44 @@ pub enum PipeTarget {
     Expr(Box<Expr>), // For function application
 }
 
+impl PipeExpr {
+    /// The call that `value |> target` stands for when the target is an
+    /// expression.
+    ///
+    /// A call target `(args) f` whose callee takes exactly one parameter
+    /// more than it was given is partially applied, so the piped value is
+    /// prepended to its arguments. Any other target is called with the
+    /// piped value as its only argument. `callee_arity` reports the
+    /// parameter count of a named callee, or `None` when it is unknown.
+    pub fn expr_target_call(
+        &self,
+        callee_arity: impl Fn(&Expr) -> Option<usize>,
+    ) -> Option<CallExpr> {
+        let PipeTarget::Expr(target) = &self.target else {
+            return None;
+        };
+
+        if let ExprKind::Call(call) = &target.kind {
+            if callee_arity(&call.function) == Some(call.args.len() + 1) {
+                let mut args = Vec::with_capacity(call.args.len() + 1);
+                args.push(self.expr.clone());
+                args.extend(call.args.iter().cloned());
+                return Some(CallExpr {
+                    function: call.function.clone(),
+                    args,
+                    type_args: call.type_args.clone(),
+                });
+            }
+        }
+
+        Some(CallExpr {
+            function: target.clone(),
+            args: vec![self.expr.clone()],
+            type_args: Vec::new(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct WithExpr {
     /// Context name (e.g., "Database" in "with Database { ... }")
@@ -817,6 +1022,10 @@ pub enum Stmt {
     Binding(BindDecl),
     Assignment(AssignStmt),
     Expr(Box<Expr>),
+    /// `break` or `break value`: leaves the innermost loop
+    Break(Option<Box<Expr>>),
+    /// `continue`: starts the next iteration of the innermost loop
+    Continue,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -843,6 +1052,34 @@ pub enum Type {
     Generic(String, Vec<Type>),
     Function(Vec<Type>, Box<Type>), // (param_types, return_type)
     Temporal(String, Vec<String>),  // Type with temporal parameters (e.g., File<~f>)
+    Refined(Box<Type>, RangeBound), // Integer type restricted to a range (e.g., Int32 in 0..100)
+}
+
+/// Inclusive bounds of a range-restricted integer type. The exclusive form
+/// `Int32 in 0..100` is stored as `min: 0, max: 99`.
+///
+/// # Example
+///
+/// ```restrict
+/// val percent: Int32 in 0..=100 = 42
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeBound {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl RangeBound {
+    /// Returns true when every value of `other` also lies within `self`.
+    pub fn contains(&self, other: &RangeBound) -> bool {
+        self.min <= other.min && other.max <= self.max
+    }
+}
+
+impl fmt::Display for RangeBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
 }
 
 impl fmt::Display for Type {
@@ -879,6 +1116,7 @@ impl fmt::Display for Type {
                 }
                 write!(f, ">")
             }
+            Type::Refined(base, bound) => write!(f, "{} in {}", base, bound),
         }
     }
 }
@@ -953,6 +1191,16 @@ pub fn assign_node_ids(program: &mut Program) -> u32 {
     next
 }
 
+/// Visit every expression in the program in pre-order, allowing rewrites.
+///
+/// A rewrite of an expression's `kind` is visible to the traversal: the
+/// children of the rewritten node are visited afterwards.
+pub fn visit_program_exprs_mut(program: &mut Program, f: &mut impl FnMut(&mut Expr)) {
+    for decl in &mut program.declarations {
+        visit_top_decl_exprs_mut(decl, f);
+    }
+}
+
 /// Reset every id in this expression subtree to `NodeId::DUMMY`.
 ///
 /// Use this when storing a clone whose later re-checks must not record
@@ -982,7 +1230,8 @@ fn visit_block_exprs_mut(block: &mut BlockExpr, f: &mut impl FnMut(&mut Expr)) {
         match stmt {
             Stmt::Binding(binding) => visit_expr_subtree_mut(&mut binding.value, f),
             Stmt::Assignment(assign) => visit_expr_subtree_mut(&mut assign.value, f),
-            Stmt::Expr(expr) => visit_expr_subtree_mut(expr, f),
+            Stmt::Expr(expr) | Stmt::Break(Some(expr)) => visit_expr_subtree_mut(expr, f),
+            Stmt::Break(None) | Stmt::Continue => {}
         }
     }
     if let Some(expr) = &mut block.expr {
@@ -999,7 +1248,9 @@ fn visit_field_init_exprs_mut(fields: &mut [FieldInit], f: &mut impl FnMut(&mut
     }
 }
 
-fn visit_expr_subtree_mut(expr: &mut Expr, f: &mut impl FnMut(&mut Expr)) {
+/// Visit `expr` and every expression nested in it in pre-order, allowing
+/// rewrites.
+pub(crate) fn visit_expr_subtree_mut(expr: &mut Expr, f: &mut impl FnMut(&mut Expr)) {
     f(expr);
     match &mut expr.kind {
         ExprKind::RecordLit(record) => visit_field_init_exprs_mut(&mut record.fields, f),
@@ -1023,9 +1274,17 @@ fn visit_expr_subtree_mut(expr: &mut Expr, f: &mut impl FnMut(&mut Expr)) {
             visit_expr_subtree_mut(&mut while_expr.condition, f);
             visit_block_exprs_mut(&mut while_expr.body, f);
         }
+        ExprKind::WhileLet(while_let) => {
+            visit_expr_subtree_mut(&mut while_let.expr, f);
+            visit_block_exprs_mut(&mut while_let.body, f);
+        }
+        ExprKind::Loop(body) => visit_block_exprs_mut(body, f),
         ExprKind::Match(match_expr) => {
             visit_expr_subtree_mut(&mut match_expr.expr, f);
             for arm in &mut match_expr.arms {
+                if let Some(guard) = &mut arm.guard {
+                    visit_expr_subtree_mut(guard, f);
+                }
                 visit_block_exprs_mut(&mut arm.body, f);
             }
         }
@@ -1054,6 +1313,10 @@ fn visit_expr_subtree_mut(expr: &mut Expr, f: &mut impl FnMut(&mut Expr)) {
         ExprKind::WithLifetime(with) => visit_block_exprs_mut(&mut with.body, f),
         ExprKind::Block(block) => visit_block_exprs_mut(block, f),
         ExprKind::FieldAccess(inner, _) => visit_expr_subtree_mut(inner, f),
+        ExprKind::FieldUpdate(update) => {
+            visit_expr_subtree_mut(&mut update.object, f);
+            visit_expr_subtree_mut(&mut update.value, f);
+        }
         ExprKind::ListLit(items) | ExprKind::ArrayLit(items) => {
             for item in items {
                 visit_expr_subtree_mut(item, f);
@@ -1066,12 +1329,14 @@ fn visit_expr_subtree_mut(expr: &mut Expr, f: &mut impl FnMut(&mut Expr)) {
         ExprKind::Some(inner)
         | ExprKind::Ok(inner)
         | ExprKind::Err(inner)
+        | ExprKind::Try(inner)
         | ExprKind::Freeze(inner)
         | ExprKind::Await(inner)
         | ExprKind::Spawn(inner) => visit_expr_subtree_mut(inner, f),
         ExprKind::Lambda(lambda) => visit_expr_subtree_mut(&mut lambda.body, f),
         ExprKind::IntLit(_)
         | ExprKind::FloatLit(_)
+        | ExprKind::DecimalLit(_)
         | ExprKind::StringLit(_)
         | ExprKind::CharLit(_)
         | ExprKind::BoolLit(_)
@@ -1110,7 +1375,8 @@ fn collect_block_ids(block: &BlockExpr, ids: &mut Vec<NodeId>) {
         match stmt {
             Stmt::Binding(binding) => collect_expr_ids(&binding.value, ids),
             Stmt::Assignment(assign) => collect_expr_ids(&assign.value, ids),
-            Stmt::Expr(expr) => collect_expr_ids(expr, ids),
+            Stmt::Expr(expr) | Stmt::Break(Some(expr)) => collect_expr_ids(expr, ids),
+            Stmt::Break(None) | Stmt::Continue => {}
         }
     }
     if let Some(expr) = &block.expr {
@@ -1151,9 +1417,17 @@ fn collect_expr_ids(expr: &Expr, ids: &mut Vec<NodeId>) {
             collect_expr_ids(&while_expr.condition, ids);
             collect_block_ids(&while_expr.body, ids);
         }
+        ExprKind::WhileLet(while_let) => {
+            collect_expr_ids(&while_let.expr, ids);
+            collect_block_ids(&while_let.body, ids);
+        }
+        ExprKind::Loop(body) => collect_block_ids(body, ids),
         ExprKind::Match(match_expr) => {
             collect_expr_ids(&match_expr.expr, ids);
             for arm in &match_expr.arms {
+                if let Some(guard) = &arm.guard {
+                    collect_expr_ids(guard, ids);
+                }
                 collect_block_ids(&arm.body, ids);
             }
         }
@@ -1182,6 +1456,10 @@ fn collect_expr_ids(expr: &Expr, ids: &mut Vec<NodeId>) {
         ExprKind::WithLifetime(with) => collect_block_ids(&with.body, ids),
         ExprKind::Block(block) => collect_block_ids(block, ids),
         ExprKind::FieldAccess(inner, _) => collect_expr_ids(inner, ids),
+        ExprKind::FieldUpdate(update) => {
+            collect_expr_ids(&update.object, ids);
+            collect_expr_ids(&update.value, ids);
+        }
         ExprKind::ListLit(items) | ExprKind::ArrayLit(items) => {
             for item in items {
                 collect_expr_ids(item, ids);
@@ -1194,12 +1472,14 @@ fn collect_expr_ids(expr: &Expr, ids: &mut Vec<NodeId>) {
         ExprKind::Some(inner)
         | ExprKind::Ok(inner)
         | ExprKind::Err(inner)
+        | ExprKind::Try(inner)
         | ExprKind::Freeze(inner)
         | ExprKind::Await(inner)
         | ExprKind::Spawn(inner) => collect_expr_ids(inner, ids),
         ExprKind::Lambda(lambda) => collect_expr_ids(&lambda.body, ids),
         ExprKind::IntLit(_)
         | ExprKind::FloatLit(_)
+        | ExprKind::DecimalLit(_)
         | ExprKind::StringLit(_)
         | ExprKind::CharLit(_)
         | ExprKind::BoolLit(_)
@@ -1209,6 +1489,521 @@ fn collect_expr_ids(expr: &Expr, ids: &mut Vec<NodeId>) {
     }
 }
 
+/// Whether two programs have the same structure.
+///
+/// Node ids and source spans are ignored, so programs that differ only in
+/// layout, comments, or other formatting compare equal. This is the
+/// equivalence a formatter round-trip has to preserve.
+pub fn structurally_eq(a: &Program, b: &Program) -> bool {
+    structural_diff(a, b).is_none()
+}
+
+/// The first node where two programs differ, as found by [`structural_diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuralDifference {
+    /// Path from the program root, e.g. `declarations[1].body.expr.right`
+    pub path: String,
+    /// The left program's node at `path`
+    pub left: String,
+    /// The right program's node at `path`
+    pub right: String,
+}
+
+impl fmt::Display for StructuralDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: {} != {}", self.path, self.left, self.right)
+    }
+}
+
+/// Find the first structural difference between two programs, visiting
+/// nodes in source order. Returns `None` exactly when [`structurally_eq`]
+/// holds.
+pub fn structural_diff(a: &Program, b: &Program) -> Option<StructuralDifference> {
+    StructuralDiff::default().program(a, b)
+}
+
+type DiffResult = Option<StructuralDifference>;
+
+/// Walks two ASTs in step. Every node is destructured in full so that a
+/// field added to the AST has to be given a comparison here.
+#[derive(Default)]
+struct StructuralDiff {
+    path: Vec<String>,
+}
+
+impl StructuralDiff {
+    fn differ(&self, left: String, right: String) -> DiffResult {
+        let path = self.path.concat();
+        let path = path.strip_prefix('.').unwrap_or(&path);
+        Some(StructuralDifference {
+            path: if path.is_empty() {
+                "program".to_string()
+            } else {
+                path.to_string()
+            },
+            left,
+            right,
+        })
+    }
+
+    fn at(&mut self, segment: String, f: impl FnOnce(&mut Self) -> DiffResult) -> DiffResult {
+        self.path.push(segment);
+        let result = f(self);
+        self.path.pop();
+        result
+    }
+
+    fn field(&mut self, name: &str, f: impl FnOnce(&mut Self) -> DiffResult) -> DiffResult {
+        self.at(format!(".{}", name), f)
+    }
+
+    /// Compare a child that holds no expressions, so `PartialEq` is already
+    /// structural and `Debug` carries no ids or spans.
+    fn value<T: PartialEq + fmt::Debug>(&mut self, name: &str, a: &T, b: &T) -> DiffResult {
+        if a == b {
+            return None;
+        }
+        self.field(name, |diff| {
+            diff.differ(format!("{:?}", a), format!("{:?}", b))
+        })
+    }
+
+    fn each<T>(
+        &mut self,
+        name: &str,
+        a: &[T],
+        b: &[T],
+        mut f: impl FnMut(&mut Self, &T, &T) -> DiffResult,
+    ) -> DiffResult {
+        self.field(name, |diff| {
+            for (index, (a, b)) in a.iter().zip(b).enumerate() {
+                let result = diff.at(format!("[{}]", index), |diff| f(diff, a, b));
+                if result.is_some() {
+                    return result;
+                }
+            }
+            if a.len() == b.len() {
+                None
+            } else {
+                diff.differ(format!("{} items", a.len()), format!("{} items", b.len()))
+            }
+        })
+    }
+
+    fn optional<T>(
+        &mut self,
+        name: &str,
+        a: &Option<T>,
+        b: &Option<T>,
+        f: impl FnOnce(&mut Self, &T, &T) -> DiffResult,
+    ) -> DiffResult {
+        match (a, b) {
+            (Some(a), Some(b)) => self.field(name, |diff| f(diff, a, b)),
+            (None, None) => None,
+            _ => {
+                let presence = |value: &Option<T>| {
+                    if value.is_some() { "present" } else { "absent" }.to_string()
+                };
+                self.field(name, |diff| diff.differ(presence(a), presence(b)))
+            }
+        }
+    }
+
+    fn program(&mut self, a: &Program, b: &Program) -> DiffResult {
+        let Program {
+            imports,
+            declarations,
+        } = a;
+        self.value("imports", imports, &b.imports).or_else(|| {
+            self.each(
+                "declarations",
+                declarations,
+                &b.declarations,
+                Self::top_decl,
+            )
+        })
+    }
+
+    fn top_decl(&mut self, a: &TopDecl, b: &TopDecl) -> DiffResult {
+        match (a, b) {
+            (TopDecl::Record(a), TopDecl::Record(b)) => self.record_decl(a, b),
+            (TopDecl::Impl(a), TopDecl::Impl(b)) => {
+                let ImplBlock { target, functions } = a;
+                self.value("target", target, &b.target)
+                    .or_else(|| self.each("functions", functions, &b.functions, Self::fun_decl))
+            }
+            (TopDecl::Context(a), TopDecl::Context(b)) => {
+                let ContextDecl { name, fields } = a;
+                self.value("name", name, &b.name)
+                    .or_else(|| self.each("fields", fields, &b.fields, Self::field_decl))
+            }
+            (TopDecl::Function(a), TopDecl::Function(b)) => self.fun_decl(a, b),
+            (TopDecl::Binding(a), TopDecl::Binding(b)) => self.bind_decl(a, b),
+            (TopDecl::Export(a), TopDecl::Export(b)) => {
+                let ExportDecl { item } = a;
+                self.field("item", |diff| diff.top_decl(item, &b.item))
+            }
+            _ => self.differ(describe_top_decl(a), describe_top_decl(b)),
+        }
+    }
+
+    fn record_decl(&mut self, a: &RecordDecl, b: &RecordDecl) -> DiffResult {
+        let RecordDecl {
+            name,
+            type_params,
+            temporal_constraints,
+            fields,
+            frozen,
+            sealed,
+            parent_hash,
+        } = a;
+        self.value("name", name, &b.name)
+            .or_else(|| self.value("type_params", type_params, &b.type_params))
+            .or_else(|| {
+                self.value(
+                    "temporal_constraints",
+                    temporal_constraints,
+                    &b.temporal_constraints,
+                )
+            })
+            .or_else(|| self.each("fields", fields, &b.fields, Self::field_decl))
+            .or_else(|| self.value("frozen", frozen, &b.frozen))
+            .or_else(|| self.value("sealed", sealed, &b.sealed))
+            .or_else(|| self.value("parent_hash", parent_hash, &b.parent_hash))
+    }
+
+    fn field_decl(&mut self, a: &FieldDecl, b: &FieldDecl) -> DiffResult {
+        let FieldDecl { name, ty, default } = a;
+        self.value("name", name, &b.name)
+            .or_else(|| self.value("ty", ty, &b.ty))
+            .or_else(|| self.optional("default", default, &b.default, Self::expr))
+    }
+
+    fn fun_decl(&mut self, a: &FunDecl, b: &FunDecl) -> DiffResult {
+        let FunDecl {
+            name,
+            is_async,
+            is_init,
+            type_params,
+            temporal_constraints,
+            params,
+            return_type,
+            body,
+        } = a;
+        self.value("name", name, &b.name)
+            .or_else(|| self.value("is_async", is_async, &b.is_async))
+            .or_else(|| self.value("is_init", is_init, &b.is_init))
+            .or_else(|| self.value("type_params", type_params, &b.type_params))
+            .or_else(|| {
+                self.value(
+                    "temporal_constraints",
+                    temporal_constraints,
+                    &b.temporal_constraints,
+                )
+            })
+            .or_else(|| self.value("params", params, &b.params))
+            .or_else(|| self.value("return_type", return_type, &b.return_type))
+            .or_else(|| self.field("body", |diff| diff.block(body, &b.body)))
+    }
+
+    fn bind_decl(&mut self, a: &BindDecl, b: &BindDecl) -> DiffResult {
+        let BindDecl {
+            mutable,
+            pattern,
+            type_annotation,
+            value,
+        } = a;
+        self.value("mutable", mutable, &b.mutable)
+            .or_else(|| self.value("pattern", pattern, &b.pattern))
+            .or_else(|| self.value("type_annotation", type_annotation, &b.type_annotation))
+            .or_else(|| self.field("value", |diff| diff.expr(value, &b.value)))
+    }
+
+    fn block(&mut self, a: &BlockExpr, b: &BlockExpr) -> DiffResult {
+        let BlockExpr { statements, expr } = a;
+        self.each("statements", statements, &b.statements, Self::stmt)
+            .or_else(|| self.optional("expr", expr, &b.expr, |diff, a, b| diff.expr(a, b)))
+    }
+
+    fn stmt(&mut self, a: &Stmt, b: &Stmt) -> DiffResult {
+        match (a, b) {
+            (Stmt::Binding(a), Stmt::Binding(b)) => self.bind_decl(a, b),
+            (Stmt::Assignment(a), Stmt::Assignment(b)) => {
+                let AssignStmt { name, value } = a;
+                self.value("name", name, &b.name)
+                    .or_else(|| self.field("value", |diff| diff.expr(value, &b.value)))
+            }
+            (Stmt::Expr(a), Stmt::Expr(b)) => self.expr(a, b),
+            (Stmt::Break(a), Stmt::Break(b)) => {
+                self.optional("value", a, b, |diff, a, b| diff.expr(a, b))
+            }
+            (Stmt::Continue, Stmt::Continue) => None,
+            _ => self.differ(describe_stmt(a), describe_stmt(b)),
+        }
+    }
+
+    /// Expressions compare by kind alone; `id` and `span` are ignored.
+    fn expr(&mut self, a: &Expr, b: &Expr) -> DiffResult {
+        let Expr {
+            id: _,
+            span: _,
+            kind,
+        } = a;
+        self.expr_kind(kind, &b.kind)
+    }
+
+    fn inner(&mut self, a: &Expr, b: &Expr) -> DiffResult {
+        self.field("inner", |diff| diff.expr(a, b))
+    }
+
+    fn expr_kind(&mut self, a: &ExprKind, b: &ExprKind) -> DiffResult {
+        match (a, b) {
+            (ExprKind::RecordLit(a), ExprKind::RecordLit(b)) => self.record_lit(a, b),
+            (ExprKind::Clone(a), ExprKind::Clone(b)) => {
+                let CloneExpr { base, updates } = a;
+                self.field("base", |diff| diff.expr(base, &b.base))
+                    .or_else(|| self.field("updates", |diff| diff.record_lit(updates, &b.updates)))
+            }
+            (ExprKind::PrototypeClone(a), ExprKind::PrototypeClone(b)) => {
+                let PrototypeCloneExpr {
+                    base,
+                    updates,
+                    freeze_immediately,
+                    sealed,
+                } = a;
+                self.value("base", base, &b.base)
+                    .or_else(|| self.field("updates", |diff| diff.record_lit(updates, &b.updates)))
+                    .or_else(|| {
+                        self.value(
+                            "freeze_immediately",
+                            freeze_immediately,
+                            &b.freeze_immediately,
+                        )
+                    })
+                    .or_else(|| self.value("sealed", sealed, &b.sealed))
+            }
+            (ExprKind::Then(a), ExprKind::Then(b)) => {
+                let ThenExpr {
+                    condition,
+                    then_block,
+                    else_ifs,
+                    else_block,
+                } = a;
+                self.field("condition", |diff| diff.expr(condition, &b.condition))
+                    .or_else(|| {
+                        self.field("then_block", |diff| diff.block(then_block, &b.then_block))
+                    })
+                    .or_else(|| {
+                        self.each("else_ifs", else_ifs, &b.else_ifs, |diff, a, b| {
+                            diff.field("condition", |diff| diff.expr(&a.0, &b.0))
+                                .or_else(|| diff.field("block", |diff| diff.block(&a.1, &b.1)))
+                        })
+                    })
+                    .or_else(|| self.optional("else_block", else_block, &b.else_block, Self::block))
+            }
+            (ExprKind::While(a), ExprKind::While(b)) => {
+                let WhileExpr { condition, body } = a;
+                self.field("condition", |diff| diff.expr(condition, &b.condition))
+                    .or_else(|| self.field("body", |diff| diff.block(body, &b.body)))
+            }
+            (ExprKind::WhileLet(a), ExprKind::WhileLet(b)) => {
+                let WhileLetExpr {
+                    pattern,
+                    expr,
+                    body,
+                } = a;
+                self.value("pattern", pattern, &b.pattern)
+                    .or_else(|| self.field("expr", |diff| diff.expr(expr, &b.expr)))
+                    .or_else(|| self.field("body", |diff| diff.block(body, &b.body)))
+            }
+            (ExprKind::Loop(a), ExprKind::Loop(b)) => self.field("body", |diff| diff.block(a, b)),
+            (ExprKind::Match(a), ExprKind::Match(b)) => {
+                let MatchExpr { expr, arms } = a;
+                self.field("expr", |diff| diff.expr(expr, &b.expr))
+                    .or_else(|| self.each("arms", arms, &b.arms, Self::match_arm))
+            }
+            (ExprKind::Call(a), ExprKind::Call(b)) => {
+                let CallExpr {
+                    function,
+                    args,
+                    type_args,
+                } = a;
+                self.field("function", |diff| diff.expr(function, &b.function))
+                    .or_else(|| self.each("args", args, &b.args, |diff, a, b| diff.expr(a, b)))
+                    .or_else(|| self.value("type_args", type_args, &b.type_args))
+            }
+            (ExprKind::Binary(a), ExprKind::Binary(b)) => {
+                let BinaryExpr { left, op, right } = a;
+                self.field("left", |diff| diff.expr(left, &b.left))
+                    .or_else(|| self.value("op", op, &b.op))
+                    .or_else(|| self.field("right", |diff| diff.expr(right, &b.right)))
+            }
+            (ExprKind::Unary(a), ExprKind::Unary(b)) => {
+                let UnaryExpr { op, expr } = a;
+                self.value("op", op, &b.op)
+                    .or_else(|| self.field("expr", |diff| diff.expr(expr, &b.expr)))
+            }
+            (ExprKind::Cast(a), ExprKind::Cast(b)) => {
+                let CastExpr { expr, target } = a;
+                self.field("expr", |diff| diff.expr(expr, &b.expr))
+                    .or_else(|| self.value("target", target, &b.target))
+            }
+            (ExprKind::Pipe(a), ExprKind::Pipe(b)) => {
+                let PipeExpr { expr, op, target } = a;
+                self.field("expr", |diff| diff.expr(expr, &b.expr))
+                    .or_else(|| self.value("op", op, &b.op))
+                    .or_else(|| {
+                        self.field("target", |diff| match (target, &b.target) {
+                            (PipeTarget::Expr(a), PipeTarget::Expr(b)) => diff.expr(a, b),
+                            (PipeTarget::Ident(a), PipeTarget::Ident(b)) if a == b => None,
+                            (a, b) => diff.differ(describe_pipe_target(a), describe_pipe_target(b)),
+                        })
+                    })
+            }
+            (ExprKind::With(a), ExprKind::With(b)) => {
+                let WithExpr {
+                    context_name,
+                    bindings,
+                    body,
+                } = a;
+                self.value("context_name", context_name, &b.context_name)
+                    .or_else(|| self.each("bindings", bindings, &b.bindings, Self::field_init))
+                    .or_else(|| self.field("body", |diff| diff.block(body, &b.body)))
+            }
+            (ExprKind::WithLifetime(a), ExprKind::WithLifetime(b)) => {
+                let WithLifetimeExpr {
+                    lifetime,
+                    anonymous,
+                    constraints,
+                    body,
+                } = a;
+                self.value("lifetime", lifetime, &b.lifetime)
+                    .or_else(|| self.value("anonymous", anonymous, &b.anonymous))
+                    .or_else(|| self.value("constraints", constraints, &b.constraints))
+                    .or_else(|| self.field("body", |diff| diff.block(body, &b.body)))
+            }
+            (ExprKind::Block(a), ExprKind::Block(b)) => self.block(a, b),
+            (ExprKind::FieldAccess(a, a_field), ExprKind::FieldAccess(b, b_field)) => self
+                .field("object", |diff| diff.expr(a, b))
+                .or_else(|| self.value("field", a_field, b_field)),
+            (ExprKind::FieldUpdate(a), ExprKind::FieldUpdate(b)) => {
+                let FieldUpdateExpr {
+                    object,
+                    field,
+                    value,
+                } = a;
+                self.field("object", |diff| diff.expr(object, &b.object))
+                    .or_else(|| self.value("field", field, &b.field))
+                    .or_else(|| self.field("value", |diff| diff.expr(value, &b.value)))
+            }
+            (ExprKind::ListLit(a), ExprKind::ListLit(b))
+            | (ExprKind::ArrayLit(a), ExprKind::ArrayLit(b)) => {
+                self.each("items", a, b, |diff, a, b| diff.expr(a, b))
+            }
+            (ExprKind::RangeLit(a), ExprKind::RangeLit(b)) => {
+                let RangeLit { start, end } = a;
+                self.field("start", |diff| diff.expr(start, &b.start))
+                    .or_else(|| self.field("end", |diff| diff.expr(end, &b.end)))
+            }
+            (ExprKind::Some(a), ExprKind::Some(b))
+            | (ExprKind::Ok(a), ExprKind::Ok(b))
+            | (ExprKind::Err(a), ExprKind::Err(b))
+            | (ExprKind::Try(a), ExprKind::Try(b))
+            | (ExprKind::Freeze(a), ExprKind::Freeze(b))
+            | (ExprKind::Await(a), ExprKind::Await(b))
+            | (ExprKind::Spawn(a), ExprKind::Spawn(b)) => self.inner(a, b),
+            (ExprKind::Lambda(a), ExprKind::Lambda(b)) => {
+                let LambdaExpr { params, body } = a;
+                self.value("params", params, &b.params)
+                    .or_else(|| self.field("body", |diff| diff.expr(body, &b.body)))
+            }
+            // Literals, identifiers and `None` hold no expressions.
+            _ if a == b => None,
+            _ => self.differ(describe_expr_kind(a), describe_expr_kind(b)),
+        }
+    }
+
+    fn record_lit(&mut self, a: &RecordLit, b: &RecordLit) -> DiffResult {
+        let RecordLit { name, fields } = a;
+        self.value("name", name, &b.name)
+            .or_else(|| self.each("fields", fields, &b.fields, Self::field_init))
+    }
+
+    fn field_init(&mut self, a: &FieldInit, b: &FieldInit) -> DiffResult {
+        match (a, b) {
+            (
+                FieldInit::Field { name, value },
+                FieldInit::Field {
+                    name: b_name,
+                    value: b_value,
+                },
+            ) => self
+                .value("name", name, b_name)
+                .or_else(|| self.field("value", |diff| diff.expr(value, b_value))),
+            (FieldInit::Spread(a), FieldInit::Spread(b)) => self.inner(a, b),
+            _ => {
+                let describe = |init: &FieldInit| match init {
+                    FieldInit::Field { name, .. } => format!("field {}", name),
+                    FieldInit::Spread(_) => "spread".to_string(),
+                };
+                self.differ(describe(a), describe(b))
+            }
+        }
+    }
+
+    fn match_arm(&mut self, a: &MatchArm, b: &MatchArm) -> DiffResult {
+        let MatchArm {
+            pattern,
+            guard,
+            body,
+        } = a;
+        self.value("pattern", pattern, &b.pattern)
+            .or_else(|| self.optional("guard", guard, &b.guard, Self::expr))
+            .or_else(|| self.field("body", |diff| diff.block(body, &b.body)))
+    }
+}
+
+fn describe_top_decl(decl: &TopDecl) -> String {
+    match decl {
+        TopDecl::Record(record) => format!("record {}", record.name),
+        TopDecl::Impl(block) => format!("impl {}", block.target),
+        TopDecl::Context(context) => format!("context {}", context.name),
+        TopDecl::Function(function) => format!("fun {}", function.name),
+        TopDecl::Binding(binding) => format!("binding {:?}", binding.pattern),
+        TopDecl::Export(export) => format!("export {}", describe_top_decl(&export.item)),
+    }
+}
+
+fn describe_stmt(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Binding(binding) => format!("binding {:?}", binding.pattern),
+        Stmt::Assignment(assign) => format!("assignment to {}", assign.name),
+        Stmt::Expr(expr) => describe_expr_kind(&expr.kind),
+        Stmt::Break(_) => "break".to_string(),
+        Stmt::Continue => "continue".to_string(),
+    }
+}
+
+fn describe_pipe_target(target: &PipeTarget) -> String {
+    match target {
+        PipeTarget::Ident(name) => format!("binding {}", name),
+        PipeTarget::Expr(expr) => describe_expr_kind(&expr.kind),
+    }
+}
+
+/// Leaf expressions are shown in full. Other variants are named only, since
+/// their `Debug` output would carry the ids and spans of every child.
+fn describe_expr_kind(kind: &ExprKind) -> String {
+    let debug = format!("{:?}", kind);
+    if !debug.contains("Expr {") {
+        return debug;
+    }
+    let end = debug
+        .find(|ch: char| !ch.is_alphanumeric())
+        .unwrap_or(debug.len());
+    debug[..end].to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1295,6 +2090,78 @@ fun main: () -> Int32 = {
         assert_eq!(collect_node_ids(&program), collect_node_ids(&clone));
     }
 
+    fn parse(source: &str) -> Program {
+        let (rest, program) = parse_program(source).expect("source should parse");
+        assert!(rest.trim().is_empty(), "parser left input: {rest:?}");
+        program
+    }
+
+    #[test]
+    fn whitespace_and_comments_are_not_structural() {
+        let reformatted = NUMBERING_SOURCE
+            .lines()
+            .map(|line| format!("  {}   // trailing comment", line.trim_end()))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let reformatted = parse(&reformatted);
+        let original = parsed_program();
+
+        assert_ne!(
+            collect_spans(&original),
+            collect_spans(&reformatted),
+            "the reformatted source should move every span"
+        );
+        assert!(structurally_eq(&original, &reformatted));
+        assert_eq!(structural_diff(&original, &reformatted), None);
+    }
+
+    #[test]
+    fn differing_literal_is_pinpointed() {
+        let original = parsed_program();
+        let changed = parse(&NUMBERING_SOURCE.replace("val px = p.x", "val px = p.y"));
+        assert!(!structurally_eq(&original, &changed));
+        assert_eq!(
+            structural_diff(&original, &changed),
+            Some(StructuralDifference {
+                path: "declarations[4].body.statements[1].value.field".to_string(),
+                left: "\"x\"".to_string(),
+                right: "\"y\"".to_string(),
+            })
+        );
+
+        let changed = parse(&NUMBERING_SOURCE.replace("0 => { 10 }", "0 => { 11 }"));
+        let difference = structural_diff(&original, &changed).expect("literals differ");
+        assert_eq!(
+            difference.to_string(),
+            "at declarations[2].body.expr.arms[0].body.expr: IntLit(10) != IntLit(11)"
+        );
+    }
+
+    #[test]
+    fn differing_node_kinds_are_named() {
+        let original = parse("fun main: () -> Int32 = { (1 + 2) * 3 }");
+        let changed = parse("fun main: () -> Int32 = { 3 * 3 }");
+        assert_eq!(
+            structural_diff(&original, &changed).map(|difference| difference.to_string()),
+            Some("at declarations[0].body.expr.left: Binary != IntLit(3)".to_string())
+        );
+
+        let longer =
+            parse("fun main: () -> Int32 = { val x = 1; x }\nfun extra: () -> () = { () }");
+        let shorter = parse("fun main: () -> Int32 = { val x = 1; x }");
+        assert_eq!(
+            structural_diff(&longer, &shorter).map(|difference| difference.to_string()),
+            Some("at declarations: 2 items != 1 items".to_string())
+        );
+    }
+
+    fn collect_spans(program: &Program) -> Vec<Option<Span>> {
+        let mut program = program.clone();
+        let mut spans = Vec::new();
+        visit_program_exprs_mut(&mut program, &mut |expr| spans.push(expr.span));
+        spans
+    }
+
     #[test]
     fn renumbering_is_idempotent() {
         let mut program = parsed_program();
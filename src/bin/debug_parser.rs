@@ -1,4 +1,4 @@
-use restrict_lang::lexer::lex;
+use restrict_lang::lexer::lex_collecting;
 use restrict_lang::parser::parse_program;
 use std::env;
 use std::fs;
@@ -20,16 +20,14 @@ fn main() {
 
     // First, test lexing
     println!("=== Tokenization ===");
-    match lex(&content) {
-        Ok((remaining, tokens)) => {
-            println!("Tokens: {:?}", tokens);
-            println!("Remaining input: {:?}", remaining);
-            println!();
-        }
-        Err(e) => {
-            eprintln!("Lexing error: {:?}", e);
-            std::process::exit(1);
+    let (tokens, errors) = lex_collecting(&content);
+    println!("Tokens: {:?}", tokens);
+    println!();
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("{}", error.describe(&content));
         }
+        std::process::exit(1);
     }
 
     // Then test parsing
@@ -34,12 +34,23 @@
 //! ```
 
 use crate::ast::*;
+use crate::const_eval::fold_constants;
+use crate::foreign::ForeignFunction;
 use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 const RECORD_TMP_MIN_COUNT: usize = 8;
-const ARENA_SIZE_BYTES: u32 = 0x1000;
+/// Arena size per page of linear memory, so arenas grow with `memory_pages`.
+const ARENA_BYTES_PER_PAGE: u32 = 0x1000;
+/// Arena header: start address, current pointer, end address.
+const ARENA_HEADER_BYTES: u32 = 12;
+const DEFAULT_MEMORY_PAGES: u32 = 1;
+const WASM_PAGE_BYTES: u32 = 0x1_0000;
 const WITH_ARENA_TMP_COUNT: usize = 8;
+const DIVISION_BY_ZERO_MESSAGE: &str = "division by zero";
+const ARENA_OOM_MESSAGE: &str = "arena out of memory";
+const DECIMAL_OVERFLOW_MESSAGE: &str = "decimal overflow";
+const UNWRAP_NONE_MESSAGE: &str = "unwrap on None";
 
 /// Code generation errors.
 #[derive(Debug, Error)]
@@ -63,8 +74,54 @@ pub enum CodeGenError {
     /// Feature not supported
     #[error("Unsupported feature: {0}")]
     UnsupportedFeature(String),
+
+    /// Division or remainder by a constant zero divisor
+    #[error("Division by zero: right operand of '{0}' is a constant zero")]
+    DivisionByZero(String),
+
+    /// Release builds drop debug-only calls, so their arguments must be pure
+    #[error("Argument to '{0}' has side effects, which release builds would drop with the call; bind the effect to a value first")]
+    EffectfulDebugArgument(String),
+
+    /// Exported function whose signature has no Wasm-level representation
+    #[error("Exported function '{function}' has a non-exportable signature: {reason}")]
+    NonExportableSignature { function: String, reason: String },
+
+    /// Builtin that needs a WASI import, called in a freestanding build
+    #[error("'{0}' needs WASI imports, which the freestanding target does not provide; build with --target=wasi")]
+    RequiresWasi(String),
+
+    /// `--verify` found generated WAT that does not assemble or validate
+    #[error("Generated WAT failed verification: {0}")]
+    InvalidWat(String),
+}
+
+/// Host environment the generated module is built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Target {
+    /// Import `fd_write` and `proc_exit` from `wasi_snapshot_preview1`,
+    /// plus the argument, environment and clock functions when `args`,
+    /// `env_var` or `now` is called.
+    #[default]
+    Wasi,
+    /// No WASI imports. Printing and host builtins are rejected, and
+    /// `panic` traps without reporting its message.
+    Freestanding,
 }
 
+/// Builtins compiled to calls of WASI imports.
+const WASI_BUILTINS: &[&str] = &[
+    "println",
+    "print",
+    "print_int",
+    "print_float",
+    "eprint",
+    "eprintln",
+    "args",
+    "env_var",
+    "now",
+];
+
 struct VariantPayloadBindContext<'a> {
     field_template: &'a Type,
     expected_source: &'a Type,
@@ -111,6 +168,35 @@ pub struct WasmCodeGen {
     specialized_functions: HashSet<String>,
     /// Functions declared through `export fun`.
     exported_functions: HashSet<String>,
+    /// Library builds never emit the `_start` entry wrapper for `main`.
+    library: bool,
+    /// Run `@init` functions from a core-module `(start ...)` section
+    /// instead of from the `_start` or `_initialize` entry point.
+    start_section: bool,
+    /// `@init` functions in declaration order.
+    init_functions: Vec<String>,
+    /// Text of the file the program was parsed from; `assert_that` quotes
+    /// its condition from it.
+    source: Option<String>,
+    /// Run the peephole pass over the finished module.
+    optimize: bool,
+    /// Assemble and validate the finished module before returning it.
+    verify: bool,
+    /// Raw module fields appended by the `inject_wat` test hook.
+    injected_wat: String,
+    /// Release builds elide `debug_assert` and `debug_print` calls.
+    release: bool,
+    /// Host environment; decides whether WASI imports are emitted.
+    target: Target,
+    /// Host functions imported for the program to call by name.
+    foreign_functions: Vec<ForeignFunction>,
+    /// Linear memory size in 64KiB pages; also scales the arena size.
+    memory_pages: u32,
+    /// The host supports the tail-call proposal, so self tail calls may use
+    /// `return_call`.
+    tail_calls: bool,
+    /// Self calls in tail position of the function being generated.
+    tail_recursion: Option<TailRecursion>,
     /// Top-level immutable globals and their Wasm ABI types.
     global_types: HashMap<String, WasmType>,
     /// Top-level immutable globals and their source-level Restrict types.
@@ -137,10 +223,24 @@ pub struct WasmCodeGen {
     lambda_counter: u32,
     /// Generated lambda function definitions
     lambda_functions: Vec<String>,
+    /// Records compared with `==`/`!=` without a user `eq` method; each
+    /// gets a structural `$record_eq_<Name>` helper after the functions.
+    record_eq_requests: Vec<String>,
     /// Function table entries for indirect calls
     function_table: Vec<String>,
     /// True once any `call_indirect` instruction has been emitted.
     has_indirect_closure_call: bool,
+    /// True when the program calls `args`, which needs the WASI argument
+    /// imports.
+    uses_program_args: bool,
+    /// True when the program calls `env_var`, which needs the WASI
+    /// environment imports.
+    uses_environ: bool,
+    /// True when the program calls `now`, which needs `clock_time_get`.
+    uses_clock: bool,
+    /// Which of the builtin `assert_eq` and `assert_ne` the program calls;
+    /// their operand-reporting helpers are only emitted when used.
+    comparison_asserts: HashSet<&'static str>,
     /// Whether we're inside a lambda with captures
     in_lambda_with_captures: bool,
     /// List of captured variable names in current lambda
@@ -169,6 +269,27 @@ pub struct WasmCodeGen {
     with_arena_depth: usize,
     /// Expected ABI for the lambda currently being generated.
     lambda_abi_stack: Vec<LambdaAbiContext>,
+    /// `break` and `continue` labels of the loops enclosing the current
+    /// expression, innermost last, with the number of `with Arena` scopes
+    /// open when the loop was entered. Each loop kind reuses its label names,
+    /// and Wasm resolves a name to the innermost label, which is always the
+    /// top of this stack.
+    loop_labels: Vec<(&'static str, &'static str, usize)>,
+    /// `(depth, arena address)` of each open `with Arena` scope, innermost
+    /// last, so a `break` or `continue` can exit the scopes it jumps out of.
+    with_arena_scopes: Vec<(usize, u32)>,
+}
+
+/// Self calls in tail position of one function, keyed by the address of the
+/// call (or pipe) expression. They reuse the current frame instead of
+/// growing the Wasm stack.
+struct TailRecursion {
+    calls: HashSet<usize>,
+    /// Parameter names and source types, in declaration order.
+    params: Vec<(String, Type)>,
+    /// Emit `return_call`; otherwise rebind the parameters and branch back
+    /// to `$tail_recur` around the body.
+    return_call: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -192,6 +313,355 @@ struct LambdaAbiContext {
     source_result: Type,
 }
 
+/// Scoped renaming behind `WasmCodeGen::lower_shadowed_bindings`. Each scope
+/// maps a source name to the name its current binding is emitted under.
+/// With `pipe_calls` set it also lowers pipe calls, whose callees depend on
+/// which names are local (see `WasmCodeGen::lower_pipe_calls`).
+#[derive(Default)]
+struct ShadowRenamer {
+    scopes: Vec<HashMap<String, String>>,
+    renamed: usize,
+    pipe_calls: Option<PipeCallLowering>,
+}
+
+/// Callee arities behind `WasmCodeGen::lower_pipe_calls`.
+struct PipeCallLowering {
+    function_arities: HashMap<String, usize>,
+    method_arities: HashMap<(String, String), usize>,
+}
+
+impl PipeCallLowering {
+    fn qualified_method(
+        &self,
+        callee: &Expr,
+        is_local: &impl Fn(&str) -> bool,
+    ) -> Option<(String, String, usize)> {
+        match &callee.kind {
+            ExprKind::FieldAccess(object, method) => match &object.kind {
+                ExprKind::Ident(record) if !is_local(record) => self
+                    .method_arities
+                    .get(&(record.clone(), method.clone()))
+                    .map(|arity| (record.clone(), method.clone(), *arity)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Lower `expr` if it is a pipe or qualified method call. A callee named
+    /// by a local binding is a closure value, never the function or record it
+    /// shadows.
+    fn lower(&self, expr: &mut Expr, is_local: impl Fn(&str) -> bool) {
+        let callee_arity = |callee: &Expr| match &callee.kind {
+            ExprKind::Ident(name) if is_local(name) => None,
+            ExprKind::Ident(name) => self.function_arities.get(name).copied(),
+            _ => self
+                .qualified_method(callee, &is_local)
+                .map(|(_, _, arity)| arity),
+        };
+        if let ExprKind::Pipe(pipe) = &expr.kind {
+            if let Some(call) = pipe.expr_target_call(callee_arity) {
+                if call.args.len() > 1 || self.qualified_method(&call.function, &is_local).is_some()
+                {
+                    expr.kind = ExprKind::Call(call);
+                }
+            }
+        }
+        if let ExprKind::Call(call) = &mut expr.kind {
+            if let Some((record, method, _)) = self.qualified_method(&call.function, &is_local) {
+                call.function.kind =
+                    ExprKind::Ident(WasmCodeGen::method_function_name(&record, &method));
+            }
+        }
+    }
+}
+
+impl ShadowRenamer {
+    fn lookup(&self, name: &str) -> Option<&String> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Record a binder that keeps its source name, such as a parameter or a
+    /// match arm binding.
+    fn bind(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), name.to_string());
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &Pattern) {
+        let mut names = Vec::new();
+        Self::pattern_names(pattern, &mut names);
+        for name in names {
+            self.bind(&name);
+        }
+    }
+
+    fn pattern_names(pattern: &Pattern, names: &mut Vec<String>) {
+        match pattern {
+            Pattern::Ident(name) => names.push(name.clone()),
+            Pattern::Binding(name, inner) => {
+                names.push(name.clone());
+                Self::pattern_names(inner, names);
+            }
+            Pattern::Record(_, fields) => {
+                for (_, field) in fields {
+                    Self::pattern_names(field, names);
+                }
+            }
+            Pattern::RecordDestruct { fields, rest, .. } => {
+                for (_, field) in fields {
+                    Self::pattern_names(field, names);
+                }
+                if let Some(rest) = rest.as_ref().filter(|rest| rest.as_str() != "_") {
+                    names.push(rest.clone());
+                }
+            }
+            Pattern::Some(inner) | Pattern::Ok(inner) | Pattern::Err(inner) => {
+                Self::pattern_names(inner, names)
+            }
+            Pattern::ListCons(head, tail) => {
+                Self::pattern_names(head, names);
+                Self::pattern_names(tail, names);
+            }
+            Pattern::ListExact(items) => {
+                for item in items {
+                    Self::pattern_names(item, names);
+                }
+            }
+            Pattern::Wildcard
+            | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::None
+            | Pattern::EmptyList => {}
+        }
+    }
+
+    /// Bind the names a `val` pattern introduces, renaming each one that
+    /// hides a binding still in scope.
+    fn bind_val_name(&mut self, name: &mut String) {
+        if self.lookup(name).is_some() {
+            let fresh = format!("{}__shadow{}", name, self.renamed);
+            self.renamed += 1;
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.insert(name.clone(), fresh.clone());
+            }
+            *name = fresh;
+        } else {
+            self.bind(name);
+        }
+    }
+
+    fn bind_val_pattern(&mut self, pattern: &mut Pattern) {
+        match pattern {
+            Pattern::Ident(name) => self.bind_val_name(name),
+            Pattern::Binding(name, inner) => {
+                self.bind_val_name(name);
+                self.bind_val_pattern(inner);
+            }
+            Pattern::Record(_, fields) => {
+                for (_, field) in fields {
+                    self.bind_val_pattern(field);
+                }
+            }
+            Pattern::RecordDestruct { fields, rest, .. } => {
+                for (_, field) in fields {
+                    self.bind_val_pattern(field);
+                }
+                if let Some(rest) = rest.as_mut().filter(|rest| rest.as_str() != "_") {
+                    self.bind_val_name(rest);
+                }
+            }
+            Pattern::Some(inner) | Pattern::Ok(inner) | Pattern::Err(inner) => {
+                self.bind_val_pattern(inner)
+            }
+            Pattern::ListCons(head, tail) => {
+                self.bind_val_pattern(head);
+                self.bind_val_pattern(tail);
+            }
+            Pattern::ListExact(items) => {
+                for item in items {
+                    self.bind_val_pattern(item);
+                }
+            }
+            Pattern::Wildcard
+            | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::None
+            | Pattern::EmptyList => {}
+        }
+    }
+
+    fn function(&mut self, func: &mut FunDecl) {
+        self.scopes.push(HashMap::new());
+        for param in &func.params {
+            self.bind(&param.name);
+        }
+        self.block(&mut func.body);
+        self.scopes.pop();
+    }
+
+    fn block(&mut self, block: &mut BlockExpr) {
+        self.scopes.push(HashMap::new());
+        for stmt in &mut block.statements {
+            match stmt {
+                Stmt::Binding(bind) => {
+                    self.expr(&mut bind.value);
+                    self.bind_val_pattern(&mut bind.pattern);
+                }
+                Stmt::Assignment(assign) => {
+                    self.expr(&mut assign.value);
+                    if let Some(name) = self.lookup(&assign.name) {
+                        assign.name = name.clone();
+                    }
+                }
+                Stmt::Expr(expr) | Stmt::Break(Some(expr)) => self.expr(expr),
+                Stmt::Break(None) | Stmt::Continue => {}
+            }
+        }
+        if let Some(expr) = &mut block.expr {
+            self.expr(expr);
+        }
+        self.scopes.pop();
+    }
+
+    fn field_inits(&mut self, fields: &mut [FieldInit]) {
+        for field in fields {
+            match field {
+                FieldInit::Field { value, .. } => self.expr(value),
+                FieldInit::Spread(expr) => self.expr(expr),
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &mut Expr) {
+        if let Some(pipe_calls) = &self.pipe_calls {
+            pipe_calls.lower(expr, |name| self.lookup(name).is_some());
+        }
+        match &mut expr.kind {
+            ExprKind::Ident(name) => {
+                if let Some(renamed) = self.lookup(name) {
+                    *name = renamed.clone();
+                }
+            }
+            ExprKind::Block(block) => self.block(block),
+            ExprKind::Then(then) => {
+                self.expr(&mut then.condition);
+                self.block(&mut then.then_block);
+                for (condition, block) in &mut then.else_ifs {
+                    self.expr(condition);
+                    self.block(block);
+                }
+                if let Some(block) = &mut then.else_block {
+                    self.block(block);
+                }
+            }
+            ExprKind::While(while_expr) => {
+                self.expr(&mut while_expr.condition);
+                self.block(&mut while_expr.body);
+            }
+            ExprKind::WhileLet(while_let) => {
+                self.expr(&mut while_let.expr);
+                self.scopes.push(HashMap::new());
+                self.bind_pattern(&while_let.pattern);
+                self.block(&mut while_let.body);
+                self.scopes.pop();
+            }
+            ExprKind::Loop(body) => self.block(body),
+            ExprKind::Match(match_expr) => {
+                self.expr(&mut match_expr.expr);
+                for arm in &mut match_expr.arms {
+                    self.scopes.push(HashMap::new());
+                    self.bind_pattern(&arm.pattern);
+                    if let Some(guard) = &mut arm.guard {
+                        self.expr(guard);
+                    }
+                    self.block(&mut arm.body);
+                    self.scopes.pop();
+                }
+            }
+            ExprKind::Lambda(lambda) => {
+                self.scopes.push(HashMap::new());
+                for param in &lambda.params {
+                    self.bind(&param.name);
+                }
+                self.expr(&mut lambda.body);
+                self.scopes.pop();
+            }
+            ExprKind::With(with) => {
+                self.field_inits(&mut with.bindings);
+                self.scopes.push(HashMap::new());
+                for binding in &with.bindings {
+                    if let FieldInit::Field { name, .. } = binding {
+                        self.bind(name);
+                    }
+                }
+                self.block(&mut with.body);
+                self.scopes.pop();
+            }
+            ExprKind::WithLifetime(with) => self.block(&mut with.body),
+            ExprKind::Pipe(pipe) => {
+                self.expr(&mut pipe.expr);
+                match &mut pipe.target {
+                    PipeTarget::Expr(target) => self.expr(target),
+                    PipeTarget::Ident(name) => {
+                        if let Some(renamed) = self.lookup(name) {
+                            *name = renamed.clone();
+                        }
+                    }
+                }
+            }
+            ExprKind::RecordLit(record) => self.field_inits(&mut record.fields),
+            ExprKind::Clone(clone) => {
+                self.expr(&mut clone.base);
+                self.field_inits(&mut clone.updates.fields);
+            }
+            ExprKind::PrototypeClone(clone) => self.field_inits(&mut clone.updates.fields),
+            ExprKind::Call(call) => {
+                self.expr(&mut call.function);
+                for arg in &mut call.args {
+                    self.expr(arg);
+                }
+            }
+            ExprKind::Binary(binary) => {
+                self.expr(&mut binary.left);
+                self.expr(&mut binary.right);
+            }
+            ExprKind::Unary(unary) => self.expr(&mut unary.expr),
+            ExprKind::Cast(cast) => self.expr(&mut cast.expr),
+            ExprKind::FieldAccess(inner, _) => self.expr(inner),
+            ExprKind::FieldUpdate(update) => {
+                self.expr(&mut update.object);
+                self.expr(&mut update.value);
+            }
+            ExprKind::ListLit(items) | ExprKind::ArrayLit(items) => {
+                for item in items {
+                    self.expr(item);
+                }
+            }
+            ExprKind::RangeLit(range) => {
+                self.expr(&mut range.start);
+                self.expr(&mut range.end);
+            }
+            ExprKind::Some(inner)
+            | ExprKind::Ok(inner)
+            | ExprKind::Err(inner)
+            | ExprKind::Try(inner)
+            | ExprKind::Freeze(inner)
+            | ExprKind::Await(inner)
+            | ExprKind::Spawn(inner) => self.expr(inner),
+            ExprKind::IntLit(_)
+            | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
+            | ExprKind::StringLit(_)
+            | ExprKind::CharLit(_)
+            | ExprKind::BoolLit(_)
+            | ExprKind::Unit
+            | ExprKind::None => {}
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum IterationInputKind {
     List,
@@ -232,6 +702,19 @@ impl WasmCodeGen {
             function_decls: HashMap::new(),
             specialized_functions: HashSet::new(),
             exported_functions: HashSet::new(),
+            library: false,
+            start_section: false,
+            source: None,
+            init_functions: Vec::new(),
+            optimize: false,
+            verify: false,
+            injected_wat: String::new(),
+            release: false,
+            target: Target::Wasi,
+            foreign_functions: Vec::new(),
+            memory_pages: DEFAULT_MEMORY_PAGES,
+            tail_calls: false,
+            tail_recursion: None,
             global_types: HashMap::new(),
             global_source_types: HashMap::new(),
             methods: HashMap::new(),
@@ -245,8 +728,13 @@ impl WasmCodeGen {
             default_arena: None,
             lambda_counter: 0,
             lambda_functions: Vec::new(),
+            record_eq_requests: Vec::new(),
             function_table: Vec::new(),
             has_indirect_closure_call: false,
+            uses_program_args: false,
+            uses_environ: false,
+            comparison_asserts: HashSet::new(),
+            uses_clock: false,
             in_lambda_with_captures: false,
             captured_vars: Vec::new(),
             records: HashMap::new(),
@@ -261,38 +749,162 @@ impl WasmCodeGen {
             record_tmp_count: RECORD_TMP_MIN_COUNT,
             with_arena_depth: 0,
             lambda_abi_stack: Vec::new(),
+            loop_labels: Vec::new(),
+            with_arena_scopes: Vec::new(),
         }
     }
 
+    /// Compile as a library: exported functions only, with no `_start`
+    /// entry wrapper even when the program declares a `main`.
+    pub fn set_library(&mut self, library: bool) {
+        self.library = library;
+    }
+
+    /// Run `@init` functions from a `(start ...)` section, so they execute
+    /// when the module is instantiated. Without it they run from `_start`
+    /// before `main`, or from an exported `_initialize` when there is no
+    /// `_start`.
+    pub fn set_start_section(&mut self, start_section: bool) {
+        self.start_section = start_section;
+    }
+
+    /// Provide the source text the program was parsed from, so a failing
+    /// `assert_that(cond)` reports `assertion failed: <cond>`. Without it,
+    /// or for nodes without a span, the message omits the condition.
+    pub fn set_source(&mut self, source: &str) {
+        self.source = Some(source.to_string());
+    }
+
+    /// Clean up the generated WAT with the peephole pass: constant folding,
+    /// identity removal, and single-use `local.set`/`local.get` pairs.
+    pub fn optimize(&mut self, optimize: bool) {
+        self.optimize = optimize;
+    }
+
+    /// Build for release: `debug_assert` and `debug_print` calls are removed
+    /// instead of compiled to `assert` and `println`.
+    pub fn release(&mut self, release: bool) {
+        self.release = release;
+    }
+
+    /// Build for `target`. `Target::Freestanding` drops the WASI imports,
+    /// so programs that print or read `args` fail to compile.
+    pub fn target(&mut self, target: Target) {
+        self.target = target;
+    }
+
+    /// Assemble and validate the generated WAT before returning it, failing
+    /// with [`CodeGenError::InvalidWat`] on the first problem.
+    pub fn verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+
+    /// Test hook: append raw module fields before the closing paren, so tests
+    /// can check that [`verify`](Self::verify) catches malformed output.
+    #[doc(hidden)]
+    pub fn inject_wat(&mut self, wat: &str) {
+        self.injected_wat.push_str(wat);
+    }
+
+    /// Import `function` from the host so the program can call it by its
+    /// [`binding`](ForeignFunction::binding).
+    pub fn declare_foreign_function(&mut self, function: ForeignFunction) {
+        self.foreign_functions.push(function);
+    }
+
+    /// Size linear memory to at least `pages` 64KiB pages. Each arena gets
+    /// `pages * 4KiB`, so allocation-heavy programs can trade memory for
+    /// headroom before `arena_alloc` traps. Modules with more arenas than
+    /// fit get extra pages.
+    pub fn memory_pages(&mut self, pages: u32) {
+        self.memory_pages = pages.max(1);
+    }
+
+    /// Emit self calls in tail position as `return_call`, which needs a host
+    /// with the tail-call proposal. Without it they are lowered to a loop
+    /// around the function body, which every host runs.
+    pub fn tail_calls(&mut self, tail_calls: bool) {
+        self.tail_calls = tail_calls;
+    }
+
+    fn arena_size(&self) -> u32 {
+        ARENA_BYTES_PER_PAGE * self.memory_pages
+    }
+
+    /// Linear memory pages: at least `memory_pages`, and enough to hold
+    /// every arena laid out so far, which all live at fixed addresses.
+    fn memory_page_count(&self) -> u32 {
+        self.next_arena_addr
+            .div_ceil(WASM_PAGE_BYTES)
+            .max(self.memory_pages)
+    }
+
     pub fn generate(&mut self, program: &Program) -> Result<String, CodeGenError> {
+        let lowered = Self::lower_shadowed_bindings(&Self::lower_context_field_access(
+            &self.lower_std_sugar_calls(&fold_constants(&Self::erase_range_restrictions(
+                &Self::lower_record_field_defaults(program),
+            ))),
+        ));
+        // Before string collection, so messages of elided calls never reach
+        // the data section.
+        let lowered = self.lower_debug_calls(&lowered)?;
+        if self.target == Target::Freestanding {
+            Self::reject_wasi_calls(&lowered)?;
+        }
+        let program = &lowered;
         self.output.push_str("(module\n");
 
         // Process module imports first
         self.generate_imports(&program.imports)?;
 
+        // Collect string constants first; this also notes whether `args`
+        // is called, which decides the WASI imports below
+        self.collect_strings(program)?;
+        self.uses_environ = Self::calls_builtin(program, "env_var");
+        self.uses_clock = Self::calls_builtin(program, "now");
+        self.comparison_asserts = ["assert_eq", "assert_ne"]
+            .into_iter()
+            .filter(|name| Self::calls_builtin(program, name))
+            .collect();
+
         // Import WASI functions for I/O
-        self.output.push_str("  ;; WASI imports\n");
-        self.output.push_str("  (import \"wasi_snapshot_preview1\" \"fd_write\" (func $fd_write (param i32 i32 i32 i32) (result i32)))\n");
-        self.output.push_str(
-            "  (import \"wasi_snapshot_preview1\" \"proc_exit\" (func $proc_exit (param i32)))\n",
-        );
+        if self.target == Target::Wasi {
+            self.output.push_str("  ;; WASI imports\n");
+            self.output.push_str("  (import \"wasi_snapshot_preview1\" \"fd_write\" (func $fd_write (param i32 i32 i32 i32) (result i32)))\n");
+            self.output.push_str(
+                "  (import \"wasi_snapshot_preview1\" \"proc_exit\" (func $proc_exit (param i32)))\n",
+            );
+            if self.uses_program_args {
+                self.output.push_str("  (import \"wasi_snapshot_preview1\" \"args_sizes_get\" (func $args_sizes_get (param i32 i32) (result i32)))\n");
+                self.output.push_str("  (import \"wasi_snapshot_preview1\" \"args_get\" (func $args_get (param i32 i32) (result i32)))\n");
+            }
+            if self.uses_environ {
+                self.output.push_str("  (import \"wasi_snapshot_preview1\" \"environ_sizes_get\" (func $environ_sizes_get (param i32 i32) (result i32)))\n");
+                self.output.push_str("  (import \"wasi_snapshot_preview1\" \"environ_get\" (func $environ_get (param i32 i32) (result i32)))\n");
+            }
+            if self.uses_clock {
+                self.output.push_str("  (import \"wasi_snapshot_preview1\" \"clock_time_get\" (func $clock_time_get (param i32 i64 i32) (result i32)))\n");
+            }
+        }
+        self.generate_foreign_imports(program)?;
 
-        // Memory
+        // Memory. Arenas are laid out statically while functions are
+        // generated, so the page count is filled in once they all have an
+        // address.
         self.output.push_str("\n  ;; Memory\n");
-        self.output.push_str("  (memory 1)\n");
+        let memory_decl_at = self.output.len();
         self.output.push_str("  (export \"memory\" (memory 0))\n");
 
         self.generate_indirect_call_types();
 
         // Note: Using direct function calls for cleanup instead of function table
 
-        // Collect string constants first
-        self.collect_strings(program)?;
-
         // Generate string data section
         if !self.strings.is_empty() {
             self.output.push_str("\n  ;; String constants\n");
-            for (s, offset) in &self.string_offsets {
+            // In interning order, so the output does not depend on hashing.
+            for s in &self.strings {
+                let offset = self.string_offsets[s];
                 let bytes = s.as_bytes();
                 let len = bytes.len() as u32;
 
@@ -326,6 +938,11 @@ impl WasmCodeGen {
             }
         }
 
+        // Arenas start above the string constants.
+        self.next_arena_addr = self
+            .next_arena_addr
+            .max(self.next_mem_offset.next_multiple_of(8));
+
         // Generate built-in functions
         self.generate_builtin_functions()?;
 
@@ -335,12 +952,20 @@ impl WasmCodeGen {
         // Generate list operation functions
         self.generate_list_functions()?;
 
+        // Generate map operation functions
+        self.generate_map_functions()?;
+
         // Generate array operation functions
         self.generate_array_functions()?;
 
         // Generate temporal cleanup functions
         self.generate_temporal_cleanup_functions()?;
 
+        // Builtin signatures are registered now, so pipe targets can be
+        // desugared against every callee's arity.
+        let lowered = self.lower_pipe_calls(program);
+        let program = &lowered;
+
         // Collect record definitions first
         for decl in &program.declarations {
             if let TopDecl::Record(record) = Self::decl_codegen_item(decl) {
@@ -352,6 +977,9 @@ impl WasmCodeGen {
                 self.register_context_definition(context)?;
             }
         }
+        if Self::calls_builtin(program, "hash") {
+            self.generate_record_hash_functions()?;
+        }
 
         for decl in &program.declarations {
             if let TopDecl::Binding(binding) = Self::decl_codegen_item(decl) {
@@ -411,6 +1039,7 @@ impl WasmCodeGen {
         for lambda_func in &self.lambda_functions {
             self.output.push_str(lambda_func);
         }
+        self.generate_record_eq_functions()?;
 
         // Generate function table if we have indirect calls
         if self.has_indirect_closure_call || !self.function_table.is_empty() {
@@ -431,6 +1060,8 @@ impl WasmCodeGen {
         // Generate module exports
         self.generate_exports(program)?;
 
+        self.generate_init_wrapper(program)?;
+
         // Export a no-result program entry wrapper only for zero-argument
         // `main`. A parameterized function named `main` remains an ordinary
         // Restrict function with its declared ABI.
@@ -438,47 +1069,683 @@ impl WasmCodeGen {
             self.generate_start_wrapper()?;
         }
 
+        self.output.push_str(&self.injected_wat);
         self.output.push_str(")\n");
+        let memory_decl = format!("  (memory {})\n", self.memory_page_count());
+        self.output.insert_str(memory_decl_at, &memory_decl);
 
-        Ok(self.output.clone())
-    }
-
-    fn decl_codegen_item(decl: &TopDecl) -> &TopDecl {
-        match decl {
-            TopDecl::Export(export_decl) => export_decl.item.as_ref(),
-            _ => decl,
+        let wat = if self.optimize {
+            crate::peephole::optimize_wat(&self.output)
+        } else {
+            self.output.clone()
+        };
+        if self.verify {
+            crate::wat_verify::verify_wat(&wat)?;
         }
+        Ok(wat)
     }
 
-    fn collect_exported_functions(&mut self, program: &Program) {
-        for decl in &program.declarations {
-            if let TopDecl::Export(export_decl) = decl {
-                if let TopDecl::Function(func) = export_decl.item.as_ref() {
-                    self.exported_functions.insert(func.name.clone());
+    /// Rewrite the callable-first list combinators (`list_map`,
+    /// `list_filter`, `list_fold`) into the container-first `map`, `filter`
+    /// and `fold` calls, so both spellings share one closure-iteration
+    /// lowering. User functions with the same names are left untouched.
+    fn lower_std_sugar_calls(&self, program: &Program) -> Program {
+        let user_functions: HashSet<&str> = program
+            .declarations
+            .iter()
+            .filter_map(|decl| match Self::decl_codegen_item(decl) {
+                TopDecl::Function(func) => Some(func.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        let mut lowered = program.clone();
+        visit_program_exprs_mut(&mut lowered, &mut |expr| {
+            if let ExprKind::Pipe(pipe) = &expr.kind {
+                if matches!(&pipe.target, PipeTarget::Ident(name) if name == "assert_that")
+                    && !user_functions.contains("assert_that")
+                {
+                    expr.kind = ExprKind::Call(CallExpr {
+                        function: Box::new(Expr::new(ExprKind::Ident("assert_that".to_string()))),
+                        args: vec![pipe.expr.clone()],
+                        type_args: Vec::new(),
+                    });
                 }
             }
-        }
-    }
-
-    fn should_generate_start_wrapper(&self) -> bool {
-        self.functions
-            .get("main")
-            .map(|sig| sig._params.is_empty())
-            .unwrap_or(false)
-    }
-
-    fn generate_builtin_functions(&mut self) -> Result<(), CodeGenError> {
-        // Built-in println function for strings
-        self.output.push_str("\n  ;; Built-in functions\n");
-        self.output.push_str("  (func $println (param $str i32)\n");
-        self.output.push_str("    (local $len i32)\n");
-        self.output.push_str("    (local $iov_base i32)\n");
-        self.output.push_str("    (local $iov_len i32)\n");
-        self.output.push_str("    (local $nwritten i32)\n");
-        self.output.push_str("    \n");
-        self.output
-            .push_str("    ;; Read string length from memory (first 4 bytes)\n");
-        self.output.push_str("    local.get $str\n");
+            let ExprKind::Call(call) = &mut expr.kind else {
+                return;
+            };
+            let ExprKind::Ident(name) = &call.function.kind else {
+                return;
+            };
+            if user_functions.contains(name.as_str()) {
+                return;
+            }
+            if name == "assert_that" && call.args.len() == 1 {
+                let condition_text = self
+                    .source
+                    .as_deref()
+                    .zip(call.args[0].span)
+                    .and_then(|(source, span)| span.slice(source));
+                let message = match condition_text {
+                    Some(text) => format!("assertion failed: {}", text.trim()),
+                    None => "assertion failed".to_string(),
+                };
+                call.args
+                    .push(Box::new(Expr::new(ExprKind::StringLit(message))));
+                call.function.kind = ExprKind::Ident("assert".to_string());
+                return;
+            }
+            let target = match (name.as_str(), call.args.len()) {
+                ("list_map", 2) => "map",
+                ("list_filter", 2) => "filter",
+                ("list_fold", 3) => "fold",
+                _ => return,
+            };
+            // (callable, [initial,] list) -> (list, [initial,] callable)
+            let last = call.args.len() - 1;
+            call.args.swap(0, last);
+            call.function.kind = ExprKind::Ident(target.to_string());
+        });
+        lowered
+    }
+
+    /// Debug builds compile `debug_assert` as `assert` and `debug_print` as
+    /// `println`. Release builds replace the call with `()`, which is only
+    /// sound when dropping the arguments drops no effects.
+    fn lower_debug_calls(&self, program: &Program) -> Result<Program, CodeGenError> {
+        let user_functions = Self::user_function_names(program);
+        let debug_arity = |callee: &Expr| match &callee.kind {
+            ExprKind::Ident(name) if !user_functions.contains(name.as_str()) => {
+                match name.as_str() {
+                    "debug_assert" => Some(2),
+                    "debug_print" => Some(1),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        let mut lowered = program.clone();
+        let mut effectful = None;
+        visit_program_exprs_mut(&mut lowered, &mut |expr| {
+            // `msg |> debug_print` and `cond |> (msg) debug_assert` become
+            // plain calls first.
+            if let ExprKind::Pipe(pipe) = &expr.kind {
+                let call = match &pipe.target {
+                    PipeTarget::Ident(name) => {
+                        let callee = Expr::new(ExprKind::Ident(name.clone()));
+                        debug_arity(&callee).map(|_| CallExpr {
+                            function: Box::new(callee),
+                            args: vec![pipe.expr.clone()],
+                            type_args: Vec::new(),
+                        })
+                    }
+                    PipeTarget::Expr(target) => {
+                        let callee = match &target.kind {
+                            ExprKind::Call(call) => &call.function,
+                            _ => target,
+                        };
+                        debug_arity(callee).and_then(|_| pipe.expr_target_call(debug_arity))
+                    }
+                };
+                if let Some(call) = call {
+                    expr.kind = ExprKind::Call(call);
+                }
+            }
+            let ExprKind::Call(call) = &mut expr.kind else {
+                return;
+            };
+            let ExprKind::Ident(name) = &call.function.kind else {
+                return;
+            };
+            let debug_target = match name.as_str() {
+                "debug_assert" => "assert",
+                "debug_print" => "println",
+                _ => return,
+            };
+            if user_functions.contains(name.as_str()) {
+                return;
+            }
+            if !self.release {
+                call.function.kind = ExprKind::Ident(debug_target.to_string());
+                return;
+            }
+            if !call.args.iter().all(|arg| Self::is_side_effect_free(arg)) {
+                effectful.get_or_insert_with(|| name.clone());
+                return;
+            }
+            expr.kind = ExprKind::Unit;
+        });
+        match effectful {
+            Some(name) => Err(CodeGenError::EffectfulDebugArgument(name)),
+            None => Ok(lowered),
+        }
+    }
+
+    /// Names of the program's own top-level functions, which shadow
+    /// builtins of the same name.
+    fn user_function_names(program: &Program) -> HashSet<&str> {
+        program
+            .declarations
+            .iter()
+            .filter_map(|decl| match Self::decl_codegen_item(decl) {
+                TopDecl::Function(func) => Some(func.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Freestanding builds have no WASI imports, so calling a builtin that
+    /// writes to a file descriptor or reads the argument vector is an error
+    /// rather than a module that fails to link.
+    fn reject_wasi_calls(program: &Program) -> Result<(), CodeGenError> {
+        let user_functions = Self::user_function_names(program);
+        let wasi_builtin =
+            |name: &str| WASI_BUILTINS.contains(&name) && !user_functions.contains(name);
+        let mut scanned = program.clone();
+        let mut found = None;
+        visit_program_exprs_mut(&mut scanned, &mut |expr| {
+            let name = match &expr.kind {
+                ExprKind::Call(call) => match &call.function.kind {
+                    ExprKind::Ident(name) => name,
+                    _ => return,
+                },
+                ExprKind::Pipe(pipe) => match &pipe.target {
+                    PipeTarget::Ident(name) => name,
+                    PipeTarget::Expr(target) => match &target.kind {
+                        ExprKind::Ident(name) => name,
+                        _ => return,
+                    },
+                },
+                _ => return,
+            };
+            if wasi_builtin(name) {
+                found.get_or_insert_with(|| name.clone());
+            }
+        });
+        match found {
+            Some(name) => Err(CodeGenError::RequiresWasi(name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether any call or pipe in `program` names the builtin `name`, and
+    /// no user function shadows it.
+    fn calls_builtin(program: &Program, name: &str) -> bool {
+        if Self::user_function_names(program).contains(name) {
+            return false;
+        }
+        let mut scanned = program.clone();
+        let mut found = false;
+        visit_program_exprs_mut(&mut scanned, &mut |expr| {
+            let callee = match &expr.kind {
+                ExprKind::Call(call) => &call.function.kind,
+                ExprKind::Pipe(pipe) => match &pipe.target {
+                    PipeTarget::Ident(callee) => {
+                        found |= callee == name;
+                        return;
+                    }
+                    PipeTarget::Expr(target) => &target.kind,
+                },
+                _ => return,
+            };
+            found |= matches!(callee, ExprKind::Ident(callee) if callee == name);
+        });
+        found
+    }
+
+    /// Conservative purity check: literals, bindings, operators, and
+    /// constructors of pure parts, plus calls to std helpers that only
+    /// compute a value. Any other call may print, trap on purpose, or
+    /// mutate, so it counts as an effect.
+    fn is_side_effect_free(expr: &Expr) -> bool {
+        const PURE_BUILTINS: &[&str] = &[
+            "int_to_string",
+            "float_to_string",
+            "bool_to_string",
+            "parse_int",
+            "parse_float",
+            "hash",
+            "char_to_int",
+            "is_digit",
+            "is_alpha",
+            "string_length",
+            "string_from_char",
+            "string_split",
+            "string_join",
+            "abs",
+            "sign",
+            "max",
+            "min",
+            "not",
+            "and",
+            "or",
+        ];
+        let pure_block = |block: &BlockExpr| {
+            block.statements.iter().all(|stmt| match stmt {
+                Stmt::Binding(binding) => Self::is_side_effect_free(&binding.value),
+                Stmt::Assignment(_) | Stmt::Break(_) | Stmt::Continue => false,
+                Stmt::Expr(expr) => Self::is_side_effect_free(expr),
+            }) && block
+                .expr
+                .as_ref()
+                .is_none_or(|expr| Self::is_side_effect_free(expr))
+        };
+        match &expr.kind {
+            ExprKind::IntLit(_)
+            | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
+            | ExprKind::StringLit(_)
+            | ExprKind::CharLit(_)
+            | ExprKind::BoolLit(_)
+            | ExprKind::Unit
+            | ExprKind::Ident(_)
+            | ExprKind::None
+            | ExprKind::Lambda(_) => true,
+            ExprKind::FieldAccess(base, _) => Self::is_side_effect_free(base),
+            ExprKind::Binary(binary) => {
+                Self::is_side_effect_free(&binary.left) && Self::is_side_effect_free(&binary.right)
+            }
+            ExprKind::Unary(unary) => Self::is_side_effect_free(&unary.expr),
+            ExprKind::Cast(cast) => Self::is_side_effect_free(&cast.expr),
+            ExprKind::Some(inner) | ExprKind::Ok(inner) | ExprKind::Err(inner) => {
+                Self::is_side_effect_free(inner)
+            }
+            ExprKind::ListLit(items) | ExprKind::ArrayLit(items) => {
+                items.iter().all(|item| Self::is_side_effect_free(item))
+            }
+            ExprKind::RecordLit(record) => record.fields.iter().all(|field| match field {
+                FieldInit::Field { value, .. } => Self::is_side_effect_free(value),
+                FieldInit::Spread(base) => Self::is_side_effect_free(base),
+            }),
+            ExprKind::Block(block) => pure_block(block),
+            ExprKind::Then(then) => {
+                Self::is_side_effect_free(&then.condition)
+                    && pure_block(&then.then_block)
+                    && then.else_ifs.iter().all(|(condition, block)| {
+                        Self::is_side_effect_free(condition) && pure_block(block)
+                    })
+                    && then.else_block.as_ref().is_none_or(pure_block)
+            }
+            ExprKind::Call(call) => {
+                matches!(&call.function.kind, ExprKind::Ident(name) if PURE_BUILTINS.contains(&name.as_str()))
+                    && call.args.iter().all(|arg| Self::is_side_effect_free(arg))
+            }
+            ExprKind::Pipe(pipe) => {
+                Self::is_side_effect_free(&pipe.expr)
+                    && match &pipe.target {
+                        PipeTarget::Ident(name) => PURE_BUILTINS.contains(&name.as_str()),
+                        PipeTarget::Expr(target) => match &target.kind {
+                            ExprKind::Ident(name) => PURE_BUILTINS.contains(&name.as_str()),
+                            _ => Self::is_side_effect_free(target),
+                        },
+                    }
+            }
+            _ => false,
+        }
+    }
+
+    /// Fill every field a record literal omits with a copy of its declared
+    /// default, so each construction evaluates the default afresh. Literals
+    /// with a spread take the remaining fields from the spread instead.
+    fn lower_record_field_defaults(program: &Program) -> Program {
+        let defaults: HashMap<&str, Vec<(&str, &Expr)>> = program
+            .declarations
+            .iter()
+            .filter_map(|decl| match Self::decl_codegen_item(decl) {
+                TopDecl::Record(record) => Some((
+                    record.name.as_str(),
+                    record
+                        .fields
+                        .iter()
+                        .filter_map(|field| Some((field.name.as_str(), field.default.as_ref()?)))
+                        .collect::<Vec<_>>(),
+                )),
+                _ => None,
+            })
+            .filter(|(_, fields)| !fields.is_empty())
+            .collect();
+        if defaults.is_empty() {
+            return program.clone();
+        }
+
+        let mut lowered = program.clone();
+        visit_program_exprs_mut(&mut lowered, &mut |expr| {
+            let ExprKind::RecordLit(record_lit) = &mut expr.kind else {
+                return;
+            };
+            let Some(fields) = defaults.get(record_lit.name.as_str()) else {
+                return;
+            };
+            if record_lit
+                .fields
+                .iter()
+                .any(|field| matches!(field, FieldInit::Spread(_)))
+            {
+                return;
+            }
+            for (name, default) in fields {
+                let provided = record_lit.fields.iter().any(
+                    |field| matches!(field, FieldInit::Field { name: given, .. } if given == name),
+                );
+                if !provided {
+                    record_lit.fields.push(FieldInit::Field {
+                        name: name.to_string(),
+                        value: Box::new((*default).clone()),
+                    });
+                }
+            }
+        });
+        lowered
+    }
+
+    /// Replace every range-restricted type with its base integer type. The
+    /// type checker has already proven the ranges, so codegen only ever sees
+    /// plain `Int32`/`Int64` values.
+    fn erase_range_restrictions(program: &Program) -> Program {
+        fn erase(ty: &mut Type) {
+            if let Type::Refined(base, _) = ty {
+                *ty = (**base).clone();
+            }
+        }
+        fn erase_block(block: &mut BlockExpr) {
+            for stmt in &mut block.statements {
+                if let Stmt::Binding(bind) = stmt {
+                    bind.type_annotation.iter_mut().for_each(erase);
+                }
+            }
+        }
+        fn erase_function(func: &mut FunDecl) {
+            for param in &mut func.params {
+                erase(&mut param.ty);
+            }
+            func.return_type.iter_mut().for_each(erase);
+            erase_block(&mut func.body);
+        }
+        fn erase_decl(decl: &mut TopDecl) {
+            match decl {
+                TopDecl::Function(func) => erase_function(func),
+                TopDecl::Binding(bind) => bind.type_annotation.iter_mut().for_each(erase),
+                TopDecl::Impl(impl_block) => {
+                    impl_block.functions.iter_mut().for_each(erase_function)
+                }
+                TopDecl::Export(export) => erase_decl(&mut export.item),
+                TopDecl::Record(_) | TopDecl::Context(_) => {}
+            }
+        }
+
+        let mut lowered = program.clone();
+        lowered.declarations.iter_mut().for_each(erase_decl);
+        visit_program_exprs_mut(&mut lowered, &mut |expr| match &mut expr.kind {
+            ExprKind::Block(block) => erase_block(block),
+            ExprKind::Then(then) => {
+                erase_block(&mut then.then_block);
+                then.else_ifs
+                    .iter_mut()
+                    .for_each(|(_, block)| erase_block(block));
+                then.else_block.iter_mut().for_each(erase_block);
+            }
+            ExprKind::While(while_expr) => erase_block(&mut while_expr.body),
+            ExprKind::Loop(body) => erase_block(body),
+            ExprKind::WhileLet(while_let) => erase_block(&mut while_let.body),
+            ExprKind::Match(match_expr) => match_expr
+                .arms
+                .iter_mut()
+                .for_each(|arm| erase_block(&mut arm.body)),
+            ExprKind::With(with) => erase_block(&mut with.body),
+            ExprKind::WithLifetime(with) => erase_block(&mut with.body),
+            _ => {}
+        });
+        lowered
+    }
+
+    /// Give every `val` binding that shadows a binding still in scope its
+    /// own name. Wasm locals are function-wide, so an inner `val x` would
+    /// otherwise overwrite the outer `x` it hides. Top-level bindings are
+    /// globals and keep their names.
+    fn lower_shadowed_bindings(program: &Program) -> Program {
+        let mut lowered = program.clone();
+        let mut renamer = ShadowRenamer::default();
+        for decl in &mut lowered.declarations {
+            let decl = match decl {
+                TopDecl::Export(export_decl) => export_decl.item.as_mut(),
+                decl => decl,
+            };
+            match decl {
+                TopDecl::Function(func) => renamer.function(func),
+                TopDecl::Impl(impl_block) => {
+                    for func in &mut impl_block.functions {
+                        renamer.function(func);
+                    }
+                }
+                _ => {}
+            }
+        }
+        lowered
+    }
+
+    /// Lower `Context.field` to a hidden local that the enclosing
+    /// `with Context` expression binds for that field before its body runs,
+    /// so a body local with the field's name cannot capture the access. The
+    /// type checker has already rejected such accesses outside a matching
+    /// `with`.
+    fn lower_context_field_access(program: &Program) -> Program {
+        let context_fields: HashMap<&str, HashMap<&str, &Type>> = program
+            .declarations
+            .iter()
+            .filter_map(|decl| match Self::decl_codegen_item(decl) {
+                TopDecl::Context(context) => Some((
+                    context.name.as_str(),
+                    context
+                        .fields
+                        .iter()
+                        .map(|field| (field.name.as_str(), &field.ty))
+                        .collect(),
+                )),
+                _ => None,
+            })
+            .collect();
+        if context_fields.is_empty() {
+            return program.clone();
+        }
+
+        let mut lowered = program.clone();
+        visit_program_exprs_mut(&mut lowered, &mut |expr| match &mut expr.kind {
+            ExprKind::With(with) => {
+                let Some(fields) = context_fields.get(with.context_name.as_str()) else {
+                    return;
+                };
+                let hidden = with.bindings.iter().filter_map(|binding| {
+                    let FieldInit::Field { name, .. } = binding else {
+                        return None;
+                    };
+                    Some(Stmt::Binding(BindDecl {
+                        mutable: false,
+                        pattern: Pattern::Ident(Self::context_field_local(
+                            &with.context_name,
+                            name,
+                        )),
+                        type_annotation: fields.get(name.as_str()).map(|ty| (*ty).clone()),
+                        value: Box::new(Expr::new(ExprKind::Ident(name.clone()))),
+                    }))
+                });
+                let hidden: Vec<Stmt> = hidden.collect();
+                with.body.statements.splice(0..0, hidden);
+            }
+            ExprKind::FieldAccess(object, field) => {
+                let ExprKind::Ident(context) = &object.kind else {
+                    return;
+                };
+                if context_fields
+                    .get(context.as_str())
+                    .is_some_and(|fields| fields.contains_key(field.as_str()))
+                {
+                    expr.kind = ExprKind::Ident(Self::context_field_local(context, field));
+                }
+            }
+            _ => {}
+        });
+        lowered
+    }
+
+    /// Name of the hidden local holding `context.field` inside a `with`
+    /// body; `__` keeps it out of the user identifier namespace.
+    fn context_field_local(context: &str, field: &str) -> String {
+        format!("__ctx_{}_{}", context, field)
+    }
+
+    /// Desugar partially applied and qualified-method pipe targets into the
+    /// calls they stand for (see `PipeExpr::expr_target_call`), and lower
+    /// qualified method callees `Record.method` to their impl functions.
+    fn lower_pipe_calls(&self, program: &Program) -> Program {
+        let mut function_arities: HashMap<String, usize> = self
+            .function_source_sigs
+            .iter()
+            .map(|(name, sig)| (name.clone(), sig.params.len()))
+            .collect();
+        let mut method_arities: HashMap<(String, String), usize> = HashMap::new();
+        for decl in &program.declarations {
+            match Self::decl_codegen_item(decl) {
+                TopDecl::Function(func) => {
+                    function_arities.insert(func.name.clone(), func.params.len());
+                }
+                TopDecl::Impl(impl_block) => {
+                    for func in &impl_block.functions {
+                        method_arities.insert(
+                            (impl_block.target.clone(), func.name.clone()),
+                            func.params.len(),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Shadowed bindings are already renamed apart, so the walk only
+        // tracks which names are local.
+        let mut lowered = program.clone();
+        let mut lowering = ShadowRenamer {
+            pipe_calls: Some(PipeCallLowering {
+                function_arities,
+                method_arities,
+            }),
+            ..ShadowRenamer::default()
+        };
+        for decl in &mut lowered.declarations {
+            let decl = match decl {
+                TopDecl::Export(export_decl) => export_decl.item.as_mut(),
+                decl => decl,
+            };
+            match decl {
+                TopDecl::Function(func) => lowering.function(func),
+                TopDecl::Impl(impl_block) => {
+                    for func in &mut impl_block.functions {
+                        lowering.function(func);
+                    }
+                }
+                TopDecl::Binding(binding) => lowering.expr(&mut binding.value),
+                TopDecl::Record(_) | TopDecl::Context(_) | TopDecl::Export(_) => {}
+            }
+        }
+        lowered
+    }
+
+    fn decl_codegen_item(decl: &TopDecl) -> &TopDecl {
+        match decl {
+            TopDecl::Export(export_decl) => export_decl.item.as_ref(),
+            _ => decl,
+        }
+    }
+
+    fn collect_exported_functions(&mut self, program: &Program) {
+        for decl in &program.declarations {
+            if let TopDecl::Export(export_decl) = decl {
+                if let TopDecl::Function(func) = export_decl.item.as_ref() {
+                    self.exported_functions.insert(func.name.clone());
+                }
+            }
+        }
+    }
+
+    fn should_generate_start_wrapper(&self) -> bool {
+        !self.library
+            && self
+                .functions
+                .get("main")
+                .map(|sig| sig._params.is_empty())
+                .unwrap_or(false)
+    }
+
+    fn generate_builtin_functions(&mut self) -> Result<(), CodeGenError> {
+        self.output.push_str("\n  ;; Built-in functions\n");
+        if self.target == Target::Wasi {
+            self.generate_wasi_print_functions();
+        }
+
+        // Add println to function signatures
+        self.functions.insert(
+            "println".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32],
+                result: None,
+            },
+        );
+        self.function_source_sigs.insert(
+            "println".to_string(),
+            FunctionSourceSig {
+                type_params: vec![],
+                params: vec![Type::Named("String".to_string())],
+                result: Some(Type::Named("Unit".to_string())),
+            },
+        );
+
+        // Add print_int to function signatures
+        self.functions.insert(
+            "print_int".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32],
+                result: None,
+            },
+        );
+
+        self.generate_std_io_functions()?;
+        self.generate_std_convert_functions()?;
+        self.generate_std_parse_functions()?;
+        self.generate_std_char_functions()?;
+        self.generate_std_string_functions()?;
+        self.generate_std_math_functions()?;
+        self.generate_std_hash_functions()?;
+        self.generate_std_prelude_functions()?;
+        self.generate_assert_comparison_functions();
+
+        for (name, arity) in [("map", 2), ("filter", 2), ("fold", 3), ("list_sort", 2)] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![WasmType::I32; arity],
+                    result: Some(WasmType::I32),
+                },
+            );
+        }
+
+        self.generate_std_option_functions()?;
+
+        Ok(())
+    }
+
+    /// Emits the printing builtins, which all write through WASI
+    /// `fd_write`. Freestanding builds skip them; their signatures are
+    /// still registered so calls resolve before `reject_wasi_calls` runs.
+    fn generate_wasi_print_functions(&mut self) {
+        // Built-in println function for strings
+        self.output.push_str("  (func $println (param $str i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $iov_base i32)\n");
+        self.output.push_str("    (local $iov_len i32)\n");
+        self.output.push_str("    (local $nwritten i32)\n");
+        self.output.push_str("    \n");
+        self.output
+            .push_str("    ;; Read string length from memory (first 4 bytes)\n");
+        self.output.push_str("    local.get $str\n");
         self.output.push_str("    i32.load\n");
         self.output.push_str("    local.set $len\n");
         self.output.push_str("    \n");
@@ -539,95 +1806,7 @@ impl WasmCodeGen {
         self.output.push_str("    \n");
         self.output
             .push_str("    ;; Use memory starting at address 400 for the buffer\n");
-        self.output
-            .push_str("    i32.const 420  ;; Start from the end of buffer and work backwards\n");
-        self.output.push_str("    local.set $buffer_end\n");
-        self.output.push_str("    local.get $buffer_end\n");
-        self.output.push_str("    local.set $buffer_start\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Check if negative\n");
-        self.output.push_str("    local.get $value\n");
-        self.output.push_str("    i32.const 0\n");
-        self.output.push_str("    i32.lt_s\n");
-        self.output.push_str("    local.set $is_negative\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Get absolute value\n");
-        self.output.push_str("    local.get $is_negative\n");
-        self.output.push_str("    (if (result i32)\n");
-        self.output.push_str("      (then\n");
-        self.output.push_str("        i32.const 0\n");
-        self.output.push_str("        local.get $value\n");
-        self.output.push_str("        i32.sub\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("      (else\n");
-        self.output.push_str("        local.get $value\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("    )\n");
-        self.output.push_str("    local.set $num\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Handle zero special case\n");
-        self.output.push_str("    local.get $num\n");
-        self.output.push_str("    i32.eqz\n");
-        self.output.push_str("    (if\n");
-        self.output.push_str("      (then\n");
-        self.output.push_str("        local.get $buffer_start\n");
-        self.output.push_str("        i32.const 1\n");
-        self.output.push_str("        i32.sub\n");
-        self.output.push_str("        local.set $buffer_start\n");
-        self.output.push_str("        local.get $buffer_start\n");
-        self.output.push_str("        i32.const 48  ;; '0'\n");
-        self.output.push_str("        i32.store8\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("      (else\n");
-        self.output.push_str("        ;; Convert digits\n");
-        self.output.push_str("        (block $break\n");
-        self.output.push_str("          (loop $digit_loop\n");
-        self.output.push_str("            local.get $num\n");
-        self.output.push_str("            i32.eqz\n");
-        self.output.push_str("            br_if $break\n");
-        self.output.push_str("          \n");
-        self.output.push_str("          ;; Get last digit\n");
-        self.output.push_str("          local.get $num\n");
-        self.output.push_str("          i32.const 10\n");
-        self.output.push_str("          i32.rem_u\n");
-        self.output.push_str("          local.set $digit\n");
-        self.output.push_str("          \n");
-        self.output.push_str("          ;; Store digit character\n");
-        self.output.push_str("          local.get $buffer_start\n");
-        self.output.push_str("          i32.const 1\n");
-        self.output.push_str("          i32.sub\n");
-        self.output.push_str("          local.set $buffer_start\n");
-        self.output.push_str("          local.get $buffer_start\n");
-        self.output.push_str("          local.get $digit\n");
-        self.output.push_str("          i32.const 48  ;; '0'\n");
-        self.output.push_str("          i32.add\n");
-        self.output.push_str("          i32.store8\n");
-        self.output.push_str("          \n");
-        self.output.push_str("          ;; Divide by 10\n");
-        self.output.push_str("          local.get $num\n");
-        self.output.push_str("          i32.const 10\n");
-        self.output.push_str("          i32.div_u\n");
-        self.output.push_str("          local.set $num\n");
-        self.output.push_str("          \n");
-        self.output.push_str("            br $digit_loop\n");
-        self.output.push_str("          )\n");
-        self.output.push_str("        )\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("    )\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Add negative sign if needed\n");
-        self.output.push_str("    local.get $is_negative\n");
-        self.output.push_str("    (if\n");
-        self.output.push_str("      (then\n");
-        self.output.push_str("        local.get $buffer_start\n");
-        self.output.push_str("        i32.const 1\n");
-        self.output.push_str("        i32.sub\n");
-        self.output.push_str("        local.set $buffer_start\n");
-        self.output.push_str("        local.get $buffer_start\n");
-        self.output.push_str("        i32.const 45  ;; '-'\n");
-        self.output.push_str("        i32.store8\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("    )\n");
+        self.emit_int_digits(420);
         self.output.push_str("    \n");
         self.output.push_str("    ;; Add newline\n");
         self.output.push_str("    local.get $buffer_end\n");
@@ -684,52 +1863,6 @@ impl WasmCodeGen {
         self.output.push_str("    )\n");
         self.output.push_str("  )\n");
 
-        // Add println to function signatures
-        self.functions.insert(
-            "println".to_string(),
-            FunctionSig {
-                _params: vec![WasmType::I32],
-                result: None,
-            },
-        );
-        self.function_source_sigs.insert(
-            "println".to_string(),
-            FunctionSourceSig {
-                type_params: vec![],
-                params: vec![Type::Named("String".to_string())],
-                result: Some(Type::Named("Unit".to_string())),
-            },
-        );
-
-        // Add print_int to function signatures
-        self.functions.insert(
-            "print_int".to_string(),
-            FunctionSig {
-                _params: vec![WasmType::I32],
-                result: None,
-            },
-        );
-
-        self.generate_std_io_functions()?;
-        self.generate_std_math_functions()?;
-        self.generate_std_prelude_functions()?;
-
-        for (name, arity) in [("map", 2), ("filter", 2), ("fold", 3)] {
-            self.functions.insert(
-                name.to_string(),
-                FunctionSig {
-                    _params: vec![WasmType::I32; arity],
-                    result: Some(WasmType::I32),
-                },
-            );
-        }
-
-        self.generate_std_option_functions();
-
-        Ok(())
-    }
-
-    fn generate_std_io_functions(&mut self) -> Result<(), CodeGenError> {
         self.emit_string_write_function("print", 1, false);
         self.emit_string_write_function("eprint", 2, false);
         self.emit_string_write_function("eprintln", 2, true);
@@ -744,122 +1877,7 @@ impl WasmCodeGen {
         self.output.push_str("    (local $is_negative i32)\n");
         self.output.push_str("    (local $len i32)\n");
         self.output.push_str("    (local $abs_value f64)\n");
-        self.output.push_str("    f64.const 0\n");
-        self.output.push_str("    local.get $value\n");
-        self.output.push_str("    f64.gt\n");
-        self.output.push_str("    local.set $is_negative\n");
-        self.output.push_str("    local.get $is_negative\n");
-        self.output.push_str("    (if (result f64)\n");
-        self.output.push_str("      (then\n");
-        self.output.push_str("        local.get $value\n");
-        self.output.push_str("        f64.neg\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("      (else\n");
-        self.output.push_str("        local.get $value\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("    )\n");
-        self.output.push_str("    local.set $abs_value\n");
-        self.output.push_str("    local.get $abs_value\n");
-        self.output.push_str("    i32.trunc_f64_s\n");
-        self.output.push_str("    local.set $num\n");
-        self.output.push_str("    local.get $abs_value\n");
-        self.output.push_str("    local.get $num\n");
-        self.output.push_str("    f64.convert_i32_s\n");
-        self.output.push_str("    f64.sub\n");
-        self.output.push_str("    f64.const 100\n");
-        self.output.push_str("    f64.mul\n");
-        self.output.push_str("    i32.trunc_f64_s\n");
-        self.output.push_str("    local.set $frac\n");
-        self.output.push_str("    i32.const 700\n");
-        self.output.push_str("    local.set $buffer_end\n");
-        self.output.push_str("    local.get $buffer_end\n");
-        self.output.push_str("    local.set $buffer_start\n");
-
-        for _ in 0..2 {
-            self.output.push_str("    local.get $frac\n");
-            self.output.push_str("    i32.const 10\n");
-            self.output.push_str("    i32.rem_u\n");
-            self.output.push_str("    local.set $digit\n");
-            self.output.push_str("    local.get $buffer_start\n");
-            self.output.push_str("    i32.const 1\n");
-            self.output.push_str("    i32.sub\n");
-            self.output.push_str("    local.set $buffer_start\n");
-            self.output.push_str("    local.get $buffer_start\n");
-            self.output.push_str("    local.get $digit\n");
-            self.output.push_str("    i32.const 48\n");
-            self.output.push_str("    i32.add\n");
-            self.output.push_str("    i32.store8\n");
-            self.output.push_str("    local.get $frac\n");
-            self.output.push_str("    i32.const 10\n");
-            self.output.push_str("    i32.div_u\n");
-            self.output.push_str("    local.set $frac\n");
-        }
-
-        self.output.push_str("    local.get $buffer_start\n");
-        self.output.push_str("    i32.const 1\n");
-        self.output.push_str("    i32.sub\n");
-        self.output.push_str("    local.set $buffer_start\n");
-        self.output.push_str("    local.get $buffer_start\n");
-        self.output.push_str("    i32.const 46\n");
-        self.output.push_str("    i32.store8\n");
-        self.output.push_str("    local.get $num\n");
-        self.output.push_str("    i32.eqz\n");
-        self.output.push_str("    (if\n");
-        self.output.push_str("      (then\n");
-        self.output.push_str("        local.get $buffer_start\n");
-        self.output.push_str("        i32.const 1\n");
-        self.output.push_str("        i32.sub\n");
-        self.output.push_str("        local.set $buffer_start\n");
-        self.output.push_str("        local.get $buffer_start\n");
-        self.output.push_str("        i32.const 48\n");
-        self.output.push_str("        i32.store8\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("      (else\n");
-        self.output
-            .push_str("        (block $print_float_digits_done\n");
-        self.output
-            .push_str("          (loop $print_float_digits\n");
-        self.output.push_str("            local.get $num\n");
-        self.output.push_str("            i32.eqz\n");
-        self.output
-            .push_str("            br_if $print_float_digits_done\n");
-        self.output.push_str("            local.get $num\n");
-        self.output.push_str("            i32.const 10\n");
-        self.output.push_str("            i32.rem_u\n");
-        self.output.push_str("            local.set $digit\n");
-        self.output
-            .push_str("            local.get $buffer_start\n");
-        self.output.push_str("            i32.const 1\n");
-        self.output.push_str("            i32.sub\n");
-        self.output
-            .push_str("            local.set $buffer_start\n");
-        self.output
-            .push_str("            local.get $buffer_start\n");
-        self.output.push_str("            local.get $digit\n");
-        self.output.push_str("            i32.const 48\n");
-        self.output.push_str("            i32.add\n");
-        self.output.push_str("            i32.store8\n");
-        self.output.push_str("            local.get $num\n");
-        self.output.push_str("            i32.const 10\n");
-        self.output.push_str("            i32.div_u\n");
-        self.output.push_str("            local.set $num\n");
-        self.output.push_str("            br $print_float_digits\n");
-        self.output.push_str("          )\n");
-        self.output.push_str("        )\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("    )\n");
-        self.output.push_str("    local.get $is_negative\n");
-        self.output.push_str("    (if\n");
-        self.output.push_str("      (then\n");
-        self.output.push_str("        local.get $buffer_start\n");
-        self.output.push_str("        i32.const 1\n");
-        self.output.push_str("        i32.sub\n");
-        self.output.push_str("        local.set $buffer_start\n");
-        self.output.push_str("        local.get $buffer_start\n");
-        self.output.push_str("        i32.const 45\n");
-        self.output.push_str("        i32.store8\n");
-        self.output.push_str("      )\n");
-        self.output.push_str("    )\n");
+        self.emit_float_digits(700);
         self.output.push_str("    local.get $buffer_end\n");
         self.output.push_str("    local.get $buffer_start\n");
         self.output.push_str("    i32.sub\n");
@@ -877,7 +1895,9 @@ impl WasmCodeGen {
         self.output.push_str("    call $fd_write\n");
         self.output.push_str("    drop\n");
         self.output.push_str("  )\n");
+    }
 
+    fn generate_std_io_functions(&mut self) -> Result<(), CodeGenError> {
         for (name, param_ty) in [
             ("print", Type::Named("String".to_string())),
             ("print_int", Type::Named("Int32".to_string())),
@@ -903,16 +1923,1926 @@ impl WasmCodeGen {
             );
         }
 
+        if self.uses_program_args {
+            self.generate_args_function();
+        }
+        if self.uses_environ {
+            self.generate_env_var_function();
+        }
+        if self.uses_clock {
+            self.generate_now_function();
+        }
+
         Ok(())
     }
 
-    fn emit_string_write_function(&mut self, name: &str, fd: i32, newline: bool) {
-        self.output
-            .push_str(&format!("  (func ${} (param $str i32)\n", name));
+    /// Emits `args`, which copies the WASI argument vector into a
+    /// `List<String>` in the current arena. `argv[0]` is the program name
+    /// and is skipped.
+    fn generate_args_function(&mut self) {
+        self.output.push_str("  (func $args (result i32)\n");
+        self.output.push_str("    (local $argc i32)\n");
+        self.output.push_str("    (local $argv i32)\n");
+        self.output.push_str("    (local $buffer i32)\n");
+        self.output.push_str("    (local $count i32)\n");
+        self.output.push_str("    (local $list i32)\n");
+        self.output.push_str("    (local $index i32)\n");
+        self.output.push_str("    (local $arg i32)\n");
         self.output.push_str("    (local $len i32)\n");
-        self.output.push_str("    local.get $str\n");
-        self.output.push_str("    i32.load\n");
-        self.output.push_str("    local.set $len\n");
+        self.output.push_str("    (local $text i32)\n");
+        self.output
+            .push_str("    ;; argc and the argument buffer size land in scratch memory\n");
+        self.output.push_str("    i32.const 200\n");
+        self.output.push_str("    i32.const 204\n");
+        self.output.push_str("    call $args_sizes_get\n");
+        self.output.push_str("    drop\n");
+        self.output.push_str("    i32.const 200\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $argc\n");
+        self.output.push_str("    local.get $argc\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $argv\n");
+        self.output.push_str("    i32.const 204\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $buffer\n");
+        self.output.push_str("    local.get $argv\n");
+        self.output.push_str("    local.get $buffer\n");
+        self.output.push_str("    call $args_get\n");
+        self.output.push_str("    drop\n");
+        self.output
+            .push_str("    ;; Skip argv[0], the program name\n");
+        self.output.push_str("    local.get $argc\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    local.get $argc\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.gt_u\n");
+        self.output.push_str("    select\n");
+        self.output.push_str("    local.set $count\n");
+        self.output.push_str("    local.get $count\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $list\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    local.get $count\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $count\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    (block $args_done\n");
+        self.output.push_str("      (loop $args_loop\n");
+        self.output.push_str("        local.get $index\n");
+        self.output.push_str("        local.get $count\n");
+        self.output.push_str("        i32.ge_u\n");
+        self.output.push_str("        br_if $args_done\n");
+        self.output.push_str("        local.get $argv\n");
+        self.output.push_str("        local.get $index\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        local.set $arg\n");
+        self.output
+            .push_str("        ;; WASI arguments are NUL-terminated; measure before copying\n");
+        self.output.push_str("        i32.const 0\n");
+        self.output.push_str("        local.set $len\n");
+        self.output.push_str("        (block $measured\n");
+        self.output.push_str("          (loop $measure\n");
+        self.output.push_str("            local.get $arg\n");
+        self.output.push_str("            local.get $len\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            i32.load8_u\n");
+        self.output.push_str("            i32.eqz\n");
+        self.output.push_str("            br_if $measured\n");
+        self.output.push_str("            local.get $len\n");
+        self.output.push_str("            i32.const 1\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.set $len\n");
+        self.output.push_str("            br $measure\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        local.get $len\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        call $allocate\n");
+        self.output.push_str("        local.set $text\n");
+        self.output.push_str("        local.get $text\n");
+        self.output.push_str("        local.get $len\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("        local.get $text\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $arg\n");
+        self.output.push_str("        local.get $len\n");
+        self.output.push_str("        memory.copy\n");
+        self.output.push_str("        local.get $list\n");
+        self.output.push_str("        i32.const 8\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $index\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $text\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("        local.get $index\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $index\n");
+        self.output.push_str("        br $args_loop\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "args".to_string(),
+            FunctionSig {
+                _params: vec![],
+                result: Some(WasmType::I32),
+            },
+        );
+        self.function_source_sigs.insert(
+            "args".to_string(),
+            FunctionSourceSig {
+                type_params: vec![],
+                params: vec![],
+                result: Some(Type::Generic(
+                    "List".to_string(),
+                    vec![Type::Named("String".to_string())],
+                )),
+            },
+        );
+    }
+
+    /// Finish a checked arithmetic helper. With the overflow flag on the
+    /// stack, fill `$option` with `None`, or with `Some` of the value that
+    /// `value` pushes, and leave `$option` as the result.
+    fn emit_checked_option_result(&mut self, value: &str) {
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str("        i32.const 0\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str(&format!("        {value}\n"));
+        self.output.push_str("        i32.store offset=4\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $option\n");
+    }
+
+    /// Emits `env_var`, which looks `name` up in the WASI environment.
+    /// Entries are `KEY=VALUE` and NUL-terminated; the value of the first
+    /// entry whose key equals `name` is copied into a `Some(String)` in the
+    /// current arena, and a missing key gives `None`.
+    fn generate_env_var_function(&mut self) {
+        self.output
+            .push_str("  (func $env_var (param $name i32) (result i32)\n");
+        self.output.push_str("    (local $count i32)\n");
+        self.output.push_str("    (local $environ i32)\n");
+        self.output.push_str("    (local $buffer i32)\n");
+        self.output.push_str("    (local $index i32)\n");
+        self.output.push_str("    (local $entry i32)\n");
+        self.output.push_str("    (local $name_len i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    (local $value i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $text i32)\n");
+        self.output.push_str("    (local $option i32)\n");
+        self.output.push_str(
+            "    ;; The entry count and the environment buffer size land in scratch memory\n",
+        );
+        self.output.push_str("    i32.const 200\n");
+        self.output.push_str("    i32.const 204\n");
+        self.output.push_str("    call $environ_sizes_get\n");
+        self.output.push_str("    drop\n");
+        self.output.push_str("    i32.const 200\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $count\n");
+        self.output.push_str("    local.get $count\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $environ\n");
+        self.output.push_str("    i32.const 204\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $buffer\n");
+        self.output.push_str("    local.get $environ\n");
+        self.output.push_str("    local.get $buffer\n");
+        self.output.push_str("    call $environ_get\n");
+        self.output.push_str("    drop\n");
+        self.output.push_str("    local.get $name\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $name_len\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $option\n");
+        self.output.push_str("    (block $not_found\n");
+        self.output.push_str("      (loop $entries\n");
+        self.output.push_str("        local.get $index\n");
+        self.output.push_str("        local.get $count\n");
+        self.output.push_str("        i32.ge_u\n");
+        self.output.push_str("        br_if $not_found\n");
+        self.output.push_str("        local.get $environ\n");
+        self.output.push_str("        local.get $index\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        local.set $entry\n");
+        self.output.push_str("        i32.const 0\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        (block $next\n");
+        self.output.push_str("          (loop $compare\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            local.get $name_len\n");
+        self.output.push_str("            i32.ge_u\n");
+        self.output.push_str("            (if\n");
+        self.output.push_str("              (then\n");
+        self.output
+            .push_str("                ;; The whole key matched; it must end at '='\n");
+        self.output.push_str("                local.get $entry\n");
+        self.output.push_str("                local.get $i\n");
+        self.output.push_str("                i32.add\n");
+        self.output.push_str("                i32.load8_u\n");
+        self.output.push_str("                i32.const 61\n");
+        self.output.push_str("                i32.ne\n");
+        self.output.push_str("                br_if $next\n");
+        self.output.push_str("                local.get $entry\n");
+        self.output.push_str("                local.get $i\n");
+        self.output.push_str("                i32.add\n");
+        self.output.push_str("                i32.const 1\n");
+        self.output.push_str("                i32.add\n");
+        self.output.push_str("                local.set $value\n");
+        self.output.push_str("                (block $measured\n");
+        self.output.push_str("                  (loop $measure\n");
+        self.output
+            .push_str("                    local.get $value\n");
+        self.output.push_str("                    local.get $len\n");
+        self.output.push_str("                    i32.add\n");
+        self.output.push_str("                    i32.load8_u\n");
+        self.output.push_str("                    i32.eqz\n");
+        self.output
+            .push_str("                    br_if $measured\n");
+        self.output.push_str("                    local.get $len\n");
+        self.output.push_str("                    i32.const 1\n");
+        self.output.push_str("                    i32.add\n");
+        self.output.push_str("                    local.set $len\n");
+        self.output.push_str("                    br $measure\n");
+        self.output.push_str("                  )\n");
+        self.output.push_str("                )\n");
+        self.output.push_str("                local.get $len\n");
+        self.output.push_str("                i32.const 4\n");
+        self.output.push_str("                i32.add\n");
+        self.output.push_str("                call $allocate\n");
+        self.output.push_str("                local.set $text\n");
+        self.output.push_str("                local.get $text\n");
+        self.output.push_str("                local.get $len\n");
+        self.output.push_str("                i32.store\n");
+        self.output.push_str("                local.get $text\n");
+        self.output.push_str("                i32.const 4\n");
+        self.output.push_str("                i32.add\n");
+        self.output.push_str("                local.get $value\n");
+        self.output.push_str("                local.get $len\n");
+        self.output.push_str("                memory.copy\n");
+        self.output.push_str("                local.get $option\n");
+        self.output.push_str("                i32.const 1\n");
+        self.output.push_str("                i32.store\n");
+        self.output.push_str("                local.get $option\n");
+        self.output.push_str("                local.get $text\n");
+        self.output.push_str("                i32.store offset=4\n");
+        self.output.push_str("                local.get $option\n");
+        self.output.push_str("                return\n");
+        self.output.push_str("              )\n");
+        self.output.push_str("            )\n");
+        self.output
+            .push_str("            ;; A shorter entry ends at its NUL before the key does\n");
+        self.output.push_str("            local.get $entry\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            i32.load8_u\n");
+        self.output.push_str("            i32.eqz\n");
+        self.output.push_str("            br_if $next\n");
+        self.output.push_str("            local.get $entry\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            i32.load8_u\n");
+        self.output.push_str("            local.get $name\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            i32.load8_u offset=4\n");
+        self.output.push_str("            i32.ne\n");
+        self.output.push_str("            br_if $next\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            i32.const 1\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.set $i\n");
+        self.output.push_str("            br $compare\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        local.get $index\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $index\n");
+        self.output.push_str("        br $entries\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "env_var".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32],
+                result: Some(WasmType::I32),
+            },
+        );
+        self.function_source_sigs.insert(
+            "env_var".to_string(),
+            FunctionSourceSig {
+                type_params: vec![],
+                params: vec![Type::Named("String".to_string())],
+                result: Some(Type::Generic(
+                    "Option".to_string(),
+                    vec![Type::Named("String".to_string())],
+                )),
+            },
+        );
+    }
+
+    /// Emits `now`, the WASI realtime clock in nanoseconds since the Unix
+    /// epoch.
+    fn generate_now_function(&mut self) {
+        self.output.push_str("  (func $now (result i64)\n");
+        self.output.push_str("    i32.const 0 ;; realtime clock\n");
+        self.output
+            .push_str("    i64.const 1 ;; nanosecond precision\n");
+        self.output.push_str("    i32.const 200\n");
+        self.output.push_str("    call $clock_time_get\n");
+        self.output.push_str("    drop\n");
+        self.output.push_str("    i32.const 200\n");
+        self.output.push_str("    i64.load\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "now".to_string(),
+            FunctionSig {
+                _params: vec![],
+                result: Some(WasmType::I64),
+            },
+        );
+        self.function_source_sigs.insert(
+            "now".to_string(),
+            FunctionSourceSig {
+                type_params: vec![],
+                params: vec![],
+                result: Some(Type::Named("Int64".to_string())),
+            },
+        );
+    }
+
+    /// Emit the decimal digits of the Int32 in `$value` into scratch memory,
+    /// writing backwards from `buffer_end`. Leaves the text in
+    /// `$buffer_start..$buffer_end`; the caller declares `$num`, `$digit`,
+    /// `$buffer_start`, `$buffer_end`, and `$is_negative`.
+    fn emit_int_digits(&mut self, buffer_end: i32) {
+        self.output.push_str(&format!(
+            "    i32.const {}  ;; Start from the end of buffer and work backwards\n",
+            buffer_end
+        ));
+        self.output.push_str("    local.set $buffer_end\n");
+        self.output.push_str("    local.get $buffer_end\n");
+        self.output.push_str("    local.set $buffer_start\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Check if negative\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.lt_s\n");
+        self.output.push_str("    local.set $is_negative\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Get absolute value\n");
+        self.output.push_str("    local.get $is_negative\n");
+        self.output.push_str("    (if (result i32)\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        i32.const 0\n");
+        self.output.push_str("        local.get $value\n");
+        self.output.push_str("        i32.sub\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output.push_str("        local.get $value\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.set $num\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Handle zero special case\n");
+        self.output.push_str("    local.get $num\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $buffer_start\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.sub\n");
+        self.output.push_str("        local.set $buffer_start\n");
+        self.output.push_str("        local.get $buffer_start\n");
+        self.output.push_str("        i32.const 48  ;; '0'\n");
+        self.output.push_str("        i32.store8\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output.push_str("        ;; Convert digits\n");
+        self.output.push_str("        (block $break\n");
+        self.output.push_str("          (loop $digit_loop\n");
+        self.output.push_str("            local.get $num\n");
+        self.output.push_str("            i32.eqz\n");
+        self.output.push_str("            br_if $break\n");
+        self.output.push_str("          \n");
+        self.output.push_str("          ;; Get last digit\n");
+        self.output.push_str("          local.get $num\n");
+        self.output.push_str("          i32.const 10\n");
+        self.output.push_str("          i32.rem_u\n");
+        self.output.push_str("          local.set $digit\n");
+        self.output.push_str("          \n");
+        self.output.push_str("          ;; Store digit character\n");
+        self.output.push_str("          local.get $buffer_start\n");
+        self.output.push_str("          i32.const 1\n");
+        self.output.push_str("          i32.sub\n");
+        self.output.push_str("          local.set $buffer_start\n");
+        self.output.push_str("          local.get $buffer_start\n");
+        self.output.push_str("          local.get $digit\n");
+        self.output.push_str("          i32.const 48  ;; '0'\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          i32.store8\n");
+        self.output.push_str("          \n");
+        self.output.push_str("          ;; Divide by 10\n");
+        self.output.push_str("          local.get $num\n");
+        self.output.push_str("          i32.const 10\n");
+        self.output.push_str("          i32.div_u\n");
+        self.output.push_str("          local.set $num\n");
+        self.output.push_str("          \n");
+        self.output.push_str("            br $digit_loop\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Add negative sign if needed\n");
+        self.output.push_str("    local.get $is_negative\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $buffer_start\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.sub\n");
+        self.output.push_str("        local.set $buffer_start\n");
+        self.output.push_str("        local.get $buffer_start\n");
+        self.output.push_str("        i32.const 45  ;; '-'\n");
+        self.output.push_str("        i32.store8\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+    }
+
+    /// Emit the Float64 in `$value` as decimal text with two fractional
+    /// digits, writing backwards from `buffer_end` like [`Self::emit_int_digits`].
+    /// The caller additionally declares `$frac` and `$abs_value`.
+    fn emit_float_digits(&mut self, buffer_end: i32) {
+        self.output.push_str("    f64.const 0\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    f64.gt\n");
+        self.output.push_str("    local.set $is_negative\n");
+        self.output.push_str("    local.get $is_negative\n");
+        self.output.push_str("    (if (result f64)\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $value\n");
+        self.output.push_str("        f64.neg\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output.push_str("        local.get $value\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.set $abs_value\n");
+        self.output.push_str("    local.get $abs_value\n");
+        self.output.push_str("    i32.trunc_f64_s\n");
+        self.output.push_str("    local.set $num\n");
+        self.output.push_str("    local.get $abs_value\n");
+        self.output.push_str("    local.get $num\n");
+        self.output.push_str("    f64.convert_i32_s\n");
+        self.output.push_str("    f64.sub\n");
+        self.output.push_str("    f64.const 100\n");
+        self.output.push_str("    f64.mul\n");
+        self.output.push_str("    i32.trunc_f64_s\n");
+        self.output.push_str("    local.set $frac\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", buffer_end));
+        self.output.push_str("    local.set $buffer_end\n");
+        self.output.push_str("    local.get $buffer_end\n");
+        self.output.push_str("    local.set $buffer_start\n");
+
+        for _ in 0..2 {
+            self.output.push_str("    local.get $frac\n");
+            self.output.push_str("    i32.const 10\n");
+            self.output.push_str("    i32.rem_u\n");
+            self.output.push_str("    local.set $digit\n");
+            self.output.push_str("    local.get $buffer_start\n");
+            self.output.push_str("    i32.const 1\n");
+            self.output.push_str("    i32.sub\n");
+            self.output.push_str("    local.set $buffer_start\n");
+            self.output.push_str("    local.get $buffer_start\n");
+            self.output.push_str("    local.get $digit\n");
+            self.output.push_str("    i32.const 48\n");
+            self.output.push_str("    i32.add\n");
+            self.output.push_str("    i32.store8\n");
+            self.output.push_str("    local.get $frac\n");
+            self.output.push_str("    i32.const 10\n");
+            self.output.push_str("    i32.div_u\n");
+            self.output.push_str("    local.set $frac\n");
+        }
+
+        self.output.push_str("    local.get $buffer_start\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    local.set $buffer_start\n");
+        self.output.push_str("    local.get $buffer_start\n");
+        self.output.push_str("    i32.const 46\n");
+        self.output.push_str("    i32.store8\n");
+        self.output.push_str("    local.get $num\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $buffer_start\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.sub\n");
+        self.output.push_str("        local.set $buffer_start\n");
+        self.output.push_str("        local.get $buffer_start\n");
+        self.output.push_str("        i32.const 48\n");
+        self.output.push_str("        i32.store8\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output
+            .push_str("        (block $print_float_digits_done\n");
+        self.output
+            .push_str("          (loop $print_float_digits\n");
+        self.output.push_str("            local.get $num\n");
+        self.output.push_str("            i32.eqz\n");
+        self.output
+            .push_str("            br_if $print_float_digits_done\n");
+        self.output.push_str("            local.get $num\n");
+        self.output.push_str("            i32.const 10\n");
+        self.output.push_str("            i32.rem_u\n");
+        self.output.push_str("            local.set $digit\n");
+        self.output
+            .push_str("            local.get $buffer_start\n");
+        self.output.push_str("            i32.const 1\n");
+        self.output.push_str("            i32.sub\n");
+        self.output
+            .push_str("            local.set $buffer_start\n");
+        self.output
+            .push_str("            local.get $buffer_start\n");
+        self.output.push_str("            local.get $digit\n");
+        self.output.push_str("            i32.const 48\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            i32.store8\n");
+        self.output.push_str("            local.get $num\n");
+        self.output.push_str("            i32.const 10\n");
+        self.output.push_str("            i32.div_u\n");
+        self.output.push_str("            local.set $num\n");
+        self.output.push_str("            br $print_float_digits\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $is_negative\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $buffer_start\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.sub\n");
+        self.output.push_str("        local.set $buffer_start\n");
+        self.output.push_str("        local.get $buffer_start\n");
+        self.output.push_str("        i32.const 45\n");
+        self.output.push_str("        i32.store8\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+    }
+
+    /// `int_to_string`, `float_to_string`, and `bool_to_string` build the
+    /// same text the print helpers write, but return it as a length-prefixed
+    /// String allocated in the current arena.
+    fn generate_std_convert_functions(&mut self) -> Result<(), CodeGenError> {
+        self.output
+            .push_str("  (func $int_to_string (param $value i32) (result i32)\n");
+        self.output.push_str("    (local $num i32)\n");
+        self.output.push_str("    (local $digit i32)\n");
+        self.output.push_str("    (local $buffer_start i32)\n");
+        self.output.push_str("    (local $buffer_end i32)\n");
+        self.output.push_str("    (local $is_negative i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $out i32)\n");
+        self.emit_int_digits(420);
+        self.emit_scratch_to_string();
+        self.output.push_str("  )\n");
+
+        self.output
+            .push_str("  (func $float_to_string (param $value f64) (result i32)\n");
+        self.output.push_str("    (local $num i32)\n");
+        self.output.push_str("    (local $frac i32)\n");
+        self.output.push_str("    (local $digit i32)\n");
+        self.output.push_str("    (local $buffer_start i32)\n");
+        self.output.push_str("    (local $buffer_end i32)\n");
+        self.output.push_str("    (local $is_negative i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $out i32)\n");
+        self.output.push_str("    (local $abs_value f64)\n");
+        self.emit_float_digits(700);
+        self.emit_scratch_to_string();
+        self.output.push_str("  )\n");
+
+        self.output
+            .push_str("  (func $bool_to_string (param $value i32) (result i32)\n");
+        self.output.push_str("    (local $out i32)\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    (if (result i32)\n");
+        self.output.push_str("      (then\n");
+        self.emit_ascii_string_alloc("true", "        ");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.emit_ascii_string_alloc("false", "        ");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("  )\n");
+
+        for (name, param_ty) in [
+            ("int_to_string", Type::Named("Int32".to_string())),
+            ("float_to_string", Type::Named("Float64".to_string())),
+            ("bool_to_string", Type::Named("Boolean".to_string())),
+        ] {
+            let wasm_param = self.convert_type(&param_ty)?;
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![wasm_param],
+                    result: Some(WasmType::I32),
+                },
+            );
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec![],
+                    params: vec![param_ty],
+                    result: Some(Type::Named("String".to_string())),
+                },
+            );
+        }
+
+        self.generate_display_functions();
+        Ok(())
+    }
+
+    /// Text for the remaining `Display` types, used by `println`: Int64,
+    /// Decimal in the same form as its literals without the `d`, and `()`.
+    fn generate_display_functions(&mut self) {
+        for (name, decimal) in [("int64_to_string", false), ("decimal_to_string", true)] {
+            self.output.push_str(&format!(
+                "  (func ${name} (param $value i64) (result i32)\n"
+            ));
+            self.output.push_str("    (local $num i64)\n");
+            self.output.push_str("    (local $frac i64)\n");
+            self.output.push_str("    (local $places i32)\n");
+            self.output.push_str("    (local $buffer_start i32)\n");
+            self.output.push_str("    (local $buffer_end i32)\n");
+            self.output.push_str("    (local $len i32)\n");
+            self.output.push_str("    (local $out i32)\n");
+            self.output.push_str("    i32.const 900\n");
+            self.output.push_str("    local.tee $buffer_end\n");
+            self.output.push_str("    local.set $buffer_start\n");
+            // The magnitude read as unsigned, so the minimum still prints.
+            self.output.push_str("    i64.const 0\n");
+            self.output.push_str("    local.get $value\n");
+            self.output.push_str("    i64.sub\n");
+            self.output.push_str("    local.get $value\n");
+            self.output.push_str("    local.get $value\n");
+            self.output.push_str("    i64.const 0\n");
+            self.output.push_str("    i64.lt_s\n");
+            self.output.push_str("    select\n");
+            self.output.push_str("    local.set $num\n");
+            if decimal {
+                self.output.push_str("    local.get $num\n");
+                self.output
+                    .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+                self.output.push_str("    i64.rem_u\n");
+                self.output.push_str("    local.set $frac\n");
+                self.output.push_str("    local.get $num\n");
+                self.output
+                    .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+                self.output.push_str("    i64.div_u\n");
+                self.output.push_str("    local.set $num\n");
+                self.output
+                    .push_str(&format!("    i32.const {}\n", DECIMAL_PLACES));
+                self.output.push_str("    local.set $places\n");
+                self.output.push_str("    local.get $frac\n");
+                self.output.push_str("    i64.eqz\n");
+                self.output.push_str("    i32.eqz\n");
+                self.output.push_str("    (if\n");
+                self.output.push_str("      (then\n");
+                // Trailing zeros are dropped, leading ones kept.
+                self.output.push_str("        (block $trimmed\n");
+                self.output.push_str("          (loop $trim\n");
+                self.output.push_str("            local.get $frac\n");
+                self.output.push_str("            i64.const 10\n");
+                self.output.push_str("            i64.rem_u\n");
+                self.output.push_str("            i64.eqz\n");
+                self.output.push_str("            i32.eqz\n");
+                self.output.push_str("            br_if $trimmed\n");
+                self.output.push_str("            local.get $frac\n");
+                self.output.push_str("            i64.const 10\n");
+                self.output.push_str("            i64.div_u\n");
+                self.output.push_str("            local.set $frac\n");
+                self.output.push_str("            local.get $places\n");
+                self.output.push_str("            i32.const 1\n");
+                self.output.push_str("            i32.sub\n");
+                self.output.push_str("            local.set $places\n");
+                self.output.push_str("            br $trim\n");
+                self.output.push_str("          )\n");
+                self.output.push_str("        )\n");
+                self.output.push_str("        (loop $fraction_digit\n");
+                self.emit_scratch_digit("$frac", "          ");
+                self.output.push_str("          local.get $places\n");
+                self.output.push_str("          i32.const 1\n");
+                self.output.push_str("          i32.sub\n");
+                self.output.push_str("          local.tee $places\n");
+                self.output.push_str("          br_if $fraction_digit\n");
+                self.output.push_str("        )\n");
+                self.emit_scratch_byte(b'.', "        ");
+                self.output.push_str("      )\n");
+                self.output.push_str("    )\n");
+            }
+            self.output.push_str("    (loop $whole_digit\n");
+            self.emit_scratch_digit("$num", "      ");
+            self.output.push_str("      local.get $num\n");
+            self.output.push_str("      i64.eqz\n");
+            self.output.push_str("      i32.eqz\n");
+            self.output.push_str("      br_if $whole_digit\n");
+            self.output.push_str("    )\n");
+            self.output.push_str("    local.get $value\n");
+            self.output.push_str("    i64.const 0\n");
+            self.output.push_str("    i64.lt_s\n");
+            self.output.push_str("    (if\n");
+            self.output.push_str("      (then\n");
+            self.emit_scratch_byte(b'-', "        ");
+            self.output.push_str("      )\n");
+            self.output.push_str("    )\n");
+            self.emit_scratch_to_string();
+            self.output.push_str("  )\n");
+        }
+
+        self.output
+            .push_str("  (func $unit_to_string (param $value i32) (result i32)\n");
+        self.output.push_str("    (local $out i32)\n");
+        self.emit_ascii_string_alloc("()", "    ");
+        self.output.push_str("  )\n");
+    }
+
+    /// Prepend the lowest decimal digit of the i64 `local` to the scratch
+    /// text and divide `local` by ten.
+    fn emit_scratch_digit(&mut self, local: &str, indent: &str) {
+        self.output
+            .push_str(&format!("{indent}local.get $buffer_start\n"));
+        self.output.push_str(&format!("{indent}i32.const 1\n"));
+        self.output.push_str(&format!("{indent}i32.sub\n"));
+        self.output
+            .push_str(&format!("{indent}local.tee $buffer_start\n"));
+        self.output
+            .push_str(&format!("{indent}local.get {local}\n"));
+        self.output.push_str(&format!("{indent}i64.const 10\n"));
+        self.output.push_str(&format!("{indent}i64.rem_u\n"));
+        self.output.push_str(&format!("{indent}i32.wrap_i64\n"));
+        self.output.push_str(&format!("{indent}i32.const 48\n"));
+        self.output.push_str(&format!("{indent}i32.add\n"));
+        self.output.push_str(&format!("{indent}i32.store8\n"));
+        self.output
+            .push_str(&format!("{indent}local.get {local}\n"));
+        self.output.push_str(&format!("{indent}i64.const 10\n"));
+        self.output.push_str(&format!("{indent}i64.div_u\n"));
+        self.output
+            .push_str(&format!("{indent}local.set {local}\n"));
+    }
+
+    /// Prepend the ASCII `byte` to the scratch text.
+    fn emit_scratch_byte(&mut self, byte: u8, indent: &str) {
+        self.output
+            .push_str(&format!("{indent}local.get $buffer_start\n"));
+        self.output.push_str(&format!("{indent}i32.const 1\n"));
+        self.output.push_str(&format!("{indent}i32.sub\n"));
+        self.output
+            .push_str(&format!("{indent}local.tee $buffer_start\n"));
+        self.output.push_str(&format!("{indent}i32.const {byte}\n"));
+        self.output.push_str(&format!("{indent}i32.store8\n"));
+    }
+
+    /// `hash` folds a value into an Int32 with 32-bit FNV-1a. `$fnv_mix`
+    /// feeds the four little-endian bytes of an i32 into a running hash;
+    /// wider scalars feed both halves, Strings feed their UTF-8 bytes, and
+    /// records feed the hash of each field in declaration order.
+    fn generate_std_hash_functions(&mut self) -> Result<(), CodeGenError> {
+        self.output
+            .push_str("  (func $fnv_mix (param $hash i32) (param $value i32) (result i32)\n");
+        for shift in [0, 8, 16, 24] {
+            self.output.push_str("    local.get $hash\n");
+            self.output.push_str("    local.get $value\n");
+            self.output.push_str(&format!("    i32.const {shift}\n"));
+            self.output.push_str("    i32.shr_u\n");
+            self.output.push_str("    i32.const 255\n");
+            self.output.push_str("    i32.and\n");
+            self.output.push_str("    i32.xor\n");
+            self.output.push_str("    i32.const 16777619\n");
+            self.output.push_str("    i32.mul\n");
+            self.output.push_str("    local.set $hash\n");
+        }
+        self.output.push_str("    local.get $hash\n");
+        self.output.push_str("  )\n");
+
+        self.output
+            .push_str("  (func $hash (param $value i32) (result i32)\n");
+        self.output.push_str("    i32.const -2128831035\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    call $fnv_mix\n");
+        self.output.push_str("  )\n");
+
+        self.output
+            .push_str("  (func $hash_i64 (param $value i64) (result i32)\n");
+        self.output.push_str("    i32.const -2128831035\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.wrap_i64\n");
+        self.output.push_str("    call $fnv_mix\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i64.const 32\n");
+        self.output.push_str("    i64.shr_u\n");
+        self.output.push_str("    i32.wrap_i64\n");
+        self.output.push_str("    call $fnv_mix\n");
+        self.output.push_str("  )\n");
+
+        self.output
+            .push_str("  (func $hash_f64 (param $value f64) (result i32)\n");
+        // Adding +0.0 turns -0.0 into +0.0, so values that compare equal
+        // hash equally.
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    f64.const 0\n");
+        self.output.push_str("    f64.add\n");
+        self.output.push_str("    i64.reinterpret_f64\n");
+        self.output.push_str("    call $hash_i64\n");
+        self.output.push_str("  )\n");
+
+        self.output
+            .push_str("  (func $hash_string (param $str i32) (result i32)\n");
+        self.output.push_str("    (local $hash i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    i32.const -2128831035\n");
+        self.output.push_str("    local.set $hash\n");
+        self.output.push_str("    (block $hashed\n");
+        self.output.push_str("      (loop $next_byte\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $str\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        i32.ge_u\n");
+        self.output.push_str("        br_if $hashed\n");
+        self.output.push_str("        local.get $hash\n");
+        self.output.push_str("        local.get $str\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load8_u offset=4\n");
+        self.output.push_str("        i32.xor\n");
+        self.output.push_str("        i32.const 16777619\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        local.set $hash\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        br $next_byte\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $hash\n");
+        self.output.push_str("  )\n");
+
+        for (name, param) in [
+            ("hash", WasmType::I32),
+            ("hash_i64", WasmType::I64),
+            ("hash_f64", WasmType::F64),
+            ("hash_string", WasmType::I32),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![param],
+                    result: Some(WasmType::I32),
+                },
+            );
+        }
+        self.function_source_sigs.insert(
+            "hash".to_string(),
+            FunctionSourceSig {
+                type_params: vec!["T".to_string()],
+                params: vec![Type::Named("T".to_string())],
+                result: Some(Type::Named("Int32".to_string())),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Emit `$hash_record_<Name>` for every non-generic record whose fields
+    /// all hash. The type checker only admits frozen records of that shape.
+    fn generate_record_hash_functions(&mut self) -> Result<(), CodeGenError> {
+        let mut names: Vec<String> = self.records.keys().cloned().collect();
+        names.sort();
+        for name in names {
+            if !self.record_hashable(&name, &mut HashSet::new()) {
+                continue;
+            }
+            let fields = self.records[&name].clone();
+            let offsets = self.record_field_offsets[&name].clone();
+            self.output.push_str(&format!(
+                "  (func $hash_record_{} (param $record i32) (result i32)\n",
+                name
+            ));
+            self.output.push_str("    i32.const -2128831035\n");
+            for (field, ty) in &fields {
+                let (load, hash_fn) = match ty {
+                    Type::Named(ty_name) => match ty_name.as_str() {
+                        "Int64" | "Decimal" => ("i64.load", "hash_i64".to_string()),
+                        "Float64" => ("f64.load", "hash_f64".to_string()),
+                        "String" => ("i32.load", "hash_string".to_string()),
+                        "Int32" | "Boolean" | "Char" => ("i32.load", "hash".to_string()),
+                        record => ("i32.load", format!("hash_record_{}", record)),
+                    },
+                    _ => unreachable!("record_hashable admits named field types only"),
+                };
+                self.output.push_str("    local.get $record\n");
+                self.output
+                    .push_str(&format!("    {} offset={}\n", load, offsets[field]));
+                self.output.push_str(&format!("    call ${}\n", hash_fn));
+                self.output.push_str("    call $fnv_mix\n");
+            }
+            self.output.push_str("  )\n");
+            self.functions.insert(
+                format!("hash_record_{}", name),
+                FunctionSig {
+                    _params: vec![WasmType::I32],
+                    result: Some(WasmType::I32),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn record_hashable(&self, name: &str, visiting: &mut HashSet<String>) -> bool {
+        let Some(fields) = self.records.get(name) else {
+            return false;
+        };
+        if !visiting.insert(name.to_string())
+            || self
+                .record_type_params
+                .get(name)
+                .is_some_and(|params| !params.is_empty())
+        {
+            return false;
+        }
+        let hashable = fields.iter().all(|(_, ty)| match ty {
+            Type::Named(ty_name) => {
+                matches!(
+                    ty_name.as_str(),
+                    "Int32" | "Int64" | "Float64" | "Decimal" | "Boolean" | "Char" | "String"
+                ) || self.record_hashable(ty_name, visiting)
+            }
+            _ => false,
+        });
+        visiting.remove(name);
+        hashable
+    }
+
+    /// `parse_int` and `parse_float` read an optionally signed decimal
+    /// String. Malformed text, an empty digit run, or a value outside the
+    /// target range yields `None`; `parse_float` accepts one decimal point.
+    fn generate_std_parse_functions(&mut self) -> Result<(), CodeGenError> {
+        self.output
+            .push_str("  (func $parse_int (param $str i32) (result i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $pos i32)\n");
+        self.output.push_str("    (local $byte i32)\n");
+        self.output.push_str("    (local $negative i32)\n");
+        self.output.push_str("    (local $value i64)\n");
+        self.output.push_str("    (local $option i32)\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $option\n");
+        self.output.push_str("    (block $none\n");
+        self.emit_parse_sign();
+        self.output.push_str("      local.get $pos\n");
+        self.output.push_str("      local.get $len\n");
+        self.output.push_str("      i32.ge_u\n");
+        self.output.push_str("      br_if $none\n");
+        self.output.push_str("      (block $digits_done\n");
+        self.output.push_str("        (loop $digits\n");
+        self.output.push_str("          local.get $pos\n");
+        self.output.push_str("          local.get $len\n");
+        self.output.push_str("          i32.ge_u\n");
+        self.output.push_str("          br_if $digits_done\n");
+        self.output.push_str("          local.get $str\n");
+        self.output.push_str("          local.get $pos\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          i32.load8_u offset=4\n");
+        self.output.push_str("          i32.const 48\n");
+        self.output.push_str("          i32.sub\n");
+        self.output.push_str("          local.tee $byte\n");
+        self.output.push_str("          i32.const 9\n");
+        self.output.push_str("          i32.gt_u\n");
+        self.output.push_str("          br_if $none\n");
+        self.output.push_str("          local.get $value\n");
+        self.output.push_str("          i64.const 10\n");
+        self.output.push_str("          i64.mul\n");
+        self.output.push_str("          local.get $byte\n");
+        self.output.push_str("          i64.extend_i32_u\n");
+        self.output.push_str("          i64.add\n");
+        self.output.push_str("          local.tee $value\n");
+        self.output.push_str("          i64.const 2147483648\n");
+        self.output.push_str("          i64.gt_u\n");
+        self.output.push_str("          br_if $none\n");
+        self.output.push_str("          local.get $pos\n");
+        self.output.push_str("          i32.const 1\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          local.set $pos\n");
+        self.output.push_str("          br $digits\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $negative\n");
+        self.output.push_str("      (if\n");
+        self.output.push_str("        (then\n");
+        self.output.push_str("          i64.const 0\n");
+        self.output.push_str("          local.get $value\n");
+        self.output.push_str("          i64.sub\n");
+        self.output.push_str("          local.set $value\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        (else\n");
+        self.output.push_str("          local.get $value\n");
+        self.output.push_str("          i64.const 2147483647\n");
+        self.output.push_str("          i64.gt_u\n");
+        self.output.push_str("          br_if $none\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      i32.store\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      local.get $value\n");
+        self.output.push_str("      i32.wrap_i64\n");
+        self.output.push_str("      i32.store offset=4\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      return\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("  )\n");
+
+        self.output
+            .push_str("  (func $parse_float (param $str i32) (result i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $pos i32)\n");
+        self.output.push_str("    (local $byte i32)\n");
+        self.output.push_str("    (local $negative i32)\n");
+        self.output.push_str("    (local $digits i32)\n");
+        self.output.push_str("    (local $seen_dot i32)\n");
+        self.output.push_str("    (local $value f64)\n");
+        self.output.push_str("    (local $scale f64)\n");
+        self.output.push_str("    (local $option i32)\n");
+        self.output.push_str("    i32.const 12\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $option\n");
+        self.output.push_str("    f64.const 1\n");
+        self.output.push_str("    local.set $scale\n");
+        self.output.push_str("    (block $none\n");
+        self.emit_parse_sign();
+        self.output.push_str("      (block $digits_done\n");
+        self.output.push_str("        (loop $digits\n");
+        self.output.push_str("          local.get $pos\n");
+        self.output.push_str("          local.get $len\n");
+        self.output.push_str("          i32.ge_u\n");
+        self.output.push_str("          br_if $digits_done\n");
+        self.output.push_str("          local.get $str\n");
+        self.output.push_str("          local.get $pos\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          i32.load8_u offset=4\n");
+        self.output.push_str("          local.set $byte\n");
+        self.output.push_str("          local.get $pos\n");
+        self.output.push_str("          i32.const 1\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          local.set $pos\n");
+        self.output.push_str("          local.get $byte\n");
+        self.output.push_str("          i32.const 46\n");
+        self.output.push_str("          i32.eq\n");
+        self.output.push_str("          (if\n");
+        self.output.push_str("            (then\n");
+        self.output.push_str("              local.get $seen_dot\n");
+        self.output.push_str("              br_if $none\n");
+        self.output.push_str("              i32.const 1\n");
+        self.output.push_str("              local.set $seen_dot\n");
+        self.output.push_str("              br $digits\n");
+        self.output.push_str("            )\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("          local.get $byte\n");
+        self.output.push_str("          i32.const 48\n");
+        self.output.push_str("          i32.sub\n");
+        self.output.push_str("          local.tee $byte\n");
+        self.output.push_str("          i32.const 9\n");
+        self.output.push_str("          i32.gt_u\n");
+        self.output.push_str("          br_if $none\n");
+        self.output.push_str("          local.get $value\n");
+        self.output.push_str("          f64.const 10\n");
+        self.output.push_str("          f64.mul\n");
+        self.output.push_str("          local.get $byte\n");
+        self.output.push_str("          f64.convert_i32_u\n");
+        self.output.push_str("          f64.add\n");
+        self.output.push_str("          local.set $value\n");
+        self.output.push_str("          local.get $digits\n");
+        self.output.push_str("          i32.const 1\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          local.set $digits\n");
+        self.output.push_str("          local.get $seen_dot\n");
+        self.output.push_str("          (if\n");
+        self.output.push_str("            (then\n");
+        self.output.push_str("              local.get $scale\n");
+        self.output.push_str("              f64.const 10\n");
+        self.output.push_str("              f64.mul\n");
+        self.output.push_str("              local.set $scale\n");
+        self.output.push_str("            )\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("          br $digits\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $digits\n");
+        self.output.push_str("      i32.eqz\n");
+        self.output.push_str("      br_if $none\n");
+        self.output.push_str("      local.get $value\n");
+        self.output.push_str("      local.get $scale\n");
+        self.output.push_str("      f64.div\n");
+        self.output.push_str("      local.set $value\n");
+        self.output.push_str("      local.get $value\n");
+        self.output.push_str("      f64.const inf\n");
+        self.output.push_str("      f64.eq\n");
+        self.output.push_str("      br_if $none\n");
+        self.output.push_str("      local.get $negative\n");
+        self.output.push_str("      (if\n");
+        self.output.push_str("        (then\n");
+        self.output.push_str("          local.get $value\n");
+        self.output.push_str("          f64.neg\n");
+        self.output.push_str("          local.set $value\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      i32.store\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      i32.const 4\n");
+        self.output.push_str("      i32.add\n");
+        self.output.push_str("      local.get $value\n");
+        self.output.push_str("      f64.store\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      return\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("  )\n");
+
+        for (name, payload) in [("parse_int", "Int32"), ("parse_float", "Float64")] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![WasmType::I32],
+                    result: Some(WasmType::I32),
+                },
+            );
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec![],
+                    params: vec![Type::Named("String".to_string())],
+                    result: Some(Type::Generic(
+                        "Option".to_string(),
+                        vec![Type::Named(payload.to_string())],
+                    )),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Load `$len` and skip a leading `-` or `+`, setting `$negative` and
+    /// `$pos`. Branches to `$none` on an empty String.
+    fn emit_parse_sign(&mut self) {
+        self.output.push_str("      local.get $str\n");
+        self.output.push_str("      i32.load\n");
+        self.output.push_str("      local.tee $len\n");
+        self.output.push_str("      i32.eqz\n");
+        self.output.push_str("      br_if $none\n");
+        self.output.push_str("      local.get $str\n");
+        self.output.push_str("      i32.load8_u offset=4\n");
+        self.output.push_str("      local.tee $byte\n");
+        self.output.push_str("      i32.const 45\n");
+        self.output.push_str("      i32.eq\n");
+        self.output.push_str("      (if\n");
+        self.output.push_str("        (then\n");
+        self.output.push_str("          i32.const 1\n");
+        self.output.push_str("          local.set $negative\n");
+        self.output.push_str("          i32.const 1\n");
+        self.output.push_str("          local.set $pos\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $byte\n");
+        self.output.push_str("      i32.const 43\n");
+        self.output.push_str("      i32.eq\n");
+        self.output.push_str("      (if\n");
+        self.output.push_str("        (then\n");
+        self.output.push_str("          i32.const 1\n");
+        self.output.push_str("          local.set $pos\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+    }
+
+    /// Char helpers. Chars and Int32 share the i32 representation, so the
+    /// conversions are identities; `int_to_char` traps on values that are not
+    /// Unicode scalar values, and the classifiers are ASCII range checks.
+    fn generate_std_char_functions(&mut self) -> Result<(), CodeGenError> {
+        self.output
+            .push_str("  (func $char_to_int (param $value i32) (result i32)\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("  )\n");
+
+        // Reject values above U+10FFFF (negative values included, as
+        // unsigned) and the surrogates U+D800..U+DFFF.
+        self.output
+            .push_str("  (func $int_to_char (param $value i32) (result i32)\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.const 1114111\n");
+        self.output.push_str("    i32.gt_u\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.const 55296\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    i32.const 2048\n");
+        self.output.push_str("    i32.lt_u\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    (if (then unreachable))\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("  )\n");
+
+        // '0'..='9'
+        self.output
+            .push_str("  (func $is_digit (param $value i32) (result i32)\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.const 48\n");
+        self.output.push_str("    i32.ge_u\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.const 57\n");
+        self.output.push_str("    i32.le_u\n");
+        self.output.push_str("    i32.and\n");
+        self.output.push_str("  )\n");
+
+        // 'A'..='Z' or 'a'..='z': setting bit 5 folds upper case onto lower.
+        self.output
+            .push_str("  (func $is_alpha (param $value i32) (result i32)\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.const 32\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    i32.const 97\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    i32.const 26\n");
+        self.output.push_str("    i32.lt_u\n");
+        self.output.push_str("  )\n");
+
+        for (name, param_ty, result_ty) in [
+            ("char_to_int", "Char", "Int32"),
+            ("int_to_char", "Int32", "Char"),
+            ("is_digit", "Char", "Boolean"),
+            ("is_alpha", "Char", "Boolean"),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![WasmType::I32],
+                    result: Some(WasmType::I32),
+                },
+            );
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec![],
+                    params: vec![Type::Named(param_ty.to_string())],
+                    result: Some(Type::Named(result_ty.to_string())),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// String helpers over the length-prefixed layout: an i32 byte length
+    /// followed by UTF-8 bytes. `string_char_at` takes a byte offset and
+    /// returns `None` when it is out of range or not at the start of a char.
+    fn generate_std_string_functions(&mut self) -> Result<(), CodeGenError> {
+        self.output
+            .push_str("  (func $string_length (param $str i32) (result i32)\n");
+        self.output.push_str("    local.get $str\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("  )\n");
+
+        self.output
+            .push_str("  (func $string_char_at (param $str i32) (param $index i32) (result i32)\n");
+        self.output.push_str("    (local $option i32)\n");
+        self.output.push_str("    (local $byte i32)\n");
+        self.output.push_str("    (local $value i32)\n");
+        self.output.push_str("    (local $width i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $option\n");
+        self.output.push_str("    (block $none\n");
+        // Unsigned comparison also rejects negative indices.
+        self.output.push_str("      local.get $index\n");
+        self.output.push_str("      local.get $str\n");
+        self.output.push_str("      i32.load\n");
+        self.output.push_str("      i32.ge_u\n");
+        self.output.push_str("      br_if $none\n");
+        self.output.push_str("      local.get $str\n");
+        self.output.push_str("      local.get $index\n");
+        self.output.push_str("      i32.add\n");
+        self.output.push_str("      i32.load8_u offset=4\n");
+        self.output.push_str("      local.set $byte\n");
+        // A continuation byte (10xxxxxx) is the middle of a char.
+        self.output.push_str("      local.get $byte\n");
+        self.output.push_str("      i32.const 192\n");
+        self.output.push_str("      i32.and\n");
+        self.output.push_str("      i32.const 128\n");
+        self.output.push_str("      i32.eq\n");
+        self.output.push_str("      br_if $none\n");
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      local.set $width\n");
+        self.output.push_str("      local.get $byte\n");
+        self.output.push_str("      local.set $value\n");
+        // Lead bytes 110xxxxx, 1110xxxx, and 11110xxx start 2-, 3-, and
+        // 4-byte sequences.
+        for (lead, width, payload_mask) in [(192, 2, 31), (224, 3, 15), (240, 4, 7)] {
+            self.output.push_str("      local.get $byte\n");
+            self.output.push_str(&format!("      i32.const {lead}\n"));
+            self.output.push_str("      i32.ge_u\n");
+            self.output.push_str("      (if\n");
+            self.output.push_str("        (then\n");
+            self.output
+                .push_str(&format!("          i32.const {width}\n"));
+            self.output.push_str("          local.set $width\n");
+            self.output.push_str("          local.get $byte\n");
+            self.output
+                .push_str(&format!("          i32.const {payload_mask}\n"));
+            self.output.push_str("          i32.and\n");
+            self.output.push_str("          local.set $value\n");
+            self.output.push_str("        )\n");
+            self.output.push_str("      )\n");
+        }
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      local.set $i\n");
+        self.output.push_str("      (block $decoded\n");
+        self.output.push_str("        (loop $continuation\n");
+        self.output.push_str("          local.get $i\n");
+        self.output.push_str("          local.get $width\n");
+        self.output.push_str("          i32.ge_u\n");
+        self.output.push_str("          br_if $decoded\n");
+        self.output.push_str("          local.get $value\n");
+        self.output.push_str("          i32.const 6\n");
+        self.output.push_str("          i32.shl\n");
+        self.output.push_str("          local.get $str\n");
+        self.output.push_str("          local.get $index\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          local.get $i\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          i32.load8_u offset=4\n");
+        self.output.push_str("          i32.const 63\n");
+        self.output.push_str("          i32.and\n");
+        self.output.push_str("          i32.or\n");
+        self.output.push_str("          local.set $value\n");
+        self.output.push_str("          local.get $i\n");
+        self.output.push_str("          i32.const 1\n");
+        self.output.push_str("          i32.add\n");
+        self.output.push_str("          local.set $i\n");
+        self.output.push_str("          br $continuation\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      i32.store\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      local.get $value\n");
+        self.output.push_str("      i32.store offset=4\n");
+        self.output.push_str("      local.get $option\n");
+        self.output.push_str("      return\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("  )\n");
+
+        // UTF-8 encode one char into a fresh 1- to 4-byte String.
+        self.output
+            .push_str("  (func $string_from_char (param $value i32) (result i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $out i32)\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    local.set $len\n");
+        for (threshold, len) in [(0x80, 2), (0x800, 3), (0x10000, 4)] {
+            self.output.push_str("    local.get $value\n");
+            self.output
+                .push_str(&format!("    i32.const {threshold}\n"));
+            self.output.push_str("    i32.ge_u\n");
+            self.output
+                .push_str(&format!("    (if (then i32.const {len} local.set $len))\n"));
+        }
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $out\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.store8 offset=4\n");
+        for (len, lead) in [(2u32, 0xC0), (3, 0xE0), (4, 0xF0)] {
+            self.output.push_str("    local.get $len\n");
+            self.output.push_str(&format!("    i32.const {len}\n"));
+            self.output.push_str("    i32.eq\n");
+            self.output.push_str("    (if\n");
+            self.output.push_str("      (then\n");
+            // Lead byte: the marker bits plus the highest payload bits.
+            self.output.push_str("        local.get $out\n");
+            self.output.push_str("        local.get $value\n");
+            self.output
+                .push_str(&format!("        i32.const {}\n", 6 * (len - 1)));
+            self.output.push_str("        i32.shr_u\n");
+            self.output.push_str(&format!("        i32.const {lead}\n"));
+            self.output.push_str("        i32.or\n");
+            self.output.push_str("        i32.store8 offset=4\n");
+            for byte in 1..len {
+                self.output.push_str("        local.get $out\n");
+                self.output.push_str("        local.get $value\n");
+                self.output
+                    .push_str(&format!("        i32.const {}\n", 6 * (len - 1 - byte)));
+                self.output.push_str("        i32.shr_u\n");
+                self.output.push_str("        i32.const 63\n");
+                self.output.push_str("        i32.and\n");
+                self.output.push_str("        i32.const 128\n");
+                self.output.push_str("        i32.or\n");
+                self.output
+                    .push_str(&format!("        i32.store8 offset={}\n", 4 + byte));
+            }
+            self.output.push_str("      )\n");
+            self.output.push_str("    )\n");
+        }
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("  )\n");
+
+        // Does `pattern` occur in `str` at byte offset `at`? The caller keeps
+        // `at + len(pattern)` within `str`.
+        self.output.push_str("  (func $string_matches_at (param $str i32) (param $at i32) (param $pattern i32) (result i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    (block $mismatch\n");
+        self.output.push_str("      (loop $compare\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $pattern\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        i32.ge_u\n");
+        self.output
+            .push_str("        (if (then i32.const 1 return))\n");
+        self.output.push_str("        local.get $str\n");
+        self.output.push_str("        local.get $at\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load8_u offset=4\n");
+        self.output.push_str("        local.get $pattern\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load8_u offset=4\n");
+        self.output.push_str("        i32.ne\n");
+        self.output.push_str("        br_if $mismatch\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        br $compare\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("  )\n");
+
+        // Copy the bytes `start..end` of `str` into a fresh String.
+        self.output.push_str("  (func $string_copy_range (param $str i32) (param $start i32) (param $end i32) (result i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $out i32)\n");
+        self.output.push_str("    local.get $end\n");
+        self.output.push_str("    local.get $start\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    local.set $len\n");
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $out\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $str\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $start\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    memory.copy\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("  )\n");
+
+        // Split on every UTF-8 encoded occurrence of the delimiter. The first pass
+        // counts the parts, one more than the delimiters, so the list is sized
+        // exactly; the second copies each part out.
+        self.output.push_str(
+            "  (func $string_split (param $str i32) (param $delimiter i32) (result i32)\n",
+        );
+        self.output.push_str("    (local $sep i32)\n");
+        self.output.push_str("    (local $sep_len i32)\n");
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    (local $count i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    (local $start i32)\n");
+        self.output.push_str("    (local $list i32)\n");
+        self.output.push_str("    (local $slot i32)\n");
+        self.output.push_str("    local.get $delimiter\n");
+        self.output.push_str("    call $string_from_char\n");
+        self.output.push_str("    local.set $sep\n");
+        self.output.push_str("    local.get $sep\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $sep_len\n");
+        self.output.push_str("    local.get $str\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $len\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    local.set $count\n");
+        self.output.push_str("    (block $counted\n");
+        self.output.push_str("      (loop $count_parts\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $sep_len\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $len\n");
+        self.output.push_str("        i32.gt_u\n");
+        self.output.push_str("        br_if $counted\n");
+        self.output.push_str("        local.get $str\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $sep\n");
+        self.output.push_str("        call $string_matches_at\n");
+        self.output.push_str("        (if\n");
+        self.output.push_str("          (then\n");
+        self.output.push_str("            local.get $count\n");
+        self.output.push_str("            i32.const 1\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.set $count\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            local.get $sep_len\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.set $i\n");
+        self.output.push_str("            br $count_parts\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        br $count_parts\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $count\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $list\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    local.get $count\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    local.get $count\n");
+        self.output.push_str("    i32.store offset=4\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.set $slot\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    local.set $i\n");
+        self.output.push_str("    (block $split\n");
+        self.output.push_str("      (loop $split_parts\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $sep_len\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $len\n");
+        self.output.push_str("        i32.gt_u\n");
+        self.output.push_str("        br_if $split\n");
+        self.output.push_str("        local.get $str\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $sep\n");
+        self.output.push_str("        call $string_matches_at\n");
+        self.output.push_str("        (if\n");
+        self.output.push_str("          (then\n");
+        self.output.push_str("            local.get $slot\n");
+        self.output.push_str("            local.get $str\n");
+        self.output.push_str("            local.get $start\n");
+        self.output.push_str("            local.get $i\n");
+        self.output
+            .push_str("            call $string_copy_range\n");
+        self.output.push_str("            i32.store\n");
+        self.output.push_str("            local.get $slot\n");
+        self.output.push_str("            i32.const 4\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.set $slot\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            local.get $sep_len\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.tee $i\n");
+        self.output.push_str("            local.set $start\n");
+        self.output.push_str("            br $split_parts\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        br $split_parts\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $slot\n");
+        self.output.push_str("    local.get $str\n");
+        self.output.push_str("    local.get $start\n");
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    call $string_copy_range\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("  )\n");
+
+        // Size the result first: every part plus a separator between each pair.
+        self.output.push_str(
+            "  (func $string_join (param $parts i32) (param $separator i32) (result i32)\n",
+        );
+        self.output.push_str("    (local $count i32)\n");
+        self.output.push_str("    (local $sep_len i32)\n");
+        self.output.push_str("    (local $total i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    (local $part i32)\n");
+        self.output.push_str("    (local $out i32)\n");
+        self.output.push_str("    (local $cursor i32)\n");
+        self.output.push_str("    local.get $parts\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $count\n");
+        self.output.push_str("    local.get $separator\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $sep_len\n");
+        self.output.push_str("    (block $measured\n");
+        self.output.push_str("      (loop $measure\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $count\n");
+        self.output.push_str("        i32.ge_u\n");
+        self.output.push_str("        br_if $measured\n");
+        self.output.push_str("        local.get $parts\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load offset=8\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        local.get $total\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $total\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        br $measure\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $count\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $count\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.sub\n");
+        self.output.push_str("        local.get $sep_len\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        local.get $total\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $total\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $total\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $out\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("    local.get $total\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.set $cursor\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    local.set $i\n");
+        self.output.push_str("    (block $joined\n");
+        self.output.push_str("      (loop $join\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $count\n");
+        self.output.push_str("        i32.ge_u\n");
+        self.output.push_str("        br_if $joined\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        (if\n");
+        self.output.push_str("          (then\n");
+        self.output.push_str("            local.get $cursor\n");
+        self.output.push_str("            local.get $separator\n");
+        self.output.push_str("            i32.const 4\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.get $sep_len\n");
+        self.output.push_str("            memory.copy\n");
+        self.output.push_str("            local.get $cursor\n");
+        self.output.push_str("            local.get $sep_len\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.set $cursor\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        local.get $parts\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load offset=8\n");
+        self.output.push_str("        local.set $part\n");
+        self.output.push_str("        local.get $cursor\n");
+        self.output.push_str("        local.get $part\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $part\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        memory.copy\n");
+        self.output.push_str("        local.get $cursor\n");
+        self.output.push_str("        local.get $part\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $cursor\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        br $join\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("  )\n");
+
+        for (name, params, result) in [
+            (
+                "string_length",
+                vec![Type::Named("String".to_string())],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "string_char_at",
+                vec![
+                    Type::Named("String".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Generic("Option".to_string(), vec![Type::Named("Char".to_string())]),
+            ),
+            (
+                "string_from_char",
+                vec![Type::Named("Char".to_string())],
+                Type::Named("String".to_string()),
+            ),
+            (
+                "string_split",
+                vec![
+                    Type::Named("String".to_string()),
+                    Type::Named("Char".to_string()),
+                ],
+                Type::Generic("List".to_string(), vec![Type::Named("String".to_string())]),
+            ),
+            (
+                "string_join",
+                vec![
+                    Type::Generic("List".to_string(), vec![Type::Named("String".to_string())]),
+                    Type::Named("String".to_string()),
+                ],
+                Type::Named("String".to_string()),
+            ),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![WasmType::I32; params.len()],
+                    result: Some(WasmType::I32),
+                },
+            );
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec![],
+                    params,
+                    result: Some(result),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Copy the scratch text in `$buffer_start..$buffer_end` into a freshly
+    /// allocated String and leave its pointer on the stack.
+    fn emit_scratch_to_string(&mut self) {
+        self.output.push_str("    local.get $buffer_end\n");
+        self.output.push_str("    local.get $buffer_start\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    local.set $len\n");
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $out\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $out\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $buffer_start\n");
+        self.output.push_str("    local.get $len\n");
+        self.output.push_str("    memory.copy\n");
+        self.output.push_str("    local.get $out\n");
+    }
+
+    /// Allocate a String holding the ASCII `text` and leave its pointer on
+    /// the stack. Uses the caller's `$out` local.
+    fn emit_ascii_string_alloc(&mut self, text: &str, indent: &str) {
+        self.output
+            .push_str(&format!("{indent}i32.const {}\n", text.len() + 4));
+        self.output.push_str(&format!("{indent}call $allocate\n"));
+        self.output.push_str(&format!("{indent}local.set $out\n"));
+        self.output.push_str(&format!("{indent}local.get $out\n"));
+        self.output
+            .push_str(&format!("{indent}i32.const {}\n", text.len()));
+        self.output.push_str(&format!("{indent}i32.store\n"));
+        for (offset, byte) in text.bytes().enumerate() {
+            self.output.push_str(&format!("{indent}local.get $out\n"));
+            self.output
+                .push_str(&format!("{indent}i32.const {}\n", byte));
+            self.output
+                .push_str(&format!("{indent}i32.store8 offset={}\n", offset + 4));
+        }
+        self.output.push_str(&format!("{indent}local.get $out\n"));
+    }
+
+    fn emit_string_write_function(&mut self, name: &str, fd: i32, newline: bool) {
+        self.output
+            .push_str(&format!("  (func ${} (param $str i32)\n", name));
+        self.output.push_str("    (local $len i32)\n");
+        self.output.push_str("    local.get $str\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $len\n");
         self.output.push_str("    i32.const 0\n");
         self.output.push_str("    local.get $str\n");
         self.output.push_str("    i32.const 4\n");
@@ -964,27 +3894,32 @@ impl WasmCodeGen {
         self.output.push_str("    )\n");
         self.output.push_str("  )\n");
 
-        self.output
-            .push_str("  (func $max (param $a i32) (param $b i32) (result i32)\n");
-        self.output.push_str("    local.get $a\n");
-        self.output.push_str("    local.get $b\n");
-        self.output.push_str("    i32.gt_s\n");
-        self.output.push_str("    (if (result i32)\n");
-        self.output
-            .push_str("      (then local.get $a)\n      (else local.get $b)\n");
-        self.output.push_str("    )\n");
-        self.output.push_str("  )\n");
-
-        self.output
-            .push_str("  (func $min (param $a i32) (param $b i32) (result i32)\n");
-        self.output.push_str("    local.get $a\n");
-        self.output.push_str("    local.get $b\n");
-        self.output.push_str("    i32.lt_s\n");
-        self.output.push_str("    (if (result i32)\n");
-        self.output
-            .push_str("      (then local.get $a)\n      (else local.get $b)\n");
-        self.output.push_str("    )\n");
-        self.output.push_str("  )\n");
+        // `max`/`min` are generic over `Ord`; each call resolves to the
+        // variant for its operand type (Float64 uses `max_f`/`min_f`).
+        for (suffix, ty, greater, less) in [
+            ("", "i32", "i32.gt_s", "i32.lt_s"),
+            ("_i64", "i64", "i64.gt_s", "i64.lt_s"),
+            (
+                "_string",
+                "i32",
+                "call $string_compare\n    i32.const 0\n    i32.gt_s",
+                "call $string_compare\n    i32.const 0\n    i32.lt_s",
+            ),
+        ] {
+            for (name, compare) in [("max", greater), ("min", less)] {
+                self.output.push_str(&format!(
+                    "  (func ${name}{suffix} (param $a {ty}) (param $b {ty}) (result {ty})\n"
+                ));
+                self.output.push_str("    local.get $a\n");
+                self.output.push_str("    local.get $b\n");
+                self.output.push_str(&format!("    {compare}\n"));
+                self.output.push_str(&format!("    (if (result {ty})\n"));
+                self.output
+                    .push_str("      (then local.get $a)\n      (else local.get $b)\n");
+                self.output.push_str("    )\n");
+                self.output.push_str("  )\n");
+            }
+        }
 
         self.output
             .push_str("  (func $pow (param $base i32) (param $exp i32) (result i32)\n");
@@ -1050,6 +3985,233 @@ impl WasmCodeGen {
         self.output.push_str("    local.get $result\n");
         self.output.push_str("  )\n");
 
+        self.output
+            .push_str("  (func $sign (param $x i32) (result i32)\n");
+        self.output.push_str("    local.get $x\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.gt_s\n");
+        self.output.push_str("    local.get $x\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.lt_s\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("  )\n");
+
+        // Euclid's algorithm on the unsigned magnitudes, so Int32::MIN works
+        // as 2^31. gcd(0, 0) is 0; a result of 2^31 does not fit and traps.
+        self.output
+            .push_str("  (func $gcd (param $a i32) (param $b i32) (result i32)\n");
+        self.output.push_str("    (local $rem i32)\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    call $abs\n");
+        self.output.push_str("    local.set $a\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    call $abs\n");
+        self.output.push_str("    local.set $b\n");
+        self.output.push_str("    (block $gcd_done\n");
+        self.output.push_str("      (loop $gcd_loop\n");
+        self.output.push_str("        local.get $b\n");
+        self.output.push_str("        i32.eqz\n");
+        self.output.push_str("        br_if $gcd_done\n");
+        self.output.push_str("        local.get $a\n");
+        self.output.push_str("        local.get $b\n");
+        self.output.push_str("        i32.rem_u\n");
+        self.output.push_str("        local.set $rem\n");
+        self.output.push_str("        local.get $b\n");
+        self.output.push_str("        local.set $a\n");
+        self.output.push_str("        local.get $rem\n");
+        self.output.push_str("        local.set $b\n");
+        self.output.push_str("        br $gcd_loop\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.lt_s\n");
+        self.output.push_str("    (if (then unreachable))\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("  )\n");
+
+        // |a| / gcd * |b| in i64; lcm with a zero operand is 0 and a result
+        // above Int32::MAX traps.
+        self.output
+            .push_str("  (func $lcm (param $a i32) (param $b i32) (result i32)\n");
+        self.output.push_str("    (local $wide i64)\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    (if (then i32.const 0 return))\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    call $abs\n");
+        self.output.push_str("    i64.extend_i32_u\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    call $gcd\n");
+        self.output.push_str("    i64.extend_i32_u\n");
+        self.output.push_str("    i64.div_u\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    call $abs\n");
+        self.output.push_str("    i64.extend_i32_u\n");
+        self.output.push_str("    i64.mul\n");
+        self.output.push_str("    local.tee $wide\n");
+        self.output
+            .push_str(&format!("    i64.const {}\n", i32::MAX));
+        self.output.push_str("    i64.gt_u\n");
+        self.output.push_str("    (if (then unreachable))\n");
+        self.output.push_str("    local.get $wide\n");
+        self.output.push_str("    i32.wrap_i64\n");
+        self.output.push_str("  )\n");
+
+        for (name, op) in [("wrapping_add", "i32.add"), ("wrapping_mul", "i32.mul")] {
+            self.output.push_str(&format!(
+                "  (func ${name} (param $a i32) (param $b i32) (result i32)\n"
+            ));
+            self.output.push_str("    local.get $a\n");
+            self.output.push_str("    local.get $b\n");
+            self.output.push_str(&format!("    {op}\n"));
+            self.output.push_str("  )\n");
+        }
+
+        // Saturating ops compute the exact result in i64 and clamp it to the
+        // Int32 range before wrapping back down.
+        for (name, op) in [("saturating_add", "i64.add"), ("saturating_sub", "i64.sub")] {
+            self.output.push_str(&format!(
+                "  (func ${name} (param $a i32) (param $b i32) (result i32)\n"
+            ));
+            self.output.push_str("    (local $wide i64)\n");
+            self.output.push_str("    local.get $a\n");
+            self.output.push_str("    i64.extend_i32_s\n");
+            self.output.push_str("    local.get $b\n");
+            self.output.push_str("    i64.extend_i32_s\n");
+            self.output.push_str(&format!("    {op}\n"));
+            self.output.push_str("    local.set $wide\n");
+            self.output.push_str("    local.get $wide\n");
+            self.output
+                .push_str(&format!("    i64.const {}\n", i32::MAX));
+            self.output.push_str("    i64.gt_s\n");
+            self.output.push_str("    (if (result i32)\n");
+            self.output
+                .push_str(&format!("      (then i32.const {})\n", i32::MAX));
+            self.output.push_str("      (else\n");
+            self.output.push_str("        local.get $wide\n");
+            self.output
+                .push_str(&format!("        i64.const {}\n", i32::MIN));
+            self.output.push_str("        i64.lt_s\n");
+            self.output.push_str("        (if (result i32)\n");
+            self.output
+                .push_str(&format!("          (then i32.const {})\n", i32::MIN));
+            self.output
+                .push_str("          (else local.get $wide i32.wrap_i64)\n");
+            self.output.push_str("        )\n");
+            self.output.push_str("      )\n");
+            self.output.push_str("    )\n");
+            self.output.push_str("  )\n");
+        }
+
+        // Checked ops also compute the exact result in i64 and return `None`
+        // when it leaves the Int32 range. The result is an 8-byte Option:
+        // tag 1 with the value at offset 4, or tag 0.
+        for (name, op) in [
+            ("checked_add", "i64.add"),
+            ("checked_sub", "i64.sub"),
+            ("checked_mul", "i64.mul"),
+        ] {
+            self.output.push_str(&format!(
+                "  (func ${name} (param $a i32) (param $b i32) (result i32)\n"
+            ));
+            self.output.push_str("    (local $wide i64)\n");
+            self.output.push_str("    (local $option i32)\n");
+            self.output.push_str("    i32.const 8\n");
+            self.output.push_str("    call $allocate\n");
+            self.output.push_str("    local.set $option\n");
+            self.output.push_str("    local.get $a\n");
+            self.output.push_str("    i64.extend_i32_s\n");
+            self.output.push_str("    local.get $b\n");
+            self.output.push_str("    i64.extend_i32_s\n");
+            self.output.push_str(&format!("    {op}\n"));
+            self.output.push_str("    local.set $wide\n");
+            self.output.push_str("    local.get $wide\n");
+            self.output
+                .push_str(&format!("    i64.const {}\n", i32::MAX));
+            self.output.push_str("    i64.gt_s\n");
+            self.output.push_str("    local.get $wide\n");
+            self.output
+                .push_str(&format!("    i64.const {}\n", i32::MIN));
+            self.output.push_str("    i64.lt_s\n");
+            self.output.push_str("    i32.or\n");
+            self.emit_checked_option_result("local.get $wide i32.wrap_i64");
+            self.output.push_str("  )\n");
+        }
+
+        // Division overflows only for Int32::MIN / -1.
+        self.output
+            .push_str("  (func $checked_div (param $a i32) (param $b i32) (result i32)\n");
+        self.output.push_str("    (local $option i32)\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $option\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    local.get $a\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", i32::MIN));
+        self.output.push_str("    i32.eq\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i32.const -1\n");
+        self.output.push_str("    i32.eq\n");
+        self.output.push_str("    i32.and\n");
+        self.output.push_str("    i32.or\n");
+        self.emit_checked_option_result("local.get $a local.get $b i32.div_s");
+        self.output.push_str("  )\n");
+
+        // `clamp` saturates to [min, max]; `checked` traps outside it.
+        self.output.push_str(
+            "  (func $clamp (param $value i32) (param $min i32) (param $max i32) (result i32)\n",
+        );
+        self.output.push_str("    local.get $min\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    local.get $min\n");
+        self.output.push_str("    i32.lt_s\n");
+        self.output.push_str("    select\n");
+        self.output.push_str("    local.set $value\n");
+        self.output.push_str("    local.get $max\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    local.get $max\n");
+        self.output.push_str("    i32.gt_s\n");
+        self.output.push_str("    select\n");
+        self.output.push_str("  )\n");
+
+        self.output.push_str(
+            "  (func $checked (param $value i32) (param $min i32) (param $max i32) (result i32)\n",
+        );
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    local.get $min\n");
+        self.output.push_str("    i32.lt_s\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    local.get $max\n");
+        self.output.push_str("    i32.gt_s\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    (if (then unreachable))\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("  )\n");
+
+        // `to_i32` keeps the low 32 bits, like `as Int32`.
+        for (name, param, result, op) in [
+            ("to_i64", "i32", "i64", "i64.extend_i32_s"),
+            ("to_i32", "i64", "i32", "i32.wrap_i64"),
+        ] {
+            self.output.push_str(&format!(
+                "  (func ${name} (param $value {param}) (result {result})\n"
+            ));
+            self.output.push_str("    local.get $value\n");
+            self.output.push_str(&format!("    {op}\n"));
+            self.output.push_str("  )\n");
+        }
+
+        self.generate_decimal_functions()?;
+
         self.output
             .push_str("  (func $abs_f (param $x f64) (result f64)\n");
         self.output.push_str("    local.get $x\n");
@@ -1077,7 +4239,25 @@ impl WasmCodeGen {
                 Type::Named("Int32".to_string()),
             ),
             (
-                "max",
+                "pow",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "factorial",
+                vec![Type::Named("Int32".to_string())],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "sign",
+                vec![Type::Named("Int32".to_string())],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "gcd",
                 vec![
                     Type::Named("Int32".to_string()),
                     Type::Named("Int32".to_string()),
@@ -1085,7 +4265,7 @@ impl WasmCodeGen {
                 Type::Named("Int32".to_string()),
             ),
             (
-                "min",
+                "lcm",
                 vec![
                     Type::Named("Int32".to_string()),
                     Type::Named("Int32".to_string()),
@@ -1093,7 +4273,7 @@ impl WasmCodeGen {
                 Type::Named("Int32".to_string()),
             ),
             (
-                "pow",
+                "wrapping_add",
                 vec![
                     Type::Named("Int32".to_string()),
                     Type::Named("Int32".to_string()),
@@ -1101,10 +4281,105 @@ impl WasmCodeGen {
                 Type::Named("Int32".to_string()),
             ),
             (
-                "factorial",
+                "wrapping_mul",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "saturating_add",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "saturating_sub",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "checked_add",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Generic("Option".to_string(), vec![Type::Named("Int32".to_string())]),
+            ),
+            (
+                "checked_sub",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Generic("Option".to_string(), vec![Type::Named("Int32".to_string())]),
+            ),
+            (
+                "checked_mul",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Generic("Option".to_string(), vec![Type::Named("Int32".to_string())]),
+            ),
+            (
+                "checked_div",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Generic("Option".to_string(), vec![Type::Named("Int32".to_string())]),
+            ),
+            (
+                "clamp",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "checked",
+                vec![
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                    Type::Named("Int32".to_string()),
+                ],
+                Type::Named("Int32".to_string()),
+            ),
+            (
+                "to_i64",
                 vec![Type::Named("Int32".to_string())],
+                Type::Named("Int64".to_string()),
+            ),
+            (
+                "to_i32",
+                vec![Type::Named("Int64".to_string())],
                 Type::Named("Int32".to_string()),
             ),
+            (
+                "decimal_div_half_even",
+                vec![
+                    Type::Named("Decimal".to_string()),
+                    Type::Named("Decimal".to_string()),
+                ],
+                Type::Named("Decimal".to_string()),
+            ),
+            (
+                "decimal_div_half_up",
+                vec![
+                    Type::Named("Decimal".to_string()),
+                    Type::Named("Decimal".to_string()),
+                ],
+                Type::Named("Decimal".to_string()),
+            ),
             (
                 "abs_f",
                 vec![Type::Named("Float64".to_string())],
@@ -1149,6 +4424,326 @@ impl WasmCodeGen {
             );
         }
 
+        for (suffix, wasm_ty) in [
+            ("", WasmType::I32),
+            ("_i64", WasmType::I64),
+            ("_string", WasmType::I32),
+        ] {
+            for name in ["max", "min"] {
+                self.functions.insert(
+                    format!("{}{}", name, suffix),
+                    FunctionSig {
+                        _params: vec![wasm_ty, wasm_ty],
+                        result: Some(wasm_ty),
+                    },
+                );
+            }
+        }
+        for name in ["max", "min"] {
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec!["T".to_string()],
+                    params: vec![Type::Named("T".to_string()), Type::Named("T".to_string())],
+                    result: Some(Type::Named("T".to_string())),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// `Decimal` values are i64 multiples of `1 / DECIMAL_SCALE`, so `+`,
+    /// `-` and comparisons are plain i64 ops. `*` and `/` rescale through
+    /// these helpers, which round the dropped digits half to even unless
+    /// `$half_even` is 0, in which case halves round away from zero.
+    fn generate_decimal_functions(&mut self) -> Result<(), CodeGenError> {
+        // Rounds `$n / $d` to the nearest integer.
+        self.output.push_str(
+            "  (func $decimal_round_div (param $n i64) (param $d i64) (param $half_even i32) (result i64)\n",
+        );
+        self.output.push_str("    (local $quotient i64)\n");
+        self.output.push_str("    (local $twice_rem i64)\n");
+        self.output.push_str("    (local $negative i32)\n");
+        self.output.push_str("    local.get $n\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    i64.lt_s\n");
+        self.output.push_str("    local.get $d\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    i64.lt_s\n");
+        self.output.push_str("    i32.xor\n");
+        self.output.push_str("    local.set $negative\n");
+        self.output.push_str("    local.get $n\n");
+        self.output.push_str("    local.get $d\n");
+        self.output.push_str("    i64.div_s\n");
+        self.output.push_str("    local.set $quotient\n");
+        // Compare twice the remainder's magnitude against the divisor's.
+        self.output.push_str("    local.get $n\n");
+        self.output.push_str("    local.get $d\n");
+        self.output.push_str("    i64.rem_s\n");
+        self.output.push_str("    local.set $twice_rem\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    local.get $twice_rem\n");
+        self.output.push_str("    i64.sub\n");
+        self.output.push_str("    local.get $twice_rem\n");
+        self.output.push_str("    local.get $twice_rem\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    i64.lt_s\n");
+        self.output.push_str("    select\n");
+        self.output.push_str("    i64.const 1\n");
+        self.output.push_str("    i64.shl\n");
+        self.output.push_str("    local.set $twice_rem\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    local.get $d\n");
+        self.output.push_str("    i64.sub\n");
+        self.output.push_str("    local.get $d\n");
+        self.output.push_str("    local.get $d\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    i64.lt_s\n");
+        self.output.push_str("    select\n");
+        self.output.push_str("    local.set $d\n");
+        // Round away from zero above the half, and at exactly the half
+        // unless rounding to even with an even quotient.
+        self.output.push_str("    local.get $twice_rem\n");
+        self.output.push_str("    local.get $d\n");
+        self.output.push_str("    i64.gt_u\n");
+        self.output.push_str("    local.get $twice_rem\n");
+        self.output.push_str("    local.get $d\n");
+        self.output.push_str("    i64.eq\n");
+        self.output.push_str("    local.get $half_even\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    local.get $quotient\n");
+        self.output.push_str("    i32.wrap_i64\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.and\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    i32.and\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    (if (result i64)\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $quotient\n");
+        self.output.push_str("        i64.const -1\n");
+        self.output.push_str("        i64.const 1\n");
+        self.output.push_str("        local.get $negative\n");
+        self.output.push_str("        select\n");
+        self.output.push_str("        i64.add\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else local.get $quotient)\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("  )\n");
+
+        // Products that do not fit a Decimal panic rather than wrap.
+        let Some(&overflow_message) = self.string_offsets.get(DECIMAL_OVERFLOW_MESSAGE) else {
+            return Err(CodeGenError::UnsupportedFeature(
+                "decimal arithmetic outside string collection scope".to_string(),
+            ));
+        };
+        self.output.push_str("  (func $decimal_overflow\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", overflow_message));
+        self.output.push_str("    call $panic\n");
+        self.output.push_str("  )\n");
+
+        // Checked `$a * $b` and `$a + $b` for non-negative operands. A
+        // product past i64 either wraps negative or no longer divides back.
+        self.output
+            .push_str("  (func $decimal_mul_unsigned (param $a i64) (param $b i64) (result i64)\n");
+        self.output.push_str("    (local $product i64)\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    i64.eqz\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        i64.const 0\n");
+        self.output.push_str("        return\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i64.mul\n");
+        self.output.push_str("    local.tee $product\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    i64.lt_s\n");
+        self.output.push_str("    local.get $product\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    i64.div_u\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i64.ne\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    (if\n");
+        self.output
+            .push_str("      (then call $decimal_overflow)\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $product\n");
+        self.output.push_str("  )\n");
+        self.output
+            .push_str("  (func $decimal_add_unsigned (param $a i64) (param $b i64) (result i64)\n");
+        self.output.push_str("    (local $sum i64)\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i64.add\n");
+        self.output.push_str("    local.tee $sum\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    i64.lt_s\n");
+        self.output.push_str("    (if\n");
+        self.output
+            .push_str("      (then call $decimal_overflow)\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $sum\n");
+        self.output.push_str("  )\n");
+
+        // a * b / SCALE, rounded once. With |a| = ah * SCALE + al and
+        // |b| = bh * SCALE + bl, the exact quotient is
+        // ah * bh * SCALE + ah * bl + al * bh + al * bl / SCALE, and only
+        // the last term has a remainder, which decides the rounding.
+        self.output.push_str(
+            "  (func $decimal_mul (param $a i64) (param $b i64) (param $half_even i32) (result i64)\n",
+        );
+        self.output.push_str("    (local $negative i32)\n");
+        self.output.push_str("    (local $ah i64)\n");
+        self.output.push_str("    (local $al i64)\n");
+        self.output.push_str("    (local $bh i64)\n");
+        self.output.push_str("    (local $bl i64)\n");
+        self.output.push_str("    (local $low i64)\n");
+        self.output.push_str("    (local $twice_rem i64)\n");
+        self.output.push_str("    (local $whole i64)\n");
+        // i64::MIN has no magnitude in i64.
+        for operand in ["$a", "$b"] {
+            self.output.push_str(&format!("    local.get {operand}\n"));
+            self.output
+                .push_str(&format!("    i64.const {}\n", i64::MIN));
+            self.output.push_str("    i64.eq\n");
+            self.output.push_str("    (if\n");
+            self.output
+                .push_str("      (then call $decimal_overflow)\n");
+            self.output.push_str("    )\n");
+        }
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    i64.lt_s\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    i64.lt_s\n");
+        self.output.push_str("    i32.xor\n");
+        self.output.push_str("    local.set $negative\n");
+        for (operand, high, low) in [("$a", "$ah", "$al"), ("$b", "$bh", "$bl")] {
+            // Magnitude, then split at SCALE.
+            self.output.push_str("    i64.const 0\n");
+            self.output.push_str(&format!("    local.get {operand}\n"));
+            self.output.push_str("    i64.sub\n");
+            self.output.push_str(&format!("    local.get {operand}\n"));
+            self.output.push_str(&format!("    local.get {operand}\n"));
+            self.output.push_str("    i64.const 0\n");
+            self.output.push_str("    i64.lt_s\n");
+            self.output.push_str("    select\n");
+            self.output.push_str(&format!("    local.tee {operand}\n"));
+            self.output
+                .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+            self.output.push_str("    i64.div_u\n");
+            self.output.push_str(&format!("    local.set {high}\n"));
+            self.output.push_str(&format!("    local.get {operand}\n"));
+            self.output
+                .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+            self.output.push_str("    i64.rem_u\n");
+            self.output.push_str(&format!("    local.set {low}\n"));
+        }
+        self.output.push_str("    local.get $al\n");
+        self.output.push_str("    local.get $bl\n");
+        self.output.push_str("    i64.mul\n");
+        self.output.push_str("    local.set $low\n");
+        self.output.push_str("    local.get $ah\n");
+        self.output.push_str("    local.get $bh\n");
+        self.output.push_str("    call $decimal_mul_unsigned\n");
+        self.output
+            .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+        self.output.push_str("    call $decimal_mul_unsigned\n");
+        self.output.push_str("    local.get $ah\n");
+        self.output.push_str("    local.get $bl\n");
+        self.output.push_str("    call $decimal_mul_unsigned\n");
+        self.output.push_str("    call $decimal_add_unsigned\n");
+        self.output.push_str("    local.get $al\n");
+        self.output.push_str("    local.get $bh\n");
+        self.output.push_str("    call $decimal_mul_unsigned\n");
+        self.output.push_str("    call $decimal_add_unsigned\n");
+        self.output.push_str("    local.get $low\n");
+        self.output
+            .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+        self.output.push_str("    i64.div_u\n");
+        self.output.push_str("    call $decimal_add_unsigned\n");
+        self.output.push_str("    local.set $whole\n");
+        self.output.push_str("    local.get $low\n");
+        self.output
+            .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+        self.output.push_str("    i64.rem_u\n");
+        self.output.push_str("    i64.const 1\n");
+        self.output.push_str("    i64.shl\n");
+        self.output.push_str("    local.set $twice_rem\n");
+        // Same rule as `$decimal_round_div`, applied to the magnitude.
+        self.output.push_str("    local.get $twice_rem\n");
+        self.output
+            .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+        self.output.push_str("    i64.gt_u\n");
+        self.output.push_str("    local.get $twice_rem\n");
+        self.output
+            .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+        self.output.push_str("    i64.eq\n");
+        self.output.push_str("    local.get $half_even\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    local.get $whole\n");
+        self.output.push_str("    i32.wrap_i64\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.and\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    i32.and\n");
+        self.output.push_str("    i32.or\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $whole\n");
+        self.output.push_str("        i64.const 1\n");
+        self.output.push_str("        call $decimal_add_unsigned\n");
+        self.output.push_str("        local.set $whole\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    i64.const 0\n");
+        self.output.push_str("    local.get $whole\n");
+        self.output.push_str("    i64.sub\n");
+        self.output.push_str("    local.get $whole\n");
+        self.output.push_str("    local.get $negative\n");
+        self.output.push_str("    select\n");
+        self.output.push_str("  )\n");
+
+        // a * SCALE / b, split as (a / b) * SCALE + (a % b) * SCALE / b.
+        self.output.push_str(
+            "  (func $decimal_div (param $a i64) (param $b i64) (param $half_even i32) (result i64)\n",
+        );
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i64.div_s\n");
+        self.output
+            .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+        self.output.push_str("    i64.mul\n");
+        self.output.push_str("    local.get $a\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    i64.rem_s\n");
+        self.output
+            .push_str(&format!("    i64.const {}\n", DECIMAL_SCALE));
+        self.output.push_str("    i64.mul\n");
+        self.output.push_str("    local.get $b\n");
+        self.output.push_str("    local.get $half_even\n");
+        self.output.push_str("    call $decimal_round_div\n");
+        self.output.push_str("    i64.add\n");
+        self.output.push_str("  )\n");
+
+        for (name, half_even) in [("decimal_div_half_even", 1), ("decimal_div_half_up", 0)] {
+            self.output.push_str(&format!(
+                "  (func ${name} (param $a i64) (param $b i64) (result i64)\n"
+            ));
+            self.output.push_str("    local.get $a\n");
+            self.output.push_str("    local.get $b\n");
+            self.output
+                .push_str(&format!("    i32.const {half_even}\n"));
+            self.output.push_str("    call $decimal_div\n");
+            self.output.push_str("  )\n");
+        }
         Ok(())
     }
 
@@ -1180,13 +4775,23 @@ impl WasmCodeGen {
         self.output.push_str("    i32.eqz\n");
         self.output.push_str("    (if\n");
         self.output.push_str("      (then\n");
-        self.output.push_str("        unreachable\n");
+        self.output.push_str("        local.get $message\n");
+        self.output.push_str("        call $panic\n");
         self.output.push_str("      )\n");
         self.output.push_str("    )\n");
         self.output.push_str("  )\n");
 
+        // panic reports its message on stderr and exits with status 1. The
+        // trailing trap covers hosts whose proc_exit returns. Freestanding
+        // builds have neither, so panic only traps.
         self.output
             .push_str("  (func $panic (param $message i32)\n");
+        if self.target == Target::Wasi {
+            self.output.push_str("    local.get $message\n");
+            self.output.push_str("    call $eprintln\n");
+            self.output.push_str("    i32.const 1\n");
+            self.output.push_str("    call $proc_exit\n");
+        }
         self.output.push_str("    unreachable\n");
         self.output.push_str("  )\n");
 
@@ -1247,10 +4852,126 @@ impl WasmCodeGen {
             );
         }
 
+        // `drop` is lowered inline at call sites; the signature only marks it void.
+        self.functions.insert(
+            "drop".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32],
+                result: None,
+            },
+        );
+        self.function_source_sigs.insert(
+            "drop".to_string(),
+            FunctionSourceSig {
+                type_params: vec!["T".to_string()],
+                params: vec![Type::Named("T".to_string())],
+                result: Some(Type::Named("Unit".to_string())),
+            },
+        );
+
         Ok(())
     }
 
-    fn generate_std_option_functions(&mut self) {
+    /// Operand handling for `assert_eq`/`assert_ne` on a `Display` type:
+    /// helper suffix, Wasm type, equality instruction, and the conversion
+    /// to String (none for String itself).
+    fn assert_operand_helper(
+        type_name: &str,
+    ) -> Option<(&'static str, WasmType, &'static str, Option<&'static str>)> {
+        Some(match type_name {
+            "Int32" => ("int32", WasmType::I32, "i32.eq", Some("int_to_string")),
+            "Int64" => ("int64", WasmType::I64, "i64.eq", Some("int64_to_string")),
+            "Float64" => ("float64", WasmType::F64, "f64.eq", Some("float_to_string")),
+            "Decimal" => (
+                "decimal",
+                WasmType::I64,
+                "i64.eq",
+                Some("decimal_to_string"),
+            ),
+            "Boolean" => ("boolean", WasmType::I32, "i32.eq", Some("bool_to_string")),
+            "Char" => ("char", WasmType::I32, "i32.eq", Some("string_from_char")),
+            "String" => ("string", WasmType::I32, "call $string_eq", None),
+            _ => return None,
+        })
+    }
+
+    /// `$assert_compare_<type>` checks `(left == right) == $equal` and, only
+    /// when that fails, panics with `message: left = .., right = ..`.
+    fn generate_assert_comparison_functions(&mut self) {
+        for name in ["assert_eq", "assert_ne"] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![WasmType::I32; 3],
+                    result: None,
+                },
+            );
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec!["T".to_string()],
+                    params: vec![
+                        Type::Named("T".to_string()),
+                        Type::Named("T".to_string()),
+                        Type::Named("String".to_string()),
+                    ],
+                    result: Some(Type::Named("Unit".to_string())),
+                },
+            );
+        }
+        if self.comparison_asserts.is_empty() {
+            return;
+        }
+
+        self.output.push_str(
+            "  (func $assert_failed (param $message i32) (param $left i32) (param $right i32)\n",
+        );
+        self.output.push_str("    (local $out i32)\n");
+        self.output.push_str("    local.get $message\n");
+        self.emit_ascii_string_alloc(": left = ", "    ");
+        self.output.push_str("    call $string_concat\n");
+        self.output.push_str("    local.get $left\n");
+        self.output.push_str("    call $string_concat\n");
+        self.emit_ascii_string_alloc(", right = ", "    ");
+        self.output.push_str("    call $string_concat\n");
+        self.output.push_str("    local.get $right\n");
+        self.output.push_str("    call $string_concat\n");
+        self.output.push_str("    call $panic\n");
+        self.output.push_str("  )\n");
+
+        for type_name in [
+            "Int32", "Int64", "Float64", "Decimal", "Boolean", "Char", "String",
+        ] {
+            let (suffix, ty, compare, to_string) = Self::assert_operand_helper(type_name)
+                .expect("every listed assert operand type has a helper");
+            let wasm_ty = self.wasm_type_str(ty);
+            self.output.push_str(&format!(
+                "  (func $assert_compare_{suffix} (param $left {wasm_ty}) (param $right {wasm_ty}) (param $equal i32) (param $message i32)\n"
+            ));
+            self.output.push_str("    local.get $left\n");
+            self.output.push_str("    local.get $right\n");
+            self.output.push_str(&format!("    {compare}\n"));
+            self.output.push_str("    local.get $equal\n");
+            self.output.push_str("    i32.ne\n");
+            self.output.push_str("    (if\n");
+            self.output.push_str("      (then\n");
+            self.output.push_str("        local.get $message\n");
+            for operand in ["left", "right"] {
+                self.output
+                    .push_str(&format!("        local.get ${operand}\n"));
+                if let Some(to_string) = to_string {
+                    self.output
+                        .push_str(&format!("        call ${to_string}\n"));
+                }
+            }
+            self.output.push_str("        call $assert_failed\n");
+            self.output.push_str("      )\n");
+            self.output.push_str("    )\n");
+            self.output.push_str("  )\n");
+        }
+    }
+
+    fn generate_std_option_functions(&mut self) -> Result<(), CodeGenError> {
         self.output.push_str("\n  ;; Option operation functions\n");
         self.output
             .push_str("  (func $option_is_some (param $option i32) (result i32)\n");
@@ -1372,6 +5093,225 @@ impl WasmCodeGen {
                 result: Some(WasmType::F64),
             },
         );
+
+        // option_unwrap panics on None instead of taking a default.
+        let Some(&none_message) = self.string_offsets.get(UNWRAP_NONE_MESSAGE) else {
+            return Err(CodeGenError::UnsupportedFeature(
+                "option_unwrap outside string collection scope".to_string(),
+            ));
+        };
+        for (name, payload_ty) in [
+            ("option_unwrap", WasmType::I32),
+            ("option_unwrap_i64", WasmType::I64),
+            ("option_unwrap_f64", WasmType::F64),
+        ] {
+            let payload = self.wasm_type_str(payload_ty);
+            self.output.push_str(&format!(
+                "  (func ${} (param $option i32) (result {})\n",
+                name, payload
+            ));
+            self.output.push_str("    local.get $option\n");
+            self.output.push_str("    i32.load\n");
+            self.output.push_str("    i32.const 1\n");
+            self.output.push_str("    i32.ne\n");
+            self.output.push_str(&format!(
+                "    (if (then i32.const {} call $panic))\n",
+                none_message
+            ));
+            self.output.push_str("    local.get $option\n");
+            self.output.push_str("    i32.const 4\n");
+            self.output.push_str("    i32.add\n");
+            self.output.push_str(&format!(
+                "    {}\n",
+                self.wasm_load_op_for_wasm_type(payload_ty)
+            ));
+            self.output.push_str("  )\n");
+
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![WasmType::I32],
+                    result: Some(payload_ty),
+                },
+            );
+        }
+        self.function_source_sigs.insert(
+            "option_unwrap".to_string(),
+            FunctionSourceSig {
+                type_params: vec!["T".to_string()],
+                params: vec![Type::Generic(
+                    "Option".to_string(),
+                    vec![Type::Named("T".to_string())],
+                )],
+                result: Some(Type::Named("T".to_string())),
+            },
+        );
+
+        let option_of =
+            |param: &str| Type::Generic("Option".to_string(), vec![Type::Named(param.to_string())]);
+        for (name, mapper_result) in [
+            ("option_map", Type::Named("U".to_string())),
+            ("option_and_then", option_of("U")),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![WasmType::I32, WasmType::I32],
+                    result: Some(WasmType::I32),
+                },
+            );
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec!["T".to_string(), "U".to_string()],
+                    params: vec![
+                        Type::Function(vec![Type::Named("T".to_string())], Box::new(mapper_result)),
+                        option_of("T"),
+                    ],
+                    result: Some(option_of("U")),
+                },
+            );
+        }
+
+        // `Some(x)` and `Ok(x)` share a layout (tag 1, payload at offset 4),
+        // as do the empty cases (tag 0), so conversions reuse the operand
+        // whenever its tag carries over and only build the other case.
+        let result_of_t_e = || {
+            Type::Generic(
+                "Result".to_string(),
+                vec![Type::Named("T".to_string()), Type::Named("E".to_string())],
+            )
+        };
+        for (name, err_ty) in [
+            ("option_ok_or", WasmType::I32),
+            ("option_ok_or_i64", WasmType::I64),
+            ("option_ok_or_f64", WasmType::F64),
+        ] {
+            self.output.push_str(&format!(
+                "  (func ${} (param $option i32) (param $err {}) (result i32)\n",
+                name,
+                self.wasm_type_str(err_ty)
+            ));
+            self.output.push_str("    (local $result i32)\n");
+            self.output.push_str("    local.get $option\n");
+            self.output.push_str("    i32.load\n");
+            self.output.push_str("    i32.const 1\n");
+            self.output.push_str("    i32.eq\n");
+            self.output.push_str("    (if (result i32)\n");
+            self.output.push_str("      (then\n");
+            self.output.push_str("        local.get $option\n");
+            self.output.push_str("      )\n");
+            self.output.push_str("      (else\n");
+            self.output.push_str(&format!(
+                "        i32.const {}\n",
+                4 + self.wasm_type_size(err_ty)
+            ));
+            self.output.push_str("        call $allocate\n");
+            self.output.push_str("        local.tee $result\n");
+            self.output.push_str("        i32.const 0 ;; Err tag\n");
+            self.output.push_str("        i32.store\n");
+            self.output.push_str("        local.get $result\n");
+            self.output.push_str("        local.get $err\n");
+            self.output.push_str(&format!(
+                "        {} offset=4\n",
+                self.wasm_store_op_for_wasm_type(err_ty)
+            ));
+            self.output.push_str("        local.get $result\n");
+            self.output.push_str("      )\n");
+            self.output.push_str("    )\n");
+            self.output.push_str("  )\n");
+
+            self.functions.insert(
+                name.to_string(),
+                FunctionSig {
+                    _params: vec![WasmType::I32, err_ty],
+                    result: Some(WasmType::I32),
+                },
+            );
+        }
+        self.function_source_sigs.insert(
+            "option_ok_or".to_string(),
+            FunctionSourceSig {
+                type_params: vec!["T".to_string(), "E".to_string()],
+                params: vec![option_of("T"), Type::Named("E".to_string())],
+                result: Some(result_of_t_e()),
+            },
+        );
+
+        self.output
+            .push_str("  (func $result_ok (param $result i32) (result i32)\n");
+        self.output.push_str("    (local $option i32)\n");
+        self.output.push_str("    local.get $result\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.eq\n");
+        self.output.push_str("    (if (result i32)\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $result\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output.push_str("        i32.const 8\n");
+        self.output.push_str("        call $allocate\n");
+        self.output.push_str("        local.tee $option\n");
+        self.output.push_str("        i32.const 0 ;; None tag\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "result_ok".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32],
+                result: Some(WasmType::I32),
+            },
+        );
+        self.function_source_sigs.insert(
+            "result_ok".to_string(),
+            FunctionSourceSig {
+                type_params: vec!["T".to_string(), "E".to_string()],
+                params: vec![result_of_t_e()],
+                result: Some(option_of("T")),
+            },
+        );
+
+        // The payload of an outer `Some` is the inner option's pointer; an
+        // outer `None` is already a valid empty `Option<T>`.
+        self.output
+            .push_str("  (func $option_flatten (param $option i32) (result i32)\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.eq\n");
+        self.output.push_str("    (if (result i32)\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str("        i32.load offset=4\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "option_flatten".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32],
+                result: Some(WasmType::I32),
+            },
+        );
+        self.function_source_sigs.insert(
+            "option_flatten".to_string(),
+            FunctionSourceSig {
+                type_params: vec!["T".to_string()],
+                params: vec![Type::Generic("Option".to_string(), vec![option_of("T")])],
+                result: Some(option_of("T")),
+            },
+        );
+
+        Ok(())
     }
 
     fn generate_indirect_call_types(&mut self) {
@@ -1437,14 +5377,27 @@ impl WasmCodeGen {
         self.output.push_str("    local.get $start\n");
         self.output.push_str("    local.get $start\n");
         self.output.push_str("    i32.store\n");
-        self.output
-            .push_str("    ;; Store current address at offset 4 (start + 8 for header)\n");
+        self.output.push_str(&format!(
+            "    ;; Store current address at offset 4 (start + {} for header)\n",
+            ARENA_HEADER_BYTES
+        ));
         self.output.push_str("    local.get $start\n");
         self.output.push_str("    i32.const 4\n");
         self.output.push_str("    i32.add\n");
         self.output.push_str("    local.get $start\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", ARENA_HEADER_BYTES));
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    i32.store\n");
+        self.output
+            .push_str("    ;; Store end address at offset 8\n");
+        self.output.push_str("    local.get $start\n");
         self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $start\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", self.arena_size()));
+        self.output.push_str("    i32.add\n");
         self.output.push_str("    i32.store\n");
         self.output.push_str("    ;; Return arena header address\n");
         self.output.push_str("    local.get $start\n");
@@ -1459,6 +5412,26 @@ impl WasmCodeGen {
             },
         );
 
+        // Arena overflow handler: report on stderr and exit like `panic`.
+        let Some(&oom_message) = self.string_offsets.get(ARENA_OOM_MESSAGE) else {
+            return Err(CodeGenError::UnsupportedFeature(
+                "arena allocator outside string collection scope".to_string(),
+            ));
+        };
+        self.output.push_str("  (func $arena_oom\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", oom_message));
+        self.output.push_str("    call $panic\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "arena_oom".to_string(),
+            FunctionSig {
+                _params: vec![],
+                result: None,
+            },
+        );
+
         // Arena alloc function
         self.output
             .push_str("  (func $arena_alloc (param $arena i32) (param $size i32) (result i32)\n");
@@ -1488,11 +5461,12 @@ impl WasmCodeGen {
         self.output.push_str("    i32.add\n");
         self.output.push_str("    local.set $new_current\n");
         self.output.push_str("    \n");
-        self.output.push_str("    ;; Arena bounds check\n");
-        self.output.push_str("    local.get $arena\n");
         self.output
-            .push_str(&format!("    i32.const {}\n", ARENA_SIZE_BYTES));
+            .push_str("    ;; Arena bounds check against the end stored in the header\n");
+        self.output.push_str("    local.get $arena\n");
+        self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.add\n");
+        self.output.push_str("    i32.load\n");
         self.output.push_str("    local.set $arena_end\n");
         self.output.push_str("    local.get $new_current\n");
         self.output.push_str("    local.get $arena_end\n");
@@ -1501,7 +5475,7 @@ impl WasmCodeGen {
         self.output.push_str("      (then\n");
         self.output
             .push_str("        ;; Arena allocation overflow - trap\n");
-        self.output.push_str("        unreachable\n");
+        self.output.push_str("        call $arena_oom\n");
         self.output.push_str("      )\n");
         self.output.push_str("    )\n");
         self.output.push_str("    \n");
@@ -1527,14 +5501,17 @@ impl WasmCodeGen {
         // Arena reset function
         self.output
             .push_str("  (func $arena_reset (param $arena i32)\n");
-        self.output
-            .push_str("    ;; Reset current to start + 8 (after header)\n");
+        self.output.push_str(&format!(
+            "    ;; Reset current to start + {} (after header)\n",
+            ARENA_HEADER_BYTES
+        ));
         self.output.push_str("    local.get $arena\n");
         self.output.push_str("    i32.const 4\n");
         self.output.push_str("    i32.add\n");
         self.output.push_str("    local.get $arena\n");
         self.output.push_str("    i32.load\n");
-        self.output.push_str("    i32.const 8\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", ARENA_HEADER_BYTES));
         self.output.push_str("    i32.add\n");
         self.output.push_str("    i32.store\n");
         self.output.push_str("  )\n");
@@ -1574,6 +5551,7 @@ impl WasmCodeGen {
 
         self.generate_string_concat_function();
         self.generate_string_eq_function();
+        self.generate_string_compare_function();
 
         Ok(())
     }
@@ -1769,6 +5747,95 @@ impl WasmCodeGen {
         );
     }
 
+    /// Emits `$string_compare`, a bytewise lexicographic comparison that
+    /// returns -1, 0 or 1; a string orders before any longer string it
+    /// prefixes.
+    fn generate_string_compare_function(&mut self) {
+        self.output.push_str("\n  ;; String ordering function\n");
+        self.output.push_str(
+            "  (func $string_compare (param $left i32) (param $right i32) (result i32)\n",
+        );
+        self.output.push_str("    (local $left_len i32)\n");
+        self.output.push_str("    (local $right_len i32)\n");
+        self.output.push_str("    (local $shared i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    (local $left_byte i32)\n");
+        self.output.push_str("    (local $right_byte i32)\n");
+        self.output.push_str("    local.get $left\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $left_len\n");
+        self.output.push_str("    local.get $right\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $right_len\n");
+        self.output.push_str("    local.get $left_len\n");
+        self.output.push_str("    local.get $right_len\n");
+        self.output.push_str("    local.get $left_len\n");
+        self.output.push_str("    local.get $right_len\n");
+        self.output.push_str("    i32.lt_u\n");
+        self.output.push_str("    select\n");
+        self.output.push_str("    local.set $shared\n");
+        self.output.push_str("    (block $string_compare_done\n");
+        self.output.push_str("      (loop $string_compare_loop\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $shared\n");
+        self.output.push_str("        i32.ge_u\n");
+        self.output.push_str("        br_if $string_compare_done\n");
+        self.output.push_str("        local.get $left\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load8_u\n");
+        self.output.push_str("        local.set $left_byte\n");
+        self.output.push_str("        local.get $right\n");
+        self.output.push_str("        i32.const 4\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.load8_u\n");
+        self.output.push_str("        local.set $right_byte\n");
+        self.output.push_str("        local.get $left_byte\n");
+        self.output.push_str("        local.get $right_byte\n");
+        self.output.push_str("        i32.ne\n");
+        self.output.push_str("        (if\n");
+        self.output.push_str("          (then\n");
+        self.output.push_str("            local.get $left_byte\n");
+        self.output.push_str("            local.get $right_byte\n");
+        self.output.push_str("            i32.gt_u\n");
+        self.output.push_str("            local.get $left_byte\n");
+        self.output.push_str("            local.get $right_byte\n");
+        self.output.push_str("            i32.lt_u\n");
+        self.output.push_str("            i32.sub\n");
+        self.output.push_str("            return\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        br $string_compare_loop\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output
+            .push_str("    ;; Equal up to the shorter length: the shorter string orders first\n");
+        self.output.push_str("    local.get $left_len\n");
+        self.output.push_str("    local.get $right_len\n");
+        self.output.push_str("    i32.gt_u\n");
+        self.output.push_str("    local.get $left_len\n");
+        self.output.push_str("    local.get $right_len\n");
+        self.output.push_str("    i32.lt_u\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "string_compare".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32, WasmType::I32],
+                result: Some(WasmType::I32),
+            },
+        );
+    }
+
     fn generate_list_functions(&mut self) -> Result<(), CodeGenError> {
         self.output.push_str("\n  ;; List operation functions\n");
 
@@ -3083,41 +7150,213 @@ impl WasmCodeGen {
         self.output.push_str("    local.get $new_list\n");
         self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.add\n");
-        self.output.push_str("    local.get $left\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    local.get $left_length\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.mul\n");
-        self.output.push_str("    memory.copy\n");
-        self.output.push_str("    local.get $new_list\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    local.get $left_length\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.mul\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    local.get $right\n");
-        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    local.get $left\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $left_length\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    memory.copy\n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $left_length\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $right\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $right_length\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    memory.copy\n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "list_concat_i64".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32, WasmType::I32],
+                result: Some(WasmType::I32),
+            },
+        );
+
+        // Tail function
+        self.output
+            .push_str("  (func $tail (param $list i32) (result i32)\n");
+        self.output.push_str("    (local $length i32)\n");
+        self.output.push_str("    (local $new_list i32)\n");
+        self.output.push_str("    (local $new_length i32)\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Load original length\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $length\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Check if list is empty\n");
+        self.output.push_str("    local.get $length\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output
+            .push_str("        ;; Return the same empty list\n");
+        self.output.push_str("        local.get $list\n");
+        self.output.push_str("        return\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Calculate new length\n");
+        self.output.push_str("    local.get $length\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    local.set $new_length\n");
+        self.output.push_str("    \n");
+        self.output
+            .push_str("    ;; Allocate new list: 8 bytes header + (new_length * 4) bytes data\n");
+        self.output.push_str("    local.get $new_length\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $new_list\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Write new length\n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("    local.get $new_length\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    \n");
+        self.output
+            .push_str("    ;; Write new capacity (same as length)\n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $new_length\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    \n");
+        self.output
+            .push_str("    ;; Copy elements from original list (skip first element)\n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    ;; destination\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    i32.const 12\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    ;; source\n");
+        self.output.push_str("    local.get $new_length\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    ;; size\n");
+        self.output.push_str("    memory.copy\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("  )\n");
+
+        self.functions.insert(
+            "tail".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32],
+                result: Some(WasmType::I32),
+            },
+        );
+        self.function_source_sigs.insert(
+            "tail".to_string(),
+            FunctionSourceSig {
+                type_params: vec!["T".to_string()],
+                params: vec![Type::Generic(
+                    "List".to_string(),
+                    vec![Type::Named("T".to_string())],
+                )],
+                result: Some(Type::Generic(
+                    "List".to_string(),
+                    vec![Type::Named("T".to_string())],
+                )),
+            },
+        );
+
+        self.output
+            .push_str("  (func $tail_f64 (param $list i32) (result i32)\n");
+        self.output.push_str("    (local $length i32)\n");
+        self.output.push_str("    (local $new_list i32)\n");
+        self.output.push_str("    (local $new_length i32)\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Load original length\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $length\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Check if list is empty\n");
+        self.output.push_str("    local.get $length\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output
+            .push_str("        ;; Return the same empty list\n");
+        self.output.push_str("        local.get $list\n");
+        self.output.push_str("        return\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Calculate new length\n");
+        self.output.push_str("    local.get $length\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    local.set $new_length\n");
+        self.output.push_str("    \n");
+        self.output
+            .push_str("    ;; Allocate new list: 8 bytes header + (new_length * 8) bytes data\n");
+        self.output.push_str("    local.get $new_length\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $new_list\n");
+        self.output.push_str("    \n");
+        self.output.push_str("    ;; Write new length\n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("    local.get $new_length\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    \n");
+        self.output
+            .push_str("    ;; Write new capacity (same as length)\n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $new_length\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    \n");
+        self.output
+            .push_str("    ;; Copy elements from original Float64 list (skip first element)\n");
+        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    ;; destination\n");
+        self.output.push_str("    local.get $list\n");
+        self.output.push_str("    i32.const 16\n");
         self.output.push_str("    i32.add\n");
-        self.output.push_str("    local.get $right_length\n");
+        self.output.push_str("    ;; source\n");
+        self.output.push_str("    local.get $new_length\n");
         self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.mul\n");
+        self.output.push_str("    ;; size\n");
         self.output.push_str("    memory.copy\n");
+        self.output.push_str("    \n");
         self.output.push_str("    local.get $new_list\n");
         self.output.push_str("  )\n");
 
         self.functions.insert(
-            "list_concat_i64".to_string(),
+            "tail_f64".to_string(),
             FunctionSig {
-                _params: vec![WasmType::I32, WasmType::I32],
+                _params: vec![WasmType::I32],
                 result: Some(WasmType::I32),
             },
         );
 
-        // Tail function
         self.output
-            .push_str("  (func $tail (param $list i32) (result i32)\n");
+            .push_str("  (func $tail_i64 (param $list i32) (result i32)\n");
         self.output.push_str("    (local $length i32)\n");
         self.output.push_str("    (local $new_list i32)\n");
         self.output.push_str("    (local $new_length i32)\n");
@@ -3146,9 +7385,9 @@ impl WasmCodeGen {
         self.output.push_str("    local.set $new_length\n");
         self.output.push_str("    \n");
         self.output
-            .push_str("    ;; Allocate new list: 8 bytes header + (new_length * 4) bytes data\n");
+            .push_str("    ;; Allocate new list: 8 bytes header + (new_length * 8) bytes data\n");
         self.output.push_str("    local.get $new_length\n");
-        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.mul\n");
         self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.add\n");
@@ -3169,204 +7408,822 @@ impl WasmCodeGen {
         self.output.push_str("    i32.store\n");
         self.output.push_str("    \n");
         self.output
-            .push_str("    ;; Copy elements from original list (skip first element)\n");
+            .push_str("    ;; Copy elements from original Int64 list (skip first element)\n");
         self.output.push_str("    local.get $new_list\n");
         self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.add\n");
         self.output.push_str("    ;; destination\n");
         self.output.push_str("    local.get $list\n");
-        self.output.push_str("    i32.const 12\n");
+        self.output.push_str("    i32.const 16\n");
         self.output.push_str("    i32.add\n");
         self.output.push_str("    ;; source\n");
         self.output.push_str("    local.get $new_length\n");
-        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.mul\n");
         self.output.push_str("    ;; size\n");
         self.output.push_str("    memory.copy\n");
         self.output.push_str("    \n");
         self.output.push_str("    local.get $new_list\n");
         self.output.push_str("  )\n");
-
-        self.functions.insert(
-            "tail".to_string(),
-            FunctionSig {
-                _params: vec![WasmType::I32],
-                result: Some(WasmType::I32),
-            },
-        );
-        self.function_source_sigs.insert(
-            "tail".to_string(),
-            FunctionSourceSig {
-                type_params: vec!["T".to_string()],
-                params: vec![Type::Generic(
-                    "List".to_string(),
-                    vec![Type::Named("T".to_string())],
-                )],
-                result: Some(Type::Generic(
-                    "List".to_string(),
-                    vec![Type::Named("T".to_string())],
-                )),
-            },
+
+        self.functions.insert(
+            "tail_i64".to_string(),
+            FunctionSig {
+                _params: vec![WasmType::I32],
+                result: Some(WasmType::I32),
+            },
+        );
+
+        self.generate_list_search_functions();
+        self.generate_list_slice_functions();
+
+        Ok(())
+    }
+
+    /// Emits `list_slice`, `list_take` and `list_drop` for 4- and 8-byte
+    /// elements. `list_slice` clamps both bounds into `0..=length` (and the
+    /// end to at least the start) and copies that range into a new list, so
+    /// out-of-range indices yield a shorter list instead of trapping.
+    fn generate_list_slice_functions(&mut self) {
+        for (suffix, stride) in [("", 4), ("_i64", 8), ("_f64", 8)] {
+            self.output.push_str(&format!(
+                "  (func $list_slice{} (param $list i32) (param $start i32) (param $end i32) (result i32)\n",
+                suffix
+            ));
+            self.output.push_str("    (local $length i32)\n");
+            self.output.push_str("    (local $new_length i32)\n");
+            self.output.push_str("    (local $new_list i32)\n");
+            self.output.push_str("    local.get $list\n");
+            self.output.push_str("    i32.load\n");
+            self.output.push_str("    local.set $length\n");
+            self.output.push_str("    ;; Clamp start into 0..=length\n");
+            self.output.push_str("    local.get $start\n");
+            self.output.push_str("    i32.const 0\n");
+            self.output.push_str("    local.get $start\n");
+            self.output.push_str("    i32.const 0\n");
+            self.output.push_str("    i32.gt_s\n");
+            self.output.push_str("    select\n");
+            self.output.push_str("    local.set $start\n");
+            self.output.push_str("    local.get $start\n");
+            self.output.push_str("    local.get $length\n");
+            self.output.push_str("    local.get $start\n");
+            self.output.push_str("    local.get $length\n");
+            self.output.push_str("    i32.lt_s\n");
+            self.output.push_str("    select\n");
+            self.output.push_str("    local.set $start\n");
+            self.output
+                .push_str("    ;; Clamp end into start..=length\n");
+            self.output.push_str("    local.get $end\n");
+            self.output.push_str("    local.get $length\n");
+            self.output.push_str("    local.get $end\n");
+            self.output.push_str("    local.get $length\n");
+            self.output.push_str("    i32.lt_s\n");
+            self.output.push_str("    select\n");
+            self.output.push_str("    local.set $end\n");
+            self.output.push_str("    local.get $end\n");
+            self.output.push_str("    local.get $start\n");
+            self.output.push_str("    local.get $end\n");
+            self.output.push_str("    local.get $start\n");
+            self.output.push_str("    i32.gt_s\n");
+            self.output.push_str("    select\n");
+            self.output.push_str("    local.set $end\n");
+            self.output.push_str("    local.get $end\n");
+            self.output.push_str("    local.get $start\n");
+            self.output.push_str("    i32.sub\n");
+            self.output.push_str("    local.set $new_length\n");
+            self.output.push_str("    local.get $new_length\n");
+            self.output.push_str(&format!("    i32.const {}\n", stride));
+            self.output.push_str("    i32.mul\n");
+            self.output.push_str("    i32.const 8\n");
+            self.output.push_str("    i32.add\n");
+            self.output.push_str("    call $allocate\n");
+            self.output.push_str("    local.set $new_list\n");
+            self.output.push_str("    local.get $new_list\n");
+            self.output.push_str("    local.get $new_length\n");
+            self.output.push_str("    i32.store\n");
+            self.output.push_str("    local.get $new_list\n");
+            self.output.push_str("    i32.const 4\n");
+            self.output.push_str("    i32.add\n");
+            self.output.push_str("    local.get $new_length\n");
+            self.output.push_str("    i32.store\n");
+            self.output.push_str("    local.get $new_list\n");
+            self.output.push_str("    i32.const 8\n");
+            self.output.push_str("    i32.add\n");
+            self.output.push_str("    local.get $list\n");
+            self.output.push_str("    i32.const 8\n");
+            self.output.push_str("    i32.add\n");
+            self.output.push_str("    local.get $start\n");
+            self.output.push_str(&format!("    i32.const {}\n", stride));
+            self.output.push_str("    i32.mul\n");
+            self.output.push_str("    i32.add\n");
+            self.output.push_str("    local.get $new_length\n");
+            self.output.push_str(&format!("    i32.const {}\n", stride));
+            self.output.push_str("    i32.mul\n");
+            self.output.push_str("    memory.copy\n");
+            self.output.push_str("    local.get $new_list\n");
+            self.output.push_str("  )\n");
+
+            self.output.push_str(&format!(
+                "  (func $list_take{} (param $list i32) (param $count i32) (result i32)\n",
+                suffix
+            ));
+            self.output.push_str("    local.get $list\n");
+            self.output.push_str("    i32.const 0\n");
+            self.output.push_str("    local.get $count\n");
+            self.output
+                .push_str(&format!("    call $list_slice{}\n", suffix));
+            self.output.push_str("  )\n");
+
+            self.output.push_str(&format!(
+                "  (func $list_drop{} (param $list i32) (param $count i32) (result i32)\n",
+                suffix
+            ));
+            self.output.push_str("    local.get $list\n");
+            self.output.push_str("    local.get $count\n");
+            self.output.push_str("    local.get $list\n");
+            self.output.push_str("    i32.load\n");
+            self.output
+                .push_str(&format!("    call $list_slice{}\n", suffix));
+            self.output.push_str("  )\n");
+
+            self.functions.insert(
+                format!("list_slice{}", suffix),
+                FunctionSig {
+                    _params: vec![WasmType::I32, WasmType::I32, WasmType::I32],
+                    result: Some(WasmType::I32),
+                },
+            );
+            for name in ["list_take", "list_drop"] {
+                self.functions.insert(
+                    format!("{}{}", name, suffix),
+                    FunctionSig {
+                        _params: vec![WasmType::I32, WasmType::I32],
+                        result: Some(WasmType::I32),
+                    },
+                );
+            }
+        }
+
+        let list_t = Type::Generic("List".to_string(), vec![Type::Named("T".to_string())]);
+        let int32 = Type::Named("Int32".to_string());
+        for (name, params) in [
+            ("list_take", vec![list_t.clone(), int32.clone()]),
+            ("list_drop", vec![list_t.clone(), int32.clone()]),
+            ("list_slice", vec![list_t.clone(), int32.clone(), int32]),
+        ] {
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec!["T".to_string()],
+                    params,
+                    result: Some(list_t.clone()),
+                },
+            );
+        }
+    }
+
+    /// Emits `list_contains` and `list_index_of` for each element ABI. Both
+    /// wrap a `list_position` scan that returns the first matching index or
+    /// -1; strings compare by content through `$string_eq`.
+    fn generate_list_search_functions(&mut self) {
+        for (suffix, wasm_ty, stride, compare) in [
+            ("", WasmType::I32, 4, "i32.eq"),
+            ("_i64", WasmType::I64, 8, "i64.eq"),
+            ("_f64", WasmType::F64, 8, "f64.eq"),
+            ("_string", WasmType::I32, 4, "call $string_eq"),
+        ] {
+            let ty = self.wasm_type_str(wasm_ty);
+            self.output.push_str(&format!(
+                "  (func $list_position{} (param $list i32) (param $item {}) (result i32)\n",
+                suffix, ty
+            ));
+            self.output.push_str("    (local $length i32)\n");
+            self.output.push_str("    (local $index i32)\n");
+            self.output.push_str("    local.get $list\n");
+            self.output.push_str("    i32.load\n");
+            self.output.push_str("    local.set $length\n");
+            self.output.push_str("    (block $not_found\n");
+            self.output.push_str("      (loop $scan\n");
+            self.output.push_str("        local.get $index\n");
+            self.output.push_str("        local.get $length\n");
+            self.output.push_str("        i32.ge_u\n");
+            self.output.push_str("        br_if $not_found\n");
+            self.output.push_str(&format!(
+                "        ;; Compare element at list + 8 + (index * {})\n",
+                stride
+            ));
+            self.output.push_str("        local.get $list\n");
+            self.output.push_str("        i32.const 8\n");
+            self.output.push_str("        i32.add\n");
+            self.output.push_str("        local.get $index\n");
+            self.output
+                .push_str(&format!("        i32.const {}\n", stride));
+            self.output.push_str("        i32.mul\n");
+            self.output.push_str("        i32.add\n");
+            self.output.push_str(&format!("        {}.load\n", ty));
+            self.output.push_str("        local.get $item\n");
+            self.output.push_str(&format!("        {}\n", compare));
+            self.output.push_str("        (if\n");
+            self.output.push_str("          (then\n");
+            self.output.push_str("            local.get $index\n");
+            self.output.push_str("            return\n");
+            self.output.push_str("          )\n");
+            self.output.push_str("        )\n");
+            self.output.push_str("        local.get $index\n");
+            self.output.push_str("        i32.const 1\n");
+            self.output.push_str("        i32.add\n");
+            self.output.push_str("        local.set $index\n");
+            self.output.push_str("        br $scan\n");
+            self.output.push_str("      )\n");
+            self.output.push_str("    )\n");
+            self.output.push_str("    i32.const -1\n");
+            self.output.push_str("  )\n");
+
+            self.output.push_str(&format!(
+                "  (func $list_contains{} (param $list i32) (param $item {}) (result i32)\n",
+                suffix, ty
+            ));
+            self.output.push_str("    local.get $list\n");
+            self.output.push_str("    local.get $item\n");
+            self.output
+                .push_str(&format!("    call $list_position{}\n", suffix));
+            self.output.push_str("    i32.const 0\n");
+            self.output.push_str("    i32.ge_s\n");
+            self.output.push_str("  )\n");
+
+            self.output.push_str(&format!(
+                "  (func $list_index_of{} (param $list i32) (param $item {}) (result i32)\n",
+                suffix, ty
+            ));
+            self.output.push_str("    (local $index i32)\n");
+            self.output.push_str("    (local $option i32)\n");
+            self.output.push_str("    local.get $list\n");
+            self.output.push_str("    local.get $item\n");
+            self.output
+                .push_str(&format!("    call $list_position{}\n", suffix));
+            self.output.push_str("    local.set $index\n");
+            self.output.push_str("    i32.const 8\n");
+            self.output.push_str("    call $allocate\n");
+            self.output.push_str("    local.set $option\n");
+            self.output.push_str("    local.get $index\n");
+            self.output.push_str("    i32.const 0\n");
+            self.output.push_str("    i32.lt_s\n");
+            self.output.push_str("    (if\n");
+            self.output.push_str("      (then\n");
+            self.output.push_str("        local.get $option\n");
+            self.output.push_str("        i32.const 0\n");
+            self.output.push_str("        i32.store\n");
+            self.output.push_str("      )\n");
+            self.output.push_str("      (else\n");
+            self.output.push_str("        local.get $option\n");
+            self.output.push_str("        i32.const 1\n");
+            self.output.push_str("        i32.store\n");
+            self.output.push_str("        local.get $option\n");
+            self.output.push_str("        i32.const 4\n");
+            self.output.push_str("        i32.add\n");
+            self.output.push_str("        local.get $index\n");
+            self.output.push_str("        i32.store\n");
+            self.output.push_str("      )\n");
+            self.output.push_str("    )\n");
+            self.output.push_str("    local.get $option\n");
+            self.output.push_str("  )\n");
+
+            for name in ["list_position", "list_contains", "list_index_of"] {
+                self.functions.insert(
+                    format!("{}{}", name, suffix),
+                    FunctionSig {
+                        _params: vec![WasmType::I32, wasm_ty],
+                        result: Some(WasmType::I32),
+                    },
+                );
+            }
+        }
+
+        for (name, result) in [
+            ("list_contains", Type::Named("Boolean".to_string())),
+            (
+                "list_index_of",
+                Type::Generic("Option".to_string(), vec![Type::Named("Int32".to_string())]),
+            ),
+        ] {
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec!["T".to_string()],
+                    params: vec![
+                        Type::Generic("List".to_string(), vec![Type::Named("T".to_string())]),
+                        Type::Named("T".to_string()),
+                    ],
+                    result: Some(result),
+                },
+            );
+        }
+    }
+
+    /// Maps are open-addressing hash tables in the arena. The 16-byte header
+    /// holds the length, the power-of-two capacity, the entry pointer and the
+    /// count of occupied slots including tombstones. Each 12-byte entry holds
+    /// a state word (0 empty, 1 live, 2 removed), the key and the value.
+    /// Keys are hashed as Int32 or by their String bytes, selected by the
+    /// `_string` ABI variants.
+    fn generate_map_functions(&mut self) -> Result<(), CodeGenError> {
+        self.output.push_str("\n  ;; Map operation functions\n");
+        self.output
+            .push_str("  (func $map_entries_alloc (param $capacity i32) (result i32)\n");
+        self.output.push_str("    (local $entries i32)\n");
+        self.output.push_str("    local.get $capacity\n");
+        self.output.push_str("    i32.const 12\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $entries\n");
+        self.output.push_str("    local.get $entries\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    local.get $capacity\n");
+        self.output.push_str("    i32.const 12\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    memory.fill\n");
+        self.output.push_str("    local.get $entries\n");
+        self.output.push_str("  )\n");
+        self.output.push_str("  (func $map_new (result i32)\n");
+        self.output.push_str("    (local $map i32)\n");
+        self.output.push_str("    i32.const 16\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $map\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.store offset=4\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    call $map_entries_alloc\n");
+        self.output.push_str("    i32.store offset=8\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    i32.store offset=12\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("  )\n");
+        self.output
+            .push_str("  (func $map_hash (param $key i32) (param $string_keys i32) (result i32)\n");
+        self.output.push_str("    (local $hash i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    local.get $string_keys\n");
+        self.output.push_str("    (if (result i32)\n");
+        self.output.push_str("      (then\n");
+        self.output
+            .push_str("        ;; FNV-1a over the UTF-8 bytes\n");
+        self.output.push_str("        i32.const -2128831035\n");
+        self.output.push_str("        local.set $hash\n");
+        self.output.push_str("        (block $hashed\n");
+        self.output.push_str("          (loop $next_byte\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            local.get $key\n");
+        self.output.push_str("            i32.load\n");
+        self.output.push_str("            i32.ge_u\n");
+        self.output.push_str("            br_if $hashed\n");
+        self.output.push_str("            local.get $hash\n");
+        self.output.push_str("            local.get $key\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            i32.load8_u offset=4\n");
+        self.output.push_str("            i32.xor\n");
+        self.output.push_str("            i32.const 16777619\n");
+        self.output.push_str("            i32.mul\n");
+        self.output.push_str("            local.set $hash\n");
+        self.output.push_str("            local.get $i\n");
+        self.output.push_str("            i32.const 1\n");
+        self.output.push_str("            i32.add\n");
+        self.output.push_str("            local.set $i\n");
+        self.output.push_str("            br $next_byte\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        local.get $hash\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output
+            .push_str("        ;; Fibonacci hashing spreads sequential keys across the table\n");
+        self.output.push_str("        local.get $key\n");
+        self.output.push_str("        i32.const -1640531535\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.tee $hash\n");
+        self.output.push_str("    local.get $hash\n");
+        self.output.push_str("    i32.const 16\n");
+        self.output.push_str("    i32.shr_u\n");
+        self.output.push_str("    i32.xor\n");
+        self.output.push_str("  )\n");
+        self.output.push_str("  (func $map_key_eq (param $left i32) (param $right i32) (param $string_keys i32) (result i32)\n");
+        self.output.push_str("    local.get $string_keys\n");
+        self.output.push_str("    (if (result i32)\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $left\n");
+        self.output.push_str("        local.get $right\n");
+        self.output.push_str("        call $string_eq\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output.push_str("        local.get $left\n");
+        self.output.push_str("        local.get $right\n");
+        self.output.push_str("        i32.eq\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("  )\n");
+        self.output
+            .push_str("  ;; Address of the live entry holding $key, or -1 when it is absent\n");
+        self.output.push_str("  (func $map_find (param $map i32) (param $key i32) (param $string_keys i32) (result i32)\n");
+        self.output.push_str("    (local $mask i32)\n");
+        self.output.push_str("    (local $index i32)\n");
+        self.output.push_str("    (local $entry i32)\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.load offset=4\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    local.set $mask\n");
+        self.output.push_str("    local.get $key\n");
+        self.output.push_str("    local.get $string_keys\n");
+        self.output.push_str("    call $map_hash\n");
+        self.output.push_str("    local.get $mask\n");
+        self.output.push_str("    i32.and\n");
+        self.output.push_str("    local.set $index\n");
+        self.output.push_str("    (loop $probe\n");
+        self.output.push_str("      local.get $map\n");
+        self.output.push_str("      i32.load offset=8\n");
+        self.output.push_str("      local.get $index\n");
+        self.output.push_str("      i32.const 12\n");
+        self.output.push_str("      i32.mul\n");
+        self.output.push_str("      i32.add\n");
+        self.output.push_str("      local.set $entry\n");
+        self.output.push_str("      local.get $entry\n");
+        self.output.push_str("      i32.load\n");
+        self.output.push_str("      i32.eqz\n");
+        self.output.push_str("      (if\n");
+        self.output.push_str("        (then\n");
+        self.output.push_str("          i32.const -1\n");
+        self.output.push_str("          return\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $entry\n");
+        self.output.push_str("      i32.load\n");
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      i32.eq\n");
+        self.output.push_str("      (if\n");
+        self.output.push_str("        (then\n");
+        self.output.push_str("          local.get $entry\n");
+        self.output.push_str("          i32.load offset=4\n");
+        self.output.push_str("          local.get $key\n");
+        self.output.push_str("          local.get $string_keys\n");
+        self.output.push_str("          call $map_key_eq\n");
+        self.output.push_str("          (if\n");
+        self.output.push_str("            (then\n");
+        self.output.push_str("              local.get $entry\n");
+        self.output.push_str("              return\n");
+        self.output.push_str("            )\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $index\n");
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      i32.add\n");
+        self.output.push_str("      local.get $mask\n");
+        self.output.push_str("      i32.and\n");
+        self.output.push_str("      local.set $index\n");
+        self.output.push_str("      br $probe\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    unreachable\n");
+        self.output.push_str("  )\n");
+        self.output
+            .push_str("  ;; First entry from the home slot of $key that holds no live key\n");
+        self.output.push_str("  (func $map_free_slot (param $map i32) (param $key i32) (param $string_keys i32) (result i32)\n");
+        self.output.push_str("    (local $mask i32)\n");
+        self.output.push_str("    (local $index i32)\n");
+        self.output.push_str("    (local $entry i32)\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.load offset=4\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.sub\n");
+        self.output.push_str("    local.set $mask\n");
+        self.output.push_str("    local.get $key\n");
+        self.output.push_str("    local.get $string_keys\n");
+        self.output.push_str("    call $map_hash\n");
+        self.output.push_str("    local.get $mask\n");
+        self.output.push_str("    i32.and\n");
+        self.output.push_str("    local.set $index\n");
+        self.output.push_str("    (loop $probe\n");
+        self.output.push_str("      local.get $map\n");
+        self.output.push_str("      i32.load offset=8\n");
+        self.output.push_str("      local.get $index\n");
+        self.output.push_str("      i32.const 12\n");
+        self.output.push_str("      i32.mul\n");
+        self.output.push_str("      i32.add\n");
+        self.output.push_str("      local.set $entry\n");
+        self.output.push_str("      local.get $entry\n");
+        self.output.push_str("      i32.load\n");
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      i32.ne\n");
+        self.output.push_str("      (if\n");
+        self.output.push_str("        (then\n");
+        self.output.push_str("          local.get $entry\n");
+        self.output.push_str("          return\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      local.get $index\n");
+        self.output.push_str("      i32.const 1\n");
+        self.output.push_str("      i32.add\n");
+        self.output.push_str("      local.get $mask\n");
+        self.output.push_str("      i32.and\n");
+        self.output.push_str("      local.set $index\n");
+        self.output.push_str("      br $probe\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    unreachable\n");
+        self.output.push_str("  )\n");
+        self.output.push_str(
+            "  ;; Rebuild the table without tombstones, doubling it once it is half full\n",
         );
-
         self.output
-            .push_str("  (func $tail_f64 (param $list i32) (result i32)\n");
-        self.output.push_str("    (local $length i32)\n");
-        self.output.push_str("    (local $new_list i32)\n");
-        self.output.push_str("    (local $new_length i32)\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Load original length\n");
-        self.output.push_str("    local.get $list\n");
+            .push_str("  (func $map_rehash (param $map i32) (param $string_keys i32)\n");
+        self.output.push_str("    (local $old_entries i32)\n");
+        self.output.push_str("    (local $old_capacity i32)\n");
+        self.output.push_str("    (local $capacity i32)\n");
+        self.output.push_str("    (local $i i32)\n");
+        self.output.push_str("    (local $entry i32)\n");
+        self.output.push_str("    (local $slot i32)\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.load offset=8\n");
+        self.output.push_str("    local.set $old_entries\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.load offset=4\n");
+        self.output.push_str("    local.tee $old_capacity\n");
+        self.output.push_str("    local.set $capacity\n");
+        self.output.push_str("    local.get $map\n");
         self.output.push_str("    i32.load\n");
-        self.output.push_str("    local.set $length\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Check if list is empty\n");
-        self.output.push_str("    local.get $length\n");
-        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    i32.const 1\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    i32.const 2\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    local.get $capacity\n");
+        self.output.push_str("    i32.gt_u\n");
         self.output.push_str("    (if\n");
         self.output.push_str("      (then\n");
-        self.output
-            .push_str("        ;; Return the same empty list\n");
-        self.output.push_str("        local.get $list\n");
+        self.output.push_str("        local.get $capacity\n");
+        self.output.push_str("        i32.const 2\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        local.set $capacity\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    local.get $capacity\n");
+        self.output.push_str("    i32.store offset=4\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    local.get $capacity\n");
+        self.output.push_str("    call $map_entries_alloc\n");
+        self.output.push_str("    i32.store offset=8\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    i32.store offset=12\n");
+        self.output.push_str("    (block $done\n");
+        self.output.push_str("      (loop $move\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        local.get $old_capacity\n");
+        self.output.push_str("        i32.ge_u\n");
+        self.output.push_str("        br_if $done\n");
+        self.output.push_str("        local.get $old_entries\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 12\n");
+        self.output.push_str("        i32.mul\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $entry\n");
+        self.output.push_str("        local.get $entry\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.eq\n");
+        self.output.push_str("        (if\n");
+        self.output.push_str("          (then\n");
+        self.output.push_str("            local.get $map\n");
+        self.output.push_str("            local.get $entry\n");
+        self.output.push_str("            i32.load offset=4\n");
+        self.output.push_str("            local.get $string_keys\n");
+        self.output.push_str("            call $map_free_slot\n");
+        self.output.push_str("            local.set $slot\n");
+        self.output.push_str("            local.get $slot\n");
+        self.output.push_str("            i32.const 1\n");
+        self.output.push_str("            i32.store\n");
+        self.output.push_str("            local.get $slot\n");
+        self.output.push_str("            local.get $entry\n");
+        self.output.push_str("            i32.load offset=4\n");
+        self.output.push_str("            i32.store offset=4\n");
+        self.output.push_str("            local.get $slot\n");
+        self.output.push_str("            local.get $entry\n");
+        self.output.push_str("            i32.load offset=8\n");
+        self.output.push_str("            i32.store offset=8\n");
+        self.output.push_str("          )\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("        local.get $i\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        local.set $i\n");
+        self.output.push_str("        br $move\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("  )\n");
+        self.output.push_str("  (func $map_insert_impl (param $map i32) (param $key i32) (param $value i32) (param $string_keys i32) (result i32)\n");
+        self.output.push_str("    (local $entry i32)\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    local.get $key\n");
+        self.output.push_str("    local.get $string_keys\n");
+        self.output.push_str("    call $map_find\n");
+        self.output.push_str("    local.tee $entry\n");
+        self.output.push_str("    i32.const -1\n");
+        self.output.push_str("    i32.ne\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $entry\n");
+        self.output.push_str("        local.get $value\n");
+        self.output.push_str("        i32.store offset=8\n");
+        self.output.push_str("        local.get $map\n");
         self.output.push_str("        return\n");
         self.output.push_str("      )\n");
         self.output.push_str("    )\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Calculate new length\n");
-        self.output.push_str("    local.get $length\n");
-        self.output.push_str("    i32.const 1\n");
-        self.output.push_str("    i32.sub\n");
-        self.output.push_str("    local.set $new_length\n");
-        self.output.push_str("    \n");
         self.output
-            .push_str("    ;; Allocate new list: 8 bytes header + (new_length * 8) bytes data\n");
-        self.output.push_str("    local.get $new_length\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.mul\n");
-        self.output.push_str("    i32.const 8\n");
+            .push_str("    ;; Keep a quarter of the slots empty so every probe terminates\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.load offset=12\n");
+        self.output.push_str("    i32.const 1\n");
         self.output.push_str("    i32.add\n");
-        self.output.push_str("    call $allocate\n");
-        self.output.push_str("    local.set $new_list\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Write new length\n");
-        self.output.push_str("    local.get $new_list\n");
-        self.output.push_str("    local.get $new_length\n");
-        self.output.push_str("    i32.store\n");
-        self.output.push_str("    \n");
-        self.output
-            .push_str("    ;; Write new capacity (same as length)\n");
-        self.output.push_str("    local.get $new_list\n");
         self.output.push_str("    i32.const 4\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    local.get $new_length\n");
-        self.output.push_str("    i32.store\n");
-        self.output.push_str("    \n");
-        self.output
-            .push_str("    ;; Copy elements from original Float64 list (skip first element)\n");
-        self.output.push_str("    local.get $new_list\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    ;; destination\n");
-        self.output.push_str("    local.get $list\n");
-        self.output.push_str("    i32.const 16\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    ;; source\n");
-        self.output.push_str("    local.get $new_length\n");
-        self.output.push_str("    i32.const 8\n");
         self.output.push_str("    i32.mul\n");
-        self.output.push_str("    ;; size\n");
-        self.output.push_str("    memory.copy\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    local.get $new_list\n");
-        self.output.push_str("  )\n");
-
-        self.functions.insert(
-            "tail_f64".to_string(),
-            FunctionSig {
-                _params: vec![WasmType::I32],
-                result: Some(WasmType::I32),
-            },
-        );
-
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.load offset=4\n");
+        self.output.push_str("    i32.const 3\n");
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    i32.gt_u\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $map\n");
+        self.output.push_str("        local.get $string_keys\n");
+        self.output.push_str("        call $map_rehash\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    local.get $key\n");
+        self.output.push_str("    local.get $string_keys\n");
+        self.output.push_str("    call $map_free_slot\n");
+        self.output.push_str("    local.set $entry\n");
         self.output
-            .push_str("  (func $tail_i64 (param $list i32) (result i32)\n");
-        self.output.push_str("    (local $length i32)\n");
-        self.output.push_str("    (local $new_list i32)\n");
-        self.output.push_str("    (local $new_length i32)\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Load original length\n");
-        self.output.push_str("    local.get $list\n");
+            .push_str("    ;; Reusing a tombstone leaves the occupied count unchanged\n");
+        self.output.push_str("    local.get $entry\n");
         self.output.push_str("    i32.load\n");
-        self.output.push_str("    local.set $length\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Check if list is empty\n");
-        self.output.push_str("    local.get $length\n");
         self.output.push_str("    i32.eqz\n");
         self.output.push_str("    (if\n");
         self.output.push_str("      (then\n");
-        self.output
-            .push_str("        ;; Return the same empty list\n");
-        self.output.push_str("        local.get $list\n");
-        self.output.push_str("        return\n");
+        self.output.push_str("        local.get $map\n");
+        self.output.push_str("        local.get $map\n");
+        self.output.push_str("        i32.load offset=12\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.add\n");
+        self.output.push_str("        i32.store offset=12\n");
         self.output.push_str("      )\n");
         self.output.push_str("    )\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Calculate new length\n");
-        self.output.push_str("    local.get $length\n");
+        self.output.push_str("    local.get $entry\n");
         self.output.push_str("    i32.const 1\n");
-        self.output.push_str("    i32.sub\n");
-        self.output.push_str("    local.set $new_length\n");
-        self.output.push_str("    \n");
-        self.output
-            .push_str("    ;; Allocate new list: 8 bytes header + (new_length * 8) bytes data\n");
-        self.output.push_str("    local.get $new_length\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.mul\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    call $allocate\n");
-        self.output.push_str("    local.set $new_list\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    ;; Write new length\n");
-        self.output.push_str("    local.get $new_list\n");
-        self.output.push_str("    local.get $new_length\n");
         self.output.push_str("    i32.store\n");
-        self.output.push_str("    \n");
-        self.output
-            .push_str("    ;; Write new capacity (same as length)\n");
-        self.output.push_str("    local.get $new_list\n");
-        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    local.get $entry\n");
+        self.output.push_str("    local.get $key\n");
+        self.output.push_str("    i32.store offset=4\n");
+        self.output.push_str("    local.get $entry\n");
+        self.output.push_str("    local.get $value\n");
+        self.output.push_str("    i32.store offset=8\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    i32.const 1\n");
         self.output.push_str("    i32.add\n");
-        self.output.push_str("    local.get $new_length\n");
         self.output.push_str("    i32.store\n");
-        self.output.push_str("    \n");
-        self.output
-            .push_str("    ;; Copy elements from original Int64 list (skip first element)\n");
-        self.output.push_str("    local.get $new_list\n");
-        self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    ;; destination\n");
-        self.output.push_str("    local.get $list\n");
-        self.output.push_str("    i32.const 16\n");
-        self.output.push_str("    i32.add\n");
-        self.output.push_str("    ;; source\n");
-        self.output.push_str("    local.get $new_length\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("  )\n");
+        self.output.push_str("  (func $map_get_impl (param $map i32) (param $key i32) (param $string_keys i32) (result i32)\n");
+        self.output.push_str("    (local $entry i32)\n");
+        self.output.push_str("    (local $option i32)\n");
         self.output.push_str("    i32.const 8\n");
-        self.output.push_str("    i32.mul\n");
-        self.output.push_str("    ;; size\n");
-        self.output.push_str("    memory.copy\n");
-        self.output.push_str("    \n");
-        self.output.push_str("    local.get $new_list\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $option\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    local.get $key\n");
+        self.output.push_str("    local.get $string_keys\n");
+        self.output.push_str("    call $map_find\n");
+        self.output.push_str("    local.tee $entry\n");
+        self.output.push_str("    i32.const -1\n");
+        self.output.push_str("    i32.eq\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str("        i32.const 0\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("      (else\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("        local.get $option\n");
+        self.output.push_str("        local.get $entry\n");
+        self.output.push_str("        i32.load offset=8\n");
+        self.output.push_str("        i32.store offset=4\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $option\n");
+        self.output.push_str("  )\n");
+        self.output.push_str("  (func $map_remove_impl (param $map i32) (param $key i32) (param $string_keys i32) (result i32)\n");
+        self.output.push_str("    (local $entry i32)\n");
+        self.output.push_str("    local.get $map\n");
+        self.output.push_str("    local.get $key\n");
+        self.output.push_str("    local.get $string_keys\n");
+        self.output.push_str("    call $map_find\n");
+        self.output.push_str("    local.tee $entry\n");
+        self.output.push_str("    i32.const -1\n");
+        self.output.push_str("    i32.ne\n");
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output
+            .push_str("        ;; A tombstone keeps the rest of the probe chain reachable\n");
+        self.output.push_str("        local.get $entry\n");
+        self.output.push_str("        i32.const 2\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("        local.get $map\n");
+        self.output.push_str("        local.get $map\n");
+        self.output.push_str("        i32.load\n");
+        self.output.push_str("        i32.const 1\n");
+        self.output.push_str("        i32.sub\n");
+        self.output.push_str("        i32.store\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    local.get $map\n");
         self.output.push_str("  )\n");
 
+        for (suffix, string_keys) in [("", 0), ("_string", 1)] {
+            self.output.push_str(&format!(
+                "  (func $map_insert{suffix} (param $map i32) (param $key i32) (param $value i32) (result i32)\n"
+            ));
+            self.output.push_str("    local.get $map\n");
+            self.output.push_str("    local.get $key\n");
+            self.output.push_str("    local.get $value\n");
+            self.output
+                .push_str(&format!("    i32.const {string_keys}\n"));
+            self.output.push_str("    call $map_insert_impl\n");
+            self.output.push_str("  )\n");
+            for operation in ["get", "remove"] {
+                self.output.push_str(&format!(
+                    "  (func $map_{operation}{suffix} (param $map i32) (param $key i32) (result i32)\n"
+                ));
+                self.output.push_str("    local.get $map\n");
+                self.output.push_str("    local.get $key\n");
+                self.output
+                    .push_str(&format!("    i32.const {string_keys}\n"));
+                self.output
+                    .push_str(&format!("    call $map_{operation}_impl\n"));
+                self.output.push_str("  )\n");
+            }
+            for (operation, arity) in [("insert", 3), ("get", 2), ("remove", 2)] {
+                self.functions.insert(
+                    format!("map_{operation}{suffix}"),
+                    FunctionSig {
+                        _params: vec![WasmType::I32; arity],
+                        result: Some(WasmType::I32),
+                    },
+                );
+            }
+        }
         self.functions.insert(
-            "tail_i64".to_string(),
+            "map_new".to_string(),
             FunctionSig {
-                _params: vec![WasmType::I32],
+                _params: vec![],
                 result: Some(WasmType::I32),
             },
         );
 
+        let key = || Type::Named("K".to_string());
+        let value = || Type::Named("V".to_string());
+        let map = || Type::Generic("Map".to_string(), vec![key(), value()]);
+        for (name, params, result) in [
+            ("map_new", vec![], map()),
+            ("map_insert", vec![map(), key(), value()], map()),
+            (
+                "map_get",
+                vec![map(), key()],
+                Type::Generic("Option".to_string(), vec![value()]),
+            ),
+            ("map_remove", vec![map(), key()], map()),
+        ] {
+            self.function_source_sigs.insert(
+                name.to_string(),
+                FunctionSourceSig {
+                    type_params: vec!["K".to_string(), "V".to_string()],
+                    params,
+                    result: Some(result),
+                },
+            );
+        }
+
         Ok(())
     }
 
@@ -3878,6 +8735,10 @@ impl WasmCodeGen {
                 }
             }
         }
+        // Interned last so program string offsets do not depend on it.
+        self.intern_string_literal(ARENA_OOM_MESSAGE);
+        self.intern_string_literal(DECIMAL_OVERFLOW_MESSAGE);
+        self.intern_string_literal(UNWRAP_NONE_MESSAGE);
         Ok(())
     }
 
@@ -3902,9 +8763,10 @@ impl WasmCodeGen {
                 Stmt::Assignment(assign) => {
                     self.collect_strings_from_expr(&assign.value)?;
                 }
-                Stmt::Expr(expr) => {
+                Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
                     self.collect_strings_from_expr(expr)?;
                 }
+                Stmt::Break(None) | Stmt::Continue => {}
             }
         }
 
@@ -3924,12 +8786,19 @@ impl WasmCodeGen {
                 self.collect_strings_from_block(block)?;
             }
             ExprKind::Call(call) => {
+                if Self::expr_is_ident(&call.function, "args") {
+                    self.uses_program_args = true;
+                }
                 self.collect_strings_from_expr(&call.function)?;
                 for arg in &call.args {
                     self.collect_strings_from_expr(arg)?;
                 }
             }
             ExprKind::Binary(binary) => {
+                if matches!(binary.op, BinaryOp::Div | BinaryOp::Mod) {
+                    // Runtime zero-divisor guards panic with this message.
+                    self.intern_string_literal(DIVISION_BY_ZERO_MESSAGE);
+                }
                 self.collect_strings_from_expr(&binary.left)?;
                 self.collect_strings_from_expr(&binary.right)?;
             }
@@ -3960,6 +8829,10 @@ impl WasmCodeGen {
             ExprKind::FieldAccess(expr, _) => {
                 self.collect_strings_from_expr(expr)?;
             }
+            ExprKind::FieldUpdate(update) => {
+                self.collect_strings_from_expr(&update.object)?;
+                self.collect_strings_from_expr(&update.value)?;
+            }
             ExprKind::ListLit(items) => {
                 for item in items {
                     self.collect_strings_from_expr(item)?;
@@ -3978,6 +8851,9 @@ impl WasmCodeGen {
                 self.collect_strings_from_expr(&match_expr.expr)?;
                 for arm in &match_expr.arms {
                     self.collect_strings_from_pattern(&arm.pattern)?;
+                    if let Some(guard) = &arm.guard {
+                        self.collect_strings_from_expr(guard)?;
+                    }
                     self.collect_strings_from_block(&arm.body)?;
                 }
             }
@@ -3996,6 +8872,14 @@ impl WasmCodeGen {
                 self.collect_strings_from_expr(&while_expr.condition)?;
                 self.collect_strings_from_block(&while_expr.body)?;
             }
+            ExprKind::WhileLet(while_let) => {
+                self.collect_strings_from_expr(&while_let.expr)?;
+                self.collect_strings_from_pattern(&while_let.pattern)?;
+                self.collect_strings_from_block(&while_let.body)?;
+            }
+            ExprKind::Loop(body) => {
+                self.collect_strings_from_block(body)?;
+            }
             ExprKind::With(with) => {
                 for binding in &with.bindings {
                     match binding {
@@ -4012,8 +8896,8 @@ impl WasmCodeGen {
             ExprKind::WithLifetime(with_lifetime) => {
                 self.collect_strings_from_block(&with_lifetime.body)?;
             }
-            ExprKind::Await(_) | ExprKind::Spawn(_) => {
-                // No strings in await/spawn expressions themselves
+            ExprKind::Await(expr) | ExprKind::Spawn(expr) => {
+                self.collect_strings_from_expr(expr)?;
             }
             ExprKind::Clone(clone) => {
                 self.collect_strings_from_expr(&clone.base)?;
@@ -4031,7 +8915,10 @@ impl WasmCodeGen {
             ExprKind::Freeze(expr) => {
                 self.collect_strings_from_expr(expr)?;
             }
-            ExprKind::Some(expr) | ExprKind::Ok(expr) | ExprKind::Err(expr) => {
+            ExprKind::Some(expr)
+            | ExprKind::Ok(expr)
+            | ExprKind::Err(expr)
+            | ExprKind::Try(expr) => {
                 self.collect_strings_from_expr(expr)?;
             }
             ExprKind::Lambda(lambda) => {
@@ -4057,7 +8944,10 @@ impl WasmCodeGen {
                     self.collect_strings_from_pattern(pattern)?;
                 }
             }
-            Pattern::Some(inner) | Pattern::Ok(inner) | Pattern::Err(inner) => {
+            Pattern::Some(inner)
+            | Pattern::Ok(inner)
+            | Pattern::Err(inner)
+            | Pattern::Binding(_, inner) => {
                 self.collect_strings_from_pattern(inner)?;
             }
             Pattern::ListCons(head, tail) => {
@@ -4072,6 +8962,7 @@ impl WasmCodeGen {
             Pattern::Wildcard
             | Pattern::Ident(_)
             | Pattern::Literal(_)
+            | Pattern::Range { .. }
             | Pattern::None
             | Pattern::EmptyList => {}
         }
@@ -4153,14 +9044,8 @@ impl WasmCodeGen {
             }
         }
 
-        if let Some(expr) = &block.expr {
-            return self.infer_expr_source_type_for_signature(expr);
-        }
-
-        match block.statements.last() {
-            Some(Stmt::Expr(expr)) => self.infer_expr_source_type_for_signature(expr),
-            _ => None,
-        }
+        let expr = block.expr.as_deref()?;
+        self.infer_expr_source_type_for_signature(expr)
     }
 
     fn infer_expr_source_type_for_signature(&mut self, expr: &Expr) -> Option<Type> {
@@ -4242,7 +9127,14 @@ impl WasmCodeGen {
                     self.set_local_source_type(name, ty.clone());
                 }
             }
+            Pattern::Binding(name, inner) => {
+                if let Some(ty) = value_ty {
+                    self.set_local_source_type(name, ty.clone());
+                }
+                self.bind_pattern_source_types_for_signature(inner, value_ty);
+            }
             Pattern::Record(record_name, fields) => {
+                let record_name = Self::pattern_record_name(record_name, value_ty);
                 self.bind_record_pattern_source_types_for_signature(record_name, fields, None);
             }
             Pattern::RecordDestruct {
@@ -4251,7 +9143,7 @@ impl WasmCodeGen {
                 rest,
             } => {
                 self.bind_record_pattern_source_types_for_signature(
-                    type_name,
+                    Self::pattern_record_name(type_name, value_ty),
                     fields,
                     rest.as_ref(),
                 );
@@ -4285,7 +9177,11 @@ impl WasmCodeGen {
                     self.bind_pattern_source_types_for_signature(pattern, element_ty.as_ref());
                 }
             }
-            Pattern::Wildcard | Pattern::Literal(_) | Pattern::None | Pattern::EmptyList => {}
+            Pattern::Wildcard
+            | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::None
+            | Pattern::EmptyList => {}
         }
     }
 
@@ -4324,9 +9220,34 @@ impl WasmCodeGen {
         let mut method = func.clone();
         method.name = Self::method_function_name(target, &func.name);
 
+        // A generic record's type parameters are in scope for every method, so
+        // the lowered function is generic over them ahead of its own.
+        let record_params = self
+            .record_type_params
+            .get(target)
+            .cloned()
+            .unwrap_or_default();
+        let self_ty = if record_params.is_empty() {
+            Type::Named(target.to_string())
+        } else {
+            Type::Generic(
+                target.to_string(),
+                record_params.iter().cloned().map(Type::Named).collect(),
+            )
+        };
+        method.type_params.splice(
+            0..0,
+            record_params.into_iter().map(|name| TypeParam {
+                name,
+                bounds: Vec::new(),
+                derivation_bound: None,
+                is_temporal: false,
+            }),
+        );
+
         if let Some(first_param) = method.params.first_mut() {
             if first_param.name == "self" {
-                first_param.ty = Type::Named(target.to_string());
+                first_param.ty = self_ty;
             }
         }
 
@@ -4402,7 +9323,7 @@ impl WasmCodeGen {
             let field_size = match &field.ty {
                 Type::Named(name) => match name.as_str() {
                     "Int32" | "Boolean" | "Char" => 4,
-                    "Int64" | "Float64" => 8,
+                    "Int64" | "Float64" | "Decimal" => 8,
                     _ => 4, // Pointers are 4 bytes
                 },
                 _ => 4, // Default to pointer size
@@ -4431,7 +9352,7 @@ impl WasmCodeGen {
         match ty {
             Type::Named(name) => match name.as_str() {
                 "Int32" | "Boolean" | "Char" => 4,
-                "Int64" | "Float64" => 8,
+                "Int64" | "Float64" | "Decimal" => 8,
                 _ => 4,
             },
             _ => 4,
@@ -4537,6 +9458,9 @@ impl WasmCodeGen {
             (ExprKind::FloatLit(value), Type::Named(name)) if name == "Float64" => {
                 Ok(format!("f64.const {}", value))
             }
+            (ExprKind::DecimalLit(value), Type::Named(name)) if name == "Decimal" => {
+                Ok(format!("i64.const {}", value))
+            }
             (ExprKind::BoolLit(value), Type::Named(name)) if name == "Boolean" => {
                 Ok(format!("i32.const {}", if *value { 1 } else { 0 }))
             }
@@ -4577,6 +9501,15 @@ impl WasmCodeGen {
                     ))
                 }
             }
+            (ExprKind::Unary(unary), Type::Named(name)) if name == "Decimal" => {
+                if let (UnaryOp::Neg, ExprKind::DecimalLit(value)) = (&unary.op, &unary.expr.kind) {
+                    Ok(format!("i64.const {}", -value))
+                } else {
+                    Err(CodeGenError::UnsupportedFeature(
+                        "Top-level Decimal constants must be literals".to_string(),
+                    ))
+                }
+            }
             _ => Err(CodeGenError::UnsupportedFeature(format!(
                 "Top-level binding of type {:?} requires runtime initialization and is not supported by codegen yet",
                 source_ty
@@ -4602,7 +9535,7 @@ impl WasmCodeGen {
         match ty {
             Type::Named(name) => match name.as_str() {
                 "Int32" | "Boolean" | "Char" | "Unit" => Ok(WasmType::I32),
-                "Int64" => Ok(WasmType::I64),
+                "Int64" | "Decimal" => Ok(WasmType::I64),
                 "Float64" => Ok(WasmType::F64),
                 "String" => Ok(WasmType::I32), // String is a pointer
                 _ if self.records.contains_key(name) => Ok(WasmType::I32),
@@ -4616,7 +9549,7 @@ impl WasmCodeGen {
                 ))),
             },
             Type::Generic(name, _params) => match name.as_str() {
-                "List" | "Option" | "Result" | "Array" | "Range" => Ok(WasmType::I32), // All are pointers
+                "List" | "Map" | "Option" | "Result" | "Array" | "Range" => Ok(WasmType::I32), // All are pointers
                 _ if self.records.contains_key(name) => Ok(WasmType::I32),
                 _ => Err(CodeGenError::UnsupportedType(format!(
                     "generic source type '{}' has no Wasm ABI{}",
@@ -4632,6 +9565,8 @@ impl WasmCodeGen {
                 // Temporal types are treated like their base type
                 self.convert_type(&Type::Named(name.clone()))
             }
+            // Range restrictions are checked statically; values are plain integers
+            Type::Refined(base, _) => self.convert_type(base),
         }
     }
 
@@ -4671,7 +9606,7 @@ impl WasmCodeGen {
 
     fn type_size(&self, ty: &Type) -> usize {
         match ty {
-            Type::Named(name) if name == "Int64" || name == "Float64" => 8,
+            Type::Named(name) if matches!(name.as_str(), "Int64" | "Float64" | "Decimal") => 8,
             _ => 4,
         }
     }
@@ -4809,7 +9744,7 @@ impl WasmCodeGen {
 
     fn wasm_load_op_for_type(&self, ty: Option<&Type>) -> &'static str {
         match ty {
-            Some(Type::Named(name)) if name == "Int64" => "i64.load",
+            Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => "i64.load",
             Some(Type::Named(name)) if name == "Float64" => "f64.load",
             _ => "i32.load",
         }
@@ -4817,7 +9752,7 @@ impl WasmCodeGen {
 
     fn wasm_store_op_for_type(&self, ty: Option<&Type>) -> &'static str {
         match ty {
-            Some(Type::Named(name)) if name == "Int64" => "i64.store",
+            Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => "i64.store",
             Some(Type::Named(name)) if name == "Float64" => "f64.store",
             _ => "i32.store",
         }
@@ -4885,6 +9820,7 @@ impl WasmCodeGen {
                 Box::new(Self::apply_record_type_args(return_type, bindings)),
             ),
             Type::Temporal(name, temporals) => Type::Temporal(name.clone(), temporals.clone()),
+            Type::Refined(base, _) => Self::apply_record_type_args(base, bindings),
         }
     }
 
@@ -5127,9 +10063,11 @@ impl WasmCodeGen {
                     args: vec![Box::new(Expr::new(ExprKind::Ident(
                         func.params[0].name.clone(),
                     )))],
+                    type_args: Vec::new(),
                 })))),
             },
             is_async: false,
+            is_init: false,
         };
 
         // Generate println_Int32 specialization
@@ -5150,9 +10088,11 @@ impl WasmCodeGen {
                     args: vec![Box::new(Expr::new(ExprKind::Ident(
                         func.params[0].name.clone(),
                     )))],
+                    type_args: Vec::new(),
                 })))),
             },
             is_async: false,
+            is_init: false,
         };
 
         // Generate the specialized functions
@@ -5162,6 +10102,61 @@ impl WasmCodeGen {
         Ok(())
     }
 
+    /// Emit `$__restrict_init`, which runs every `@init` function in
+    /// declaration order inside its own arena, and hook it up to the
+    /// `(start ...)` section or `_initialize` export.
+    fn generate_init_wrapper(&mut self, program: &Program) -> Result<(), CodeGenError> {
+        self.init_functions = program
+            .declarations
+            .iter()
+            .filter_map(|decl| match Self::decl_codegen_item(decl) {
+                TopDecl::Function(func) if func.is_init => Some(func.name.clone()),
+                _ => None,
+            })
+            .collect();
+        if self.init_functions.is_empty() {
+            return Ok(());
+        }
+        let init_arena = self.next_arena_addr;
+        self.next_arena_addr += self.arena_size();
+
+        self.output.push_str("\n  ;; Module initialization\n");
+        self.output.push_str("  (func $__restrict_init\n");
+        self.output.push_str("    (local $init_prev_arena i32)\n");
+        self.output.push_str("    global.get $current_arena\n");
+        self.output.push_str("    local.set $init_prev_arena\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", init_arena));
+        self.output.push_str("    call $arena_init\n");
+        self.output.push_str("    global.set $current_arena\n");
+        for name in self.init_functions.clone() {
+            let returns_value = self
+                .functions
+                .get(&name)
+                .ok_or_else(|| CodeGenError::UndefinedFunction(name.clone()))?
+                .result
+                .is_some();
+            self.output.push_str(&format!("    call ${}\n", name));
+            if returns_value {
+                self.output.push_str("    drop\n");
+            }
+        }
+        self.output
+            .push_str(&format!("    i32.const {}\n", init_arena));
+        self.output.push_str("    call $arena_reset\n");
+        self.output.push_str("    local.get $init_prev_arena\n");
+        self.output.push_str("    global.set $current_arena\n");
+        self.output.push_str("  )\n");
+
+        if self.start_section {
+            self.output.push_str("  (start $__restrict_init)\n");
+        } else if !self.should_generate_start_wrapper() {
+            self.output
+                .push_str("  (export \"_initialize\" (func $__restrict_init))\n");
+        }
+        Ok(())
+    }
+
     fn generate_start_wrapper(&mut self) -> Result<(), CodeGenError> {
         let main_sig = self
             .functions
@@ -5172,7 +10167,7 @@ impl WasmCodeGen {
         }
         let main_returns_value = main_sig.result.is_some();
         let start_arena = self.next_arena_addr;
-        self.next_arena_addr += ARENA_SIZE_BYTES;
+        self.next_arena_addr += self.arena_size();
 
         self.output.push_str("\n  ;; Program entry wrapper\n");
         self.output.push_str("  (func $__restrict_start\n");
@@ -5185,6 +10180,9 @@ impl WasmCodeGen {
             .push_str(&format!("    i32.const {}\n", start_arena));
         self.output.push_str("    call $arena_init\n");
         self.output.push_str("    global.set $current_arena\n\n");
+        if !self.init_functions.is_empty() && !self.start_section {
+            self.output.push_str("    call $__restrict_init\n");
+        }
         self.output.push_str("    call $main\n");
         if main_returns_value {
             self.output.push_str("    drop\n");
@@ -5349,6 +10347,15 @@ impl WasmCodeGen {
         self.set_local_type("f64_mod_right", WasmType::F64);
         next_idx += 1;
 
+        self.output.push_str("    (local $div_rhs_i32 i32)\n");
+        self.add_local("div_rhs_i32", next_idx);
+        next_idx += 1;
+
+        self.output.push_str("    (local $div_rhs_i64 i64)\n");
+        self.add_local("div_rhs_i64", next_idx);
+        self.set_local_type("div_rhs_i64", WasmType::I64);
+        next_idx += 1;
+
         self.output.push_str("    (local $tail_len i32)\n");
         self.add_local("tail_len", next_idx);
         next_idx += 1;
@@ -5454,7 +10461,7 @@ impl WasmCodeGen {
         // functions inherit their caller's arena unless they enter `with Arena`.
         let function_default_arena = if is_host_entry {
             let arena_addr = self.next_arena_addr;
-            self.next_arena_addr += ARENA_SIZE_BYTES;
+            self.next_arena_addr += self.arena_size();
             self.default_arena = Some(arena_addr);
             Some(arena_addr)
         } else {
@@ -5471,6 +10478,22 @@ impl WasmCodeGen {
             self.output.push_str("    global.set $current_arena\n\n");
         }
 
+        // Self calls in tail position reuse this frame. Host entries always
+        // take the loop, since `return_call` would skip their arena reset.
+        let tail_recursion = self.tail_recursion_for(func, is_host_entry);
+        let tail_loop = tail_recursion
+            .as_ref()
+            .is_some_and(|tail| !tail.return_call);
+        let outer_tail_recursion = std::mem::replace(&mut self.tail_recursion, tail_recursion);
+        if tail_loop {
+            self.output.push_str("    (loop $tail_recur");
+            if let Some(result_type) = self.functions.get(&func.name).and_then(|sig| sig.result) {
+                self.output
+                    .push_str(&format!(" (result {})", self.wasm_type_str(result_type)));
+            }
+            self.output.push('\n');
+        }
+
         // Generate function body
         if let Some(return_type) = body_expected_source.as_ref() {
             self.generate_block_with_expected_source(&func.body, return_type)?;
@@ -5491,15 +10514,15 @@ impl WasmCodeGen {
                 }
             }
         }
+        if tail_loop {
+            self.output.push_str("    )\n");
+        }
+        self.tail_recursion = outer_tail_recursion;
 
         // Reset entry-point default arena before returning to the host.
         if let Some(default_arena) = function_default_arena {
-            self.output.push_str("\n    ;; Reset default arena\n");
-            self.output
-                .push_str(&format!("    i32.const {}\n", default_arena));
-            self.output.push_str("    call $arena_reset\n");
-            self.output.push_str("    local.get $entry_prev_arena\n");
-            self.output.push_str("    global.set $current_arena\n");
+            self.output.push('\n');
+            self.generate_entry_arena_reset(default_arena);
         }
 
         self.output.push_str("  )\n");
@@ -5511,6 +10534,107 @@ impl WasmCodeGen {
         Ok(())
     }
 
+    /// Find the self calls in tail position of `func`: its trailing
+    /// expression, looking through blocks and `then`/`match` branches.
+    fn tail_recursion_for(&self, func: &FunDecl, is_host_entry: bool) -> Option<TailRecursion> {
+        let mut calls = HashSet::new();
+        if let Some(expr) = &func.body.expr {
+            Self::collect_tail_self_calls(func, expr, &mut calls);
+        }
+        if calls.is_empty() {
+            return None;
+        }
+        Some(TailRecursion {
+            calls,
+            params: func
+                .params
+                .iter()
+                .map(|param| (param.name.clone(), param.ty.clone()))
+                .collect(),
+            return_call: self.tail_calls && !is_host_entry,
+        })
+    }
+
+    fn collect_tail_self_calls(func: &FunDecl, expr: &Expr, calls: &mut HashSet<usize>) {
+        let is_self = |name: &str, arity: usize| name == func.name && arity == func.params.len();
+        let collect_block = |block: &BlockExpr, calls: &mut HashSet<usize>| {
+            if let Some(expr) = &block.expr {
+                Self::collect_tail_self_calls(func, expr, calls);
+            }
+        };
+        match &expr.kind {
+            ExprKind::Call(call) if matches!(&call.function.kind, ExprKind::Ident(name) if is_self(name, call.args.len())) =>
+            {
+                calls.insert(expr as *const Expr as usize);
+            }
+            ExprKind::Pipe(pipe)
+                if pipe.op == PipeOp::Pipe
+                    && matches!(&pipe.target, PipeTarget::Ident(name) if is_self(name, 1)) =>
+            {
+                calls.insert(expr as *const Expr as usize);
+            }
+            ExprKind::Block(block) => collect_block(block, calls),
+            ExprKind::Then(then) => {
+                collect_block(&then.then_block, calls);
+                for (_, block) in &then.else_ifs {
+                    collect_block(block, calls);
+                }
+                if let Some(block) = &then.else_block {
+                    collect_block(block, calls);
+                }
+            }
+            ExprKind::Match(match_expr) => {
+                for arm in &match_expr.arms {
+                    collect_block(&arm.body, calls);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Generate `expr` as a tail self call if it is one, returning whether
+    /// it was.
+    fn generate_tail_self_call(&mut self, expr: &Expr) -> Result<bool, CodeGenError> {
+        let Some(tail) = self
+            .tail_recursion
+            .as_ref()
+            .filter(|tail| tail.calls.contains(&(expr as *const Expr as usize)))
+        else {
+            return Ok(false);
+        };
+        let params = tail.params.clone();
+        let return_call = tail.return_call;
+        let args: Vec<&Expr> = match &expr.kind {
+            ExprKind::Call(call) => call.args.iter().map(|arg| arg.as_ref()).collect(),
+            ExprKind::Pipe(pipe) => vec![pipe.expr.as_ref()],
+            _ => return Ok(false),
+        };
+
+        for (arg, (_, source_ty)) in args.iter().zip(&params) {
+            self.generate_expr_with_expected_source(arg, source_ty)?;
+        }
+        if return_call {
+            let name = self.current_function.clone().unwrap_or_default();
+            self.output
+                .push_str(&format!("    return_call ${}\n", name));
+        } else {
+            for (name, _) in params.iter().rev() {
+                self.output.push_str(&format!("    local.set ${}\n", name));
+            }
+            self.output.push_str("    br $tail_recur\n");
+        }
+        Ok(true)
+    }
+
+    fn generate_entry_arena_reset(&mut self, default_arena: u32) {
+        self.output.push_str("    ;; Reset default arena\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", default_arena));
+        self.output.push_str("    call $arena_reset\n");
+        self.output.push_str("    local.get $entry_prev_arena\n");
+        self.output.push_str("    global.set $current_arena\n");
+    }
+
     fn generate_block(&mut self, block: &BlockExpr) -> Result<(), CodeGenError> {
         self.generate_block_internal(block, false, None)
     }
@@ -5559,7 +10683,6 @@ impl WasmCodeGen {
     ) -> Result<(), CodeGenError> {
         // Generate statements
         for (i, stmt) in block.statements.iter().enumerate() {
-            let is_last_stmt = i == block.statements.len() - 1;
             match stmt {
                 Stmt::Binding(bind) => self.generate_binding_with_later_array_context(
                     bind,
@@ -5569,27 +10692,27 @@ impl WasmCodeGen {
                 )?,
                 Stmt::Assignment(assign) => self.generate_assignment(assign)?,
                 Stmt::Expr(expr) => {
-                    if block.expr.is_none() && is_last_stmt {
-                        if let Some(expected_source) = expected_source {
-                            self.generate_expr_with_expected_source(expr, expected_source)?;
-                        } else {
-                            self.generate_expr(expr)?;
-                        }
-                    } else {
-                        self.generate_expr(expr)?;
-                    }
-                    // Pop the result if it's not the last expression and the expression leaves a value
-                    let should_drop = if as_expression {
-                        // In expression context, only drop if not the final value
-                        !is_last_stmt || block.expr.is_some()
-                    } else {
-                        // In statement context, drop unless it's the final expression
-                        (block.expr.is_some() || !is_last_stmt) && self.expr_leaves_value(expr)
-                    };
-                    if should_drop && self.expr_leaves_value(expr) {
+                    // Only `block.expr` is the block value; statement results are unused.
+                    self.generate_expr(expr)?;
+                    if self.expr_leaves_value(expr) {
                         self.output.push_str("    drop\n");
                     }
                 }
+                Stmt::Break(value) => {
+                    let (break_label, _, arena_scopes) = self.innermost_loop_labels("break")?;
+                    if let Some(value) = value {
+                        self.generate_expr(value)?;
+                    }
+                    self.exit_with_arena_scopes_above(arena_scopes);
+                    self.output.push_str(&format!("    br {}\n", break_label));
+                }
+                Stmt::Continue => {
+                    let (_, continue_label, arena_scopes) =
+                        self.innermost_loop_labels("continue")?;
+                    self.exit_with_arena_scopes_above(arena_scopes);
+                    self.output
+                        .push_str(&format!("    br {}\n", continue_label));
+                }
             }
         }
 
@@ -5601,13 +10724,7 @@ impl WasmCodeGen {
                 self.generate_expr(expr)?;
             }
         } else if as_expression {
-            let final_stmt_leaves_value = matches!(
-                block.statements.last(),
-                Some(Stmt::Expr(expr)) if self.expr_leaves_value(expr)
-            );
-            if !final_stmt_leaves_value {
-                self.output.push_str("    i32.const 0\n");
-            }
+            self.output.push_str("    i32.const 0\n");
         } else if block.statements.is_empty() && !as_expression {
             // Empty block returns 0 (Unit) only in statement context
             self.output.push_str("    i32.const 0\n");
@@ -5750,9 +10867,10 @@ impl WasmCodeGen {
                     found
                 }
             }
-            Stmt::Expr(expr) => {
+            Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
                 self.find_iteration_item_context_for_ident_in_expr(name, container_name, expr)
             }
+            Stmt::Break(None) | Stmt::Continue => None,
             Stmt::Assignment(assign) => self.find_iteration_item_context_for_ident_in_expr(
                 name,
                 container_name,
@@ -5866,6 +10984,7 @@ impl WasmCodeGen {
             ExprKind::Some(inner)
             | ExprKind::Ok(inner)
             | ExprKind::Err(inner)
+            | ExprKind::Try(inner)
             | ExprKind::Freeze(inner) => {
                 self.find_iteration_item_context_for_ident_in_expr(name, container_name, inner)
             }
@@ -6079,6 +11198,16 @@ impl WasmCodeGen {
             ExprKind::While(while_expr) => self
                 .infer_ident_source_type_from_expr_usage(name, &while_expr.condition)
                 .or_else(|| self.infer_ident_source_type_from_block_usage(name, &while_expr.body)),
+            ExprKind::Loop(body) => self.infer_ident_source_type_from_block_usage(name, body),
+            ExprKind::WhileLet(while_let) => self
+                .infer_ident_source_type_from_expr_usage(name, &while_let.expr)
+                .or_else(|| {
+                    if Self::pattern_binds_name(&while_let.pattern, name) {
+                        None
+                    } else {
+                        self.infer_ident_source_type_from_block_usage(name, &while_let.body)
+                    }
+                }),
             ExprKind::Match(match_expr) => self
                 .infer_ident_source_type_from_expr_usage(name, &match_expr.expr)
                 .or_else(|| {
@@ -6123,10 +11252,14 @@ impl WasmCodeGen {
             ExprKind::FieldAccess(object, _) => {
                 self.infer_ident_source_type_from_expr_usage(name, object)
             }
+            ExprKind::FieldUpdate(update) => self
+                .infer_ident_source_type_from_expr_usage(name, &update.object)
+                .or_else(|| self.infer_ident_source_type_from_expr_usage(name, &update.value)),
             ExprKind::Freeze(inner)
             | ExprKind::Some(inner)
             | ExprKind::Ok(inner)
             | ExprKind::Err(inner)
+            | ExprKind::Try(inner)
             | ExprKind::Await(inner)
             | ExprKind::Spawn(inner) => self.infer_ident_source_type_from_expr_usage(name, inner),
             ExprKind::ListLit(items) | ExprKind::ArrayLit(items) => items
@@ -6168,6 +11301,7 @@ impl WasmCodeGen {
             }
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -6201,11 +11335,12 @@ impl WasmCodeGen {
                         return Some(ty);
                     }
                 }
-                Stmt::Expr(expr) => {
+                Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
                     if let Some(ty) = self.infer_ident_source_type_from_expr_usage(name, expr) {
                         return Some(ty);
                     }
                 }
+                Stmt::Break(None) | Stmt::Continue => {}
             }
         }
 
@@ -6223,19 +11358,21 @@ impl WasmCodeGen {
 
         match op {
             BinaryOp::Add => match named {
-                "Int32" | "Int64" | "Float64" | "String" => Some(other.clone()),
+                "Int32" | "Int64" | "Float64" | "Decimal" | "String" => Some(other.clone()),
                 _ => None,
             },
             BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => match named {
-                "Int32" | "Int64" | "Float64" => Some(other.clone()),
+                "Int32" | "Int64" | "Float64" | "Decimal" => Some(other.clone()),
                 _ => None,
             },
             BinaryOp::Eq | BinaryOp::Ne => match named {
-                "Int32" | "Int64" | "Float64" | "Boolean" | "Char" => Some(other.clone()),
+                "Int32" | "Int64" | "Float64" | "Decimal" | "Boolean" | "Char" => {
+                    Some(other.clone())
+                }
                 _ => None,
             },
             BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => match named {
-                "Int32" | "Int64" | "Float64" => Some(other.clone()),
+                "Int32" | "Int64" | "Float64" | "Decimal" => Some(other.clone()),
                 _ => None,
             },
             BinaryOp::And | BinaryOp::Or => match named {
@@ -6321,17 +11458,15 @@ impl WasmCodeGen {
         substitution: &mut HashMap<String, Type>,
     ) {
         match &expr.kind {
-            ExprKind::FieldAccess(object, field) => {
-                if Self::expr_is_ident(object, binding_name) {
-                    if let Some(field_ty) = expected_source {
-                        self.bind_record_binding_field_context(
-                            record_name,
-                            field,
-                            field_ty,
-                            type_params,
-                            substitution,
-                        );
-                    }
+            ExprKind::FieldAccess(object, field) if Self::expr_is_ident(object, binding_name) => {
+                if let Some(field_ty) = expected_source {
+                    self.bind_record_binding_field_context(
+                        record_name,
+                        field,
+                        field_ty,
+                        type_params,
+                        substitution,
+                    );
                 }
             }
             ExprKind::Match(match_expr) => {
@@ -6480,15 +11615,6 @@ impl WasmCodeGen {
                 type_params,
                 substitution,
             );
-        } else if let Some(Stmt::Expr(expr)) = block.statements.last() {
-            self.bind_record_binding_type_params_from_expr(
-                binding_name,
-                record_name,
-                expr,
-                expected_source,
-                type_params,
-                substitution,
-            );
         }
     }
 
@@ -6665,16 +11791,8 @@ impl WasmCodeGen {
         block: &BlockExpr,
         expected_source: Option<&Type>,
     ) -> Option<Type> {
-        if let Some(expr) = block.expr.as_deref() {
-            return self.expected_source_for_ident_in_expr(name, expr, expected_source);
-        }
-
-        match block.statements.last() {
-            Some(Stmt::Expr(expr)) => {
-                self.expected_source_for_ident_in_expr(name, expr, expected_source)
-            }
-            _ => None,
-        }
+        let expr = block.expr.as_deref()?;
+        self.expected_source_for_ident_in_expr(name, expr, expected_source)
     }
 
     fn expected_source_for_ident_in_expr(
@@ -6876,6 +11994,8 @@ impl WasmCodeGen {
             | Pattern::ListCons(_, _)
             | Pattern::ListExact(_)
             | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::Binding(_, _)
             | Pattern::Wildcard => self.generate_pattern_binding(bind)?,
         }
 
@@ -7144,7 +12264,7 @@ impl WasmCodeGen {
     ) -> Result<(), CodeGenError> {
         // Create a new arena for this temporal scope
         let arena_addr = self.next_arena_addr;
-        self.next_arena_addr += 0x1000; // Reserve 4KB for each arena
+        self.next_arena_addr += self.arena_size();
 
         // Push arena onto stack and track temporal scope
         self.arena_stack.push(arena_addr);
@@ -7262,7 +12382,60 @@ impl WasmCodeGen {
         Ok(())
     }
 
+    /// Push the address of `obj_expr.field` and return the field's source
+    /// type, if known.
+    fn generate_field_address(
+        &mut self,
+        obj_expr: &Expr,
+        field: &str,
+    ) -> Result<Option<Type>, CodeGenError> {
+        // Generate object expression
+        self.generate_expr(obj_expr)?;
+
+        // Get the type of the object expression
+        let object_source_ty = self.infer_expr_source_type(obj_expr);
+        let record_name = if let Some(source_ty) = &object_source_ty {
+            self.source_record_name(source_ty)
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    CodeGenError::NotImplemented(format!("field access for {}", field))
+                })?
+        } else if let ExprKind::Ident(var_name) = &obj_expr.kind {
+            // For identifiers, look up the record type from variable tracking.
+            self.var_types.get(var_name).cloned().ok_or_else(|| {
+                CodeGenError::NotImplemented(format!(
+                    "field access on unknown variable: {}",
+                    var_name
+                ))
+            })?
+        } else if let ExprKind::RecordLit(record_lit) = &obj_expr.kind {
+            // Direct record literal
+            record_lit.name.clone()
+        } else {
+            return Err(CodeGenError::NotImplemented(format!(
+                "field access for {}",
+                field
+            )));
+        };
+
+        // Look up the field offset
+        let field_offset =
+            self.instantiated_record_field_offset(&record_name, object_source_ty.as_ref(), field)?;
+
+        self.output
+            .push_str(&format!("    i32.const {}\n", field_offset));
+        self.output.push_str("    i32.add\n");
+        let field_type = object_source_ty
+            .as_ref()
+            .and_then(|source_ty| self.instantiated_record_field_type(source_ty, field))
+            .or_else(|| self.record_field_type(&record_name, field).cloned());
+        Ok(field_type)
+    }
+
     fn generate_expr(&mut self, expr: &Expr) -> Result<(), CodeGenError> {
+        if self.generate_tail_self_call(expr)? {
+            return Ok(());
+        }
         match &expr.kind {
             ExprKind::IntLit(n) => {
                 let wasm_ty = Self::int_literal_wasm_type(*n);
@@ -7275,6 +12448,9 @@ impl WasmCodeGen {
             ExprKind::FloatLit(f) => {
                 self.output.push_str(&format!("    f64.const {}\n", f));
             }
+            ExprKind::DecimalLit(n) => {
+                self.output.push_str(&format!("    i64.const {}\n", n));
+            }
             ExprKind::BoolLit(b) => {
                 self.output
                     .push_str(&format!("    i32.const {}\n", if *b { 1 } else { 0 }));
@@ -7329,54 +12505,27 @@ impl WasmCodeGen {
                 self.generate_record_literal_with_source_type(record_lit, &record_source_ty)?;
             }
             ExprKind::FieldAccess(obj_expr, field) => {
-                // Generate object expression
-                self.generate_expr(obj_expr)?;
-
-                // Get the type of the object expression
-                let object_source_ty = self.infer_expr_source_type(obj_expr);
-                let record_name = if let Some(source_ty) = &object_source_ty {
-                    self.source_record_name(source_ty)
-                        .map(str::to_string)
-                        .ok_or_else(|| {
-                            CodeGenError::NotImplemented(format!("field access for {}", field))
-                        })?
-                } else if let ExprKind::Ident(var_name) = &obj_expr.kind {
-                    // For identifiers, look up the record type from variable tracking.
-                    self.var_types.get(var_name).cloned().ok_or_else(|| {
-                        CodeGenError::NotImplemented(format!(
-                            "field access on unknown variable: {}",
-                            var_name
-                        ))
-                    })?
-                } else if let ExprKind::RecordLit(record_lit) = &obj_expr.kind {
-                    // Direct record literal
-                    record_lit.name.clone()
-                } else {
-                    return Err(CodeGenError::NotImplemented(format!(
-                        "field access for {}",
-                        field
-                    )));
-                };
-
-                // Look up the field offset
-                let field_offset = self.instantiated_record_field_offset(
-                    &record_name,
-                    object_source_ty.as_ref(),
-                    field,
-                )?;
-
-                self.output
-                    .push_str(&format!("    i32.const {}\n", field_offset));
-                self.output.push_str("    i32.add\n");
-                let field_type = object_source_ty
-                    .as_ref()
-                    .and_then(|source_ty| self.instantiated_record_field_type(source_ty, field))
-                    .or_else(|| self.record_field_type(&record_name, field).cloned());
+                let field_type = self.generate_field_address(obj_expr, field)?;
                 self.output.push_str(&format!(
                     "    {}\n",
                     self.wasm_load_op_for_type(field_type.as_ref())
                 ));
             }
+            ExprKind::FieldUpdate(update) => {
+                let field_type = self.generate_field_address(&update.object, &update.field)?;
+                match &field_type {
+                    Some(field_type) => {
+                        self.generate_expr_with_expected_source(&update.value, field_type)?
+                    }
+                    None => self.generate_expr(&update.value)?,
+                }
+                self.output.push_str(&format!(
+                    "    {}\n",
+                    self.wasm_store_op_for_type(field_type.as_ref())
+                ));
+                // The update evaluates to `()`.
+                self.output.push_str("    i32.const 0\n");
+            }
             ExprKind::StringLit(s) => {
                 if let Some(offset) = self.string_offsets.get(s) {
                     self.output.push_str(&format!("    i32.const {}\n", offset));
@@ -7411,6 +12560,12 @@ impl WasmCodeGen {
             ExprKind::While(while_expr) => {
                 self.generate_while_expr(while_expr)?;
             }
+            ExprKind::WhileLet(while_let) => {
+                self.generate_while_let_expr(while_let)?;
+            }
+            ExprKind::Loop(body) => {
+                self.generate_loop_expr(body)?;
+            }
             ExprKind::With(with_expr) => {
                 self.generate_with_expr(with_expr)?;
             }
@@ -7452,6 +12607,9 @@ impl WasmCodeGen {
             ExprKind::Err(inner) => {
                 self.generate_variant_constructor("Err", 0, inner)?;
             }
+            ExprKind::Try(operand) => {
+                self.generate_try_expr(expr, operand)?;
+            }
             ExprKind::Lambda(lambda) => {
                 self.generate_lambda_expr(lambda)?;
             }
@@ -7554,6 +12712,8 @@ impl WasmCodeGen {
         lambda_code.push_str("    (local $option_value_f64_tmp f64)\n");
         lambda_code.push_str("    (local $f64_mod_left f64)\n");
         lambda_code.push_str("    (local $f64_mod_right f64)\n");
+        lambda_code.push_str("    (local $div_rhs_i32 i32)\n");
+        lambda_code.push_str("    (local $div_rhs_i64 i64)\n");
         lambda_code.push_str("    (local $tail_len i32)\n");
         lambda_code.push_str("    (local $tail_tmp i32)\n");
         for name in [
@@ -7630,6 +12790,7 @@ impl WasmCodeGen {
         let old_record_literal_depth = self.record_literal_depth;
         let old_record_tmp_count = self.record_tmp_count;
         let old_with_arena_depth = self.with_arena_depth;
+        let old_with_arena_scopes = std::mem::take(&mut self.with_arena_scopes);
         self.record_literal_depth = 0;
         self.record_tmp_count = lambda_record_tmp_count;
         self.with_arena_depth = 0;
@@ -7673,6 +12834,7 @@ impl WasmCodeGen {
         self.record_literal_depth = old_record_literal_depth;
         self.record_tmp_count = old_record_tmp_count;
         self.with_arena_depth = old_with_arena_depth;
+        self.with_arena_scopes = old_with_arena_scopes;
 
         body_result?;
 
@@ -7824,6 +12986,10 @@ impl WasmCodeGen {
             ExprKind::FieldAccess(object, _) => {
                 self.collect_free_variables_for_codegen(object, bound, seen, free_vars)?;
             }
+            ExprKind::FieldUpdate(update) => {
+                self.collect_free_variables_for_codegen(&update.object, bound, seen, free_vars)?;
+                self.collect_free_variables_for_codegen(&update.value, bound, seen, free_vars)?;
+            }
             ExprKind::RecordLit(record) => {
                 for field in &record.fields {
                     match field {
@@ -7849,7 +13015,10 @@ impl WasmCodeGen {
                     }
                 }
             }
-            ExprKind::Freeze(value) | ExprKind::Await(value) | ExprKind::Spawn(value) => {
+            ExprKind::Freeze(value)
+            | ExprKind::Try(value)
+            | ExprKind::Await(value)
+            | ExprKind::Spawn(value) => {
                 self.collect_free_variables_for_codegen(value, bound, seen, free_vars)?;
             }
             ExprKind::PrototypeClone(proto) => {
@@ -7898,11 +13067,33 @@ impl WasmCodeGen {
                     free_vars,
                 )?;
             }
+            ExprKind::Loop(body) => {
+                self.collect_free_variables_in_block_for_codegen(body, bound, seen, free_vars)?;
+            }
+            ExprKind::WhileLet(while_let) => {
+                self.collect_free_variables_for_codegen(&while_let.expr, bound, seen, free_vars)?;
+                let mut body_bound = bound.clone();
+                self.collect_pattern_bindings_for_codegen(&while_let.pattern, &mut body_bound);
+                self.collect_free_variables_in_block_for_codegen(
+                    &while_let.body,
+                    &mut body_bound,
+                    seen,
+                    free_vars,
+                )?;
+            }
             ExprKind::Match(match_expr) => {
                 self.collect_free_variables_for_codegen(&match_expr.expr, bound, seen, free_vars)?;
                 for arm in &match_expr.arms {
                     let mut arm_bound = bound.clone();
                     self.collect_pattern_bindings_for_codegen(&arm.pattern, &mut arm_bound);
+                    if let Some(guard) = &arm.guard {
+                        self.collect_free_variables_for_codegen(
+                            guard,
+                            &mut arm_bound,
+                            seen,
+                            free_vars,
+                        )?;
+                    }
                     self.collect_free_variables_in_block_for_codegen(
                         &arm.body,
                         &mut arm_bound,
@@ -7971,6 +13162,7 @@ impl WasmCodeGen {
             }
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -8056,7 +13248,7 @@ impl WasmCodeGen {
                         free_vars,
                     )?;
                 }
-                Stmt::Expr(expr) => {
+                Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
                     self.collect_free_variables_for_codegen(
                         expr,
                         &mut block_bound,
@@ -8064,6 +13256,7 @@ impl WasmCodeGen {
                         free_vars,
                     )?;
                 }
+                Stmt::Break(None) | Stmt::Continue => {}
             }
         }
 
@@ -8079,6 +13272,10 @@ impl WasmCodeGen {
             Pattern::Ident(name) => {
                 bound.insert(name.clone());
             }
+            Pattern::Binding(name, inner) => {
+                bound.insert(name.clone());
+                self.collect_pattern_bindings_for_codegen(inner, bound);
+            }
             Pattern::Record(_, fields) => {
                 for (_, pattern) in fields {
                     self.collect_pattern_bindings_for_codegen(pattern, bound);
@@ -8108,7 +13305,11 @@ impl WasmCodeGen {
                     self.collect_pattern_bindings_for_codegen(pattern, bound);
                 }
             }
-            Pattern::Wildcard | Pattern::Literal(_) | Pattern::None | Pattern::EmptyList => {}
+            Pattern::Wildcard
+            | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::None
+            | Pattern::EmptyList => {}
         }
     }
 
@@ -8162,7 +13363,16 @@ impl WasmCodeGen {
             return Ok(());
         }
 
+        if let Some(record_name) = self.compared_record_name(binary) {
+            return self.generate_record_comparison(binary, &record_name);
+        }
+
+        if let Some(function) = self.decimal_rescale_function(binary) {
+            Self::reject_constant_zero_divisor(binary, WasmType::I64)?;
+            return self.generate_decimal_rescale(binary, function);
+        }
         let operand_type = self.infer_binary_operand_type(binary)?;
+        Self::reject_constant_zero_divisor(binary, operand_type)?;
 
         if operand_type == WasmType::F64 && binary.op == BinaryOp::Mod {
             self.generate_f64_mod_expr(binary)?;
@@ -8220,20 +13430,150 @@ impl WasmCodeGen {
             (_, BinaryOp::Or) => "i32.or",
         };
 
+        if matches!(binary.op, BinaryOp::Div | BinaryOp::Mod) {
+            self.emit_integer_divisor_guard(operand_type)?;
+        }
         self.output.push_str(&format!("    {}\n", op));
 
         Ok(())
     }
 
+    fn compared_record_name(&self, binary: &BinaryExpr) -> Option<String> {
+        if matches!(
+            binary.op,
+            BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge
+        ) {
+            match self.infer_expr_source_type(&binary.left) {
+                Some(Type::Named(name)) if self.records.contains_key(&name) => Some(name),
+                Some(Type::Generic(name, _)) if self.records.contains_key(&name) => Some(name),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// `==`/`!=` call the record's `eq` method, or its structural helper
+    /// when it has none. Orderings call `cmp` and test the sign.
+    fn generate_record_comparison(
+        &mut self,
+        binary: &BinaryExpr,
+        record_name: &str,
+    ) -> Result<(), CodeGenError> {
+        self.generate_expr(&binary.left)?;
+        self.generate_expr(&binary.right)?;
+        let sign_test = match &binary.op {
+            BinaryOp::Eq | BinaryOp::Ne => {
+                let callee = self.record_eq_function(record_name);
+                self.output.push_str(&format!("    call ${}\n", callee));
+                if binary.op == BinaryOp::Ne {
+                    self.output.push_str("    i32.eqz\n");
+                }
+                return Ok(());
+            }
+            BinaryOp::Lt => "i32.lt_s",
+            BinaryOp::Le => "i32.le_s",
+            BinaryOp::Gt => "i32.gt_s",
+            _ => "i32.ge_s",
+        };
+        self.output.push_str(&format!(
+            "    call ${}\n",
+            Self::method_function_name(record_name, "cmp")
+        ));
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str(&format!("    {}\n", sign_test));
+        Ok(())
+    }
+
+    /// The function implementing `==` for `record_name`, queueing its
+    /// structural helper if the record has no `eq` method.
+    fn record_eq_function(&mut self, record_name: &str) -> String {
+        if self
+            .methods
+            .get(record_name)
+            .is_some_and(|methods| methods.contains_key("eq"))
+        {
+            return Self::method_function_name(record_name, "eq");
+        }
+        if !self
+            .record_eq_requests
+            .iter()
+            .any(|name| name == record_name)
+        {
+            self.record_eq_requests.push(record_name.to_string());
+        }
+        format!("record_eq_{}", record_name)
+    }
+
+    fn generate_record_eq_functions(&mut self) -> Result<(), CodeGenError> {
+        let mut generated = 0;
+        while generated < self.record_eq_requests.len() {
+            let name = self.record_eq_requests[generated].clone();
+            generated += 1;
+            let fields = self.records[&name].clone();
+            let offsets = self.record_field_offsets[&name].clone();
+            self.output.push_str(&format!(
+                "  (func $record_eq_{} (param $left i32) (param $right i32) (result i32)\n",
+                name
+            ));
+            self.output.push_str("    (block $differ\n");
+            for (field, ty) in &fields {
+                let (load, compare) = match ty {
+                    Type::Named(ty_name) => match ty_name.as_str() {
+                        "Int64" | "Decimal" => ("i64.load", "i64.eq".to_string()),
+                        "Float64" => ("f64.load", "f64.eq".to_string()),
+                        "String" => ("i32.load", "call $string_eq".to_string()),
+                        "Int32" | "Boolean" | "Char" | "Unit" => ("i32.load", "i32.eq".to_string()),
+                        record if self.records.contains_key(record) => (
+                            "i32.load",
+                            format!("call ${}", self.record_eq_function(record)),
+                        ),
+                        other => {
+                            return Err(CodeGenError::UnsupportedType(format!(
+                                "structural equality for field '{}: {}' of record '{}'",
+                                field, other, name
+                            )))
+                        }
+                    },
+                    other => {
+                        return Err(CodeGenError::UnsupportedType(format!(
+                            "structural equality for field '{}: {}' of record '{}'",
+                            field, other, name
+                        )))
+                    }
+                };
+                for side in ["left", "right"] {
+                    self.output
+                        .push_str(&format!("      local.get ${}\n", side));
+                    self.output
+                        .push_str(&format!("      {} offset={}\n", load, offsets[field]));
+                }
+                self.output.push_str(&format!("      {}\n", compare));
+                self.output.push_str("      i32.eqz\n");
+                self.output.push_str("      br_if $differ\n");
+            }
+            self.output.push_str("      i32.const 1\n");
+            self.output.push_str("      return\n");
+            self.output.push_str("    )\n");
+            self.output.push_str("    i32.const 0\n");
+            self.output.push_str("  )\n");
+        }
+        Ok(())
+    }
+
     fn generate_binary_expr_with_operand_type(
         &mut self,
         binary: &BinaryExpr,
         operand_type: WasmType,
     ) -> Result<(), CodeGenError> {
+        Self::reject_constant_zero_divisor(binary, operand_type)?;
         if operand_type == WasmType::F64 && binary.op == BinaryOp::Mod {
             self.generate_f64_mod_expr(binary)?;
             return Ok(());
         }
+        if let Some(function) = self.decimal_rescale_function(binary) {
+            return self.generate_decimal_rescale(binary, function);
+        }
 
         self.generate_expr_with_wasm_type(&binary.left, operand_type)?;
         self.generate_expr_with_wasm_type(&binary.right, operand_type)?;
@@ -8262,10 +13602,94 @@ impl WasmCodeGen {
             }
         };
 
+        if matches!(binary.op, BinaryOp::Div | BinaryOp::Mod) {
+            self.emit_integer_divisor_guard(operand_type)?;
+        }
         self.output.push_str(&format!("    {}\n", op));
         Ok(())
     }
 
+    /// `Decimal` `*` and `/` go through a helper that rescales the i64
+    /// result, rounding half to even like `decimal_div_half_even`.
+    fn decimal_rescale_function(&self, binary: &BinaryExpr) -> Option<&'static str> {
+        let function = match binary.op {
+            BinaryOp::Mul => "decimal_mul",
+            BinaryOp::Div => "decimal_div",
+            _ => return None,
+        };
+        self.is_decimal_binary(binary).then_some(function)
+    }
+
+    fn is_decimal_binary(&self, binary: &BinaryExpr) -> bool {
+        [&binary.left, &binary.right].into_iter().any(|operand| {
+            matches!(
+                self.infer_expr_source_type(operand),
+                Some(Type::Named(name)) if name == "Decimal"
+            )
+        })
+    }
+
+    fn generate_decimal_rescale(
+        &mut self,
+        binary: &BinaryExpr,
+        function: &str,
+    ) -> Result<(), CodeGenError> {
+        self.generate_expr_with_wasm_type(&binary.left, WasmType::I64)?;
+        self.generate_expr_with_wasm_type(&binary.right, WasmType::I64)?;
+        if binary.op == BinaryOp::Div {
+            self.emit_integer_divisor_guard(WasmType::I64)?;
+        }
+        self.output
+            .push_str("    i32.const 1 ;; round half to even\n");
+        self.output.push_str(&format!("    call ${}\n", function));
+        Ok(())
+    }
+
+    /// Reject integer and `Decimal` division by a literal zero, which would
+    /// always panic. Float division by zero is left to IEEE 754, which
+    /// yields an infinity or NaN; the checker still warns about it.
+    fn reject_constant_zero_divisor(
+        binary: &BinaryExpr,
+        operand_type: WasmType,
+    ) -> Result<(), CodeGenError> {
+        if matches!(operand_type, WasmType::I32 | WasmType::I64)
+            && matches!(binary.op, BinaryOp::Div | BinaryOp::Mod)
+            && binary.right.is_constant_zero()
+        {
+            return Err(CodeGenError::DivisionByZero(binary.op.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Guard the integer divisor on top of the stack so a zero divisor
+    /// panics with a message instead of an opaque `div_s`/`rem_s` trap.
+    /// Float division follows IEEE 754 and needs no guard.
+    fn emit_integer_divisor_guard(&mut self, operand_type: WasmType) -> Result<(), CodeGenError> {
+        let (local, ty) = match operand_type {
+            WasmType::I32 => ("div_rhs_i32", "i32"),
+            WasmType::I64 => ("div_rhs_i64", "i64"),
+            WasmType::F32 | WasmType::F64 => return Ok(()),
+        };
+        let Some(&message_offset) = self.string_offsets.get(DIVISION_BY_ZERO_MESSAGE) else {
+            return Err(CodeGenError::UnsupportedFeature(
+                "integer division outside string collection scope".to_string(),
+            ));
+        };
+        self.output
+            .push_str("    ;; runtime guard: integer division by zero panics\n");
+        self.output.push_str(&format!("    local.tee ${}\n", local));
+        self.output.push_str(&format!("    {}.eqz\n", ty));
+        self.output.push_str("    (if\n");
+        self.output.push_str("      (then\n");
+        self.output
+            .push_str(&format!("        i32.const {}\n", message_offset));
+        self.output.push_str("        call $panic\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+        self.output.push_str(&format!("    local.get ${}\n", local));
+        Ok(())
+    }
+
     fn generate_expr_with_wasm_type(
         &mut self,
         expr: &Expr,
@@ -8359,8 +13783,26 @@ impl WasmCodeGen {
         let source_ty = self.infer_expr_type(&cast.expr)?;
         let target_ty = self.convert_type(&cast.target)?;
 
+        // `123L` parses to `123 as Int64`; emit the constant at full width.
+        if let (ExprKind::IntLit(value), WasmType::I64) = (&cast.expr.kind, target_ty) {
+            self.output.push_str(&format!("    i64.const {}\n", value));
+            return Ok(());
+        }
+
         self.generate_expr_with_wasm_type(&cast.expr, source_ty)?;
 
+        // Chars share the i32 representation; `as Char` validates the code
+        // point like `int_to_char`.
+        let char_target = cast.target == Type::Named("Char".to_string());
+        let char_source = matches!(
+            self.infer_expr_source_type(&cast.expr),
+            Some(Type::Named(name)) if name == "Char"
+        );
+        if char_target && !char_source {
+            self.output.push_str("    call $int_to_char\n");
+            return Ok(());
+        }
+
         match (source_ty, target_ty) {
             (source_ty, target_ty) if source_ty == target_ty => {}
             (WasmType::I32, WasmType::I64) => self.output.push_str("    i64.extend_i32_s\n"),
@@ -8441,7 +13883,48 @@ impl WasmCodeGen {
         Ok(())
     }
 
+    /// `operand?`: tag 0 (`None`/`Err`) returns the operand itself, which
+    /// already has the enclosing function's layout; otherwise the payload at
+    /// offset 4 is left on the stack.
+    fn generate_try_expr(&mut self, expr: &Expr, operand: &Expr) -> Result<(), CodeGenError> {
+        if !self.arena_stack.is_empty() {
+            return Err(CodeGenError::UnsupportedFeature(
+                "`?` inside a `with` block".to_string(),
+            ));
+        }
+        let payload_ty = self.infer_expr_type(expr)?;
+
+        self.generate_expr(operand)?;
+        self.output.push_str("    ;; ? early return\n");
+        self.output.push_str("    local.set $match_tmp\n");
+        self.output.push_str("    local.get $match_tmp\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    i32.eqz\n");
+        self.output.push_str("    if\n");
+        let entry_arena = self
+            .current_function
+            .as_ref()
+            .filter(|name| self.exported_functions.contains(*name))
+            .and(self.default_arena);
+        if let Some(default_arena) = entry_arena {
+            self.generate_entry_arena_reset(default_arena);
+        }
+        self.output.push_str("      local.get $match_tmp\n");
+        self.output.push_str("      return\n");
+        self.output.push_str("    end\n");
+        self.output.push_str("    local.get $match_tmp\n");
+        self.output.push_str(&format!(
+            "    {} offset=4\n",
+            self.wasm_load_op_for_wasm_type(payload_ty)
+        ));
+        Ok(())
+    }
+
     fn generate_call_expr(&mut self, call: &CallExpr) -> Result<(), CodeGenError> {
+        if let Some(method_call) = self.receiver_method_call(call) {
+            return self.generate_call_expr(&method_call);
+        }
+
         if let ExprKind::Ident(func_name) = &call.function.kind {
             if let Some(target_name) = self.lookup_generic_function_alias(func_name) {
                 match target_name.as_str() {
@@ -8465,13 +13948,34 @@ impl WasmCodeGen {
                 "map" => return self.generate_map_call(call),
                 "filter" => return self.generate_filter_call(call),
                 "fold" => return self.generate_fold_call(call),
+                "list_sort" => return self.generate_list_sort_call(call),
+                "option_map" | "option_and_then" => {
+                    return self.generate_option_combinator_call(call, func_name);
+                }
+                "println" if call.args.len() == 1 => {
+                    return self.generate_println_value(&call.args[0]);
+                }
+                "drop" if call.args.len() == 1 => {
+                    return self.generate_drop_value(&call.args[0]);
+                }
+                "assert_eq" | "assert_ne"
+                    if call.args.len() == 3
+                        && self.comparison_asserts.contains(func_name.as_str()) =>
+                {
+                    return self.generate_assert_comparison(call, func_name == "assert_eq");
+                }
                 _ => {}
             }
         }
 
         if let ExprKind::Ident(func_name) = &call.function.kind {
-            if self.functions.contains_key(func_name) {
-                let target_name = self.resolve_named_function_call_target(func_name, &call.args)?;
+            if self.calls_named_function(func_name) {
+                let target_name = self.resolve_named_function_call_target_with_expected(
+                    func_name,
+                    &call.args,
+                    &call.type_args,
+                    None,
+                )?;
                 if let Some(source_params) =
                     self.concrete_source_params_for_call_target(&target_name, &call.args)
                 {
@@ -8521,12 +14025,22 @@ impl WasmCodeGen {
                         "identity expects exactly one argument".to_string(),
                     ));
                 }
-                self.generate_expr(&call.args[0])?;
+                match call.type_args.first() {
+                    Some(type_arg) => {
+                        self.generate_expr_with_expected_source(&call.args[0], type_arg)?
+                    }
+                    None => self.generate_expr(&call.args[0])?,
+                }
                 return Ok(());
             }
 
-            if self.functions.contains_key(func_name) {
-                let target_name = self.resolve_named_function_call_target(func_name, &call.args)?;
+            if self.calls_named_function(func_name) {
+                let target_name = self.resolve_named_function_call_target_with_expected(
+                    func_name,
+                    &call.args,
+                    &call.type_args,
+                    None,
+                )?;
                 self.generate_call_args_for_target(&call.args, &target_name)?;
                 self.output
                     .push_str(&format!("    call ${}\n", target_name));
@@ -8617,7 +14131,7 @@ impl WasmCodeGen {
             .is_some_and(|sig| !sig.type_params.is_empty())
             && self.function_decls.contains_key(&target_name)
         {
-            self.ensure_generic_function_call_specialization(&target_name, args, None)
+            self.ensure_generic_function_call_specialization(&target_name, args, &[], None)
         } else {
             Ok(target_name)
         }
@@ -8645,6 +14159,35 @@ impl WasmCodeGen {
             .filter(|type_name| self.records.contains_key(type_name))
     }
 
+    /// Rewrite a receiver method call `(args...) value.method` into the
+    /// mangled `Record_method` call with the receiver passed as `self`.
+    /// Function-typed fields keep their callable-field form.
+    fn receiver_method_call(&self, call: &CallExpr) -> Option<CallExpr> {
+        let ExprKind::FieldAccess(obj_expr, method_name) = &call.function.kind else {
+            return None;
+        };
+        let record_name = self.method_receiver_record_name(obj_expr)?;
+        if !self.methods.get(&record_name)?.contains_key(method_name)
+            || self
+                .records
+                .get(&record_name)
+                .is_some_and(|fields| fields.iter().any(|(field, _)| field == method_name))
+        {
+            return None;
+        }
+
+        let mut function = call.function.clone();
+        function.kind = ExprKind::Ident(Self::method_function_name(&record_name, method_name));
+        let mut args = Vec::with_capacity(call.args.len() + 1);
+        args.push(obj_expr.clone());
+        args.extend(call.args.iter().cloned());
+        Some(CallExpr {
+            function,
+            args,
+            type_args: call.type_args.clone(),
+        })
+    }
+
     fn resolve_method_call_target(
         &self,
         method_name: &str,
@@ -8784,7 +14327,18 @@ impl WasmCodeGen {
         expr: &Expr,
         expected_source: &Type,
     ) -> Result<(), CodeGenError> {
+        if self.generate_tail_self_call(expr)? {
+            return Ok(());
+        }
         if let ExprKind::Call(call) = &expr.kind {
+            if let Some(method_call) = self.receiver_method_call(call) {
+                let method_expr = Expr {
+                    id: expr.id,
+                    span: expr.span,
+                    kind: ExprKind::Call(method_call),
+                };
+                return self.generate_expr_with_expected_source(&method_expr, expected_source);
+            }
             if let ExprKind::Ident(func_name) = &call.function.kind {
                 if let Some(target_name) = self.lookup_generic_function_alias(func_name) {
                     match target_name.as_str() {
@@ -8801,8 +14355,17 @@ impl WasmCodeGen {
                             "identity expects exactly one argument".to_string(),
                         ));
                     }
+                    let expected_source = call.type_args.first().unwrap_or(expected_source);
                     return self.generate_expr_with_expected_source(&call.args[0], expected_source);
                 }
+
+                if matches!(func_name.as_str(), "option_map" | "option_and_then") {
+                    return self.generate_option_combinator_call(call, func_name);
+                }
+
+                if call.args.len() == 3 && self.comparison_asserts.contains(func_name.as_str()) {
+                    return self.generate_assert_comparison(call, func_name == "assert_eq");
+                }
             }
         }
 
@@ -8820,12 +14383,13 @@ impl WasmCodeGen {
 
         if let ExprKind::Call(call) = &expr.kind {
             if let ExprKind::Ident(func_name) = &call.function.kind {
-                if !matches!(func_name.as_str(), "map" | "filter" | "fold")
-                    && self.functions.contains_key(func_name)
+                if !Self::is_iteration_function_name(func_name)
+                    && self.calls_named_function(func_name)
                 {
                     let target_name = self.resolve_named_function_call_target_with_expected(
                         func_name,
                         &call.args,
+                        &call.type_args,
                         Some(expected_source),
                     )?;
                     let has_function_param = self
@@ -8901,7 +14465,7 @@ impl WasmCodeGen {
                 &call.function.kind,
                 ExprKind::Ident(func_name)
                     if matches!(func_name.as_str(), "map" | "filter" | "fold")
-                        || self.functions.contains_key(func_name)
+                        || self.calls_named_function(func_name)
             ) {
                 if let Ok(arg_source_tys) = call
                     .args
@@ -8934,11 +14498,10 @@ impl WasmCodeGen {
                 return self
                     .generate_with_expr_with_expected_source(with_expr, Some(expected_source));
             }
-            ExprKind::RecordLit(record_lit) => {
-                if self.source_record_name(expected_source) == Some(record_lit.name.as_str()) {
-                    return self
-                        .generate_record_literal_with_source_type(record_lit, expected_source);
-                }
+            ExprKind::RecordLit(record_lit)
+                if self.source_record_name(expected_source) == Some(record_lit.name.as_str()) =>
+            {
+                return self.generate_record_literal_with_source_type(record_lit, expected_source);
             }
             ExprKind::Some(inner) => {
                 if let Type::Generic(name, args) = expected_source {
@@ -9023,7 +14586,7 @@ impl WasmCodeGen {
             self.generate_block_internal(block, true, Some(expected_source))
         } else if let Type::Named(name) = expected_source {
             match name.as_str() {
-                "Int64" => self.generate_expr_with_wasm_type(expr, WasmType::I64),
+                "Int64" | "Decimal" => self.generate_expr_with_wasm_type(expr, WasmType::I64),
                 "Int32" => self.generate_expr_with_wasm_type(expr, WasmType::I32),
                 "Float64" => self.generate_expr_with_wasm_type(expr, WasmType::F64),
                 _ => self.generate_expr(expr),
@@ -9040,7 +14603,9 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "list_get_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "list_get_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "list_get_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "list_head" => match args
@@ -9048,7 +14613,9 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "list_head_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "list_head_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "list_head_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "list_tail" => match args
@@ -9056,7 +14623,9 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "list_tail_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "list_tail_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "list_tail_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "tail" => match args
@@ -9064,7 +14633,9 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "tail_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "tail_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "tail_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "list_reverse" => match args
@@ -9072,7 +14643,9 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "list_reverse_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "list_reverse_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "list_reverse_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "list_append" => match args
@@ -9080,7 +14653,9 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "list_append_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "list_append_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "list_append_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "list_prepend" => match args
@@ -9088,7 +14663,9 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "list_prepend_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "list_prepend_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "list_prepend_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "list_concat" => match args
@@ -9096,7 +14673,57 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "list_concat_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "list_concat_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "list_concat_i64".to_string()
+                }
+                _ => func_name.to_string(),
+            },
+            "hash" => match args
+                .first()
+                .and_then(|arg| self.infer_expr_source_type(arg))
+            {
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "hash_i64".to_string()
+                }
+                Some(Type::Named(name)) if name == "Float64" => "hash_f64".to_string(),
+                Some(Type::Named(name)) if name == "String" => "hash_string".to_string(),
+                Some(Type::Named(name)) if self.records.contains_key(&name) => {
+                    format!("hash_record_{}", name)
+                }
+                _ => func_name.to_string(),
+            },
+            "max" | "min" => match args
+                .first()
+                .and_then(|arg| self.infer_expr_source_type(arg))
+                .or_else(|| args.get(1).and_then(|arg| self.infer_expr_source_type(arg)))
+            {
+                Some(Type::Named(name)) if name == "Float64" => format!("{}_f", func_name),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    format!("{}_i64", func_name)
+                }
+                Some(Type::Named(name)) if name == "String" => format!("{}_string", func_name),
+                _ => func_name.to_string(),
+            },
+            "list_take" | "list_drop" | "list_slice" => match args
+                .first()
+                .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
+            {
+                Some(Type::Named(name)) if name == "Float64" => format!("{}_f64", func_name),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    format!("{}_i64", func_name)
+                }
+                _ => func_name.to_string(),
+            },
+            "list_contains" | "list_index_of" => match args
+                .first()
+                .and_then(|arg| self.indexed_collection_element_source_type(arg, "List"))
+                .or_else(|| args.get(1).and_then(|arg| self.infer_expr_source_type(arg)))
+            {
+                Some(Type::Named(name)) if name == "Float64" => format!("{}_f64", func_name),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    format!("{}_i64", func_name)
+                }
+                Some(Type::Named(name)) if name == "String" => format!("{}_string", func_name),
                 _ => func_name.to_string(),
             },
             "array_get" => match args
@@ -9104,7 +14731,9 @@ impl WasmCodeGen {
                 .and_then(|arg| self.indexed_collection_element_source_type(arg, "Array"))
             {
                 Some(Type::Named(name)) if name == "Float64" => "array_get_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "array_get_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "array_get_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "array_set" => match args
@@ -9113,7 +14742,34 @@ impl WasmCodeGen {
                 .or_else(|| args.get(2).and_then(|arg| self.infer_expr_source_type(arg)))
             {
                 Some(Type::Named(name)) if name == "Float64" => "array_set_f64".to_string(),
-                Some(Type::Named(name)) if name == "Int64" => "array_set_i64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "array_set_i64".to_string()
+                }
+                _ => func_name.to_string(),
+            },
+            "map_insert" | "map_get" | "map_remove" => match args
+                .first()
+                .and_then(|arg| self.indexed_collection_element_source_type(arg, "Map"))
+                .or_else(|| args.get(1).and_then(|arg| self.infer_expr_source_type(arg)))
+            {
+                Some(Type::Named(name)) if name == "String" => format!("{}_string", func_name),
+                _ => func_name.to_string(),
+            },
+            "option_unwrap" => match args
+                .first()
+                .and_then(|arg| self.option_payload_source_type(arg))
+            {
+                Some(Type::Named(name)) if name == "Float64" => "option_unwrap_f64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "option_unwrap_i64".to_string()
+                }
+                _ => func_name.to_string(),
+            },
+            "option_ok_or" => match args.get(1).and_then(|arg| self.infer_expr_source_type(arg)) {
+                Some(Type::Named(name)) if name == "Float64" => "option_ok_or_f64".to_string(),
+                Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => {
+                    "option_ok_or_i64".to_string()
+                }
                 _ => func_name.to_string(),
             },
             "option_unwrap_or" => {
@@ -9135,20 +14791,52 @@ impl WasmCodeGen {
         }
     }
 
+    /// Map entries hold 4-byte values; 8-byte values have no entry layout yet.
+    fn reject_wide_map_values(
+        &self,
+        func_name: &str,
+        args: &[Box<Expr>],
+    ) -> Result<(), CodeGenError> {
+        let value_ty = match args
+            .first()
+            .and_then(|arg| self.infer_expr_source_type(arg))
+        {
+            Some(Type::Generic(name, params)) if name == "Map" => params.get(1).cloned(),
+            _ => None,
+        }
+        .or_else(|| {
+            (func_name == "map_insert")
+                .then(|| args.get(2).and_then(|arg| self.infer_expr_source_type(arg)))
+                .flatten()
+        });
+        match value_ty {
+            Some(Type::Named(name)) if matches!(name.as_str(), "Float64" | "Int64" | "Decimal") => {
+                Err(CodeGenError::UnsupportedFeature(format!(
+                    "Map values of type {name} are not supported yet; maps currently hold 4-byte values"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn resolve_named_function_call_target(
         &mut self,
         func_name: &str,
         args: &[Box<Expr>],
     ) -> Result<String, CodeGenError> {
-        self.resolve_named_function_call_target_with_expected(func_name, args, None)
+        self.resolve_named_function_call_target_with_expected(func_name, args, &[], None)
     }
 
     fn resolve_named_function_call_target_with_expected(
         &mut self,
         func_name: &str,
         args: &[Box<Expr>],
+        type_args: &[Type],
         expected_source: Option<&Type>,
     ) -> Result<String, CodeGenError> {
+        if matches!(func_name, "map_insert" | "map_get" | "map_remove") {
+            self.reject_wide_map_values(func_name, args)?;
+        }
         let builtin_target = self.resolve_builtin_abi_function(func_name, args);
         if builtin_target != func_name {
             return Ok(builtin_target);
@@ -9163,6 +14851,7 @@ impl WasmCodeGen {
             return self.ensure_generic_function_call_specialization(
                 func_name,
                 args,
+                type_args,
                 expected_source,
             );
         }
@@ -9174,6 +14863,7 @@ impl WasmCodeGen {
         &mut self,
         function_name: &str,
         args: &[Box<Expr>],
+        type_args: &[Type],
         expected_source: Option<&Type>,
     ) -> Result<String, CodeGenError> {
         let source_sig = self
@@ -9191,7 +14881,9 @@ impl WasmCodeGen {
             )));
         }
 
-        let mut substitution = HashMap::new();
+        // Explicit type arguments are the specialization key; inferred
+        // bindings only fill the parameters they leave open.
+        let mut substitution = Self::explicit_type_arg_substitution(&source_sig, type_args);
         for (param_ty, arg) in source_sig.params.iter().zip(args.iter()) {
             if let Some(arg_ty) = self.infer_expr_source_type(arg) {
                 Self::bind_source_type_params(
@@ -9237,6 +14929,18 @@ impl WasmCodeGen {
         )
     }
 
+    fn explicit_type_arg_substitution(
+        source_sig: &FunctionSourceSig,
+        type_args: &[Type],
+    ) -> HashMap<String, Type> {
+        source_sig
+            .type_params
+            .iter()
+            .cloned()
+            .zip(type_args.iter().cloned())
+            .collect()
+    }
+
     fn infer_contextual_call_argument_source_type(
         &self,
         template: &Type,
@@ -9381,6 +15085,7 @@ impl WasmCodeGen {
         let specialized_func = FunDecl {
             name: specialized_name.clone(),
             is_async: func.is_async,
+            is_init: func.is_init,
             type_params: vec![],
             temporal_constraints: func.temporal_constraints.clone(),
             params: specialized_params,
@@ -9441,6 +15146,7 @@ impl WasmCodeGen {
                 );
                 parts.join("_")
             }
+            Type::Refined(base, _) => Self::source_type_suffix(base),
         }
     }
 
@@ -9492,6 +15198,9 @@ impl WasmCodeGen {
                     .unwrap_or_else(|| Type::Temporal(name.clone(), temporals.clone()))
             }
             Type::Temporal(name, temporals) => Type::Temporal(name.clone(), temporals.clone()),
+            Type::Refined(base, _) => {
+                Self::substitute_source_type_params_partial(base, type_params, substitution)
+            }
         }
     }
 
@@ -9625,7 +15334,7 @@ impl WasmCodeGen {
                 return self.source_function_abi(arg_source_tys, &result_source);
             }
 
-            if self.functions.contains_key(name) {
+            if self.calls_named_function(name) {
                 return if expected_result_source.is_some() {
                     self.named_function_abi_for_arg_sources_with_expected(
                         name,
@@ -9700,7 +15409,7 @@ impl WasmCodeGen {
                 );
             }
 
-            if self.functions.contains_key(name) {
+            if self.calls_named_function(name) {
                 return self.generate_named_function_reference_with_abi(name, abi);
             }
         }
@@ -9993,7 +15702,7 @@ impl WasmCodeGen {
                 );
             }
 
-            if self.functions.contains_key(name) {
+            if self.calls_named_function(name) {
                 let abi = LambdaAbiContext {
                     params,
                     result,
@@ -10049,11 +15758,13 @@ impl WasmCodeGen {
 
         match self.iteration_input_kind(call, "map")? {
             IterationInputKind::Option => self.generate_option_map_call(
-                call,
+                &call.args[0],
+                &call.args[1],
                 &item_source_ty,
                 item_ty,
                 result_source_ty,
                 result_ty,
+                true,
             ),
             IterationInputKind::List | IterationInputKind::Unknown => self.generate_list_map_call(
                 call,
@@ -10126,19 +15837,25 @@ impl WasmCodeGen {
         Ok(())
     }
 
+    /// Apply `mapper` to the payload of `option` when its tag is Some. With
+    /// `wrap_some` the result is wrapped in a fresh Some (`map`); otherwise
+    /// the mapper already returns an Option (`option_and_then`).
+    #[allow(clippy::too_many_arguments)]
     fn generate_option_map_call(
         &mut self,
-        call: &CallExpr,
+        option: &Expr,
+        mapper: &Expr,
         item_source_ty: &Type,
         item_ty: WasmType,
         result_source_ty: Type,
         result_ty: WasmType,
+        wrap_some: bool,
     ) -> Result<(), CodeGenError> {
         self.output.push_str("    ;; map(option, mapper)\n");
-        self.generate_expr(&call.args[0])?;
+        self.generate_expr(option)?;
         self.output.push_str("    local.set $match_tmp\n");
         self.generate_lambda_argument(
-            &call.args[1],
+            mapper,
             vec![item_ty],
             result_ty,
             vec![item_source_ty.clone()],
@@ -10160,14 +15877,75 @@ impl WasmCodeGen {
             self.wasm_load_op_for_wasm_type(item_ty)
         ));
         self.emit_iter_func_call(&[item_ty], result_ty, "        ");
-        self.generate_variant_from_stack("Some", 1, result_ty)?;
+        if wrap_some {
+            self.generate_variant_from_stack("Some", 1, result_ty)?;
+        }
         self.output.push_str("      )\n");
         self.output.push_str("      (else\n");
         self.generate_none_value_with_temp("option_value_tmp");
         self.output.push_str("      )\n");
         self.output.push_str("    )\n");
 
-        Ok(())
+        Ok(())
+    }
+
+    /// `option_map(mapper, option)` and `option_and_then(f, option)`: the
+    /// closure-first std combinators share the tag branch of `map` over an
+    /// Option.
+    fn generate_option_combinator_call(
+        &mut self,
+        call: &CallExpr,
+        function_name: &str,
+    ) -> Result<(), CodeGenError> {
+        if call.args.len() != 2 {
+            return Err(CodeGenError::UnsupportedFeature(format!(
+                "{} expects function and option arguments",
+                function_name
+            )));
+        }
+
+        let item_source_ty = self
+            .container_item_source_type(&call.args[1], "Option")
+            .ok_or_else(|| {
+                CodeGenError::UnsupportedFeature(format!(
+                    "{} code generation requires a known Option item type",
+                    function_name
+                ))
+            })?;
+        let item_ty = self.convert_type(&item_source_ty)?;
+        let result_source_ty = self
+            .infer_callable_return_source_type(&call.args[0], std::slice::from_ref(&item_source_ty))
+            .ok_or_else(|| {
+                CodeGenError::UnsupportedFeature(format!(
+                    "{} code generation requires an inferable function result type",
+                    function_name
+                ))
+            })?;
+        let wrap_some = function_name == "option_map";
+        if !wrap_some
+            && Self::container_item_from_source_type(&result_source_ty, "Option").is_none()
+        {
+            return Err(CodeGenError::UnsupportedFeature(format!(
+                "{} requires a function returning Option, found {}",
+                function_name, result_source_ty
+            )));
+        }
+        let result_ty = self.convert_type(&result_source_ty)?;
+        self.ensure_supported_closure_wasm_type(item_ty, &format!("{} input", function_name))?;
+        self.ensure_supported_closure_wasm_type(
+            result_ty,
+            &format!("{} function result", function_name),
+        )?;
+
+        self.generate_option_map_call(
+            &call.args[1],
+            &call.args[0],
+            &item_source_ty,
+            item_ty,
+            result_source_ty,
+            result_ty,
+            wrap_some,
+        )
     }
 
     fn generate_filter_call(&mut self, call: &CallExpr) -> Result<(), CodeGenError> {
@@ -10276,6 +16054,135 @@ impl WasmCodeGen {
         Ok(())
     }
 
+    /// Insertion sort into a fresh list: each input item is shifted left past
+    /// the already-sorted items the comparator ranks above it, so equal items
+    /// keep their input order.
+    fn generate_list_sort_call(&mut self, call: &CallExpr) -> Result<(), CodeGenError> {
+        if call.args.len() != 2 {
+            return Err(CodeGenError::UnsupportedFeature(
+                "list_sort expects comparator and list arguments".to_string(),
+            ));
+        }
+
+        let item_source_ty = self
+            .container_item_source_type(&call.args[1], "List")
+            .ok_or_else(|| {
+                CodeGenError::UnsupportedFeature(
+                    "list_sort code generation requires a known List item type".to_string(),
+                )
+            })?;
+        let item_ty = self.convert_type(&item_source_ty)?;
+        self.ensure_supported_closure_wasm_type(item_ty, "list_sort input")?;
+
+        let item_size = self.wasm_type_size(item_ty);
+        let value_local = self.iter_value_local(item_ty);
+        let previous_local = self.iter_acc_local(item_ty);
+        let load_op = self.wasm_load_op_for_wasm_type(item_ty);
+        let store_op = self.wasm_store_op_for_wasm_type(item_ty);
+
+        self.output.push_str("    ;; list_sort(compare, list)\n");
+        self.generate_expr(&call.args[1])?;
+        self.output.push_str("    local.set $iter_list\n");
+        self.generate_lambda_argument(
+            &call.args[0],
+            vec![item_ty, item_ty],
+            WasmType::I32,
+            vec![item_source_ty.clone(), item_source_ty],
+            Type::Named("Int32".to_string()),
+        )?;
+        self.output.push_str("    local.set $iter_func\n");
+
+        self.output.push_str("    local.get $iter_list\n");
+        self.output.push_str("    i32.load\n");
+        self.output.push_str("    local.set $iter_len\n");
+
+        self.output.push_str("    local.get $iter_len\n");
+        self.output
+            .push_str(&format!("    i32.const {}\n", item_size));
+        self.output.push_str("    i32.mul\n");
+        self.output.push_str("    i32.const 8\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    call $allocate\n");
+        self.output.push_str("    local.set $iter_out\n");
+
+        self.output.push_str("    local.get $iter_out\n");
+        self.output.push_str("    local.get $iter_len\n");
+        self.output.push_str("    i32.store\n");
+        self.output.push_str("    local.get $iter_out\n");
+        self.output.push_str("    i32.const 4\n");
+        self.output.push_str("    i32.add\n");
+        self.output.push_str("    local.get $iter_len\n");
+        self.output.push_str("    i32.store\n");
+
+        self.output.push_str("    i32.const 0\n");
+        self.output.push_str("    local.set $iter_index\n");
+
+        self.output.push_str("    (loop $sort_loop\n");
+        self.output.push_str("      local.get $iter_index\n");
+        self.output.push_str("      local.get $iter_len\n");
+        self.output.push_str("      i32.lt_u\n");
+        self.output.push_str("      (if\n");
+        self.output.push_str("        (then\n");
+        self.load_current_iter_value(item_ty)?;
+        self.output.push_str("          local.get $iter_index\n");
+        self.output
+            .push_str("          local.set $iter_out_index\n");
+        self.output.push_str("          (block $sort_shift_done\n");
+        self.output.push_str("            (loop $sort_shift\n");
+        self.output
+            .push_str("              local.get $iter_out_index\n");
+        self.output.push_str("              i32.eqz\n");
+        self.output
+            .push_str("              br_if $sort_shift_done\n");
+        // previous = out[j - 1]
+        self.store_current_iter_output_address("iter_out_index", item_ty)?;
+        self.output
+            .push_str(&format!("              i32.const {}\n", item_size));
+        self.output.push_str("              i32.sub\n");
+        self.output
+            .push_str(&format!("              {}\n", load_op));
+        self.output
+            .push_str(&format!("              local.set ${}\n", previous_local));
+        // Stop once compare(previous, value) <= 0.
+        self.output
+            .push_str(&format!("              local.get ${}\n", previous_local));
+        self.output
+            .push_str(&format!("              local.get ${}\n", value_local));
+        self.emit_iter_func_call(&[item_ty, item_ty], WasmType::I32, "              ");
+        self.output.push_str("              i32.const 0\n");
+        self.output.push_str("              i32.le_s\n");
+        self.output
+            .push_str("              br_if $sort_shift_done\n");
+        // out[j] = previous; j -= 1
+        self.store_current_iter_output_address("iter_out_index", item_ty)?;
+        self.output
+            .push_str(&format!("              local.get ${}\n", previous_local));
+        self.output
+            .push_str(&format!("              {}\n", store_op));
+        self.output
+            .push_str("              local.get $iter_out_index\n");
+        self.output.push_str("              i32.const 1\n");
+        self.output.push_str("              i32.sub\n");
+        self.output
+            .push_str("              local.set $iter_out_index\n");
+        self.output.push_str("              br $sort_shift\n");
+        self.output.push_str("            )\n");
+        self.output.push_str("          )\n");
+        self.store_current_iter_output_address("iter_out_index", item_ty)?;
+        self.output
+            .push_str(&format!("          local.get ${}\n", value_local));
+        self.output.push_str(&format!("          {}\n", store_op));
+        self.increment_local("iter_index")?;
+        self.output.push_str("          br $sort_loop\n");
+        self.output.push_str("        )\n");
+        self.output.push_str("      )\n");
+        self.output.push_str("    )\n");
+
+        self.output.push_str("    local.get $iter_out\n");
+
+        Ok(())
+    }
+
     fn generate_option_filter_call(
         &mut self,
         call: &CallExpr,
@@ -10578,8 +16485,9 @@ impl WasmCodeGen {
         match &expr.kind {
             ExprKind::IntLit(value) => Ok(Self::int_literal_wasm_type(*value)),
             ExprKind::FloatLit(_) => Ok(WasmType::F64),
+            ExprKind::DecimalLit(_) => Ok(WasmType::I64),
             ExprKind::BoolLit(_) => Ok(WasmType::I32),
-            ExprKind::Unit => Ok(WasmType::I32),
+            ExprKind::Unit | ExprKind::FieldUpdate(_) => Ok(WasmType::I32),
             ExprKind::Ident(name) => {
                 if let Some(ty) = self.lookup_local_abi_type(name)? {
                     Ok(ty)
@@ -10596,12 +16504,17 @@ impl WasmCodeGen {
             ExprKind::Binary(binary) => self.infer_binary_expr_type(binary),
             ExprKind::Unary(unary) => self.infer_unary_expr_type(unary),
             ExprKind::Cast(cast) => self.convert_type(&cast.target),
+            ExprKind::Try(_) => match self.infer_expr_source_type(expr) {
+                Some(source_ty) => self.convert_type(&source_ty),
+                None => Ok(WasmType::I32),
+            },
             ExprKind::Then(then) => self.infer_then_result_type(then),
             ExprKind::Match(match_expr) => match match_expr.arms.first() {
                 Some(arm) => self.infer_block_result_type(&arm.body),
                 None => Ok(WasmType::I32),
             },
             ExprKind::Block(block) => self.infer_block_result_type(block),
+            ExprKind::Loop(body) => Ok(self.loop_result_type(body)?.unwrap_or(WasmType::I32)),
             ExprKind::With(with) => {
                 if let Some(source_ty) = self.infer_expr_source_type(expr) {
                     return self.convert_type(&source_ty);
@@ -10666,10 +16579,11 @@ impl WasmCodeGen {
         match &expr.kind {
             ExprKind::IntLit(value) => Some(Self::int_literal_source_type(*value)),
             ExprKind::FloatLit(_) => Some(Type::Named("Float64".to_string())),
+            ExprKind::DecimalLit(_) => Some(Type::Named("Decimal".to_string())),
             ExprKind::BoolLit(_) => Some(Type::Named("Boolean".to_string())),
             ExprKind::CharLit(_) => Some(Type::Named("Char".to_string())),
             ExprKind::StringLit(_) => Some(Type::Named("String".to_string())),
-            ExprKind::Unit => Some(Type::Named("Unit".to_string())),
+            ExprKind::Unit | ExprKind::FieldUpdate(_) => Some(Type::Named("Unit".to_string())),
             ExprKind::Ident(name) => self.lookup_local_source_type(name).or_else(|| {
                 if self.lookup_local(name).is_none() {
                     self.named_function_source_type(name)
@@ -10683,6 +16597,12 @@ impl WasmCodeGen {
             ExprKind::Some(inner) => self
                 .infer_expr_source_type(inner)
                 .map(|ty| Type::Generic("Option".to_string(), vec![ty])),
+            ExprKind::Try(operand) => match self.infer_expr_source_type(operand)? {
+                Type::Generic(name, args) if name == "Option" || name == "Result" => {
+                    args.into_iter().next()
+                }
+                _ => None,
+            },
             ExprKind::Binary(binary) => match binary.op {
                 BinaryOp::Eq
                 | BinaryOp::Ne
@@ -10699,6 +16619,9 @@ impl WasmCodeGen {
 
                     match self.infer_binary_expr_type(binary) {
                         Ok(WasmType::F64) => Some(Type::Named("Float64".to_string())),
+                        Ok(WasmType::I64) if self.is_decimal_binary(binary) => {
+                            Some(Type::Named("Decimal".to_string()))
+                        }
                         Ok(WasmType::I64) => Some(Type::Named("Int64".to_string())),
                         Ok(_) => Some(Type::Named("Int32".to_string())),
                         Err(_) => None,
@@ -10728,6 +16651,10 @@ impl WasmCodeGen {
                 self.infer_match_source_type_with_bindings(match_expr, &HashMap::new())
             }
             ExprKind::Block(block) => self.infer_block_source_type(block),
+            ExprKind::Loop(body) => match Self::loop_break_value(body) {
+                Some(value) => self.infer_expr_source_type(value),
+                None => Some(Type::Named("Unit".to_string())),
+            },
             ExprKind::With(with) => {
                 let bindings = self.context_source_bindings(with, &HashMap::new());
                 self.infer_block_source_type_with_bindings(&with.body, &bindings)
@@ -10749,7 +16676,15 @@ impl WasmCodeGen {
                 Some(Type::Function(params, Box::new(return_ty)))
             }
             ExprKind::Call(call) => {
+                if let Some(method_call) = self.receiver_method_call(call) {
+                    return self.infer_expr_source_type(&Expr::new(ExprKind::Call(method_call)));
+                }
                 if let ExprKind::Ident(name) = &call.function.kind {
+                    if let Some(return_ty) =
+                        self.infer_explicit_type_arg_call_source_type(name, &call.type_args)
+                    {
+                        return Some(return_ty);
+                    }
                     let arg_exprs = call.args.iter().map(|arg| arg.as_ref()).collect::<Vec<_>>();
                     if self.can_infer_named_function_call_source_type(name, false) {
                         if let Some(return_ty) =
@@ -10759,6 +16694,20 @@ impl WasmCodeGen {
                         }
                     }
 
+                    // Generic methods infer their type arguments like generic
+                    // functions, including from contextually typed lambdas.
+                    if !self.functions.contains_key(name) {
+                        if let Ok(Some(target_name)) =
+                            self.resolve_method_call_target(name, &call.args)
+                        {
+                            if let Some(return_ty) =
+                                self.infer_function_call_source_type(&target_name, &arg_exprs)
+                            {
+                                return Some(return_ty);
+                            }
+                        }
+                    }
+
                     let arg_tys = call
                         .args
                         .iter()
@@ -10823,6 +16772,24 @@ impl WasmCodeGen {
         }
     }
 
+    /// The return type of a call whose explicit type arguments alone
+    /// determine it, as in `() empty<Int32>`.
+    fn infer_explicit_type_arg_call_source_type(
+        &self,
+        name: &str,
+        type_args: &[Type],
+    ) -> Option<Type> {
+        if type_args.is_empty() {
+            return None;
+        }
+        if name == "identity" {
+            return type_args.first().cloned();
+        }
+        let sig = self.function_source_sigs.get(name)?;
+        let substitution = Self::explicit_type_arg_substitution(sig, type_args);
+        Self::substitute_source_type_params(sig.result.as_ref()?, &sig.type_params, &substitution)
+    }
+
     fn infer_function_call_source_type(&self, name: &str, args: &[&Expr]) -> Option<Type> {
         if let Some(function_name) = self.lookup_generic_function_alias(name) {
             return self.infer_function_call_source_type(&function_name, args);
@@ -10834,6 +16801,7 @@ impl WasmCodeGen {
             }
             "filter" if args.len() == 2 => return self.infer_expr_source_type(args[0]),
             "fold" if args.len() == 3 => return self.infer_expr_source_type(args[1]),
+            "list_sort" if args.len() == 2 => return self.infer_expr_source_type(args[1]),
             _ => {}
         }
 
@@ -10879,7 +16847,7 @@ impl WasmCodeGen {
     }
 
     fn is_iteration_function_name(name: &str) -> bool {
-        matches!(name, "map" | "filter" | "fold")
+        matches!(name, "map" | "filter" | "fold" | "list_sort")
     }
 
     fn can_infer_named_function_call_source_type(&self, name: &str, bound_in_expr: bool) -> bool {
@@ -10961,7 +16929,7 @@ impl WasmCodeGen {
     fn list_get_function_for_element(&self, element_source_ty: Option<&Type>) -> &'static str {
         match element_source_ty {
             Some(Type::Named(name)) if name == "Float64" => "list_get_f64",
-            Some(Type::Named(name)) if name == "Int64" => "list_get_i64",
+            Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => "list_get_i64",
             _ => "list_get",
         }
     }
@@ -10969,7 +16937,7 @@ impl WasmCodeGen {
     fn list_tail_function_for_element(&self, element_source_ty: Option<&Type>) -> &'static str {
         match element_source_ty {
             Some(Type::Named(name)) if name == "Float64" => "tail_f64",
-            Some(Type::Named(name)) if name == "Int64" => "tail_i64",
+            Some(Type::Named(name)) if name == "Int64" || name == "Decimal" => "tail_i64",
             _ => "tail",
         }
     }
@@ -11185,10 +17153,11 @@ impl WasmCodeGen {
                 }),
             ExprKind::IntLit(value) => Some(Self::int_literal_source_type(*value)),
             ExprKind::FloatLit(_) => Some(Type::Named("Float64".to_string())),
+            ExprKind::DecimalLit(_) => Some(Type::Named("Decimal".to_string())),
             ExprKind::BoolLit(_) => Some(Type::Named("Boolean".to_string())),
             ExprKind::CharLit(_) => Some(Type::Named("Char".to_string())),
             ExprKind::StringLit(_) => Some(Type::Named("String".to_string())),
-            ExprKind::Unit => Some(Type::Named("Unit".to_string())),
+            ExprKind::Unit | ExprKind::FieldUpdate(_) => Some(Type::Named("Unit".to_string())),
             ExprKind::Some(inner) => self
                 .infer_expr_source_type_with_bindings(inner, bindings)
                 .map(|ty| Type::Generic("Option".to_string(), vec![ty])),
@@ -11202,6 +17171,11 @@ impl WasmCodeGen {
             }
             ExprKind::Call(call) => {
                 if let ExprKind::Ident(name) = &call.function.kind {
+                    if let Some(return_ty) =
+                        self.infer_explicit_type_arg_call_source_type(name, &call.type_args)
+                    {
+                        return Some(return_ty);
+                    }
                     let arg_exprs = call.args.iter().map(|arg| arg.as_ref()).collect::<Vec<_>>();
                     if self.can_infer_named_function_call_source_type(
                         name,
@@ -11307,12 +17281,20 @@ impl WasmCodeGen {
                     bindings.insert(name.clone(), ty.clone());
                 }
             }
+            Pattern::Binding(name, inner) => {
+                if let Some(ty) = value_ty {
+                    bindings.insert(name.clone(), ty.clone());
+                }
+                self.extend_pattern_source_bindings(inner, value_ty, bindings);
+            }
             Pattern::Record(record_name, fields) => {
+                let record_name = Self::pattern_record_name(record_name, value_ty);
                 self.extend_record_pattern_source_bindings(record_name, fields, bindings);
             }
             Pattern::RecordDestruct {
                 type_name, fields, ..
             } => {
+                let type_name = Self::pattern_record_name(type_name, value_ty);
                 self.extend_record_pattern_source_bindings(type_name, fields, bindings);
             }
             Pattern::Some(inner) => {
@@ -11351,7 +17333,11 @@ impl WasmCodeGen {
                     self.extend_pattern_source_bindings(item, element_ty.as_ref(), bindings);
                 }
             }
-            Pattern::Wildcard | Pattern::Literal(_) | Pattern::None | Pattern::EmptyList => {}
+            Pattern::Wildcard
+            | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::None
+            | Pattern::EmptyList => {}
             Pattern::Ident(_) => {}
         }
     }
@@ -11398,6 +17384,10 @@ impl WasmCodeGen {
                     || matches!(right_ty, Type::Named(ref name) if name == "Float64")
                 {
                     Some(Type::Named("Float64".to_string()))
+                } else if matches!(left_ty, Type::Named(ref name) if name == "Decimal")
+                    || matches!(right_ty, Type::Named(ref name) if name == "Decimal")
+                {
+                    Some(Type::Named("Decimal".to_string()))
                 } else if matches!(left_ty, Type::Named(ref name) if name == "Int64")
                     || matches!(right_ty, Type::Named(ref name) if name == "Int64")
                 {
@@ -11429,16 +17419,8 @@ impl WasmCodeGen {
             }
         }
 
-        if let Some(expr) = &block.expr {
-            return self.infer_expr_source_type_with_bindings(expr, &block_bindings);
-        }
-
-        match block.statements.last() {
-            Some(Stmt::Expr(expr)) => {
-                self.infer_expr_source_type_with_bindings(expr, &block_bindings)
-            }
-            _ => None,
-        }
+        let expr = block.expr.as_deref()?;
+        self.infer_expr_source_type_with_bindings(expr, &block_bindings)
     }
 
     fn bind_source_type_params(
@@ -11532,6 +17514,9 @@ impl WasmCodeGen {
             Type::Temporal(name, temporals) => {
                 Some(Type::Temporal(name.clone(), temporals.clone()))
             }
+            Type::Refined(base, _) => {
+                Self::substitute_source_type_params(base, type_params, substitution)
+            }
         }
     }
 
@@ -11600,15 +17585,10 @@ impl WasmCodeGen {
     }
 
     fn infer_block_result_type(&self, block: &BlockExpr) -> Result<WasmType, CodeGenError> {
-        if let Some(expr) = &block.expr {
-            return self.infer_expr_type(expr);
-        }
-
-        if let Some(Stmt::Expr(expr)) = block.statements.last() {
-            return self.infer_expr_type(expr);
+        match &block.expr {
+            Some(expr) => self.infer_expr_type(expr),
+            None => Ok(WasmType::I32),
         }
-
-        Ok(WasmType::I32)
     }
 
     fn infer_then_result_type(&self, then: &ThenExpr) -> Result<WasmType, CodeGenError> {
@@ -11781,6 +17761,12 @@ impl WasmCodeGen {
         None
     }
 
+    /// Whether a call through `name` targets a declared function rather than
+    /// a local binding that shadows it.
+    fn calls_named_function(&self, name: &str) -> bool {
+        self.functions.contains_key(name) && self.lookup_local(name).is_none()
+    }
+
     fn lookup_local(&self, name: &str) -> Option<u32> {
         if let Some(local_name) = self.lookup_local_alias(name) {
             for scope in self.locals.iter().rev() {
@@ -11937,6 +17923,11 @@ impl WasmCodeGen {
                 self.expr_is_replay_safe_for_deferred_callable(&match_expr.expr)
                     && !match_expr.arms.is_empty()
                     && match_expr.arms.iter().all(|arm| {
+                        if arm.guard.as_ref().is_some_and(|guard| {
+                            !self.expr_is_replay_safe_for_deferred_callable(guard)
+                        }) {
+                            return false;
+                        }
                         let mut arm_bindings = HashMap::new();
                         self.extend_pattern_source_bindings(
                             &arm.pattern,
@@ -11968,7 +17959,7 @@ impl WasmCodeGen {
         }
 
         let mut block_bindings = bindings.clone();
-        for stmt in self.deferred_callable_prefix_statements(block) {
+        for stmt in &block.statements {
             let Stmt::Binding(bind) = stmt else {
                 return false;
             };
@@ -12007,30 +17998,12 @@ impl WasmCodeGen {
     }
 
     fn block_terminal_lambda<'a>(&self, block: &'a BlockExpr) -> Option<&'a LambdaExpr> {
-        if let Some(ExprKind::Lambda(lambda)) = block.expr.as_deref().map(|e| &e.kind) {
-            return Some(lambda);
-        }
-
-        match block.statements.last() {
-            Some(Stmt::Expr(expr)) => match &expr.kind {
-                ExprKind::Lambda(lambda) => Some(lambda),
-                _ => None,
-            },
+        match block.expr.as_deref().map(|e| &e.kind) {
+            Some(ExprKind::Lambda(lambda)) => Some(lambda),
             _ => None,
         }
     }
 
-    fn deferred_callable_prefix_statements<'a>(&self, block: &'a BlockExpr) -> &'a [Stmt] {
-        if block.expr.is_some() {
-            &block.statements
-        } else if matches!(block.statements.last(), Some(Stmt::Expr(expr)) if matches!(&expr.kind, ExprKind::Lambda(_)))
-        {
-            &block.statements[..block.statements.len() - 1]
-        } else {
-            &block.statements
-        }
-    }
-
     fn expr_is_replay_safe_for_deferred_callable(&self, expr: &Expr) -> bool {
         self.expr_is_replay_safe_for_deferred_callable_with_bindings(expr, &HashMap::new())
     }
@@ -12043,6 +18016,7 @@ impl WasmCodeGen {
         match &expr.kind {
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -12090,7 +18064,7 @@ impl WasmCodeGen {
             Type::Named(name) => {
                 matches!(
                     name.as_str(),
-                    "Int32" | "Int64" | "Float64" | "Boolean" | "Char" | "Unit"
+                    "Int32" | "Int64" | "Float64" | "Decimal" | "Boolean" | "Char" | "Unit"
                 )
             }
             Type::Generic(name, args) if name == "Option" => {
@@ -12158,6 +18132,14 @@ impl WasmCodeGen {
                     self.set_local_source_type(name, source_ty.clone());
                 }
             }
+            Pattern::Binding(name, inner) => {
+                locals.push((name.clone(), *ty));
+                self.set_local_type(name, *ty);
+                if let Some(source_ty) = source_ty {
+                    self.set_local_source_type(name, source_ty.clone());
+                }
+                self.collect_locals_from_pattern(inner, ty, source_ty, locals)?;
+            }
             Pattern::RecordDestruct {
                 type_name,
                 fields,
@@ -12220,7 +18202,11 @@ impl WasmCodeGen {
                 let inner_wasm_ty = self.variant_payload_wasm_type(inner_source_ty)?;
                 self.collect_locals_from_pattern(inner, &inner_wasm_ty, inner_source_ty, locals)?;
             }
-            Pattern::Wildcard | Pattern::None | Pattern::EmptyList | Pattern::Literal(_) => {
+            Pattern::Wildcard
+            | Pattern::None
+            | Pattern::EmptyList
+            | Pattern::Literal(_)
+            | Pattern::Range { .. } => {
                 // These patterns don't bind variables
             }
         }
@@ -12235,6 +18221,7 @@ impl WasmCodeGen {
         rest: Option<&String>,
         locals: &mut Vec<(String, WasmType)>,
     ) -> Result<(), CodeGenError> {
+        let record_name = Self::pattern_record_name(record_name, source_ty);
         for (field_name, field_pattern) in fields {
             let field_type = self
                 .instantiated_record_field_type_by_name(record_name, source_ty, field_name)
@@ -12329,7 +18316,10 @@ impl WasmCodeGen {
                 found
             }
             Stmt::Assignment(assign) => self.find_array_use_for_ident_in_expr(name, &assign.value),
-            Stmt::Expr(expr) => self.find_array_use_for_ident_in_expr(name, expr),
+            Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
+                self.find_array_use_for_ident_in_expr(name, expr)
+            }
+            Stmt::Break(None) | Stmt::Continue => (false, None),
         }
     }
 
@@ -12481,6 +18471,27 @@ impl WasmCodeGen {
                     self.find_array_use_for_ident_in_block(name, &while_expr.body),
                 );
             }
+            ExprKind::Loop(body) => {
+                Self::merge_array_use(
+                    &mut found_array_use,
+                    &mut elem_ty,
+                    self.find_array_use_for_ident_in_block(name, body),
+                );
+            }
+            ExprKind::WhileLet(while_let) => {
+                Self::merge_array_use(
+                    &mut found_array_use,
+                    &mut elem_ty,
+                    self.find_array_use_for_ident_in_expr(name, &while_let.expr),
+                );
+                if !Self::pattern_binds_name(&while_let.pattern, name) {
+                    Self::merge_array_use(
+                        &mut found_array_use,
+                        &mut elem_ty,
+                        self.find_array_use_for_ident_in_block(name, &while_let.body),
+                    );
+                }
+            }
             ExprKind::Match(match_expr) => {
                 Self::merge_array_use(
                     &mut found_array_use,
@@ -12590,6 +18601,7 @@ impl WasmCodeGen {
             | ExprKind::Some(inner)
             | ExprKind::Ok(inner)
             | ExprKind::Err(inner)
+            | ExprKind::Try(inner)
             | ExprKind::Await(inner)
             | ExprKind::Spawn(inner)
             | ExprKind::FieldAccess(inner, _) => {
@@ -12599,6 +18611,15 @@ impl WasmCodeGen {
                     self.find_array_use_for_ident_in_expr(name, inner),
                 );
             }
+            ExprKind::FieldUpdate(update) => {
+                for inner in [&update.object, &update.value] {
+                    Self::merge_array_use(
+                        &mut found_array_use,
+                        &mut elem_ty,
+                        self.find_array_use_for_ident_in_expr(name, inner),
+                    );
+                }
+            }
             ExprKind::ListLit(elements) | ExprKind::ArrayLit(elements) => {
                 for element in elements {
                     Self::merge_array_use(
@@ -12622,6 +18643,7 @@ impl WasmCodeGen {
             }
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -12660,6 +18682,9 @@ impl WasmCodeGen {
     fn pattern_binds_name(pattern: &Pattern, name: &str) -> bool {
         match pattern {
             Pattern::Ident(binding) => binding == name,
+            Pattern::Binding(binding, inner) => {
+                binding == name || Self::pattern_binds_name(inner, name)
+            }
             Pattern::Record(_, fields) => fields
                 .iter()
                 .any(|(_, field_pattern)| Self::pattern_binds_name(field_pattern, name)),
@@ -12678,7 +18703,11 @@ impl WasmCodeGen {
             Pattern::ListExact(patterns) => patterns
                 .iter()
                 .any(|pattern| Self::pattern_binds_name(pattern, name)),
-            Pattern::Wildcard | Pattern::Literal(_) | Pattern::None | Pattern::EmptyList => false,
+            Pattern::Wildcard
+            | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::None
+            | Pattern::EmptyList => false,
         }
     }
 
@@ -12761,10 +18790,11 @@ impl WasmCodeGen {
                     }
                     self.collect_locals_from_expr(&assign.value, locals)?;
                 }
-                Stmt::Expr(expr) => {
+                Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
                     // Check for nested blocks and match expressions
                     self.collect_locals_from_expr(expr, locals)?;
                 }
+                Stmt::Break(None) | Stmt::Continue => {}
             }
         }
 
@@ -12835,7 +18865,7 @@ impl WasmCodeGen {
         match &expr.kind {
             ExprKind::Call(call) => {
                 if let ExprKind::Ident(func_name) = &call.function.kind {
-                    if self.functions.contains_key(func_name) {
+                    if self.calls_named_function(func_name) {
                         let target_name = self.resolve_builtin_abi_function(func_name, &call.args);
                         return self
                             .function_source_sigs
@@ -12888,7 +18918,8 @@ impl WasmCodeGen {
             Stmt::Binding(bind) => Self::max_record_tmp_depth_in_expr(&bind.value)
                 .max(Self::max_record_tmp_depth_in_pattern(&bind.pattern)),
             Stmt::Assignment(assign) => Self::max_record_tmp_depth_in_expr(&assign.value),
-            Stmt::Expr(expr) => Self::max_record_tmp_depth_in_expr(expr),
+            Stmt::Expr(expr) | Stmt::Break(Some(expr)) => Self::max_record_tmp_depth_in_expr(expr),
+            Stmt::Break(None) | Stmt::Continue => 0,
         }
     }
 
@@ -12909,7 +18940,8 @@ impl WasmCodeGen {
             | ExprKind::Spawn(inner)
             | ExprKind::Some(inner)
             | ExprKind::Ok(inner)
-            | ExprKind::Err(inner) => Self::max_record_tmp_depth_in_expr(inner),
+            | ExprKind::Err(inner)
+            | ExprKind::Try(inner) => Self::max_record_tmp_depth_in_expr(inner),
             ExprKind::Then(then) => {
                 let else_if_depth = then
                     .else_ifs
@@ -12934,12 +18966,21 @@ impl WasmCodeGen {
                 Self::max_record_tmp_depth_in_expr(&while_expr.condition)
                     .max(Self::max_record_tmp_depth_in_block(&while_expr.body))
             }
+            ExprKind::WhileLet(while_let) => Self::max_record_tmp_depth_in_expr(&while_let.expr)
+                .max(Self::max_record_tmp_depth_in_pattern(&while_let.pattern))
+                .max(Self::max_record_tmp_depth_in_block(&while_let.body)),
+            ExprKind::Loop(body) => Self::max_record_tmp_depth_in_block(body),
             ExprKind::Match(match_expr) => {
                 let arm_depth = match_expr
                     .arms
                     .iter()
                     .map(|arm| {
                         Self::max_record_tmp_depth_in_pattern(&arm.pattern)
+                            .max(
+                                arm.guard
+                                    .as_ref()
+                                    .map_or(0, Self::max_record_tmp_depth_in_expr),
+                            )
                             .max(Self::max_record_tmp_depth_in_block(&arm.body))
                     })
                     .max()
@@ -12980,6 +19021,8 @@ impl WasmCodeGen {
             }
             ExprKind::Block(block) => Self::max_record_tmp_depth_in_block(block),
             ExprKind::FieldAccess(object, _) => Self::max_record_tmp_depth_in_expr(object),
+            ExprKind::FieldUpdate(update) => Self::max_record_tmp_depth_in_expr(&update.object)
+                .max(Self::max_record_tmp_depth_in_expr(&update.value)),
             ExprKind::ListLit(items) | ExprKind::ArrayLit(items) => items
                 .iter()
                 .map(|item| Self::max_record_tmp_depth_in_expr(item))
@@ -12993,6 +19036,7 @@ impl WasmCodeGen {
             }
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -13025,9 +19069,10 @@ impl WasmCodeGen {
             Pattern::RecordDestruct { fields, .. } => {
                 1 + Self::max_record_tmp_depth_in_pattern_fields(fields)
             }
-            Pattern::Some(inner) | Pattern::Ok(inner) | Pattern::Err(inner) => {
-                Self::max_record_tmp_depth_in_pattern(inner)
-            }
+            Pattern::Some(inner)
+            | Pattern::Ok(inner)
+            | Pattern::Err(inner)
+            | Pattern::Binding(_, inner) => Self::max_record_tmp_depth_in_pattern(inner),
             Pattern::ListCons(head, tail) => Self::max_record_tmp_depth_in_pattern(head)
                 .max(Self::max_record_tmp_depth_in_pattern(tail)),
             Pattern::ListExact(patterns) => patterns
@@ -13038,6 +19083,7 @@ impl WasmCodeGen {
             Pattern::Wildcard
             | Pattern::Ident(_)
             | Pattern::Literal(_)
+            | Pattern::Range { .. }
             | Pattern::None
             | Pattern::EmptyList => 0,
         }
@@ -13115,6 +19161,14 @@ impl WasmCodeGen {
                     .unwrap_or(fallback_wasm_ty);
                 bindings.push((name.clone(), wasm_ty, source_ty.cloned()));
             }
+            Pattern::Binding(name, inner) => {
+                let wasm_ty = source_ty
+                    .map(|ty| self.convert_type(ty))
+                    .transpose()?
+                    .unwrap_or(fallback_wasm_ty);
+                bindings.push((name.clone(), wasm_ty, source_ty.cloned()));
+                self.collect_pattern_binding_types(inner, source_ty, fallback_wasm_ty, bindings)?;
+            }
             Pattern::Record(record_name, fields) => {
                 self.collect_record_pattern_binding_types(
                     record_name,
@@ -13183,7 +19237,11 @@ impl WasmCodeGen {
                 let payload_wasm_ty = self.variant_payload_wasm_type(payload_ty)?;
                 self.collect_pattern_binding_types(inner, payload_ty, payload_wasm_ty, bindings)?;
             }
-            Pattern::Wildcard | Pattern::None | Pattern::EmptyList | Pattern::Literal(_) => {}
+            Pattern::Wildcard
+            | Pattern::None
+            | Pattern::EmptyList
+            | Pattern::Literal(_)
+            | Pattern::Range { .. } => {}
         }
 
         Ok(())
@@ -13197,6 +19255,7 @@ impl WasmCodeGen {
         rest: Option<&String>,
         bindings: &mut Vec<(String, WasmType, Option<Type>)>,
     ) -> Result<(), CodeGenError> {
+        let record_name = Self::pattern_record_name(record_name, source_ty);
         if let Some(rest_name) = rest {
             if rest_name != "_" {
                 bindings.push((rest_name.clone(), WasmType::I32, None));
@@ -13280,6 +19339,9 @@ impl WasmCodeGen {
                         }
                     }
 
+                    if let Some(guard) = &arm.guard {
+                        self.collect_locals_from_expr(guard, locals)?;
+                    }
                     self.collect_locals_from_block_with_expected(
                         &arm.body,
                         locals,
@@ -13304,6 +19366,29 @@ impl WasmCodeGen {
             ExprKind::While(while_expr) => {
                 self.collect_locals_from_block(&while_expr.body, locals)?;
             }
+            ExprKind::Loop(body) => {
+                self.collect_locals_from_block(body, locals)?;
+            }
+            ExprKind::WhileLet(while_let) => {
+                self.collect_locals_from_expr(&while_let.expr, locals)?;
+                let value_source_ty = self.infer_expr_source_type(&while_let.expr);
+                let value_wasm_ty = if let Some(source_ty) = &value_source_ty {
+                    self.convert_type(source_ty)?
+                } else {
+                    self.infer_expr_type(&while_let.expr)?
+                };
+                self.push_scope();
+                let mut pattern_locals = Vec::new();
+                self.collect_locals_from_pattern(
+                    &while_let.pattern,
+                    &value_wasm_ty,
+                    value_source_ty.as_ref(),
+                    &mut pattern_locals,
+                )?;
+                locals.extend(pattern_locals);
+                self.collect_locals_from_block(&while_let.body, locals)?;
+                self.pop_scope();
+            }
             ExprKind::With(with) => {
                 let mut scoped_bindings = Vec::new();
                 for binding in &with.bindings {
@@ -13441,13 +19526,20 @@ impl WasmCodeGen {
                     )?;
                 }
             },
-            ExprKind::Ok(inner) | ExprKind::Err(inner) | ExprKind::Some(inner) => {
+            ExprKind::Ok(inner)
+            | ExprKind::Err(inner)
+            | ExprKind::Some(inner)
+            | ExprKind::Try(inner) => {
                 self.collect_locals_from_expr(inner, locals)?;
             }
             ExprKind::RangeLit(range) => {
                 self.collect_locals_from_expr(&range.start, locals)?;
                 self.collect_locals_from_expr(&range.end, locals)?;
             }
+            ExprKind::Binary(binary) => {
+                self.collect_locals_from_expr(&binary.left, locals)?;
+                self.collect_locals_from_expr(&binary.right, locals)?;
+            }
             ExprKind::Unary(unary) => {
                 self.collect_locals_from_expr(&unary.expr, locals)?;
             }
@@ -13466,12 +19558,12 @@ impl WasmCodeGen {
                 if name == "identity" {
                     // identity is a no-op in the value pipeline.
                     self.generate_expr(&pipe.expr)?;
-                } else if name == "println" {
-                    // Special handling for generic println - determine type at runtime
-                    let specialized_name = self.resolve_generic_function_call(name, &pipe.expr)?;
-                    self.generate_expr(&pipe.expr)?;
-                    self.output
-                        .push_str(&format!("    call ${}\n", specialized_name));
+                } else if name == "println" || name == "drop" {
+                    if name == "println" {
+                        self.generate_println_value(&pipe.expr)?;
+                    } else {
+                        self.generate_drop_value(&pipe.expr)?;
+                    }
                     // These functions return nothing, so we need to push unit value for pipe result
                     // But only if we're not in main function (which returns nothing)
                     if self.current_function != Some("main".to_string()) {
@@ -13533,6 +19625,15 @@ impl WasmCodeGen {
                         if func_name == "identity" {
                             // identity is a no-op in the value pipeline.
                             self.generate_expr(&pipe.expr)?;
+                        } else if func_name == "println" || func_name == "drop" {
+                            if func_name == "println" {
+                                self.generate_println_value(&pipe.expr)?;
+                            } else {
+                                self.generate_drop_value(&pipe.expr)?;
+                            }
+                            if self.current_function != Some("main".to_string()) {
+                                self.output.push_str("    i32.const 0\n");
+                            }
                         } else if self.functions.contains_key(func_name) {
                             let target_name = self.resolve_named_function_call_target(
                                 func_name,
@@ -13593,45 +19694,107 @@ impl WasmCodeGen {
                     }
                 }
             }
-        }
+        }
+
+        Ok(())
+    }
+
+    /// `println` is generic over `Display`: print the argument with the
+    /// helper for its static type, converting to a String first where no
+    /// direct newline-terminated printer exists.
+    fn generate_println_value(&mut self, arg: &Expr) -> Result<(), CodeGenError> {
+        let source_ty = self.infer_expr_source_type(arg).ok_or_else(|| {
+            CodeGenError::UnsupportedFeature(
+                "println requires an argument with an inferable Display type".to_string(),
+            )
+        })?;
+
+        let (to_string, printer) = match &source_ty {
+            Type::Named(type_name) => match type_name.as_str() {
+                "String" => Some((None, "println")),
+                "Int32" => Some((None, "print_int")),
+                "Float64" => Some((Some("float_to_string"), "println")),
+                "Boolean" => Some((Some("bool_to_string"), "println")),
+                "Char" => Some((Some("string_from_char"), "println")),
+                "Int64" => Some((Some("int64_to_string"), "println")),
+                "Decimal" => Some((Some("decimal_to_string"), "println")),
+                "Unit" => Some((Some("unit_to_string"), "println")),
+                _ => None,
+            },
+            _ => None,
+        }
+        .ok_or_else(|| {
+            CodeGenError::UnsupportedFeature(format!(
+                "println does not support argument type {}; use a Display type such as String, Int32, Int64, Float64, Decimal, Boolean, Char, or ()",
+                source_ty
+            ))
+        })?;
+
+        self.generate_expr_with_expected_source(arg, &source_ty)?;
+        if let Some(to_string) = to_string {
+            self.output.push_str(&format!("    call ${}\n", to_string));
+        }
+        self.output.push_str(&format!("    call ${}\n", printer));
+        Ok(())
+    }
+
+    /// `assert_eq`/`assert_ne` on a `Display` type hand both operands to
+    /// `$assert_compare_<type>` so a failure can print them. Other `Eq`
+    /// types, such as records, compare with `==` and panic with the message
+    /// alone.
+    fn generate_assert_comparison(
+        &mut self,
+        call: &CallExpr,
+        equal: bool,
+    ) -> Result<(), CodeGenError> {
+        let (left, right, message) = (&call.args[0], &call.args[1], &call.args[2]);
+        let operand_ty = match (
+            self.infer_expr_source_type(left),
+            self.infer_expr_source_type(right),
+        ) {
+            (Some(left_ty), Some(right_ty)) if left_ty == right_ty => Some(left_ty),
+            // An integer literal takes the type of the other operand.
+            (_, Some(ty)) if matches!(left.kind, ExprKind::IntLit(_)) => Some(ty),
+            (Some(ty), _) if matches!(right.kind, ExprKind::IntLit(_)) => Some(ty),
+            _ => None,
+        };
+        let helper = operand_ty.and_then(|ty| match &ty {
+            Type::Named(type_name) => {
+                Self::assert_operand_helper(type_name).map(|(suffix, ..)| (suffix, ty.clone()))
+            }
+            _ => None,
+        });
 
+        let Some((suffix, operand_ty)) = helper else {
+            let op = if equal { BinaryOp::Eq } else { BinaryOp::Ne };
+            let condition = Expr::new(ExprKind::Binary(BinaryExpr {
+                left: left.clone(),
+                op,
+                right: right.clone(),
+            }));
+            return self.generate_call_expr(&CallExpr {
+                function: Box::new(Expr::new(ExprKind::Ident("assert".to_string()))),
+                args: vec![Box::new(condition), message.clone()],
+                type_args: Vec::new(),
+            });
+        };
+        self.generate_expr_with_expected_source(left, &operand_ty)?;
+        self.generate_expr_with_expected_source(right, &operand_ty)?;
+        self.output
+            .push_str(&format!("    i32.const {}\n", i32::from(equal)));
+        self.generate_expr(message)?;
+        self.output
+            .push_str(&format!("    call $assert_compare_{}\n", suffix));
         Ok(())
     }
 
-    fn resolve_generic_function_call(
-        &self,
-        name: &str,
-        arg_expr: &Expr,
-    ) -> Result<String, CodeGenError> {
-        if name == "println" {
-            let source_ty = self.infer_expr_source_type(arg_expr).ok_or_else(|| {
-                CodeGenError::UnsupportedFeature(
-                    "println requires an inferable String or Int32 argument; use print_float for Float64"
-                        .to_string(),
-                )
-            })?;
-
-            match source_ty {
-                Type::Named(type_name) if type_name == "String" => Ok("println".to_string()),
-                Type::Named(type_name) if type_name == "Int32" => Ok("print_int".to_string()),
-                other => Err(CodeGenError::UnsupportedFeature(format!(
-                    "println does not support argument type {}; use print_int, print_float, or a String value",
-                    other
-                ))),
-            }
-        } else {
-            match self.infer_expr_source_type(arg_expr) {
-                Some(Type::Named(type_name)) => Ok(format!("{}_{}", name, type_name)),
-                Some(other) => Err(CodeGenError::UnsupportedFeature(format!(
-                    "generic function '{}' requires a concrete named specialization for argument type {}",
-                    name, other
-                ))),
-                None => Err(CodeGenError::UnsupportedFeature(format!(
-                    "generic function '{}' requires an inferable argument type for specialization",
-                    name
-                ))),
-            }
+    /// Evaluate `arg` for its effects and discard the result.
+    fn generate_drop_value(&mut self, arg: &Expr) -> Result<(), CodeGenError> {
+        self.generate_expr(arg)?;
+        if self.expr_leaves_value(arg) || self.expr_synthesizes_unit_value(arg) {
+            self.output.push_str("    drop\n");
         }
+        Ok(())
     }
 
     fn generate_list_literal(&mut self, items: &[Box<Expr>]) -> Result<(), CodeGenError> {
@@ -13847,14 +20010,6 @@ impl WasmCodeGen {
                 self.output.push_str("      (else\n");
             }
 
-            // Generate pattern matching code
-            self.output
-                .push_str(&format!("    local.get ${}\n", match_local));
-            let bindings = self.generate_pattern_match(
-                &arm.pattern,
-                scrutinee_source_ty.as_ref(),
-                match_local,
-            )?;
             let mut binding_infos = Vec::new();
             self.collect_pattern_binding_types(
                 &arm.pattern,
@@ -13862,12 +20017,43 @@ impl WasmCodeGen {
                 scrutinee_wasm_ty,
                 &mut binding_infos,
             )?;
+            self.push_scope();
+            // Resolve per-arm storage before emitting the pattern so `@`
+            // bindings can store the scrutinee straight into it.
+            let mut local_names = Vec::with_capacity(binding_infos.len());
+            for (binding_index, (name, _, _)) in binding_infos.iter().enumerate() {
+                let local_name = if conflicting_pattern_bindings.contains(name) {
+                    let local_name =
+                        self.match_pattern_local_name(match_expr, i, binding_index, name);
+                    self.set_local_alias(name, local_name.clone());
+                    local_name
+                } else {
+                    name.clone()
+                };
+                local_names.push(local_name);
+            }
 
+            // Generate pattern matching code
             self.output
-                .push_str(&format!("    (if (result {})\n", result_type_name));
+                .push_str(&format!("    local.get ${}\n", match_local));
+            let bindings = self.generate_pattern_match(
+                &arm.pattern,
+                scrutinee_source_ty.as_ref(),
+                match_local,
+            )?;
+
+            // A guarded arm binds the pattern variables and evaluates the guard
+            // only after a structural match; a false guard falls through to the
+            // next arm like a failed pattern.
+            if arm.guard.is_some() {
+                self.output
+                    .push_str("    (if (result i32) ;; match guard\n");
+            } else {
+                self.output
+                    .push_str(&format!("    (if (result {})\n", result_type_name));
+            }
             self.output.push_str("      (then\n");
 
-            self.push_scope();
             // Apply bindings
             for (binding_index, (name, load_code)) in bindings.into_iter().enumerate() {
                 let (_, wasm_ty, source_ty) =
@@ -13877,14 +20063,7 @@ impl WasmCodeGen {
                             name
                         ))
                     })?;
-                let local_name = if conflicting_pattern_bindings.contains(&name) {
-                    let local_name =
-                        self.match_pattern_local_name(match_expr, i, binding_index, &name);
-                    self.set_local_alias(&name, local_name.clone());
-                    local_name
-                } else {
-                    name.clone()
-                };
+                let local_name = &local_names[binding_index];
                 self.output.push_str(&load_code);
                 self.output
                     .push_str(&format!("        local.set ${}\n", local_name));
@@ -13894,6 +20073,18 @@ impl WasmCodeGen {
                 }
             }
 
+            if let Some(guard) = &arm.guard {
+                self.generate_expr(guard)?;
+                self.output.push_str("      )\n");
+                self.output.push_str("      (else\n");
+                self.output.push_str("        i32.const 0\n");
+                self.output.push_str("      )\n");
+                self.output.push_str("    )\n");
+                self.output
+                    .push_str(&format!("    (if (result {})\n", result_type_name));
+                self.output.push_str("      (then\n");
+            }
+
             // Generate arm body as expression (match arms should produce values)
             self.generate_block_internal(&arm.body, true, expected_source)?;
             self.pop_scope();
@@ -13946,6 +20137,15 @@ impl WasmCodeGen {
                 self.output
                     .push_str("    i32.const 1 ;; var always matches\n");
             }
+            Pattern::Binding(name, inner) => {
+                // Store the whole scrutinee before the inner pattern reuses the
+                // shared match temporaries.
+                let local_name = self.lookup_local_alias(name).unwrap_or(name).to_string();
+                self.output
+                    .push_str(&format!("    local.tee ${} ;; @ binding\n", local_name));
+                bindings.push((name.clone(), format!("    local.get ${}\n", local_name)));
+                bindings.extend(self.generate_pattern_match(inner, source_ty, match_local)?);
+            }
             Pattern::Literal(lit) => match lit {
                 Literal::Int(n) => {
                     if matches!(source_ty, Some(Type::Named(name)) if name == "Int64") {
@@ -13967,6 +20167,10 @@ impl WasmCodeGen {
                     self.output.push_str(&format!("    f64.const {}\n", value));
                     self.output.push_str("    f64.eq\n");
                 }
+                Literal::Decimal(value) => {
+                    self.output.push_str(&format!("    i64.const {}\n", value));
+                    self.output.push_str("    i64.eq\n");
+                }
                 Literal::Char(c) => {
                     self.output
                         .push_str(&format!("    i32.const {}\n", *c as u32));
@@ -13982,6 +20186,20 @@ impl WasmCodeGen {
                     self.output.push_str("    i32.eq\n");
                 }
             },
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                // `start <= v <= last` as one unsigned compare of `v - start`,
+                // so the scrutinee on the stack is read once.
+                let last = if *inclusive { *end } else { end - 1 };
+                self.output.push_str(&format!("    i32.const {}\n", start));
+                self.output.push_str("    i32.sub\n");
+                self.output
+                    .push_str(&format!("    i32.const {}\n", (last - start) as u32 as i32));
+                self.output.push_str("    i32.le_u ;; within range\n");
+            }
             Pattern::EmptyList => {
                 // Check if list is empty
                 self.output.push_str("    call $list_length\n");
@@ -14282,6 +20500,7 @@ impl WasmCodeGen {
         field_patterns: &[(String, Pattern)],
         rest: Option<&String>,
     ) -> Result<Vec<(String, String)>, CodeGenError> {
+        let record_name = Self::pattern_record_name(record_name, source_ty);
         let mut bindings = Vec::new();
 
         // Type checking has already proven the scrutinee is this record type.
@@ -14374,6 +20593,10 @@ impl WasmCodeGen {
                         self.output.push_str(&format!("    f64.const {}\n", value));
                         self.output.push_str("    f64.eq\n");
                     }
+                    Literal::Decimal(value) => {
+                        self.output.push_str(&format!("    i64.const {}\n", value));
+                        self.output.push_str("    i64.eq\n");
+                    }
                     Literal::Unit => {
                         self.output.push_str("    i32.const 0\n");
                         self.output.push_str("    i32.eq\n");
@@ -14576,6 +20799,7 @@ impl WasmCodeGen {
     }
 
     fn generate_while_expr(&mut self, while_expr: &WhileExpr) -> Result<(), CodeGenError> {
+        self.output.push_str("    (block $while_exit\n");
         self.output.push_str("    (loop $while_loop\n");
 
         // Generate condition
@@ -14585,13 +20809,114 @@ impl WasmCodeGen {
         self.output.push_str("        (then\n");
 
         // Generate body
-        self.generate_block(&while_expr.body)?;
+        self.loop_labels
+            .push(("$while_exit", "$while_loop", self.with_arena_scopes.len()));
+        let body = self.generate_block(&while_expr.body);
+        self.loop_labels.pop();
+        body?;
 
         // Loop back
         self.output.push_str("          br $while_loop\n");
         self.output.push_str("        )\n");
         self.output.push_str("      )\n");
         self.output.push_str("    )\n");
+        self.output.push_str("    )\n");
+
+        // While loops return unit
+        self.output.push_str("    i32.const 0 ;; unit\n");
+
+        Ok(())
+    }
+
+    fn generate_while_let_expr(&mut self, while_let: &WhileLetExpr) -> Result<(), CodeGenError> {
+        let value_source_ty = self.infer_expr_source_type(&while_let.expr);
+        let value_wasm_ty = if let Some(source_ty) = &value_source_ty {
+            self.convert_type(source_ty)?
+        } else {
+            self.infer_expr_type(&while_let.expr)?
+        };
+        let match_local = self.match_temp_local(value_wasm_ty);
+
+        let mut binding_infos = Vec::new();
+        self.collect_pattern_binding_types(
+            &while_let.pattern,
+            value_source_ty.as_ref(),
+            value_wasm_ty,
+            &mut binding_infos,
+        )?;
+
+        self.output.push_str(
+            "    (block $while_let_exit
+",
+        );
+        self.output.push_str(
+            "    (loop $while_let_loop
+",
+        );
+
+        // Re-evaluate the value on every iteration and leave the loop as soon
+        // as it stops matching.
+        self.generate_expr(&while_let.expr)?;
+        self.output.push_str(&format!(
+            "    local.set ${}
+",
+            match_local
+        ));
+        self.output.push_str(&format!(
+            "    local.get ${}
+",
+            match_local
+        ));
+        self.push_scope();
+        let bindings =
+            self.generate_pattern_match(&while_let.pattern, value_source_ty.as_ref(), match_local)?;
+        self.output.push_str(
+            "    i32.eqz
+",
+        );
+        self.output.push_str(
+            "    br_if $while_let_exit
+",
+        );
+
+        for (binding_index, (name, load_code)) in bindings.into_iter().enumerate() {
+            let (_, wasm_ty, source_ty) =
+                binding_infos.get(binding_index).cloned().ok_or_else(|| {
+                    CodeGenError::UnsupportedFeature(format!(
+                        "missing pattern binding metadata for '{}'",
+                        name
+                    ))
+                })?;
+            self.output.push_str(&load_code);
+            self.output.push_str(&format!("    local.set ${}\n", name));
+            self.set_local_type(&name, wasm_ty);
+            if let Some(source_ty) = source_ty {
+                self.set_local_source_type(&name, source_ty);
+            }
+        }
+
+        // Generate the body as an expression so it leaves exactly one value,
+        // which is discarded before looping back.
+        self.loop_labels.push((
+            "$while_let_exit",
+            "$while_let_loop",
+            self.with_arena_scopes.len(),
+        ));
+        let body = self.generate_block_internal(&while_let.body, true, None);
+        self.loop_labels.pop();
+        body?;
+        let body_leaves_value = match &while_let.body.expr {
+            Some(expr) => self.expr_leaves_value(expr) || self.expr_synthesizes_unit_value(expr),
+            None => true,
+        };
+        if body_leaves_value {
+            self.output.push_str("    drop\n");
+        }
+        self.pop_scope();
+
+        self.output.push_str("    br $while_let_loop\n");
+        self.output.push_str("    )\n");
+        self.output.push_str("    )\n");
 
         // While loops return unit
         self.output.push_str("    i32.const 0 ;; unit\n");
@@ -14599,6 +20924,96 @@ impl WasmCodeGen {
         Ok(())
     }
 
+    /// `loop { ... }` runs its body until a `break`. The exit block carries
+    /// the loop's value when its `break`s have one; the loop itself never
+    /// falls through, so the block ends `unreachable`.
+    fn generate_loop_expr(&mut self, body: &BlockExpr) -> Result<(), CodeGenError> {
+        let result_ty = self.loop_result_type(body)?;
+        match result_ty {
+            Some(ty) => self.output.push_str(&format!(
+                "    (block $loop_exit (result {})\n",
+                self.wasm_type_str(ty)
+            )),
+            None => self.output.push_str("    (block $loop_exit\n"),
+        }
+        self.output.push_str("    (loop $loop_continue\n");
+
+        self.loop_labels
+            .push(("$loop_exit", "$loop_continue", self.with_arena_scopes.len()));
+        let generated = self.generate_block(body);
+        self.loop_labels.pop();
+        generated?;
+
+        // Loop back; any value the body left is discarded by the branch.
+        self.output.push_str("    br $loop_continue\n");
+        self.output.push_str("    )\n");
+        if result_ty.is_some() {
+            self.output.push_str("    unreachable\n");
+        }
+        self.output.push_str("    )\n");
+
+        if result_ty.is_none() {
+            self.output.push_str("    i32.const 0 ;; unit\n");
+        }
+
+        Ok(())
+    }
+
+    /// Wasm type of the value a `loop` leaves through its `break`s, if any.
+    fn loop_result_type(&self, body: &BlockExpr) -> Result<Option<WasmType>, CodeGenError> {
+        Self::loop_break_value(body)
+            .map(|value| match self.infer_expr_source_type(value) {
+                Some(source_ty) => self.convert_type(&source_ty),
+                None => self.infer_expr_type(value),
+            })
+            .transpose()
+    }
+
+    fn innermost_loop_labels(
+        &self,
+        keyword: &str,
+    ) -> Result<(&'static str, &'static str, usize), CodeGenError> {
+        self.loop_labels.last().copied().ok_or_else(|| {
+            CodeGenError::UnsupportedFeature(format!("`{}` outside of a loop", keyword))
+        })
+    }
+
+    /// The first `break value` leaving the loop with this body. Nested loops
+    /// and lambdas are skipped, since their `break`s leave them instead.
+    fn loop_break_value(body: &BlockExpr) -> Option<&Expr> {
+        body.statements
+            .iter()
+            .find_map(|stmt| match stmt {
+                Stmt::Break(value) => value.as_deref(),
+                Stmt::Binding(bind) => Self::loop_break_value_in_expr(&bind.value),
+                Stmt::Assignment(assign) => Self::loop_break_value_in_expr(&assign.value),
+                Stmt::Expr(expr) => Self::loop_break_value_in_expr(expr),
+                Stmt::Continue => None,
+            })
+            .or_else(|| {
+                body.expr
+                    .as_deref()
+                    .and_then(Self::loop_break_value_in_expr)
+            })
+    }
+
+    fn loop_break_value_in_expr(expr: &Expr) -> Option<&Expr> {
+        match &expr.kind {
+            ExprKind::Block(block) => Self::loop_break_value(block),
+            ExprKind::Then(then) => std::iter::once(&then.then_block)
+                .chain(then.else_ifs.iter().map(|(_, block)| block))
+                .chain(&then.else_block)
+                .find_map(Self::loop_break_value),
+            ExprKind::Match(match_expr) => match_expr
+                .arms
+                .iter()
+                .find_map(|arm| Self::loop_break_value(&arm.body)),
+            ExprKind::With(with) => Self::loop_break_value(&with.body),
+            ExprKind::WithLifetime(with) => Self::loop_break_value(&with.body),
+            _ => None,
+        }
+    }
+
     fn generate_with_expr(&mut self, with_expr: &WithExpr) -> Result<(), CodeGenError> {
         self.generate_with_expr_with_expected_source(with_expr, None)
     }
@@ -14701,8 +21116,9 @@ impl WasmCodeGen {
         self.with_arena_depth += 1;
 
         let arena_addr = self.next_arena_addr;
-        self.next_arena_addr += ARENA_SIZE_BYTES;
+        self.next_arena_addr += self.arena_size();
         self.arena_stack.push(arena_addr);
+        self.with_arena_scopes.push((depth, arena_addr));
 
         self.output.push_str("    ;; Enter with Arena scope\n");
         self.output.push_str("    global.get $current_arena\n");
@@ -14717,6 +21133,15 @@ impl WasmCodeGen {
     }
 
     fn end_with_arena_scope(&mut self, depth: usize, arena_addr: u32) -> Result<(), CodeGenError> {
+        self.emit_with_arena_exit(depth, arena_addr);
+
+        self.arena_stack.pop();
+        self.with_arena_scopes.pop();
+        self.with_arena_depth -= 1;
+        Ok(())
+    }
+
+    fn emit_with_arena_exit(&mut self, depth: usize, arena_addr: u32) {
         self.output.push_str("    ;; Exit with Arena scope\n");
         self.output
             .push_str(&format!("    i32.const {}\n", arena_addr));
@@ -14724,10 +21149,16 @@ impl WasmCodeGen {
         self.output
             .push_str(&format!("    local.get $with_prev_arena_{}\n", depth));
         self.output.push_str("    global.set $current_arena\n");
+    }
 
-        self.arena_stack.pop();
-        self.with_arena_depth -= 1;
-        Ok(())
+    /// Exit, innermost first, the `with Arena` scopes opened after the first
+    /// `open_at_loop_entry`, before a `break` or `continue` branches past
+    /// their normal exit code.
+    fn exit_with_arena_scopes_above(&mut self, open_at_loop_entry: usize) {
+        let scopes = self.with_arena_scopes[open_at_loop_entry..].to_vec();
+        for (depth, arena_addr) in scopes.into_iter().rev() {
+            self.emit_with_arena_exit(depth, arena_addr);
+        }
     }
 
     fn generate_clone_expr(&mut self, clone: &CloneExpr) -> Result<(), CodeGenError> {
@@ -14819,9 +21250,8 @@ impl WasmCodeGen {
     }
 
     fn generate_freeze_expr(&mut self, expr: &Expr) -> Result<(), CodeGenError> {
-        // Freeze expressions create an immutable copy at the type level. Runtime
-        // records currently have no metadata header, so codegen must preserve the
-        // registered field layout exactly instead of inventing a frozen flag slot.
+        // Frozen is a type-level flag: the checker rejects updates through the
+        // frozen type, so the record itself is passed through unchanged.
         let source_ty = self.infer_expr_source_type(expr).ok_or_else(|| {
             CodeGenError::UnsupportedFeature(
                 "freeze requires an expression with a known record type".to_string(),
@@ -14835,38 +21265,12 @@ impl WasmCodeGen {
                 )
             })?
             .to_string();
-        let record_size = self.instantiated_record_size(&record_name, Some(&source_ty), 0);
-
-        // Generate the expression to get the record to freeze
-        self.generate_expr(expr)?;
-        self.output.push_str("    local.set $freeze_tmp\n");
 
         self.output.push_str(&format!(
-            "    ;; Freeze {} by copying record layout\n",
+            "    ;; Freeze {} (type-level only)\n",
             record_name
         ));
-
-        // Allocate new record
-        self.output.push_str(&format!(
-            "    i32.const {} ;; frozen record size\n",
-            record_size
-        ));
-        self.output.push_str("    call $allocate\n");
-        self.output.push_str("    local.set $clone_tmp\n");
-
-        // Copy the entire record
-        self.output
-            .push_str("    local.get $clone_tmp ;; destination\n");
-        self.output
-            .push_str("    local.get $freeze_tmp ;; source\n");
-        self.output
-            .push_str(&format!("    i32.const {} ;; size\n", record_size));
-        self.output.push_str("    memory.copy\n");
-
-        // Return the frozen record
-        self.output.push_str("    local.get $clone_tmp\n");
-
-        Ok(())
+        self.generate_expr(expr)
     }
 
     // Generate specialized versions for generic list functions
@@ -15000,6 +21404,66 @@ impl WasmCodeGen {
         )))
     }
 
+    /// Import each declared foreign function and register its signature, so
+    /// calls to it compile like calls to program functions.
+    fn generate_foreign_imports(&mut self, program: &Program) -> Result<(), CodeGenError> {
+        if self.foreign_functions.is_empty() {
+            return Ok(());
+        }
+
+        self.output.push_str("  ;; Foreign imports\n");
+        for function in self.foreign_functions.clone() {
+            let binding = function.binding();
+            let declared = program.declarations.iter().any(|decl| {
+                matches!(Self::decl_codegen_item(decl), TopDecl::Function(func) if func.name == binding)
+            });
+            if declared {
+                return Err(CodeGenError::UnsupportedFeature(format!(
+                    "function '{}' is declared by the program and imported as foreign function {}",
+                    binding, function
+                )));
+            }
+
+            let params = function
+                .params
+                .iter()
+                .map(|ty| self.convert_signature_type(ty, &[]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let result = self.convert_signature_result_type(&function.result, &[])?;
+
+            self.output.push_str(&format!(
+                "  (import \"{}\" \"{}\" (func ${}",
+                function.module, function.name, binding
+            ));
+            for param in &params {
+                self.output
+                    .push_str(&format!(" (param {})", self.wasm_type_str(*param)));
+            }
+            if let Some(result) = result {
+                self.output
+                    .push_str(&format!(" (result {})", self.wasm_type_str(result)));
+            }
+            self.output.push_str("))\n");
+
+            self.functions.insert(
+                binding.clone(),
+                FunctionSig {
+                    _params: params,
+                    result,
+                },
+            );
+            self.function_source_sigs.insert(
+                binding,
+                FunctionSourceSig {
+                    type_params: vec![],
+                    params: function.params.clone(),
+                    result: Some(function.result.clone()),
+                },
+            );
+        }
+        Ok(())
+    }
+
     fn format_import(import: &ImportDecl) -> String {
         let module_name = import.module_path.join(".");
 
@@ -15015,7 +21479,7 @@ impl WasmCodeGen {
             Type::Named(name)
                 if matches!(
                     name.as_str(),
-                    "Int32" | "Int64" | "Float64" | "Boolean" | "Char" | "Unit"
+                    "Int32" | "Int64" | "Float64" | "Decimal" | "Boolean" | "Char" | "Unit"
                 )
         )
     }
@@ -15030,7 +21494,7 @@ impl WasmCodeGen {
         }
 
         Err(CodeGenError::UnsupportedFeature(format!(
-            "Exported function '{}' {} type {} requires a composite host ABI; v0.0.1 exports support only scalar Int32, Int64, Float64, Boolean, Char, and ()",
+            "Exported function '{}' {} type {} requires a composite host ABI; v0.0.1 exports support only scalar Int32, Int64, Float64, Decimal, Boolean, Char, and ()",
             export_name, position, ty
         )))
     }
@@ -15057,13 +21521,96 @@ impl WasmCodeGen {
         Ok(())
     }
 
+    /// Reject exported signatures that mention closures, temporal types, or
+    /// un-monomorphized type parameters. Scalars, String, records, lists, and
+    /// Option are representable and are left to the host ABI checks.
+    fn ensure_exportable_signature(
+        func: &FunDecl,
+        source_sig: Option<&FunctionSourceSig>,
+    ) -> Result<(), CodeGenError> {
+        let mut type_params: Vec<&str> = func
+            .type_params
+            .iter()
+            .map(|param| param.name.as_str())
+            .collect();
+        if let Some(sig) = source_sig {
+            type_params.extend(sig.type_params.iter().map(String::as_str));
+        }
+
+        let positions = func
+            .params
+            .iter()
+            .map(|param| (format!("parameter '{}'", param.name), &param.ty))
+            .chain(
+                source_sig
+                    .and_then(|sig| sig.result.as_ref())
+                    .or(func.return_type.as_ref())
+                    .map(|ty| ("return".to_string(), ty)),
+            );
+
+        for (position, ty) in positions {
+            if let Some((inner, problem)) = Self::non_exportable_type(ty, &type_params) {
+                let reason = if inner == ty {
+                    format!("{} type {} {}", position, ty, problem)
+                } else {
+                    format!(
+                        "{} type {} contains {}, which {}",
+                        position, ty, inner, problem
+                    )
+                };
+                return Err(CodeGenError::NonExportableSignature {
+                    function: func.name.clone(),
+                    reason,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The record a pattern destructures. Unnamed `val { .. } = value`
+    /// patterns leave the name empty and take it from the value's type.
+    fn pattern_record_name<'a>(record_name: &'a str, source_ty: Option<&'a Type>) -> &'a str {
+        if !record_name.is_empty() {
+            return record_name;
+        }
+        match source_ty {
+            Some(Type::Named(name) | Type::Generic(name, _)) => name,
+            Some(Type::Refined(base, _)) => Self::pattern_record_name(record_name, Some(base)),
+            _ => record_name,
+        }
+    }
+
+    /// Find the first part of `ty` that cannot cross the Wasm boundary.
+    fn non_exportable_type<'t>(
+        ty: &'t Type,
+        type_params: &[&str],
+    ) -> Option<(&'t Type, &'static str)> {
+        match ty {
+            Type::Function(..) => Some((ty, "is a function type with no Wasm representation")),
+            Type::Temporal(..) => Some((
+                ty,
+                "carries temporal parameters that only exist at compile time",
+            )),
+            Type::Named(name) if type_params.contains(&name.as_str()) => Some((
+                ty,
+                "is an un-monomorphized type parameter and requires a concrete ABI",
+            )),
+            Type::Named(_) => None,
+            Type::Generic(_, args) => args
+                .iter()
+                .find_map(|arg| Self::non_exportable_type(arg, type_params)),
+            Type::Refined(base, _) => Self::non_exportable_type(base, type_params),
+        }
+    }
+
     fn ensure_scalar_host_export_global(name: &str, ty: &Type) -> Result<(), CodeGenError> {
         if Self::is_scalar_host_export_type(ty) {
             return Ok(());
         }
 
         Err(CodeGenError::UnsupportedFeature(format!(
-            "Exported top-level binding '{}' has type {} which requires a composite host ABI; v0.0.1 global exports support only scalar Int32, Int64, Float64, Boolean, Char, and ()",
+            "Exported top-level binding '{}' has type {} which requires a composite host ABI; v0.0.1 global exports support only scalar Int32, Int64, Float64, Decimal, Boolean, Char, and ()",
             name, ty
         )))
     }
@@ -15080,6 +21627,10 @@ impl WasmCodeGen {
 
                 match &*export_decl.item {
                     TopDecl::Function(func) => {
+                        Self::ensure_exportable_signature(
+                            func,
+                            self.function_source_sigs.get(&func.name),
+                        )?;
                         if !func.type_params.is_empty() {
                             return Err(CodeGenError::UnsupportedFeature(format!(
                                 "Exported generic function '{}' requires a concrete ABI and is not supported yet",
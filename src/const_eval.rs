@@ -0,0 +1,273 @@
+//! # Constant Evaluation
+//!
+//! Folds integer, float, and boolean expressions built only from literals
+//! into literal nodes, so `val x = 2 + 3 * 4` reaches codegen as `14`.
+//!
+//! Integer literals take their width from context (`Int32` by default,
+//! `Int64` under an `Int64` expectation), which the AST alone does not
+//! know. Folding therefore only rewrites integer arithmetic when every
+//! width agrees on the result: untyped literals fold while the result fits
+//! `Int32`, and `Int64` constants (`7L`, i.e. `7 as Int64`) fold while the
+//! result fits `Int64`. Anything else is left for the type checker, which
+//! knows the width and reports overflow through [`eval_int_binary`].
+
+use std::fmt;
+
+use crate::ast::{
+    visit_program_exprs_mut, BinaryExpr, BinaryOp, CastExpr, Expr, ExprKind, Program, Type,
+    UnaryExpr, UnaryOp,
+};
+
+/// Integer width a constant expression is evaluated at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntWidth {
+    Int32,
+    Int64,
+}
+
+impl fmt::Display for IntWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntWidth::Int32 => write!(f, "Int32"),
+            IntWidth::Int64 => write!(f, "Int64"),
+        }
+    }
+}
+
+/// A constant integer expression whose value does not fit its width.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstOverflow {
+    /// The overflowing operation with its operands evaluated, e.g. `2147483647 + 1`
+    pub expression: String,
+    pub width: IntWidth,
+}
+
+impl fmt::Display for ConstOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} overflows {}", self.expression, self.width)
+    }
+}
+
+/// Returns a copy of `program` with every constant subexpression folded.
+pub fn fold_constants(program: &Program) -> Program {
+    let mut folded = program.clone();
+    visit_program_exprs_mut(&mut folded, &mut fold_expr);
+    folded
+}
+
+/// Evaluates a binary integer expression built only from literals at
+/// `width`. Returns `None` when the expression is not constant (or divides
+/// by zero, which is reported separately).
+pub fn eval_int_binary(binary: &BinaryExpr, width: IntWidth) -> Option<Result<i64, ConstOverflow>> {
+    let left = match eval_int(&binary.left, width)? {
+        Ok(value) => value,
+        Err(overflow) => return Some(Err(overflow)),
+    };
+    let right = match eval_int(&binary.right, width)? {
+        Ok(value) => value,
+        Err(overflow) => return Some(Err(overflow)),
+    };
+    if matches!(binary.op, BinaryOp::Div | BinaryOp::Mod) && right == 0 {
+        return None;
+    }
+    let result = match binary.op {
+        BinaryOp::Add => left.checked_add(right),
+        BinaryOp::Sub => left.checked_sub(right),
+        BinaryOp::Mul => left.checked_mul(right),
+        BinaryOp::Div => left.checked_div(right),
+        BinaryOp::Mod => left.checked_rem(right),
+        _ => return None,
+    };
+    Some(
+        result
+            .filter(|value| fits(*value, width))
+            .ok_or_else(|| ConstOverflow {
+                expression: format!("{} {} {}", left, binary.op, right),
+                width,
+            }),
+    )
+}
+
+fn eval_int(expr: &Expr, width: IntWidth) -> Option<Result<i64, ConstOverflow>> {
+    match &expr.kind {
+        ExprKind::IntLit(value) if fits(*value, width) => Some(Ok(*value)),
+        ExprKind::Unary(UnaryExpr {
+            op: UnaryOp::Neg,
+            expr,
+        }) => {
+            // `-2147483648` parses as a negated literal that only fits once
+            // negated.
+            if let ExprKind::IntLit(value) = &expr.kind {
+                return value
+                    .checked_neg()
+                    .filter(|value| fits(*value, width))
+                    .map(Ok);
+            }
+            Some(eval_int(expr, width)?.and_then(|value| {
+                value
+                    .checked_neg()
+                    .filter(|value| fits(*value, width))
+                    .ok_or_else(|| ConstOverflow {
+                        expression: format!("-{}", value),
+                        width,
+                    })
+            }))
+        }
+        ExprKind::Cast(CastExpr { expr, target })
+            if width == IntWidth::Int64 && is_int64(target) =>
+        {
+            eval_int(expr, width)
+        }
+        ExprKind::Binary(binary) => eval_int_binary(binary, width),
+        _ => None,
+    }
+}
+
+fn fits(value: i64, width: IntWidth) -> bool {
+    match width {
+        IntWidth::Int32 => i32::try_from(value).is_ok(),
+        IntWidth::Int64 => true,
+    }
+}
+
+fn is_int64(ty: &Type) -> bool {
+    matches!(ty, Type::Named(name) if name == "Int64")
+}
+
+/// A literal value as seen by the folder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Const {
+    /// Untyped integer literal in `Int32` range
+    Int(i64),
+    /// `Int64` constant, written `n as Int64` (or `nL`)
+    Int64(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Const {
+    fn of(expr: &Expr) -> Option<Self> {
+        match &expr.kind {
+            ExprKind::IntLit(value) if fits(*value, IntWidth::Int32) => Some(Const::Int(*value)),
+            ExprKind::Cast(CastExpr { expr, target }) if is_int64(target) => match &expr.kind {
+                ExprKind::IntLit(value) => Some(Const::Int64(*value)),
+                _ => None,
+            },
+            ExprKind::FloatLit(value) => Some(Const::Float(*value)),
+            ExprKind::BoolLit(value) => Some(Const::Bool(*value)),
+            _ => None,
+        }
+    }
+
+    fn into_kind(self) -> ExprKind {
+        match self {
+            Const::Int(value) => ExprKind::IntLit(value),
+            Const::Int64(value) => ExprKind::Cast(CastExpr {
+                expr: Box::new(Expr::new(ExprKind::IntLit(value))),
+                target: Type::Named("Int64".to_string()),
+            }),
+            Const::Float(value) => ExprKind::FloatLit(value),
+            Const::Bool(value) => ExprKind::BoolLit(value),
+        }
+    }
+}
+
+/// Folds `expr` bottom-up through the operators the folder understands.
+/// Other children are reached by the surrounding program traversal.
+fn fold_expr(expr: &mut Expr) {
+    let folded = match &mut expr.kind {
+        ExprKind::Binary(binary) => {
+            fold_expr(&mut binary.left);
+            fold_expr(&mut binary.right);
+            fold_binary(binary)
+        }
+        ExprKind::Unary(unary) => {
+            fold_expr(&mut unary.expr);
+            fold_unary(unary)
+        }
+        ExprKind::Cast(cast) => {
+            fold_expr(&mut cast.expr);
+            None
+        }
+        _ => None,
+    };
+    if let Some(value) = folded {
+        expr.kind = value.into_kind();
+    }
+}
+
+fn fold_binary(binary: &BinaryExpr) -> Option<Const> {
+    let left = Const::of(&binary.left)?;
+    let right = Const::of(&binary.right)?;
+    match (left, right) {
+        (Const::Int(left), Const::Int(right)) => {
+            fold_int(&binary.op, left, right, IntWidth::Int32, Const::Int)
+        }
+        (Const::Int(left) | Const::Int64(left), Const::Int(right) | Const::Int64(right)) => {
+            fold_int(&binary.op, left, right, IntWidth::Int64, Const::Int64)
+        }
+        (Const::Float(left), Const::Float(right)) => fold_float(&binary.op, left, right),
+        (Const::Bool(left), Const::Bool(right)) => match binary.op {
+            BinaryOp::And => Some(Const::Bool(left && right)),
+            BinaryOp::Or => Some(Const::Bool(left || right)),
+            BinaryOp::Eq => Some(Const::Bool(left == right)),
+            BinaryOp::Ne => Some(Const::Bool(left != right)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn fold_int(
+    op: &BinaryOp,
+    left: i64,
+    right: i64,
+    width: IntWidth,
+    make: fn(i64) -> Const,
+) -> Option<Const> {
+    let value = match op {
+        BinaryOp::Add => left.checked_add(right)?,
+        BinaryOp::Sub => left.checked_sub(right)?,
+        BinaryOp::Mul => left.checked_mul(right)?,
+        BinaryOp::Div => left.checked_div(right)?,
+        BinaryOp::Mod => left.checked_rem(right)?,
+        BinaryOp::Eq => return Some(Const::Bool(left == right)),
+        BinaryOp::Ne => return Some(Const::Bool(left != right)),
+        BinaryOp::Lt => return Some(Const::Bool(left < right)),
+        BinaryOp::Le => return Some(Const::Bool(left <= right)),
+        BinaryOp::Gt => return Some(Const::Bool(left > right)),
+        BinaryOp::Ge => return Some(Const::Bool(left >= right)),
+        BinaryOp::And | BinaryOp::Or => return None,
+    };
+    fits(value, width).then(|| make(value))
+}
+
+fn fold_float(op: &BinaryOp, left: f64, right: f64) -> Option<Const> {
+    let value = match op {
+        BinaryOp::Add => left + right,
+        BinaryOp::Sub => left - right,
+        BinaryOp::Mul => left * right,
+        BinaryOp::Div => left / right,
+        BinaryOp::Eq => return Some(Const::Bool(left == right)),
+        BinaryOp::Ne => return Some(Const::Bool(left != right)),
+        BinaryOp::Lt => return Some(Const::Bool(left < right)),
+        BinaryOp::Le => return Some(Const::Bool(left <= right)),
+        BinaryOp::Gt => return Some(Const::Bool(left > right)),
+        BinaryOp::Ge => return Some(Const::Bool(left >= right)),
+        BinaryOp::Mod | BinaryOp::And | BinaryOp::Or => return None,
+    };
+    // Infinities and NaN stay as runtime arithmetic rather than literals.
+    value.is_finite().then_some(Const::Float(value))
+}
+
+fn fold_unary(unary: &UnaryExpr) -> Option<Const> {
+    match (&unary.op, Const::of(&unary.expr)?) {
+        (UnaryOp::Neg, Const::Int(value)) => {
+            fits(-value, IntWidth::Int32).then(|| Const::Int(-value))
+        }
+        (UnaryOp::Neg, Const::Int64(value)) => value.checked_neg().map(Const::Int64),
+        (UnaryOp::Neg, Const::Float(value)) => Some(Const::Float(-value)),
+        (UnaryOp::Not, Const::Bool(value)) => Some(Const::Bool(!value)),
+        _ => None,
+    }
+}
@@ -1 +1,350 @@
-// Debug visualizer module
+//! Debug visualizer for parsed programs.
+//!
+//! Renders the AST as an indented tree with one node per line. When the
+//! original source is supplied, each expression line also shows the exact
+//! source text its span covers, which makes desugared forms such as pipes
+//! and `some` easy to trace back to what was written. After type checking,
+//! each expression line can also show the type the checker resolved for it.
+
+use crate::ast::*;
+use crate::type_checker::{format_typed_type, TypeChecker};
+
+/// Render `program` as an indented tree of node labels.
+pub fn visualize(program: &Program) -> String {
+    let mut visualizer = Visualizer {
+        source: None,
+        types: None,
+        out: String::new(),
+    };
+    visualizer.program(program);
+    visualizer.out
+}
+
+/// Render `program` as an indented tree, annotating every expression with
+/// the slice of `source` it was parsed from.
+///
+/// `source` must be the text `program` was parsed from; nodes whose span
+/// is missing or falls outside `source` are rendered without a slice.
+pub fn visualize_with_source(program: &Program, source: &str) -> String {
+    let mut visualizer = Visualizer {
+        source: Some(source),
+        types: None,
+        out: String::new(),
+    };
+    visualizer.program(program);
+    visualizer.out
+}
+
+/// Render `program` as an indented tree, annotating every expression with
+/// the type `checker` recorded for it while checking `program`.
+///
+/// `checker` must have checked this `program` (or a clone with the same node
+/// ids); expressions it recorded no type for are rendered without one.
+pub fn visualize_with_types(program: &Program, checker: &TypeChecker) -> String {
+    let mut visualizer = Visualizer {
+        source: None,
+        types: Some(checker),
+        out: String::new(),
+    };
+    visualizer.program(program);
+    visualizer.out
+}
+
+struct Visualizer<'a> {
+    source: Option<&'a str>,
+    types: Option<&'a TypeChecker>,
+    out: String,
+}
+
+impl Visualizer<'_> {
+    fn line(&mut self, depth: usize, label: &str) {
+        for _ in 0..depth {
+            self.out.push_str("  ");
+        }
+        self.out.push_str(label);
+        self.out.push('\n');
+    }
+
+    fn program(&mut self, program: &Program) {
+        self.line(0, "Program");
+        for decl in &program.declarations {
+            self.top_decl(1, decl);
+        }
+    }
+
+    fn top_decl(&mut self, depth: usize, decl: &TopDecl) {
+        match decl {
+            TopDecl::Record(record) => self.line(depth, &format!("Record {}", record.name)),
+            TopDecl::Context(context) => self.line(depth, &format!("Context {}", context.name)),
+            TopDecl::Impl(impl_block) => {
+                self.line(depth, &format!("Impl {}", impl_block.target));
+                for func in &impl_block.functions {
+                    self.function(depth + 1, func);
+                }
+            }
+            TopDecl::Function(func) => self.function(depth, func),
+            TopDecl::Binding(binding) => self.binding(depth, binding),
+            TopDecl::Export(export) => {
+                self.line(depth, "Export");
+                self.top_decl(depth + 1, &export.item);
+            }
+        }
+    }
+
+    fn function(&mut self, depth: usize, func: &FunDecl) {
+        self.line(depth, &format!("Function {}", func.name));
+        self.block(depth + 1, &func.body);
+    }
+
+    fn binding(&mut self, depth: usize, binding: &BindDecl) {
+        let keyword = if binding.mutable { "mut val" } else { "val" };
+        self.line(depth, &format!("Binding {} {:?}", keyword, binding.pattern));
+        self.expr(depth + 1, &binding.value);
+    }
+
+    fn block(&mut self, depth: usize, block: &BlockExpr) {
+        for stmt in &block.statements {
+            match stmt {
+                Stmt::Binding(binding) => self.binding(depth, binding),
+                Stmt::Assignment(assign) => {
+                    self.line(depth, &format!("Assign {}", assign.name));
+                    self.expr(depth + 1, &assign.value);
+                }
+                Stmt::Expr(expr) => self.expr(depth, expr),
+                Stmt::Break(value) => {
+                    self.line(depth, "Break");
+                    if let Some(value) = value {
+                        self.expr(depth + 1, value);
+                    }
+                }
+                Stmt::Continue => self.line(depth, "Continue"),
+            }
+        }
+        if let Some(expr) = &block.expr {
+            self.expr(depth, expr);
+        }
+    }
+
+    fn fields(&mut self, depth: usize, fields: &[FieldInit]) {
+        for field in fields {
+            match field {
+                FieldInit::Field { name, value } => {
+                    self.line(depth, &format!("Field {}", name));
+                    self.expr(depth + 1, value);
+                }
+                FieldInit::Spread(expr) => {
+                    self.line(depth, "Spread");
+                    self.expr(depth + 1, expr);
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, depth: usize, expr: &Expr) {
+        let mut label = expr_label(&expr.kind);
+        if let Some(ty) = self
+            .types
+            .and_then(|checker| checker.checked_expr_type(expr))
+        {
+            label.push_str(&format!(" : {}", format_typed_type(&ty)));
+        }
+        if let Some(text) = self
+            .source
+            .zip(expr.span)
+            .and_then(|(source, span)| span.slice(source))
+        {
+            label.push_str(&format!("  `{}`", text.escape_debug()));
+        }
+        self.line(depth, &label);
+
+        let depth = depth + 1;
+        match &expr.kind {
+            ExprKind::RecordLit(record) => self.fields(depth, &record.fields),
+            ExprKind::Clone(clone) => {
+                self.expr(depth, &clone.base);
+                self.fields(depth, &clone.updates.fields);
+            }
+            ExprKind::PrototypeClone(clone) => self.fields(depth, &clone.updates.fields),
+            ExprKind::Then(then) => {
+                self.expr(depth, &then.condition);
+                self.line(depth, "Then");
+                self.block(depth + 1, &then.then_block);
+                for (condition, block) in &then.else_ifs {
+                    self.line(depth, "ElseIf");
+                    self.expr(depth + 1, condition);
+                    self.block(depth + 1, block);
+                }
+                if let Some(block) = &then.else_block {
+                    self.line(depth, "Else");
+                    self.block(depth + 1, block);
+                }
+            }
+            ExprKind::While(while_expr) => {
+                self.expr(depth, &while_expr.condition);
+                self.block(depth, &while_expr.body);
+            }
+            ExprKind::WhileLet(while_let) => {
+                self.expr(depth, &while_let.expr);
+                self.line(depth, &format!("Pattern {:?}", while_let.pattern));
+                self.block(depth, &while_let.body);
+            }
+            ExprKind::Loop(body) => self.block(depth, body),
+            ExprKind::Match(match_expr) => {
+                self.expr(depth, &match_expr.expr);
+                for arm in &match_expr.arms {
+                    self.line(depth, &format!("Arm {:?}", arm.pattern));
+                    if let Some(guard) = &arm.guard {
+                        self.line(depth + 1, "Guard");
+                        self.expr(depth + 2, guard);
+                    }
+                    self.block(depth + 1, &arm.body);
+                }
+            }
+            ExprKind::Call(call) => {
+                self.expr(depth, &call.function);
+                for arg in &call.args {
+                    self.expr(depth, arg);
+                }
+            }
+            ExprKind::Binary(binary) => {
+                self.expr(depth, &binary.left);
+                self.expr(depth, &binary.right);
+            }
+            ExprKind::Unary(unary) => self.expr(depth, &unary.expr),
+            ExprKind::Cast(cast) => self.expr(depth, &cast.expr),
+            ExprKind::Pipe(pipe) => {
+                self.expr(depth, &pipe.expr);
+                match &pipe.target {
+                    PipeTarget::Ident(name) => self.line(depth, &format!("Target {}", name)),
+                    PipeTarget::Expr(target) => self.expr(depth, target),
+                }
+            }
+            ExprKind::With(with) => {
+                self.fields(depth, &with.bindings);
+                self.block(depth, &with.body);
+            }
+            ExprKind::WithLifetime(with) => self.block(depth, &with.body),
+            ExprKind::Block(block) => self.block(depth, block),
+            ExprKind::FieldAccess(inner, _) => self.expr(depth, inner),
+            ExprKind::FieldUpdate(update) => {
+                self.expr(depth, &update.object);
+                self.expr(depth, &update.value);
+            }
+            ExprKind::ListLit(items) | ExprKind::ArrayLit(items) => {
+                for item in items {
+                    self.expr(depth, item);
+                }
+            }
+            ExprKind::RangeLit(range) => {
+                self.expr(depth, &range.start);
+                self.expr(depth, &range.end);
+            }
+            ExprKind::Some(inner)
+            | ExprKind::Ok(inner)
+            | ExprKind::Err(inner)
+            | ExprKind::Try(inner)
+            | ExprKind::Freeze(inner)
+            | ExprKind::Await(inner)
+            | ExprKind::Spawn(inner) => self.expr(depth, inner),
+            ExprKind::Lambda(lambda) => self.expr(depth, &lambda.body),
+            ExprKind::IntLit(_)
+            | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
+            | ExprKind::StringLit(_)
+            | ExprKind::CharLit(_)
+            | ExprKind::BoolLit(_)
+            | ExprKind::Unit
+            | ExprKind::Ident(_)
+            | ExprKind::None => {}
+        }
+    }
+}
+
+fn expr_label(kind: &ExprKind) -> String {
+    match kind {
+        ExprKind::IntLit(value) => format!("IntLit {}", value),
+        ExprKind::FloatLit(value) => format!("FloatLit {}", value),
+        ExprKind::DecimalLit(value) => format!("DecimalLit {}d", format_decimal(*value)),
+        ExprKind::StringLit(value) => format!("StringLit {:?}", value),
+        ExprKind::CharLit(value) => format!("CharLit {:?}", value),
+        ExprKind::BoolLit(value) => format!("BoolLit {}", value),
+        ExprKind::Unit => "Unit".to_string(),
+        ExprKind::Ident(name) => format!("Ident {}", name),
+        ExprKind::RecordLit(record) => format!("RecordLit {}", record.name),
+        ExprKind::Clone(_) => "Clone".to_string(),
+        ExprKind::Freeze(_) => "Freeze".to_string(),
+        ExprKind::PrototypeClone(clone) => format!("PrototypeClone {}", clone.base),
+        ExprKind::Then(_) => "Then".to_string(),
+        ExprKind::While(_) => "While".to_string(),
+        ExprKind::WhileLet(_) => "WhileLet".to_string(),
+        ExprKind::Loop(_) => "Loop".to_string(),
+        ExprKind::Match(_) => "Match".to_string(),
+        ExprKind::Call(_) => "Call".to_string(),
+        ExprKind::Binary(binary) => format!("Binary {:?}", binary.op),
+        ExprKind::Unary(unary) => format!("Unary {:?}", unary.op),
+        ExprKind::Cast(cast) => format!("Cast {:?}", cast.target),
+        ExprKind::Pipe(pipe) => match pipe.op {
+            PipeOp::Pipe => "Pipe |>".to_string(),
+            PipeOp::Bar => "Pipe |".to_string(),
+        },
+        ExprKind::With(_) => "With".to_string(),
+        ExprKind::WithLifetime(with) => format!("WithLifetime ~{}", with.lifetime),
+        ExprKind::Block(_) => "Block".to_string(),
+        ExprKind::FieldAccess(_, field) => format!("FieldAccess .{}", field),
+        ExprKind::FieldUpdate(update) => format!("FieldUpdate .{}", update.field),
+        ExprKind::ListLit(_) => "ListLit".to_string(),
+        ExprKind::RangeLit(_) => "RangeLit".to_string(),
+        ExprKind::ArrayLit(_) => "ArrayLit".to_string(),
+        ExprKind::Some(_) => "Some".to_string(),
+        ExprKind::None => "None".to_string(),
+        ExprKind::Ok(_) => "Ok".to_string(),
+        ExprKind::Err(_) => "Err".to_string(),
+        ExprKind::Try(_) => "Try ?".to_string(),
+        ExprKind::Lambda(lambda) => {
+            let params: Vec<&str> = lambda.params.iter().map(|p| p.name.as_str()).collect();
+            format!("Lambda |{}|", params.join(", "))
+        }
+        ExprKind::Await(_) => "Await".to_string(),
+        ExprKind::Spawn(_) => "Spawn".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_program;
+
+    #[test]
+    fn pipe_visualization_includes_pipe_source_slice() {
+        let source = "fun main: () -> Int32 = {\n    val x = 41\n    x |> inc\n}\n";
+        let (_, program) = parse_program(source).unwrap();
+
+        let tree = visualize_with_source(&program, source);
+        assert!(tree.contains("Pipe |>  `x |> inc`"), "{}", tree);
+        assert!(tree.contains("Ident x  `x`"), "{}", tree);
+        assert!(tree.contains("IntLit 41  `41`"), "{}", tree);
+    }
+
+    #[test]
+    fn typed_visualization_shows_checked_expression_types() {
+        let source = "fun main: () -> Int32 = {\n    val xs = [1, 2];\n    40 + 2\n}\n";
+        let (_, program) = parse_program(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&program).unwrap();
+
+        let tree = visualize_with_types(&program, &checker);
+        assert!(tree.contains("IntLit 40 : Int32"), "{}", tree);
+        assert!(tree.contains("ListLit : List<Int32>"), "{}", tree);
+        assert!(tree.contains("Binary Add : Int32"), "{}", tree);
+    }
+
+    #[test]
+    fn visualization_without_source_omits_slices() {
+        let source = "fun main: () -> Int32 = {\n    (1) some\n}\n";
+        let (_, program) = parse_program(source).unwrap();
+
+        let tree = visualize(&program);
+        assert!(tree.contains("Function main"), "{}", tree);
+        assert!(!tree.contains('`'), "{}", tree);
+    }
+}
@@ -1,5 +1,7 @@
 //! User-facing diagnostic formatting helpers.
 
+use crate::ast::Span;
+
 pub type NomError<'a> = nom::Err<nom::error::Error<&'a str>>;
 
 pub fn format_lex_error(source: &str, error: NomError<'_>) -> String {
@@ -45,7 +47,8 @@ fn is_slice_from_source(source: &str, input: &str) -> bool {
     input_start >= source_start && input_end <= source_end
 }
 
-fn line_column(source: &str, offset: usize) -> (usize, usize) {
+/// Converts a byte offset into a 1-based line and column.
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
     let offset = offset.min(source.len());
     let mut line = 1usize;
     let mut line_start = 0usize;
@@ -63,6 +66,69 @@ fn line_column(source: &str, offset: usize) -> (usize, usize) {
     (line, offset.saturating_sub(line_start) + 1)
 }
 
+/// A 1-based source position whose column counts UTF-16 code units, the unit
+/// used by LSP clients and JavaScript editors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Converts a byte span into its start and end positions in `source`.
+///
+/// Offsets past the end of `source` are clamped, and offsets inside a
+/// multi-byte character snap back to the start of that character.
+pub fn span_to_line_col(source: &str, span: Span) -> (LineCol, LineCol) {
+    let start = offset_to_line_col(source, span.start);
+    let end = offset_to_line_col(source, span.end.max(span.start));
+    (start, end)
+}
+
+fn offset_to_line_col(source: &str, offset: usize) -> LineCol {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+
+    LineCol {
+        line: before.matches('\n').count() + 1,
+        column: utf16_len(&before[line_start..]) + 1,
+    }
+}
+
+/// Number of UTF-16 code units needed to encode `text`.
+pub fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Byte span of the input a lex or parse error points at: the run of
+/// non-whitespace text at the error position, or an empty span at end of
+/// input. Returns `None` for errors that carry a message instead of a source
+/// position.
+pub fn nom_error_span(source: &str, error: &NomError<'_>) -> Option<Span> {
+    let input = match error {
+        nom::Err::Error(error) | nom::Err::Failure(error) => error.input,
+        nom::Err::Incomplete(_) => return None,
+    };
+    if !input.is_empty() && !is_slice_from_source(source, input) {
+        return None;
+    }
+
+    let rest = input.trim_start();
+    let start = source.len() - rest.len();
+    let width = rest
+        .find(char::is_whitespace)
+        .unwrap_or(rest.len())
+        .max(rest.chars().next().map_or(0, char::len_utf8));
+
+    Some(Span {
+        start,
+        end: start + width,
+    })
+}
+
 fn truncate_for_diagnostic(input: &str, max_chars: usize) -> String {
     let mut chars = input.chars();
     let mut output = String::new();
@@ -96,6 +162,70 @@ mod tests {
         assert!(!message.contains("nom"));
     }
 
+    #[test]
+    fn span_to_line_col_maps_ascii_spans() {
+        let source = "val x = 1\nval yy = 2\n";
+        let (start, end) = span_to_line_col(source, Span { start: 14, end: 16 });
+
+        assert_eq!(start, LineCol { line: 2, column: 5 });
+        assert_eq!(end, LineCol { line: 2, column: 7 });
+    }
+
+    #[test]
+    fn span_to_line_col_counts_utf16_code_units() {
+        // "é" is 2 bytes / 1 UTF-16 unit; "😀" is 4 bytes / 2 UTF-16 units.
+        let source = "val s = \"é😀\" $\n";
+        let at = source.find('$').unwrap();
+        let (start, end) = span_to_line_col(
+            source,
+            Span {
+                start: at,
+                end: at + 1,
+            },
+        );
+
+        assert_eq!(
+            start,
+            LineCol {
+                line: 1,
+                column: 15
+            }
+        );
+        assert_eq!(
+            end,
+            LineCol {
+                line: 1,
+                column: 16
+            }
+        );
+
+        let emoji = source.find('😀').unwrap();
+        let (inside, _) = span_to_line_col(
+            source,
+            Span {
+                start: emoji + 2,
+                end: emoji + 4,
+            },
+        );
+        assert_eq!(
+            inside,
+            LineCol {
+                line: 1,
+                column: 11
+            }
+        );
+    }
+
+    #[test]
+    fn nom_error_span_covers_the_offending_word() {
+        let source = "fun main: () -> Int32 = {\n    val answer =\n}\n";
+        let err = parse_program(source).expect_err("source should not parse");
+        let span = nom_error_span(source, &err).expect("error should point into source");
+
+        assert!(span.start < span.end);
+        assert!(!span.slice(source).unwrap().contains(char::is_whitespace));
+    }
+
     #[test]
     fn parse_error_formatter_preserves_unsupported_feature_messages() {
         let source = "enum ReviewState { Ready }\n";
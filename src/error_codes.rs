@@ -0,0 +1,984 @@
+//! Stable error codes and the long-form descriptions behind `--explain`.
+//!
+//! Every [`TypeError`](crate::type_checker::TypeError) variant maps to one
+//! code through `TypeError::code`. Codes are never reused or renumbered, so
+//! they can be quoted in issues and searched for in documentation.
+
+/// Long-form description of one error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorExplanation {
+    /// Stable code such as `E0001`
+    pub code: &'static str,
+    /// One-line summary of the error
+    pub title: &'static str,
+    /// Description with an erroneous example and how to fix it
+    pub explanation: &'static str,
+}
+
+/// Looks up the explanation for `code`, ignoring ASCII case.
+pub fn explain(code: &str) -> Option<&'static ErrorExplanation> {
+    let code = code.trim();
+    EXPLANATIONS
+        .iter()
+        .find(|entry| entry.code.eq_ignore_ascii_case(code))
+}
+
+/// Formats an explanation the way `--explain` prints it.
+pub fn format_explanation(entry: &ErrorExplanation) -> String {
+    format!(
+        "{}: {}\n\n{}\n",
+        entry.code,
+        entry.title,
+        entry.explanation.trim()
+    )
+}
+
+/// All known error codes in numeric order.
+pub static EXPLANATIONS: &[ErrorExplanation] = &[
+    ErrorExplanation {
+        code: "E0001",
+        title: "value used after it was consumed",
+        explanation: r#"
+Restrict values are affine: a binding may be used at most once. Passing it to a
+function, returning it, or binding it to another name consumes it, and any
+later use is rejected.
+
+Erroneous example:
+
+    fun twice: (name: String) -> String = {
+        val first = name;
+        name
+    }
+
+Use the value once, or bind it as `mut val` and produce a new value for each
+use. When two independent copies are needed, make the copy explicit with
+`.clone { }` for records. Copyable types (`Int32`, `Int64`, `Float64`,
+`Boolean`, `Char`, `Unit`) can be reused freely.
+
+    fun once: (name: String) -> String = {
+        name
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0002",
+        title: "undefined variable",
+        explanation: r#"
+A name was used that is not bound in the current scope.
+
+Erroneous example:
+
+    fun total: () -> Int32 = {
+        count + 1
+    }
+
+Bind the value first, or take it as a parameter:
+
+    fun total: (count: Int32) -> Int32 = {
+        count + 1
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0003",
+        title: "type mismatch",
+        explanation: r#"
+An expression has a different type from the one its context requires, such as
+a function argument, an annotated binding, or a declared return type.
+
+Erroneous example:
+
+    fun label: () -> String = {
+        42
+    }
+
+Return a value of the declared type, or convert it explicitly:
+
+    fun label: () -> String = {
+        "42"
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0004",
+        title: "match guard consumes an affine value",
+        explanation: r#"
+A failed guard hands the matched value on to the next arm, so the guard must
+not consume anything that arm might still use.
+
+Erroneous example:
+
+    fun bad: (value: Option<String>) -> Int32 = {
+        value match {
+            Some(s) if (s |> string_length) > 0 => { 1 }
+            _ => { 0 }
+        }
+    }
+
+Test the value inside the arm body instead, or guard on a copyable binding:
+
+    fun good: (value: Option<String>) -> Int32 = {
+        value match {
+            Some(s) => { (s |> string_length) > 0 then { 1 } else { 0 } }
+            None => { 0 }
+        }
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0005",
+        title: "reassignment of an immutable binding",
+        explanation: r#"
+Only bindings declared with `mut val` can be reassigned.
+
+Erroneous example:
+
+    val count = 0;
+    count = count + 1;
+
+Declare the binding mutable:
+
+    mut val count = 0;
+    count = count + 1;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0006",
+        title: "unknown type",
+        explanation: r#"
+A type annotation names a type that is neither built in nor declared. Common
+causes are the shorthand names `Int`, `Float`, and `Bool`.
+
+Erroneous example:
+
+    val count: Int = 1;
+
+Use the full built-in name or declare the record:
+
+    val count: Int32 = 1;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0007",
+        title: "unknown record field",
+        explanation: r#"
+A record literal or field access names a field the record does not declare.
+
+Erroneous example:
+
+    record Point { x: Int32, y: Int32 }
+    val p = Point { x: 1, z: 2 };
+
+Use a declared field name:
+
+    val p = Point { x: 1, y: 2 };
+"#,
+    },
+    ErrorExplanation {
+        code: "E0008",
+        title: "missing record field",
+        explanation: r#"
+A record literal must initialize every declared field.
+
+Erroneous example:
+
+    record Point { x: Int32, y: Int32 }
+    val p = Point { x: 1 };
+
+Provide every field:
+
+    val p = Point { x: 1, y: 0 };
+"#,
+    },
+    ErrorExplanation {
+        code: "E0009",
+        title: "clone of a frozen record",
+        explanation: r#"
+A frozen record is a finished prototype and cannot be cloned.
+
+Erroneous example:
+
+    val frozen = point freeze;
+    val moved = frozen.clone { x: 2 };
+
+Clone before freezing:
+
+    val moved = point.clone { x: 2 } freeze;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0010",
+        title: "record frozen twice",
+        explanation: r#"
+`freeze` was applied to a record that is already frozen.
+
+Erroneous example:
+
+    val frozen = point freeze;
+    val again = frozen freeze;
+
+Freeze a value once and pass the frozen value along:
+
+    val frozen = point freeze;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0011",
+        title: "freeze of a record reachable from a mutable binding",
+        explanation: r#"
+A record reached from a `mut val` binding could still be updated with `<-`
+after freezing, which would change the frozen value.
+
+Erroneous example:
+
+    mut val point = Point { x: 1, y: 2 };
+    val frozen = point freeze;
+
+Freeze a clone instead:
+
+    val frozen = point.clone { } freeze;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0012",
+        title: "field update through an immutable binding",
+        explanation: r#"
+`record.field <- value` writes in place, so the record must be reached from a
+`mut val` binding.
+
+Erroneous example:
+
+    val point = Point { x: 1, y: 2 };
+    point.x <- 5;
+
+Declare the binding mutable, or build a new value with `.clone { }`:
+
+    mut val point = Point { x: 1, y: 2 };
+    point.x <- 5;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0013",
+        title: "value outside a range-restricted type",
+        explanation: r#"
+A range-restricted integer such as `Int32 in 0..=100` only accepts values the
+checker can prove lie within the range.
+
+Erroneous example:
+
+    fun percent: (raw: Int32) -> Int32 in 0..=100 = {
+        raw
+    }
+
+Convert the value with `clamp`, which saturates, or `checked`, which traps:
+
+    fun percent: (raw: Int32) -> Int32 in 0..=100 = {
+        (raw, 0, 100) clamp
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0014",
+        title: "invalid range restriction",
+        explanation: r#"
+Range restrictions apply to integer types and need a non-empty range.
+
+Erroneous example:
+
+    val ratio: Float64 in 0..1 = 0.5;
+
+Restrict an integer type, or drop the restriction:
+
+    val ratio: Float64 = 0.5;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0015",
+        title: "constant arithmetic overflow",
+        explanation: r#"
+Integer arithmetic on constants was evaluated at compile time and does not fit
+its type.
+
+Erroneous example:
+
+    val big = 2147483647 + 1;
+
+Use a wider type, or the wrapping helpers if wrapping is intended:
+
+    val big = (2147483647, 1) wrapping_add;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0016",
+        title: "mutation of a frozen record",
+        explanation: r#"
+Frozen records are immutable. Field updates are rejected, and so are method
+calls whose `self: Record` receiver could update the record in place.
+
+Erroneous examples:
+
+    mut val frozen = point freeze;
+    frozen.x <- 5;
+    (frozen) move_right;
+
+Create an updated copy before freezing instead:
+
+    val updated = point.clone { x: 5 } freeze;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0017",
+        title: "undefined record",
+        explanation: r#"
+A record literal, pattern, or `impl` block names a record that is not declared
+or imported.
+
+Erroneous example:
+
+    val p = Point { x: 1, y: 2 };
+
+Declare or import the record first:
+
+    record Point { x: Int32, y: Int32 }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0018",
+        title: "undefined function",
+        explanation: r#"
+A call names a function that is not declared, imported, or built in.
+When a known function is a likely typo away, such as `print` for
+`printn`, the message suggests it.
+
+Erroneous example:
+
+    val n = (3) triple;
+
+Declare the function, or import it from its module:
+
+    fun triple: (n: Int32) -> Int32 = { n * 3 }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0019",
+        title: "unknown method",
+        explanation: r#"
+An OSV method call resolved to a record whose `impl` blocks do not declare the
+method. When a declared method has a similar name, the error suggests it.
+
+Erroneous example:
+
+    record Score { value: Int32 }
+    val total = (score) sum;
+
+Declare the method in an `impl` block for that record:
+
+    impl Score {
+        fun sum: (self: Score) -> Int32 = { self.value }
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0020",
+        title: "wrong number of arguments",
+        explanation: r#"
+A call passed a different number of arguments from the function's parameters.
+
+Erroneous example:
+
+    fun add: (a: Int32, b: Int32) -> Int32 = { a + b }
+    val n = (1) add;
+
+Pass every argument in the OSV group:
+
+    val n = (1, 2) add;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0021",
+        title: "wrong number of type arguments",
+        explanation: r#"
+Explicit type arguments must match the function's type parameters.
+
+Erroneous example:
+
+    fun empty: <T>() -> List<T> = { [] }
+    val xs = () empty<Int32, String>;
+
+Pass one type argument per type parameter:
+
+    val xs = () empty<Int32>;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0022",
+        title: "context not available",
+        explanation: r#"
+A context field or context-bound function was used outside a matching
+`with` block. The host builtins `env_var` and `now` are bound to the
+built-in `Env` and `Clock` contexts the same way.
+
+Erroneous example:
+
+    context Config { limit: Int32 }
+    fun limit: () -> Int32 = { Config.limit }
+
+Provide the context with `with`:
+
+    fun limit: () -> Int32 = {
+        with Config { limit: 10 } {
+            Config.limit
+        }
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0023",
+        title: "heap-backed value escapes an arena",
+        explanation: r#"
+Values allocated inside `with Arena { } { ... }` are released when the arena
+ends, so heap-backed results such as `String` or `List<T>` cannot leave it,
+either as the block result or by assignment to a `mut val` declared outside.
+
+Erroneous example:
+
+    fun leak: () -> List<Int32> = {
+        with Arena { } {
+            [1, 2, 3]
+        }
+    }
+
+Reduce the value to a copyable result inside the arena, or allocate it
+outside:
+
+    fun total: () -> Int32 = {
+        with Arena { } {
+            [1, 2, 3] |> list_length
+        }
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0024",
+        title: "unsupported feature",
+        explanation: r#"
+The program uses syntax or a type combination that the current release does
+not support. The message names the construct; rewrite it with a supported
+form, for example an OSV call instead of a dot method call.
+
+Erroneous example:
+
+    val n = score.bump(2);
+
+Use the grouped OSV form:
+
+    val n = (score, 2) bump;
+"#,
+    },
+    ErrorExplanation {
+        code: "E0025",
+        title: "type is not derived from the required prototype",
+        explanation: r#"
+A type parameter bounded with `T from Parent` was instantiated with a record
+that does not derive from `Parent`.
+
+Erroneous example:
+
+    record Animal { name: String }
+    record Dog from Animal { name: String }
+    fun describe: <T from Animal>(value: T) -> Int32 = { 0 }
+    val n = (Point { x: 1, y: 2 }) describe;
+
+Pass a record derived from the required prototype, such as a `Dog`.
+"#,
+    },
+    ErrorExplanation {
+        code: "E0026",
+        title: "clone of a sealed prototype",
+        explanation: r#"
+A sealed prototype forbids further derivation, so it cannot be cloned.
+
+Erroneous example:
+
+    val child = sealed_base.clone { };
+
+Clone the unsealed prototype it was built from instead.
+"#,
+    },
+    ErrorExplanation {
+        code: "E0027",
+        title: "prototype derivation too deep",
+        explanation: r#"
+Prototype chains are limited to three levels of derivation so method and field
+resolution stays predictable.
+
+Erroneous example:
+
+    val d = a.clone { }.clone { }.clone { }.clone { };
+
+Flatten the chain by cloning from a shallower prototype.
+"#,
+    },
+    ErrorExplanation {
+        code: "E0028",
+        title: "cyclic prototype derivation",
+        explanation: r#"
+A prototype derives, directly or indirectly, from itself. The message lists
+the cycle.
+
+Break the cycle so every chain ends at a record declared without a parent.
+"#,
+    },
+    ErrorExplanation {
+        code: "E0029",
+        title: "unknown parent prototype",
+        explanation: r#"
+A record names a parent prototype hash that matches no known prototype,
+usually because the parent was removed or renamed.
+
+Declare the parent prototype, or remove the parent reference.
+"#,
+    },
+    ErrorExplanation {
+        code: "E0030",
+        title: "temporal constraint violation",
+        explanation: r#"
+A value with a temporal type variable such as `File<~f>` was used in a way that
+breaks a declared `where ~inner within ~outer` constraint, for example by
+pairing a transaction with a database scope it does not live within.
+
+Erroneous example:
+
+    record Transaction<~tx, ~db> where ~tx within ~db { id: Int32 }
+    // a Transaction<~tx, ~db> built from a ~tx that outlives ~db
+
+Create the inner value inside the outer scope so the constraint holds.
+"#,
+    },
+    ErrorExplanation {
+        code: "E0031",
+        title: "temporal value escapes its scope",
+        explanation: r#"
+A value tied to a temporal scope outlives that scope, for example by being
+returned from the block that owns the resource, or by being captured by a task
+spawned on an AsyncRuntime whose lifetime is longer than the scope.
+
+Return a value derived from the resource instead of the resource itself, or
+widen the scope so the consumer runs inside it.
+"#,
+    },
+    ErrorExplanation {
+        code: "E0032",
+        title: "invalid temporal constraint",
+        explanation: r#"
+A `where ~inner within ~outer` clause names a temporal variable that is not
+declared, or relates a variable to itself.
+
+Erroneous example:
+
+    record Transaction<~tx> where ~tx within ~db { id: Int32 }
+
+Declare every temporal variable the constraint mentions:
+
+    record Transaction<~tx, ~db> where ~tx within ~db { id: Int32 }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0033",
+        title: "non-exhaustive match",
+        explanation: r#"
+A `match` must handle every possible value. Guarded arms do not count toward
+exhaustiveness.
+
+Erroneous example:
+
+    value match {
+        Some(n) => { n }
+    }
+
+Cover the missing cases, or add a wildcard arm:
+
+    value match {
+        Some(n) => { n }
+        None => { 0 }
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0034",
+        title: "type cannot be inferred",
+        explanation: r#"
+The checker could not determine a concrete type, typically for an empty list,
+`None`, or `Ok`/`Err` with nothing constraining the other side.
+
+Erroneous example:
+
+    val items = [];
+
+Add an annotation, or use the binding where a concrete type is expected:
+
+    val items: List<Int32> = [];
+"#,
+    },
+    ErrorExplanation {
+        code: "E0035",
+        title: "unresolved associated type",
+        explanation: r#"
+An associated type projection such as `C.Item` could not be resolved after
+inference, because the container type was never made concrete.
+
+Annotate the value whose associated type is being projected so its container
+type is known.
+"#,
+    },
+    ErrorExplanation {
+        code: "E0036",
+        title: "module could not be resolved",
+        explanation: r#"
+An `import` names a module that could not be found, parsed, or linked.
+
+Erroneous example:
+
+    import std.mathx.{max}
+
+Check the module path and that the file exists next to the importing file or
+in the standard library:
+
+    import std.math.{max}
+"#,
+    },
+    ErrorExplanation {
+        code: "E0037",
+        title: "imported declaration is not exported",
+        explanation: r#"
+An `import` names a declaration that its module does not mark with `export`.
+
+Erroneous example:
+
+    // geometry.rl
+    fun area: (w: Int32, h: Int32) -> Int32 = { w * h }
+
+    // main.rl
+    import geometry.{area}
+
+Export the declaration from its module:
+
+    export fun area: (w: Int32, h: Int32) -> Int32 = { w * h }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0038",
+        title: "invalid @init function",
+        explanation: r#"
+An `@init` function runs once when the module starts, before `main`. The module
+calls it itself, with nothing to pass in and nowhere to send a result, so it
+must take no parameters, have no type parameters, not be `async`, and return
+`()`.
+
+Erroneous example:
+
+    @init
+    fun setup: (level: Int32) -> Int32 = { level }
+
+Move the parameters into the body and return `()`:
+
+    @init
+    fun setup: () -> () = {
+        "starting" |> println
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0039",
+        title: "record field default uses an outside variable",
+        explanation: r#"
+A field default is evaluated again at every record literal that omits the
+field, so it cannot capture or consume variables from the surrounding scope.
+
+Erroneous example:
+
+    val fallback = "localhost"
+    record Config { host: String = fallback }
+
+Write the default as a self-contained expression, or pass the value in the
+record literal instead:
+
+    record Config { host: String = "localhost" }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0040",
+        title: "`?` cannot return from the enclosing function",
+        explanation: r#"
+`value?` unwraps a `Some` or `Ok`, and otherwise returns the `None` or `Err`
+from the enclosing function. That function must declare an `Option` return type
+for `?` on an `Option`, or a `Result` with the same error type for `?` on a
+`Result`. Lambdas have no declared return type, so `?` cannot be used inside
+them.
+
+Erroneous example:
+
+    fun first_or_zero: (values: List<Int32>) -> Int32 = {
+        values |> list_head?
+    }
+
+Return an `Option` from the function, or handle `None` with a `match`:
+
+    fun first_doubled: (values: List<Int32>) -> Option<Int32> = {
+        val first = values |> list_head?;
+        Some(first * 2)
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0041",
+        title: "`break` or `continue` has no loop to leave",
+        explanation: r#"
+`break` leaves the innermost enclosing loop and `continue` starts its next
+iteration, so both must appear inside a `loop`, `while`, or `while let` body.
+Function and lambda bodies start outside every loop, even when they are
+written inside one.
+
+Only `loop` has a result value, so `break value` cannot leave a `while` loop,
+which always evaluates to `()`.
+
+Erroneous example:
+
+    fun first_even: (limit: Int32) -> Int32 = {
+        mut val n = 1;
+        n < limit while {
+            n % 2 == 0 then { break n } else { n = n + 1 }
+        }
+        n
+    }
+
+Use `loop` to leave with a value:
+
+    fun first_even: (limit: Int32) -> Int32 = {
+        mut val n = 1;
+        loop {
+            n % 2 == 0 || n >= limit then { break n } else { n = n + 1 }
+        }
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0042",
+        title: "function value escapes a capability context",
+        explanation: r#"
+`with Env { ... }` and `with Clock { ... }` grant `env_var` and `now` only
+inside the block. A function value created there could call them after the
+block ends, so it cannot leave the block, either as the block result or by
+assignment to a `mut val` declared outside.
+
+Erroneous example:
+
+    fun stamp: () -> (Int32) -> Int32 = {
+        with Clock { |x: Int32| { val _t = () now; x } }
+    }
+
+Call the function inside the block and let only its result leave:
+
+    fun stamp: () -> Int64 = {
+        with Clock { () now }
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0043",
+        title: "field update on a record shared with a collection",
+        explanation: r#"
+Reading a `mut val` collection of records leaves the collection readable, so
+a record taken out of it is still reachable through the collection. Updating
+a field of that record in place would change what the collection holds.
+
+Erroneous example:
+
+    fun bump: () -> Int32 = {
+        mut val points = [Point { x: 1 }];
+        mut val first = points |> list_head |> option_unwrap;
+        first.x <- 99;
+        first.x
+    }
+
+Build an updated copy with `clone` instead:
+
+    fun bump: () -> Int32 = {
+        mut val points = [Point { x: 1 }];
+        val first = points |> list_head |> option_unwrap;
+        val bumped = first.clone { x: 99 };
+        bumped.x
+    }
+"#,
+    },
+    ErrorExplanation {
+        code: "E0044",
+        title: "redefinition of an immutable binding",
+        explanation: r#"
+A `val` reuses the name of an immutable binding declared earlier in the same
+block. Shadowing is only allowed in an inner block, or over a `mut val`.
+
+Erroneous example:
+
+    val total = 1;
+    val total = total + 1;
+
+Use a new name, or move the new binding into an inner block:
+
+    val total = 1;
+    val next_total = total + 1;
+"#,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::const_eval::{ConstOverflow, IntWidth};
+    use crate::type_checker::TypeError;
+    use std::collections::HashSet;
+
+    fn one_of_each_type_error() -> Vec<TypeError> {
+        let name = || "x".to_string();
+        vec![
+            TypeError::UndefinedVariable {
+                name: name(),
+                suggestion: None,
+            },
+            TypeError::TypeMismatch {
+                expected: name(),
+                found: name(),
+            },
+            TypeError::AffineViolation(name()),
+            TypeError::GuardConsumesAffineValue(name()),
+            TypeError::ImmutableReassignment(name()),
+            TypeError::UnknownType {
+                name: name(),
+                suggestion: None,
+            },
+            TypeError::UnknownField {
+                record: name(),
+                field: name(),
+                suggestion: None,
+            },
+            TypeError::MissingField {
+                record: name(),
+                field: name(),
+            },
+            TypeError::CloneFrozenRecord,
+            TypeError::FreezeAlreadyFrozen,
+            TypeError::FreezeMutableBinding(name()),
+            TypeError::ImmutableFieldUpdate(name()),
+            TypeError::RefinementViolation {
+                expected: name(),
+                found: name(),
+            },
+            TypeError::InvalidRefinement(name()),
+            TypeError::ConstantOverflow(ConstOverflow {
+                expression: name(),
+                width: IntWidth::Int32,
+            }),
+            TypeError::MutationOfFrozen {
+                record: name(),
+                operation: name(),
+            },
+            TypeError::UndefinedRecord(name()),
+            TypeError::UndefinedFunction {
+                name: name(),
+                suggestion: None,
+            },
+            TypeError::UnknownMethod {
+                record: name(),
+                method: name(),
+                suggestion: None,
+            },
+            TypeError::ArityMismatch {
+                expected: 1,
+                found: 2,
+            },
+            TypeError::TypeArgumentCountMismatch {
+                function: name(),
+                expected: 1,
+                found: 2,
+            },
+            TypeError::UnavailableContext(name()),
+            TypeError::ArenaEscape(name()),
+            TypeError::UnsupportedFeature(name()),
+            TypeError::NotDerivedFrom(name(), name()),
+            TypeError::CannotCloneSealed(name()),
+            TypeError::DerivationTooDeep(4),
+            TypeError::CyclicDerivation(vec![name()]),
+            TypeError::UnknownParentPrototype {
+                record: name(),
+                parent_hash: name(),
+            },
+            TypeError::TemporalConstraintViolation(name()),
+            TypeError::TemporalEscape {
+                temporal: name(),
+                message: name(),
+            },
+            TypeError::InvalidTemporalConstraint(name(), name()),
+            TypeError::NonExhaustivePatterns {
+                missing: name(),
+                suggestion: name(),
+            },
+            TypeError::CannotInferType(name()),
+            TypeError::UnresolvedProjection(name()),
+            TypeError::ModuleResolution(name()),
+            TypeError::NotExported {
+                module: name(),
+                name: name(),
+            },
+            TypeError::InvalidInitFunction(name()),
+            TypeError::ImpureFieldDefault {
+                record: name(),
+                field: name(),
+                variable: name(),
+            },
+            TypeError::InvalidTry {
+                operand: name(),
+                required: name(),
+                found: None,
+            },
+            TypeError::InvalidLoopControl(name()),
+            TypeError::CapabilityEscape(name()),
+            TypeError::SharedFieldUpdate(name()),
+            TypeError::Redefinition(name()),
+        ]
+    }
+
+    #[test]
+    fn every_type_error_has_a_unique_explained_code() {
+        let errors = one_of_each_type_error();
+        let mut seen = HashSet::new();
+        for error in &errors {
+            let code = error.code();
+            assert!(seen.insert(code), "duplicate code {code} for {error:?}");
+            let entry = explain(code).unwrap_or_else(|| panic!("{code} has no explanation"));
+            assert!(!entry.title.trim().is_empty(), "{code} has an empty title");
+            assert!(
+                !entry.explanation.trim().is_empty(),
+                "{code} has an empty explanation"
+            );
+        }
+        assert_eq!(
+            seen.len(),
+            EXPLANATIONS.len(),
+            "every explanation should belong to a TypeError variant"
+        );
+    }
+
+    #[test]
+    fn explanations_are_listed_in_code_order() {
+        let codes: Vec<_> = EXPLANATIONS.iter().map(|entry| entry.code).collect();
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+        assert_eq!(codes, sorted);
+    }
+
+    #[test]
+    fn explain_ignores_case_and_surrounding_whitespace() {
+        assert_eq!(explain(" e0001 ").map(|entry| entry.code), Some("E0001"));
+        assert!(explain("E9999").is_none());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::ast::Type;
+use std::fmt;
+
+/// Scalar types a foreign function may take or return; they cross the Wasm
+/// boundary as a single core value.
+const FOREIGN_SCALAR_TYPES: &[&str] = &["Int32", "Int64", "Float64", "Boolean", "Char"];
+
+/// A function provided by the host under a Wasm import, such as an export of
+/// a foreign dependency. Programs call it by [`binding`](Self::binding) like
+/// any other function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignFunction {
+    /// Wasm import module the function is read from.
+    pub module: String,
+    /// Wasm import field name.
+    pub name: String,
+    pub params: Vec<Type>,
+    pub result: Type,
+}
+
+impl ForeignFunction {
+    /// Parse `module.name=(Type, ...) -> Type`. The result may be left out
+    /// for functions returning `Unit`; the module is everything before the
+    /// last `.` and may itself contain dots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use restrict_lang::ForeignFunction;
+    ///
+    /// let function = ForeignFunction::parse("geometry.scale=(Int32, Int32) -> Int32").unwrap();
+    /// assert_eq!(function.module, "geometry");
+    /// assert_eq!(function.params.len(), 2);
+    /// ```
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (path, signature) = spec.split_once('=').ok_or_else(|| {
+            format!(
+                "expected `module.name=(Type, ...) -> Type`, found `{}`",
+                spec
+            )
+        })?;
+        let (module, name) = path
+            .trim()
+            .rsplit_once('.')
+            .filter(|(module, name)| !module.is_empty() && !name.is_empty())
+            .ok_or_else(|| format!("expected `module.name` before `=`, found `{}`", path))?;
+
+        let signature = signature.trim();
+        let (params, result) = match signature.split_once("->") {
+            Some((params, result)) => (params.trim(), Some(result.trim())),
+            None => (signature, None),
+        };
+        let params = params
+            .strip_prefix('(')
+            .and_then(|params| params.strip_suffix(')'))
+            .ok_or_else(|| {
+                format!(
+                    "expected a parenthesized parameter list, found `{}`",
+                    params
+                )
+            })?;
+        let params = params
+            .split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            .map(|param| foreign_type(param, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        let result = match result {
+            Some(result) => foreign_type(result, true)?,
+            None => Type::Named("Unit".to_string()),
+        };
+
+        Ok(Self {
+            module: module.to_string(),
+            name: name.to_string(),
+            params,
+            result,
+        })
+    }
+
+    /// Name the program calls the function by: the import name with `-`,
+    /// which WIT names use between words, replaced by `_`.
+    pub fn binding(&self) -> String {
+        self.name.replace('-', "_")
+    }
+}
+
+impl fmt::Display for ForeignFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}=(", self.module, self.name)?;
+        for (index, param) in self.params.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", param)?;
+        }
+        write!(f, ") -> {}", self.result)
+    }
+}
+
+fn foreign_type(name: &str, result: bool) -> Result<Type, String> {
+    if FOREIGN_SCALAR_TYPES.contains(&name) || (result && matches!(name, "Unit" | "()")) {
+        let name = if name == "()" { "Unit" } else { name };
+        return Ok(Type::Named(name.to_string()));
+    }
+    if result {
+        Err(format!(
+            "foreign function results must be Int32, Int64, Float64, Boolean, Char or Unit, found `{}`",
+            name
+        ))
+    } else {
+        Err(format!(
+            "foreign function parameters must be Int32, Int64, Float64, Boolean or Char, found `{}`",
+            name
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_module_name_and_signature() {
+        let function =
+            ForeignFunction::parse("geometry/shapes.unit-distance=(Float64, Int64) -> Float64")
+                .unwrap();
+        assert_eq!(function.module, "geometry/shapes");
+        assert_eq!(function.binding(), "unit_distance");
+        assert_eq!(
+            function.to_string(),
+            "geometry/shapes.unit-distance=(Float64, Int64) -> Float64"
+        );
+
+        let function = ForeignFunction::parse("log.flush=()").unwrap();
+        assert!(function.params.is_empty());
+        assert_eq!(function.result, Type::Named("Unit".to_string()));
+    }
+
+    #[test]
+    fn rejects_non_scalar_types() {
+        let err = ForeignFunction::parse("io.read=(String) -> Int32").unwrap_err();
+        assert!(err.contains("found `String`"), "{}", err);
+        assert!(ForeignFunction::parse("io.read").is_err());
+        assert!(ForeignFunction::parse("read=(Int32)").is_err());
+    }
+}
@@ -212,6 +212,15 @@ impl<'a> CheckedIrBuilder<'a> {
                 Stmt::Expr(expr) => {
                     last_value = self.push_typed_exprs_from_expr(expr, exprs, sites, bindings)?;
                 }
+                Stmt::Break(value) => {
+                    last_value = match value {
+                        Some(value) => {
+                            self.push_typed_exprs_from_expr(value, exprs, sites, bindings)?
+                        }
+                        None => None,
+                    };
+                }
+                Stmt::Continue => last_value = None,
             }
         }
         if let Some(expr) = &block.expr {
@@ -290,9 +299,14 @@ impl<'a> CheckedIrBuilder<'a> {
             | ExprKind::Some(inner)
             | ExprKind::Ok(inner)
             | ExprKind::Err(inner)
+            | ExprKind::Try(inner)
             | ExprKind::FieldAccess(inner, _) => {
                 self.push_typed_exprs_from_expr(inner, exprs, sites, bindings)?;
             }
+            ExprKind::FieldUpdate(update) => {
+                self.push_typed_exprs_from_expr(&update.object, exprs, sites, bindings)?;
+                self.push_typed_exprs_from_expr(&update.value, exprs, sites, bindings)?;
+            }
             ExprKind::PrototypeClone(clone) => {
                 for field in &clone.updates.fields {
                     self.push_typed_exprs_from_field_init(field, exprs, sites, bindings)?;
@@ -313,9 +327,19 @@ impl<'a> CheckedIrBuilder<'a> {
                 self.push_typed_exprs_from_expr(&while_expr.condition, exprs, sites, bindings)?;
                 self.push_typed_exprs_from_block(&while_expr.body, exprs, sites, bindings)?;
             }
+            ExprKind::WhileLet(while_let) => {
+                self.push_typed_exprs_from_expr(&while_let.expr, exprs, sites, bindings)?;
+                self.push_typed_exprs_from_block(&while_let.body, exprs, sites, bindings)?;
+            }
+            ExprKind::Loop(body) => {
+                self.push_typed_exprs_from_block(body, exprs, sites, bindings)?;
+            }
             ExprKind::Match(match_expr) => {
                 self.push_typed_exprs_from_expr(&match_expr.expr, exprs, sites, bindings)?;
                 for arm in &match_expr.arms {
+                    if let Some(guard) = &arm.guard {
+                        self.push_typed_exprs_from_expr(guard, exprs, sites, bindings)?;
+                    }
                     self.push_typed_exprs_from_block(&arm.body, exprs, sites, bindings)?;
                 }
             }
@@ -355,6 +379,7 @@ impl<'a> CheckedIrBuilder<'a> {
             }
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
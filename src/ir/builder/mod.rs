@@ -575,12 +575,13 @@ fn contains_record_layout_type_param(ty: &TypedType) -> bool {
         | TypedType::Temporal {
             base_type: inner, ..
         } => contains_record_layout_type_param(inner),
-        TypedType::Result(ok, err) => {
+        TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
             contains_record_layout_type_param(ok) || contains_record_layout_type_param(err)
         }
         TypedType::Int32
         | TypedType::Int64
         | TypedType::Float64
+        | TypedType::Decimal
         | TypedType::Boolean
         | TypedType::String
         | TypedType::Char
@@ -641,6 +642,10 @@ fn collect_layout(layout: LayoutId, layout_table: &LayoutTable, layouts: &mut Ha
         LayoutKind::List(list) => {
             collect_layouts_from_repr(list.element.repr, layout_table, layouts);
         }
+        LayoutKind::Map(map) => {
+            collect_layouts_from_repr(map.key.repr, layout_table, layouts);
+            collect_layouts_from_repr(map.value.repr, layout_table, layouts);
+        }
         LayoutKind::Array(array) => {
             collect_layouts_from_repr(array.element.repr, layout_table, layouts);
         }
@@ -724,6 +729,7 @@ fn is_literal_expr(expr: &Expr) -> bool {
         &expr.kind,
         ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -769,6 +775,10 @@ fn collect_pattern_bound_names(pattern: &Pattern, names: &mut Vec<String>) {
         Pattern::Some(inner) | Pattern::Ok(inner) | Pattern::Err(inner) => {
             collect_pattern_bound_names(inner, names);
         }
+        Pattern::Binding(name, inner) => {
+            names.push(name.clone());
+            collect_pattern_bound_names(inner, names);
+        }
         Pattern::ListCons(head, tail) => {
             collect_pattern_bound_names(head, names);
             collect_pattern_bound_names(tail, names);
@@ -780,6 +790,7 @@ fn collect_pattern_bound_names(pattern: &Pattern, names: &mut Vec<String>) {
         }
         Pattern::Wildcard
         | Pattern::Literal(_)
+        | Pattern::Range { .. }
         | Pattern::None
         | Pattern::EmptyList
         | Pattern::Ident(_) => {}
@@ -68,7 +68,7 @@ impl LayoutTable {
             TypedType::Int32 | TypedType::Boolean | TypedType::Char => {
                 ValueRepr::Scalar(ScalarRepr::I32)
             }
-            TypedType::Int64 => ValueRepr::Scalar(ScalarRepr::I64),
+            TypedType::Int64 | TypedType::Decimal => ValueRepr::Scalar(ScalarRepr::I64),
             TypedType::Float64 => ValueRepr::Scalar(ScalarRepr::F64),
             TypedType::String => {
                 let id = self.insert(LayoutKind::String(StringLayout {
@@ -82,6 +82,12 @@ impl LayoutTable {
                 let id = self.insert(LayoutKind::List(ListLayout { element }));
                 ValueRepr::Ref(id)
             }
+            TypedType::Map(key, value) => {
+                let key = self.element_layout_with_record_fields(key, record_fields);
+                let value = self.element_layout_with_record_fields(value, record_fields);
+                let id = self.insert(LayoutKind::Map(MapLayout { key, value }));
+                ValueRepr::Ref(id)
+            }
             TypedType::Array(inner, length) => {
                 let element = self.element_layout_with_record_fields(inner, record_fields);
                 let id = self.insert(LayoutKind::Array(ArrayLayout {
@@ -338,6 +344,7 @@ pub struct LayoutDescriptor {
 pub enum LayoutKind {
     String(StringLayout),
     List(ListLayout),
+    Map(MapLayout),
     Array(ArrayLayout),
     Range(RangeLayout),
     Record(RecordLayout),
@@ -369,6 +376,14 @@ pub struct ListLayout {
     pub element: ElementLayout,
 }
 
+/// Open-addressing hash table: a header holding the length, capacity and
+/// entry pointer, then entries of a state word, key and value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MapLayout {
+    pub key: ElementLayout,
+    pub value: ElementLayout,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ArrayLayout {
     pub element: ElementLayout,
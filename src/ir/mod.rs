@@ -71,7 +71,9 @@ fn contains_inference_type(ty: &TypedType) -> bool {
         | TypedType::Temporal {
             base_type: inner, ..
         } => contains_inference_type(inner),
-        TypedType::Result(ok, err) => contains_inference_type(ok) || contains_inference_type(err),
+        TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
+            contains_inference_type(ok) || contains_inference_type(err)
+        }
         _ => false,
     }
 }
@@ -90,7 +92,9 @@ fn contains_type_param(ty: &TypedType) -> bool {
         | TypedType::Temporal {
             base_type: inner, ..
         } => contains_type_param(inner),
-        TypedType::Result(ok, err) => contains_type_param(ok) || contains_type_param(err),
+        TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
+            contains_type_param(ok) || contains_type_param(err)
+        }
         TypedType::InferVar(_) | TypedType::Projection { .. } => true,
         _ => false,
     }
@@ -135,7 +139,7 @@ impl HostAbi {
             TypedType::Int32 | TypedType::Boolean | TypedType::Char => {
                 HostAbi::Scalar(ScalarRepr::I32)
             }
-            TypedType::Int64 => HostAbi::Scalar(ScalarRepr::I64),
+            TypedType::Int64 | TypedType::Decimal => HostAbi::Scalar(ScalarRepr::I64),
             TypedType::Float64 => HostAbi::Scalar(ScalarRepr::F64),
             TypedType::String
             | TypedType::Record { .. }
@@ -143,6 +147,7 @@ impl HostAbi {
             | TypedType::Option(_)
             | TypedType::Result(_, _)
             | TypedType::List(_)
+            | TypedType::Map(_, _)
             | TypedType::Array(_, _)
             | TypedType::Temporal { .. } => {
                 HostAbi::InternalOnly(InternalOnlyReason::CompositeHostAbiUnstable)
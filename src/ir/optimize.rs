@@ -258,6 +258,7 @@ pub fn summarize_checked_function_layout_optimizations(
             }
             LayoutKind::String(_)
             | LayoutKind::List(_)
+            | LayoutKind::Map(_)
             | LayoutKind::Array(_)
             | LayoutKind::Range(_)
             | LayoutKind::Closure(_)
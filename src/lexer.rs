@@ -20,7 +20,8 @@
 //!     .is_empty());
 //! ```
 
-use crate::diagnostics::format_lex_error;
+use crate::ast::{format_decimal, DECIMAL_PLACES, DECIMAL_SCALE};
+use crate::diagnostics::{format_lex_error, line_column};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_until, take_while, take_while1},
@@ -31,6 +32,7 @@ use nom::{
     IResult,
 };
 use std::fmt;
+use std::ops::Range;
 
 /// Token types in Restrict Language.
 ///
@@ -68,6 +70,12 @@ pub enum Token {
     Else,
     /// `while` keyword for loops
     While,
+    /// `loop` keyword for unconditional loops
+    Loop,
+    /// `break` keyword for leaving a loop
+    Break,
+    /// `continue` keyword for skipping to the next iteration
+    Continue,
     /// `match` keyword for pattern matching
     Match,
     /// `async` keyword for asynchronous functions
@@ -112,8 +120,13 @@ pub enum Token {
     Ident(String),
     /// Integer literal
     IntLit(i64),
+    /// Integer literal with an `L` suffix (e.g., `123L`), typed `Int64`
+    Int64Lit(i64),
     /// Floating-point literal
     FloatLit(f64),
+    /// Decimal literal with a `d` suffix (e.g., `1.99d`), scaled by
+    /// [`DECIMAL_SCALE`]
+    DecimalLit(i64),
     /// String literal
     StringLit(String),
     /// Character literal
@@ -127,6 +140,7 @@ pub enum Token {
     Assign,    // =
     Arrow,     // =>
     ThinArrow, // ->
+    LeftArrow, // <-
     Plus,      // +
     Minus,     // -
     Star,      // *
@@ -146,6 +160,12 @@ pub enum Token {
     // Temporal
     Tilde, // ~ (for temporal type variables)
 
+    // Patterns
+    At, // @ (for name @ pattern bindings)
+
+    // Early return
+    Question, // ? (for Option/Result early return)
+
     // Delimiters
     LBrace,    // {
     RBrace,    // }
@@ -157,6 +177,7 @@ pub enum Token {
     Colon,     // :
     Dot,       // .
     DotDot,    // .. (range literal separator)
+    DotDotEq,  // ..= (inclusive range pattern)
     DotDotDot, // ... (for spread destructuring)
     Semicolon, // ;
 
@@ -164,6 +185,96 @@ pub enum Token {
     Eof,
 }
 
+impl Token {
+    /// Variant name without its payload, e.g. `Ident` for `Ident("x")`.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Token::Record => "Record",
+            Token::Clone => "Clone",
+            Token::Freeze => "Freeze",
+            Token::Impl => "Impl",
+            Token::Context => "Context",
+            Token::Enum => "Enum",
+            Token::Form => "Form",
+            Token::Takes => "Takes",
+            Token::With => "With",
+            Token::Fun => "Fun",
+            Token::Val => "Val",
+            Token::Mut => "Mut",
+            Token::Then => "Then",
+            Token::Else => "Else",
+            Token::While => "While",
+            Token::Loop => "Loop",
+            Token::Break => "Break",
+            Token::Continue => "Continue",
+            Token::Match => "Match",
+            Token::Async => "Async",
+            Token::Return => "Return",
+            Token::True => "True",
+            Token::False => "False",
+            Token::Unit => "Unit",
+            Token::Some => "Some",
+            Token::None => "None",
+            Token::Import => "Import",
+            Token::Export => "Export",
+            Token::Pub => "Pub",
+            Token::Sealed => "Sealed",
+            Token::From => "From",
+            Token::Within => "Within",
+            Token::Where => "Where",
+            Token::Lifetime => "Lifetime",
+            Token::Await => "Await",
+            Token::Spawn => "Spawn",
+            Token::As => "As",
+            Token::Ident(_) => "Ident",
+            Token::IntLit(_) => "IntLit",
+            Token::Int64Lit(_) => "Int64Lit",
+            Token::FloatLit(_) => "FloatLit",
+            Token::DecimalLit(_) => "DecimalLit",
+            Token::StringLit(_) => "StringLit",
+            Token::CharLit(_) => "CharLit",
+            Token::Pipe => "Pipe",
+            Token::Bar => "Bar",
+            Token::Assign => "Assign",
+            Token::Arrow => "Arrow",
+            Token::ThinArrow => "ThinArrow",
+            Token::LeftArrow => "LeftArrow",
+            Token::Plus => "Plus",
+            Token::Minus => "Minus",
+            Token::Star => "Star",
+            Token::Asterisk => "Asterisk",
+            Token::Slash => "Slash",
+            Token::Percent => "Percent",
+            Token::Eq => "Eq",
+            Token::Ne => "Ne",
+            Token::Not => "Not",
+            Token::Lt => "Lt",
+            Token::Le => "Le",
+            Token::Gt => "Gt",
+            Token::Ge => "Ge",
+            Token::And => "And",
+            Token::Or => "Or",
+            Token::Tilde => "Tilde",
+            Token::At => "At",
+            Token::Question => "Question",
+            Token::LBrace => "LBrace",
+            Token::RBrace => "RBrace",
+            Token::LParen => "LParen",
+            Token::RParen => "RParen",
+            Token::LBracket => "LBracket",
+            Token::RBracket => "RBracket",
+            Token::Comma => "Comma",
+            Token::Colon => "Colon",
+            Token::Dot => "Dot",
+            Token::DotDot => "DotDot",
+            Token::DotDotEq => "DotDotEq",
+            Token::DotDotDot => "DotDotDot",
+            Token::Semicolon => "Semicolon",
+            Token::Eof => "Eof",
+        }
+    }
+}
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -182,6 +293,9 @@ impl fmt::Display for Token {
             Token::Then => write!(f, "then"),
             Token::Else => write!(f, "else"),
             Token::While => write!(f, "while"),
+            Token::Loop => write!(f, "loop"),
+            Token::Break => write!(f, "break"),
+            Token::Continue => write!(f, "continue"),
             Token::Match => write!(f, "match"),
             Token::Async => write!(f, "async"),
             Token::Return => write!(f, "return"),
@@ -203,7 +317,9 @@ impl fmt::Display for Token {
             Token::As => write!(f, "as"),
             Token::Ident(s) => write!(f, "{}", s),
             Token::IntLit(n) => write!(f, "{}", n),
+            Token::Int64Lit(n) => write!(f, "{}L", n),
             Token::FloatLit(n) => write!(f, "{}", n),
+            Token::DecimalLit(n) => write!(f, "{}d", format_decimal(*n)),
             Token::StringLit(s) => write!(f, "\"{}\"", s),
             Token::CharLit(c) => write!(f, "'{}'", c),
             Token::Pipe => write!(f, "|>"),
@@ -211,6 +327,7 @@ impl fmt::Display for Token {
             Token::Assign => write!(f, "="),
             Token::Arrow => write!(f, "=>"),
             Token::ThinArrow => write!(f, "->"),
+            Token::LeftArrow => write!(f, "<-"),
             Token::Plus => write!(f, "+"),
             Token::Minus => write!(f, "-"),
             Token::Star => write!(f, "*"),
@@ -227,6 +344,8 @@ impl fmt::Display for Token {
             Token::And => write!(f, "&&"),
             Token::Or => write!(f, "||"),
             Token::Tilde => write!(f, "~"),
+            Token::At => write!(f, "@"),
+            Token::Question => write!(f, "?"),
             Token::LBrace => write!(f, "{{"),
             Token::RBrace => write!(f, "}}"),
             Token::LParen => write!(f, "("),
@@ -237,6 +356,7 @@ impl fmt::Display for Token {
             Token::Colon => write!(f, ":"),
             Token::Dot => write!(f, "."),
             Token::DotDot => write!(f, ".."),
+            Token::DotDotEq => write!(f, "..="),
             Token::DotDotDot => write!(f, "..."),
             Token::Semicolon => write!(f, ";"),
             Token::Eof => write!(f, "EOF"),
@@ -277,6 +397,9 @@ fn keyword(input: &str) -> IResult<&str, Token> {
         "then" => Token::Then,
         "else" => Token::Else,
         "while" => Token::While,
+        "loop" => Token::Loop,
+        "break" => Token::Break,
+        "continue" => Token::Continue,
         "match" => Token::Match,
         "async" => Token::Async,
         "return" => Token::Return,
@@ -324,7 +447,7 @@ fn integer(input: &str) -> IResult<&str, Token> {
         let digits = literal.replace('_', "");
         let value = i64::from_str_radix(&digits, 16)
             .map_err(|_| lexer_error(input, nom::error::ErrorKind::MapRes))?;
-        return Ok((&rest[len..], Token::IntLit(value)));
+        return Ok(integer_suffix(&rest[len..], value));
     }
 
     let len = input
@@ -342,7 +465,21 @@ fn integer(input: &str) -> IResult<&str, Token> {
     let value = digits
         .parse::<i64>()
         .map_err(|_| lexer_error(input, nom::error::ErrorKind::MapRes))?;
-    Ok((&input[len..], Token::IntLit(value)))
+    Ok(integer_suffix(&input[len..], value))
+}
+
+/// An `L` directly after the digits makes the literal an `Int64` literal.
+fn integer_suffix(rest: &str, value: i64) -> (&str, Token) {
+    if let Some(after) = rest.strip_prefix('L') {
+        if !after
+            .chars()
+            .next()
+            .is_some_and(|ch| ch.is_alphanumeric() || ch == '_')
+        {
+            return (after, Token::Int64Lit(value));
+        }
+    }
+    (rest, Token::IntLit(value))
 }
 
 fn scan_decimal_digits(input: &str, mut idx: usize) -> usize {
@@ -356,6 +493,73 @@ fn scan_decimal_digits(input: &str, mut idx: usize) -> usize {
     idx
 }
 
+/// A `d` directly after a plain or fractional number makes a `Decimal`
+/// literal. More fractional digits than `Decimal` keeps is an error rather
+/// than a silent rounding.
+fn decimal(input: &str) -> IResult<&str, Token> {
+    if !input.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return Err(lexer_error(input, nom::error::ErrorKind::Digit));
+    }
+
+    let whole_end = scan_decimal_digits(input, 0);
+    let mut idx = whole_end;
+    let mut fraction = String::new();
+    if input[idx..].starts_with('.') {
+        let frac_start = idx + 1;
+        let frac_end = scan_decimal_digits(input, frac_start);
+        if frac_end == frac_start {
+            return Err(lexer_error(input, nom::error::ErrorKind::Float));
+        }
+        fraction = input[frac_start..frac_end].replace('_', "");
+        idx = frac_end;
+    }
+
+    let Some(rest) = input[idx..].strip_prefix('d') else {
+        return Err(lexer_error(input, nom::error::ErrorKind::Float));
+    };
+    if rest.starts_with(|ch: char| ch.is_alphanumeric() || ch == '_') {
+        return Err(lexer_error(input, nom::error::ErrorKind::Float));
+    }
+    if fraction.len() > DECIMAL_PLACES as usize {
+        // `lex_collecting` reports these through `over_precise_decimal_len`
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Float,
+        )));
+    }
+
+    let whole = input[..whole_end]
+        .replace('_', "")
+        .parse::<i64>()
+        .map_err(|_| lexer_error(input, nom::error::ErrorKind::MapRes))?;
+    let fraction = format!("{:0<width$}", fraction, width = DECIMAL_PLACES as usize)
+        .parse::<i64>()
+        .map_err(|_| lexer_error(input, nom::error::ErrorKind::MapRes))?;
+    let value = whole
+        .checked_mul(DECIMAL_SCALE)
+        .and_then(|scaled| scaled.checked_add(fraction))
+        .ok_or_else(|| lexer_error(input, nom::error::ErrorKind::MapRes))?;
+    Ok((rest, Token::DecimalLit(value)))
+}
+
+/// Byte length of the `Decimal` literal at the start of `input` when it has
+/// more fractional digits than `Decimal` keeps, which [`decimal`] rejects.
+fn over_precise_decimal_len(input: &str) -> Option<usize> {
+    let whole_end = scan_decimal_digits(input, 0);
+    if whole_end == 0 || !input[whole_end..].starts_with('.') {
+        return None;
+    }
+    let frac_end = scan_decimal_digits(input, whole_end + 1);
+    let places = input[whole_end + 1..frac_end].replace('_', "").len();
+    let after = input[frac_end..].strip_prefix('d')?;
+    if places <= DECIMAL_PLACES as usize
+        || after.starts_with(|ch: char| ch.is_alphanumeric() || ch == '_')
+    {
+        return None;
+    }
+    Some(frac_end + 1)
+}
+
 fn float(input: &str) -> IResult<&str, Token> {
     let first = input
         .chars()
@@ -382,12 +586,26 @@ fn float(input: &str) -> IResult<&str, Token> {
     if matches!(input[idx..].chars().next(), Some('e' | 'E')) {
         let exp_marker = idx;
         idx += 1;
-        if matches!(input[idx..].chars().next(), Some('+' | '-')) {
+        let has_sign = matches!(input[idx..].chars().next(), Some('+' | '-'));
+        if has_sign {
             idx += 1;
         }
         let exp_start = idx;
         idx = scan_decimal_digits(input, idx);
         if idx == exp_start {
+            // `1e`, `1e+` and `2.5E-` are malformed exponents rather than a
+            // number followed by an identifier; only `1ex`-style words fall
+            // back to the integer lexer.
+            let continues_word = input[exp_marker + 1..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_');
+            if has_sign || has_dot || !continues_word {
+                return Err(nom::Err::Failure(nom::error::Error::new(
+                    input,
+                    nom::error::ErrorKind::Float,
+                )));
+            }
             return Err(lexer_error(
                 &input[exp_marker..],
                 nom::error::ErrorKind::Float,
@@ -408,6 +626,9 @@ fn float(input: &str) -> IResult<&str, Token> {
 }
 
 fn string_lit(input: &str) -> IResult<&str, Token> {
+    if input.starts_with(RAW_STRING_DELIMITER) {
+        return raw_string_lit(input);
+    }
     let Some(rest) = input.strip_prefix('"') else {
         return Err(lexer_error(input, nom::error::ErrorKind::Char));
     };
@@ -434,6 +655,55 @@ fn string_lit(input: &str) -> IResult<&str, Token> {
     Err(lexer_error(input, nom::error::ErrorKind::TakeUntil))
 }
 
+const RAW_STRING_DELIMITER: &str = "\"\"\"";
+
+/// Lexes a `"""..."""` raw string. The body runs to the first closing `"""`
+/// and is taken verbatim: backslashes are not escapes, and quotes and
+/// newlines need no escaping. The value is then tidied for indented source:
+///
+/// - a line break right after the opening `"""` is dropped,
+/// - whitespace before a closing `"""` on its own line is dropped,
+/// - the indentation shared by every non-blank line is removed.
+fn raw_string_lit(input: &str) -> IResult<&str, Token> {
+    let body_start = &input[RAW_STRING_DELIMITER.len()..];
+    let Some(end) = body_start.find(RAW_STRING_DELIMITER) else {
+        return Err(lexer_error(input, nom::error::ErrorKind::TakeUntil));
+    };
+    let rest = &body_start[end + RAW_STRING_DELIMITER.len()..];
+
+    let mut body = &body_start[..end];
+    body = body
+        .strip_prefix("\r\n")
+        .or_else(|| body.strip_prefix('\n'))
+        .unwrap_or(body);
+    if let Some(last_break) = body.rfind('\n') {
+        if body[last_break + 1..].trim_matches([' ', '\t']).is_empty() {
+            body = &body[..last_break + 1];
+        }
+    }
+
+    Ok((rest, Token::StringLit(dedent(body))))
+}
+
+/// Removes the longest run of leading spaces and tabs that every non-blank
+/// line of `text` starts with.
+fn dedent(text: &str) -> String {
+    let indent_len = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let common = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(indent_len)
+        .min()
+        .unwrap_or(0);
+    if common == 0 {
+        return text.to_string();
+    }
+
+    text.split_inclusive('\n')
+        .map(|line| &line[common.min(indent_len(line))..])
+        .collect()
+}
+
 fn char_lit(input: &str) -> IResult<&str, Token> {
     let Some(rest) = input.strip_prefix('\'') else {
         return Err(lexer_error(input, nom::error::ErrorKind::Char));
@@ -488,6 +758,7 @@ fn operator(input: &str) -> IResult<&str, Token> {
             value(Token::Pipe, tag("|>")),
             value(Token::Or, tag("||")),
             value(Token::DotDotDot, tag("...")), // Check ... before .
+            value(Token::DotDotEq, tag("..=")),
             value(Token::DotDot, tag("..")),
             value(Token::ThinArrow, tag("->")),
             value(Token::Arrow, tag("=>")),
@@ -495,6 +766,7 @@ fn operator(input: &str) -> IResult<&str, Token> {
             value(Token::Ne, tag("!=")),
             value(Token::And, tag("&&")),
             value(Token::Le, tag("<=")),
+            value(Token::LeftArrow, tag("<-")),
             value(Token::Ge, tag(">=")),
         )),
         alt((
@@ -509,6 +781,8 @@ fn operator(input: &str) -> IResult<&str, Token> {
             value(Token::Lt, tag("<")),
             value(Token::Gt, tag(">")),
             value(Token::Tilde, tag("~")),
+            value(Token::At, tag("@")),
+            value(Token::Question, tag("?")),
         )),
     ))(input)
 }
@@ -530,7 +804,7 @@ fn delimiter(input: &str) -> IResult<&str, Token> {
 
 fn token(input: &str) -> IResult<&str, Token> {
     alt((
-        float, integer, keyword, string_lit, char_lit, operator, delimiter,
+        decimal, float, integer, keyword, string_lit, char_lit, operator, delimiter,
     ))(input)
 }
 
@@ -566,6 +840,123 @@ pub fn lex(input: &str) -> IResult<&str, Vec<Token>> {
     Ok((input, tokens))
 }
 
+/// Tokenizes `input` like [`lex`], pairing each token with the byte range of
+/// its source text.
+pub fn lex_spanned(input: &str) -> IResult<&str, Vec<(Token, Range<usize>)>> {
+    let mut rest = input;
+    let mut tokens = Vec::new();
+    loop {
+        let (after_skip, _) = skip(rest)?;
+        match token(after_skip) {
+            Ok((after_token, tok)) => {
+                let start = input.len() - after_skip.len();
+                let end = input.len() - after_token.len();
+                tokens.push((tok, start..end));
+                rest = after_token;
+            }
+            Err(nom::Err::Error(_)) => return Ok((after_skip, tokens)),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A point where no token could be read, as reported by [`lex_collecting`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LexError {
+    /// Byte offset of the offending character in the source
+    pub offset: usize,
+    /// The character no token can start with, or that starts a malformed
+    /// token such as an unterminated string
+    pub found: char,
+    /// Why no token could be read here
+    pub kind: LexErrorKind,
+}
+
+/// The reason behind a [`LexError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexErrorKind {
+    /// No token starts with the character
+    UnexpectedCharacter,
+    /// A `Decimal` literal, `len` bytes long, with more fractional digits
+    /// than `Decimal` keeps
+    DecimalTooPrecise { len: usize },
+}
+
+impl LexError {
+    /// The error as a user diagnostic with a 1-based line and column.
+    pub fn describe(&self, source: &str) -> String {
+        let (line, column) = line_column(source, self.offset);
+        match self.kind {
+            LexErrorKind::UnexpectedCharacter => format!(
+                "Lexing error at line {line}, column {column}: unexpected character {:?}",
+                self.found
+            ),
+            LexErrorKind::DecimalTooPrecise { len } => format!(
+                "Lexing error at line {line}, column {column}: Decimal literal `{}` has more than {DECIMAL_PLACES} decimal places",
+                &source[self.offset..self.offset + len]
+            ),
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LexErrorKind::UnexpectedCharacter => write!(
+                f,
+                "unexpected character {:?} at byte {}",
+                self.found, self.offset
+            ),
+            LexErrorKind::DecimalTooPrecise { .. } => write!(
+                f,
+                "Decimal literal with more than {DECIMAL_PLACES} decimal places at byte {}",
+                self.offset
+            ),
+        }
+    }
+}
+
+/// Tokenizes all of `source`, recovering from lexical errors. Each time no
+/// token can be read, the offending character is recorded and skipped and
+/// lexing resumes after it, so one pass reports every bad character. The
+/// tokens are those [`lex`] would produce with the bad characters removed.
+pub fn lex_collecting(source: &str) -> (Vec<Token>, Vec<LexError>) {
+    let mut rest = source;
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    loop {
+        // `skip` never fails; it stops at the first non-trivia input.
+        rest = skip(rest).map_or(rest, |(after_skip, _)| after_skip);
+        let Some(found) = rest.chars().next() else {
+            return (tokens, errors);
+        };
+        match token(rest) {
+            Ok((after_token, tok)) => {
+                tokens.push(tok);
+                rest = after_token;
+            }
+            Err(_) => {
+                let offset = source.len() - rest.len();
+                if let Some(len) = over_precise_decimal_len(rest) {
+                    errors.push(LexError {
+                        offset,
+                        found,
+                        kind: LexErrorKind::DecimalTooPrecise { len },
+                    });
+                    rest = &rest[len..];
+                    continue;
+                }
+                errors.push(LexError {
+                    offset,
+                    found,
+                    kind: LexErrorKind::UnexpectedCharacter,
+                });
+                rest = &rest[found.len_utf8()..];
+            }
+        }
+    }
+}
+
 // Wrapper function that tokenizes the entire input or returns an error
 pub fn lex_tokens(input: &str) -> Result<Vec<Token>, String> {
     match lex(input) {
@@ -612,6 +1003,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_kind_name_drops_the_payload() {
+        assert_eq!(Token::Ident("x".to_string()).kind_name(), "Ident");
+        assert_eq!(Token::IntLit(1).kind_name(), "IntLit");
+        assert_eq!(Token::DotDotEq.kind_name(), "DotDotEq");
+        assert_eq!(Token::Eof.kind_name(), "Eof");
+    }
+
     #[test]
     fn test_operators() {
         assert_eq!(lex("|>").unwrap().1, vec![Token::Pipe]);
@@ -620,6 +1019,8 @@ mod tests {
         assert_eq!(lex("||").unwrap().1, vec![Token::Or]);
         assert_eq!(lex("!").unwrap().1, vec![Token::Not]);
         assert_eq!(lex("=>").unwrap().1, vec![Token::Arrow]);
+        assert_eq!(lex("<-").unwrap().1, vec![Token::LeftArrow]);
+        assert_eq!(lex("< -").unwrap().1, vec![Token::Lt, Token::Minus]);
     }
 
     #[test]
@@ -639,6 +1040,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_int64_suffix_literals() {
+        let tokens = lex("123L 0xFFL 1_000L 5Lx").unwrap().1;
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Int64Lit(123),
+                Token::Int64Lit(255),
+                Token::Int64Lit(1_000),
+                Token::IntLit(5),
+                Token::Ident("Lx".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decimal_suffix_literals() {
+        let tokens = lex("1.99d 5d 0.1d 1_000.50d 2dx").unwrap().1;
+        assert_eq!(
+            tokens,
+            vec![
+                Token::DecimalLit(19_900),
+                Token::DecimalLit(50_000),
+                Token::DecimalLit(1_000),
+                Token::DecimalLit(10_005_000),
+                Token::IntLit(2),
+                Token::Ident("dx".to_string()),
+            ]
+        );
+        assert!(lex("1.23456d").is_err());
+    }
+
+    #[test]
+    fn lex_collecting_names_over_precise_decimal_literals() {
+        let source = "val x = 1.23456d + 1d";
+        let (tokens, errors) = lex_collecting(source);
+
+        assert_eq!(
+            errors,
+            vec![LexError {
+                offset: 8,
+                found: '1',
+                kind: LexErrorKind::DecimalTooPrecise { len: 8 },
+            }]
+        );
+        assert_eq!(
+            errors[0].describe(source),
+            "Lexing error at line 1, column 9: Decimal literal `1.23456d` has more than 4 decimal places"
+        );
+        assert_eq!(tokens.last(), Some(&Token::DecimalLit(10_000)));
+    }
+
     #[test]
     fn test_spec_number_literals() {
         let tokens = lex("0xFF 1_000_000 1.5e10 3.14E-2").unwrap().1;
@@ -653,6 +1106,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scientific_float_literals() {
+        let tokens = lex("1e10 2.5e-3 1E6 1.0e2 4e+2").unwrap().1;
+        assert_eq!(
+            tokens,
+            vec![
+                Token::FloatLit(1e10),
+                Token::FloatLit(2.5e-3),
+                Token::FloatLit(1e6),
+                Token::FloatLit(100.0),
+                Token::FloatLit(400.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_float_literals_need_digits_on_both_sides_of_the_dot() {
+        assert_eq!(lex("1.").unwrap().1, vec![Token::IntLit(1), Token::Dot]);
+        assert_eq!(lex(".5").unwrap().1, vec![Token::Dot, Token::IntLit(5)]);
+        assert_eq!(
+            lex("1..5").unwrap().1,
+            vec![Token::IntLit(1), Token::DotDot, Token::IntLit(5)]
+        );
+    }
+
+    #[test]
+    fn test_float_exponent_without_digits_is_rejected() {
+        for source in ["1e", "1e+", "2.5E-", "3e x"] {
+            assert!(
+                matches!(lex_token(source), Err(nom::Err::Failure(_))),
+                "{source} should be a malformed exponent"
+            );
+        }
+    }
+
     #[test]
     fn test_spec_string_and_char_escapes() {
         let tokens = lex(r#""a\nb\t\\\"\'" '\n' '\t' '\\' '\''"#).unwrap().1;
@@ -668,6 +1156,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_raw_multiline_string_keeps_quotes_newlines_and_backslashes() {
+        let source = "val json = \"\"\"\n    {\"name\": \"ada\",\n      \"path\": \"C:\\temp\\n\"}\n    \"\"\"\n";
+        let tokens = lex(source).unwrap().1;
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Val,
+                Token::Ident("json".to_string()),
+                Token::Assign,
+                Token::StringLit("{\"name\": \"ada\",\n  \"path\": \"C:\\temp\\n\"}\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_string_indentation_rules() {
+        // Inline raw strings are verbatim.
+        assert_eq!(
+            lex(r#""""say "hi" \n""""#).unwrap().1,
+            vec![Token::StringLit(r#"say "hi" \n"#.to_string())]
+        );
+        // Blank lines do not limit the shared indentation, and text on the
+        // closing line is kept.
+        assert_eq!(
+            lex("\"\"\"\n    first\n\n      second\n    last\"\"\"")
+                .unwrap()
+                .1,
+            vec![Token::StringLit("first\n\n  second\nlast".to_string())]
+        );
+        // Only one leading line break is dropped.
+        assert_eq!(
+            lex("\"\"\"\n\nbody\n\"\"\"").unwrap().1,
+            vec![Token::StringLit("\nbody\n".to_string())]
+        );
+        assert_eq!(
+            lex("\"\"\"\"\"\"").unwrap().1,
+            vec![Token::StringLit(String::new())]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_raw_string_is_an_error() {
+        assert!(lex_token("\"\"\"never closed\n\"").is_err());
+    }
+
     #[test]
     fn test_temporal_tilde() {
         let tokens = lex("record File<~f> { }").unwrap().1;
@@ -752,10 +1286,10 @@ mod tests {
 
     #[test]
     fn lex_tokens_formats_leftover_input_as_user_diagnostic() {
-        let message = lex_tokens("val x = 1\nval y = @").expect_err("unknown token should fail");
+        let message = lex_tokens("val x = 1\nval y = $").expect_err("unknown token should fail");
 
         assert!(message.contains("Lexing error at line 2, column 9"));
-        assert!(message.contains("unexpected input near `@`"));
+        assert!(message.contains("unexpected input near `$`"));
         assert_no_raw_nom_debug(&message);
     }
 
@@ -768,6 +1302,91 @@ mod tests {
         assert_no_raw_nom_debug(&message);
     }
 
+    #[test]
+    fn lex_collecting_reports_the_position_of_an_invalid_character() {
+        let source = "val x = 1\nval y = $";
+        let (tokens, errors) = lex_collecting(source);
+
+        assert_eq!(
+            errors,
+            vec![LexError {
+                offset: 18,
+                found: '$',
+                kind: LexErrorKind::UnexpectedCharacter,
+            }]
+        );
+        assert_eq!(
+            errors[0].describe(source),
+            "Lexing error at line 2, column 9: unexpected character '$'"
+        );
+        assert_eq!(errors[0].to_string(), "unexpected character '$' at byte 18");
+        assert_eq!(tokens.len(), 7);
+    }
+
+    #[test]
+    fn lex_collecting_recovers_past_each_invalid_character() {
+        let source = "val a = 1 $ + 2\nval b = § # 3 // done\n";
+        let (tokens, errors) = lex_collecting(source);
+
+        assert_eq!(
+            errors,
+            vec![
+                LexError {
+                    offset: 10,
+                    found: '$',
+                    kind: LexErrorKind::UnexpectedCharacter,
+                },
+                LexError {
+                    offset: 24,
+                    found: '§',
+                    kind: LexErrorKind::UnexpectedCharacter,
+                },
+                LexError {
+                    offset: 27,
+                    found: '#',
+                    kind: LexErrorKind::UnexpectedCharacter,
+                },
+            ]
+        );
+        let (_, expected) = lex("val a = 1 + 2\nval b = 3").unwrap();
+        assert_eq!(tokens, expected);
+
+        let (tokens, errors) = lex_collecting("fun main: () -> Int32 = { 0 }");
+        assert!(errors.is_empty());
+        assert_eq!(tokens, lex("fun main: () -> Int32 = { 0 }").unwrap().1);
+    }
+
+    #[test]
+    fn lex_spanned_matches_lex_and_records_source_ranges() {
+        let source = "val total = (a, 10) add // sum\n\"hi\" |> print";
+        let (rest, spanned) = lex_spanned(source).unwrap();
+        let (_, tokens) = lex(source).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(
+            spanned
+                .iter()
+                .map(|(tok, _)| tok.clone())
+                .collect::<Vec<_>>(),
+            tokens
+        );
+        let texts: Vec<&str> = spanned
+            .iter()
+            .map(|(_, span)| &source[span.clone()])
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["val", "total", "=", "(", "a", ",", "10", ")", "add", "\"hi\"", "|>", "print"]
+        );
+    }
+
+    #[test]
+    fn lex_spanned_stops_at_unknown_input() {
+        let (rest, spanned) = lex_spanned("val x = $").unwrap();
+        assert_eq!(rest, "$");
+        assert_eq!(spanned.len(), 3);
+    }
+
     fn assert_no_raw_nom_debug(message: &str) {
         assert!(
             !message.contains("Error("),
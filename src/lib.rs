@@ -50,7 +50,9 @@
 //! - [`parser`] - Parsing Restrict Language's OSV syntax
 //! - [`type_checker`] - Type checking with affine types and generic inference
 //! - [`ir`] - Typed IR, layout metadata, and optimization-stage foundations
+//! - [`const_eval`] - Constant folding of literal-only expressions
 //! - [`codegen`] - WebAssembly code generation with monomorphization
+//! - [`peephole`] - Peephole cleanup of generated WAT for optimized builds
 //! - [`module`] - Module system for managing imports/exports
 //! - [`lsp`] - Language Server Protocol implementation for IDE support
 
@@ -76,9 +78,21 @@ pub mod type_constraints;
 /// Typed IR, internal ABI layout, and optimization-stage foundations
 pub mod ir;
 
+/// Compile-time folding of constant expressions
+pub mod const_eval;
+
 /// Code generation module for producing WebAssembly output
 pub mod codegen;
 
+/// Peephole optimization over generated WAT
+pub mod peephole;
+
+/// Assembly and validation of generated WAT
+pub mod wat_verify;
+
+/// Lints over the constant-folded program
+pub mod lint;
+
 /// Lifetime inference module for Temporal Affine Types
 pub mod lifetime_inference;
 
@@ -88,12 +102,21 @@ pub mod test_framework;
 /// Debug visualizer for AST and type information
 pub mod debug_visualizer;
 
+/// Interactive read-eval-print loop core
+pub mod repl;
+
 /// Module system for managing imports and exports
 pub mod module;
 
+/// Functions imported from the host, such as foreign dependency exports
+pub mod foreign;
+
 /// User-facing diagnostic formatting helpers
 pub mod diagnostics;
 
+/// Stable error codes and their `--explain` descriptions
+pub mod error_codes;
+
 /// v0.0.1 release-surface validation
 pub mod release_surface;
 
@@ -110,13 +133,15 @@ pub mod web;
 
 // Re-exports for convenience
 pub use ast::*;
-pub use codegen::{CodeGenError, WasmCodeGen};
+pub use codegen::{CodeGenError, Target, WasmCodeGen};
+pub use foreign::ForeignFunction;
 pub use lexer::*;
 pub use parser::*;
 pub use release_surface::{check_v001_release_surface, ReleaseSurfaceError};
 pub use type_checker::{
-    format_typed_type, type_check, TemporalConstraint as TypeCheckerTemporalConstraint,
-    TemporalContext, TypeChecker, TypeError, TypeSubstitution, TypedType,
+    format_typed_type, type_check, CheckPhase, TemporalConstraint as TypeCheckerTemporalConstraint,
+    TemporalContext, TraceEvent, Tracer, TypeChecker, TypeError, TypeSubstitution, TypedType,
+    Warning,
 };
 
 /// Legacy convenience function for tests
@@ -199,9 +199,10 @@ impl LifetimeInference {
                 Stmt::Assignment(assign_stmt) => {
                     self.collect_from_expr(&assign_stmt.value)?;
                 }
-                Stmt::Expr(expr) => {
+                Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
                     self.collect_from_expr(expr)?;
                 }
+                Stmt::Break(None) | Stmt::Continue => {}
             }
         }
 
@@ -0,0 +1,94 @@
+//! # Lints
+//!
+//! Warnings about code that type checks but is probably not what the author
+//! meant. Lints run on the constant-folded program, so a condition such as
+//! `1 == 1` is seen as the literal `true` it folds to.
+
+use crate::ast::{visit_program_exprs_mut, Expr, ExprKind, Program};
+use crate::const_eval::fold_constants;
+use crate::type_checker::Warning;
+
+/// Collects lint warnings for `program` in source order.
+pub fn lint_program(program: &Program) -> Vec<Warning> {
+    let mut folded = fold_constants(program);
+    let mut warnings = Vec::new();
+    visit_program_exprs_mut(&mut folded, &mut |expr| lint_expr(expr, &mut warnings));
+    warnings
+}
+
+fn lint_expr(expr: &Expr, warnings: &mut Vec<Warning>) {
+    match &expr.kind {
+        ExprKind::Then(then) => {
+            let conditions =
+                std::iter::once(&then.condition).chain(then.else_ifs.iter().map(|(cond, _)| cond));
+            for condition in conditions {
+                if let ExprKind::BoolLit(value) = condition.kind {
+                    warnings.push(Warning::ConstantBranchCondition(value));
+                }
+            }
+        }
+        ExprKind::While(while_expr) => {
+            if let ExprKind::BoolLit(value) = while_expr.condition.kind {
+                warnings.push(Warning::ConstantLoopCondition(value));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_program;
+
+    fn lint(source: &str) -> Vec<Warning> {
+        let (_, program) = parse_program(source).unwrap();
+        lint_program(&program)
+    }
+
+    #[test]
+    fn constant_false_branch_is_dead_code() {
+        let warnings = lint(
+            r#"
+fun main: () -> Int32 = {
+    false then { 1 } else { 2 }
+}
+"#,
+        );
+        assert_eq!(warnings, vec![Warning::ConstantBranchCondition(false)]);
+        assert!(warnings[0].to_string().contains("dead code"));
+    }
+
+    #[test]
+    fn folded_loop_condition_is_likely_infinite() {
+        let warnings = lint(
+            r#"
+fun main: () -> Int32 = {
+    mut val n = 0;
+    1 < 2 while {
+        n = n + 1
+    }
+    n
+}
+"#,
+        );
+        assert_eq!(warnings, vec![Warning::ConstantLoopCondition(true)]);
+        assert!(warnings[0].to_string().contains("infinite"));
+    }
+
+    #[test]
+    fn runtime_conditions_are_not_flagged() {
+        let warnings = lint(
+            r#"
+fun main: (limit: Int32) -> Int32 = {
+    mut val n = 0;
+    n < limit while {
+        n = n + 1
+    }
+    n == 1 then { 1 } else n == 2 then { 2 } else { 3 }
+}
+"#,
+        );
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+}
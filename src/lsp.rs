@@ -1,5 +1,6 @@
-use crate::ast::{Literal, Pattern};
-use crate::diagnostics::{format_lex_error, format_parse_error};
+use crate::ast::Span;
+use crate::ast::{format_decimal, Literal, Pattern};
+use crate::diagnostics::{format_lex_error, format_parse_error, span_to_line_col, utf16_len};
 use crate::module::resolve_program_imports_for_file;
 use crate::release_surface::check_v001_release_surface;
 use crate::type_checker::TypeError;
@@ -46,12 +47,14 @@ fn diagnostic_for_message(source: &str, message: String) -> Diagnostic {
 }
 
 fn diagnostic_for_type_error(source: &str, error: &TypeError) -> Diagnostic {
-    diagnostic_for_message(source, lsp_type_error_message(error))
+    let mut diagnostic = diagnostic_for_message(source, lsp_type_error_message(error));
+    diagnostic.code = Some(NumberOrString::String(error.code().to_string()));
+    diagnostic
 }
 
 fn diagnostic_range_for_message(source: &str, message: &str) -> Range {
-    if let Some(position) = line_column_from_message(message) {
-        return single_character_range(source, position);
+    if let Some((line, column)) = line_column_from_message(message) {
+        return single_character_range(source, line, column);
     }
 
     if let Some(binding_name) = binding_name_from_message(message) {
@@ -75,7 +78,8 @@ fn diagnostic_range_for_message(source: &str, message: &str) -> Range {
     Range::new(Position::new(0, 0), Position::new(0, 1))
 }
 
-fn line_column_from_message(message: &str) -> Option<Position> {
+/// 1-based line and byte column reported by a lex or parse error message.
+fn line_column_from_message(message: &str) -> Option<(usize, usize)> {
     let line_start = message.find("line ")? + "line ".len();
     let line_end = line_start
         + message[line_start..]
@@ -83,7 +87,7 @@ fn line_column_from_message(message: &str) -> Option<Position> {
             .take_while(|ch| ch.is_ascii_digit())
             .map(char::len_utf8)
             .sum::<usize>();
-    let line = message[line_start..line_end].parse::<u32>().ok()?;
+    let line = message[line_start..line_end].parse::<usize>().ok()?;
 
     let column_marker = message[line_end..].find("column ")? + line_end + "column ".len();
     let column_end = column_marker
@@ -92,12 +96,9 @@ fn line_column_from_message(message: &str) -> Option<Position> {
             .take_while(|ch| ch.is_ascii_digit())
             .map(char::len_utf8)
             .sum::<usize>();
-    let column = message[column_marker..column_end].parse::<u32>().ok()?;
+    let column = message[column_marker..column_end].parse::<usize>().ok()?;
 
-    Some(Position::new(
-        line.saturating_sub(1),
-        column.saturating_sub(1),
-    ))
+    Some((line, column))
 }
 
 fn binding_name_from_message(message: &str) -> Option<&str> {
@@ -183,20 +184,37 @@ fn binding_name_range(source: &str, binding_name: &str) -> Option<Range> {
     None
 }
 
-fn single_character_range(source: &str, position: Position) -> Range {
-    let line = source.lines().nth(position.line as usize).unwrap_or("");
-    let line_len = line.chars().count() as u32;
-    let start_character = position.character.min(line_len);
-    let end_character = start_character.saturating_add(1).min(line_len.max(1));
+fn single_character_range(source: &str, line: usize, column: usize) -> Range {
+    let line_start = source
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let line_text = source[line_start..].lines().next().unwrap_or("");
+    let mut start = line_start + column.saturating_sub(1).min(line_text.len());
+    while !source.is_char_boundary(start) {
+        start -= 1;
+    }
+    let end = source[start..]
+        .chars()
+        .next()
+        .filter(|ch| *ch != '\n')
+        .map_or(start, |ch| start + ch.len_utf8());
+
+    span_range(source, Span { start, end })
+}
 
+/// LSP range for a byte span; LSP positions are 0-based with UTF-16 columns.
+fn span_range(source: &str, span: Span) -> Range {
+    let (start, end) = span_to_line_col(source, span);
     Range::new(
-        Position::new(position.line, start_character),
-        Position::new(position.line, end_character),
+        Position::new((start.line - 1) as u32, (start.column - 1) as u32),
+        Position::new((end.line - 1) as u32, (end.column - 1) as u32),
     )
 }
 
 fn byte_to_character(line: &str, byte_index: usize) -> usize {
-    line[..byte_index.min(line.len())].chars().count()
+    utf16_len(&line[..byte_index.min(line.len())])
 }
 
 fn collect_diagnostics_for_source(uri: &Url, text: &str) -> Vec<Diagnostic> {
@@ -207,8 +225,13 @@ fn collect_diagnostics_for_source(uri: &Url, text: &str) -> Vec<Diagnostic> {
         Ok((remaining, _tokens)) => {
             // Only report unparsed input if it contains non-whitespace characters
             if !remaining.trim().is_empty() {
-                diagnostics.push(diagnostic_for_message(
-                    text,
+                let start = text.len() - remaining.trim_start().len();
+                let end = text[start..]
+                    .chars()
+                    .next()
+                    .map_or(start, |ch| start + ch.len_utf8());
+                diagnostics.push(Diagnostic::new_simple(
+                    span_range(text, Span { start, end }),
                     format!("Lexer: unparsed input remaining: '{}'", remaining.trim()),
                 ));
             }
@@ -372,6 +395,12 @@ fn builtin_completion_items() -> Vec<CompletionItem> {
         CompletionItem::new_simple("then".to_string(), "Conditional expression".to_string()),
         CompletionItem::new_simple("else".to_string(), "Else clause".to_string()),
         CompletionItem::new_simple("while".to_string(), "While loop".to_string()),
+        CompletionItem::new_simple("loop".to_string(), "Unconditional loop".to_string()),
+        CompletionItem::new_simple("break".to_string(), "Leave the innermost loop".to_string()),
+        CompletionItem::new_simple(
+            "continue".to_string(),
+            "Skip to the next loop iteration".to_string(),
+        ),
         CompletionItem::new_simple("match".to_string(), "Pattern matching".to_string()),
         CompletionItem::new_simple("with".to_string(), "Context binding".to_string()),
         CompletionItem::new_simple("clone".to_string(), "Clone object".to_string()),
@@ -398,11 +427,11 @@ fn builtin_completion_items() -> Vec<CompletionItem> {
         ),
         CompletionItem::new_simple(
             "max".to_string(),
-            "fun max: (a: Int32, b: Int32) -> Int32".to_string(),
+            "fun max: <T: Ord>(a: T, b: T) -> T".to_string(),
         ),
         CompletionItem::new_simple(
             "min".to_string(),
-            "fun min: (a: Int32, b: Int32) -> Int32".to_string(),
+            "fun min: <T: Ord>(a: T, b: T) -> T".to_string(),
         ),
         CompletionItem::new_simple(
             "pow".to_string(),
@@ -412,6 +441,135 @@ fn builtin_completion_items() -> Vec<CompletionItem> {
             "factorial".to_string(),
             "fun factorial: (n: Int32) -> Int32".to_string(),
         ),
+        CompletionItem::new_simple(
+            "sign".to_string(),
+            "fun sign: (x: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "gcd".to_string(),
+            "fun gcd: (a: Int32, b: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "lcm".to_string(),
+            "fun lcm: (a: Int32, b: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "wrapping_add".to_string(),
+            "fun wrapping_add: (a: Int32, b: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "wrapping_mul".to_string(),
+            "fun wrapping_mul: (a: Int32, b: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "saturating_add".to_string(),
+            "fun saturating_add: (a: Int32, b: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "saturating_sub".to_string(),
+            "fun saturating_sub: (a: Int32, b: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "checked_add".to_string(),
+            "fun checked_add: (a: Int32, b: Int32) -> Option<Int32>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "checked_sub".to_string(),
+            "fun checked_sub: (a: Int32, b: Int32) -> Option<Int32>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "checked_mul".to_string(),
+            "fun checked_mul: (a: Int32, b: Int32) -> Option<Int32>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "checked_div".to_string(),
+            "fun checked_div: (a: Int32, b: Int32) -> Option<Int32>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "clamp".to_string(),
+            "fun clamp: (value: Int32, min: Int32, max: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "checked".to_string(),
+            "fun checked: (value: Int32, min: Int32, max: Int32) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "string_length".to_string(),
+            "fun string_length: (text: String) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "string_char_at".to_string(),
+            "fun string_char_at: (text: String, index: Int32) -> Option<Char>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "string_from_char".to_string(),
+            "fun string_from_char: (value: Char) -> String".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "string_split".to_string(),
+            "fun string_split: (text: String, delimiter: Char) -> List<String>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "string_join".to_string(),
+            "fun string_join: (parts: List<String>, separator: String) -> String".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "char_to_int".to_string(),
+            "fun char_to_int: (value: Char) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "int_to_char".to_string(),
+            "fun int_to_char: (value: Int32) -> Char".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "is_digit".to_string(),
+            "fun is_digit: (value: Char) -> Boolean".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "is_alpha".to_string(),
+            "fun is_alpha: (value: Char) -> Boolean".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "int_to_string".to_string(),
+            "fun int_to_string: (value: Int32) -> String".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "float_to_string".to_string(),
+            "fun float_to_string: (value: Float64) -> String".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "bool_to_string".to_string(),
+            "fun bool_to_string: (value: Boolean) -> String".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "hash".to_string(),
+            "fun hash: <T: Hash>(value: T) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "parse_int".to_string(),
+            "fun parse_int: (text: String) -> Option<Int32>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "parse_float".to_string(),
+            "fun parse_float: (text: String) -> Option<Float64>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "to_i64".to_string(),
+            "fun to_i64: (value: Int32) -> Int64".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "to_i32".to_string(),
+            "fun to_i32: (value: Int64) -> Int32".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "decimal_div_half_even".to_string(),
+            "fun decimal_div_half_even: (dividend: Decimal, divisor: Decimal) -> Decimal"
+                .to_string(),
+        ),
+        CompletionItem::new_simple(
+            "decimal_div_half_up".to_string(),
+            "fun decimal_div_half_up: (dividend: Decimal, divisor: Decimal) -> Decimal".to_string(),
+        ),
         CompletionItem::new_simple(
             "list_head".to_string(),
             "fun list_head: <T>(list: List<T>) -> Option<T>".to_string(),
@@ -440,6 +598,60 @@ fn builtin_completion_items() -> Vec<CompletionItem> {
             "list_is_empty".to_string(),
             "fun list_is_empty: <T>(list: List<T>) -> Boolean".to_string(),
         ),
+        CompletionItem::new_simple(
+            "list_map".to_string(),
+            "fun list_map: <T, U>(mapper: (T) -> U, list: List<T>) -> List<U>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "list_filter".to_string(),
+            "fun list_filter: <T>(predicate: (T) -> Boolean, list: List<T>) -> List<T>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "list_contains".to_string(),
+            "fun list_contains: <T: Eq>(list: List<T>, item: T) -> Boolean".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "list_index_of".to_string(),
+            "fun list_index_of: <T: Eq>(list: List<T>, item: T) -> Option<Int32>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "list_take".to_string(),
+            "fun list_take: <T>(list: List<T>, count: Int32) -> List<T>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "list_drop".to_string(),
+            "fun list_drop: <T>(list: List<T>, count: Int32) -> List<T>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "list_slice".to_string(),
+            "fun list_slice: <T>(list: List<T>, start: Int32, end: Int32) -> List<T>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "list_fold".to_string(),
+            "fun list_fold: <T, A>(reducer: (A, T) -> A, initial: A, list: List<T>) -> A"
+                .to_string(),
+        ),
+        CompletionItem::new_simple(
+            "list_sort".to_string(),
+            "fun list_sort: <T>(compare: (T, T) -> Int32, list: List<T>) -> List<T>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "map_new".to_string(),
+            "fun map_new: <K: MapKey, V>() -> Map<K, V>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "map_insert".to_string(),
+            "fun map_insert: <K: MapKey, V>(map: Map<K, V>, key: K, value: V) -> Map<K, V>"
+                .to_string(),
+        ),
+        CompletionItem::new_simple(
+            "map_get".to_string(),
+            "fun map_get: <K: MapKey, V>(map: Map<K, V>, key: K) -> Option<V>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "map_remove".to_string(),
+            "fun map_remove: <K: MapKey, V>(map: Map<K, V>, key: K) -> Map<K, V>".to_string(),
+        ),
         CompletionItem::new_simple(
             "option_is_some".to_string(),
             "fun option_is_some: <T>(opt: Option<T>) -> Boolean".to_string(),
@@ -452,6 +664,18 @@ fn builtin_completion_items() -> Vec<CompletionItem> {
             "option_unwrap_or".to_string(),
             "fun option_unwrap_or: <T>(opt: Option<T>, default: T) -> T".to_string(),
         ),
+        CompletionItem::new_simple(
+            "option_ok_or".to_string(),
+            "fun option_ok_or: <T, E>(opt: Option<T>, err: E) -> Result<T, E>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "option_flatten".to_string(),
+            "fun option_flatten: <T>(opt: Option<Option<T>>) -> Option<T>".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "result_ok".to_string(),
+            "fun result_ok: <T, E>(result: Result<T, E>) -> Option<T>".to_string(),
+        ),
         CompletionItem::new_simple(
             "not".to_string(),
             "fun not: (b: Boolean) -> Boolean".to_string(),
@@ -468,10 +692,26 @@ fn builtin_completion_items() -> Vec<CompletionItem> {
             "identity".to_string(),
             "fun identity: <T>(x: T) -> T".to_string(),
         ),
+        CompletionItem::new_simple(
+            "drop".to_string(),
+            "fun drop: <T>(value: T) -> ()".to_string(),
+        ),
         CompletionItem::new_simple(
             "assert".to_string(),
             "fun assert: (condition: Boolean, message: String) -> ()".to_string(),
         ),
+        CompletionItem::new_simple(
+            "assert_that".to_string(),
+            "fun assert_that: (condition: Boolean) -> ()".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "assert_eq".to_string(),
+            "fun assert_eq: <T: Eq>(left: T, right: T, message: String) -> ()".to_string(),
+        ),
+        CompletionItem::new_simple(
+            "assert_ne".to_string(),
+            "fun assert_ne: <T: Eq>(left: T, right: T, message: String) -> ()".to_string(),
+        ),
         CompletionItem::new_simple(
             "panic".to_string(),
             "fun panic: (message: String) -> ()".to_string(),
@@ -483,6 +723,7 @@ fn literal_symbol_label(literal: &Literal) -> String {
     match literal {
         Literal::Int(value) => value.to_string(),
         Literal::Float(value) => value.to_string(),
+        Literal::Decimal(value) => format!("{}d", format_decimal(*value)),
         Literal::String(value) => format!("\"{value}\""),
         Literal::Char(value) => format!("'{value}'"),
         Literal::Bool(value) => value.to_string(),
@@ -490,14 +731,29 @@ fn literal_symbol_label(literal: &Literal) -> String {
     }
 }
 
+/// `Point ` for named record patterns; unnamed `val { .. }` patterns have none.
+fn record_pattern_prefix(name: &str) -> String {
+    if name.is_empty() {
+        String::new()
+    } else {
+        format!("{name} ")
+    }
+}
+
 fn pattern_symbol_label(pattern: &Pattern) -> String {
     match pattern {
         Pattern::Wildcard => "_".to_string(),
         Pattern::Literal(literal) => literal_symbol_label(literal),
+        Pattern::Range {
+            start,
+            end,
+            inclusive,
+        } => format!("{start}{}{end}", if *inclusive { "..=" } else { ".." }),
         Pattern::Ident(name) => name.clone(),
+        Pattern::Binding(name, inner) => format!("{} @ {}", name, pattern_symbol_label(inner)),
         Pattern::Record(name, fields) => format!(
-            "{} {{ {} }}",
-            name,
+            "{}{{ {} }}",
+            record_pattern_prefix(name),
             fields
                 .iter()
                 .map(|(field, pattern)| {
@@ -530,7 +786,11 @@ fn pattern_symbol_label(pattern: &Pattern) -> String {
                 parts.push(format!("...{rest}"));
             }
 
-            format!("{} {{ {} }}", type_name, parts.join(", "))
+            format!(
+                "{}{{ {} }}",
+                record_pattern_prefix(type_name),
+                parts.join(", ")
+            )
         }
         Pattern::Some(inner) => format!("Some({})", pattern_symbol_label(inner)),
         Pattern::None => "None".to_string(),
@@ -856,6 +1116,18 @@ impl RestrictLanguageServer {
                         .to_string(),
                 ),
                 "while" => Some("**while** - Loop expression\n\nLoop construct.".to_string()),
+                "loop" => Some(
+                    "**loop** - Unconditional loop\n\nRepeats its body until a `break`."
+                        .to_string(),
+                ),
+                "break" => Some(
+                    "**break** - Leave a loop\n\nLeaves the innermost loop, optionally with a value."
+                        .to_string(),
+                ),
+                "continue" => Some(
+                    "**continue** - Next iteration\n\nSkips to the next iteration of the innermost loop."
+                        .to_string(),
+                ),
                 "with" => {
                     Some("**with** - Context binding\n\nBinds context variables.".to_string())
                 }
@@ -873,10 +1145,11 @@ impl RestrictLanguageServer {
                     match word {
                         "println" => Some("**fun println: (s: String) -> ()**\n\nPrints a string followed by a newline.".to_string()),
                         "abs" => Some("**fun abs: (x: Int32) -> Int32**\n\nReturns the absolute value of an integer.".to_string()),
-                        "max" => Some("**fun max: (a: Int32, b: Int32) -> Int32**\n\nReturns the maximum of two integers.".to_string()),
-                        "min" => Some("**fun min: (a: Int32, b: Int32) -> Int32**\n\nReturns the minimum of two integers.".to_string()),
+                        "max" => Some("**fun max: <T: Ord>(a: T, b: T) -> T**\n\nReturns the maximum of two ordered values.".to_string()),
+                        "min" => Some("**fun min: <T: Ord>(a: T, b: T) -> T**\n\nReturns the minimum of two ordered values.".to_string()),
                         "list_head" => Some("**fun list_head: <T>(list: List<T>) -> Option<T>**\n\nReturns the first element of a list.".to_string()),
                         "list_tail" => Some("**fun list_tail: <T>(list: List<T>) -> Option<List<T>>**\n\nReturns the tail of a list.".to_string()),
+                        "map_get" => Some("**fun map_get: <K: MapKey, V>(map: Map<K, V>, key: K) -> Option<V>**\n\nLooks up a key in a map.".to_string()),
                         "option_unwrap_or" => Some("**fun option_unwrap_or: <T>(opt: Option<T>, default: T) -> T**\n\nUnwraps an Option or returns a default value.".to_string()),
                         _ => Some(format!("Symbol: **{}**", word)),
                     }
@@ -1137,6 +1410,9 @@ impl RestrictLanguageServer {
             ("then", SEMANTIC_TOKEN_KEYWORD),
             ("else", SEMANTIC_TOKEN_KEYWORD),
             ("while", SEMANTIC_TOKEN_KEYWORD),
+            ("loop", SEMANTIC_TOKEN_KEYWORD),
+            ("break", SEMANTIC_TOKEN_KEYWORD),
+            ("continue", SEMANTIC_TOKEN_KEYWORD),
             ("match", SEMANTIC_TOKEN_KEYWORD),
             ("with", SEMANTIC_TOKEN_KEYWORD),
             ("clone", SEMANTIC_TOKEN_KEYWORD),
@@ -1290,6 +1566,19 @@ mod tests {
         assert_eq!(diagnostic.range.end, Position::new(1, 13));
     }
 
+    #[test]
+    fn published_lex_diagnostics_use_utf16_columns_after_multibyte_text() {
+        let source = "fun main: () -> Int32 = {\n    val s = \"日本😀\" $\n}\n";
+        let uri = Url::parse("file:///tmp/multibyte.rl").expect("valid file uri");
+        let diagnostics = collect_diagnostics_for_source(&uri, source);
+
+        assert!(diagnostics[0]
+            .message
+            .starts_with("Lexer: unparsed input remaining"));
+        assert_eq!(diagnostics[0].range.start, Position::new(1, 19));
+        assert_eq!(diagnostics[0].range.end, Position::new(1, 20));
+    }
+
     #[test]
     fn published_diagnostics_include_release_surface_errors() {
         let source = r#"
@@ -1,30 +1,170 @@
-use restrict_lang::diagnostics::{format_lex_error, format_parse_error};
+use restrict_lang::debug_visualizer::visualize_with_types;
+use restrict_lang::diagnostics::{format_lex_error, format_parse_error, line_column};
+use restrict_lang::error_codes;
+use restrict_lang::lint::lint_program;
 use restrict_lang::module::resolve_program_imports_for_file;
-use restrict_lang::{check_v001_release_surface, lex, parse_program, TypeChecker, WasmCodeGen};
+use restrict_lang::repl::Repl;
+use restrict_lang::{
+    check_v001_release_surface, lex_collecting, lex_spanned, parse_program, ForeignFunction,
+    Target, TypeChecker, WasmCodeGen,
+};
+use serde::Serialize;
 use std::env;
 use std::fs;
+use std::io::{self, BufRead, Write};
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 #[cfg(not(target_arch = "wasm32"))]
 use restrict_lang::lsp;
 
 const BIN_NAME: &str = env!("CARGO_PKG_NAME");
 
+/// Type errors printed before the rest are summarized; see `--max-errors`.
+const DEFAULT_MAX_ERRORS: usize = 20;
+
 fn usage_text() -> String {
     format!(
         "\
 Usage: {BIN_NAME} [OPTIONS] <source_file> [output_file]
 Options:
   --version     Show compiler version
+  --explain <code>
+                Describe an error code such as E0001 with an example and fix
   --check       Check imports, types, and v0.0.1 release surface without code generation
   --ast         Show AST only (no compilation)
+  --dump-typed-ast
+                Type check, then show the AST with each expression's inferred type
+  --lib         Compile as a library without a `_start` entry wrapper
+  --max-errors <n>
+                Print at most n type errors, then a count of the rest (default 20)
+  --start-section
+                Run `@init` functions from a core-module `(start ...)` section
+  --optimize    Run peephole optimizations over the generated WAT
+  --release     Drop debug_assert/debug_print calls (implies --optimize)
+  --foreign <module.name=(Type, ...) -> Type>
+                Import a host function the program may call as `name`; repeatable
+  --target <wasi|freestanding>
+                Emit WASI imports (default), or none for hosts without WASI
+  --tail-calls  Emit self tail calls as `return_call` (Wasm tail-call proposal)
+  --verify      Assemble and validate the generated WAT, failing on invalid output
+  --tokens      Show the token stream, one token per line (no compilation)
+  --tokens-json Show the token stream as JSON (no compilation)
   --verbose     Show lexing, parsing, and codegen progress details
+  --time        Report how long lexing, parsing, type checking, and codegen took
   --lsp         Start Language Server Protocol mode
+  --repl        Start an interactive loop that type checks each entry
   --help        Show this help message
 "
     )
 }
 
+#[derive(Clone, Copy)]
+enum TokenFormat {
+    Lines,
+    Json,
+}
+
+/// One entry of the `--tokens-json` dump.
+#[derive(Serialize)]
+struct TokenRecord<'a> {
+    kind: &'static str,
+    text: &'a str,
+    start: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+}
+
+/// Prints the token stream for `--tokens` / `--tokens-json` and exits on lex errors.
+fn dump_tokens(source: &str, format: TokenFormat) {
+    let (remaining, tokens) = match lex_spanned(source) {
+        Ok(lexed) => lexed,
+        Err(e) => {
+            eprintln!("{}", format_lex_error(source, e));
+            std::process::exit(1);
+        }
+    };
+
+    match format {
+        TokenFormat::Lines => {
+            for (token, span) in &tokens {
+                let (line, column) = line_column(source, span.start);
+                println!(
+                    "{line}:{column}\t{}\t{:?}",
+                    token.kind_name(),
+                    &source[span.clone()]
+                );
+            }
+        }
+        TokenFormat::Json => {
+            let records: Vec<TokenRecord> = tokens
+                .iter()
+                .map(|(token, span)| {
+                    let (line, column) = line_column(source, span.start);
+                    TokenRecord {
+                        kind: token.kind_name(),
+                        text: &source[span.clone()],
+                        start: span.start,
+                        end: span.end,
+                        line,
+                        column,
+                    }
+                })
+                .collect();
+            match serde_json::to_string_pretty(&records) {
+                Ok(json) => println!("{json}"),
+                Err(e) => {
+                    eprintln!("Error serializing tokens: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    if !remaining.is_empty() {
+        let (line, column) = line_column(source, source.len() - remaining.len());
+        eprintln!(
+            "Warning: Lexer unparsed input remaining at line {line}, column {column}: {:?}",
+            remaining.lines().next().unwrap_or("")
+        );
+        eprintln!("This might indicate a lexer issue.");
+    }
+}
+
+/// Runs the `--repl` loop on stdin until end of input or `:quit`.
+fn run_repl() {
+    let mut repl = Repl::new();
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("{}", if repl.is_continuing() { "... " } else { "> " });
+        let _ = io::stdout().flush();
+
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            break;
+        };
+        if !repl.is_continuing() && line.trim() == ":quit" {
+            break;
+        }
+        if let Some(output) = repl.feed_line(&line) {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+        }
+    }
+}
+
+/// Prints the `--time` table: one row per phase that ran, then the total.
+fn print_timings(phases: &[(&str, Duration)], total: Duration) {
+    println!("\n{:<12} {:>12}", "Phase", "Time (ms)");
+    for (label, elapsed) in phases {
+        println!("{:<12} {:>12.3}", label, elapsed.as_secs_f64() * 1000.0);
+    }
+    println!("{:<12} {:>12.3}", "total", total.as_secs_f64() * 1000.0);
+}
+
 #[tokio::main]
 async fn main() {
     let args: Vec<String> = env::args().collect();
@@ -37,8 +177,20 @@ async fn main() {
     // Parse command line arguments
     let mut check_only = false;
     let mut show_ast = false;
+    let mut dump_typed_ast = false;
+    let mut library = false;
+    let mut start_section = false;
+    let mut max_errors = DEFAULT_MAX_ERRORS;
+    let mut optimize = false;
+    let mut verify = false;
+    let mut release = false;
+    let mut target = Target::Wasi;
+    let mut foreign_functions = Vec::new();
+    let mut tail_calls = false;
+    let mut show_tokens = None;
     let mut lsp_mode = false;
     let mut verbose = false;
+    let mut time_phases = false;
     let mut source_file = String::new();
     let mut output_file = None;
 
@@ -49,10 +201,73 @@ async fn main() {
                 println!("{BIN_NAME} {}", env!("CARGO_PKG_VERSION"));
                 std::process::exit(0);
             }
+            "--explain" => {
+                let Some(code) = args.get(i + 1) else {
+                    eprintln!("--explain requires an error code, e.g. --explain E0001");
+                    std::process::exit(1);
+                };
+                match error_codes::explain(code) {
+                    Some(entry) => {
+                        print!("{}", error_codes::format_explanation(entry));
+                        std::process::exit(0);
+                    }
+                    None => {
+                        eprintln!("Unknown error code: {code}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "--max-errors" => {
+                let Some(limit) = args.get(i + 1).and_then(|limit| limit.parse().ok()) else {
+                    eprintln!("--max-errors requires a number, e.g. --max-errors 20");
+                    std::process::exit(1);
+                };
+                max_errors = limit;
+                i += 1;
+            }
+            "--foreign" => {
+                let Some(spec) = args.get(i + 1) else {
+                    eprintln!("--foreign requires a signature, e.g. --foreign 'geometry.scale=(Int32, Int32) -> Int32'");
+                    std::process::exit(1);
+                };
+                match ForeignFunction::parse(spec) {
+                    Ok(function) => foreign_functions.push(function),
+                    Err(e) => {
+                        eprintln!("Invalid --foreign signature: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                i += 1;
+            }
+            "--target" => {
+                target = match args.get(i + 1).map(String::as_str) {
+                    Some("wasi") => Target::Wasi,
+                    Some("freestanding") => Target::Freestanding,
+                    _ => {
+                        eprintln!("--target requires wasi or freestanding, e.g. --target wasi");
+                        std::process::exit(1);
+                    }
+                };
+                i += 1;
+            }
             "--check" => check_only = true,
             "--ast" => show_ast = true,
+            "--dump-typed-ast" => dump_typed_ast = true,
+            "--lib" => library = true,
+            "--start-section" => start_section = true,
+            "--optimize" => optimize = true,
+            "--verify" => verify = true,
+            "--release" => release = true,
+            "--tail-calls" => tail_calls = true,
+            "--tokens" => show_tokens = Some(TokenFormat::Lines),
+            "--tokens-json" => show_tokens = Some(TokenFormat::Json),
             "--verbose" => verbose = true,
+            "--time" => time_phases = true,
             "--lsp" => lsp_mode = true,
+            "--repl" => {
+                run_repl();
+                return;
+            }
             "--help" => {
                 print!("{}", usage_text());
                 std::process::exit(0);
@@ -98,28 +313,35 @@ async fn main() {
         }
     };
 
+    if let Some(format) = show_tokens {
+        dump_tokens(&source, format);
+        return;
+    }
+
+    let mut timings: Vec<(&str, Duration)> = Vec::new();
+    let total_start = Instant::now();
+
     // Lex the source
+    let phase_start = Instant::now();
     if verbose && !show_ast {
         println!("=== Lexing ===");
     }
-    let _tokens = match lex(&source) {
-        Ok((remaining, tokens)) => {
-            if !remaining.is_empty() && verbose && !show_ast {
-                eprintln!("Warning: Lexer unparsed input remaining: {:?}", remaining);
-                eprintln!("This might indicate a lexer issue.");
-            }
-            if verbose && !show_ast {
-                println!("Tokens: {:?}", tokens);
-            }
-            tokens
+    // Report every bad character at once rather than only the first
+    let (tokens, lex_errors) = lex_collecting(&source);
+    if !lex_errors.is_empty() {
+        for error in &lex_errors {
+            eprintln!("{}", error.describe(&source));
         }
-        Err(e) => {
-            eprintln!("{}", format_lex_error(&source, e));
-            std::process::exit(1);
-        }
-    };
+        std::process::exit(1);
+    }
+    if verbose && !show_ast {
+        println!("Tokens: {:?}", tokens);
+    }
+
+    timings.push(("lexing", phase_start.elapsed()));
 
     // Parse the source
+    let phase_start = Instant::now();
     if verbose && !show_ast {
         println!("\n=== Parsing ===");
     }
@@ -169,37 +391,82 @@ async fn main() {
             std::process::exit(1);
         }
     };
+    timings.push(("parsing", phase_start.elapsed()));
 
     // Type check
+    let phase_start = Instant::now();
     if verbose {
         println!("\n=== Type Checking ===");
     }
     let mut type_checker = TypeChecker::new();
-    match type_checker.check_program(&ast) {
-        Ok(()) => {
+    for function in &foreign_functions {
+        if let Err(e) = type_checker.declare_foreign_function(function) {
+            eprintln!("Type error[{}]: {}", e.code(), e);
+            std::process::exit(1);
+        }
+    }
+    let errors = type_checker.check_program_collecting(&ast);
+    match errors.first() {
+        None => {
+            for warning in type_checker.take_warnings() {
+                eprintln!("Warning: {}", warning.describe(&source));
+            }
+            for warning in lint_program(&ast) {
+                eprintln!("Warning: {}", warning.describe(&source));
+            }
+            if dump_typed_ast {
+                print!("{}", visualize_with_types(&ast, &type_checker));
+                return;
+            }
             if let Err(e) = check_v001_release_surface(&ast, &type_checker) {
                 eprintln!("Release surface error: {}", e);
                 std::process::exit(1);
             }
+            timings.push(("type check", phase_start.elapsed()));
             if check_only {
                 println!("OK {}", filename);
+                if time_phases {
+                    print_timings(&timings, total_start.elapsed());
+                }
                 return;
             }
             if verbose {
                 println!("Type checking passed!");
             }
         }
-        Err(e) => {
-            eprintln!("Type error: {}", e);
+        Some(first) => {
+            for e in errors.iter().take(max_errors) {
+                eprintln!("Type error[{}]: {}", e.code(), e);
+            }
+            if let Some(hidden) = errors.len().checked_sub(max_errors).filter(|&n| n > 0) {
+                let noun = if hidden == 1 { "error" } else { "errors" };
+                eprintln!("... and {hidden} more {noun}");
+            }
+            eprintln!(
+                "For more information about this error, try `{BIN_NAME} --explain {}`.",
+                first.code()
+            );
             std::process::exit(1);
         }
     }
 
     // Generate WASM
+    let phase_start = Instant::now();
     if verbose {
         println!("\n=== WASM Code Generation ===");
     }
     let mut codegen = WasmCodeGen::new();
+    codegen.set_library(library);
+    codegen.set_start_section(start_section);
+    codegen.set_source(&source);
+    codegen.optimize(optimize || release);
+    codegen.release(release);
+    codegen.target(target);
+    codegen.tail_calls(tail_calls);
+    codegen.verify(verify);
+    for function in foreign_functions {
+        codegen.declare_foreign_function(function);
+    }
     let wat = match codegen.generate(&ast) {
         Ok(wat) => {
             if verbose {
@@ -212,6 +479,7 @@ async fn main() {
             std::process::exit(1);
         }
     };
+    timings.push(("codegen", phase_start.elapsed()));
 
     // Write output
     let output_filename = output_file.unwrap_or_else(|| {
@@ -225,6 +493,9 @@ async fn main() {
     match fs::write(&output_filename, wat) {
         Ok(()) => {
             println!("\n✓ Successfully compiled to {}", output_filename);
+            if time_phases {
+                print_timings(&timings, total_start.elapsed());
+            }
         }
         Err(e) => {
             eprintln!("Error writing output file {}: {}", output_filename, e);
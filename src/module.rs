@@ -1,19 +1,39 @@
 use crate::ast::{
-    AssignStmt, BindDecl, BlockExpr, CallExpr, CloneExpr, Expr, ExprKind, FieldInit, FunDecl,
-    ImplBlock, ImportItems, MatchArm, MatchExpr, Pattern, PipeExpr, PipeTarget, Program,
-    PrototypeCloneExpr, RecordDecl, RecordLit, Stmt, ThenExpr, TopDecl, Type, WhileExpr, WithExpr,
-    WithLifetimeExpr,
+    AssignStmt, BindDecl, BlockExpr, CallExpr, CloneExpr, Expr, ExprKind, FieldInit,
+    FieldUpdateExpr, FunDecl, ImplBlock, ImportItems, MatchArm, MatchExpr, Pattern, PipeExpr,
+    PipeTarget, Program, PrototypeCloneExpr, RecordDecl, RecordLit, Stmt, ThenExpr, TopDecl, Type,
+    WhileExpr, WhileLetExpr, WithExpr, WithLifetimeExpr,
 };
 use crate::diagnostics::format_parse_error;
 use crate::parser::parse_program;
 use anyhow::{bail, Context, Result};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 const UNSUPPORTED_STD_SOURCE_IMPORT_ERROR: &str =
     "standard-library source imports are unsupported in v0.0.1; std helpers are compiler-registered and available without importing std aggregators";
 
+/// An import named a declaration that its module declares without `export`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotExportedError {
+    pub module: String,
+    pub name: String,
+}
+
+impl fmt::Display for NotExportedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is private to module {}; only `export` declarations can be imported",
+            self.name, self.module
+        )
+    }
+}
+
+impl std::error::Error for NotExportedError {}
+
 #[derive(Debug, Clone)]
 pub struct Module {
     pub path: PathBuf,
@@ -22,6 +42,7 @@ pub struct Module {
     pub exports: HashMap<String, TopDecl>,
 }
 
+#[derive(Debug, Clone)]
 pub struct ModuleResolver {
     modules: HashMap<Vec<String>, Module>,
     search_paths: Vec<PathBuf>,
@@ -43,6 +64,19 @@ impl ModuleResolver {
         }
     }
 
+    /// A resolver for the imports of `source_file`: relative module paths are
+    /// looked up next to that file before the working directory.
+    pub fn for_source_file(source_file: &Path) -> Self {
+        let mut resolver = Self::new();
+        if let Some(base_dir) = source_file
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            resolver.search_paths.insert(0, base_dir.to_path_buf());
+        }
+        resolver
+    }
+
     pub fn add_search_path(&mut self, path: PathBuf) {
         self.search_paths.push(path);
     }
@@ -185,6 +219,15 @@ impl ModuleResolver {
             }
         }
 
+        // Imported spans index their own module's source, not the root file
+        // whose text callers pair the spliced program with, so drop them.
+        let mut imported = Program {
+            imports: Vec::new(),
+            declarations,
+        };
+        crate::ast::visit_program_exprs_mut(&mut imported, &mut |expr| expr.span = None);
+        let mut declarations = imported.declarations;
+
         declarations.extend(program.declarations);
         program.imports.clear();
         program.declarations = declarations;
@@ -233,13 +276,10 @@ impl ModuleResolver {
             ImportItems::Named(names) => {
                 let mut result = Vec::new();
                 for name in names {
-                    let decl = module.exports.get(name).with_context(|| {
-                        format!(
-                            "Export '{}' not found in module {}",
-                            name,
-                            module_path.join(".")
-                        )
-                    })?;
+                    let decl = module
+                        .exports
+                        .get(name)
+                        .ok_or_else(|| missing_export_error(module, module_path, name))?;
                     result.push((name.clone(), decl.clone()));
                 }
                 Ok(result)
@@ -266,11 +306,7 @@ impl ModuleResolver {
             ImportItems::Named(names) => {
                 for name in names {
                     if !module.exports.contains_key(name) {
-                        bail!(
-                            "Export '{}' not found in module {}",
-                            name,
-                            module_path.join(".")
-                        );
+                        return Err(missing_export_error(module, module_path, name));
                     }
                 }
                 Ok(names.clone())
@@ -287,7 +323,7 @@ impl ModuleResolver {
             .iter()
             .map(|name| (name.clone(), name.clone()))
             .collect::<HashMap<_, _>>();
-        let mut visiting = HashSet::new();
+        let mut visiting = Vec::new();
 
         self.get_import_closure_decls_with_aliases(module_path, &requested_aliases, &mut visiting)
     }
@@ -296,12 +332,19 @@ impl ModuleResolver {
         &self,
         module_path: &[String],
         requested_aliases: &HashMap<String, String>,
-        visiting: &mut HashSet<Vec<String>>,
+        visiting: &mut Vec<Vec<String>>,
     ) -> Result<Vec<TopDecl>> {
-        let module_key = module_path.to_vec();
-        if !visiting.insert(module_key.clone()) {
-            bail!("Cyclic module import detected at {}", module_path.join("."));
+        // `visiting` is the chain of modules currently being expanded, so a
+        // repeat names the whole cycle back to the first repeated module.
+        if let Some(start) = visiting.iter().position(|path| path == module_path) {
+            let cycle = visiting[start..]
+                .iter()
+                .chain(std::iter::once(&module_path.to_vec()))
+                .map(|path| path.join("."))
+                .collect::<Vec<_>>();
+            bail!("Cyclic module import detected: {}", cycle.join(" -> "));
         }
+        visiting.push(module_path.to_vec());
 
         let module = self
             .modules
@@ -358,17 +401,17 @@ impl ModuleResolver {
             declarations.push(rename_top_decl(decl.clone(), &rename_map)?);
         }
 
-        visiting.remove(&module_key);
+        visiting.pop();
         Ok(declarations)
     }
 }
 
 pub fn resolve_program_imports_for_file(program: Program, source_file: &Path) -> Result<Program> {
-    let base_dir = source_file
-        .parent()
-        .filter(|parent| !parent.as_os_str().is_empty());
+    if program.imports.is_empty() {
+        return Ok(program);
+    }
 
-    resolve_program_imports_with_base_dir(program, base_dir)
+    ModuleResolver::for_source_file(source_file).resolve_program_imports(program)
 }
 
 pub fn resolve_program_imports_with_base_dir(
@@ -420,6 +463,29 @@ pub fn parse_module_source_key(module_key: &str) -> Result<Vec<String>> {
     Ok(path)
 }
 
+fn missing_export_error(module: &Module, module_path: &[String], name: &str) -> anyhow::Error {
+    let declared_privately = module.program.declarations.iter().any(|decl| {
+        !matches!(decl, TopDecl::Impl(_))
+            && get_top_decl_name_for_collision(decl)
+                .ok()
+                .flatten()
+                .is_some_and(|declared| declared == name)
+    });
+    if declared_privately {
+        NotExportedError {
+            module: module_path.join("."),
+            name: name.to_string(),
+        }
+        .into()
+    } else {
+        anyhow::anyhow!(
+            "Export '{}' not found in module {}",
+            name,
+            module_path.join(".")
+        )
+    }
+}
+
 fn virtual_module_path(module_path: &[String]) -> PathBuf {
     PathBuf::from(format!("<module:{}>", module_path.join(".")))
 }
@@ -478,6 +544,9 @@ fn rename_type(
             Box::new(rename_type(*return_type, rename_map, type_params)),
         ),
         Type::Temporal(name, temporals) => Type::Temporal(rename_name(name, rename_map), temporals),
+        Type::Refined(base, bound) => {
+            Type::Refined(Box::new(rename_type(*base, rename_map, type_params)), bound)
+        }
     }
 }
 
@@ -620,6 +689,12 @@ fn rename_block_expr(
                     bound,
                 ))));
             }
+            Stmt::Break(value) => {
+                statements.push(Stmt::Break(value.map(|value| {
+                    Box::new(rename_expr(*value, rename_map, type_params, bound))
+                })));
+            }
+            Stmt::Continue => statements.push(Stmt::Continue),
         }
     }
 
@@ -690,6 +765,21 @@ fn rename_expr(
             type_params,
             bound,
         ))),
+        ExprKind::WhileLet(while_let) => Expr::new(ExprKind::WhileLet(rename_while_let_expr(
+            while_let,
+            rename_map,
+            type_params,
+            bound,
+        ))),
+        ExprKind::Loop(body) => {
+            let mut body_bound = bound.clone();
+            Expr::new(ExprKind::Loop(rename_block_expr(
+                body,
+                rename_map,
+                type_params,
+                &mut body_bound,
+            )))
+        }
         ExprKind::Match(match_expr) => Expr::new(ExprKind::Match(rename_match_expr(
             match_expr,
             rename_map,
@@ -739,6 +829,11 @@ fn rename_expr(
             Box::new(rename_expr(*expr, rename_map, type_params, bound)),
             field,
         )),
+        ExprKind::FieldUpdate(update) => Expr::new(ExprKind::FieldUpdate(FieldUpdateExpr {
+            object: Box::new(rename_expr(*update.object, rename_map, type_params, bound)),
+            field: update.field,
+            value: Box::new(rename_expr(*update.value, rename_map, type_params, bound)),
+        })),
         ExprKind::ListLit(elements) => Expr::new(ExprKind::ListLit(
             elements
                 .into_iter()
@@ -769,6 +864,12 @@ fn rename_expr(
             type_params,
             bound,
         )))),
+        ExprKind::Try(expr) => Expr::new(ExprKind::Try(Box::new(rename_expr(
+            *expr,
+            rename_map,
+            type_params,
+            bound,
+        )))),
         ExprKind::Lambda(lambda) => {
             let mut lambda_bound = bound.clone();
             for param in &lambda.params {
@@ -912,6 +1013,20 @@ fn rename_while_expr(
     while_expr
 }
 
+fn rename_while_let_expr(
+    mut while_let: WhileLetExpr,
+    rename_map: &HashMap<String, String>,
+    type_params: &HashSet<String>,
+    bound: &HashSet<String>,
+) -> WhileLetExpr {
+    while_let.expr = Box::new(rename_expr(*while_let.expr, rename_map, type_params, bound));
+    while_let.pattern = rename_pattern_type_names(while_let.pattern, rename_map, type_params);
+    let mut body_bound = bound.clone();
+    collect_pattern_bindings(&while_let.pattern, &mut body_bound);
+    while_let.body = rename_block_expr(while_let.body, rename_map, type_params, &mut body_bound);
+    while_let
+}
+
 fn rename_match_expr(
     mut match_expr: MatchExpr,
     rename_map: &HashMap<String, String>,
@@ -941,6 +1056,9 @@ fn rename_match_arm(
     arm.pattern = rename_pattern_type_names(arm.pattern, rename_map, type_params);
     let mut arm_bound = bound.clone();
     collect_pattern_bindings(&arm.pattern, &mut arm_bound);
+    arm.guard = arm
+        .guard
+        .map(|guard| rename_expr(guard, rename_map, type_params, &arm_bound));
     arm.body = rename_block_expr(arm.body, rename_map, type_params, &mut arm_bound);
     arm
 }
@@ -957,6 +1075,11 @@ fn rename_call_expr(
         .into_iter()
         .map(|arg| Box::new(rename_expr(*arg, rename_map, type_params, bound)))
         .collect();
+    call.type_args = call
+        .type_args
+        .into_iter()
+        .map(|ty| rename_type(ty, rename_map, type_params))
+        .collect();
     call
 }
 
@@ -1064,6 +1187,10 @@ fn rename_pattern_type_names(
             rename_map,
             type_params,
         ))),
+        Pattern::Binding(name, pattern) => Pattern::Binding(
+            name,
+            Box::new(rename_pattern_type_names(*pattern, rename_map, type_params)),
+        ),
         Pattern::ListCons(head, tail) => Pattern::ListCons(
             Box::new(rename_pattern_type_names(*head, rename_map, type_params)),
             Box::new(rename_pattern_type_names(*tail, rename_map, type_params)),
@@ -1101,6 +1228,10 @@ fn collect_pattern_bindings(pattern: &Pattern, bindings: &mut HashSet<String>) {
         Pattern::Some(pattern) | Pattern::Ok(pattern) | Pattern::Err(pattern) => {
             collect_pattern_bindings(pattern, bindings);
         }
+        Pattern::Binding(name, pattern) => {
+            bindings.insert(name.clone());
+            collect_pattern_bindings(pattern, bindings);
+        }
         Pattern::ListCons(head, tail) => {
             collect_pattern_bindings(head, bindings);
             collect_pattern_bindings(tail, bindings);
@@ -1110,7 +1241,11 @@ fn collect_pattern_bindings(pattern: &Pattern, bindings: &mut HashSet<String>) {
                 collect_pattern_bindings(pattern, bindings);
             }
         }
-        Pattern::Wildcard | Pattern::Literal(_) | Pattern::None | Pattern::EmptyList => {}
+        Pattern::Wildcard
+        | Pattern::Literal(_)
+        | Pattern::Range { .. }
+        | Pattern::None
+        | Pattern::EmptyList => {}
     }
 }
 
@@ -27,15 +27,214 @@ use crate::ast::*;
 use crate::lexer::{lex_token, skip, Token};
 use nom::{
     branch::alt,
-    combinator::{map, opt, value},
+    combinator::{map, not, opt, value, verify},
     multi::{many0, many1, separated_list0, separated_list1},
-    sequence::{delimited, preceded, tuple},
+    sequence::{delimited, preceded, terminated, tuple},
     IResult,
 };
 
 /// Type alias for parser results.
 type ParseResult<'a, T> = IResult<&'a str, T>;
 
+/// Default limit on how deeply expressions, types and patterns may nest
+/// before [`parse_program`] gives up with [`ParseError::NestingTooDeep`].
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 32;
+
+/// Parser errors that callers can recognise by kind rather than by message.
+///
+/// They are reported as `nom::Err::Failure` values carrying [`message`],
+/// like the other user-facing syntax errors, so existing diagnostics keep
+/// working; use [`ParseError::from_nom`] to recover the kind.
+///
+/// [`message`]: ParseError::message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The source nests deeper than the configured maximum, which would
+    /// otherwise overflow the stack of the recursive-descent parser.
+    NestingTooDeep,
+}
+
+impl ParseError {
+    pub fn message(self) -> &'static str {
+        match self {
+            ParseError::NestingTooDeep => NESTING_TOO_DEEP_ERROR,
+        }
+    }
+
+    /// The structured error carried by a parser failure, if any.
+    pub fn from_nom(error: &nom::Err<nom::error::Error<&str>>) -> Option<Self> {
+        match error {
+            nom::Err::Failure(error) if error.input == NESTING_TOO_DEEP_ERROR => {
+                Some(ParseError::NestingTooDeep)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// Nesting bookkeeping for the parse running on this thread.
+///
+/// The parser's combinators are plain `fn(&str)` values, so the depth is
+/// threaded through a thread-local rather than through every signature.
+/// Once the limit is hit the state stays `exceeded`, so alternatives that
+/// swallow the failure and backtrack bail out immediately instead of
+/// re-exploring the deep input.
+#[derive(Clone, Copy)]
+struct Nesting {
+    depth: usize,
+    max_depth: usize,
+    exceeded: bool,
+}
+
+thread_local! {
+    static NESTING: std::cell::Cell<Nesting> = const {
+        std::cell::Cell::new(Nesting {
+            depth: 0,
+            max_depth: DEFAULT_MAX_NESTING_DEPTH,
+            exceeded: false,
+        })
+    };
+}
+
+/// Go one nesting level deeper, failing once the limit is hit. The caller
+/// restores the depth; see `nested` and `chained`.
+fn descend<'a>() -> Result<(), nom::Err<nom::error::Error<&'a str>>> {
+    let nesting = NESTING.get();
+    if nesting.exceeded || nesting.depth >= nesting.max_depth {
+        NESTING.set(Nesting {
+            exceeded: true,
+            ..nesting
+        });
+        return user_syntax_failure(NESTING_TOO_DEEP_ERROR).map(|(_, ())| ());
+    }
+    NESTING.set(Nesting {
+        depth: nesting.depth + 1,
+        ..nesting
+    });
+    Ok(())
+}
+
+/// Run `parser` with the nesting depth it started with restored afterwards.
+fn restoring_depth<'a, T>(
+    input: &'a str,
+    parser: impl FnOnce(&'a str) -> ParseResult<'a, T>,
+) -> ParseResult<'a, T> {
+    let depth = NESTING.get().depth;
+    let result = parser(input);
+    NESTING.set(Nesting {
+        depth,
+        ..NESTING.get()
+    });
+    result
+}
+
+/// Run `parser` one nesting level deeper, failing once the limit is hit.
+fn nested<'a, T>(
+    input: &'a str,
+    parser: impl FnOnce(&'a str) -> ParseResult<'a, T>,
+) -> ParseResult<'a, T> {
+    restoring_depth(input, |input| {
+        descend()?;
+        parser(input)
+    })
+}
+
+/// Run a parser for a left-deep chain such as `a + b + c` or
+/// `x |> f |> g`. It loops rather than recursing, but the tree it builds
+/// nests one level per link, and checking and code generation recurse
+/// through every level, so each link calls `descend` and the chain's
+/// depth is given back when it ends.
+fn chained<'a, T>(
+    input: &'a str,
+    parser: impl FnOnce(&'a str) -> ParseResult<'a, T>,
+) -> ParseResult<'a, T> {
+    restoring_depth(input, parser)
+}
+
+/// Run a top-level parse with a fresh nesting budget of `max_depth`.
+///
+/// A failure swallowed somewhere inside still surfaces as
+/// [`ParseError::NestingTooDeep`].
+fn with_nesting_limit<'a, T>(
+    input: &'a str,
+    max_depth: usize,
+    parser: impl FnOnce(&'a str) -> ParseResult<'a, T>,
+) -> ParseResult<'a, T> {
+    let outer = NESTING.replace(Nesting {
+        depth: 0,
+        max_depth,
+        exceeded: false,
+    });
+    let result = parser(input);
+    let exceeded = NESTING.replace(outer).exceeded;
+    if exceeded {
+        user_syntax_failure(NESTING_TOO_DEEP_ERROR)
+    } else {
+        result
+    }
+}
+
+/// Record that `expr` was parsed from `input` up to `rest`, unless an inner
+/// parser already did. Sub-parsers only see suffixes of the source, so the
+/// span is kept as distances from the end of the source until
+/// `parse_program` makes it absolute.
+fn spanned(input: &str, rest: &str, mut expr: Expr) -> Expr {
+    if expr.span.is_none() {
+        let start = skip(input).map_or(input, |(start, _)| start);
+        // Some parsers consume the whitespace that follows them; keep it
+        // out of the span.
+        let consumed = &start[..start.len().saturating_sub(rest.len())];
+        let trailing = consumed.len() - consumed.trim_end().len();
+        expr.span = Some(Span {
+            start: start.len(),
+            end: rest.len() + trailing,
+        });
+    }
+    expr
+}
+
+/// Parse with `parser` and record the span of the expression it produced.
+fn with_span<'a>(
+    mut parser: impl FnMut(&'a str) -> ParseResult<'a, Expr>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, Expr> {
+    move |input| {
+        let (rest, expr) = parser(input)?;
+        Ok((rest, spanned(input, rest, expr)))
+    }
+}
+
+/// The end-relative span covering both `first` and `last`, while parsing.
+fn joined_span(first: &Expr, last: &Expr) -> Option<Span> {
+    match (first.span, last.span) {
+        (Some(first), Some(last)) => Some(Span {
+            start: first.start.max(last.start),
+            end: first.end.min(last.end),
+        }),
+        _ => None,
+    }
+}
+
+/// Convert the end-relative spans recorded while parsing `source_len` bytes
+/// into absolute byte offsets.
+fn resolve_spans(program: &mut Program, source_len: usize) {
+    visit_program_exprs_mut(program, &mut |expr| resolve_span(expr, source_len));
+}
+
+fn resolve_span(expr: &mut Expr, source_len: usize) {
+    if let Some(span) = &mut expr.span {
+        *span = Span {
+            start: source_len.saturating_sub(span.start),
+            end: source_len.saturating_sub(span.end),
+        };
+    }
+}
+
 const UNSUPPORTED_ENUM_DECL_ERROR: &str =
     "enum declarations are unsupported in v0.0.1; user-defined enum declarations are not implemented";
 const UNSUPPORTED_FORM_TAKES_DECL_ERROR: &str =
@@ -56,6 +255,10 @@ const NONE_TYPE_ARGUMENT_ERROR: &str =
     "stale syntax `None<T>` is not valid Restrict; write `None` and provide an expected `Option<T>` type through an annotation or typed context";
 const STALE_UNIT_ERROR: &str =
     "stale syntax `Unit` is not valid Restrict; use `()` for the unit value or unit type";
+const WITH_INSTANCE_MISMATCH_ERROR: &str =
+    "`with Ctx = ...` must build an instance of the same context, as in `with DB = DB { host: \"x\" } { ... }`";
+const NESTING_TOO_DEEP_ERROR: &str =
+    "source nests too deeply; split deeply nested expressions, types or patterns into separate bindings";
 
 /// Expects a specific token and consumes it.
 ///
@@ -77,6 +280,19 @@ fn expect_token<'a>(expected: Token) -> impl Fn(&'a str) -> ParseResult<'a, ()>
     }
 }
 
+/// Accepts an optional comma after the last of `items`, so multi-line
+/// lists may end every element with a comma. An empty list takes none.
+fn trailing_comma<'a, T>(items: &[T]) -> impl Fn(&'a str) -> ParseResult<'a, ()> {
+    let allowed = !items.is_empty();
+    move |input| {
+        if allowed {
+            map(opt(expect_token(Token::Comma)), |_| ())(input)
+        } else {
+            Ok((input, ()))
+        }
+    }
+}
+
 fn user_syntax_failure<'a, T>(message: &'static str) -> ParseResult<'a, T> {
     Err(nom::Err::Failure(nom::error::Error::new(
         message,
@@ -145,7 +361,62 @@ fn ident(input: &str) -> ParseResult<'_, String> {
 /// // Temporal types: File<~f>, Transaction<~tx, ~db>
 /// ```
 fn parse_type(input: &str) -> ParseResult<'_, Type> {
-    parse_function_type(input)
+    nested(input, parse_function_type)
+}
+
+/// Parses a type that may carry an integer range restriction.
+///
+/// Refinements are only accepted on `val` annotations, function parameters
+/// and return types; they cannot appear nested inside other types. As in
+/// range patterns, `start..end` stops just before `end` and `start..=end`
+/// includes it.
+///
+/// # Examples
+///
+/// ```
+/// // Int32 in 0..100
+/// // Int64 in -1..=1
+/// ```
+fn parse_refinable_type(input: &str) -> ParseResult<'_, Type> {
+    let (input, ty) = parse_type(input)?;
+    match lex_token(input) {
+        Ok((after_in, Token::Ident(word))) if word == "in" => {
+            let (input, min) = range_bound_literal(after_in)?;
+            let (input, inclusive) = alt((
+                value(true, expect_token(Token::DotDotEq)),
+                value(false, expect_token(Token::DotDot)),
+            ))(input)?;
+            let end_input = input;
+            let (input, end) = range_bound_literal(input)?;
+            let max = if inclusive {
+                end
+            } else {
+                end.checked_sub(1).ok_or_else(|| {
+                    nom::Err::Error(nom::error::Error::new(
+                        end_input,
+                        nom::error::ErrorKind::Digit,
+                    ))
+                })?
+            };
+            Ok((input, Type::Refined(Box::new(ty), RangeBound { min, max })))
+        }
+        _ => Ok((input, ty)),
+    }
+}
+
+fn range_bound_literal(input: &str) -> ParseResult<'_, i64> {
+    let original_input = input;
+    let (input, negative) = opt(expect_token(Token::Minus))(input)?;
+    let (input, token) = lex_token(input)?;
+    match token {
+        Token::IntLit(value) | Token::Int64Lit(value) => {
+            Ok((input, if negative.is_some() { -value } else { value }))
+        }
+        _ => Err(nom::Err::Error(nom::error::Error::new(
+            original_input,
+            nom::error::ErrorKind::Digit,
+        ))),
+    }
 }
 
 enum TypeArg {
@@ -270,7 +541,11 @@ fn field_decl(input: &str) -> ParseResult<'_, FieldDecl> {
     let (input, name) = ident(input)?;
     let (input, _) = expect_token(Token::Colon)(input)?;
     let (input, ty) = parse_type(input)?;
-    Ok((input, FieldDecl { name, ty }))
+    let (input, default) = opt(|input| {
+        let (input, _) = expect_token(Token::Assign)(input)?;
+        expression_in_statement(input)
+    })(input)?;
+    Ok((input, FieldDecl { name, ty, default }))
 }
 
 fn field_decls(input: &str) -> ParseResult<'_, Vec<FieldDecl>> {
@@ -303,13 +578,10 @@ fn record_decl(input: &str) -> ParseResult<'_, RecordDecl> {
         let (input, _) = expect_token(Token::Gt)(input)?;
         Ok((input, params))
     })(input)?;
-    let type_params = type_params.unwrap_or_default();
+    let mut type_params = type_params.unwrap_or_default();
 
-    // Parse optional temporal constraints: where ~tx within ~db
-    let (input, temporal_constraints) = opt(|input| {
-        let (input, _) = expect_token(Token::Where)(input)?;
-        separated_list1(expect_token(Token::Comma), temporal_constraint)(input)
-    })(input)?;
+    // Parse optional where clause: where ~tx within ~db, T: Display
+    let (input, temporal_constraints) = opt(|input| where_clause(input, &mut type_params))(input)?;
     let temporal_constraints = temporal_constraints.unwrap_or_default();
 
     let (input, _) = expect_token(Token::LBrace)(input)?;
@@ -334,6 +606,57 @@ fn record_decl(input: &str) -> ParseResult<'_, RecordDecl> {
     ))
 }
 
+/// A `where` clause mixing temporal constraints (`~tx within ~db`) and trait
+/// bounds (`T: Display + Clone`). Trait bounds are merged into the matching
+/// entry of `type_params`, so they behave exactly like inline bounds; a bound
+/// on a name that is not a type parameter fails to parse.
+fn where_clause<'a>(
+    input: &'a str,
+    type_params: &mut [TypeParam],
+) -> ParseResult<'a, Vec<TemporalConstraint>> {
+    let (mut input, _) = expect_token(Token::Where)(input)?;
+    let mut temporal_constraints = Vec::new();
+
+    loop {
+        if let Ok((rest, constraint)) = temporal_constraint(input) {
+            temporal_constraints.push(constraint);
+            input = rest;
+        } else {
+            let (rest, name) = ident(input)?;
+            let (rest, _) = expect_token(Token::Colon)(rest)?;
+            let (rest, bounds) = trait_bounds(rest)?;
+            let Some(param) = type_params
+                .iter_mut()
+                .find(|param| param.name == name && !param.is_temporal)
+            else {
+                return Err(nom::Err::Error(nom::error::Error::new(
+                    input,
+                    nom::error::ErrorKind::Verify,
+                )));
+            };
+            for bound in bounds {
+                if !param.bounds.contains(&bound) {
+                    param.bounds.push(bound);
+                }
+            }
+            input = rest;
+        }
+
+        match expect_token::<'_>(Token::Comma)(input) {
+            Ok((rest, _)) => input = rest,
+            Err(_) => return Ok((input, temporal_constraints)),
+        }
+    }
+}
+
+/// `Display + Clone + Debug`
+fn trait_bounds(input: &str) -> ParseResult<'_, Vec<TypeBound>> {
+    separated_list1(expect_token(Token::Plus), |input| {
+        let (input, trait_name) = ident(input)?;
+        Ok((input, TypeBound { trait_name }))
+    })(input)
+}
+
 // Parse a temporal constraint: ~tx within ~db
 fn temporal_constraint(input: &str) -> ParseResult<'_, TemporalConstraint> {
     let (input, _) = expect_token(Token::Tilde)(input)?;
@@ -350,7 +673,7 @@ fn param(input: &str) -> ParseResult<'_, Param> {
 
     let (input, name) = ident(input)?;
     let (input, _) = expect_token(Token::Colon)(input)?;
-    let (input, ty) = parse_type(input)?;
+    let (input, ty) = parse_refinable_type(input)?;
     Ok((
         input,
         Param {
@@ -361,6 +684,9 @@ fn param(input: &str) -> ParseResult<'_, Param> {
     ))
 }
 
+/// `{ stmt* expr? }`. An expression directly before the closing `}` is the
+/// block's value (`block.expr`); one followed by `;` is a statement, so
+/// `{ x }` yields `x` while `{ x; }` yields Unit.
 fn block_expr(input: &str) -> ParseResult<'_, BlockExpr> {
     let (input, _) = expect_token(Token::LBrace)(input)?;
 
@@ -390,6 +716,13 @@ fn block_expr(input: &str) -> ParseResult<'_, BlockExpr> {
             return user_syntax_failure(STALE_VAL_MUT_ERROR);
         }
 
+        if let Ok((after_stmt, stmt)) = loop_control_stmt(remaining) {
+            statements.push(stmt);
+            let (after_semi, _) = opt(expect_token(Token::Semicolon))(after_stmt)?;
+            remaining = after_semi;
+            continue;
+        }
+
         // Try to parse a binding first
         if let Ok((after_bind, bind_decl)) = bind_decl_in_statement(remaining) {
             statements.push(Stmt::Binding(bind_decl));
@@ -408,8 +741,11 @@ fn block_expr(input: &str) -> ParseResult<'_, BlockExpr> {
             continue;
         }
 
-        // Otherwise, parse an expression with statement context
-        let (after_expr, expr) = expression_in_statement(remaining)?;
+        // Otherwise, parse a field update or an expression with statement context
+        let (after_expr, expr) = match field_update_expr(remaining) {
+            Ok(parsed) => parsed,
+            Err(_) => expression_in_statement(remaining)?,
+        };
 
         // Peek ahead to see if this is the final expression
         if let Ok((_, _)) = expect_token::<'_>(Token::RBrace)(after_expr) {
@@ -438,6 +774,13 @@ fn fun_decl(input: &str) -> ParseResult<'_, FunDecl> {
     // Skip leading whitespace
     let (input, _) = skip(input)?;
 
+    // Check for optional @init attribute
+    let (input, is_init) = opt(|input| {
+        let (input, _) = expect_token(Token::At)(input)?;
+        verify(ident, |name: &str| name == "init")(input)
+    })(input)?;
+    let is_init = is_init.is_some();
+
     // Check for optional async keyword
     let (input, is_async) = opt(expect_token(Token::Async))(input)?;
     let is_async = is_async.is_some();
@@ -460,12 +803,13 @@ fn fun_decl(input: &str) -> ParseResult<'_, FunDecl> {
             let (input, _) = expect_token(Token::Gt)(input)?;
             Ok((input, params))
         })(input)?;
-        let type_params = type_params.unwrap_or_default();
+        let mut type_params = type_params.unwrap_or_default();
 
         // Parse parameter list: (x: Int32, y: Int32) or inline params: x: Int32 y: Int32
         let (input, params) = if let Ok((input2, _)) = expect_token(Token::LParen)(input) {
             // Parenthesized parameters
             let (input, params) = separated_list0(expect_token(Token::Comma), param)(input2)?;
+            let (input, _) = trailing_comma(&params)(input)?;
             let (input, _) = expect_token(Token::RParen)(input)?;
             (input, params)
         } else {
@@ -476,14 +820,12 @@ fn fun_decl(input: &str) -> ParseResult<'_, FunDecl> {
         // Parse optional return type: -> ReturnType
         let (input, return_type) = opt(|input| {
             let (input, _) = expect_token(Token::ThinArrow)(input)?;
-            parse_type(input)
+            parse_refinable_type(input)
         })(input)?;
 
-        // Parse optional temporal constraints: where ~tx within ~db
-        let (input, temporal_constraints) = opt(|input| {
-            let (input, _) = expect_token(Token::Where)(input)?;
-            separated_list1(expect_token(Token::Comma), temporal_constraint)(input)
-        })(input)?;
+        // Parse optional where clause: where ~tx within ~db, T: Display
+        let (input, temporal_constraints) =
+            opt(|input| where_clause(input, &mut type_params))(input)?;
         let temporal_constraints = temporal_constraints.unwrap_or_default();
 
         // Now expect the assignment
@@ -510,6 +852,7 @@ fn fun_decl(input: &str) -> ParseResult<'_, FunDecl> {
         FunDecl {
             name,
             is_async,
+            is_init,
             type_params,
             temporal_constraints,
             params,
@@ -549,16 +892,7 @@ fn type_param(input: &str) -> ParseResult<'_, TypeParam> {
     })(input)?;
 
     // Parse optional trait bounds: : Display + Clone + Debug
-    let (input, bounds) = opt(|input| {
-        let (input, _) = expect_token(Token::Colon)(input)?;
-        separated_list1(
-            expect_token(Token::Plus), // + for trait bounds
-            |input| {
-                let (input, trait_name) = ident(input)?;
-                Ok((input, TypeBound { trait_name }))
-            },
-        )(input)
-    })(input)?;
+    let (input, bounds) = opt(preceded(expect_token(Token::Colon), trait_bounds))(input)?;
 
     let bounds = bounds.unwrap_or_default();
     Ok((
@@ -599,6 +933,9 @@ fn context_decl(input: &str) -> ParseResult<'_, ContextDecl> {
 
     let (input, _) = expect_token(Token::LBrace)(input)?;
     let (input, fields) = field_decls(input)?;
+    if fields.iter().any(|field| field.default.is_some()) {
+        return user_syntax_failure("context fields cannot have default values");
+    }
     let (input, _) = expect_token(Token::RBrace)(input)?;
     Ok((input, ContextDecl { name, fields }))
 }
@@ -607,9 +944,10 @@ fn bind_decl_in_statement(input: &str) -> ParseResult<'_, BindDecl> {
     let (input, mutable) = opt(expect_token(Token::Mut))(input)?;
     let (input, _) = expect_token(Token::Val)(input)?;
 
-    let (input, bind_pattern) = pattern(input)?;
+    let (input, bind_pattern) = bind_pattern(input)?;
 
-    let (input, type_annotation) = opt(preceded(expect_token(Token::Colon), parse_type))(input)?;
+    let (input, type_annotation) =
+        opt(preceded(expect_token(Token::Colon), parse_refinable_type))(input)?;
     let (input, _) = expect_token(Token::Assign)(input)?;
     let (input, value) = expression_in_statement(input)?; // Use statement-aware expression parsing
     Ok((
@@ -627,9 +965,10 @@ pub fn bind_decl(input: &str) -> ParseResult<'_, BindDecl> {
     let (input, mutable) = opt(expect_token(Token::Mut))(input)?;
     let (input, _) = expect_token(Token::Val)(input)?;
 
-    let (input, bind_pattern) = pattern(input)?;
+    let (input, bind_pattern) = bind_pattern(input)?;
 
-    let (input, type_annotation) = opt(preceded(expect_token(Token::Colon), parse_type))(input)?;
+    let (input, type_annotation) =
+        opt(preceded(expect_token(Token::Colon), parse_refinable_type))(input)?;
     let (input, _) = expect_token(Token::Assign)(input)?;
     let (input, value) = expression(input)?; // Use normal expression parsing for binding values
     Ok((
@@ -647,7 +986,16 @@ fn literal(input: &str) -> ParseResult<'_, Expr> {
     let (input, token) = lex_token(input)?;
     match token {
         Token::IntLit(n) => Ok((input, Expr::new(ExprKind::IntLit(n)))),
+        // `123L` is sugar for `123 as Int64`
+        Token::Int64Lit(n) => Ok((
+            input,
+            Expr::new(ExprKind::Cast(CastExpr {
+                expr: Box::new(Expr::new(ExprKind::IntLit(n))),
+                target: Type::Named("Int64".to_string()),
+            })),
+        )),
         Token::FloatLit(f) => Ok((input, Expr::new(ExprKind::FloatLit(f)))),
+        Token::DecimalLit(n) => Ok((input, Expr::new(ExprKind::DecimalLit(n)))),
         Token::StringLit(s) => Ok((input, Expr::new(ExprKind::StringLit(s)))),
         Token::CharLit(c) => Ok((input, Expr::new(ExprKind::CharLit(c)))),
         Token::True => Ok((input, Expr::new(ExprKind::BoolLit(true)))),
@@ -691,6 +1039,7 @@ fn record_lit(input: &str) -> ParseResult<'_, RecordLit> {
             let (input, name) = ident(input)?;
             let (input, _) = expect_token(Token::LBrace)(input)?;
             let (input, fields) = separated_list0(expect_token(Token::Comma), field_init)(input)?;
+            let (input, _) = trailing_comma(&fields)(input)?;
             let (input, _) = expect_token(Token::RBrace)(input)?;
             Ok((input, RecordLit { name, fields }))
         },
@@ -698,6 +1047,7 @@ fn record_lit(input: &str) -> ParseResult<'_, RecordLit> {
         |input| {
             let (input, _) = expect_token(Token::LBrace)(input)?;
             let (input, fields) = separated_list0(expect_token(Token::Comma), field_init)(input)?;
+            let (input, _) = trailing_comma(&fields)(input)?;
             let (input, _) = expect_token(Token::RBrace)(input)?;
             Ok((
                 input,
@@ -712,10 +1062,10 @@ fn record_lit(input: &str) -> ParseResult<'_, RecordLit> {
 
 #[allow(dead_code)]
 fn unary_expr(input: &str) -> ParseResult<'_, Expr> {
-    alt((
+    with_span(alt((
         |input| {
             let (input, _) = expect_token(Token::Minus)(input)?;
-            let (input, expr) = unary_expr(input)?;
+            let (input, expr) = nested(input, unary_expr)?;
             Ok((
                 input,
                 Expr::new(ExprKind::Unary(UnaryExpr {
@@ -726,7 +1076,7 @@ fn unary_expr(input: &str) -> ParseResult<'_, Expr> {
         },
         |input| {
             let (input, _) = expect_token(Token::Not)(input)?;
-            let (input, expr) = unary_expr(input)?;
+            let (input, expr) = nested(input, unary_expr)?;
             Ok((
                 input,
                 Expr::new(ExprKind::Unary(UnaryExpr {
@@ -736,11 +1086,11 @@ fn unary_expr(input: &str) -> ParseResult<'_, Expr> {
             ))
         },
         postfix_expr,
-    ))(input)
+    )))(input)
 }
 
 fn atom_expr(input: &str) -> ParseResult<'_, Expr> {
-    alt((
+    with_span(alt((
         literal,
         unit_expr,
         lambda_expr, // Try lambda before other expressions that use |
@@ -758,7 +1108,7 @@ fn atom_expr(input: &str) -> ParseResult<'_, Expr> {
         ),
         with_expr,
         map(block_expr, |b| Expr::new(ExprKind::Block(b))),
-    ))(input)
+    )))(input)
 }
 
 fn unit_expr(input: &str) -> ParseResult<'_, Expr> {
@@ -837,6 +1187,7 @@ fn list_lit(input: &str) -> ParseResult<'_, Expr> {
         expect_token(Token::Comma),
         map(expression, Box::new),
     ))(input)?;
+    let (input, _) = opt(expect_token(Token::Comma))(input)?;
     let (input, _) = expect_token(Token::RBracket)(input)?;
     let mut elements = vec![Box::new(first)];
     elements.extend(rest);
@@ -892,6 +1243,25 @@ fn with_expr(input: &str) -> ParseResult<'_, Expr> {
     // Parse context name
     let (input, context_name) = ident(input)?;
 
+    // `with Context = Context { bindings } { body }` names the instance
+    // explicitly; the bindings block is then required.
+    if let Ok((after_eq, _)) = expect_token(Token::Assign)(input) {
+        let (after_name, instance) = ident(after_eq)?;
+        if instance != context_name {
+            return user_syntax_failure(WITH_INSTANCE_MISMATCH_ERROR);
+        }
+        let (after_bindings, bindings) = context_bindings_block(after_name)?;
+        let (input, body) = block_expr(after_bindings)?;
+        return Ok((
+            input,
+            Expr::new(ExprKind::With(WithExpr {
+                context_name,
+                bindings,
+                body,
+            })),
+        ));
+    }
+
     // Prefer `with Context { bindings } { body }` when a second block follows.
     if let Ok((after_bindings, bindings)) = context_bindings_block(input) {
         if let Ok((after_body, body)) = block_expr(after_bindings) {
@@ -930,6 +1300,7 @@ fn with_expr(input: &str) -> ParseResult<'_, Expr> {
 fn context_bindings_block(input: &str) -> ParseResult<'_, Vec<FieldInit>> {
     let (input, _) = expect_token(Token::LBrace)(input)?;
     let (input, bindings) = separated_list0(expect_token(Token::Comma), field_init)(input)?;
+    let (input, _) = trailing_comma(&bindings)(input)?;
     let (input, _) = expect_token(Token::RBrace)(input)?;
     Ok((input, bindings))
 }
@@ -983,6 +1354,10 @@ fn with_lifetime_expr(input: &str) -> ParseResult<'_, Expr> {
 }
 
 fn pattern(input: &str) -> ParseResult<'_, Pattern> {
+    nested(input, pattern_inner)
+}
+
+fn pattern_inner(input: &str) -> ParseResult<'_, Pattern> {
     alt((
         // Check for wildcard pattern
         |input| {
@@ -995,6 +1370,7 @@ fn pattern(input: &str) -> ParseResult<'_, Pattern> {
                 ))),
             }
         },
+        binding_pattern, // `name @ pattern` before the bare identifier
         some_pattern,
         none_pattern,
         ok_pattern,
@@ -1002,9 +1378,11 @@ fn pattern(input: &str) -> ParseResult<'_, Pattern> {
         record_pattern, // Try record patterns before identifiers
         list_pattern,   // Try list patterns before literals
         unit_pattern,
+        range_pattern, // `0..=9` before the bare `0` literal
         map(literal, |expr| match expr.kind {
             ExprKind::IntLit(n) => Pattern::Literal(Literal::Int(n)),
             ExprKind::FloatLit(f) => Pattern::Literal(Literal::Float(f)),
+            ExprKind::DecimalLit(n) => Pattern::Literal(Literal::Decimal(n)),
             ExprKind::StringLit(s) => Pattern::Literal(Literal::String(s)),
             ExprKind::CharLit(c) => Pattern::Literal(Literal::Char(c)),
             ExprKind::BoolLit(b) => Pattern::Literal(Literal::Bool(b)),
@@ -1015,6 +1393,44 @@ fn pattern(input: &str) -> ParseResult<'_, Pattern> {
     ))(input)
 }
 
+fn range_pattern(input: &str) -> ParseResult<'_, Pattern> {
+    let (input, start) = range_pattern_bound(input)?;
+    let (input, inclusive) = alt((
+        value(true, expect_token(Token::DotDotEq)),
+        value(false, expect_token(Token::DotDot)),
+    ))(input)?;
+    let (input, end) = range_pattern_bound(input)?;
+    Ok((
+        input,
+        Pattern::Range {
+            start,
+            end,
+            inclusive,
+        },
+    ))
+}
+
+/// An integer literal with an optional leading `-`.
+fn range_pattern_bound(input: &str) -> ParseResult<'_, i64> {
+    let (input, negative) = opt(expect_token(Token::Minus))(input)?;
+    let (input, token) = lex_token(input)?;
+    match token {
+        Token::IntLit(n) if negative.is_some() => Ok((input, -n)),
+        Token::IntLit(n) => Ok((input, n)),
+        _ => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Digit,
+        ))),
+    }
+}
+
+fn binding_pattern(input: &str) -> ParseResult<'_, Pattern> {
+    let (input, name) = ident(input)?;
+    let (input, _) = expect_token(Token::At)(input)?;
+    let (input, inner) = pattern(input)?;
+    Ok((input, Pattern::Binding(name, Box::new(inner))))
+}
+
 fn unit_pattern(input: &str) -> ParseResult<'_, Pattern> {
     let (input, _) = expect_token(Token::LParen)(input)?;
     let (input, _) = expect_token(Token::RParen)(input)?;
@@ -1068,6 +1484,16 @@ fn err_pattern(input: &str) -> ParseResult<'_, Pattern> {
 fn record_pattern(input: &str) -> ParseResult<'_, Pattern> {
     // Try to parse an identifier followed by {
     let (input, name) = ident(input)?;
+    record_pattern_body(input, name)
+}
+
+/// `val { x, y } = p`: a record pattern without a type name, which takes the
+/// record type of the bound value. Only `val` bindings accept it.
+fn bind_pattern(input: &str) -> ParseResult<'_, Pattern> {
+    alt((|input| record_pattern_body(input, String::new()), pattern))(input)
+}
+
+fn record_pattern_body(input: &str, name: String) -> ParseResult<'_, Pattern> {
     let (input, _) = expect_token(Token::LBrace)(input)?;
 
     // Parse fields and check for spread
@@ -1169,9 +1595,23 @@ fn list_pattern(input: &str) -> ParseResult<'_, Pattern> {
 
 fn match_arm(input: &str) -> ParseResult<'_, MatchArm> {
     let (input, pattern) = pattern(input)?;
+    let (input, guard) = opt(match_guard)(input)?;
     let (input, _) = expect_token(Token::Arrow)(input)?;
     let (input, body) = block_expr(input)?;
-    Ok((input, MatchArm { pattern, body }))
+    Ok((
+        input,
+        MatchArm {
+            pattern,
+            guard,
+            body,
+        },
+    ))
+}
+
+// `if` is contextual: it only introduces a guard between a pattern and `=>`.
+fn match_guard(input: &str) -> ParseResult<'_, Expr> {
+    let (input, _) = expect_token(Token::Ident("if".to_string()))(input)?;
+    expression(input)
 }
 
 #[allow(dead_code)]
@@ -1180,6 +1620,7 @@ fn match_expr(input: &str) -> ParseResult<'_, Expr> {
 }
 
 fn match_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Expr> {
+    let start = input;
     let (input, expr) = pipe_expr_with_context(input, in_statement)?;
     let (input, arms) = opt(preceded(
         expect_token(Token::Match),
@@ -1193,10 +1634,14 @@ fn match_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, E
     match arms {
         Some(arms) => Ok((
             input,
-            Expr::new(ExprKind::Match(MatchExpr {
-                expr: Box::new(expr),
-                arms,
-            })),
+            spanned(
+                start,
+                input,
+                Expr::new(ExprKind::Match(MatchExpr {
+                    expr: Box::new(expr),
+                    arms,
+                })),
+            ),
         )),
         None => Ok((input, expr)),
     }
@@ -1208,16 +1653,44 @@ fn while_expr(input: &str) -> ParseResult<'_, Expr> {
 }
 
 fn while_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Expr> {
+    let start = input;
+    // `loop { ... }` is a complete loop; it takes no postfix condition.
+    if let Ok((input, body)) = preceded(expect_token(Token::Loop), block_expr)(input) {
+        return Ok((
+            input,
+            spanned(start, input, Expr::new(ExprKind::Loop(body))),
+        ));
+    }
     let (input, expr) = match_expr_with_context(input, in_statement)?;
-    let (input, body) = opt(preceded(expect_token(Token::While), block_expr))(input)?;
+    // `expr while pattern { ... }` loops while `expr` matches `pattern`.
+    let (input, body) = opt(preceded(
+        expect_token(Token::While),
+        tuple((opt(pattern), block_expr)),
+    ))(input)?;
 
     match body {
-        Some(body) => Ok((
+        Some((None, body)) => Ok((
             input,
-            Expr::new(ExprKind::While(WhileExpr {
-                condition: Box::new(expr),
-                body,
-            })),
+            spanned(
+                start,
+                input,
+                Expr::new(ExprKind::While(WhileExpr {
+                    condition: Box::new(expr),
+                    body,
+                })),
+            ),
+        )),
+        Some((Some(pattern), body)) => Ok((
+            input,
+            spanned(
+                start,
+                input,
+                Expr::new(ExprKind::WhileLet(WhileLetExpr {
+                    pattern,
+                    expr: Box::new(expr),
+                    body,
+                })),
+            ),
         )),
         None => Ok((input, expr)),
     }
@@ -1228,6 +1701,11 @@ fn then_expr(input: &str) -> ParseResult<'_, Expr> {
 }
 
 fn then_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Expr> {
+    nested(input, |input| then_expr_inner(input, in_statement))
+}
+
+fn then_expr_inner(input: &str, in_statement: bool) -> ParseResult<'_, Expr> {
+    let start = input;
     let (input, first_cond) = while_expr_with_context(input, in_statement)?;
     let (input, then_part) = opt(preceded(
         expect_token(Token::Then),
@@ -1251,12 +1729,16 @@ fn then_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Ex
                 .collect();
             Ok((
                 input,
-                Expr::new(ExprKind::Then(ThenExpr {
-                    condition: Box::new(first_cond),
-                    then_block,
-                    else_ifs,
-                    else_block,
-                })),
+                spanned(
+                    start,
+                    input,
+                    Expr::new(ExprKind::Then(ThenExpr {
+                        condition: Box::new(first_cond),
+                        then_block,
+                        else_ifs,
+                        else_block,
+                    })),
+                ),
             ))
         }
         None => Ok((input, first_cond)),
@@ -1344,6 +1826,13 @@ fn binary_expr_min_precedence(
     in_statement: bool,
     min_precedence: u8,
 ) -> ParseResult<'_, Expr> {
+    chained(input, |input| {
+        binary_chain(input, in_statement, min_precedence)
+    })
+}
+
+fn binary_chain(input: &str, in_statement: bool, min_precedence: u8) -> ParseResult<'_, Expr> {
+    let start = input;
     let (mut input, mut left) = call_expr_with_context(input, in_statement)?;
 
     while let Ok((after_op, op)) = binary_op(input) {
@@ -1351,14 +1840,19 @@ fn binary_expr_min_precedence(
         if precedence < min_precedence {
             break;
         }
+        descend()?;
 
         let (after_right, right) =
             binary_expr_min_precedence(after_op, in_statement, precedence + 1)?;
-        left = Expr::new(ExprKind::Binary(BinaryExpr {
-            left: Box::new(left),
-            op,
-            right: Box::new(right),
-        }));
+        left = spanned(
+            start,
+            after_right,
+            Expr::new(ExprKind::Binary(BinaryExpr {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            })),
+        );
         input = after_right;
     }
 
@@ -1371,25 +1865,42 @@ fn pipe_expr(input: &str) -> ParseResult<'_, Expr> {
 }
 
 fn pipe_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Expr> {
-    let (input, first) = binary_expr_with_context(input, in_statement)?;
-    let (input, pipes) = many0(tuple((
+    chained(input, |input| pipe_chain(input, in_statement))
+}
+
+fn pipe_chain(input: &str, in_statement: bool) -> ParseResult<'_, Expr> {
+    let start = input;
+    let (mut input, mut expr) = binary_expr_with_context(input, in_statement)?;
+    let mut pipe_step = tuple((
         pipe_op,
         alt((
-            map(ident, PipeTarget::Ident),
+            // A bare name binds or calls; a dotted name such as
+            // `Point.translate` is a qualified method target expression.
+            map(
+                terminated(ident, not(expect_token(Token::Dot))),
+                PipeTarget::Ident,
+            ),
             map(
                 |i| binary_expr_with_context(i, in_statement),
                 |e| PipeTarget::Expr(Box::new(e)),
             ),
         )),
-    )))(input)?;
-
-    let expr = pipes.into_iter().fold(first, |acc, (op, target)| {
-        Expr::new(ExprKind::Pipe(PipeExpr {
-            expr: Box::new(acc),
-            op,
-            target,
-        }))
-    });
+    ));
+
+    while let Ok((after_step, (op, target))) = pipe_step(input) {
+        descend()?;
+        expr = spanned(
+            start,
+            after_step,
+            Expr::new(ExprKind::Pipe(PipeExpr {
+                expr: Box::new(expr),
+                op,
+                target,
+            })),
+        );
+        input = after_step;
+        (input, expr) = postfix_try(start, input, expr);
+    }
     Ok((input, expr))
 }
 
@@ -1399,14 +1910,30 @@ fn call_expr(input: &str) -> ParseResult<'_, Expr> {
 }
 
 fn call_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Expr> {
-    alt((
+    let start = input;
+    let (input, expr) = osv_call_expr(input, in_statement)?;
+    Ok(postfix_try(start, input, expr))
+}
+
+/// Wrap `expr` in one `Try` per trailing `?`. The operator applies to a
+/// whole call or pipe step, so `(text) parse_int?` unwraps the call's result
+/// rather than the callee.
+fn postfix_try<'a>(start: &'a str, mut input: &'a str, mut expr: Expr) -> (&'a str, Expr) {
+    while let Ok((after_try, _)) = expect_token::<'_>(Token::Question)(input) {
+        expr = spanned(start, after_try, Expr::new(ExprKind::Try(Box::new(expr))));
+        input = after_try;
+    }
+    (input, expr)
+}
+
+fn osv_call_expr(input: &str, in_statement: bool) -> ParseResult<'_, Expr> {
+    with_span(alt((
         // Multiple arguments with parentheses: (a,b,c) func - OSV syntax
         |input| {
-            let (input, args) = delimited(
-                expect_token(Token::LParen),
-                separated_list0(expect_token(Token::Comma), expression),
-                expect_token(Token::RParen),
-            )(input)?;
+            let (input, _) = expect_token(Token::LParen)(input)?;
+            let (input, args) = separated_list0(expect_token(Token::Comma), expression)(input)?;
+            let (input, _) = trailing_comma(&args)(input)?;
+            let (input, _) = expect_token(Token::RParen)(input)?;
 
             if let Ok((_, tok)) = lex_token(input) {
                 if starts_infix_or_pipe(&tok) || matches!(tok, Token::Not) {
@@ -1418,11 +1945,16 @@ fn call_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Ex
             }
 
             let (input, func) = simple_expr(input)?;
+            let (input, type_args) = match &func.kind {
+                ExprKind::Ident(_) => explicit_type_args(input)?,
+                _ => (input, Vec::new()),
+            };
             Ok((
                 input,
                 Expr::new(ExprKind::Call(CallExpr {
                     function: Box::new(func),
                     args: args.into_iter().map(Box::new).collect(),
+                    type_args,
                 })),
             ))
         },
@@ -1484,7 +2016,9 @@ fn call_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Ex
                         Ok((
                             _,
                             Token::IntLit(_)
+                                | Token::Int64Lit(_)
                                 | Token::FloatLit(_)
+                                | Token::DecimalLit(_)
                                 | Token::StringLit(_)
                                 | Token::CharLit(_)
                                 | Token::True
@@ -1531,15 +2065,34 @@ fn call_expr_with_context(input: &str, in_statement: bool) -> ParseResult<'_, Ex
             } else {
                 // OSV: obj subj.verb => subj.verb(obj)
                 let result = rest.into_iter().fold(first, |arg, func| {
-                    Expr::new(ExprKind::Call(CallExpr {
+                    let span = joined_span(&arg, &func);
+                    let mut call = Expr::new(ExprKind::Call(CallExpr {
                         function: Box::new(func),
                         args: vec![Box::new(arg)],
-                    }))
+                        type_args: Vec::new(),
+                    }));
+                    call.span = span;
+                    call
                 });
                 Ok((input, result))
             }
         },
-    ))(input)
+    )))(input)
+}
+
+/// Explicit type arguments after a call's callee, as in `("x") identity<String>`.
+///
+/// A `<` that does not open a complete type argument list is left for the
+/// comparison operator.
+fn explicit_type_args(input: &str) -> ParseResult<'_, Vec<Type>> {
+    map(
+        opt(delimited(
+            expect_token(Token::Lt),
+            separated_list1(expect_token(Token::Comma), parse_type),
+            expect_token(Token::Gt),
+        )),
+        Option::unwrap_or_default,
+    )(input)
 }
 
 pub fn simple_expr(input: &str) -> ParseResult<'_, Expr> {
@@ -1547,14 +2100,19 @@ pub fn simple_expr(input: &str) -> ParseResult<'_, Expr> {
 }
 
 fn cast_expr(input: &str) -> ParseResult<'_, Expr> {
+    let start = input;
     let (mut input, mut expr) = unary_expr(input)?;
 
     while let Ok((after_as, _)) = expect_token::<'_>(Token::As)(input) {
         let (after_type, target) = parse_type(after_as)?;
-        expr = Expr::new(ExprKind::Cast(CastExpr {
-            expr: Box::new(expr),
-            target,
-        }));
+        expr = spanned(
+            start,
+            after_type,
+            Expr::new(ExprKind::Cast(CastExpr {
+                expr: Box::new(expr),
+                target,
+            })),
+        );
         input = after_type;
     }
 
@@ -1562,6 +2120,7 @@ fn cast_expr(input: &str) -> ParseResult<'_, Expr> {
 }
 
 fn postfix_expr(input: &str) -> ParseResult<'_, Expr> {
+    let start = input;
     let (mut input, mut expr) = atom_expr(input)?;
 
     // Handle postfix operations
@@ -1582,19 +2141,28 @@ fn postfix_expr(input: &str) -> ParseResult<'_, Expr> {
                     let (new_input, _) = expect_token(Token::LBrace)(new_input)?;
                     let (new_input, fields) =
                         separated_list0(expect_token(Token::Comma), field_init)(new_input)?;
+                    let (new_input, _) = trailing_comma(&fields)(new_input)?;
                     let (new_input, _) = expect_token(Token::RBrace)(new_input)?;
 
-                    let mut clone_expr = Expr::new(ExprKind::Clone(CloneExpr {
-                        base: Box::new(expr),
-                        updates: RecordLit {
-                            name: String::new(),
-                            fields,
-                        },
-                    }));
+                    let mut clone_expr = spanned(
+                        start,
+                        new_input,
+                        Expr::new(ExprKind::Clone(CloneExpr {
+                            base: Box::new(expr),
+                            updates: RecordLit {
+                                name: String::new(),
+                                fields,
+                            },
+                        })),
+                    );
 
                     // Check if freeze follows the clone
                     if let Ok((freeze_input, _)) = expect_token::<'_>(Token::Freeze)(new_input) {
-                        clone_expr = Expr::new(ExprKind::Freeze(Box::new(clone_expr)));
+                        clone_expr = spanned(
+                            start,
+                            freeze_input,
+                            Expr::new(ExprKind::Freeze(Box::new(clone_expr))),
+                        );
                         input = freeze_input;
                     } else {
                         input = new_input;
@@ -1604,12 +2172,20 @@ fn postfix_expr(input: &str) -> ParseResult<'_, Expr> {
                 } else {
                     // Regular field access
                     let (new_input, field) = ident(new_input)?;
-                    expr = Expr::new(ExprKind::FieldAccess(Box::new(expr), field));
+                    expr = spanned(
+                        start,
+                        new_input,
+                        Expr::new(ExprKind::FieldAccess(Box::new(expr), field)),
+                    );
                     input = new_input;
                 }
             }
             Some(PostfixOp::Freeze) => {
-                expr = Expr::new(ExprKind::Freeze(Box::new(expr)));
+                expr = spanned(
+                    start,
+                    new_input,
+                    Expr::new(ExprKind::Freeze(Box::new(expr))),
+                );
                 input = new_input;
             }
             None => break,
@@ -1636,12 +2212,36 @@ fn expression_in_statement(input: &str) -> ParseResult<'_, Expr> {
 #[allow(dead_code)]
 fn statement(input: &str) -> ParseResult<'_, Stmt> {
     alt((
+        loop_control_stmt,
         map(bind_decl_in_statement, Stmt::Binding),
         assignment_stmt,
         map(expression, |e| Stmt::Expr(Box::new(e))),
     ))(input)
 }
 
+/// `continue`, `break`, or `break value`. A `break` directly followed by `;`
+/// or `}` carries no value.
+fn loop_control_stmt(input: &str) -> ParseResult<'_, Stmt> {
+    let (after_keyword, token) = lex_token(input)?;
+    match token {
+        Token::Continue => Ok((after_keyword, Stmt::Continue)),
+        Token::Break => {
+            if matches!(
+                lex_token(after_keyword),
+                Ok((_, Token::Semicolon | Token::RBrace))
+            ) {
+                return Ok((after_keyword, Stmt::Break(None)));
+            }
+            let (input, value) = expression_in_statement(after_keyword)?;
+            Ok((input, Stmt::Break(Some(Box::new(value)))))
+        }
+        _ => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Tag,
+        ))),
+    }
+}
+
 fn assignment_stmt(input: &str) -> ParseResult<'_, Stmt> {
     let (input, name) = ident(input)?;
     let (input, _) = expect_token(Token::Assign)(input)?;
@@ -1655,6 +2255,32 @@ fn assignment_stmt(input: &str) -> ParseResult<'_, Stmt> {
     ))
 }
 
+/// `record.field <- value`: an in-place update through a field path.
+fn field_update_expr(input: &str) -> ParseResult<'_, Expr> {
+    let start = input;
+    let (after_target, target) = nested(input, postfix_expr)?;
+    let ExprKind::FieldAccess(object, field) = target.kind else {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Tag,
+        )));
+    };
+    let (input, _) = expect_token(Token::LeftArrow)(after_target)?;
+    let (input, value) = expression_in_statement(input)?;
+    Ok((
+        input,
+        spanned(
+            start,
+            input,
+            Expr::new(ExprKind::FieldUpdate(FieldUpdateExpr {
+                object,
+                field,
+                value: Box::new(value),
+            })),
+        ),
+    ))
+}
+
 fn import_decl(input: &str) -> ParseResult<'_, ImportDecl> {
     let (input, _) = expect_token(Token::Import)(input)?;
     if matches!(lex_token(input), Ok((_, Token::StringLit(_)))) {
@@ -1756,7 +2382,31 @@ pub fn top_decl(input: &str) -> ParseResult<'_, TopDecl> {
     }
 }
 
+/// Parses a single standalone expression, such as a REPL entry.
+///
+/// Spans are absolute offsets into `input`.
+pub fn parse_expression(input: &str) -> ParseResult<'_, Expr> {
+    let source_len = input.len();
+    let (input, _) = skip(input)?;
+    let (input, mut expr) = with_nesting_limit(input, DEFAULT_MAX_NESTING_DEPTH, expression)?;
+    visit_expr_subtree_mut(&mut expr, &mut |expr| resolve_span(expr, source_len));
+    Ok((input, expr))
+}
+
+/// Parses a whole source file, nesting at most
+/// [`DEFAULT_MAX_NESTING_DEPTH`] levels deep.
 pub fn parse_program(input: &str) -> ParseResult<'_, Program> {
+    parse_program_with_max_depth(input, DEFAULT_MAX_NESTING_DEPTH)
+}
+
+/// Parses a whole source file, failing with [`ParseError::NestingTooDeep`]
+/// once expressions, types or patterns nest more than `max_depth` levels.
+pub fn parse_program_with_max_depth(input: &str, max_depth: usize) -> ParseResult<'_, Program> {
+    with_nesting_limit(input, max_depth, program)
+}
+
+fn program(input: &str) -> ParseResult<'_, Program> {
+    let source_len = input.len();
     // Skip leading whitespace/comments first
     let (input, _) = skip(input)?;
     let (input, imports) = many0(import_decl)(input)?;
@@ -1836,6 +2486,7 @@ pub fn parse_program(input: &str) -> ParseResult<'_, Program> {
     // Number expression nodes once the full program structure is known, so
     // downstream stages can key per-node facts by stable NodeId.
     assign_node_ids(&mut program);
+    resolve_spans(&mut program, source_len);
 
     Ok((remaining, program))
 }
@@ -1852,6 +2503,84 @@ mod tests {
         assert_eq!(decl.fields.len(), 2);
     }
 
+    #[test]
+    fn test_record_where_clause_adds_bounds() {
+        let input = "record Labeled<T, ~r> where T: Display + Clone, ~r within ~r { value: T }";
+        let (rest, decl) = record_decl(input).unwrap();
+        assert!(rest.trim().is_empty());
+        assert_eq!(
+            decl.type_params[0].bounds,
+            vec![
+                TypeBound {
+                    trait_name: "Display".to_string()
+                },
+                TypeBound {
+                    trait_name: "Clone".to_string()
+                },
+            ]
+        );
+        assert_eq!(decl.temporal_constraints.len(), 1);
+    }
+
+    #[test]
+    fn test_record_decl_field_defaults() {
+        let input = "record Config {\n    host: String = \"localhost\"\n    port: Int32 = 8080\n    debug: Boolean\n}";
+        let (rest, decl) = record_decl(input).unwrap();
+        assert!(rest.trim().is_empty());
+        let defaults: Vec<_> = decl
+            .fields
+            .iter()
+            .map(|field| field.default.as_ref().map(|expr| expr.kind.clone()))
+            .collect();
+        assert_eq!(
+            defaults,
+            vec![
+                Some(ExprKind::StringLit("localhost".to_string())),
+                Some(ExprKind::IntLit(8080)),
+                None,
+            ]
+        );
+
+        assert!(context_decl("context Db { url: String = \"x\" }").is_err());
+    }
+
+    #[test]
+    fn test_try_applies_to_whole_call() {
+        let (rest, expr) = expression("(text) parse_int? + 1").unwrap();
+        assert!(rest.trim().is_empty());
+        let ExprKind::Binary(binary) = expr.kind else {
+            panic!("expected a binary expression, got {:?}", expr.kind);
+        };
+        let ExprKind::Try(operand) = binary.left.kind else {
+            panic!(
+                "expected `?` on the left operand, got {:?}",
+                binary.left.kind
+            );
+        };
+        assert!(matches!(operand.kind, ExprKind::Call(_)));
+    }
+
+    #[test]
+    fn test_range_patterns() {
+        assert_eq!(
+            pattern("0..=9").unwrap().1,
+            Pattern::Range {
+                start: 0,
+                end: 9,
+                inclusive: true
+            }
+        );
+        assert_eq!(
+            pattern("-10..0").unwrap().1,
+            Pattern::Range {
+                start: -10,
+                end: 0,
+                inclusive: false
+            }
+        );
+        assert_eq!(pattern("7").unwrap().1, Pattern::Literal(Literal::Int(7)));
+    }
+
     #[test]
     fn test_fun_decl() {
         let input = "fun add: (a: Int32, b: Int32) -> Int32 = { a }";
@@ -1868,6 +2597,148 @@ mod tests {
         assert!(matches!(&expr.kind, ExprKind::Pipe(_)));
     }
 
+    #[test]
+    fn test_pipe_into_qualified_method_target() {
+        let (rest, expr) = pipe_expr("p |> Point.translate").unwrap();
+        assert!(rest.trim().is_empty());
+        let ExprKind::Pipe(pipe) = &expr.kind else {
+            panic!("expected pipe, got {:?}", expr.kind);
+        };
+        let PipeTarget::Expr(target) = &pipe.target else {
+            panic!("expected expression target, got {:?}", pipe.target);
+        };
+        assert!(matches!(&target.kind, ExprKind::FieldAccess(_, method) if method == "translate"));
+    }
+
+    #[test]
+    fn test_field_update_in_block() {
+        let (rest, block) = block_expr("{ p.x <- p.x + 1\n p }").unwrap();
+        assert!(rest.trim().is_empty());
+        let Stmt::Expr(update) = &block.statements[0] else {
+            panic!(
+                "expected expression statement, got {:?}",
+                block.statements[0]
+            );
+        };
+        let ExprKind::FieldUpdate(update) = &update.kind else {
+            panic!("expected field update, got {:?}", update.kind);
+        };
+        assert_eq!(update.field, "x");
+        assert!(matches!(&update.object.kind, ExprKind::Ident(name) if name == "p"));
+        assert!(matches!(&update.value.kind, ExprKind::Binary(_)));
+    }
+
+    #[test]
+    fn test_trailing_semicolon_makes_the_block_value_a_statement() {
+        let (rest, valued) = block_expr("{ x }").unwrap();
+        assert!(rest.trim().is_empty());
+        assert!(valued.statements.is_empty());
+        assert!(matches!(
+            valued.expr.as_deref().map(|expr| &expr.kind),
+            Some(ExprKind::Ident(name)) if name == "x"
+        ));
+
+        let (rest, unit) = block_expr("{ x; }").unwrap();
+        assert!(rest.trim().is_empty());
+        assert!(unit.expr.is_none());
+        assert!(matches!(
+            unit.statements.as_slice(),
+            [Stmt::Expr(expr)] if matches!(&expr.kind, ExprKind::Ident(name) if name == "x")
+        ));
+
+        let (_, commented) = block_expr(
+            "{ x // result
+}",
+        )
+        .unwrap();
+        assert!(commented.expr.is_some());
+    }
+
+    #[test]
+    fn test_program_expression_spans_are_absolute() {
+        let source = "// spans\nval total = (1, 2) add |> double\n";
+        let (_, program) = parse_program(source).unwrap();
+        let TopDecl::Binding(binding) = &program.declarations[0] else {
+            panic!("expected binding, got {:?}", program.declarations[0]);
+        };
+        let span = binding.value.span.expect("pipe span");
+        assert_eq!(span.slice(source), Some("(1, 2) add |> double"));
+        let ExprKind::Pipe(pipe) = &binding.value.kind else {
+            panic!("expected pipe, got {:?}", binding.value.kind);
+        };
+        assert_eq!(pipe.expr.span.unwrap().slice(source), Some("(1, 2) add"));
+    }
+
+    #[test]
+    fn test_call_with_explicit_type_args() {
+        let (rest, expr) = expression("(\"x\") identity<String>").unwrap();
+        assert!(rest.trim().is_empty());
+        let ExprKind::Call(call) = &expr.kind else {
+            panic!("expected call, got {:?}", expr.kind);
+        };
+        assert_eq!(call.type_args, vec![Type::Named("String".to_string())]);
+
+        // A `<` that does not open a type argument list stays a comparison.
+        let (rest, expr) = expression("(a) f < b").unwrap();
+        assert!(rest.trim().is_empty());
+        assert!(matches!(
+            &expr.kind,
+            ExprKind::Binary(BinaryExpr {
+                op: BinaryOp::Lt,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_trailing_comma_in_record_literal() {
+        let (rest, lit) = record_lit("Point { x: 1, y: 2, }").unwrap();
+        assert!(rest.trim().is_empty());
+        assert_eq!(lit.name, "Point");
+        assert_eq!(lit.fields.len(), 2);
+
+        assert!(record_lit("Point { , }").is_err());
+    }
+
+    #[test]
+    fn test_trailing_comma_in_list_literal() {
+        let (rest, expr) = expression("[1, 2, 3,]").unwrap();
+        assert!(rest.trim().is_empty());
+        assert!(matches!(&expr.kind, ExprKind::ListLit(items) if items.len() == 3));
+
+        assert!(list_lit("[,]").is_err());
+    }
+
+    #[test]
+    fn test_trailing_comma_in_array_literal() {
+        let source = "val cells: Array<Int32, 3> = [\n    1,\n    2,\n    3,\n]\n";
+        let (rest, program) = parse_program(source).unwrap();
+        assert!(rest.trim().is_empty());
+        let TopDecl::Binding(binding) = &program.declarations[0] else {
+            panic!("expected binding, got {:?}", program.declarations[0]);
+        };
+        assert!(matches!(&binding.value.kind, ExprKind::ListLit(items) if items.len() == 3));
+    }
+
+    #[test]
+    fn test_trailing_comma_in_fun_params() {
+        let (rest, decl) = fun_decl("fun add: (a: Int32, b: Int32,) -> Int32 = { a }").unwrap();
+        assert!(rest.trim().is_empty());
+        assert_eq!(decl.params.len(), 2);
+
+        assert!(fun_decl("fun none: (,) -> Int32 = { 0 }").is_err());
+    }
+
+    #[test]
+    fn test_trailing_comma_in_call_args() {
+        let (rest, expr) = expression("(1, 2,) add").unwrap();
+        assert!(rest.trim().is_empty());
+        let ExprKind::Call(call) = &expr.kind else {
+            panic!("expected call, got {:?}", expr.kind);
+        };
+        assert_eq!(call.args.len(), 2);
+    }
+
     #[test]
     fn test_mutable_pipe_rejected() {
         assert!(pipe_expr("42 |>> add").is_err());
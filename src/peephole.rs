@@ -0,0 +1,334 @@
+//! # Peephole Optimization
+//!
+//! A small cleanup pass over the WAT produced by [`WasmCodeGen`]. Function
+//! bodies are emitted as flat instruction lines, so the pass lifts each body
+//! into a tiny instruction IR, rewrites short windows of straight-line code,
+//! and prints the result back with the original indentation.
+//!
+//! Rewrites:
+//! - constant folding: `i32.const 2` `i32.const 3` `i32.mul` → `i32.const 6`
+//! - identities: `i32.const 0` `i32.add`/`i32.sub` and `i32.const 1`
+//!   `i32.mul` are removed
+//! - `local.set $x` `local.get $x` is removed when `$x` has no other use in
+//!   the function, and becomes `local.tee $x` otherwise
+//!
+//! Lines the IR does not model (control flow, calls, folded expressions,
+//! declarations) are kept verbatim and act as barriers between windows.
+//!
+//! [`WasmCodeGen`]: crate::codegen::WasmCodeGen
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumType {
+    I32,
+    I64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Instr {
+    Const(NumType, i64),
+    Binary(NumType, BinOp),
+    LocalGet(String),
+    LocalSet(String),
+    LocalTee(String),
+    /// Any line the pass does not model, kept verbatim.
+    Other(String),
+}
+
+impl Instr {
+    fn parse(line: &str) -> Self {
+        let trimmed = line.trim();
+        let mut parts = trimmed.split_whitespace();
+        let (Some(op), arg, None) = (parts.next(), parts.next(), parts.next()) else {
+            return Instr::Other(line.to_string());
+        };
+        let parsed = match (op, arg) {
+            ("i32.const", Some(value)) => value.parse::<i32>().ok().map(|value| {
+                // Keep the i32 bit pattern; folding wraps at 32 bits.
+                Instr::Const(NumType::I32, value as i64)
+            }),
+            ("i64.const", Some(value)) => value
+                .parse::<i64>()
+                .ok()
+                .map(|value| Instr::Const(NumType::I64, value)),
+            ("local.get", Some(name)) if name.starts_with('$') => {
+                Some(Instr::LocalGet(name.to_string()))
+            }
+            ("local.set", Some(name)) if name.starts_with('$') => {
+                Some(Instr::LocalSet(name.to_string()))
+            }
+            ("local.tee", Some(name)) if name.starts_with('$') => {
+                Some(Instr::LocalTee(name.to_string()))
+            }
+            (op, None) => Self::parse_binary(op),
+            _ => None,
+        };
+        parsed.unwrap_or_else(|| Instr::Other(line.to_string()))
+    }
+
+    fn parse_binary(op: &str) -> Option<Self> {
+        let (ty, name) = op.split_once('.')?;
+        let ty = match ty {
+            "i32" => NumType::I32,
+            "i64" => NumType::I64,
+            _ => return None,
+        };
+        let op = match name {
+            "add" => BinOp::Add,
+            "sub" => BinOp::Sub,
+            "mul" => BinOp::Mul,
+            _ => return None,
+        };
+        Some(Instr::Binary(ty, op))
+    }
+
+    fn render(&self, indent: &str) -> String {
+        match self {
+            Instr::Const(NumType::I32, value) => format!("{indent}i32.const {value}"),
+            Instr::Const(NumType::I64, value) => format!("{indent}i64.const {value}"),
+            Instr::Binary(ty, op) => {
+                let ty = match ty {
+                    NumType::I32 => "i32",
+                    NumType::I64 => "i64",
+                };
+                let op = match op {
+                    BinOp::Add => "add",
+                    BinOp::Sub => "sub",
+                    BinOp::Mul => "mul",
+                };
+                format!("{indent}{ty}.{op}")
+            }
+            Instr::LocalGet(name) => format!("{indent}local.get {name}"),
+            Instr::LocalSet(name) => format!("{indent}local.set {name}"),
+            Instr::LocalTee(name) => format!("{indent}local.tee {name}"),
+            Instr::Other(line) => line.clone(),
+        }
+    }
+}
+
+fn fold(ty: NumType, op: BinOp, left: i64, right: i64) -> i64 {
+    match ty {
+        NumType::I32 => {
+            let (left, right) = (left as i32, right as i32);
+            let value = match op {
+                BinOp::Add => left.wrapping_add(right),
+                BinOp::Sub => left.wrapping_sub(right),
+                BinOp::Mul => left.wrapping_mul(right),
+            };
+            value as i64
+        }
+        NumType::I64 => match op {
+            BinOp::Add => left.wrapping_add(right),
+            BinOp::Sub => left.wrapping_sub(right),
+            BinOp::Mul => left.wrapping_mul(right),
+        },
+    }
+}
+
+/// Optimizes every function body in a WAT module.
+pub fn optimize_wat(wat: &str) -> String {
+    let lines: Vec<&str> = wat.lines().collect();
+    let mut output = String::with_capacity(wat.len());
+    let mut i = 0;
+    while i < lines.len() {
+        if !is_func_header(lines[i]) {
+            output.push_str(lines[i]);
+            output.push('\n');
+            i += 1;
+            continue;
+        }
+        output.push_str(lines[i]);
+        output.push('\n');
+        let start = i + 1;
+        let mut end = start;
+        while end < lines.len() && !is_top_level_line(lines[end]) {
+            end += 1;
+        }
+        for line in optimize_body(&lines[start..end]) {
+            output.push_str(&line);
+            output.push('\n');
+        }
+        i = end;
+    }
+    output
+}
+
+/// A function whose body continues on the following lines.
+fn is_func_header(line: &str) -> bool {
+    line.starts_with("  (func ") && line.matches('(').count() > line.matches(')').count()
+}
+
+/// Lines at module indentation close the current function or start the next
+/// module field.
+fn is_top_level_line(line: &str) -> bool {
+    line.starts_with("  (") || line.starts_with("  )") || line.starts_with(')')
+}
+
+fn optimize_body(lines: &[&str]) -> Vec<String> {
+    let mut instrs: Vec<(String, Instr)> = lines
+        .iter()
+        .map(|line| {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            (indent.to_string(), Instr::parse(line))
+        })
+        .collect();
+
+    let mut uses = local_uses(lines);
+    loop {
+        let mut changed = false;
+        let mut i = 0;
+        while i < instrs.len() {
+            if let Some((removed, replacement)) = rewrite_at(&instrs[i..], &uses) {
+                let indent = instrs[i].0.clone();
+                for (_, instr) in &instrs[i..i + removed] {
+                    if let Instr::LocalGet(name) | Instr::LocalSet(name) = instr {
+                        if let Some(count) = uses.get_mut(name) {
+                            *count -= 1;
+                        }
+                    }
+                }
+                if let Some(Instr::LocalTee(name)) = &replacement {
+                    *uses.entry(name.clone()).or_insert(0) += 1;
+                }
+                instrs.splice(i..i + removed, replacement.map(|instr| (indent, instr)));
+                changed = true;
+                // Step back so a fold can enable one on the previous window.
+                i = i.saturating_sub(2);
+                continue;
+            }
+            i += 1;
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    instrs
+        .into_iter()
+        .map(|(indent, instr)| instr.render(&indent))
+        .collect()
+}
+
+/// Returns how many instructions at the start of `window` to replace, and
+/// the instruction replacing them (if any).
+fn rewrite_at(
+    window: &[(String, Instr)],
+    uses: &HashMap<String, usize>,
+) -> Option<(usize, Option<Instr>)> {
+    let first = &window.first()?.1;
+    let second = &window.get(1)?.1;
+    match (first, second, window.get(2).map(|(_, instr)| instr)) {
+        (
+            Instr::Const(left_ty, left),
+            Instr::Const(right_ty, right),
+            Some(Instr::Binary(op_ty, op)),
+        ) if left_ty == right_ty && right_ty == op_ty => Some((
+            3,
+            Some(Instr::Const(*op_ty, fold(*op_ty, *op, *left, *right))),
+        )),
+        (Instr::Const(ty, 0), Instr::Binary(op_ty, BinOp::Add | BinOp::Sub), _)
+        | (Instr::Const(ty, 1), Instr::Binary(op_ty, BinOp::Mul), _)
+            if ty == op_ty =>
+        {
+            Some((2, None))
+        }
+        (Instr::LocalSet(set), Instr::LocalGet(get), _) if set == get => {
+            if uses.get(set).copied() == Some(2) {
+                Some((2, None))
+            } else {
+                Some((2, Some(Instr::LocalTee(set.clone()))))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Counts every `$name` reference in a body, including those inside lines
+/// the IR keeps verbatim, so a local is only treated as single-use when
+/// nothing else can observe it.
+fn local_uses(lines: &[&str]) -> HashMap<String, usize> {
+    let mut uses = HashMap::new();
+    for line in lines {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("(local ") {
+            continue;
+        }
+        for token in trimmed.split(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+            if token.starts_with('$') {
+                *uses.entry(token.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+    uses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn optimize_lines(body: &[&str]) -> Vec<String> {
+        optimize_body(body)
+            .into_iter()
+            .map(|line| line.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn folds_nested_constant_arithmetic() {
+        assert_eq!(
+            optimize_lines(&[
+                "    i32.const 2",
+                "    i32.const 3",
+                "    i32.mul",
+                "    i32.const 4",
+                "    i32.add",
+            ]),
+            vec!["i32.const 10"]
+        );
+        assert_eq!(
+            optimize_lines(&["    i32.const 2147483647", "    i32.const 1", "    i32.add"]),
+            vec!["i32.const -2147483648"]
+        );
+    }
+
+    #[test]
+    fn keeps_locals_with_other_uses_as_tee() {
+        assert_eq!(
+            optimize_lines(&[
+                "    local.get $a",
+                "    local.set $x",
+                "    local.get $x",
+                "    local.get $x",
+                "    i32.mul",
+            ]),
+            vec!["local.get $a", "local.tee $x", "local.get $x", "i32.mul"]
+        );
+    }
+
+    #[test]
+    fn leaves_mixed_width_and_unknown_lines_alone() {
+        let body = [
+            "    i32.const 1",
+            "    i64.const 2",
+            "    i64.add",
+            "    (if (result i32)",
+            "    local.set $x",
+            "    call $f",
+            "    local.get $x",
+        ];
+        assert_eq!(
+            optimize_lines(&body),
+            body.iter()
+                .map(|line| line.trim().to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -10,7 +10,7 @@ use crate::type_checker::{format_typed_type, TypeChecker, TypedType};
 use std::error::Error;
 use std::fmt;
 
-const SCALAR_EXPORT_TYPES: &str = "Int32, Int64, Float64, Boolean, Char, and ()";
+const SCALAR_EXPORT_TYPES: &str = "Int32, Int64, Float64, Decimal, Boolean, Char, and ()";
 const TAT_RELEASE_GATE_MESSAGE: &str =
     "Temporal Affine Types (TAT) are outside the default v0.0.1 release gate";
 
@@ -210,7 +210,7 @@ fn is_scalar_source_type(ty: &Type) -> bool {
         Type::Named(name)
             if matches!(
                 name.as_str(),
-                "Int32" | "Int64" | "Float64" | "Boolean" | "Char" | "Unit"
+                "Int32" | "Int64" | "Float64" | "Decimal" | "Boolean" | "Char" | "Unit"
             )
     )
 }
@@ -221,6 +221,7 @@ fn is_scalar_checked_type(ty: &TypedType) -> bool {
         TypedType::Int32
             | TypedType::Int64
             | TypedType::Float64
+            | TypedType::Decimal
             | TypedType::Boolean
             | TypedType::Char
             | TypedType::Unit
@@ -231,13 +232,14 @@ fn is_scalar_literal_constant(expr: &Expr) -> bool {
     match &expr.kind {
         ExprKind::IntLit(_)
         | ExprKind::FloatLit(_)
+        | ExprKind::DecimalLit(_)
         | ExprKind::BoolLit(_)
         | ExprKind::CharLit(_)
         | ExprKind::Unit => true,
         ExprKind::Unary(unary) if matches!(unary.op, UnaryOp::Neg) => {
             matches!(
                 &unary.expr.kind,
-                ExprKind::IntLit(_) | ExprKind::FloatLit(_)
+                ExprKind::IntLit(_) | ExprKind::FloatLit(_) | ExprKind::DecimalLit(_)
             )
         }
         _ => false,
@@ -343,6 +345,7 @@ fn reject_tat_type(context: &str, ty: &Type) -> Result<(), ReleaseSurfaceError>
             }
             reject_tat_type(context, return_type)
         }
+        Type::Refined(base, _) => reject_tat_type(context, base),
         Type::Named(_) => Ok(()),
     }
 }
@@ -352,7 +355,8 @@ fn reject_tat_block(block: &BlockExpr) -> Result<(), ReleaseSurfaceError> {
         match stmt {
             Stmt::Binding(binding) => reject_tat_binding(binding)?,
             Stmt::Assignment(assign) => reject_tat_expr(&assign.value)?,
-            Stmt::Expr(expr) => reject_tat_expr(expr)?,
+            Stmt::Expr(expr) | Stmt::Break(Some(expr)) => reject_tat_expr(expr)?,
+            Stmt::Break(None) | Stmt::Continue => {}
         }
     }
     if let Some(expr) = &block.expr {
@@ -387,6 +391,10 @@ fn reject_tat_expr(expr: &Expr) -> Result<(), ReleaseSurfaceError> {
             Ok(())
         }
         ExprKind::FieldAccess(base, _) => reject_tat_expr(base),
+        ExprKind::FieldUpdate(update) => {
+            reject_tat_expr(&update.object)?;
+            reject_tat_expr(&update.value)
+        }
         ExprKind::RecordLit(record) => reject_tat_record_lit(record),
         ExprKind::Clone(clone_expr) => {
             reject_tat_expr(&clone_expr.base)?;
@@ -397,6 +405,7 @@ fn reject_tat_expr(expr: &Expr) -> Result<(), ReleaseSurfaceError> {
         | ExprKind::Some(inner)
         | ExprKind::Ok(inner)
         | ExprKind::Err(inner)
+        | ExprKind::Try(inner)
         | ExprKind::Await(inner)
         | ExprKind::Spawn(inner) => reject_tat_expr(inner),
         ExprKind::ListLit(elements) | ExprKind::ArrayLit(elements) => {
@@ -412,6 +421,9 @@ fn reject_tat_expr(expr: &Expr) -> Result<(), ReleaseSurfaceError> {
         ExprKind::Match(match_expr) => {
             reject_tat_expr(&match_expr.expr)?;
             for arm in &match_expr.arms {
+                if let Some(guard) = &arm.guard {
+                    reject_tat_expr(guard)?;
+                }
                 reject_tat_block(&arm.body)?;
             }
             Ok(())
@@ -432,7 +444,11 @@ fn reject_tat_expr(expr: &Expr) -> Result<(), ReleaseSurfaceError> {
             reject_tat_expr(&while_expr.condition)?;
             reject_tat_block(&while_expr.body)
         }
-        ExprKind::Block(block) => reject_tat_block(block),
+        ExprKind::WhileLet(while_let) => {
+            reject_tat_expr(&while_let.expr)?;
+            reject_tat_block(&while_let.body)
+        }
+        ExprKind::Loop(block) | ExprKind::Block(block) => reject_tat_block(block),
         ExprKind::Lambda(lambda) => {
             for param in &lambda.params {
                 if let Some(annotation) = &param.type_annotation {
@@ -453,6 +469,7 @@ fn reject_tat_expr(expr: &Expr) -> Result<(), ReleaseSurfaceError> {
         ))),
         ExprKind::IntLit(_)
         | ExprKind::FloatLit(_)
+        | ExprKind::DecimalLit(_)
         | ExprKind::StringLit(_)
         | ExprKind::CharLit(_)
         | ExprKind::BoolLit(_)
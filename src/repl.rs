@@ -0,0 +1,239 @@
+//! Interactive read-eval-print loop core.
+//!
+//! [`Repl`] accepts source one line at a time, buffers entries whose
+//! braces, parentheses, or brackets are still open, and type checks each
+//! complete entry against a persistent [`TypeChecker`], so earlier `val`
+//! and `fun` declarations stay in scope. Entries are only checked; nothing
+//! is compiled or run. The terminal loop lives in the CLI binary.
+
+use crate::ast::{Pattern, Program, TopDecl};
+use crate::diagnostics::format_parse_error;
+use crate::parser::{parse_expression, parse_program};
+use crate::type_checker::{format_typed_type, TypeChecker};
+
+/// REPL state: the type checker shared by every entry and the lines of an
+/// entry that is still open.
+pub struct Repl {
+    checker: TypeChecker,
+    pending: String,
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self {
+            checker: TypeChecker::new(),
+            pending: String::new(),
+        }
+    }
+
+    /// Whether the previous lines left an entry open, so the next line
+    /// continues it.
+    pub fn is_continuing(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Feed one line of input.
+    ///
+    /// Returns `None` while the entry is incomplete, and otherwise the text
+    /// to print for the finished entry: the type of each expression or
+    /// binding, or the error that rejected it. A rejected entry leaves the
+    /// session as it was before the entry.
+    pub fn feed_line(&mut self, line: &str) -> Option<String> {
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        if open_delimiters(&self.pending) > 0 {
+            return None;
+        }
+
+        let entry = std::mem::take(&mut self.pending);
+        if entry.trim().is_empty() {
+            return Some(String::new());
+        }
+        Some(self.eval(&entry))
+    }
+
+    fn eval(&mut self, entry: &str) -> String {
+        let snapshot = self.checker.clone();
+        let result = match parse_program(entry) {
+            Ok((rest, program)) if rest.trim().is_empty() => self.eval_program(&program),
+            program_result => match parse_expression(entry) {
+                Ok((rest, expr)) if rest.trim().is_empty() => self
+                    .checker
+                    .check_expression(&expr)
+                    .map(|ty| format_typed_type(&ty))
+                    .map_err(|e| format!("Type error: {}", e)),
+                _ => Err(match program_result {
+                    Ok((rest, _)) => format!(
+                        "Parse error: unexpected input near `{}`",
+                        rest.trim().lines().next().unwrap_or_default()
+                    ),
+                    Err(e) => format_parse_error(entry, e),
+                }),
+            },
+        };
+
+        match result {
+            Ok(output) => output,
+            Err(message) => {
+                self.checker = snapshot;
+                message
+            }
+        }
+    }
+
+    fn eval_program(&mut self, program: &Program) -> Result<String, String> {
+        if !program.imports.is_empty() {
+            return Err("imports are not supported in the REPL".to_string());
+        }
+
+        let mut lines = Vec::new();
+        for decl in &program.declarations {
+            self.checker
+                .check_decl(decl)
+                .map_err(|e| format!("Type error: {}", e))?;
+            lines.push(self.describe_decl(decl));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn describe_decl(&self, decl: &TopDecl) -> String {
+        match decl {
+            TopDecl::Binding(binding) => match &binding.pattern {
+                Pattern::Ident(name) => match self.checker.checked_variable_type(name) {
+                    Some(ty) => format!("{}: {}", name, format_typed_type(&ty)),
+                    None => name.clone(),
+                },
+                _ => "val".to_string(),
+            },
+            TopDecl::Function(func) => match self.checker.checked_function_signature(&func.name) {
+                Some(sig) => {
+                    let params = sig
+                        .params
+                        .iter()
+                        .map(|(_, ty)| format_typed_type(ty))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!(
+                        "fun {}: ({}) -> {}",
+                        func.name,
+                        params,
+                        format_typed_type(&sig.return_type)
+                    )
+                }
+                None => format!("fun {}", func.name),
+            },
+            TopDecl::Record(record) => format!("record {}", record.name),
+            TopDecl::Impl(impl_block) => format!("impl {}", impl_block.target),
+            TopDecl::Context(context) => format!("context {}", context.name),
+            TopDecl::Export(export) => self.describe_decl(&export.item),
+        }
+    }
+}
+
+/// How many `{`, `(`, and `[` remain unclosed, ignoring string and
+/// character literals and `//` comments.
+fn open_delimiters(source: &str) -> i32 {
+    let mut depth = 0;
+    let mut chars = source.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            '"' | '\'' => {
+                while let Some(inner) = chars.next() {
+                    match inner {
+                        '\\' => {
+                            chars.next();
+                        }
+                        _ if inner == ch => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for inner in chars.by_ref() {
+                    if inner == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_all(repl: &mut Repl, lines: &[&str]) -> Vec<String> {
+        lines
+            .iter()
+            .filter_map(|line| repl.feed_line(line))
+            .collect()
+    }
+
+    #[test]
+    fn prints_types_and_keeps_declarations_in_scope() {
+        let mut repl = Repl::new();
+        let outputs = feed_all(
+            &mut repl,
+            &[
+                "val base = 40",
+                "fun bump: (n: Int32) -> Int32 = {",
+                "    n + 2",
+                "}",
+                "(base) bump",
+                "\"done\"",
+            ],
+        );
+
+        assert_eq!(
+            outputs,
+            vec![
+                "base: Int32",
+                "fun bump: (Int32) -> Int32",
+                "Int32",
+                "String"
+            ]
+        );
+    }
+
+    #[test]
+    fn multi_line_entries_wait_for_balanced_braces() {
+        let mut repl = Repl::new();
+        assert_eq!(repl.feed_line("fun greet: () -> String = {"), None);
+        assert!(repl.is_continuing());
+        assert_eq!(repl.feed_line("    \"}\" // {"), None);
+        assert_eq!(
+            repl.feed_line("}").as_deref(),
+            Some("fun greet: () -> String")
+        );
+        assert!(!repl.is_continuing());
+    }
+
+    #[test]
+    fn errors_are_reported_and_the_session_continues() {
+        let mut repl = Repl::new();
+        let outputs = feed_all(
+            &mut repl,
+            &["missing_name", "val flag = true", "flag + 1", "1 + 2"],
+        );
+
+        assert!(
+            outputs[0].contains("Undefined variable: missing_name"),
+            "{:?}",
+            outputs
+        );
+        assert_eq!(outputs[1], "flag: Boolean");
+        assert!(outputs[2].starts_with("Type error:"), "{:?}", outputs);
+        assert_eq!(outputs[3], "Int32");
+    }
+}
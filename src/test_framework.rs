@@ -1 +1,596 @@
-// Test framework module
+//! Property-based testing support.
+//!
+//! A [`Gen`] produces random values of one type and knows how to shrink a
+//! value toward simpler candidates. [`check_property`] runs a property
+//! against many generated inputs and, when one fails, shrinks it to a minimal
+//! counterexample.
+//!
+//! [`Value`] and [`Gen::for_type`] bridge generators to Restrict source:
+//! generated values render as Restrict literals, which is how `warder test`
+//! feeds inputs to `prop_*` functions.
+
+use crate::ast::{Program, TopDecl, Type};
+use std::fmt;
+use std::rc::Rc;
+
+/// Number of generated inputs a property is checked against by default.
+pub const DEFAULT_ITERATIONS: usize = 100;
+
+/// Seed used by [`check_property`], so failures reproduce across runs.
+pub const DEFAULT_SEED: u64 = 0x5EED_7E57;
+
+/// Largest size hint passed to generators. Sizes grow linearly over the run
+/// so early inputs are small.
+const MAX_SIZE: usize = 100;
+
+/// Upper bound on accepted shrink steps, in case a shrinker never converges.
+const MAX_SHRINK_STEPS: usize = 1000;
+
+/// Deterministic SplitMix64 generator.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `low..=high`.
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        let span = (high - low) as u64 + 1;
+        low + (self.next_u64() % span) as i64
+    }
+}
+
+type GenerateFn<T> = Rc<dyn Fn(&mut Rng, usize) -> T>;
+type ShrinkFn<T> = Rc<dyn Fn(&T) -> Vec<T>>;
+
+/// A generator of random `T` values together with a shrinker.
+pub struct Gen<T> {
+    generate: GenerateFn<T>,
+    shrink: ShrinkFn<T>,
+}
+
+impl<T> Clone for Gen<T> {
+    fn clone(&self) -> Self {
+        Self {
+            generate: Rc::clone(&self.generate),
+            shrink: Rc::clone(&self.shrink),
+        }
+    }
+}
+
+impl<T: 'static> Gen<T> {
+    /// Build a generator from a `(rng, size)` producer and a shrinker that
+    /// returns simpler candidates, simplest first.
+    pub fn new(
+        generate: impl Fn(&mut Rng, usize) -> T + 'static,
+        shrink: impl Fn(&T) -> Vec<T> + 'static,
+    ) -> Self {
+        Self {
+            generate: Rc::new(generate),
+            shrink: Rc::new(shrink),
+        }
+    }
+
+    pub fn generate(&self, rng: &mut Rng, size: usize) -> T {
+        (self.generate)(rng, size)
+    }
+
+    pub fn shrink(&self, value: &T) -> Vec<T> {
+        (self.shrink)(value)
+    }
+
+    /// Convert generated values with `forward`. `back` recovers the original
+    /// value so shrinking still happens in terms of `T`.
+    pub fn map<U: 'static>(
+        self,
+        forward: impl Fn(T) -> U + 'static,
+        back: impl Fn(&U) -> Option<T> + 'static,
+    ) -> Gen<U> {
+        let forward = Rc::new(forward);
+        let shrink_forward = Rc::clone(&forward);
+        let Gen { generate, shrink } = self;
+        Gen::new(
+            move |rng, size| forward(generate(rng, size)),
+            move |value| match back(value) {
+                Some(original) => shrink(&original)
+                    .into_iter()
+                    .map(|candidate| shrink_forward(candidate))
+                    .collect(),
+                None => Vec::new(),
+            },
+        )
+    }
+}
+
+impl Gen<i32> {
+    /// Integers in `-size..=size`, shrinking toward zero.
+    pub fn int32() -> Self {
+        Gen::new(
+            |rng, size| rng.range(-(size as i64), size as i64) as i32,
+            |&value| {
+                let mut candidates = Vec::new();
+                if value != 0 {
+                    candidates.push(0);
+                }
+                for candidate in [value / 2, value - value.signum()] {
+                    if candidate != value && !candidates.contains(&candidate) {
+                        candidates.push(candidate);
+                    }
+                }
+                candidates
+            },
+        )
+    }
+}
+
+impl Gen<f64> {
+    /// Hundredths in `-size..=size`, shrinking toward zero and whole numbers.
+    pub fn float64() -> Self {
+        Gen::new(
+            |rng, size| {
+                let bound = size as i64 * 100;
+                rng.range(-bound, bound) as f64 / 100.0
+            },
+            |&value| {
+                let mut candidates = Vec::new();
+                for candidate in [0.0, value.trunc(), (value / 2.0).trunc()] {
+                    if candidate != value && !candidates.contains(&candidate) {
+                        candidates.push(candidate);
+                    }
+                }
+                if value.abs() >= 1.0 && value.fract() == 0.0 {
+                    candidates.push(value - value.signum());
+                }
+                candidates
+            },
+        )
+    }
+}
+
+impl Gen<bool> {
+    /// Either boolean, shrinking `true` to `false`.
+    pub fn boolean() -> Self {
+        Gen::new(
+            |rng, _| rng.next_u64() % 2 == 0,
+            |&value| if value { vec![false] } else { Vec::new() },
+        )
+    }
+}
+
+impl Gen<String> {
+    /// Alphanumeric strings of up to `size` characters.
+    pub fn string() -> Self {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 ";
+        Gen::new(
+            |rng, size| {
+                let len = rng.range(0, size as i64) as usize;
+                (0..len)
+                    .map(|_| ALPHABET[rng.range(0, ALPHABET.len() as i64 - 1) as usize] as char)
+                    .collect()
+            },
+            |value: &String| {
+                let chars: Vec<char> = value.chars().collect();
+                shrink_sequence(&chars, |&c| if c == 'a' { Vec::new() } else { vec!['a'] })
+                    .into_iter()
+                    .map(|chars| chars.into_iter().collect())
+                    .collect()
+            },
+        )
+    }
+}
+
+impl<T: Clone + 'static> Gen<Vec<T>> {
+    /// Lists of up to `size` elements drawn from `element`.
+    pub fn list(element: Gen<T>) -> Self {
+        let shrink_element = element.clone();
+        Gen::new(
+            move |rng, size| {
+                let len = rng.range(0, size as i64) as usize;
+                (0..len)
+                    .map(|_| element.generate(rng, size))
+                    .collect::<Vec<_>>()
+            },
+            move |value: &Vec<T>| shrink_sequence(value, |item| shrink_element.shrink(item)),
+        )
+    }
+
+    /// Fixed-length sequences with one generator per position, such as the
+    /// parameter list of a property.
+    pub fn tuple(elements: Vec<Gen<T>>) -> Self {
+        let shrink_elements = elements.clone();
+        Gen::new(
+            move |rng, size| {
+                elements
+                    .iter()
+                    .map(|g| g.generate(rng, size))
+                    .collect::<Vec<_>>()
+            },
+            move |value: &Vec<T>| {
+                let mut candidates = Vec::new();
+                for (i, element) in shrink_elements.iter().enumerate() {
+                    for item in element.shrink(&value[i]) {
+                        let mut candidate = value.clone();
+                        candidate[i] = item;
+                        candidates.push(candidate);
+                    }
+                }
+                candidates
+            },
+        )
+    }
+}
+
+/// Shrink candidates for a sequence: empty, each half, each element removed,
+/// then each element shrunk in place.
+fn shrink_sequence<T: Clone>(items: &[T], shrink_item: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let mut candidates = Vec::new();
+    if items.is_empty() {
+        return candidates;
+    }
+
+    candidates.push(Vec::new());
+    if items.len() > 2 {
+        let mid = items.len() / 2;
+        candidates.push(items[..mid].to_vec());
+        candidates.push(items[mid..].to_vec());
+    }
+    if items.len() > 1 {
+        for i in 0..items.len() {
+            let mut candidate = items.to_vec();
+            candidate.remove(i);
+            candidates.push(candidate);
+        }
+    }
+    for (i, item) in items.iter().enumerate() {
+        for shrunk in shrink_item(item) {
+            let mut candidate = items.to_vec();
+            candidate[i] = shrunk;
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// A generated Restrict value. `Display` renders it as a source literal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int32(i32),
+    Float64(f64),
+    Boolean(bool),
+    String(String),
+    List(Vec<Value>),
+    Record {
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int32(n) => write!(f, "{}", n),
+            Value::Float64(x) => write!(f, "{:?}", x),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::String(s) => {
+                write!(f, "\"")?;
+                for c in s.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        _ => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Record { name, fields } => {
+                write!(f, "{} {{ ", name)?;
+                for (i, (field, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", field, value)?;
+                }
+                write!(f, " }}")
+            }
+        }
+    }
+}
+
+impl Gen<Value> {
+    /// Records named `name`, deriving each field from its own generator.
+    pub fn record(name: impl Into<String>, fields: Vec<(String, Gen<Value>)>) -> Self {
+        let name = name.into();
+        let (field_names, field_gens): (Vec<String>, Vec<Gen<Value>>) = fields.into_iter().unzip();
+        Gen::tuple(field_gens).map(
+            move |values| Value::Record {
+                name: name.clone(),
+                fields: field_names.iter().cloned().zip(values).collect(),
+            },
+            |value| match value {
+                Value::Record { fields, .. } => {
+                    Some(fields.iter().map(|(_, value)| value.clone()).collect())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    /// Generator for a Restrict type, resolving record names against the
+    /// records declared in `program`.
+    pub fn for_type(ty: &Type, program: &Program) -> Result<Self, String> {
+        Self::for_type_in(ty, program, &mut Vec::new())
+    }
+
+    fn for_type_in(
+        ty: &Type,
+        program: &Program,
+        visiting: &mut Vec<String>,
+    ) -> Result<Self, String> {
+        match ty {
+            Type::Named(name) => match name.as_str() {
+                "Int32" => Ok(Gen::int32().map(Value::Int32, |value| match value {
+                    Value::Int32(n) => Some(*n),
+                    _ => None,
+                })),
+                "Float64" => Ok(Gen::float64().map(Value::Float64, |value| match value {
+                    Value::Float64(x) => Some(*x),
+                    _ => None,
+                })),
+                "Boolean" => Ok(Gen::boolean().map(Value::Boolean, |value| match value {
+                    Value::Boolean(b) => Some(*b),
+                    _ => None,
+                })),
+                "String" => Ok(Gen::string().map(Value::String, |value| match value {
+                    Value::String(s) => Some(s.clone()),
+                    _ => None,
+                })),
+                _ => Self::record_for(name, program, visiting),
+            },
+            Type::Generic(name, args) if name == "List" && args.len() == 1 => {
+                let element = Self::for_type_in(&args[0], program, visiting)?;
+                Ok(Gen::list(element).map(Value::List, |value| match value {
+                    Value::List(items) => Some(items.clone()),
+                    _ => None,
+                }))
+            }
+            _ => Err(format!("no generator for type {}", ty)),
+        }
+    }
+
+    fn record_for(
+        name: &str,
+        program: &Program,
+        visiting: &mut Vec<String>,
+    ) -> Result<Self, String> {
+        let record = program
+            .declarations
+            .iter()
+            .find_map(|decl| match decl {
+                TopDecl::Record(record) if record.name == name => Some(record),
+                _ => None,
+            })
+            .ok_or_else(|| format!("no generator for type {}", name))?;
+
+        if !record.type_params.is_empty() {
+            return Err(format!("no generator for generic record {}", name));
+        }
+        if visiting.iter().any(|visited| visited == name) {
+            return Err(format!("no generator for recursive record {}", name));
+        }
+
+        visiting.push(name.to_string());
+        let fields = record
+            .fields
+            .iter()
+            .map(|field| {
+                Self::for_type_in(&field.ty, program, visiting).map(|gen| (field.name.clone(), gen))
+            })
+            .collect::<Result<Vec<_>, _>>();
+        visiting.pop();
+
+        Ok(Gen::record(name, fields?))
+    }
+}
+
+/// Outcome of [`check_property`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyResult<T> {
+    Passed { iterations: usize },
+    Failed(Counterexample<T>),
+}
+
+/// A failing input, before and after shrinking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counterexample<T> {
+    /// The generated input that first failed.
+    pub original: T,
+    /// The smallest failing input shrinking found.
+    pub shrunk: T,
+    /// The property's failure message for `shrunk`.
+    pub message: String,
+    /// Number of inputs checked before the first failure, inclusive.
+    pub iterations: usize,
+    pub shrink_steps: usize,
+}
+
+/// Check `property` against `iterations` inputs from `gen`, shrinking the
+/// first failing input. The property returns `Err` with a message to fail.
+pub fn check_property<T: Clone + 'static>(
+    gen: &Gen<T>,
+    property: impl FnMut(&T) -> Result<(), String>,
+    iterations: usize,
+) -> PropertyResult<T> {
+    check_property_with_seed(gen, property, iterations, DEFAULT_SEED)
+}
+
+pub fn check_property_with_seed<T: Clone + 'static>(
+    gen: &Gen<T>,
+    mut property: impl FnMut(&T) -> Result<(), String>,
+    iterations: usize,
+    seed: u64,
+) -> PropertyResult<T> {
+    let mut rng = Rng::new(seed);
+    for i in 0..iterations {
+        let size = ((i + 1) * MAX_SIZE / iterations).max(1);
+        let input = gen.generate(&mut rng, size);
+        if let Err(message) = property(&input) {
+            return PropertyResult::Failed(shrink_failure(
+                gen,
+                &mut property,
+                input,
+                message,
+                i + 1,
+            ));
+        }
+    }
+    PropertyResult::Passed { iterations }
+}
+
+/// Greedily move to the first shrink candidate that still fails until no
+/// candidate does.
+fn shrink_failure<T: Clone + 'static>(
+    gen: &Gen<T>,
+    property: &mut impl FnMut(&T) -> Result<(), String>,
+    original: T,
+    message: String,
+    iterations: usize,
+) -> Counterexample<T> {
+    let mut shrunk = original.clone();
+    let mut message = message;
+    let mut shrink_steps = 0;
+
+    'shrinking: while shrink_steps < MAX_SHRINK_STEPS {
+        for candidate in gen.shrink(&shrunk) {
+            if let Err(candidate_message) = property(&candidate) {
+                shrunk = candidate;
+                message = candidate_message;
+                shrink_steps += 1;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+
+    Counterexample {
+        original,
+        shrunk,
+        message,
+        iterations,
+        shrink_steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_program;
+
+    #[test]
+    fn reverse_twice_is_identity() {
+        let gen = Gen::list(Gen::int32());
+        let result = check_property(
+            &gen,
+            |xs| {
+                let mut twice = xs.clone();
+                twice.reverse();
+                twice.reverse();
+                if &twice == xs {
+                    Ok(())
+                } else {
+                    Err(format!("{:?} reversed twice is {:?}", xs, twice))
+                }
+            },
+            DEFAULT_ITERATIONS,
+        );
+        assert_eq!(
+            result,
+            PropertyResult::Passed {
+                iterations: DEFAULT_ITERATIONS
+            }
+        );
+    }
+
+    #[test]
+    fn failing_list_property_shrinks_to_minimal_counterexample() {
+        let gen = Gen::list(Gen::int32());
+        let result = check_property(
+            &gen,
+            |xs| {
+                if xs.len() < 3 {
+                    Ok(())
+                } else {
+                    Err("list too long".to_string())
+                }
+            },
+            DEFAULT_ITERATIONS,
+        );
+        let PropertyResult::Failed(counterexample) = result else {
+            panic!("property should fail");
+        };
+        assert_eq!(counterexample.shrunk, vec![0, 0, 0]);
+        assert_eq!(counterexample.message, "list too long");
+    }
+
+    #[test]
+    fn failing_int_property_shrinks_to_boundary() {
+        let result = check_property(
+            &Gen::int32(),
+            |&n| if n < 10 { Ok(()) } else { Err(n.to_string()) },
+            DEFAULT_ITERATIONS,
+        );
+        let PropertyResult::Failed(counterexample) = result else {
+            panic!("property should fail");
+        };
+        assert_eq!(counterexample.shrunk, 10);
+    }
+
+    #[test]
+    fn record_generator_derives_fields_and_renders_source() {
+        let (_, program) =
+            parse_program("record Point {\n    x: Int32,\n    label: String\n}\n").unwrap();
+        let ty = Type::Generic("List".to_string(), vec![Type::Named("Point".to_string())]);
+        let gen = Gen::for_type(&ty, &program).unwrap();
+
+        let mut rng = Rng::new(DEFAULT_SEED);
+        let Value::List(points) = gen.generate(&mut rng, 5) else {
+            panic!("expected a list");
+        };
+        assert!(points.iter().all(|point| matches!(
+            point,
+            Value::Record { name, fields } if name == "Point" && fields.len() == 2
+        )));
+
+        let point = Value::Record {
+            name: "Point".to_string(),
+            fields: vec![
+                ("x".to_string(), Value::Int32(-3)),
+                ("label".to_string(), Value::String("a\"b".to_string())),
+            ],
+        };
+        assert_eq!(point.to_string(), "Point { x: -3, label: \"a\\\"b\" }");
+        assert!(Gen::for_type(&Type::Named("Missing".to_string()), &program).is_err());
+    }
+}
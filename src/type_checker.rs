@@ -38,7 +38,11 @@
 //! ```
 
 use crate::ast::*;
+use crate::const_eval::{eval_int_binary, ConstOverflow, IntWidth};
+use crate::diagnostics::line_column;
+use crate::foreign::ForeignFunction;
 use crate::lifetime_inference::LifetimeInference;
+use crate::module::{ModuleResolver, NotExportedError};
 use crate::type_constraints::{
     finalize_type, fresh_type_param_map, solve_constraints_partial_with_forms_and_initial,
     solve_constraints_with_forms_and_initial, substitute_type_params, unify as unify_constraint,
@@ -47,6 +51,7 @@ use crate::type_constraints::{
 };
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::Arc;
 
 /// Type checking errors.
 ///
@@ -54,8 +59,11 @@ use std::fmt;
 /// about type system violations.
 #[derive(Debug, PartialEq)]
 pub enum TypeError {
-    /// Variable not found in scope
-    UndefinedVariable(String),
+    /// Variable not found in scope, with the closest in-scope name if any
+    UndefinedVariable {
+        name: String,
+        suggestion: Option<String>,
+    },
 
     /// Type mismatch between expected and actual
     TypeMismatch {
@@ -66,16 +74,26 @@ pub enum TypeError {
     /// Attempt to use a value that has already been consumed
     AffineViolation(String),
 
+    /// Match guard that consumes an affine value
+    GuardConsumesAffineValue(String),
+
     /// Attempt to mutate an immutable binding
     ImmutableReassignment(String),
 
-    /// Type name not found
-    UnknownType(String),
+    /// `val` that redefines an immutable binding of the same scope
+    Redefinition(String),
+
+    /// Type name not found, with the closest record name if any
+    UnknownType {
+        name: String,
+        suggestion: Option<String>,
+    },
 
-    /// Field not found in record
+    /// Field not found in record, with the closest field name if any
     UnknownField {
         record: String,
         field: String,
+        suggestion: Option<String>,
     },
 
     /// Required field missing from record literal
@@ -90,16 +108,44 @@ pub enum TypeError {
     /// Attempt to freeze an already frozen record
     FreezeAlreadyFrozen,
 
+    /// Attempt to freeze a record that a `mut val` binding can still update
+    FreezeMutableBinding(String),
+
+    /// Attempt to update a field reached from an immutable binding
+    ImmutableFieldUpdate(String),
+
+    /// Integer value not provably within a range-restricted type
+    RefinementViolation {
+        expected: String,
+        found: String,
+    },
+
+    /// Range restriction on a non-integer type or with empty bounds
+    InvalidRefinement(String),
+
+    /// Constant integer arithmetic whose result does not fit its type
+    ConstantOverflow(ConstOverflow),
+
+    /// Field update or method call that could change a frozen record
+    MutationOfFrozen {
+        record: String,
+        operation: String,
+    },
+
     /// Record type not found
     UndefinedRecord(String),
 
-    /// Function not found
-    UndefinedFunction(String),
+    /// Function not found, with the closest function name if any
+    UndefinedFunction {
+        name: String,
+        suggestion: Option<String>,
+    },
 
-    /// Method not found for record type
-    UndefinedMethod {
+    /// Method not found for record type, with the closest method name if any
+    UnknownMethod {
+        record: String,
         method: String,
-        record_type: String,
+        suggestion: Option<String>,
     },
 
     /// Wrong number of function arguments
@@ -108,12 +154,26 @@ pub enum TypeError {
         found: usize,
     },
 
+    /// Wrong number of explicit type arguments at a call
+    TypeArgumentCountMismatch {
+        function: String,
+        expected: usize,
+        found: usize,
+    },
+
     /// Context not available in current scope
     UnavailableContext(String),
 
     /// Heap-backed value escapes an arena scope
     ArenaEscape(String),
 
+    /// Function value escapes the capability context it was created in
+    CapabilityEscape(String),
+
+    /// Field update through a binding whose record may also be reachable
+    /// from a `mut val` collection that is still readable
+    SharedFieldUpdate(String),
+
     /// Feature not yet implemented
     UnsupportedFeature(String),
 
@@ -125,6 +185,16 @@ pub enum TypeError {
 
     DerivationTooDeep(usize),
 
+    /// Prototype chain that derives from itself, listed from the first
+    /// repeated prototype back to itself
+    CyclicDerivation(Vec<String>),
+
+    /// Prototype whose parent hash matches no known prototype
+    UnknownParentPrototype {
+        record: String,
+        parent_hash: String,
+    },
+
     /// Temporal constraint violation
     TemporalConstraintViolation(String),
 
@@ -148,12 +218,98 @@ pub enum TypeError {
 
     /// Associated type projection remains unresolved after type inference
     UnresolvedProjection(String),
+
+    /// Imported module could not be loaded, parsed or linked
+    ModuleResolution(String),
+
+    /// Import of a declaration its module does not export
+    NotExported {
+        module: String,
+        name: String,
+    },
+
+    /// `@init` function that takes parameters, is generic or async, or
+    /// returns something other than `()`
+    InvalidInitFunction(String),
+
+    /// Record field default that refers to a variable from outside the
+    /// record declaration
+    ImpureFieldDefault {
+        record: String,
+        field: String,
+        variable: String,
+    },
+
+    /// `?` whose `None`/`Err` the enclosing function cannot return
+    InvalidTry {
+        operand: String,
+        required: String,
+        found: Option<String>,
+    },
+
+    /// `break` or `continue` outside a loop, or `break value` out of a loop
+    /// that has no result value
+    InvalidLoopControl(String),
+}
+
+impl TypeError {
+    /// Stable error code, explained in detail by `--explain <code>`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeError::AffineViolation(_) => "E0001",
+            TypeError::UndefinedVariable { .. } => "E0002",
+            TypeError::TypeMismatch { .. } => "E0003",
+            TypeError::GuardConsumesAffineValue(_) => "E0004",
+            TypeError::ImmutableReassignment(_) => "E0005",
+            TypeError::UnknownType { .. } => "E0006",
+            TypeError::UnknownField { .. } => "E0007",
+            TypeError::MissingField { .. } => "E0008",
+            TypeError::CloneFrozenRecord => "E0009",
+            TypeError::FreezeAlreadyFrozen => "E0010",
+            TypeError::FreezeMutableBinding(_) => "E0011",
+            TypeError::ImmutableFieldUpdate(_) => "E0012",
+            TypeError::RefinementViolation { .. } => "E0013",
+            TypeError::InvalidRefinement(_) => "E0014",
+            TypeError::ConstantOverflow(_) => "E0015",
+            TypeError::MutationOfFrozen { .. } => "E0016",
+            TypeError::UndefinedRecord(_) => "E0017",
+            TypeError::UndefinedFunction { .. } => "E0018",
+            TypeError::UnknownMethod { .. } => "E0019",
+            TypeError::ArityMismatch { .. } => "E0020",
+            TypeError::TypeArgumentCountMismatch { .. } => "E0021",
+            TypeError::UnavailableContext(_) => "E0022",
+            TypeError::ArenaEscape(_) => "E0023",
+            TypeError::UnsupportedFeature(_) => "E0024",
+            TypeError::NotDerivedFrom(_, _) => "E0025",
+            TypeError::CannotCloneSealed(_) => "E0026",
+            TypeError::DerivationTooDeep(_) => "E0027",
+            TypeError::CyclicDerivation(_) => "E0028",
+            TypeError::UnknownParentPrototype { .. } => "E0029",
+            TypeError::TemporalConstraintViolation(_) => "E0030",
+            TypeError::TemporalEscape { .. } => "E0031",
+            TypeError::InvalidTemporalConstraint(_, _) => "E0032",
+            TypeError::NonExhaustivePatterns { .. } => "E0033",
+            TypeError::CannotInferType(_) => "E0034",
+            TypeError::UnresolvedProjection(_) => "E0035",
+            TypeError::ModuleResolution(_) => "E0036",
+            TypeError::NotExported { .. } => "E0037",
+            TypeError::InvalidInitFunction(_) => "E0038",
+            TypeError::ImpureFieldDefault { .. } => "E0039",
+            TypeError::InvalidTry { .. } => "E0040",
+            TypeError::InvalidLoopControl(_) => "E0041",
+            TypeError::CapabilityEscape(_) => "E0042",
+            TypeError::SharedFieldUpdate(_) => "E0043",
+            TypeError::Redefinition(_) => "E0044",
+        }
+    }
 }
 
 impl fmt::Display for TypeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TypeError::UndefinedVariable(name) => write!(f, "Undefined variable: {name}"),
+            TypeError::UndefinedVariable { name, suggestion } => {
+                write!(f, "Undefined variable: {name}{}", did_you_mean(suggestion))
+            }
             TypeError::TypeMismatch { expected, found } => write!(
                 f,
                 "Type mismatch: expected {}, found {}",
@@ -166,30 +322,99 @@ impl fmt::Display for TypeError {
                     "Variable {name} has already been used (affine type violation)"
                 )
             }
+            TypeError::GuardConsumesAffineValue(name) => write!(
+                f,
+                "Match guard cannot consume {name}: a failed guard falls through to the next arm"
+            ),
             TypeError::ImmutableReassignment(name) => {
                 write!(f, "Cannot reassign to immutable variable {name}")
             }
-            TypeError::UnknownType(name) => write!(f, "Unknown type: {name}"),
-            TypeError::UnknownField { record, field } => {
-                write!(f, "Unknown field {field} in record {record}")
+            TypeError::Redefinition(name) => write!(
+                f,
+                "Binding {name} is already defined in this scope; use a new name, or declare the first binding `mut val`"
+            ),
+            TypeError::UnknownType { name, suggestion } => {
+                write!(f, "Unknown type: {name}{}", did_you_mean(suggestion))
             }
+            TypeError::UnknownField {
+                record,
+                field,
+                suggestion,
+            } => write!(
+                f,
+                "Unknown field {field} in record {record}{}",
+                did_you_mean(suggestion)
+            ),
             TypeError::MissingField { record, field } => {
                 write!(f, "Missing field {field} in record {record}")
             }
             TypeError::CloneFrozenRecord => write!(f, "Cannot clone a frozen record"),
             TypeError::FreezeAlreadyFrozen => write!(f, "Cannot freeze an already frozen record"),
+            TypeError::FreezeMutableBinding(name) => {
+                write!(
+                    f,
+                    "Cannot freeze a record reached from mutable variable {name}; later field updates would change the frozen value. Freeze a clone instead"
+                )
+            }
+            TypeError::RefinementViolation { expected, found } => {
+                write!(
+                    f,
+                    "Range violation: expected {expected}, found {found}; convert it with `clamp` or `checked`"
+                )
+            }
+            TypeError::InvalidRefinement(message) => {
+                write!(f, "Invalid range restriction: {message}")
+            }
+            TypeError::ConstantOverflow(overflow) => {
+                write!(
+                    f,
+                    "Constant arithmetic overflow: {overflow}; use `wrapping_add`/`wrapping_mul` if wrapping is intended"
+                )
+            }
+            TypeError::ImmutableFieldUpdate(name) => {
+                write!(
+                    f,
+                    "Cannot update a field of immutable variable {name}; declare it with `mut val`"
+                )
+            }
+            TypeError::SharedFieldUpdate(name) => {
+                write!(
+                    f,
+                    "Cannot update a field of {name}: its record may still be reachable from a `mut val` collection; build a new record with `clone` instead"
+                )
+            }
+            TypeError::MutationOfFrozen { record, operation } => {
+                write!(f, "Cannot {operation} of frozen record {record}")
+            }
             TypeError::UndefinedRecord(name) => write!(f, "Record {name} is not defined"),
-            TypeError::UndefinedFunction(name) => write!(f, "Function {name} is not defined"),
-            TypeError::UndefinedMethod {
+            TypeError::UndefinedFunction { name, suggestion } => {
+                write!(f, "Function {name} is not defined{}", did_you_mean(suggestion))
+            }
+            TypeError::UnknownMethod {
+                record,
                 method,
-                record_type,
-            } => write!(f, "Method {method} not found for record type {record_type}"),
+                suggestion,
+            } => write!(
+                f,
+                "Method {method} not found for record type {record}{}",
+                did_you_mean(suggestion)
+            ),
             TypeError::ArityMismatch { expected, found } => {
                 write!(
                     f,
                     "Wrong number of arguments: expected {expected}, found {found}"
                 )
             }
+            TypeError::TypeArgumentCountMismatch {
+                function,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "Wrong number of type arguments for {function}: expected {expected}, found {found}"
+                )
+            }
             TypeError::UnavailableContext(name) => {
                 write!(f, "Context {name} is not available in this scope")
             }
@@ -215,6 +440,16 @@ impl fmt::Display for TypeError {
             TypeError::DerivationTooDeep(depth) => {
                 write!(f, "Derivation depth too deep: {depth} > 3")
             }
+            TypeError::CyclicDerivation(cycle) => {
+                write!(f, "Cyclic prototype derivation: {}", cycle.join(" -> "))
+            }
+            TypeError::UnknownParentPrototype {
+                record,
+                parent_hash,
+            } => write!(
+                f,
+                "Record {record} derives from unknown prototype with hash {parent_hash}"
+            ),
             TypeError::TemporalConstraintViolation(message) => write!(
                 f,
                 "Temporal constraint violation: {}",
@@ -280,6 +515,48 @@ impl fmt::Display for TypeError {
                     write!(f, "Cannot infer type: {detail}")
                 }
             }
+            TypeError::ModuleResolution(message) => {
+                write!(f, "Module resolution failed: {message}")
+            }
+            TypeError::NotExported { module, name } => {
+                write!(
+                    f,
+                    "{name} is not exported from module {module}; mark it `export` to use it outside that module"
+                )
+            }
+            TypeError::InvalidInitFunction(name) => write!(
+                f,
+                "@init function {name} must be a non-generic, non-async function with no parameters returning ()"
+            ),
+            TypeError::ImpureFieldDefault {
+                record,
+                field,
+                variable,
+            } => write!(
+                f,
+                "Default for field {field} of record {record} cannot use variable {variable}; field defaults are evaluated wherever the record is built"
+            ),
+            TypeError::InvalidTry {
+                operand,
+                required,
+                found: Some(found),
+            } => write!(
+                f,
+                "`?` on {operand} needs the enclosing function to return {required}, but it returns {found}"
+            ),
+            TypeError::InvalidTry {
+                operand,
+                required,
+                found: None,
+            } => write!(
+                f,
+                "`?` on {operand} needs an enclosing function with a declared {required} return type"
+            ),
+            TypeError::InvalidLoopControl(message) => write!(f, "{message}"),
+            TypeError::CapabilityEscape(context) => write!(
+                f,
+                "Function value cannot leave `with {context}`: it could use {context} after the block ends"
+            ),
             TypeError::UnresolvedProjection(message) => {
                 let detail = sanitize_diagnostic_text(message);
                 let base = "Cannot resolve generic collection result type. Add a concrete List/Option annotation or use the generic call in a typed context";
@@ -295,6 +572,160 @@ impl fmt::Display for TypeError {
 
 impl std::error::Error for TypeError {}
 
+/// Non-fatal diagnostics collected while type checking.
+///
+/// Warnings never stop compilation; callers drain them with
+/// [`TypeChecker::take_warnings`] after checking a program.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// Division or remainder whose divisor is a literal zero, with the span
+    /// of the divisor when known
+    DivisionByZero(BinaryOp, Option<Span>),
+    /// Immutable `val` binding that nothing in its block reads
+    UnusedBinding(String),
+    /// `val` binding that hides an earlier binding of the same name, with
+    /// the span of the earlier binding's value when known
+    Shadowed(String, Option<Span>),
+    /// Match arm that can never run because an earlier unguarded arm already
+    /// matches every value it would; both positions are 1-based
+    UnreachableArm { arm: usize, covered_by: usize },
+    /// `then` or else-if condition that folds to a constant, so one of the
+    /// branches can never run
+    ConstantBranchCondition(bool),
+    /// `while` condition that folds to a constant, so the loop never runs
+    /// or never ends on its own
+    ConstantLoopCondition(bool),
+}
+
+impl Warning {
+    /// The warning as a user diagnostic, with any source position it
+    /// carries given as a 1-based line and column of `source`.
+    pub fn describe(&self, source: &str) -> String {
+        let at = |span: &Span| {
+            let (line, column) = line_column(source, span.start);
+            format!("line {line}, column {column}")
+        };
+        match self {
+            Warning::DivisionByZero(op, Some(span)) => format!(
+                "Division by zero: right operand of '{op}' at {} is a constant zero",
+                at(span)
+            ),
+            Warning::Shadowed(name, Some(span)) => format!(
+                "Binding '{name}' shadows an earlier binding at {}",
+                at(span)
+            ),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::DivisionByZero(op, _) => {
+                write!(
+                    f,
+                    "Division by zero: right operand of '{op}' is a constant zero"
+                )
+            }
+            Warning::UnusedBinding(name) => {
+                write!(
+                    f,
+                    "Unused binding '{name}': prefix it with '_' if it is intentionally unused"
+                )
+            }
+            Warning::Shadowed(name, _) => {
+                write!(f, "Binding '{name}' shadows an earlier binding")
+            }
+            Warning::UnreachableArm { arm, covered_by } => {
+                write!(
+                    f,
+                    "Unreachable match arm {arm}: arm {covered_by} already matches every value it would"
+                )
+            }
+            Warning::ConstantBranchCondition(true) => {
+                write!(
+                    f,
+                    "Condition is always true: the branches after it are dead code"
+                )
+            }
+            Warning::ConstantBranchCondition(false) => {
+                write!(f, "Condition is always false: its block is dead code")
+            }
+            Warning::ConstantLoopCondition(true) => {
+                write!(
+                    f,
+                    "Loop condition is always true: the loop is likely infinite"
+                )
+            }
+            Warning::ConstantLoopCondition(false) => {
+                write!(
+                    f,
+                    "Loop condition is always false: the loop body never runs"
+                )
+            }
+        }
+    }
+}
+
+/// Checking passes reported through [`TraceEvent::Phase`], in the order
+/// [`TypeChecker::check_program`] runs them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckPhase {
+    /// Record and context declarations are registered.
+    Declarations,
+    /// Function and impl method signatures are registered.
+    Signatures,
+    /// Impl method bodies are checked.
+    ImplBodies,
+    /// Unannotated function return types are inferred.
+    ReturnInference,
+    /// Remaining function bodies and top-level bindings are checked.
+    Bodies,
+}
+
+/// Progress events reported to the tracer installed with
+/// [`TypeChecker::set_tracer`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent {
+    /// A checking pass started
+    Phase(CheckPhase),
+    /// A function body is about to be checked
+    CheckingFunction(String),
+    /// A generic call resolved one of its type parameters
+    GenericInstantiated {
+        function: String,
+        param: String,
+        ty: TypedType,
+    },
+    /// An affine binding was consumed
+    AffineConsumed(String),
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::Phase(phase) => write!(f, "phase {:?}", phase),
+            TraceEvent::CheckingFunction(name) => write!(f, "checking function {}", name),
+            TraceEvent::GenericInstantiated {
+                function,
+                param,
+                ty,
+            } => write!(
+                f,
+                "resolved generic {}={} in {}",
+                param,
+                format_typed_type(ty),
+                function
+            ),
+            TraceEvent::AffineConsumed(name) => write!(f, "consumed {}", name),
+        }
+    }
+}
+
+/// Callback receiving [`TraceEvent`]s.
+pub type Tracer = Box<dyn Fn(TraceEvent) + Send + Sync>;
+
 fn sanitize_diagnostic_text(message: &str) -> String {
     let message = message
         .replace("InferVar", "inference variable")
@@ -364,6 +795,8 @@ pub enum TypedType {
     Int32,
     Int64,
     Float64,
+    /// Fixed-point number backed by an `i64` scaled by `DECIMAL_SCALE`
+    Decimal,
     Boolean,
     String,
     Char,
@@ -382,6 +815,7 @@ pub enum TypedType {
     Option(Box<TypedType>),
     Result(Box<TypedType>, Box<TypedType>),
     List(Box<TypedType>),
+    Map(Box<TypedType>, Box<TypedType>),
     Array(Box<TypedType>, ArrayLength),
     TypeParam(String),   // Generic type parameter
     InferVar(TypeVarId), // Inference meta-variable for A-layer and provisional signatures
@@ -402,6 +836,7 @@ pub fn format_typed_type(ty: &TypedType) -> String {
         TypedType::Int32 => "Int32".to_string(),
         TypedType::Int64 => "Int64".to_string(),
         TypedType::Float64 => "Float64".to_string(),
+        TypedType::Decimal => "Decimal".to_string(),
         TypedType::Boolean => "Boolean".to_string(),
         TypedType::String => "String".to_string(),
         TypedType::Char => "Char".to_string(),
@@ -438,6 +873,11 @@ pub fn format_typed_type(ty: &TypedType) -> String {
             format_typed_type(err)
         ),
         TypedType::List(inner) => format!("List<{}>", format_typed_type(inner)),
+        TypedType::Map(key, value) => format!(
+            "Map<{}, {}>",
+            format_typed_type(key),
+            format_typed_type(value)
+        ),
         TypedType::Array(inner, size) => {
             let size = match size {
                 ArrayLength::Known(size) => size.to_string(),
@@ -499,6 +939,120 @@ fn expected_type_mismatch(expected: impl Into<String>, found: &TypedType) -> Typ
     }
 }
 
+/// The `; did you mean ...?` tail of an undefined-name diagnostic.
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    suggestion
+        .as_ref()
+        .map(|name| format!("; did you mean `{name}`?"))
+        .unwrap_or_default()
+}
+
+/// Levenshtein distance between two names, counted in characters, with a
+/// swap of adjacent characters (`emial` for `email`) counting as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows: Vec<Vec<usize>> = vec![(0..=b.len()).collect()];
+    for i in 1..=a.len() {
+        let mut row = vec![i];
+        for j in 1..=b.len() {
+            let substitution = rows[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution.min(rows[i - 1][j] + 1).min(row[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            row.push(distance);
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+/// The candidate closest to `name` by edit distance, if it is close enough
+/// to be a likely typo: at most a third of the name's length (but at least
+/// one edit) and fewer edits than the name has characters, so `z` is not
+/// "corrected" to `x`. Ties go to the alphabetically first candidate so
+/// diagnostics are stable.
+fn closest_name<I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let length = name.chars().count();
+    let threshold = (length / 3).max(1);
+    candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let candidate = candidate.as_ref();
+            // Compiler-generated names such as `__closure_0` are not suggested
+            if candidate == name || candidate.starts_with("__") {
+                return None;
+            }
+            let distance = edit_distance(name, candidate);
+            (distance <= threshold && distance < length).then(|| (distance, candidate.to_string()))
+        })
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// 64-bit FNV-1a over each part followed by a `0xff` separator, as 16 hex
+/// digits. Unlike `DefaultHasher` the result is fixed across runs and Rust
+/// releases, so prototype hashes are reproducible.
+fn stable_hash<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = parts
+        .into_iter()
+        .flat_map(|part| part.bytes().chain(std::iter::once(0xff)))
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        });
+    format!("{:016x}", hash)
+}
+
+/// Inclusive bounds of the integers an Int literal or range pattern matches.
+fn int_pattern_bounds(pattern: &Pattern) -> Option<(i64, i64)> {
+    match pattern {
+        Pattern::Literal(Literal::Int(value)) => Some((*value, *value)),
+        Pattern::Range {
+            start,
+            end,
+            inclusive,
+        } => Some((*start, if *inclusive { *end } else { end - 1 })),
+        _ => None,
+    }
+}
+
+/// The record name and field patterns of either record pattern form.
+fn record_pattern_parts(pattern: &Pattern) -> Option<(&str, &[(String, Pattern)])> {
+    match pattern {
+        Pattern::Record(name, fields) => Some((name, fields)),
+        Pattern::RecordDestruct {
+            type_name, fields, ..
+        } => Some((type_name, fields)),
+        _ => None,
+    }
+}
+
+/// Host builtins and the built-in context that must enclose their use.
+/// Programs opt into the environment or the clock with `with Env { ... }`
+/// or `with Clock { ... }`; nothing else can reach them.
+const CAPABILITY_BUILTINS: &[(&str, &str)] = &[("env_var", "Env"), ("now", "Clock")];
+
+fn capability_context(function: &str) -> Option<&'static str> {
+    CAPABILITY_BUILTINS
+        .iter()
+        .find(|(builtin, _)| *builtin == function)
+        .map(|(_, context)| *context)
+}
+
+fn is_capability_context(name: &str) -> bool {
+    CAPABILITY_BUILTINS
+        .iter()
+        .any(|(_, context)| *context == name)
+}
+
 fn lowercase_option_constructor_error(name: &str) -> TypeError {
     let replacement = match name {
         "some" => "`Some(value)`",
@@ -551,6 +1105,9 @@ impl TypeSubstitution {
             TypedType::Result(ok, err) => {
                 TypedType::Result(Box::new(self.apply(ok)), Box::new(self.apply(err)))
             }
+            TypedType::Map(key, value) => {
+                TypedType::Map(Box::new(self.apply(key)), Box::new(self.apply(value)))
+            }
             TypedType::Function {
                 params,
                 return_type,
@@ -599,9 +1156,23 @@ struct Variable {
     ty: TypedType,
     mutable: bool,
     used: bool, // For affine type checking
+    // A `mut val` whose non-copyable value was moved out, so another
+    // binding may share its storage. Until it is reassigned, field updates
+    // through it are rejected and, when it is a record, so are reads.
+    moved: bool,
+    // Bound from a value read out of a `mut val` collection of records
+    // that stays readable, so its records may be shared with that collection
+    shared: bool,
     pending_inference_uses: usize,
     deferred: Option<DeferredBinding>,
     flexible_collection_literal: bool,
+    // Statically known range of an integer binding
+    bound: Option<RangeBound>,
+    // Bindings in the same scope that share this value's storage (from
+    // `name @ pattern`); consuming one consumes them all
+    aliases: Vec<String>,
+    // Source span of the `val` value that introduced this binding
+    span: Option<Span>,
 }
 
 #[derive(Debug, Clone)]
@@ -627,10 +1198,12 @@ struct DeferredLambdaCandidate {
     captures: Vec<(String, TypedType)>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct RecordDef {
     fields: HashMap<String, TypedType>,
     field_order: Vec<String>,
+    // Fields a record literal may omit because the declaration gives a default
+    defaulted_fields: HashSet<String>,
     type_params: Vec<TypeParam>,
     temporal_constraints: Vec<TemporalConstraint>,
     hash: Option<String>,
@@ -639,6 +1212,7 @@ struct RecordDef {
 
 type RecordDefSnapshot = (
     HashMap<String, TypedType>,
+    HashSet<String>,
     Vec<TypeParam>,
     Vec<TemporalConstraint>,
     Option<String>,
@@ -654,6 +1228,15 @@ struct FunctionDef {
     temporal_constraints: Vec<TemporalConstraint>,
 }
 
+/// Range restrictions declared on a user function's parameters and return
+/// type. Every user function has an entry, so builtins are the names
+/// without one.
+#[derive(Debug, Clone, Default)]
+struct FunctionRefinements {
+    params: Vec<Option<RangeBound>>,
+    return_bound: Option<RangeBound>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CheckedFunctionSignature {
     pub params: Vec<(String, TypedType)>,
@@ -662,12 +1245,28 @@ pub struct CheckedFunctionSignature {
     pub temporal_constraints: Vec<TemporalConstraint>,
 }
 
+/// A loop that `break` and `continue` inside it refer to.
+#[derive(Debug, Clone)]
+struct LoopFrame {
+    /// Whether `break value` is allowed; only `loop` has a result value
+    accepts_value: bool,
+    /// Type the loop is expected to have, used to check the first `break`
+    expected: Option<TypedType>,
+    /// Type of the first `break`, `Unit` for a plain `break`
+    break_type: Option<TypedType>,
+}
+
 struct VariantPayloadExpectedContext<'a> {
     field_template: &'a TypedType,
     expected: Option<&'a TypedType>,
     substitution: &'a mut ConstraintSubstitution,
 }
 
+/// Receives the error of each failed declaration during checking; see
+/// `TypeChecker::check_program_reporting`.
+type ErrorSink<'a> = dyn FnMut(TypeError) -> Result<(), TypeError> + 'a;
+
+#[derive(Clone)]
 pub struct TypeChecker {
     // Variable environment (stack of scopes)
     var_env: Vec<HashMap<String, Variable>>,
@@ -681,6 +1280,8 @@ pub struct TypeChecker {
     records: HashMap<String, RecordDef>,
     // Function definitions
     functions: HashMap<String, FunctionDef>,
+    // Range restrictions of user function signatures
+    function_refinements: HashMap<String, FunctionRefinements>,
     // Checked expression types, keyed by stable AST node id.
     checked_expr_types: HashMap<NodeId, TypedType>,
     // Method implementations: record_name -> method_name -> function_def
@@ -691,8 +1292,22 @@ pub struct TypeChecker {
     provisional_method_returns: HashSet<(String, String)>,
     // Prototype metadata: record_name -> (hash, parent_hash, sealed)
     prototypes: HashMap<String, (String, Option<String>, bool)>,
-    // Available contexts
-    _contexts: Vec<String>,
+    // Record clones: clone hash -> (base hash, derivation depth)
+    record_clones: HashMap<String, (String, usize)>,
+    // Declared user contexts
+    context_names: HashSet<String>,
+    // Contexts entered by enclosing `with` expressions
+    active_contexts: Vec<String>,
+    // Scope holding each active `with` expression's field bindings
+    context_binding_scopes: Vec<(String, usize)>,
+    // Scope depth at which each enclosing `with Arena` body starts
+    arena_scopes: Vec<usize>,
+    // Number of reads that may hand out records still reachable from a
+    // readable `mut val`; a binding made while it grows is `shared`
+    shared_record_reads: usize,
+    // Capability and scope depth at which each enclosing `with Env` or
+    // `with Clock` body starts
+    capability_scopes: Vec<(String, usize)>,
     // Temporal context for tracking temporal variables and constraints
     temporal_context: TemporalContext,
     // AsyncRuntime context stack for tracking async scopes
@@ -701,6 +1316,18 @@ pub struct TypeChecker {
     type_var_generator: TypeVarGenerator,
     // Built-in form/adoption environment used by A-layer constraint solving.
     form_environment: FormEnvironment,
+    // Non-fatal diagnostics collected during checking.
+    warnings: Vec<Warning>,
+    // Declared return type of each enclosing function body, innermost last;
+    // `None` for lambdas and unannotated functions, which `?` cannot leave.
+    try_return_types: Vec<Option<TypedType>>,
+    // Loops enclosing the expression being checked, innermost last. Function
+    // and lambda bodies start with none, so `break` cannot leave them.
+    loop_frames: Vec<LoopFrame>,
+    // Loads the modules named by source-level imports, when configured.
+    module_resolver: Option<ModuleResolver>,
+    // Receives progress events for IDE clients; shared by speculative clones.
+    tracer: Option<Arc<dyn Fn(TraceEvent) + Send + Sync>>,
 }
 
 impl Default for TypeChecker {
@@ -718,16 +1345,28 @@ impl TypeChecker {
             trait_impls: HashMap::new(),
             records: HashMap::new(),
             functions: HashMap::new(),
+            function_refinements: HashMap::new(),
             checked_expr_types: HashMap::new(),
             methods: HashMap::new(),
             provisional_function_returns: HashSet::new(),
             provisional_method_returns: HashSet::new(),
             prototypes: HashMap::new(),
-            _contexts: Vec::new(),
+            record_clones: HashMap::new(),
+            context_names: HashSet::new(),
+            active_contexts: Vec::new(),
+            context_binding_scopes: Vec::new(),
+            arena_scopes: Vec::new(),
+            shared_record_reads: 0,
+            capability_scopes: Vec::new(),
             temporal_context: TemporalContext::default(),
             async_runtime_stack: Vec::new(),
             type_var_generator: TypeVarGenerator::new(),
             form_environment: FormEnvironment::new(),
+            warnings: Vec::new(),
+            try_return_types: Vec::new(),
+            loop_frames: Vec::new(),
+            module_resolver: None,
+            tracer: None,
         };
 
         // Register built-in functions and traits
@@ -808,6 +1447,35 @@ impl TypeChecker {
         self.peek_var_type(name)
     }
 
+    /// Drain the warnings collected so far.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Report checking progress to `tracer`. Without a tracer, no events are
+    /// built.
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        self.tracer = Some(Arc::from(tracer));
+    }
+
+    fn tracing(&self) -> bool {
+        self.tracer.is_some()
+    }
+
+    fn trace(&self, event: TraceEvent) {
+        if let Some(tracer) = &self.tracer {
+            tracer(event);
+        }
+    }
+
+    fn warn(&mut self, warning: Warning) {
+        // Deferred callables and branch inference may re-check the same
+        // expression, so identical warnings are reported once.
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
     fn record_checked_expr_type(&mut self, expr: &Expr, ty: &TypedType) {
         if expr.id == NodeId::DUMMY {
             // Synthesized desugar nodes have no stable identity. Source
@@ -834,59 +1502,91 @@ impl TypeChecker {
     fn register_builtin_traits(&mut self) {
         // Register trait implementations for built-in types
 
-        // Int32 implements Display, Clone, Copy, Debug
+        // Int32 implements Display, Clone, Copy, Debug, Eq, Ord, MapKey, Hash
         let mut int32_traits = HashSet::new();
         int32_traits.insert("Display".to_string());
         int32_traits.insert("Clone".to_string());
         int32_traits.insert("Copy".to_string());
         int32_traits.insert("Debug".to_string());
+        int32_traits.insert("Eq".to_string());
+        int32_traits.insert("Ord".to_string());
+        int32_traits.insert("MapKey".to_string());
+        int32_traits.insert("Hash".to_string());
         self.trait_impls.insert("Int32".to_string(), int32_traits);
 
-        // Int64 implements Display, Clone, Copy, Debug
+        // Int64 implements Display, Clone, Copy, Debug, Eq, Ord, Hash
         let mut int64_traits = HashSet::new();
         int64_traits.insert("Display".to_string());
         int64_traits.insert("Clone".to_string());
         int64_traits.insert("Copy".to_string());
         int64_traits.insert("Debug".to_string());
+        int64_traits.insert("Eq".to_string());
+        int64_traits.insert("Ord".to_string());
+        int64_traits.insert("Hash".to_string());
         self.trait_impls.insert("Int64".to_string(), int64_traits);
 
-        // String implements Display, Clone, Debug (NOT Copy - strings are heap allocated)
+        // String implements Display, Clone, Debug, Eq, Ord, MapKey, Hash (NOT Copy - strings are heap allocated)
         let mut string_traits = HashSet::new();
         string_traits.insert("Display".to_string());
         string_traits.insert("Clone".to_string());
         string_traits.insert("Debug".to_string());
+        string_traits.insert("Eq".to_string());
+        string_traits.insert("Ord".to_string());
+        string_traits.insert("MapKey".to_string());
+        string_traits.insert("Hash".to_string());
         self.trait_impls.insert("String".to_string(), string_traits);
 
-        // Boolean implements Display, Clone, Copy, Debug
+        // Boolean implements Display, Clone, Copy, Debug, Eq, Hash
         let mut bool_traits = HashSet::new();
         bool_traits.insert("Display".to_string());
         bool_traits.insert("Clone".to_string());
         bool_traits.insert("Copy".to_string());
         bool_traits.insert("Debug".to_string());
+        bool_traits.insert("Eq".to_string());
+        bool_traits.insert("Hash".to_string());
         self.trait_impls.insert("Boolean".to_string(), bool_traits);
 
-        // Float64 implements Display, Clone, Copy, Debug
+        // Float64 implements Display, Clone, Copy, Debug, Eq, Ord, Hash
         let mut float_traits = HashSet::new();
         float_traits.insert("Display".to_string());
         float_traits.insert("Clone".to_string());
         float_traits.insert("Copy".to_string());
         float_traits.insert("Debug".to_string());
+        float_traits.insert("Eq".to_string());
+        float_traits.insert("Ord".to_string());
+        float_traits.insert("Hash".to_string());
         self.trait_impls.insert("Float64".to_string(), float_traits);
 
-        // Char implements Display, Clone, Copy, Debug
+        // Decimal implements Display, Clone, Copy, Debug, Eq, Ord, Hash
+        let mut decimal_traits = HashSet::new();
+        decimal_traits.insert("Display".to_string());
+        decimal_traits.insert("Clone".to_string());
+        decimal_traits.insert("Copy".to_string());
+        decimal_traits.insert("Debug".to_string());
+        decimal_traits.insert("Eq".to_string());
+        decimal_traits.insert("Ord".to_string());
+        decimal_traits.insert("Hash".to_string());
+        self.trait_impls
+            .insert("Decimal".to_string(), decimal_traits);
+
+        // Char implements Display, Clone, Copy, Debug, Eq, Ord, Hash
         let mut char_traits = HashSet::new();
         char_traits.insert("Display".to_string());
         char_traits.insert("Clone".to_string());
         char_traits.insert("Copy".to_string());
         char_traits.insert("Debug".to_string());
+        char_traits.insert("Eq".to_string());
+        char_traits.insert("Ord".to_string());
+        char_traits.insert("Hash".to_string());
         self.trait_impls.insert("Char".to_string(), char_traits);
 
-        // Unit implements Display, Clone, Copy, Debug
+        // Unit implements Display, Clone, Copy, Debug, Eq
         let mut unit_traits = HashSet::new();
         unit_traits.insert("Display".to_string());
         unit_traits.insert("Clone".to_string());
         unit_traits.insert("Copy".to_string());
         unit_traits.insert("Debug".to_string());
+        unit_traits.insert("Eq".to_string());
         self.trait_impls.insert("Unit".to_string(), unit_traits);
     }
 
@@ -896,10 +1596,10 @@ impl TypeChecker {
     fn enter_async_runtime(&mut self, lifetime: &str) -> Result<(), TypeError> {
         // Verify that the lifetime is in the current temporal scope
         if !self.temporal_context.active_temporals.contains(lifetime) {
-            return Err(TypeError::UndefinedVariable(format!(
-                "Lifetime ~{} not in scope",
-                lifetime
-            )));
+            return Err(TypeError::UndefinedVariable {
+                name: format!("Lifetime ~{} not in scope", lifetime),
+                suggestion: None,
+            });
         }
 
         // Push the async runtime onto the stack
@@ -1009,13 +1709,21 @@ impl TypeChecker {
     }
 
     fn register_builtins(&mut self) {
-        // println function
+        // println function: generic over Display, dispatched by static type
+        // during code generation
         self.functions.insert(
             "println".to_string(),
             FunctionDef {
-                params: vec![("s".to_string(), TypedType::String)],
+                params: vec![("value".to_string(), TypedType::TypeParam("T".to_string()))],
                 return_type: TypedType::Unit,
-                type_params: vec![],
+                type_params: vec![TypeParam {
+                    name: "T".to_string(),
+                    bounds: vec![TypeBound {
+                        trait_name: "Display".to_string(),
+                    }],
+                    derivation_bound: None,
+                    is_temporal: false,
+                }],
                 temporal_constraints: vec![],
             },
         );
@@ -1123,7 +1831,12 @@ impl TypeChecker {
         self.register_std_math();
         self.register_std_list();
         self.register_std_option();
+        self.register_std_map();
         self.register_std_io();
+        self.register_std_convert();
+        self.register_std_parse();
+        self.register_std_char();
+        self.register_std_string();
         self.register_std_forms();
         self.register_std_prelude();
 
@@ -1143,33 +1856,48 @@ impl TypeChecker {
             },
         );
 
-        // max function
+        // max<T: Ord> and min<T: Ord>; codegen picks the comparison from
+        // the concrete element type
+        let ord_param = TypeParam {
+            name: "T".to_string(),
+            bounds: vec![TypeBound {
+                trait_name: "Ord".to_string(),
+            }],
+            derivation_bound: None,
+            is_temporal: false,
+        };
+        // hash<T: Hash> folds scalars and frozen records into an FNV-1a Int32
         self.functions.insert(
-            "max".to_string(),
+            "hash".to_string(),
             FunctionDef {
-                params: vec![
-                    ("a".to_string(), TypedType::Int32),
-                    ("b".to_string(), TypedType::Int32),
-                ],
+                params: vec![("value".to_string(), TypedType::TypeParam("T".to_string()))],
                 return_type: TypedType::Int32,
-                type_params: vec![],
+                type_params: vec![TypeParam {
+                    name: "T".to_string(),
+                    bounds: vec![TypeBound {
+                        trait_name: "Hash".to_string(),
+                    }],
+                    derivation_bound: None,
+                    is_temporal: false,
+                }],
                 temporal_constraints: vec![],
             },
         );
 
-        // min function
-        self.functions.insert(
-            "min".to_string(),
-            FunctionDef {
-                params: vec![
-                    ("a".to_string(), TypedType::Int32),
-                    ("b".to_string(), TypedType::Int32),
-                ],
-                return_type: TypedType::Int32,
-                type_params: vec![],
-                temporal_constraints: vec![],
-            },
-        );
+        for name in ["max", "min"] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        ("a".to_string(), TypedType::TypeParam("T".to_string())),
+                        ("b".to_string(), TypedType::TypeParam("T".to_string())),
+                    ],
+                    return_type: TypedType::TypeParam("T".to_string()),
+                    type_params: vec![ord_param.clone()],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
 
         // pow function
         self.functions.insert(
@@ -1196,9 +1924,126 @@ impl TypeChecker {
             },
         );
 
-        // Float versions
+        // sign returns -1, 0, or 1
         self.functions.insert(
-            "abs_f".to_string(),
+            "sign".to_string(),
+            FunctionDef {
+                params: vec![("x".to_string(), TypedType::Int32)],
+                return_type: TypedType::Int32,
+                type_params: vec![],
+                temporal_constraints: vec![],
+            },
+        );
+
+        // gcd and lcm work on magnitudes, so their results are never negative
+        for name in ["gcd", "lcm"] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        ("a".to_string(), TypedType::Int32),
+                        ("b".to_string(), TypedType::Int32),
+                    ],
+                    return_type: TypedType::Int32,
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        // Explicit overflow behavior: wrapping (two's complement) and
+        // saturating (clamped to Int32 bounds) arithmetic
+        for name in [
+            "wrapping_add",
+            "wrapping_mul",
+            "saturating_add",
+            "saturating_sub",
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        ("a".to_string(), TypedType::Int32),
+                        ("b".to_string(), TypedType::Int32),
+                    ],
+                    return_type: TypedType::Int32,
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        // Checked arithmetic: `None` on overflow or division by zero
+        for name in ["checked_add", "checked_sub", "checked_mul", "checked_div"] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        ("a".to_string(), TypedType::Int32),
+                        ("b".to_string(), TypedType::Int32),
+                    ],
+                    return_type: TypedType::Option(Box::new(TypedType::Int32)),
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        // Conversions into a range-restricted Int32: `clamp` saturates to
+        // the bounds and `checked` traps when the value falls outside them
+        for name in ["clamp", "checked"] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        ("value".to_string(), TypedType::Int32),
+                        ("min".to_string(), TypedType::Int32),
+                        ("max".to_string(), TypedType::Int32),
+                    ],
+                    return_type: TypedType::Int32,
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        // Explicit integer width conversions; mixed Int32/Int64 arithmetic
+        // is rejected, so these are how values cross between the two
+        for (name, param, result) in [
+            ("to_i64", TypedType::Int32, TypedType::Int64),
+            ("to_i32", TypedType::Int64, TypedType::Int32),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![("value".to_string(), param)],
+                    return_type: result,
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        // Decimal division with an explicit rounding mode; `/` itself rounds
+        // half to even
+        for name in ["decimal_div_half_even", "decimal_div_half_up"] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        ("dividend".to_string(), TypedType::Decimal),
+                        ("divisor".to_string(), TypedType::Decimal),
+                    ],
+                    return_type: TypedType::Decimal,
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        // Float versions
+        self.functions.insert(
+            "abs_f".to_string(),
             FunctionDef {
                 params: vec![("x".to_string(), TypedType::Float64)],
                 return_type: TypedType::Float64,
@@ -1367,6 +2212,232 @@ impl TypeChecker {
                 temporal_constraints: vec![],
             },
         );
+
+        // list_take<T>, list_drop<T> and list_slice<T> copy a clamped range
+        // into a new list
+        let list_t = TypedType::List(Box::new(TypedType::TypeParam("T".to_string())));
+        for (name, params) in [
+            ("list_take", vec!["count"]),
+            ("list_drop", vec!["count"]),
+            ("list_slice", vec!["start", "end"]),
+        ] {
+            let mut params: Vec<_> = params
+                .into_iter()
+                .map(|param| (param.to_string(), TypedType::Int32))
+                .collect();
+            params.insert(0, ("list".to_string(), list_t.clone()));
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params,
+                    return_type: list_t.clone(),
+                    type_params: vec![t_param.clone()],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        // list_contains<T: Eq> and list_index_of<T: Eq> compare elements
+        // with `==`, so T must be equality-comparable.
+        let eq_param = TypeParam {
+            name: "T".to_string(),
+            bounds: vec![TypeBound {
+                trait_name: "Eq".to_string(),
+            }],
+            derivation_bound: None,
+            is_temporal: false,
+        };
+        for (name, return_type) in [
+            ("list_contains", TypedType::Boolean),
+            (
+                "list_index_of",
+                TypedType::Option(Box::new(TypedType::Int32)),
+            ),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        (
+                            "list".to_string(),
+                            TypedType::List(Box::new(TypedType::TypeParam("T".to_string()))),
+                        ),
+                        ("item".to_string(), TypedType::TypeParam("T".to_string())),
+                    ],
+                    return_type,
+                    type_params: vec![eq_param.clone()],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        let u_param = TypeParam {
+            name: "U".to_string(),
+            bounds: vec![],
+            derivation_bound: None,
+            is_temporal: false,
+        };
+        let a_param = TypeParam {
+            name: "A".to_string(),
+            bounds: vec![],
+            derivation_bound: None,
+            is_temporal: false,
+        };
+
+        // list_map<T, U>: ((T) -> U, List<T>) -> List<U>
+        self.functions.insert(
+            "list_map".to_string(),
+            FunctionDef {
+                params: vec![
+                    (
+                        "mapper".to_string(),
+                        TypedType::Function {
+                            params: vec![TypedType::TypeParam("T".to_string())],
+                            return_type: Box::new(TypedType::TypeParam("U".to_string())),
+                        },
+                    ),
+                    (
+                        "list".to_string(),
+                        TypedType::List(Box::new(TypedType::TypeParam("T".to_string()))),
+                    ),
+                ],
+                return_type: TypedType::List(Box::new(TypedType::TypeParam("U".to_string()))),
+                type_params: vec![t_param.clone(), u_param],
+                temporal_constraints: vec![],
+            },
+        );
+
+        // list_filter<T>: ((T) -> Boolean, List<T>) -> List<T>
+        self.functions.insert(
+            "list_filter".to_string(),
+            FunctionDef {
+                params: vec![
+                    (
+                        "predicate".to_string(),
+                        TypedType::Function {
+                            params: vec![TypedType::TypeParam("T".to_string())],
+                            return_type: Box::new(TypedType::Boolean),
+                        },
+                    ),
+                    (
+                        "list".to_string(),
+                        TypedType::List(Box::new(TypedType::TypeParam("T".to_string()))),
+                    ),
+                ],
+                return_type: TypedType::List(Box::new(TypedType::TypeParam("T".to_string()))),
+                type_params: vec![t_param.clone()],
+                temporal_constraints: vec![],
+            },
+        );
+
+        // list_fold<T, A>: ((A, T) -> A, A, List<T>) -> A
+        self.functions.insert(
+            "list_fold".to_string(),
+            FunctionDef {
+                params: vec![
+                    (
+                        "reducer".to_string(),
+                        TypedType::Function {
+                            params: vec![
+                                TypedType::TypeParam("A".to_string()),
+                                TypedType::TypeParam("T".to_string()),
+                            ],
+                            return_type: Box::new(TypedType::TypeParam("A".to_string())),
+                        },
+                    ),
+                    ("initial".to_string(), TypedType::TypeParam("A".to_string())),
+                    (
+                        "list".to_string(),
+                        TypedType::List(Box::new(TypedType::TypeParam("T".to_string()))),
+                    ),
+                ],
+                return_type: TypedType::TypeParam("A".to_string()),
+                type_params: vec![t_param.clone(), a_param],
+                temporal_constraints: vec![],
+            },
+        );
+
+        // list_sort<T>: ((T, T) -> Int32, List<T>) -> List<T>
+        self.functions.insert(
+            "list_sort".to_string(),
+            FunctionDef {
+                params: vec![
+                    (
+                        "compare".to_string(),
+                        TypedType::Function {
+                            params: vec![
+                                TypedType::TypeParam("T".to_string()),
+                                TypedType::TypeParam("T".to_string()),
+                            ],
+                            return_type: Box::new(TypedType::Int32),
+                        },
+                    ),
+                    (
+                        "list".to_string(),
+                        TypedType::List(Box::new(TypedType::TypeParam("T".to_string()))),
+                    ),
+                ],
+                return_type: TypedType::List(Box::new(TypedType::TypeParam("T".to_string()))),
+                type_params: vec![t_param],
+                temporal_constraints: vec![],
+            },
+        );
+    }
+
+    fn register_std_map(&mut self) {
+        // Keys are hashed by the runtime, which handles Int32 and String only
+        let k_param = TypeParam {
+            name: "K".to_string(),
+            bounds: vec![TypeBound {
+                trait_name: "MapKey".to_string(),
+            }],
+            derivation_bound: None,
+            is_temporal: false,
+        };
+        let v_param = TypeParam {
+            name: "V".to_string(),
+            bounds: vec![],
+            derivation_bound: None,
+            is_temporal: false,
+        };
+        let key = || TypedType::TypeParam("K".to_string());
+        let value = || TypedType::TypeParam("V".to_string());
+        let map = || TypedType::Map(Box::new(key()), Box::new(value()));
+
+        // Mutating operations consume the map and return it, so an affine
+        // binding can never observe the table after it changed.
+        for (name, params, return_type) in [
+            ("map_new", vec![], map()),
+            (
+                "map_insert",
+                vec![
+                    ("map".to_string(), map()),
+                    ("key".to_string(), key()),
+                    ("value".to_string(), value()),
+                ],
+                map(),
+            ),
+            (
+                "map_get",
+                vec![("map".to_string(), map()), ("key".to_string(), key())],
+                TypedType::Option(Box::new(value())),
+            ),
+            (
+                "map_remove",
+                vec![("map".to_string(), map()), ("key".to_string(), key())],
+                map(),
+            ),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params,
+                    return_type,
+                    type_params: vec![k_param.clone(), v_param.clone()],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
     }
 
     fn register_std_option(&mut self) {
@@ -1421,6 +2492,103 @@ impl TypeChecker {
                 temporal_constraints: vec![],
             },
         );
+
+        // option_unwrap<T>: traps on None
+        self.functions.insert(
+            "option_unwrap".to_string(),
+            FunctionDef {
+                params: vec![(
+                    "opt".to_string(),
+                    TypedType::Option(Box::new(TypedType::TypeParam("T".to_string()))),
+                )],
+                return_type: TypedType::TypeParam("T".to_string()),
+                type_params: vec![t_param.clone()],
+                temporal_constraints: vec![],
+            },
+        );
+
+        let u_param = TypeParam {
+            name: "U".to_string(),
+            ..t_param.clone()
+        };
+        let option_of =
+            |param: &str| TypedType::Option(Box::new(TypedType::TypeParam(param.to_string())));
+
+        // option_map<T, U>: ((T) -> U, Option<T>) -> Option<U>
+        // option_and_then<T, U>: ((T) -> Option<U>, Option<T>) -> Option<U>
+        for (name, mapper_result) in [
+            ("option_map", TypedType::TypeParam("U".to_string())),
+            ("option_and_then", option_of("U")),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        (
+                            "f".to_string(),
+                            TypedType::Function {
+                                params: vec![TypedType::TypeParam("T".to_string())],
+                                return_type: Box::new(mapper_result),
+                            },
+                        ),
+                        ("opt".to_string(), option_of("T")),
+                    ],
+                    return_type: option_of("U"),
+                    type_params: vec![t_param.clone(), u_param.clone()],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
+        let e_param = TypeParam {
+            name: "E".to_string(),
+            ..t_param.clone()
+        };
+        let result_of_t_e = || {
+            TypedType::Result(
+                Box::new(TypedType::TypeParam("T".to_string())),
+                Box::new(TypedType::TypeParam("E".to_string())),
+            )
+        };
+
+        // option_ok_or<T, E>: (Option<T>, E) -> Result<T, E>
+        self.functions.insert(
+            "option_ok_or".to_string(),
+            FunctionDef {
+                params: vec![
+                    ("opt".to_string(), option_of("T")),
+                    ("err".to_string(), TypedType::TypeParam("E".to_string())),
+                ],
+                return_type: result_of_t_e(),
+                type_params: vec![t_param.clone(), e_param.clone()],
+                temporal_constraints: vec![],
+            },
+        );
+
+        // result_ok<T, E>: (Result<T, E>) -> Option<T>
+        self.functions.insert(
+            "result_ok".to_string(),
+            FunctionDef {
+                params: vec![("result".to_string(), result_of_t_e())],
+                return_type: option_of("T"),
+                type_params: vec![t_param.clone(), e_param],
+                temporal_constraints: vec![],
+            },
+        );
+
+        // option_flatten<T>: (Option<Option<T>>) -> Option<T>
+        self.functions.insert(
+            "option_flatten".to_string(),
+            FunctionDef {
+                params: vec![(
+                    "opt".to_string(),
+                    TypedType::Option(Box::new(option_of("T"))),
+                )],
+                return_type: option_of("T"),
+                type_params: vec![t_param],
+                temporal_constraints: vec![],
+            },
+        );
     }
 
     fn register_std_io(&mut self) {
@@ -1478,16 +2646,157 @@ impl TypeChecker {
                 temporal_constraints: vec![],
             },
         );
-    }
 
-    fn register_std_forms(&mut self) {
-        self.form_environment
-            .register_builtin_container_adoptions()
-            .expect("standard Container form adoptions should be valid");
-    }
+        // args function: command-line arguments after the program name
+        self.functions.insert(
+            "args".to_string(),
+            FunctionDef {
+                params: vec![],
+                return_type: TypedType::List(Box::new(TypedType::String)),
+                type_params: vec![],
+                temporal_constraints: vec![],
+            },
+        );
 
-    fn register_std_prelude(&mut self) {
-        let c_param = TypeParam {
+        // Host capabilities; each call needs its context, see `CAPABILITY_BUILTINS`
+        self.functions.insert(
+            "env_var".to_string(),
+            FunctionDef {
+                params: vec![("name".to_string(), TypedType::String)],
+                return_type: TypedType::Option(Box::new(TypedType::String)),
+                type_params: vec![],
+                temporal_constraints: vec![],
+            },
+        );
+        self.functions.insert(
+            "now".to_string(),
+            FunctionDef {
+                params: vec![],
+                return_type: TypedType::Int64,
+                type_params: vec![],
+                temporal_constraints: vec![],
+            },
+        );
+    }
+
+    fn register_std_convert(&mut self) {
+        // Conversions to String; the text matches what the print helpers write
+        for (name, param) in [
+            ("int_to_string", TypedType::Int32),
+            ("float_to_string", TypedType::Float64),
+            ("bool_to_string", TypedType::Boolean),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![("value".to_string(), param)],
+                    return_type: TypedType::String,
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+    }
+
+    fn register_std_parse(&mut self) {
+        // Decimal text to numbers; malformed or out-of-range input is None
+        for (name, payload) in [
+            ("parse_int", TypedType::Int32),
+            ("parse_float", TypedType::Float64),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![("text".to_string(), TypedType::String)],
+                    return_type: TypedType::Option(Box::new(payload)),
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+    }
+
+    fn register_std_char(&mut self) {
+        // Chars are Unicode scalar values; `int_to_char` traps on anything else
+        for (name, param, return_type) in [
+            ("char_to_int", TypedType::Char, TypedType::Int32),
+            ("int_to_char", TypedType::Int32, TypedType::Char),
+            ("is_digit", TypedType::Char, TypedType::Boolean),
+            ("is_alpha", TypedType::Char, TypedType::Boolean),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![("value".to_string(), param)],
+                    return_type,
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+    }
+
+    fn register_std_string(&mut self) {
+        // Indices are UTF-8 byte offsets into the length-prefixed layout
+        for (name, params, return_type) in [
+            (
+                "string_length",
+                vec![("text".to_string(), TypedType::String)],
+                TypedType::Int32,
+            ),
+            (
+                "string_char_at",
+                vec![
+                    ("text".to_string(), TypedType::String),
+                    ("index".to_string(), TypedType::Int32),
+                ],
+                TypedType::Option(Box::new(TypedType::Char)),
+            ),
+            (
+                "string_from_char",
+                vec![("value".to_string(), TypedType::Char)],
+                TypedType::String,
+            ),
+            (
+                "string_split",
+                vec![
+                    ("text".to_string(), TypedType::String),
+                    ("delimiter".to_string(), TypedType::Char),
+                ],
+                TypedType::List(Box::new(TypedType::String)),
+            ),
+            (
+                "string_join",
+                vec![
+                    (
+                        "parts".to_string(),
+                        TypedType::List(Box::new(TypedType::String)),
+                    ),
+                    ("separator".to_string(), TypedType::String),
+                ],
+                TypedType::String,
+            ),
+        ] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params,
+                    return_type,
+                    type_params: vec![],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+    }
+
+    fn register_std_forms(&mut self) {
+        self.form_environment
+            .register_builtin_container_adoptions()
+            .expect("standard Container form adoptions should be valid");
+    }
+
+    fn register_std_prelude(&mut self) {
+        let c_param = TypeParam {
             name: "C".to_string(),
             bounds: vec![TypeBound {
                 trait_name: "Container".to_string(),
@@ -1519,6 +2828,17 @@ impl TypeChecker {
             },
         );
 
+        // drop<T>: consumes its argument like any other call and discards it
+        self.functions.insert(
+            "drop".to_string(),
+            FunctionDef {
+                params: vec![("value".to_string(), TypedType::TypeParam("T".to_string()))],
+                return_type: TypedType::Unit,
+                type_params: vec![t_param.clone()],
+                temporal_constraints: vec![],
+            },
+        );
+
         let container_ty = TypedType::TypeParam("C".to_string());
         let container_item_ty = TypedType::Projection {
             base: Box::new(container_ty.clone()),
@@ -1617,6 +2937,66 @@ impl TypeChecker {
             },
         );
 
+        // assert_that: codegen supplies the condition's source text as the message
+        self.functions.insert(
+            "assert_that".to_string(),
+            FunctionDef {
+                params: vec![("condition".to_string(), TypedType::Boolean)],
+                return_type: TypedType::Unit,
+                type_params: vec![],
+                temporal_constraints: vec![],
+            },
+        );
+
+        // debug_assert / debug_print: compiled in debug builds, elided in release
+        self.functions.insert(
+            "debug_assert".to_string(),
+            FunctionDef {
+                params: vec![
+                    ("condition".to_string(), TypedType::Boolean),
+                    ("message".to_string(), TypedType::String),
+                ],
+                return_type: TypedType::Unit,
+                type_params: vec![],
+                temporal_constraints: vec![],
+            },
+        );
+        self.functions.insert(
+            "debug_print".to_string(),
+            FunctionDef {
+                params: vec![("message".to_string(), TypedType::String)],
+                return_type: TypedType::Unit,
+                type_params: vec![],
+                temporal_constraints: vec![],
+            },
+        );
+
+        // assert_eq<T: Eq> / assert_ne<T: Eq>: both operands unify to the
+        // same T, which must be equality-comparable.
+        let eq_param = TypeParam {
+            name: "T".to_string(),
+            bounds: vec![TypeBound {
+                trait_name: "Eq".to_string(),
+            }],
+            derivation_bound: None,
+            is_temporal: false,
+        };
+        for name in ["assert_eq", "assert_ne"] {
+            self.functions.insert(
+                name.to_string(),
+                FunctionDef {
+                    params: vec![
+                        ("left".to_string(), TypedType::TypeParam("T".to_string())),
+                        ("right".to_string(), TypedType::TypeParam("T".to_string())),
+                        ("message".to_string(), TypedType::String),
+                    ],
+                    return_type: TypedType::Unit,
+                    type_params: vec![eq_param.clone()],
+                    temporal_constraints: vec![],
+                },
+            );
+        }
+
         // filter<C: Container>: C, (C.Item -> Boolean) -> C
         self.functions.insert(
             "filter".to_string(),
@@ -1723,6 +3103,24 @@ impl TypeChecker {
         result.map(|value| (value, branch_env))
     }
 
+    /// Check an `else if` condition from `condition_env`, the environment
+    /// after every earlier condition ran, and advance it. Conditions in a
+    /// chain are evaluated one after another, so they consume sequentially;
+    /// only the branch bodies are alternatives.
+    fn check_else_if_condition(
+        &mut self,
+        condition_env: &mut Vec<HashMap<String, Variable>>,
+        condition: &Expr,
+    ) -> Result<(), TypeError> {
+        self.var_env = condition_env.clone();
+        let condition_ty = self.check_expr(condition)?;
+        if condition_ty != TypedType::Boolean {
+            return Err(expected_type_mismatch("Boolean", &condition_ty));
+        }
+        *condition_env = self.var_env.clone();
+        Ok(())
+    }
+
     fn merge_branch_var_usage(
         &mut self,
         base_env: Vec<HashMap<String, Variable>>,
@@ -1742,6 +3140,8 @@ impl TypeChecker {
 
                 let branch_used = branch_vars.iter().any(|branch_var| branch_var.used);
                 let mut used = var.used || branch_used;
+                let moved = var.moved || branch_vars.iter().any(|branch_var| branch_var.moved);
+                let shared = var.shared || branch_vars.iter().any(|branch_var| branch_var.shared);
                 let mut pending_inference_uses = var.pending_inference_uses;
 
                 if let Some(max_pending_inference_uses) = branch_vars
@@ -1781,13 +3181,15 @@ impl TypeChecker {
                     name.clone(),
                     merged_ty,
                     used,
+                    moved,
+                    shared,
                     pending_inference_uses,
                 ));
             }
         }
 
         self.var_env = base_env;
-        for (scope_idx, name, merged_ty, used, pending_inference_uses) in updates {
+        for (scope_idx, name, merged_ty, used, moved, shared, pending_inference_uses) in updates {
             if let Some(var) = self
                 .var_env
                 .get_mut(scope_idx)
@@ -1795,6 +3197,8 @@ impl TypeChecker {
             {
                 var.ty = merged_ty;
                 var.used = used;
+                var.moved = moved;
+                var.shared = shared;
                 var.pending_inference_uses = pending_inference_uses;
             }
         }
@@ -1912,6 +3316,10 @@ impl TypeChecker {
                 Box::new(Self::apply_type_arg_bindings(ok, bindings)),
                 Box::new(Self::apply_type_arg_bindings(err, bindings)),
             ),
+            TypedType::Map(key, value) => TypedType::Map(
+                Box::new(Self::apply_type_arg_bindings(key, bindings)),
+                Box::new(Self::apply_type_arg_bindings(value, bindings)),
+            ),
             TypedType::Function {
                 params,
                 return_type,
@@ -1994,6 +3402,10 @@ impl TypeChecker {
                 .trait_impls
                 .get("Float64")
                 .is_some_and(|traits| traits.contains(trait_name)),
+            TypedType::Decimal => self
+                .trait_impls
+                .get("Decimal")
+                .is_some_and(|traits| traits.contains(trait_name)),
             TypedType::Char => self
                 .trait_impls
                 .get("Char")
@@ -2007,10 +3419,74 @@ impl TypeChecker {
                 self.get_type_bounds(param_name)
                     .contains(&trait_name.to_string())
             }
+            TypedType::Record { frozen: true, .. } if trait_name == "Hash" => {
+                self.record_fields_hashable(ty)
+            }
             _ => false, // Other types don't implement traits for now
         }
     }
 
+    /// A frozen record hashes structurally, so every field must hash too.
+    /// Freezing is transitive, so record-typed fields count as frozen.
+    fn record_fields_hashable(&self, ty: &TypedType) -> bool {
+        let TypedType::Record { name, .. } = ty else {
+            return false;
+        };
+        let Some(record) = self.records.get(name) else {
+            return false;
+        };
+        record
+            .fields
+            .keys()
+            .all(|field| match self.record_field_type(ty, field) {
+                Ok(field_ty @ TypedType::Record { .. }) => self.record_fields_hashable(&field_ty),
+                Ok(field_ty) => self.type_implements_trait(&field_ty, "Hash"),
+                Err(_) => false,
+            })
+    }
+
+    /// Records compare with a user `eq` method when their impl block defines
+    /// one, and field by field otherwise. Structural equality needs every
+    /// field to be `Eq` and is not derived for generic records.
+    fn record_implements_eq(&self, name: &str, visiting: &mut HashSet<String>) -> bool {
+        if self.record_method_with_signature(name, "eq", &TypedType::Boolean) {
+            return true;
+        }
+        let Some(record) = self.records.get(name) else {
+            return false;
+        };
+        if record.type_params.iter().any(|param| !param.is_temporal)
+            || !visiting.insert(name.to_string())
+        {
+            return false;
+        }
+        let derivable = record.fields.values().all(|field_ty| match field_ty {
+            TypedType::Record { name, .. } => self.record_implements_eq(name, visiting),
+            field_ty => self.type_implements_trait(field_ty, "Eq"),
+        });
+        visiting.remove(name);
+        derivable
+    }
+
+    /// Whether `record`'s impl block defines `method: (self, other) -> result`
+    /// with both parameters of the record type. Comparison operators on
+    /// records desugar to such a method.
+    fn record_method_with_signature(&self, record: &str, method: &str, result: &TypedType) -> bool {
+        let Some(def) = self
+            .methods
+            .get(record)
+            .and_then(|methods| methods.get(method))
+        else {
+            return false;
+        };
+        def.return_type == *result
+            && def.params.len() == 2
+            && def
+                .params
+                .iter()
+                .all(|(_, ty)| matches!(ty, TypedType::Record { name, .. } if name == record))
+    }
+
     /// Check if a type is copyable (implements the Copy trait)
     /// Copyable types can be used multiple times without consuming the original binding
     fn is_copyable(&self, ty: &TypedType) -> bool {
@@ -2020,14 +3496,15 @@ impl TypeChecker {
             | TypedType::Int64
             | TypedType::Boolean
             | TypedType::Float64
+            | TypedType::Decimal
             | TypedType::Char
             | TypedType::Unit => true,
             // Composite types are copy only if all their components are copy
             TypedType::Option(inner) => self.is_copyable(inner),
             TypedType::Result(ok, err) => self.is_copyable(ok) && self.is_copyable(err),
             TypedType::Array(inner, _) => self.is_copyable(inner),
-            // Lists are always heap-allocated, so not copyable
-            TypedType::List(_) => false,
+            // Lists and maps are always heap-allocated, so not copyable
+            TypedType::List(_) | TypedType::Map(_, _) => false,
             // Strings are heap-allocated, so not copyable
             TypedType::String => false,
             // Records and functions are not copyable by default
@@ -2056,32 +3533,100 @@ impl TypeChecker {
         }
 
         if let Some((scope_idx, var)) = found_var {
-            // Mutable variables can be used multiple times
-            if var.mutable {
-                return Ok(var.ty.clone());
-            }
+            return self.use_var_at(scope_idx, name, &var);
+        }
 
-            if Self::contains_inference_internal_type(&var.ty) {
-                self.mark_var_pending_inference_use(scope_idx, name)?;
-                return Ok(var.ty.clone());
-            }
+        Err(TypeError::UndefinedVariable {
+            name: name.to_string(),
+            suggestion: None,
+        })
+    }
 
-            // Copyable types can be used multiple times without being consumed
-            if self.is_copyable(&var.ty) {
-                return Ok(var.ty.clone());
-            }
+    /// Read `var`, bound as `name` in scope `scope_idx`, applying the affine
+    /// rules for its type.
+    fn use_var_at(
+        &mut self,
+        scope_idx: usize,
+        name: &str,
+        var: &Variable,
+    ) -> Result<TypedType, TypeError> {
+        if Self::hands_out_shared_records(var) {
+            self.shared_record_reads += 1;
+        }
 
-            // For non-copyable, immutable types: enforce affine constraint
-            if var.used || var.pending_inference_uses > 0 {
+        // Mutable variables can be used multiple times, except a record
+        // that now has another owner who may update it in place
+        if var.mutable {
+            if var.moved && Self::is_record_value(&var.ty) {
                 return Err(TypeError::AffineViolation(name.to_string()));
             }
+            if !var.moved && !self.is_copyable(&var.ty) {
+                if let Some(var) = self
+                    .var_env
+                    .get_mut(scope_idx)
+                    .and_then(|scope| scope.get_mut(name))
+                {
+                    var.moved = true;
+                }
+            }
+            return Ok(var.ty.clone());
+        }
 
-            // Mark as used for affine types
-            self.mark_var_used(scope_idx, name)?;
+        if Self::contains_inference_internal_type(&var.ty) {
+            self.mark_var_pending_inference_use(scope_idx, name)?;
             return Ok(var.ty.clone());
         }
 
-        Err(TypeError::UndefinedVariable(name.to_string()))
+        // Copyable types can be used multiple times without being consumed
+        if self.is_copyable(&var.ty) {
+            return Ok(var.ty.clone());
+        }
+
+        // For non-copyable, immutable types: enforce affine constraint
+        if var.used || var.pending_inference_uses > 0 {
+            return Err(TypeError::AffineViolation(name.to_string()));
+        }
+
+        // Mark as used for affine types
+        self.mark_var_used(scope_idx, name)?;
+        Ok(var.ty.clone())
+    }
+
+    /// Whether reading `var` may produce records that stay reachable through
+    /// a readable binding: a shared value, or a `mut val` collection of
+    /// records, which unlike a `mut val` record is not closed by a move.
+    fn hands_out_shared_records(var: &Variable) -> bool {
+        Self::holds_record_value(&var.ty)
+            && (var.shared || (var.mutable && !Self::is_record_value(&var.ty)))
+    }
+
+    fn is_record_value(ty: &TypedType) -> bool {
+        match ty {
+            TypedType::Record { .. } => true,
+            TypedType::Temporal { base_type, .. } => Self::is_record_value(base_type),
+            _ => false,
+        }
+    }
+
+    /// Mark the `names` just bound as shared when reads made while checking
+    /// their value, since `reads_before`, may have handed out shared records.
+    fn mark_shared_bindings<'a>(
+        &mut self,
+        names: impl IntoIterator<Item = &'a String>,
+        reads_before: usize,
+    ) {
+        if self.shared_record_reads == reads_before {
+            return;
+        }
+        for name in names {
+            if let Some(var) = self
+                .var_env
+                .last_mut()
+                .and_then(|scope| scope.get_mut(name))
+            {
+                var.shared = true;
+            }
+        }
     }
 
     fn mark_var_used(&mut self, scope_idx: usize, name: &str) -> Result<(), TypeError> {
@@ -2092,11 +3637,39 @@ impl TypeChecker {
         })?;
         let var = scope
             .get_mut(name)
-            .ok_or_else(|| TypeError::UndefinedVariable(name.to_string()))?;
+            .ok_or_else(|| TypeError::UndefinedVariable {
+                name: name.to_string(),
+                suggestion: None,
+            })?;
         var.used = true;
+        let aliases = var.aliases.clone();
+        for alias in aliases {
+            if let Some(alias_var) = scope.get_mut(&alias) {
+                alias_var.used = true;
+            }
+        }
+        self.trace(TraceEvent::AffineConsumed(name.to_string()));
         Ok(())
     }
 
+    /// Link `name` with the variables bound by `inner` after binding
+    /// `name @ inner`, so consuming either side consumes the shared value.
+    fn link_pattern_aliases(&mut self, name: &str, inner: &Pattern) {
+        let mut inner_names = HashSet::new();
+        self.collect_pattern_bindings(inner, &mut inner_names);
+        let Some(scope) = self.var_env.last_mut() else {
+            return;
+        };
+        for inner_name in &inner_names {
+            if let Some(var) = scope.get_mut(inner_name) {
+                var.aliases.push(name.to_string());
+            }
+        }
+        if let Some(var) = scope.get_mut(name) {
+            var.aliases.extend(inner_names);
+        }
+    }
+
     fn mark_var_pending_inference_use(
         &mut self,
         scope_idx: usize,
@@ -2110,7 +3683,10 @@ impl TypeChecker {
         })?;
         let var = scope
             .get_mut(name)
-            .ok_or_else(|| TypeError::UndefinedVariable(name.to_string()))?;
+            .ok_or_else(|| TypeError::UndefinedVariable {
+                name: name.to_string(),
+                suggestion: None,
+            })?;
         var.pending_inference_uses += 1;
         Ok(())
     }
@@ -2121,7 +3697,7 @@ impl TypeChecker {
             TypedType::Option(inner) | TypedType::List(inner) | TypedType::Array(inner, _) => {
                 Self::contains_inference_internal_type(inner)
             }
-            TypedType::Result(ok, err) => {
+            TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
                 Self::contains_inference_internal_type(ok)
                     || Self::contains_inference_internal_type(err)
             }
@@ -2602,6 +4178,7 @@ impl TypeChecker {
                 RecordDef {
                     fields: remaining_fields,
                     field_order: remaining_names,
+                    defaulted_fields: HashSet::new(),
                     type_params: vec![],
                     temporal_constraints: vec![],
                     hash: None,
@@ -2633,7 +4210,35 @@ impl TypeChecker {
                 return Ok(var);
             }
         }
-        Err(TypeError::UndefinedVariable(name.to_string()))
+        Err(self.undefined_variable(name))
+    }
+
+    /// `UndefinedVariable` for `name`, suggesting the closest variable in scope.
+    fn undefined_variable(&self, name: &str) -> TypeError {
+        TypeError::UndefinedVariable {
+            name: name.to_string(),
+            suggestion: closest_name(name, self.var_env.iter().flat_map(|scope| scope.keys())),
+        }
+    }
+
+    /// `UndefinedFunction` for `name`, suggesting the closest known function.
+    fn undefined_function(&self, name: &str) -> TypeError {
+        TypeError::UndefinedFunction {
+            name: name.to_string(),
+            suggestion: closest_name(name, self.functions.keys()),
+        }
+    }
+
+    /// `UnknownType` for `name`, suggesting the closest record or scalar type.
+    fn unknown_type(&self, name: &str) -> TypeError {
+        const SCALAR_TYPES: [&str; 8] = [
+            "Int32", "Int64", "Float64", "Decimal", "Boolean", "String", "Char", "Unit",
+        ];
+        let candidates = self.records.keys().map(String::as_str).chain(SCALAR_TYPES);
+        TypeError::UnknownType {
+            name: name.to_string(),
+            suggestion: closest_name(name, candidates),
+        }
     }
 
     fn instantiated_record_fields(
@@ -2693,63 +4298,338 @@ impl TypeChecker {
                 ty,
                 mutable,
                 used: false,
+                moved: false,
+                shared: false,
                 pending_inference_uses: 0,
                 deferred,
                 flexible_collection_literal: false,
+                bound: None,
+                aliases: Vec::new(),
+                span: None,
             },
         );
         Ok(())
     }
 
-    fn lookup_var_for_assignment(&mut self, name: &str) -> Result<(TypedType, bool), TypeError> {
-        // Look up variable without marking it as used (for assignment target)
-        for scope in self.var_env.iter().rev() {
-            if let Some(var) = scope.get(name) {
-                return Ok((var.ty.clone(), var.mutable));
-            }
+    fn set_var_bound(&mut self, name: &str, bound: Option<RangeBound>) {
+        if let Some(var) = self
+            .var_env
+            .last_mut()
+            .and_then(|scope| scope.get_mut(name))
+        {
+            var.bound = bound;
         }
-        Err(TypeError::UndefinedVariable(name.to_string()))
     }
 
-    fn reassign_var(&mut self, name: &str, ty: &TypedType) -> Result<(), TypeError> {
-        // Find the variable and check if it's mutable
-        for scope in self.var_env.iter_mut().rev() {
-            if let Some(var) = scope.get_mut(name) {
-                if !var.mutable {
-                    return Err(TypeError::ImmutableReassignment(name.to_string()));
-                }
-                if &var.ty != ty {
-                    return Err(typed_type_mismatch(&var.ty, ty));
-                }
-                // Don't mark as used for reassignment
-                return Ok(());
-            }
+    fn set_var_span(&mut self, name: &str, span: Option<Span>) {
+        if let Some(var) = self
+            .var_env
+            .last_mut()
+            .and_then(|scope| scope.get_mut(name))
+        {
+            var.span = span;
         }
-        Err(TypeError::UndefinedVariable(name.to_string()))
     }
 
-    /// Helper method to find a record by its hash in the prototype chain
-    fn find_record_by_hash(&self, hash: &str) -> Option<String> {
-        // Look through all records to find one with the matching hash
-        // This is O(n) but works for the current implementation
-        for (record_name, record_def) in &self.records {
-            if let Some(record_hash) = &record_def.hash {
-                if record_hash == hash {
-                    return Some(record_name.clone());
-                }
+    /// Check the names a `val` pattern introduces against bindings still in
+    /// scope. Redefining an immutable binding in the same scope is rejected;
+    /// any other shadowing is allowed with a warning.
+    fn check_shadowing(&mut self, pattern: &Pattern) -> Result<(), TypeError> {
+        let mut names = HashSet::new();
+        self.collect_pattern_bindings(pattern, &mut names);
+        let mut names: Vec<_> = names.into_iter().collect();
+        names.sort();
+
+        for name in names {
+            let Some((depth, previous)) = self
+                .var_env
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(depth, scope)| scope.get(&name).map(|var| (depth, var)))
+            else {
+                continue;
+            };
+            if depth + 1 == self.var_env.len() && !previous.mutable {
+                return Err(TypeError::Redefinition(name));
             }
+            let span = previous.span;
+            self.warn(Warning::Shadowed(name, span));
         }
-        None
+        Ok(())
     }
 
-    /// Resolve a method call with arguments (for OSV syntax: obj.method(args))
-    /// This is different from resolve_method as it also validates the arguments
-    fn resolve_method_call(
-        &mut self,
-        obj_ty: &TypedType,
-        method_name: &str,
-        args: &[Box<Expr>],
-    ) -> Result<TypedType, TypeError> {
+    fn peek_var_bound(&self, name: &str) -> Option<RangeBound> {
+        self.var_env
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .and_then(|var| var.bound)
+    }
+
+    fn refinement_bound(ty: &Type) -> Option<RangeBound> {
+        match ty {
+            Type::Refined(_, bound) => Some(*bound),
+            _ => None,
+        }
+    }
+
+    fn require_within_bound(bound: RangeBound, range: Option<RangeBound>) -> Result<(), TypeError> {
+        match range {
+            Some(range) if bound.contains(&range) => Ok(()),
+            Some(range) => Err(TypeError::RefinementViolation {
+                expected: format!("a value in {}", bound),
+                found: if range.min == range.max {
+                    range.min.to_string()
+                } else {
+                    format!("a value in {}", range)
+                },
+            }),
+            None => Err(TypeError::RefinementViolation {
+                expected: format!("a value in {}", bound),
+                found: "a value with no static bound".to_string(),
+            }),
+        }
+    }
+
+    /// Check the arguments of a call to a user function against the ranges
+    /// its parameters declare.
+    fn check_call_refinements(&self, name: &str, args: &[Box<Expr>]) -> Result<(), TypeError> {
+        let Some(refinements) = self.function_refinements.get(name) else {
+            return Ok(());
+        };
+        for (bound, arg) in refinements.params.iter().zip(args) {
+            if let Some(bound) = bound {
+                Self::require_within_bound(*bound, self.static_int_range(arg, &HashMap::new()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The range an integer expression is statically known to stay within,
+    /// or `None` when nothing can be proven. `locals` holds the ranges of
+    /// bindings made inside blocks that are being evaluated symbolically and
+    /// are therefore not in the variable environment.
+    fn static_int_range(
+        &self,
+        expr: &Expr,
+        locals: &HashMap<String, Option<RangeBound>>,
+    ) -> Option<RangeBound> {
+        match &expr.kind {
+            ExprKind::IntLit(value) => Some(RangeBound {
+                min: *value,
+                max: *value,
+            }),
+            ExprKind::Ident(name) => match locals.get(name) {
+                Some(range) => *range,
+                None => self.peek_var_bound(name),
+            },
+            ExprKind::Unary(unary) if unary.op == UnaryOp::Neg => {
+                let inner = self.static_int_range(&unary.expr, locals)?;
+                Some(RangeBound {
+                    min: inner.max.checked_neg()?,
+                    max: inner.min.checked_neg()?,
+                })
+            }
+            ExprKind::Cast(cast) if cast.target == Type::Named("Int64".to_string()) => {
+                self.static_int_range(&cast.expr, locals)
+            }
+            ExprKind::Binary(binary) => {
+                let left = self.static_int_range(&binary.left, locals)?;
+                let right = self.static_int_range(&binary.right, locals)?;
+                match binary.op {
+                    BinaryOp::Add => Some(RangeBound {
+                        min: left.min.checked_add(right.min)?,
+                        max: left.max.checked_add(right.max)?,
+                    }),
+                    BinaryOp::Sub => Some(RangeBound {
+                        min: left.min.checked_sub(right.max)?,
+                        max: left.max.checked_sub(right.min)?,
+                    }),
+                    BinaryOp::Mul => {
+                        let products = [
+                            left.min.checked_mul(right.min)?,
+                            left.min.checked_mul(right.max)?,
+                            left.max.checked_mul(right.min)?,
+                            left.max.checked_mul(right.max)?,
+                        ];
+                        Some(RangeBound {
+                            min: *products.iter().min()?,
+                            max: *products.iter().max()?,
+                        })
+                    }
+                    _ => None,
+                }
+            }
+            ExprKind::Call(call) => match &call.function.kind {
+                ExprKind::Ident(name) => self.static_call_range(name, &call.args, locals),
+                _ => None,
+            },
+            ExprKind::Pipe(pipe) => match &pipe.target {
+                PipeTarget::Ident(name) => {
+                    self.static_call_range(name, std::slice::from_ref(&pipe.expr), locals)
+                }
+                PipeTarget::Expr(_) => None,
+            },
+            ExprKind::Block(block) => self.static_block_range(block, locals),
+            ExprKind::Then(then) => {
+                let else_block = then.else_block.as_ref()?;
+                let mut range = self.static_block_range(&then.then_block, locals)?;
+                for block in then
+                    .else_ifs
+                    .iter()
+                    .map(|(_, block)| block)
+                    .chain(std::iter::once(else_block))
+                {
+                    let branch = self.static_block_range(block, locals)?;
+                    range = RangeBound {
+                        min: range.min.min(branch.min),
+                        max: range.max.max(branch.max),
+                    };
+                }
+                Some(range)
+            }
+            _ => None,
+        }
+    }
+
+    fn static_call_range(
+        &self,
+        name: &str,
+        args: &[Box<Expr>],
+        locals: &HashMap<String, Option<RangeBound>>,
+    ) -> Option<RangeBound> {
+        if locals.contains_key(name) || self.peek_var_type(name).is_some() {
+            return None;
+        }
+        if let Some(refinements) = self.function_refinements.get(name) {
+            return refinements.return_bound;
+        }
+        match (name, args) {
+            ("clamp" | "checked", [_, min, max]) => {
+                let min = self.static_int_range(min, locals)?;
+                let max = self.static_int_range(max, locals)?;
+                (min.min <= max.max).then_some(RangeBound {
+                    min: min.min,
+                    max: max.max,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluate a block's bindings symbolically to find the range of its
+    /// final expression.
+    fn static_block_range(
+        &self,
+        block: &BlockExpr,
+        locals: &HashMap<String, Option<RangeBound>>,
+    ) -> Option<RangeBound> {
+        let mut locals = locals.clone();
+        for stmt in &block.statements {
+            match stmt {
+                Stmt::Binding(bind) => {
+                    let declared = bind
+                        .type_annotation
+                        .as_ref()
+                        .and_then(Self::refinement_bound);
+                    let range = if bind.mutable {
+                        declared
+                    } else {
+                        declared.or_else(|| self.static_int_range(&bind.value, &locals))
+                    };
+                    let mut names = HashSet::new();
+                    self.collect_pattern_bindings(&bind.pattern, &mut names);
+                    for name in names {
+                        locals.insert(name, None);
+                    }
+                    if let Pattern::Ident(name) = &bind.pattern {
+                        locals.insert(name.clone(), range);
+                    }
+                }
+                Stmt::Expr(expr) => {
+                    if let ExprKind::Pipe(PipeExpr {
+                        target: PipeTarget::Ident(name),
+                        ..
+                    }) = &expr.kind
+                    {
+                        if !self.functions.contains_key(name) {
+                            locals.insert(name.clone(), None);
+                        }
+                    }
+                }
+                Stmt::Assignment(_) | Stmt::Break(_) | Stmt::Continue => {}
+            }
+        }
+        self.static_int_range(block.expr.as_ref()?, &locals)
+    }
+
+    fn lookup_var_for_assignment(&mut self, name: &str) -> Result<(TypedType, bool), TypeError> {
+        // Look up variable without marking it as used (for assignment target)
+        for scope in self.var_env.iter().rev() {
+            if let Some(var) = scope.get(name) {
+                return Ok((var.ty.clone(), var.mutable));
+            }
+        }
+        Err(self.undefined_variable(name))
+    }
+
+    fn reassign_var(&mut self, name: &str, ty: &TypedType) -> Result<(), TypeError> {
+        // Find the variable and check if it's mutable
+        for scope in self.var_env.iter().rev() {
+            if let Some(var) = scope.get(name) {
+                if !var.mutable {
+                    return Err(TypeError::ImmutableReassignment(name.to_string()));
+                }
+                if !self.type_matches_expected(&var.ty, ty) {
+                    return Err(typed_type_mismatch(&var.ty, ty));
+                }
+                break;
+            }
+        }
+        // Don't mark as used for reassignment; the new value is not shared
+        // with whatever the old one was moved into
+        if let Some(var) = self
+            .var_env
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            var.moved = false;
+            return Ok(());
+        }
+        Err(self.undefined_variable(name))
+    }
+
+    /// Helper method to find a record by its hash in the prototype chain
+    fn find_record_by_hash(&self, hash: &str) -> Option<String> {
+        // Look through all records to find one with the matching hash
+        // This is O(n) but works for the current implementation
+        for (record_name, record_def) in &self.records {
+            if let Some(record_hash) = &record_def.hash {
+                if record_hash == hash {
+                    return Some(record_name.clone());
+                }
+            }
+        }
+        None
+    }
+
+    /// Resolve a receiver method call `(args...) obj.method`, binding `obj`
+    /// as `self` and validating the remaining arguments against the method.
+    /// The method of `record` whose name is closest to `method`, if any.
+    fn closest_method(&self, record: &str, method: &str) -> Option<String> {
+        self.methods
+            .get(record)
+            .and_then(|methods| closest_name(method, methods.keys()))
+    }
+
+    fn resolve_method_call(
+        &mut self,
+        obj_ty: &TypedType,
+        method_name: &str,
+        args: &[Box<Expr>],
+    ) -> Result<TypedType, TypeError> {
         match obj_ty {
             TypedType::Record {
                 name,
@@ -2766,6 +4646,8 @@ impl TypeChecker {
                 };
 
                 if let Some(method_info) = method_info {
+                    self.ensure_method_return_inferred(name, method_name)?;
+
                     // Method signature includes 'self' parameter, so we need to skip it
                     let method_params =
                         if !method_info.params.is_empty() && method_info.params[0].0 == "self" {
@@ -2786,7 +4668,7 @@ impl TypeChecker {
                     for (i, arg) in args.iter().enumerate() {
                         let expected_ty = &method_params[i].1;
                         let actual_ty = self.check_expr_with_expected(arg, Some(expected_ty))?;
-                        if &actual_ty != expected_ty {
+                        if !self.type_matches_expected(expected_ty, &actual_ty) {
                             return Err(typed_type_mismatch(expected_ty, &actual_ty));
                         }
                     }
@@ -2804,15 +4686,37 @@ impl TypeChecker {
                             hash: None,
                             parent_hash: None,
                         };
-                        return self.resolve_method_call(&parent_ty, method_name, args);
+                        // A near miss among this record's own methods is a
+                        // better suggestion than none from the parent.
+                        return self
+                            .resolve_method_call(&parent_ty, method_name, args)
+                            .map_err(|error| match error {
+                                TypeError::UnknownMethod {
+                                    record,
+                                    method,
+                                    suggestion: None,
+                                } => TypeError::UnknownMethod {
+                                    suggestion: self.closest_method(name, &method),
+                                    record,
+                                    method,
+                                },
+                                error => error,
+                            });
                     }
                 }
 
-                Err(TypeError::UndefinedMethod {
+                Err(TypeError::UnknownMethod {
+                    record: name.clone(),
                     method: method_name.to_string(),
-                    record_type: name.clone(),
+                    suggestion: self.closest_method(name, method_name),
                 })
             }
+            TypedType::Temporal { base_type, .. }
+                if matches!(base_type.as_ref(), TypedType::Record { .. }) =>
+            {
+                let base_type = base_type.as_ref().clone();
+                self.resolve_method_call(&base_type, method_name, args)
+            }
             _ => Err(expected_type_mismatch("record type", obj_ty)),
         }
     }
@@ -2860,17 +4764,59 @@ impl TypeChecker {
                 }
                 Some(func_info.return_type.clone())
             }
-            PipeTarget::Expr(target) => {
-                let call = CallExpr {
-                    function: target.clone(),
-                    args: vec![pipe.expr.clone()],
-                };
+            PipeTarget::Expr(_) => {
+                let call = pipe.expr_target_call(|callee| self.pipe_callee_arity(callee))?;
+                if let ExprKind::FieldAccess(obj_expr, method_name) = &call.function.kind {
+                    let (record_name, method_info) =
+                        self.qualified_method(obj_expr, method_name)?;
+                    if self
+                        .provisional_method_returns
+                        .contains(&(record_name, method_name.clone()))
+                        || method_info.params.len() != call.args.len()
+                    {
+                        return None;
+                    }
+                    return Some(method_info.return_type);
+                }
                 self.peek_named_call_return_type(&call)
             }
             _ => None,
         }
     }
 
+    /// Parameter count of a named pipe callee: a top-level function or a
+    /// qualified method such as `Point.translate` (counting `self`).
+    /// Bindings shadow functions and are never partially applied.
+    fn pipe_callee_arity(&self, callee: &Expr) -> Option<usize> {
+        match &callee.kind {
+            ExprKind::Ident(name) if self.peek_var_type(name).is_none() => {
+                self.functions.get(name).map(|func| func.params.len())
+            }
+            ExprKind::FieldAccess(obj_expr, method_name) => self
+                .qualified_method(obj_expr, method_name)
+                .map(|(_, method_info)| method_info.params.len()),
+            _ => None,
+        }
+    }
+
+    /// Resolve `Record.method` where `Record` names a record type (and is
+    /// not shadowed by a binding) that declares `method` in an impl block.
+    fn qualified_method(
+        &self,
+        obj_expr: &Expr,
+        method_name: &str,
+    ) -> Option<(String, FunctionDef)> {
+        let ExprKind::Ident(record_name) = &obj_expr.kind else {
+            return None;
+        };
+        if self.peek_var_type(record_name).is_some() || !self.records.contains_key(record_name) {
+            return None;
+        }
+
+        let method_info = self.methods.get(record_name)?.get(method_name)?.clone();
+        Some((record_name.clone(), method_info))
+    }
+
     fn check_osv_method_call(
         &mut self,
         method_name: &str,
@@ -2902,15 +4848,21 @@ impl TypeChecker {
             return Ok(None);
         };
 
-        if self
-            .provisional_method_returns
-            .contains(&(record_name.clone(), method_name.to_string()))
-        {
-            return Err(TypeError::CannotInferType(format!(
-                "method '{}' for record '{}' is used before its return type has been inferred; add an explicit return annotation",
-                method_name, record_name
-            )));
-        }
+        self.check_method_info_call(&record_name, method_name, method_info, args)
+            .map(Some)
+    }
+
+    /// Check an OSV method call `(receiver, args...) method` once the
+    /// receiver's record has been resolved. The receiver is checked against
+    /// the method's `self` parameter like any other argument.
+    fn check_method_info_call(
+        &mut self,
+        record_name: &str,
+        method_name: &str,
+        method_info: FunctionDef,
+        args: &[Box<Expr>],
+    ) -> Result<TypedType, TypeError> {
+        self.ensure_method_return_inferred(record_name, method_name)?;
 
         if args.len() != method_info.params.len() {
             return Err(TypeError::ArityMismatch {
@@ -2923,21 +4875,42 @@ impl TypeChecker {
             let call = CallExpr {
                 function: Box::new(Expr::new(ExprKind::Ident(method_name.to_string()))),
                 args: args.to_vec(),
+                type_args: Vec::new(),
             };
-            return self
-                .check_function_call_with_inference(&method_info, &call, None)
-                .map(Some);
+            return self.check_function_call_with_inference(&method_info, &call, None);
         }
 
         for (i, arg) in args.iter().enumerate() {
             let expected_ty = &method_info.params[i].1;
             let actual_ty = self.check_expr_with_expected(arg, Some(expected_ty))?;
+            // A `self: Record` receiver may be updated in place through a
+            // `mut` binding, so a frozen record cannot be passed as one.
+            if i == 0 && matches!(expected_ty, TypedType::Record { frozen: false, .. }) {
+                Self::require_unfrozen(&actual_ty, &format!("call method `{}`", method_name))?;
+            }
             if !self.type_matches_expected(expected_ty, &actual_ty) {
                 return Err(typed_type_mismatch(expected_ty, &actual_ty));
             }
         }
 
-        Ok(Some(method_info.return_type))
+        Ok(method_info.return_type)
+    }
+
+    fn ensure_method_return_inferred(
+        &self,
+        record_name: &str,
+        method_name: &str,
+    ) -> Result<(), TypeError> {
+        if self
+            .provisional_method_returns
+            .contains(&(record_name.to_string(), method_name.to_string()))
+        {
+            return Err(TypeError::CannotInferType(format!(
+                "method '{}' for record '{}' is used before its return type has been inferred; add an explicit return annotation",
+                method_name, record_name
+            )));
+        }
+        Ok(())
     }
 
     fn convert_type(&mut self, ty: &Type) -> Result<TypedType, TypeError> {
@@ -2946,13 +4919,23 @@ impl TypeChecker {
                 "Int32" => Ok(TypedType::Int32),
                 "Int64" => Ok(TypedType::Int64),
                 "Float64" => Ok(TypedType::Float64),
+                "Decimal" => Ok(TypedType::Decimal),
                 "Boolean" => Ok(TypedType::Boolean),
                 "String" => Ok(TypedType::String),
                 "Char" => Ok(TypedType::Char),
                 "Unit" => Ok(TypedType::Unit),
-                "Int" => Err(TypeError::UnknownType("`Int`; use `Int32`".to_string())),
-                "Float" => Err(TypeError::UnknownType("`Float`; use `Float64`".to_string())),
-                "Bool" => Err(TypeError::UnknownType("`Bool`; use `Boolean`".to_string())),
+                "Int" => Err(TypeError::UnknownType {
+                    name: "`Int`; use `Int32`".to_string(),
+                    suggestion: None,
+                }),
+                "Float" => Err(TypeError::UnknownType {
+                    name: "`Float`; use `Float64`".to_string(),
+                    suggestion: None,
+                }),
+                "Bool" => Err(TypeError::UnknownType {
+                    name: "`Bool`; use `Boolean`".to_string(),
+                    suggestion: None,
+                }),
                 _ => {
                     // Check if it's a type parameter
                     if self.is_type_param(name) {
@@ -2970,7 +4953,7 @@ impl TypeChecker {
                             parent_hash: None,
                         })
                     } else {
-                        Err(TypeError::UnknownType(name.clone()))
+                        Err(self.unknown_type(name))
                     }
                 }
             },
@@ -2985,6 +4968,10 @@ impl TypeChecker {
                 "List" if params.len() == 1 => {
                     Ok(TypedType::List(Box::new(self.convert_type(&params[0])?)))
                 }
+                "Map" if params.len() == 2 => Ok(TypedType::Map(
+                    Box::new(self.convert_type(&params[0])?),
+                    Box::new(self.convert_type(&params[1])?),
+                )),
                 "Range" if params.len() == 1 => {
                     let elem_type = self.convert_type(&params[0])?;
                     if elem_type == TypedType::Int32 {
@@ -2995,22 +4982,28 @@ impl TypeChecker {
                         ))
                     }
                 }
-                "Array" if params.len() == 1 => Err(TypeError::UnknownType(
-                    "Array type requires explicit length: use Array<T, N>".to_string(),
-                )),
+                "Array" if params.len() == 1 => Err(TypeError::UnknownType {
+                    name: "Array type requires explicit length: use Array<T, N>".to_string(),
+                    suggestion: None,
+                }),
                 "Array" if params.len() == 2 => {
                     let elem_type = self.convert_type(&params[0])?;
                     let size = match &params[1] {
-                        Type::Named(size) => size.parse::<usize>().map_err(|_| {
-                            TypeError::UnknownType(format!(
-                                "Array length must be a non-negative integer literal, got {}",
-                                size
-                            ))
-                        })?,
+                        Type::Named(size) => {
+                            size.parse::<usize>().map_err(|_| TypeError::UnknownType {
+                                name: format!(
+                                    "Array length must be a non-negative integer literal, got {}",
+                                    size
+                                ),
+                                suggestion: None,
+                            })?
+                        }
                         _ => {
-                            return Err(TypeError::UnknownType(
-                                "Array length must be a non-negative integer literal".to_string(),
-                            ));
+                            return Err(TypeError::UnknownType {
+                                name: "Array length must be a non-negative integer literal"
+                                    .to_string(),
+                                suggestion: None,
+                            });
                         }
                     };
                     Ok(TypedType::Array(
@@ -3019,21 +5012,23 @@ impl TypeChecker {
                     ))
                 }
                 _ if self.records.contains_key(name) => {
-                    let record_def = self
-                        .records
-                        .get(name)
-                        .ok_or_else(|| TypeError::UnknownType(name.clone()))?;
+                    let record_def =
+                        self.records
+                            .get(name)
+                            .ok_or_else(|| TypeError::UnknownType {
+                                name: name.clone(),
+                                suggestion: None,
+                            })?;
                     let regular_param_count = record_def
                         .type_params
                         .iter()
                         .filter(|param| !param.is_temporal)
                         .count();
                     if params.len() != regular_param_count {
-                        return Err(TypeError::UnknownType(format!(
-                            "{}<{}>",
-                            name,
-                            params.len()
-                        )));
+                        return Err(TypeError::UnknownType {
+                            name: format!("{}<{}>", name, params.len()),
+                            suggestion: None,
+                        });
                     }
 
                     Ok(TypedType::Record {
@@ -3047,11 +5042,10 @@ impl TypeChecker {
                         parent_hash: None,
                     })
                 }
-                _ => Err(TypeError::UnknownType(format!(
-                    "{}<{}>",
-                    name,
-                    params.len()
-                ))),
+                _ => Err(TypeError::UnknownType {
+                    name: format!("{}<{}>", name, params.len()),
+                    suggestion: None,
+                }),
             },
             Type::Function(params, return_type) => Ok(TypedType::Function {
                 params: params
@@ -3071,10 +5065,131 @@ impl TypeChecker {
                     temporals: temporals.clone(),
                 })
             }
+            // The range is tracked beside the binding; the value itself is a
+            // plain integer of the base type.
+            Type::Refined(base, bound) => {
+                let base_type = self.convert_type(base)?;
+                let (type_min, type_max) = match base_type {
+                    TypedType::Int32 => (i64::from(i32::MIN), i64::from(i32::MAX)),
+                    TypedType::Int64 => (i64::MIN, i64::MAX),
+                    _ => {
+                        return Err(TypeError::InvalidRefinement(format!(
+                            "only Int32 and Int64 can be restricted to a range, not {}",
+                            base
+                        )))
+                    }
+                };
+                if bound.min > bound.max {
+                    return Err(TypeError::InvalidRefinement(format!("{} is empty", bound)));
+                }
+                if bound.min < type_min || bound.max > type_max {
+                    return Err(TypeError::InvalidRefinement(format!(
+                        "{} does not fit in {}",
+                        bound, base
+                    )));
+                }
+                Ok(base_type)
+            }
+        }
+    }
+
+    /// Load imported modules through `resolver` during checking instead of
+    /// rejecting programs that still carry source-level imports.
+    pub fn set_module_resolver(&mut self, resolver: ModuleResolver) {
+        self.module_resolver = Some(resolver);
+    }
+
+    /// Make `function` callable from checked programs under its
+    /// [`binding`](ForeignFunction::binding), like a builtin. The name must
+    /// not already belong to a builtin or another foreign function.
+    pub fn declare_foreign_function(
+        &mut self,
+        function: &ForeignFunction,
+    ) -> Result<(), TypeError> {
+        if self.functions.contains_key(&function.binding()) {
+            return Err(TypeError::UnsupportedFeature(format!(
+                "foreign function {} would shadow the existing function '{}'",
+                function,
+                function.binding()
+            )));
         }
+        let params = function
+            .params
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| Ok((format!("arg{}", index), self.convert_type(ty)?)))
+            .collect::<Result<Vec<_>, TypeError>>()?;
+        let return_type = self.convert_type(&function.result)?;
+        self.functions.insert(
+            function.binding(),
+            FunctionDef {
+                params,
+                return_type,
+                type_params: vec![],
+                temporal_constraints: vec![],
+            },
+        );
+        Ok(())
+    }
+
+    /// Splice the declarations a program imports into it, so that the
+    /// result has no imports left and can be checked and compiled on its
+    /// own. Programs without imports are returned unchanged.
+    pub fn resolve_imports(&mut self, program: Program) -> Result<Program, TypeError> {
+        if program.imports.is_empty() {
+            return Ok(program);
+        }
+        let Some(resolver) = self.module_resolver.as_mut() else {
+            self.reject_unresolved_imports(&program.imports)?;
+            return Ok(program);
+        };
+        resolver.resolve_program_imports(program).map_err(|err| {
+            match err.downcast_ref::<NotExportedError>() {
+                Some(NotExportedError { module, name }) => TypeError::NotExported {
+                    module: module.clone(),
+                    name: name.clone(),
+                },
+                None => TypeError::ModuleResolution(format!("{err:#}")),
+            }
+        })
     }
 
     pub fn check_program(&mut self, program: &Program) -> Result<(), TypeError> {
+        self.check_program_reporting(program, &mut Err)
+    }
+
+    /// Check `program` like [`check_program`](Self::check_program), but keep
+    /// going after a declaration fails and return every error found, in
+    /// source order within each checking phase. An empty result means the
+    /// program type checks.
+    ///
+    /// Errors that leave nothing sensible to check, such as unresolved
+    /// imports, still end checking early.
+    pub fn check_program_collecting(&mut self, program: &Program) -> Vec<TypeError> {
+        let mut errors = Vec::new();
+        let result = self.check_program_reporting(program, &mut |error| {
+            errors.push(error);
+            Ok(())
+        });
+        if let Err(error) = result {
+            errors.push(error);
+        }
+        errors
+    }
+
+    /// Runs every checking phase, handing each failed declaration's error to
+    /// `report`. Returning `Err` from `report` stops checking; returning
+    /// `Ok` moves on to the next declaration.
+    fn check_program_reporting(
+        &mut self,
+        program: &Program,
+        report: &mut ErrorSink<'_>,
+    ) -> Result<(), TypeError> {
+        if !program.imports.is_empty() && self.module_resolver.is_some() {
+            let resolved = self.resolve_imports(program.clone())?;
+            return self.check_program_reporting(&resolved, report);
+        }
+
         self.checked_expr_types.clear();
         self.reject_unresolved_imports(&program.imports)?;
 
@@ -3095,22 +5210,36 @@ impl TypeChecker {
 
         // First pass: register record/context shapes before any signature that
         // may mention them, regardless of source order.
+        self.trace(TraceEvent::Phase(CheckPhase::Declarations));
         for decl in &program.declarations {
             match Self::decl_registration_item(decl) {
                 TopDecl::Record(record) => {
-                    self.check_record_decl(record)?;
+                    self.recover(report, |checker| checker.check_record_decl(record))?;
                 }
                 TopDecl::Context(context) => {
-                    self.check_context_decl(context)?;
+                    self.recover(report, |checker| checker.check_context_decl(context))?;
                 }
                 _ => {}
             }
         }
 
+        // Validate declared prototype chains once every record is known, so
+        // parents may be declared after their children.
+        for decl in &program.declarations {
+            if let TopDecl::Record(record) = Self::decl_registration_item(decl) {
+                if record.parent_hash.is_some() {
+                    self.recover(report, |checker| {
+                        checker.check_derivation_depth(&record.name).map(|_| ())
+                    })?;
+                }
+            }
+        }
+
         // Second pass: register function signatures for forward references.
+        self.trace(TraceEvent::Phase(CheckPhase::Signatures));
         for decl in &program.declarations {
             if let TopDecl::Function(func) = Self::decl_registration_item(decl) {
-                self.register_function_signature(func)?;
+                self.recover(report, |checker| checker.register_function_signature(func))?;
             }
         }
 
@@ -3118,28 +5247,37 @@ impl TypeChecker {
         // so OSV method calls can refer to impl blocks declared later.
         for decl in &program.declarations {
             if let TopDecl::Impl(impl_block) = Self::decl_registration_item(decl) {
-                self.register_impl_method_signatures(impl_block)?;
+                self.recover(report, |checker| {
+                    checker.register_impl_method_signatures(impl_block)
+                })?;
             }
         }
 
         // Fourth pass: check impl bodies before ordinary functions. This turns
         // unannotated method returns from provisional signatures into inferred
         // concrete method signatures before function bodies call them.
+        self.trace(TraceEvent::Phase(CheckPhase::ImplBodies));
         for decl in &program.declarations {
             if let TopDecl::Impl(impl_block) = Self::decl_registration_item(decl) {
-                self.check_impl_block(impl_block)?;
+                self.recover(report, |checker| checker.check_impl_block(impl_block))?;
             }
         }
 
         // Fifth pass: infer unannotated ordinary function returns before
         // annotated functions and top-level bindings use those functions.
-        self.infer_unannotated_function_returns(program)?;
+        self.trace(TraceEvent::Phase(CheckPhase::ReturnInference));
+        self.infer_unannotated_function_returns(program, report)?;
 
         // Final pass: check all remaining declarations
+        self.trace(TraceEvent::Phase(CheckPhase::Bodies));
         for decl in &program.declarations {
             match Self::decl_registration_item(decl) {
-                TopDecl::Record(_) => {
-                    // Already processed in first pass
+                TopDecl::Record(record) => {
+                    // Shapes were registered in the first pass; defaults may
+                    // call functions and build records declared anywhere.
+                    self.recover(report, |checker| {
+                        checker.check_record_field_defaults(record)
+                    })?;
                 }
                 TopDecl::Context(_) => {
                     // Already processed in first pass
@@ -3151,15 +5289,52 @@ impl TypeChecker {
                     // Already processed before annotated function bodies
                 }
                 _ => {
-                    self.check_top_decl(decl)?;
+                    self.recover(report, |checker| checker.check_top_decl(decl))?;
                 }
             }
         }
-        self.reject_unresolved_inference_in_current_scope()?;
-        Ok(())
+        self.recover(report, |checker| {
+            checker.reject_unresolved_inference_in_current_scope()
+        })
+    }
+
+    /// Run one declaration's check. On failure, unwind the scopes it left
+    /// open so the next declaration starts from a clean slate, then hand
+    /// the error to `report`.
+    fn recover(
+        &mut self,
+        report: &mut ErrorSink<'_>,
+        check: impl FnOnce(&mut Self) -> Result<(), TypeError>,
+    ) -> Result<(), TypeError> {
+        let var_depth = self.var_env.len();
+        let type_param_depth = self.type_param_env.len();
+        let context_depth = self.active_contexts.len();
+        let context_binding_depth = self.context_binding_scopes.len();
+        let arena_depth = self.arena_scopes.len();
+        let capability_depth = self.capability_scopes.len();
+        let async_depth = self.async_runtime_stack.len();
+
+        let Err(error) = check(self) else {
+            return Ok(());
+        };
+        self.var_env.truncate(var_depth);
+        self.type_param_env.truncate(type_param_depth);
+        self.type_bounds_env.truncate(type_param_depth);
+        self.active_contexts.truncate(context_depth);
+        self.context_binding_scopes.truncate(context_binding_depth);
+        self.arena_scopes.truncate(arena_depth);
+        self.capability_scopes.truncate(capability_depth);
+        self.async_runtime_stack.truncate(async_depth);
+        self.temporal_context.active_temporals.clear();
+        self.temporal_context.constraints.clear();
+        report(error)
     }
 
-    fn infer_unannotated_function_returns(&mut self, program: &Program) -> Result<(), TypeError> {
+    fn infer_unannotated_function_returns(
+        &mut self,
+        program: &Program,
+        report: &mut ErrorSink<'_>,
+    ) -> Result<(), TypeError> {
         let mut pending = program
             .declarations
             .iter()
@@ -3186,12 +5361,14 @@ impl TypeChecker {
 
             if let Some(idx) = next_idx {
                 let func = pending.remove(idx);
-                self.check_function_decl(func)?;
+                self.recover(report, |checker| checker.check_function_decl(func))?;
                 pending_names.remove(&func.name);
             } else {
                 // Preserve the existing diagnostic for recursive or mutually
                 // recursive unannotated functions.
-                return self.check_function_decl(pending[0]);
+                let func = pending.remove(0);
+                self.recover(report, |checker| checker.check_function_decl(func))?;
+                pending_names.remove(&func.name);
             }
         }
 
@@ -3226,13 +5403,14 @@ impl TypeChecker {
                         unannotated_names,
                     ));
                 }
-                Stmt::Expr(expr) => {
+                Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
                     deps.extend(self.collect_unannotated_function_deps_in_expr(
                         expr,
                         &block_bound,
                         unannotated_names,
                     ));
                 }
+                Stmt::Break(None) | Stmt::Continue => {}
             }
         }
 
@@ -3329,6 +5507,18 @@ impl TypeChecker {
                     unannotated_names,
                 ));
             }
+            ExprKind::FieldUpdate(update) => {
+                deps.extend(self.collect_unannotated_function_deps_in_expr(
+                    &update.object,
+                    bound_vars,
+                    unannotated_names,
+                ));
+                deps.extend(self.collect_unannotated_function_deps_in_expr(
+                    &update.value,
+                    bound_vars,
+                    unannotated_names,
+                ));
+            }
             ExprKind::RecordLit(record_lit) => {
                 for field in &record_lit.fields {
                     match field {
@@ -3377,6 +5567,7 @@ impl TypeChecker {
             | ExprKind::Some(inner)
             | ExprKind::Ok(inner)
             | ExprKind::Err(inner)
+            | ExprKind::Try(inner)
             | ExprKind::Await(inner)
             | ExprKind::Spawn(inner) => {
                 deps.extend(self.collect_unannotated_function_deps_in_expr(
@@ -3415,6 +5606,13 @@ impl TypeChecker {
                 for arm in &match_expr.arms {
                     let mut arm_bound = bound_vars.clone();
                     self.collect_pattern_bindings(&arm.pattern, &mut arm_bound);
+                    if let Some(guard) = &arm.guard {
+                        deps.extend(self.collect_unannotated_function_deps_in_expr(
+                            guard,
+                            &arm_bound,
+                            unannotated_names,
+                        ));
+                    }
                     deps.extend(self.collect_unannotated_function_deps_in_block(
                         &arm.body,
                         &arm_bound,
@@ -3465,6 +5663,27 @@ impl TypeChecker {
                     unannotated_names,
                 ));
             }
+            ExprKind::Loop(body) => {
+                deps.extend(self.collect_unannotated_function_deps_in_block(
+                    body,
+                    bound_vars,
+                    unannotated_names,
+                ));
+            }
+            ExprKind::WhileLet(while_let) => {
+                deps.extend(self.collect_unannotated_function_deps_in_expr(
+                    &while_let.expr,
+                    bound_vars,
+                    unannotated_names,
+                ));
+                let mut body_bound = bound_vars.clone();
+                self.collect_pattern_bindings(&while_let.pattern, &mut body_bound);
+                deps.extend(self.collect_unannotated_function_deps_in_block(
+                    &while_let.body,
+                    &body_bound,
+                    unannotated_names,
+                ));
+            }
             ExprKind::Block(block) => {
                 deps.extend(self.collect_unannotated_function_deps_in_block(
                     block,
@@ -3519,6 +5738,7 @@ impl TypeChecker {
             }
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -3574,6 +5794,20 @@ impl TypeChecker {
     }
 
     fn register_function_signature(&mut self, func: &FunDecl) -> Result<(), TypeError> {
+        // `@init` functions are called by the module itself, so nothing can
+        // pass them arguments or use their result.
+        if func.is_init
+            && (!func.params.is_empty()
+                || !func.type_params.is_empty()
+                || func.is_async
+                || func
+                    .return_type
+                    .as_ref()
+                    .is_some_and(|ty| !matches!(ty, Type::Named(name) if name == "Unit")))
+        {
+            return Err(TypeError::InvalidInitFunction(func.name.clone()));
+        }
+
         // Push type parameter scope for generics
         self.push_type_param_scope(&func.type_params);
 
@@ -3582,6 +5816,17 @@ impl TypeChecker {
             let ty = self.convert_type(&param.ty)?;
             param_types.push((param.name.clone(), ty));
         }
+        self.function_refinements.insert(
+            func.name.clone(),
+            FunctionRefinements {
+                params: func
+                    .params
+                    .iter()
+                    .map(|param| Self::refinement_bound(&param.ty))
+                    .collect(),
+                return_bound: func.return_type.as_ref().and_then(Self::refinement_bound),
+            },
+        );
 
         // Annotated return types are part of the public signature and are
         // available to forward references. Unannotated functions get an
@@ -3622,9 +5867,32 @@ impl TypeChecker {
         self.check_program(program)
     }
 
+    /// Check one more top-level declaration against everything checked so
+    /// far, as an interactive session feeds them.
+    ///
+    /// The declaration goes through the same registration passes as in
+    /// [`TypeChecker::check_program`]; earlier declarations are not re-checked.
+    pub fn check_decl(&mut self, decl: &TopDecl) -> Result<(), TypeError> {
+        self.check_program(&Program {
+            imports: Vec::new(),
+            declarations: vec![decl.clone()],
+        })
+    }
+
+    /// Check a standalone expression in the top-level scope and return its
+    /// type, as an interactive session evaluates bare expressions.
+    pub fn check_expression(&mut self, expr: &Expr) -> Result<TypedType, TypeError> {
+        let ty = self.check_expr(expr)?;
+        self.reject_unresolved_inference_in_current_scope()?;
+        Ok(ty)
+    }
+
     fn check_top_decl(&mut self, decl: &TopDecl) -> Result<(), TypeError> {
         match decl {
-            TopDecl::Record(record) => self.check_record_decl(record),
+            TopDecl::Record(record) => {
+                self.check_record_decl(record)?;
+                self.check_record_field_defaults(record)
+            }
             TopDecl::Function(func) => self.check_function_decl(func),
             TopDecl::Binding(bind) => self.check_bind_decl(bind),
             TopDecl::Impl(impl_block) => self.check_impl_block(impl_block),
@@ -3665,6 +5933,7 @@ impl TypeChecker {
                 ));
             }
         }
+        Self::reject_cyclic_temporal_constraints(&record.temporal_constraints)?;
 
         self.push_type_param_scope(&record.type_params);
         let field_order = record
@@ -3688,6 +5957,12 @@ impl TypeChecker {
             RecordDef {
                 fields,
                 field_order,
+                defaulted_fields: record
+                    .fields
+                    .iter()
+                    .filter(|field| field.default.is_some())
+                    .map(|field| field.name.clone())
+                    .collect(),
                 type_params: record.type_params.clone(),
                 temporal_constraints: record
                     .temporal_constraints
@@ -3701,6 +5976,14 @@ impl TypeChecker {
                 parent_hash: record.parent_hash.clone(),
             },
         );
+        self.prototypes.insert(
+            record.name.clone(),
+            (
+                Self::record_prototype_hash(record),
+                record.parent_hash.clone(),
+                record.sealed,
+            ),
+        );
 
         // Clear temporal context for this record
         self.temporal_context.active_temporals.clear();
@@ -3709,7 +5992,45 @@ impl TypeChecker {
         Ok(())
     }
 
+    /// Check each field default against its declared type. Defaults are
+    /// evaluated at every literal that omits the field, so they are checked
+    /// without any enclosing variable in scope.
+    fn check_record_field_defaults(&mut self, record: &RecordDecl) -> Result<(), TypeError> {
+        if record.fields.iter().all(|field| field.default.is_none()) {
+            return Ok(());
+        }
+        let outer_env = std::mem::replace(&mut self.var_env, vec![HashMap::new()]);
+        self.push_type_param_scope(&record.type_params);
+        let result = record.fields.iter().try_for_each(|field| {
+            let Some(default) = &field.default else {
+                return Ok(());
+            };
+            let expected = self.convert_type(&field.ty)?;
+            let found = self
+                .check_expr_with_expected(default, Some(&expected))
+                .map_err(|error| match error {
+                    TypeError::UndefinedVariable { name: variable, .. } => {
+                        TypeError::ImpureFieldDefault {
+                            record: record.name.clone(),
+                            field: field.name.clone(),
+                            variable,
+                        }
+                    }
+                    error => error,
+                })?;
+            if !self.type_matches_expected(&expected, &found) {
+                return Err(typed_type_mismatch(&expected, &found));
+            }
+            Ok(())
+        });
+        self.pop_type_param_scope();
+        self.var_env = outer_env;
+        result
+    }
+
     fn check_function_decl(&mut self, func: &FunDecl) -> Result<(), TypeError> {
+        self.trace(TraceEvent::CheckingFunction(func.name.clone()));
+
         // Push type parameter scope for generics (including temporal parameters)
         self.push_type_param_scope(&func.type_params);
 
@@ -3744,6 +6065,7 @@ impl TypeChecker {
                 ));
             }
         }
+        Self::reject_cyclic_temporal_constraints(&func.temporal_constraints)?;
 
         self.push_scope();
 
@@ -3752,6 +6074,7 @@ impl TypeChecker {
             let ty = self.convert_type(&param.ty)?;
             param_types.push((param.name.clone(), ty.clone()));
             self.bind_var(param.name.clone(), ty, false)?;
+            self.set_var_bound(&param.name, Self::refinement_bound(&param.ty));
         }
 
         let expected_return_type = func
@@ -3760,13 +6083,17 @@ impl TypeChecker {
             .map(|return_type| self.convert_type(return_type))
             .transpose()?;
         let body_return_type =
-            self.check_block_expr_with_expected(&func.body, expected_return_type.as_ref())?;
+            self.check_function_body(&func.body, expected_return_type.as_ref())?;
 
         if let Some(expected_return_type) = &expected_return_type {
             if !self.type_matches_expected(expected_return_type, &body_return_type) {
                 return Err(typed_type_mismatch(expected_return_type, &body_return_type));
             }
         }
+        if let Some(bound) = func.return_type.as_ref().and_then(Self::refinement_bound) {
+            let range = self.static_block_range(&func.body, &HashMap::new());
+            Self::require_within_bound(bound, range)?;
+        }
 
         let return_type = expected_return_type.unwrap_or(body_return_type);
         Self::reject_unresolved_return_type("function", &func.name, &return_type)?;
@@ -3812,6 +6139,36 @@ impl TypeChecker {
         Ok(())
     }
 
+    /// Check a function or method body, recording its declared return type
+    /// as the target of any `?` inside it.
+    fn check_function_body(
+        &mut self,
+        body: &BlockExpr,
+        expected_return_type: Option<&TypedType>,
+    ) -> Result<TypedType, TypeError> {
+        self.try_return_types.push(expected_return_type.cloned());
+        let outer_loops = std::mem::take(&mut self.loop_frames);
+        let result = self.check_block_expr_with_expected(body, expected_return_type);
+        self.loop_frames = outer_loops;
+        self.try_return_types.pop();
+        result
+    }
+
+    /// Check a lambda body. A lambda has no declared return type for `?` to
+    /// return through, so `?` is rejected inside it.
+    fn check_lambda_body(
+        &mut self,
+        body: &Expr,
+        expected_return_type: Option<&TypedType>,
+    ) -> Result<TypedType, TypeError> {
+        self.try_return_types.push(None);
+        let outer_loops = std::mem::take(&mut self.loop_frames);
+        let result = self.check_expr_with_expected(body, expected_return_type);
+        self.loop_frames = outer_loops;
+        self.try_return_types.pop();
+        result
+    }
+
     fn check_bind_decl(&mut self, bind: &BindDecl) -> Result<(), TypeError> {
         self.check_bind_decl_with_expected(bind, None)
     }
@@ -3821,6 +6178,7 @@ impl TypeChecker {
         bind: &BindDecl,
         contextual_expected_ty: Option<&TypedType>,
     ) -> Result<(), TypeError> {
+        let shared_reads_before = self.shared_record_reads;
         let annotated_ty = bind
             .type_annotation
             .as_ref()
@@ -3872,7 +6230,17 @@ impl TypeChecker {
             }
         }
 
+        let declared_bound = bind
+            .type_annotation
+            .as_ref()
+            .and_then(Self::refinement_bound);
+        let value_range = self.static_int_range(&bind.value, &HashMap::new());
+        if let Some(bound) = declared_bound {
+            Self::require_within_bound(bound, value_range)?;
+        }
+
         self.check_pattern(&bind.pattern, &ty)?;
+        self.check_shadowing(&bind.pattern)?;
 
         // Handle pattern binding
         if let (Pattern::Ident(name), Some(deferred)) = (&bind.pattern, deferred_binding) {
@@ -3880,6 +6248,22 @@ impl TypeChecker {
         } else {
             self.bind_pattern(&bind.pattern, &ty, bind.mutable)?;
         }
+        let mut bound_names = HashSet::new();
+        self.collect_pattern_bindings(&bind.pattern, &mut bound_names);
+        for name in &bound_names {
+            self.set_var_span(name, bind.value.span);
+        }
+        self.mark_shared_bindings(&bound_names, shared_reads_before);
+        if let Pattern::Ident(name) = &bind.pattern {
+            // A mutable binding only keeps its declared range: later
+            // assignments are checked against that, not the initial value.
+            let bound = if bind.mutable {
+                declared_bound
+            } else {
+                declared_bound.or(value_range)
+            };
+            self.set_var_bound(name, bound);
+        }
 
         if annotated_ty.is_none()
             && contextual_expected_ty.is_none()
@@ -3946,8 +6330,7 @@ impl TypeChecker {
         } else {
             None
         };
-        let body_result =
-            self.check_expr_with_expected(&lambda.body, inferred_return_type.as_ref());
+        let body_result = self.check_lambda_body(&lambda.body, inferred_return_type.as_ref());
         let param_types = lambda
             .params
             .iter()
@@ -4052,14 +6435,11 @@ impl TypeChecker {
         branch_envs.push(then_env);
         candidates.push(then_candidate);
 
+        let mut condition_env = branch_base.clone();
         for (else_cond, else_block) in &then.else_ifs {
+            self.check_else_if_condition(&mut condition_env, else_cond)?;
             let (else_if_candidate, else_if_env) =
-                self.check_branch_from_env(&branch_base, |checker| {
-                    let else_cond_ty = checker.check_expr(else_cond)?;
-                    if else_cond_ty != TypedType::Boolean {
-                        return Err(expected_type_mismatch("Boolean", &else_cond_ty));
-                    }
-
+                self.check_branch_from_env(&condition_env, |checker| {
                     checker.push_scope();
                     let result = checker.check_block_as_deferred_callable_result(else_block);
                     checker.pop_scope();
@@ -4074,7 +6454,7 @@ impl TypeChecker {
                 "lambda-producing then expressions require an else branch".to_string(),
             )
         })?;
-        let (else_candidate, else_env) = self.check_branch_from_env(&branch_base, |checker| {
+        let (else_candidate, else_env) = self.check_branch_from_env(&condition_env, |checker| {
             checker.push_scope();
             let result = checker.check_block_as_deferred_callable_result(else_block);
             checker.pop_scope();
@@ -4122,6 +6502,9 @@ impl TypeChecker {
                 checker.push_scope();
                 let result = (|| {
                     checker.bind_pattern_vars(&arm.pattern, &scrutinee_type)?;
+                    if let Some(guard) = &arm.guard {
+                        checker.check_match_guard(guard)?;
+                    }
                     checker.check_block_as_deferred_callable_result(&arm.body)
                 })();
                 checker.pop_scope();
@@ -4130,6 +6513,7 @@ impl TypeChecker {
             branch_envs.push(arm_env);
             candidates.push(candidate);
         }
+        self.warn_unreachable_arms(&match_expr.arms);
 
         if !self.is_pattern_exhaustive(&match_expr.arms, &scrutinee_type) {
             if let Err(missing_patterns) =
@@ -4167,7 +6551,7 @@ impl TypeChecker {
         &mut self,
         block: &BlockExpr,
     ) -> Result<DeferredCallableCandidate, TypeError> {
-        for stmt in self.deferred_callable_prefix_statements(block) {
+        for stmt in &block.statements {
             match stmt {
                 Stmt::Binding(bind) => {
                     let Pattern::Ident(name) = &bind.pattern else {
@@ -4191,7 +6575,10 @@ impl TypeChecker {
 
                     self.check_bind_decl_with_expected(bind, None)?;
                     let Some(bound_ty) = self.peek_var_type(name) else {
-                        return Err(TypeError::UndefinedVariable(name.clone()));
+                        return Err(TypeError::UndefinedVariable {
+                            name: name.clone(),
+                            suggestion: None,
+                        });
                     };
                     if !self.is_copyable(&bound_ty) {
                         return Err(TypeError::CannotInferType(format!(
@@ -4200,7 +6587,7 @@ impl TypeChecker {
                         )));
                     }
                 }
-                Stmt::Assignment(_) | Stmt::Expr(_) => {
+                Stmt::Assignment(_) | Stmt::Expr(_) | Stmt::Break(_) | Stmt::Continue => {
                     return Err(TypeError::CannotInferType(
                         "deferred callable branch prefixes support only replay-safe val bindings"
                             .to_string(),
@@ -4216,7 +6603,7 @@ impl TypeChecker {
                 .map(DeferredCallableCandidate::Lambda);
         }
 
-        let Some(expr) = self.block_terminal_expr(block) else {
+        let Some(expr) = block.expr.as_deref() else {
             return Err(TypeError::CannotInferType(
                 "deferred callable branch blocks require a callable result".to_string(),
             ));
@@ -4376,19 +6763,20 @@ impl TypeChecker {
             ExprKind::Match(match_expr) => {
                 self.branch_expr_has_terminal_lambda(expr)
                     && !match_expr.arms.is_empty()
-                    && match_expr
-                        .arms
-                        .iter()
-                        .all(|arm| self.block_result_is_deferred_callable(&arm.body))
+                    && match_expr.arms.iter().all(|arm| {
+                        arm.guard.as_ref().is_none_or(|guard| {
+                            self.expr_is_replay_safe_for_deferred_callable(guard)
+                        }) && self.block_result_is_deferred_callable(&arm.body)
+                    })
             }
             _ => false,
         }
     }
 
     fn block_result_is_deferred_callable(&self, block: &BlockExpr) -> bool {
-        self.block_terminal_expr(block).is_some()
-            && self
-                .deferred_callable_prefix_statements(block)
+        block.expr.is_some()
+            && block
+                .statements
                 .iter()
                 .all(|stmt| self.stmt_is_deferred_callable_prefix(stmt))
     }
@@ -4422,37 +6810,17 @@ impl TypeChecker {
     }
 
     fn block_terminal_lambda<'a>(&self, block: &'a BlockExpr) -> Option<&'a LambdaExpr> {
-        match self.block_terminal_expr(block).map(|expr| &expr.kind) {
+        match block.expr.as_deref().map(|expr| &expr.kind) {
             Some(ExprKind::Lambda(lambda)) => Some(lambda),
             _ => None,
         }
     }
 
-    fn block_terminal_expr<'a>(&self, block: &'a BlockExpr) -> Option<&'a Expr> {
-        if let Some(expr) = block.expr.as_deref() {
-            return Some(expr);
-        }
-
-        match block.statements.last() {
-            Some(Stmt::Expr(expr)) => Some(expr.as_ref()),
-            _ => None,
-        }
-    }
-
-    fn deferred_callable_prefix_statements<'a>(&self, block: &'a BlockExpr) -> &'a [Stmt] {
-        if block.expr.is_some() {
-            &block.statements
-        } else if matches!(block.statements.last(), Some(Stmt::Expr(_))) {
-            &block.statements[..block.statements.len() - 1]
-        } else {
-            &block.statements
-        }
-    }
-
     fn expr_is_replay_safe_for_deferred_callable(&self, expr: &Expr) -> bool {
         match &expr.kind {
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -4504,7 +6872,8 @@ impl TypeChecker {
             (
                 TypedType::Result(expected_ok, expected_err),
                 TypedType::Result(actual_ok, actual_err),
-            ) => {
+            )
+            | (TypedType::Map(expected_ok, expected_err), TypedType::Map(actual_ok, actual_err)) => {
                 self.type_matches_expected(expected_ok, actual_ok)
                     && self.type_matches_expected(expected_err, actual_err)
             }
@@ -4530,21 +6899,19 @@ impl TypeChecker {
                     name: expected_name,
                     type_args: expected_args,
                     frozen: expected_frozen,
-                    hash: expected_hash,
-                    parent_hash: expected_parent_hash,
+                    ..
                 },
                 TypedType::Record {
                     name: actual_name,
                     type_args: actual_args,
                     frozen: actual_frozen,
-                    hash: actual_hash,
-                    parent_hash: actual_parent_hash,
+                    ..
                 },
             ) => {
+                // Hashes only record which value a clone derives from, so a
+                // clone has the type of its record
                 expected_name == actual_name
                     && expected_frozen == actual_frozen
-                    && expected_hash == actual_hash
-                    && expected_parent_hash == actual_parent_hash
                     && expected_args.len() == actual_args.len()
                     && expected_args
                         .iter()
@@ -4584,23 +6951,27 @@ impl TypeChecker {
     ) -> Result<(), TypeError> {
         match pattern {
             Pattern::Ident(name) => {
-                // Simple variable binding
-                if let Ok((_existing_ty, _is_mutable)) = self.lookup_var_for_assignment(name) {
-                    // This is a reassignment
-                    self.reassign_var(name, ty)?;
-                } else {
-                    // This is a new binding
-                    self.bind_var(name.clone(), ty.clone(), mutable)?;
-                }
+                // `val` always introduces a new binding; reassignment is
+                // spelled `name = value`. `check_shadowing` has already
+                // rejected or warned about any earlier binding of `name`.
+                self.bind_var(name.clone(), ty.clone(), mutable)?;
+            }
+            Pattern::Binding(name, inner) => {
+                self.bind_var(name.clone(), ty.clone(), mutable)?;
+                self.bind_pattern(inner, ty, mutable)?;
+                self.link_pattern_aliases(name, inner);
             }
             Pattern::RecordDestruct {
                 type_name,
                 fields,
                 rest,
             } => {
-                // Record destructuring with spread
+                // Record destructuring with spread. An unnamed pattern
+                // (`val { x, ...rest } = p`) takes the value's record type.
                 match ty {
-                    TypedType::Record { name: rec_name, .. } if rec_name == type_name => {
+                    TypedType::Record { name: rec_name, .. }
+                        if type_name.is_empty() || rec_name == type_name =>
+                    {
                         let (_, instantiated_fields) = self.instantiated_record_fields(ty)?;
                         let field_types: Vec<(String, TypedType)> = fields
                             .iter()
@@ -4611,6 +6982,10 @@ impl TypeChecker {
                                     Err(TypeError::UnknownField {
                                         record: rec_name.clone(),
                                         field: field_name.clone(),
+                                        suggestion: closest_name(
+                                            field_name,
+                                            instantiated_fields.keys(),
+                                        ),
                                     })
                                 }
                             })
@@ -4621,7 +6996,7 @@ impl TypeChecker {
                             if rest_name == "_" {
                                 None
                             } else {
-                                Some(self.ensure_residual_record_type(type_name, fields, ty)?)
+                                Some(self.ensure_residual_record_type(rec_name, fields, ty)?)
                             }
                         } else {
                             None
@@ -4648,7 +7023,9 @@ impl TypeChecker {
             Pattern::Record(rec_name, fields) => {
                 // Old-style record pattern
                 match ty {
-                    TypedType::Record { name: ty_name, .. } if ty_name == rec_name => {
+                    TypedType::Record { name: ty_name, .. }
+                        if rec_name.is_empty() || ty_name == rec_name =>
+                    {
                         let (_, instantiated_fields) = self.instantiated_record_fields(ty)?;
                         let field_types: Vec<(String, TypedType)> = fields
                             .iter()
@@ -4659,6 +7036,10 @@ impl TypeChecker {
                                     Err(TypeError::UnknownField {
                                         record: ty_name.clone(),
                                         field: field_name.clone(),
+                                        suggestion: closest_name(
+                                            field_name,
+                                            instantiated_fields.keys(),
+                                        ),
                                     })
                                 }
                             })
@@ -4715,7 +7096,11 @@ impl TypeChecker {
                 }
                 _ => return Err(expected_type_mismatch("Result", ty)),
             },
-            Pattern::None | Pattern::EmptyList | Pattern::Wildcard | Pattern::Literal(_) => {
+            Pattern::None
+            | Pattern::EmptyList
+            | Pattern::Wildcard
+            | Pattern::Literal(_)
+            | Pattern::Range { .. } => {
                 // These patterns don't bind variables
             }
         }
@@ -4728,7 +7113,14 @@ impl TypeChecker {
             return Err(TypeError::ImmutableReassignment(assign.name.clone()));
         }
 
+        let shared_reads_before = self.shared_record_reads;
         let value_ty = self.check_expr_with_expected(&assign.value, Some(&target_ty))?;
+        self.check_arena_assignment_escape(&assign.name, &value_ty)?;
+        self.check_capability_assignment_escape(&assign.name, &value_ty)?;
+        if let Some(bound) = self.peek_var_bound(&assign.name) {
+            let range = self.static_int_range(&assign.value, &HashMap::new());
+            Self::require_within_bound(bound, range)?;
+        }
         let resolved_target_ty = if Self::contains_inference_internal_type(&target_ty)
             || Self::contains_inference_internal_type(&value_ty)
         {
@@ -4744,7 +7136,18 @@ impl TypeChecker {
             target_ty
         };
 
-        self.reassign_var(&assign.name, &resolved_target_ty)
+        self.reassign_var(&assign.name, &resolved_target_ty)?;
+        // The new value replaces whatever the old one shared
+        let shared = self.shared_record_reads != shared_reads_before;
+        if let Some(var) = self
+            .var_env
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(&assign.name))
+        {
+            var.shared = shared;
+        }
+        Ok(())
     }
 
     fn impl_method_param_types(
@@ -4759,7 +7162,11 @@ impl TypeChecker {
             let ty = if i == 0 && param.name == "self" {
                 TypedType::Record {
                     name: target.to_string(),
-                    type_args: Vec::new(),
+                    type_args: self
+                        .record_value_type_params(target)
+                        .into_iter()
+                        .map(|param| TypedType::TypeParam(param.name))
+                        .collect(),
                     frozen: false,
                     hash: None,
                     parent_hash: None,
@@ -4780,7 +7187,21 @@ impl TypeChecker {
             )));
         };
 
-        if param.name != "self" || param.ty != crate::ast::Type::Named(target.to_string()) {
+        // A generic record's receiver may spell out its own type parameters,
+        // as in `self: Box<T>`.
+        let record_params = self.record_value_type_params(target);
+        let generic_receiver = !record_params.is_empty()
+            && param.ty
+                == crate::ast::Type::Generic(
+                    target.to_string(),
+                    record_params
+                        .iter()
+                        .map(|param| crate::ast::Type::Named(param.name.clone()))
+                        .collect(),
+                );
+        if param.name != "self"
+            || (param.ty != crate::ast::Type::Named(target.to_string()) && !generic_receiver)
+        {
             return Err(TypeError::UnsupportedFeature(format!(
                 "Impl method '{}' for record '{}' must declare first parameter as self: {}",
                 func.name, target, target
@@ -4790,6 +7211,45 @@ impl TypeChecker {
         Ok(())
     }
 
+    /// Non-temporal type parameters declared on a record, which are in scope
+    /// for every method of its impl block.
+    fn record_value_type_params(&self, record_name: &str) -> Vec<TypeParam> {
+        self.records
+            .get(record_name)
+            .map(|record| {
+                record
+                    .type_params
+                    .iter()
+                    .filter(|param| !param.is_temporal)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The record's type parameters followed by the method's own, so a call
+    /// infers both from the receiver and the remaining arguments.
+    fn impl_method_type_params(
+        &self,
+        target: &str,
+        func: &FunDecl,
+    ) -> Result<Vec<TypeParam>, TypeError> {
+        let mut type_params = self.record_value_type_params(target);
+        for param in &func.type_params {
+            if type_params
+                .iter()
+                .any(|existing| existing.name == param.name)
+            {
+                return Err(TypeError::UnsupportedFeature(format!(
+                    "method '{}.{}' redeclares type parameter '{}' of record '{}'",
+                    target, func.name, param.name, target
+                )));
+            }
+            type_params.push(param.clone());
+        }
+        Ok(type_params)
+    }
+
     fn register_impl_method_signatures(&mut self, impl_block: &ImplBlock) -> Result<(), TypeError> {
         if !self.records.contains_key(&impl_block.target) {
             return Err(TypeError::UndefinedRecord(impl_block.target.clone()));
@@ -4809,7 +7269,8 @@ impl TypeChecker {
                 )));
             }
 
-            self.push_type_param_scope(&func.type_params);
+            let type_params = self.impl_method_type_params(&target, func)?;
+            self.push_type_param_scope(&type_params);
             let signature_result = (|| {
                 let param_types = self.impl_method_param_types(&target, func)?;
                 let return_type = if let Some(return_type) = &func.return_type {
@@ -4821,7 +7282,7 @@ impl TypeChecker {
                 Ok(FunctionDef {
                     params: param_types,
                     return_type,
-                    type_params: func.type_params.clone(),
+                    type_params,
                     temporal_constraints: func
                         .temporal_constraints
                         .iter()
@@ -4858,8 +7319,22 @@ impl TypeChecker {
         let target = impl_block.target.clone();
 
         for func in &impl_block.functions {
+            if func
+                .params
+                .iter()
+                .map(|param| &param.ty)
+                .chain(&func.return_type)
+                .any(|ty| Self::refinement_bound(ty).is_some())
+            {
+                return Err(TypeError::UnsupportedFeature(format!(
+                    "method '{}.{}' uses a range-restricted type; ranges are supported on functions and bindings only",
+                    target, func.name
+                )));
+            }
+
             // Check the method, but with special handling for 'self' parameter
-            self.push_type_param_scope(&func.type_params);
+            let type_params = self.impl_method_type_params(&target, func)?;
+            self.push_type_param_scope(&type_params);
             self.push_scope();
 
             let param_types = self.impl_method_param_types(&target, func)?;
@@ -4873,7 +7348,7 @@ impl TypeChecker {
                 .map(|return_type| self.convert_type(return_type))
                 .transpose()?;
             let body_return_type =
-                self.check_block_expr_with_expected(&func.body, expected_return_type.as_ref())?;
+                self.check_function_body(&func.body, expected_return_type.as_ref())?;
 
             if let Some(expected_return_type) = &expected_return_type {
                 if !self.type_matches_expected(expected_return_type, &body_return_type) {
@@ -4892,7 +7367,7 @@ impl TypeChecker {
                 FunctionDef {
                     params: param_types,
                     return_type,
-                    type_params: func.type_params.clone(),
+                    type_params,
                     temporal_constraints: func
                         .temporal_constraints
                         .iter()
@@ -4920,8 +7395,7 @@ impl TypeChecker {
             fields.insert(field.name.clone(), ty);
         }
 
-        // Add to available contexts
-        self._contexts.push(context.name.clone());
+        self.context_names.insert(context.name.clone());
 
         // Store as a special record type for field access
         self.records.insert(
@@ -4929,6 +7403,7 @@ impl TypeChecker {
             RecordDef {
                 fields,
                 field_order,
+                defaulted_fields: HashSet::new(),
                 type_params: vec![],
                 temporal_constraints: vec![],
                 hash: None,
@@ -4974,6 +7449,7 @@ impl TypeChecker {
             match &expr.kind {
                 ExprKind::IntLit(value) => self.check_int_lit(*value, expected),
                 ExprKind::FloatLit(_) => Ok(TypedType::Float64),
+                ExprKind::DecimalLit(_) => Ok(TypedType::Decimal),
                 ExprKind::StringLit(_) => Ok(TypedType::String),
                 ExprKind::CharLit(_) => Ok(TypedType::Char),
                 ExprKind::BoolLit(_) => Ok(TypedType::Boolean),
@@ -5014,6 +7490,7 @@ impl TypeChecker {
                             // zero-argument function must still be invoked with the
                             // OSV unit form `() function` when a value is expected.
                             if let Some(func_def) = self.functions.get(name).cloned() {
+                                self.check_capability(name)?;
                                 if self.provisional_function_returns.contains(name) {
                                     return Err(TypeError::CannotInferType(format!(
                                     "function '{}' is used before its return type has been inferred; add an explicit return annotation",
@@ -5035,7 +7512,12 @@ impl TypeChecker {
                                 if matches!(name.as_str(), "some" | "none") {
                                     return Err(lowercase_option_constructor_error(name));
                                 }
-                                Err(e) // Return the original error
+                                match e {
+                                    TypeError::UndefinedVariable { .. } => {
+                                        Err(self.undefined_variable(name))
+                                    }
+                                    e => Err(e), // Return the original error
+                                }
                             }
                         }
                     }
@@ -5046,9 +7528,14 @@ impl TypeChecker {
                 ExprKind::Clone(clone_expr) => self.check_clone_expr(clone_expr),
                 ExprKind::Freeze(expr) => self.check_freeze_expr(expr),
                 ExprKind::FieldAccess(expr, field) => self.check_field_access(expr, field),
+                ExprKind::FieldUpdate(update) => self.check_field_update(update),
                 ExprKind::Call(call) => self.check_call_expr_with_expected(call, expected),
                 ExprKind::Block(block) => self.check_block_expr_with_expected(block, expected),
-                ExprKind::Binary(binary) => self.check_binary_expr(binary, expected),
+                ExprKind::Binary(binary) => {
+                    let ty = self.check_binary_expr(binary, expected)?;
+                    Self::check_constant_overflow(binary, &ty)?;
+                    Ok(ty)
+                }
                 ExprKind::Unary(unary) => self.check_unary_expr(unary, expected),
                 ExprKind::Cast(cast) => self.check_cast_expr(cast),
                 ExprKind::Pipe(pipe) => self.check_pipe_expr_with_expected(pipe, expected),
@@ -5058,6 +7545,8 @@ impl TypeChecker {
                 }
                 ExprKind::Then(then) => self.check_then_expr_with_expected(then, expected),
                 ExprKind::While(while_expr) => self.check_while_expr(while_expr),
+                ExprKind::Loop(body) => self.check_loop_expr(body, expected),
+                ExprKind::WhileLet(while_let) => self.check_while_let_expr(while_let),
                 ExprKind::Match(match_expr) => {
                     self.check_match_expr_with_expected(match_expr, expected)
                 }
@@ -5131,6 +7620,7 @@ impl TypeChecker {
                         "Err requires an expected Result type".to_string(),
                     )),
                 },
+                ExprKind::Try(operand) => self.check_try_expr(operand, expected),
                 ExprKind::Lambda(lambda) => self.check_lambda_expr(lambda, expected),
                 ExprKind::PrototypeClone(proto_clone) => {
                     self.check_prototype_clone_expr(proto_clone)
@@ -5147,6 +7637,53 @@ impl TypeChecker {
         result
     }
 
+    /// `operand?` evaluates to the `Some`/`Ok` payload and otherwise returns
+    /// the `None`/`Err` from the enclosing function, which must therefore
+    /// return an `Option`, or a `Result` with the same error type.
+    fn check_try_expr(
+        &mut self,
+        operand: &Expr,
+        expected: Option<&TypedType>,
+    ) -> Result<TypedType, TypeError> {
+        let function_return = self.try_return_types.last().cloned().flatten();
+        let operand_expected = match (&function_return, expected) {
+            (Some(TypedType::Option(_)), Some(value)) => {
+                Some(TypedType::Option(Box::new(value.clone())))
+            }
+            (Some(TypedType::Result(_, err)), Some(value)) => {
+                Some(TypedType::Result(Box::new(value.clone()), err.clone()))
+            }
+            _ => None,
+        };
+        let operand_type = self.check_expr_with_expected(operand, operand_expected.as_ref())?;
+
+        let (value, required, compatible) = match &operand_type {
+            TypedType::Option(value) => (
+                value,
+                "Option<_>".to_string(),
+                matches!(function_return, Some(TypedType::Option(_))),
+            ),
+            TypedType::Result(value, err) => (
+                value,
+                format!("Result<_, {}>", format_typed_type(err)),
+                matches!(
+                    &function_return,
+                    Some(TypedType::Result(_, return_err))
+                        if self.type_matches_expected(return_err, err)
+                ),
+            ),
+            other => return Err(expected_type_mismatch("Option or Result", other)),
+        };
+        if !compatible {
+            return Err(TypeError::InvalidTry {
+                operand: format_typed_type(&operand_type),
+                required,
+                found: function_return.as_ref().map(format_typed_type),
+            });
+        }
+        Ok((**value).clone())
+    }
+
     fn expected_record_type_args(
         expected: Option<&TypedType>,
         record_name: &str,
@@ -5170,6 +7707,7 @@ impl TypeChecker {
         // First check if record exists and collect field types
         let (
             field_types,
+            defaulted_fields,
             type_params,
             temporal_constraints,
             record_hash,
@@ -5181,6 +7719,7 @@ impl TypeChecker {
                 .ok_or_else(|| TypeError::UndefinedRecord(record_lit.name.clone()))?;
             (
                 record_def.fields.clone(),
+                record_def.defaulted_fields.clone(),
                 record_def.type_params.clone(),
                 record_def.temporal_constraints.clone(),
                 record_def.hash.clone(),
@@ -5234,6 +7773,7 @@ impl TypeChecker {
                         TypeError::UnknownField {
                             record: record_lit.name.clone(),
                             field: name.clone(),
+                            suggestion: closest_name(name, instantiated_field_types.keys()),
                         }
                     })?;
                     let expected_ty = field_substitution.apply(expected_ty)?;
@@ -5307,7 +7847,7 @@ impl TypeChecker {
 
         if !has_spread {
             for field_name in instantiated_field_types.keys() {
-                if !provided_fields.contains(field_name) {
+                if !provided_fields.contains(field_name) && !defaulted_fields.contains(field_name) {
                     return Err(TypeError::MissingField {
                         record: record_lit.name.clone(),
                         field: field_name.clone(),
@@ -5439,14 +7979,19 @@ impl TypeChecker {
     }
 
     fn check_clone_expr(&mut self, clone_expr: &CloneExpr) -> Result<TypedType, TypeError> {
-        let base_ty = self.check_expr(&clone_expr.base)?;
+        // Cloning copies the record, so a `mut val` base is only read
+        let base_ty = match self.mutable_place_type(&clone_expr.base)? {
+            Some(ty) => ty,
+            None => self.check_expr(&clone_expr.base)?,
+        };
 
         match &base_ty {
             TypedType::Record {
                 name,
                 type_args,
                 frozen,
-                ..
+                hash,
+                parent_hash,
             } => {
                 if *frozen {
                     return Err(TypeError::CloneFrozenRecord);
@@ -5485,6 +8030,7 @@ impl TypeChecker {
                                 TypeError::UnknownField {
                                     record: name.clone(),
                                     field: field_name.clone(),
+                                    suggestion: closest_name(field_name, field_types.keys()),
                                 }
                             })?;
 
@@ -5527,12 +8073,40 @@ impl TypeChecker {
                     &final_field_sources,
                     "record clone",
                 )?;
+
+                // A clone of a prototype-derived record extends its prototype
+                // chain; clones of plain records stay plain records
+                let derived = hash.is_some()
+                    || parent_hash.is_some()
+                    || self
+                        .prototypes
+                        .get(name)
+                        .is_some_and(|(_, parent, _)| parent.is_some());
+                if !derived {
+                    return Ok(TypedType::Record {
+                        name: name.clone(),
+                        type_args: type_args.clone(),
+                        frozen: false,
+                        hash: None,
+                        parent_hash: None,
+                    });
+                }
+                let (base_hash, base_depth) = self.record_derivation_link(name, hash.as_ref())?;
+                let depth = base_depth + 1;
+                if depth > 3 {
+                    return Err(TypeError::DerivationTooDeep(depth));
+                }
+                let content = format!("{}:{:?}", base_hash, clone_expr.updates);
+                let clone_hash = self.generate_prototype_hash(name, &content);
+                self.record_clones
+                    .insert(clone_hash.clone(), (base_hash.clone(), depth));
+
                 Ok(TypedType::Record {
                     name: name.clone(),
                     type_args: type_args.clone(),
                     frozen: false,
-                    hash: None,
-                    parent_hash: None,
+                    hash: Some(clone_hash),
+                    parent_hash: Some(base_hash),
                 })
             }
             other => Err(expected_type_mismatch("record", other)),
@@ -5540,6 +8114,14 @@ impl TypeChecker {
     }
 
     fn check_freeze_expr(&mut self, expr: &Expr) -> Result<TypedType, TypeError> {
+        // Freezing shares the record rather than copying it, so the operand
+        // must not remain reachable for `<-` updates.
+        if let Some(name) = Self::field_place_root(expr) {
+            if matches!(self.lookup_var_for_assignment(name), Ok((_, true))) {
+                return Err(TypeError::FreezeMutableBinding(name.to_string()));
+            }
+        }
+
         let ty = self.check_expr(expr)?;
 
         match ty {
@@ -5665,6 +8247,10 @@ impl TypeChecker {
                 Box::new(self.lower_associated_type_projections(*ok, constraints, origin.clone())),
                 Box::new(self.lower_associated_type_projections(*err, constraints, origin)),
             ),
+            TypedType::Map(key, value) => TypedType::Map(
+                Box::new(self.lower_associated_type_projections(*key, constraints, origin.clone())),
+                Box::new(self.lower_associated_type_projections(*value, constraints, origin)),
+            ),
             TypedType::Function {
                 params,
                 return_type,
@@ -5717,6 +8303,15 @@ impl TypeChecker {
             });
         }
 
+        let explicit_type_params = Self::regular_type_param_names(&func_info.type_params);
+        if !call.type_args.is_empty() && call.type_args.len() != explicit_type_params.len() {
+            return Err(TypeError::TypeArgumentCountMismatch {
+                function: Self::call_constraint_name(call),
+                expected: explicit_type_params.len(),
+                found: call.type_args.len(),
+            });
+        }
+
         // If the function is not generic, use simple type checking
         if func_info.type_params.is_empty() {
             let param_types: Vec<TypedType> =
@@ -5787,6 +8382,24 @@ impl TypeChecker {
             }),
         );
 
+        // Explicit type arguments pin their parameters before anything is
+        // inferred, so a conflicting argument or return context is an error.
+        for (type_param, type_arg) in explicit_type_params.iter().zip(&call.type_args) {
+            let explicit = self.convert_type(type_arg)?;
+            if let Some(ty) = type_vars.get(type_param) {
+                self.solve_type_constraint(
+                    &mut constraints,
+                    &mut substitution,
+                    ty.clone(),
+                    explicit,
+                    Self::constraint_origin(ConstraintKind::TypeArgument {
+                        func_name: func_name.clone(),
+                        type_param: type_param.clone(),
+                    }),
+                )?;
+            }
+        }
+
         self.seed_constrained_apply_return(
             &mut constraints,
             &mut substitution,
@@ -5820,7 +8433,18 @@ impl TypeChecker {
                     if Self::is_form_bound(&bound.trait_name) {
                         continue;
                     }
-                    if !self.type_implements_trait(&concrete_type, &bound.trait_name) {
+                    let implements = match &concrete_type {
+                        // assert_eq/assert_ne compare with `==`, which
+                        // records support structurally.
+                        TypedType::Record { name, .. }
+                            if bound.trait_name == "Eq"
+                                && matches!(func_name.as_str(), "assert_eq" | "assert_ne") =>
+                        {
+                            self.record_implements_eq(name, &mut HashSet::new())
+                        }
+                        _ => self.type_implements_trait(&concrete_type, &bound.trait_name),
+                    };
+                    if !implements {
                         return Err(TypeError::UnsupportedFeature(format!(
                             "Type {} does not implement trait {}",
                             format_typed_type(&concrete_type),
@@ -5843,6 +8467,24 @@ impl TypeChecker {
             expected_return,
             &func_name,
         )?;
+        if self.tracing() {
+            for type_param in &func_info.type_params {
+                let Some(ty) = type_vars
+                    .get(&type_param.name)
+                    .and_then(|ty| finalize_type(ty, &substitution).ok())
+                else {
+                    continue;
+                };
+                if Self::contains_inference_internal_type(&ty) {
+                    continue;
+                }
+                self.trace(TraceEvent::GenericInstantiated {
+                    function: func_name.clone(),
+                    param: type_param.name.clone(),
+                    ty,
+                });
+            }
+        }
         self.apply_substitution_to_var_env(&substitution)?;
         for (arg, actual_ty) in call.args.iter().zip(checked_arg_types.iter()) {
             let resolved =
@@ -6055,7 +8697,7 @@ impl TypeChecker {
             self.bind_var(param.name.clone(), param_type, false)?;
         }
 
-        let body_result = self.check_expr_with_expected(&lambda.body, Some(return_type.as_ref()));
+        let body_result = self.check_lambda_body(&lambda.body, Some(return_type.as_ref()));
         let observed_param_types = lambda
             .params
             .iter()
@@ -6346,13 +8988,27 @@ impl TypeChecker {
     }
 
     fn check_field_access(&mut self, expr: &Expr, field: &str) -> Result<TypedType, TypeError> {
-        if let ExprKind::Ident(name) = &expr.kind {
-            let var = self._peek_var(name)?.clone();
-            let field_ty = self.record_field_type(&var.ty, field)?;
+        if let Some(record_ty) = self.mutable_place_type(expr)? {
+            let field_ty =
+                Self::inherit_frozen(&record_ty, self.record_field_type(&record_ty, field)?);
+            // Reading a record out of a `mut val` shares it with the reader
+            if Self::holds_record_value(&field_ty) {
+                if let Some(root) = Self::field_place_root(expr) {
+                    if self._peek_var(root)?.shared {
+                        self.shared_record_reads += 1;
+                    }
+                    self.mark_var_moved(root);
+                }
+            }
+            return Ok(field_ty);
+        }
 
-            if var.mutable {
-                return Ok(field_ty);
+        if let ExprKind::Ident(name) = &expr.kind {
+            if self.context_names.contains(name) && self._peek_var(name).is_err() {
+                return self.check_context_field_access(name, field);
             }
+            let var = self._peek_var(name)?.clone();
+            let field_ty = Self::inherit_frozen(&var.ty, self.record_field_type(&var.ty, field)?);
 
             if var.used {
                 return Err(TypeError::AffineViolation(name.clone()));
@@ -6369,20 +9025,203 @@ impl TypeChecker {
         }
 
         let ty = self.check_expr(expr)?;
-        self.record_field_type(&ty, field)
+        let field_ty = self.record_field_type(&ty, field)?;
+        Ok(Self::inherit_frozen(&ty, field_ty))
     }
 
-    fn record_field_type(&self, ty: &TypedType, field: &str) -> Result<TypedType, TypeError> {
-        // Handle temporal types by unwrapping to the base type
-        let base_ty = match ty {
-            TypedType::Temporal { base_type, .. } => base_type.as_ref(),
-            _ => ty,
-        };
-
-        match base_ty {
-            TypedType::Record {
-                name, type_args, ..
-            } => {
+    /// The type of a field path rooted at a `mut val`, read without moving
+    /// the binding, or `None` when `expr` is not such a path.
+    fn mutable_place_type(&mut self, expr: &Expr) -> Result<Option<TypedType>, TypeError> {
+        match &expr.kind {
+            ExprKind::Ident(name) => match self._peek_var(name) {
+                Ok(var) if var.mutable => {
+                    if var.moved && Self::is_record_value(&var.ty) {
+                        return Err(TypeError::AffineViolation(name.clone()));
+                    }
+                    Ok(Some(var.ty.clone()))
+                }
+                _ => Ok(None),
+            },
+            ExprKind::FieldAccess(inner, field) => match self.mutable_place_type(inner)? {
+                Some(record_ty) => {
+                    let field_ty = self.record_field_type(&record_ty, field)?;
+                    Ok(Some(Self::inherit_frozen(&record_ty, field_ty)))
+                }
+                None => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn mark_var_moved(&mut self, name: &str) {
+        if let Some(var) = self
+            .var_env
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            var.moved = true;
+        }
+    }
+
+    /// Freezing is transitive: a record field read from a frozen record is
+    /// itself frozen, so `outer.inner.clone { }` is rejected like cloning
+    /// `outer` would be.
+    fn inherit_frozen(record_ty: &TypedType, field_ty: TypedType) -> TypedType {
+        match (record_ty, field_ty) {
+            (
+                TypedType::Record { frozen: true, .. },
+                TypedType::Record {
+                    name,
+                    type_args,
+                    hash,
+                    parent_hash,
+                    ..
+                },
+            ) => TypedType::Record {
+                name,
+                type_args,
+                frozen: true,
+                hash,
+                parent_hash,
+            },
+            (_, field_ty) => field_ty,
+        }
+    }
+
+    /// Check `Context.field`, which reads the field bound by the innermost
+    /// enclosing `with Context { ... }` expression.
+    fn check_context_field_access(
+        &mut self,
+        context: &str,
+        field: &str,
+    ) -> Result<TypedType, TypeError> {
+        let declared = self
+            .records
+            .get(context)
+            .is_some_and(|record| record.fields.contains_key(field));
+        if !declared {
+            return Err(TypeError::UnknownField {
+                record: context.to_string(),
+                field: field.to_string(),
+                suggestion: self
+                    .records
+                    .get(context)
+                    .and_then(|record| closest_name(field, record.fields.keys())),
+            });
+        }
+        if !self.is_context_active(context) {
+            return Err(TypeError::UnavailableContext(context.to_string()));
+        }
+
+        let binding = self
+            .context_binding_scopes
+            .iter()
+            .rev()
+            .find(|(name, _)| name == context)
+            .and_then(|(_, scope_idx)| {
+                self.var_env[*scope_idx]
+                    .get(field)
+                    .map(|var| (*scope_idx, var.clone()))
+            });
+        match binding {
+            Some((scope_idx, var)) => self.use_var_at(scope_idx, field, &var),
+            None => Err(TypeError::UnavailableContext(format!(
+                "{}.{}",
+                context, field
+            ))),
+        }
+    }
+
+    /// Check `object.field <- value`. The object must be a field path rooted
+    /// at a `mut` binding with no frozen record along the way, and the value
+    /// must match the field type. Resolving the path does not consume the
+    /// root binding.
+    fn check_field_update(&mut self, update: &FieldUpdateExpr) -> Result<TypedType, TypeError> {
+        let record_ty = self.field_update_target_type(&update.object)?;
+        Self::require_unfrozen(&record_ty, &format!("update field `{}`", update.field))?;
+        let field_ty = self.record_field_type(&record_ty, &update.field)?;
+        let value_ty = self.check_expr_with_expected(&update.value, Some(&field_ty))?;
+        if !self.type_matches_expected(&field_ty, &value_ty) {
+            return Err(typed_type_mismatch(&field_ty, &value_ty));
+        }
+        // The updated record lives as long as its root binding
+        if let Some(root) = Self::field_place_root(&update.object) {
+            self.check_arena_assignment_escape(root, &value_ty)?;
+            self.check_capability_assignment_escape(root, &value_ty)?;
+        }
+        Ok(TypedType::Unit)
+    }
+
+    /// The variable at the root of a `name.field.field` place expression.
+    fn field_place_root(expr: &Expr) -> Option<&str> {
+        match &expr.kind {
+            ExprKind::Ident(name) => Some(name),
+            ExprKind::FieldAccess(inner, _) => Self::field_place_root(inner),
+            _ => None,
+        }
+    }
+
+    fn field_update_target_type(&mut self, object: &Expr) -> Result<TypedType, TypeError> {
+        let ty = match &object.kind {
+            ExprKind::Ident(name) => {
+                let (ty, mutable) = self.lookup_var_for_assignment(name)?;
+                if !mutable {
+                    return Err(TypeError::ImmutableFieldUpdate(name.clone()));
+                }
+                // After a move another binding may share the record
+                let var = self._peek_var(name)?;
+                if var.moved {
+                    return Err(TypeError::AffineViolation(name.clone()));
+                }
+                if var.shared {
+                    return Err(TypeError::SharedFieldUpdate(name.clone()));
+                }
+                ty
+            }
+            ExprKind::FieldAccess(inner, field) => {
+                let inner_ty = self.field_update_target_type(inner)?;
+                self.record_field_type(&inner_ty, field)?
+            }
+            _ => return Err(TypeError::UnsupportedFeature(
+                "field updates must target a field of a `mut val` binding, such as `point.x <- 1`"
+                    .to_string(),
+            )),
+        };
+
+        Ok(ty)
+    }
+
+    /// Reject `operation` when `ty` is a frozen record. Every path that can
+    /// change a record in place goes through here, so frozen values stay
+    /// immutable however they are reached.
+    fn require_unfrozen(ty: &TypedType, operation: &str) -> Result<(), TypeError> {
+        let base_ty = match ty {
+            TypedType::Temporal { base_type, .. } => base_type.as_ref(),
+            _ => ty,
+        };
+        match base_ty {
+            TypedType::Record {
+                name, frozen: true, ..
+            } => Err(TypeError::MutationOfFrozen {
+                record: name.clone(),
+                operation: operation.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_field_type(&self, ty: &TypedType, field: &str) -> Result<TypedType, TypeError> {
+        // Handle temporal types by unwrapping to the base type
+        let base_ty = match ty {
+            TypedType::Temporal { base_type, .. } => base_type.as_ref(),
+            _ => ty,
+        };
+
+        match base_ty {
+            TypedType::Record {
+                name, type_args, ..
+            } => {
                 let record_def = self
                     .records
                     .get(name)
@@ -6395,6 +9234,7 @@ impl TypeChecker {
                         .ok_or_else(|| TypeError::UnknownField {
                             record: name.clone(),
                             field: field.to_string(),
+                            suggestion: closest_name(field, record_def.fields.keys()),
                         })?;
                 Ok(Self::apply_type_arg_bindings(field_ty, &bindings))
             }
@@ -6407,6 +9247,21 @@ impl TypeChecker {
         call: &CallExpr,
         expected_return: Option<&TypedType>,
     ) -> Result<TypedType, TypeError> {
+        if !call.type_args.is_empty() {
+            let names_function = match &call.function.kind {
+                ExprKind::Ident(name) => {
+                    self.peek_var_type(name).is_none() && self.functions.contains_key(name)
+                }
+                _ => false,
+            };
+            if !names_function {
+                return Err(TypeError::UnsupportedFeature(format!(
+                    "explicit type arguments are only supported on calls to declared functions, not {}",
+                    Self::call_constraint_name(call)
+                )));
+            }
+        }
+
         // First check the function expression type
         match &call.function.kind {
             ExprKind::Ident(name) => {
@@ -6420,7 +9275,7 @@ impl TypeChecker {
                             expected_return,
                         );
                     }
-                    Err(TypeError::UndefinedVariable(_)) => {}
+                    Err(TypeError::UndefinedVariable { .. }) => {}
                     Err(err) => return Err(err),
                 }
 
@@ -6462,6 +9317,7 @@ impl TypeChecker {
 
                 // Otherwise try to find a regular function
                 if let Some(func_info) = self.functions.get(name).cloned() {
+                    self.check_capability(name)?;
                     if self.provisional_function_returns.contains(name) {
                         return Err(TypeError::CannotInferType(format!(
                             "function '{}' is used before its return type has been inferred; add an explicit return annotation",
@@ -6476,7 +9332,10 @@ impl TypeChecker {
                             "Internal error: spawn/await should be handled earlier".to_string(),
                         ));
                     }
-                    self.check_function_call_with_inference(&func_info, call, expected_return)
+                    let return_type =
+                        self.check_function_call_with_inference(&func_info, call, expected_return)?;
+                    self.check_call_refinements(name, &call.args)?;
+                    Ok(return_type)
                 } else {
                     if matches!(name.as_str(), "some" | "none") {
                         return Err(lowercase_option_constructor_error(name));
@@ -6486,10 +9345,23 @@ impl TypeChecker {
                         return Ok(return_type);
                     }
 
-                    Err(TypeError::UndefinedFunction(name.clone()))
+                    Err(self.undefined_function(name))
                 }
             }
             ExprKind::FieldAccess(obj_expr, method_name) => {
+                // A qualified method `Record.method` takes its receiver as the
+                // first argument, as `value |> Record.method` desugars.
+                if let Some((record_name, method_info)) =
+                    self.qualified_method(obj_expr, method_name)
+                {
+                    return self.check_method_info_call(
+                        &record_name,
+                        method_name,
+                        method_info,
+                        &call.args,
+                    );
+                }
+
                 // Parser-level field access can still appear in callable position.
                 // Function-typed fields are first-class callable values; otherwise
                 // the public method form remains OSV: `(receiver, args...) method`.
@@ -6534,8 +9406,6 @@ impl TypeChecker {
     ) -> Result<TypedType, TypeError> {
         self.push_scope();
 
-        let mut last_expr_type = None;
-
         for (i, stmt) in block.statements.iter().enumerate() {
             match stmt {
                 Stmt::Binding(bind) => {
@@ -6570,31 +9440,69 @@ impl TypeChecker {
                 }
                 Stmt::Assignment(assign) => self.check_assignment(assign)?,
                 Stmt::Expr(expr) => {
-                    let ty = self.check_expr(expr)?;
-                    // Keep track of the last expression's type
-                    if i == block.statements.len() - 1 {
-                        last_expr_type = Some(ty);
+                    self.check_expr(expr)?;
+                }
+                Stmt::Break(value) => self.check_break(value.as_deref())?,
+                Stmt::Continue => {
+                    if self.loop_frames.is_empty() {
+                        return Err(TypeError::InvalidLoopControl(
+                            "`continue` can only be used inside a loop".to_string(),
+                        ));
                     }
                 }
             }
         }
 
-        let result = if let Some(expr) = &block.expr {
-            self.check_expr_with_expected(expr, expected)?
-        } else if let Some(ty) = last_expr_type {
-            // If no explicit return expression but last statement was an expression,
-            // use its type as the block's type
-            ty
-        } else {
-            TypedType::Unit
+        // Only a trailing expression without `;` gives the block a value.
+        let result = match &block.expr {
+            Some(expr) => self.check_expr_with_expected(expr, expected)?,
+            None => TypedType::Unit,
         };
 
+        self.warn_unused_bindings(block);
+
         let unresolved_result = self.reject_unresolved_inference_in_current_scope();
         self.pop_scope();
         unresolved_result?;
         Ok(result)
     }
 
+    /// Warn about immutable `val` bindings in `block` that no later statement
+    /// or the block result reads. Names starting with `_` opt out. Must run
+    /// while the block's scope is still active.
+    fn warn_unused_bindings(&mut self, block: &BlockExpr) {
+        for (i, stmt) in block.statements.iter().enumerate() {
+            let Stmt::Binding(bind) = stmt else {
+                continue;
+            };
+            let Pattern::Ident(name) = &bind.pattern else {
+                continue;
+            };
+            if bind.mutable || name.starts_with('_') {
+                continue;
+            }
+            if !self.block_tail_reads(name, &block.statements[i + 1..], block.expr.as_deref()) {
+                self.warn(Warning::UnusedBinding(name.clone()));
+            }
+        }
+    }
+
+    /// Whether `name` is read by `statements` or `final_expr`.
+    fn block_tail_reads(&self, name: &str, statements: &[Stmt], final_expr: Option<&Expr>) -> bool {
+        let bound_vars = HashSet::new();
+        let reads = |expr: &Expr| {
+            self.collect_free_variables(expr, &bound_vars)
+                .contains(name)
+        };
+
+        statements.iter().any(|stmt| match stmt {
+            Stmt::Binding(bind) => reads(&bind.value),
+            Stmt::Assignment(assign) => reads(&assign.value),
+            Stmt::Expr(expr) | Stmt::Break(Some(expr)) => reads(expr),
+            Stmt::Break(None) | Stmt::Continue => false,
+        }) || final_expr.is_some_and(reads)
+    }
+
     fn infer_unannotated_binding_expected_type_from_later_context(
         &mut self,
         name: &str,
@@ -6831,17 +9739,15 @@ impl TypeChecker {
         substitution: &mut ConstraintSubstitution,
     ) -> Result<(), TypeError> {
         match &expr.kind {
-            ExprKind::FieldAccess(object, field) => {
-                if Self::expr_is_ident(object, binding_name) {
-                    if let Some(field_ty) = expected {
-                        self.bind_record_binding_field_expected_type(
-                            record_name,
-                            field,
-                            field_ty,
-                            type_arg_bindings,
-                            substitution,
-                        )?;
-                    }
+            ExprKind::FieldAccess(object, field) if Self::expr_is_ident(object, binding_name) => {
+                if let Some(field_ty) = expected {
+                    self.bind_record_binding_field_expected_type(
+                        record_name,
+                        field,
+                        field_ty,
+                        type_arg_bindings,
+                        substitution,
+                    )?;
                 }
             }
             ExprKind::Match(match_expr) => {
@@ -6921,15 +9827,6 @@ impl TypeChecker {
                 type_arg_bindings,
                 substitution,
             )?;
-        } else if let Some(Stmt::Expr(expr)) = block.statements.last() {
-            self.bind_record_binding_expected_type_params_from_expr(
-                binding_name,
-                record_name,
-                expr,
-                expected,
-                type_arg_bindings,
-                substitution,
-            )?;
         }
         Ok(())
     }
@@ -7052,10 +9949,6 @@ impl TypeChecker {
             return self.expected_type_for_ident_in_expr(name, expr, expected);
         }
 
-        if let Some(Stmt::Expr(expr)) = block.statements.last() {
-            return self.expected_type_for_ident_in_expr(name, expr, expected);
-        }
-
         Ok(None)
     }
 
@@ -7070,10 +9963,8 @@ impl TypeChecker {
         }
 
         match &expr.kind {
-            ExprKind::Pipe(pipe) => {
-                if Self::expr_is_ident(&pipe.expr, name) {
-                    return self.expected_type_for_pipe_target_first_arg(&pipe.target);
-                }
+            ExprKind::Pipe(pipe) if Self::expr_is_ident(&pipe.expr, name) => {
+                return self.expected_type_for_pipe_target_first_arg(&pipe.target);
             }
             ExprKind::Call(call) => {
                 if let ExprKind::Ident(func_name) = &call.function.kind {
@@ -7189,6 +10080,7 @@ impl TypeChecker {
         match &expr.kind {
             ExprKind::IntLit(value) => Some(Self::int_literal_type(*value)),
             ExprKind::FloatLit(_) => Some(TypedType::Float64),
+            ExprKind::DecimalLit(_) => Some(TypedType::Decimal),
             ExprKind::StringLit(_) => Some(TypedType::String),
             ExprKind::CharLit(_) => Some(TypedType::Char),
             ExprKind::BoolLit(_) => Some(TypedType::Boolean),
@@ -7267,6 +10159,9 @@ impl TypeChecker {
                     (_, Some(TypedType::Float64)) => {
                         (Some(&TypedType::Float64), Some(&TypedType::Float64))
                     }
+                    (_, Some(TypedType::Decimal)) => {
+                        (Some(&TypedType::Decimal), Some(&TypedType::Decimal))
+                    }
                     _ => (None, None),
                 }
             }
@@ -7304,6 +10199,13 @@ impl TypeChecker {
             )?;
         }
 
+        if matches!(binary.op, BinaryOp::Div | BinaryOp::Mod) && binary.right.is_constant_zero() {
+            self.warn(Warning::DivisionByZero(
+                binary.op.clone(),
+                binary.right.span,
+            ));
+        }
+
         // Type check based on operator
         match binary.op {
             BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
@@ -7312,6 +10214,7 @@ impl TypeChecker {
                     (TypedType::Int32, TypedType::Int32) => Ok(TypedType::Int32),
                     (TypedType::Int64, TypedType::Int64) => Ok(TypedType::Int64),
                     (TypedType::Float64, TypedType::Float64) => Ok(TypedType::Float64),
+                    (TypedType::Decimal, TypedType::Decimal) => Ok(TypedType::Decimal),
                     (TypedType::String, TypedType::String) if binary.op == BinaryOp::Add => {
                         Ok(TypedType::String)
                     }
@@ -7328,6 +10231,14 @@ impl TypeChecker {
             BinaryOp::Eq | BinaryOp::Ne => {
                 // Equality operators work on same types
                 if left_ty == right_ty {
+                    if let TypedType::Record { name, .. } = &left_ty {
+                        if !self.record_implements_eq(name, &mut HashSet::new()) {
+                            return Err(TypeError::UnsupportedFeature(format!(
+                                "Type {} does not implement trait Eq",
+                                format_typed_type(&left_ty)
+                            )));
+                        }
+                    }
                     Ok(TypedType::Boolean)
                 } else {
                     Err(TypeError::TypeMismatch {
@@ -7342,6 +10253,20 @@ impl TypeChecker {
                     (TypedType::Int32, TypedType::Int32) => Ok(TypedType::Boolean),
                     (TypedType::Int64, TypedType::Int64) => Ok(TypedType::Boolean),
                     (TypedType::Float64, TypedType::Float64) => Ok(TypedType::Boolean),
+                    (TypedType::Decimal, TypedType::Decimal) => Ok(TypedType::Boolean),
+                    (
+                        TypedType::Record { name: left, .. },
+                        TypedType::Record { name: right, .. },
+                    ) if left == right => {
+                        if self.record_method_with_signature(left, "cmp", &TypedType::Int32) {
+                            Ok(TypedType::Boolean)
+                        } else {
+                            Err(TypeError::UnsupportedFeature(format!(
+                                "Type {} does not implement trait Ord",
+                                format_typed_type(&left_ty)
+                            )))
+                        }
+                    }
                     _ => Err(TypeError::TypeMismatch {
                         expected: "numeric types".to_string(),
                         found: Self::format_type_pair(&left_ty, &right_ty),
@@ -7358,6 +10283,20 @@ impl TypeChecker {
         }
     }
 
+    /// Rejects literal-only integer arithmetic that overflows the width it
+    /// was checked at, instead of letting it wrap at runtime.
+    fn check_constant_overflow(binary: &BinaryExpr, ty: &TypedType) -> Result<(), TypeError> {
+        let width = match ty {
+            TypedType::Int32 => IntWidth::Int32,
+            TypedType::Int64 => IntWidth::Int64,
+            _ => return Ok(()),
+        };
+        match eval_int_binary(binary, width) {
+            Some(Err(overflow)) => Err(TypeError::ConstantOverflow(overflow)),
+            _ => Ok(()),
+        }
+    }
+
     fn is_int_literal_expr(expr: &Expr) -> bool {
         matches!(&expr.kind, ExprKind::IntLit(_))
             || matches!(
@@ -7375,25 +10314,32 @@ impl TypeChecker {
     ) -> Option<&'a TypedType> {
         match op {
             BinaryOp::Add => match ty {
-                TypedType::Int32 | TypedType::Int64 | TypedType::Float64 | TypedType::String => {
-                    Some(ty)
-                }
+                TypedType::Int32
+                | TypedType::Int64
+                | TypedType::Float64
+                | TypedType::Decimal
+                | TypedType::String => Some(ty),
                 _ => None,
             },
             BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => match ty {
-                TypedType::Int32 | TypedType::Int64 | TypedType::Float64 => Some(ty),
+                TypedType::Int32 | TypedType::Int64 | TypedType::Float64 | TypedType::Decimal => {
+                    Some(ty)
+                }
                 _ => None,
             },
             BinaryOp::Eq | BinaryOp::Ne => match ty {
                 TypedType::Int32
                 | TypedType::Int64
                 | TypedType::Float64
+                | TypedType::Decimal
                 | TypedType::Boolean
                 | TypedType::Char => Some(ty),
                 _ => None,
             },
             BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => match ty {
-                TypedType::Int32 | TypedType::Int64 | TypedType::Float64 => Some(ty),
+                TypedType::Int32 | TypedType::Int64 | TypedType::Float64 | TypedType::Decimal => {
+                    Some(ty)
+                }
                 _ => None,
             },
             BinaryOp::And | BinaryOp::Or => match ty {
@@ -7414,6 +10360,7 @@ impl TypeChecker {
                     Some(TypedType::Int32) => Some(&TypedType::Int32),
                     Some(TypedType::Int64) => Some(&TypedType::Int64),
                     Some(TypedType::Float64) => Some(&TypedType::Float64),
+                    Some(TypedType::Decimal) => Some(&TypedType::Decimal),
                     _ => None,
                 };
                 let operand_ty = self.check_expr_with_expected(&unary.expr, expected_operand)?;
@@ -7421,6 +10368,7 @@ impl TypeChecker {
                     TypedType::Int32 => Ok(TypedType::Int32),
                     TypedType::Int64 => Ok(TypedType::Int64),
                     TypedType::Float64 => Ok(TypedType::Float64),
+                    TypedType::Decimal => Ok(TypedType::Decimal),
                     other => Err(expected_type_mismatch("numeric type", &other)),
                 }
             }
@@ -7439,11 +10387,20 @@ impl TypeChecker {
         let source_ty = self.check_expr(&cast.expr)?;
         let target_ty = self.convert_type(&cast.target)?;
 
-        if Self::is_numeric_cast_type(&source_ty) && Self::is_numeric_cast_type(&target_ty) {
+        let numeric =
+            Self::is_numeric_cast_type(&source_ty) && Self::is_numeric_cast_type(&target_ty);
+        let char_code = matches!(
+            (&source_ty, &target_ty),
+            (TypedType::Char, TypedType::Int32 | TypedType::Char)
+                | (TypedType::Int32, TypedType::Char)
+        );
+        if numeric || char_code {
             Ok(target_ty)
         } else {
             Err(TypeError::TypeMismatch {
-                expected: "numeric cast between Int32, Int64, or Float64".to_string(),
+                expected:
+                    "numeric cast between Int32, Int64, or Float64, or between Char and Int32"
+                        .to_string(),
                 found: format!(
                     "{} as {}",
                     format_typed_type(&source_ty),
@@ -7473,6 +10430,7 @@ impl TypeChecker {
                     let call = CallExpr {
                         function: Box::new(Expr::new(ExprKind::Ident(name.clone()))),
                         args: vec![pipe.expr.clone()],
+                        type_args: Vec::new(),
                     };
                     self.check_call_expr_with_expected(&call, expected)
                 } else if matches!(name.as_str(), "some" | "none") {
@@ -7484,14 +10442,14 @@ impl TypeChecker {
                     Ok(expr_ty)
                 }
             }
-            PipeTarget::Expr(target_expr) => {
-                // Pipe to expression: expr |> (func_expr). As above, the
-                // cloned object and target keep their ids, so checking the
-                // desugared call records facts under the source nodes.
-                let call = CallExpr {
-                    function: target_expr.clone(),
-                    args: vec![pipe.expr.clone()],
-                };
+            PipeTarget::Expr(_) => {
+                // Pipe to expression: expr |> (func_expr), expr |> (args) f
+                // or expr |> Record.method. As above, the cloned object and
+                // target keep their ids, so checking the desugared call
+                // records facts under the source nodes.
+                let call = pipe
+                    .expr_target_call(|callee| self.pipe_callee_arity(callee))
+                    .expect("expression pipe targets always desugar to a call");
                 self.check_call_expr_with_expected(&call, expected)
             }
         }
@@ -7503,16 +10461,35 @@ impl TypeChecker {
         expected: Option<&TypedType>,
     ) -> Result<TypedType, TypeError> {
         // Push context onto the stack
-        let original_len = self._contexts.len();
+        let original_len = self.active_contexts.len();
         let mut entered_async_runtime = false;
         let mut context_bindings: Vec<(String, TypedType)> = Vec::new();
 
         // Check if it's a built-in context or a user-defined context
         let ctx_name = &with.context_name;
         let is_arena_context = ctx_name == "Arena";
+        let is_capability = is_capability_context(ctx_name);
         if is_arena_context {
             // Arena is a built-in context
-            self._contexts.push(ctx_name.clone());
+            self.active_contexts.push(ctx_name.clone());
+        } else if is_capability {
+            // Capability contexts grant host builtins and carry no fields
+            match with.bindings.first() {
+                Some(FieldInit::Field { name, .. }) => {
+                    return Err(TypeError::UnknownField {
+                        record: ctx_name.clone(),
+                        field: name.clone(),
+                        suggestion: None,
+                    });
+                }
+                Some(FieldInit::Spread(_)) => {
+                    return Err(TypeError::UnavailableContext(
+                        "Spread operations not supported in context bindings".to_string(),
+                    ));
+                }
+                None => {}
+            }
+            self.active_contexts.push(ctx_name.clone());
         } else if ctx_name.starts_with("AsyncRuntime") {
             // AsyncRuntime context with lifetime parameter
             // Extract lifetime from AsyncRuntime<~async>
@@ -7525,10 +10502,10 @@ impl TypeChecker {
                     ctx_name
                 )));
             }
-            self._contexts.push(ctx_name.clone());
+            self.active_contexts.push(ctx_name.clone());
         } else if self.records.contains_key(ctx_name) {
             // User-defined context - add to context stack
-            self._contexts.push(ctx_name.clone());
+            self.active_contexts.push(ctx_name.clone());
             let field_types = self
                 .records
                 .get(ctx_name)
@@ -7542,10 +10519,11 @@ impl TypeChecker {
                         let expected_ty = match field_types.get(name) {
                             Some(ty) => ty,
                             None => {
-                                self._contexts.truncate(original_len);
+                                self.active_contexts.truncate(original_len);
                                 return Err(TypeError::UnknownField {
                                     record: ctx_name.clone(),
                                     field: name.clone(),
+                                    suggestion: closest_name(name, field_types.keys()),
                                 });
                             }
                         };
@@ -7553,19 +10531,19 @@ impl TypeChecker {
                             match self.check_expr_with_expected(value, Some(expected_ty)) {
                                 Ok(ty) => ty,
                                 Err(err) => {
-                                    self._contexts.truncate(original_len);
+                                    self.active_contexts.truncate(original_len);
                                     return Err(err);
                                 }
                             };
                         if !self.type_matches_expected(expected_ty, &actual_ty) {
-                            self._contexts.truncate(original_len);
+                            self.active_contexts.truncate(original_len);
                             return Err(typed_type_mismatch(expected_ty, &actual_ty));
                         }
                         context_bindings.push((name.clone(), expected_ty.clone()));
                     }
                     FieldInit::Spread(_expr) => {
                         // Spread operations not currently supported in context bindings
-                        self._contexts.truncate(original_len);
+                        self.active_contexts.truncate(original_len);
                         return Err(TypeError::UnavailableContext(
                             "Spread operations not supported in context bindings".to_string(),
                         ));
@@ -7583,13 +10561,31 @@ impl TypeChecker {
             for (name, ty) in &context_bindings {
                 if let Err(err) = self.bind_var(name.clone(), ty.clone(), false) {
                     self.pop_scope();
-                    self._contexts.truncate(original_len);
+                    self.active_contexts.truncate(original_len);
                     return Err(err);
                 }
             }
         }
+        if has_binding_scope {
+            self.context_binding_scopes
+                .push((ctx_name.clone(), self.var_env.len() - 1));
+        }
+        if is_arena_context {
+            self.arena_scopes.push(self.var_env.len());
+        }
+        if is_capability {
+            self.capability_scopes
+                .push((ctx_name.clone(), self.var_env.len()));
+        }
         let result = self.check_block_expr_with_expected(&with.body, expected);
+        if is_arena_context {
+            self.arena_scopes.pop();
+        }
+        if is_capability {
+            self.capability_scopes.pop();
+        }
         if has_binding_scope {
+            self.context_binding_scopes.pop();
             self.pop_scope();
         }
 
@@ -7599,13 +10595,16 @@ impl TypeChecker {
         } else {
             Ok(())
         };
-        self._contexts.truncate(original_len);
+        self.active_contexts.truncate(original_len);
 
         cleanup_result?;
         let result_ty = result?;
         if is_arena_context {
             self.check_arena_result_escape(&result_ty)?;
         }
+        if is_capability && Self::holds_function_value(&result_ty) {
+            return Err(TypeError::CapabilityEscape(ctx_name.clone()));
+        }
         Ok(result_ty)
     }
 
@@ -7617,11 +10616,91 @@ impl TypeChecker {
         }
     }
 
+    /// Reject storing heap-backed arena values into a variable declared
+    /// outside the innermost `with Arena` block, which would outlive the reset.
+    fn check_arena_assignment_escape(&self, name: &str, ty: &TypedType) -> Result<(), TypeError> {
+        let Some(&arena_start) = self.arena_scopes.last() else {
+            return Ok(());
+        };
+        let declared_outside = self
+            .var_env
+            .iter()
+            .rposition(|scope| scope.contains_key(name))
+            .is_some_and(|scope_idx| scope_idx < arena_start);
+        if declared_outside {
+            self.check_arena_result_escape(ty)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reject storing a function value created inside a `with Env` or
+    /// `with Clock` block into a variable declared outside it, where calling
+    /// it would use the capability after the block ends.
+    fn check_capability_assignment_escape(
+        &self,
+        name: &str,
+        ty: &TypedType,
+    ) -> Result<(), TypeError> {
+        if !Self::holds_function_value(ty) {
+            return Ok(());
+        }
+        let Some(declared_at) = self
+            .var_env
+            .iter()
+            .rposition(|scope| scope.contains_key(name))
+        else {
+            return Ok(());
+        };
+        match self
+            .capability_scopes
+            .iter()
+            .find(|(_, start)| declared_at < *start)
+        {
+            Some((context, _)) => Err(TypeError::CapabilityEscape(context.clone())),
+            None => Ok(()),
+        }
+    }
+
+    fn holds_record_value(ty: &TypedType) -> bool {
+        match ty {
+            TypedType::Record { .. } => true,
+            TypedType::Option(inner)
+            | TypedType::List(inner)
+            | TypedType::Array(inner, _)
+            | TypedType::Temporal {
+                base_type: inner, ..
+            } => Self::holds_record_value(inner),
+            TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
+                Self::holds_record_value(ok) || Self::holds_record_value(err)
+            }
+            _ => false,
+        }
+    }
+
+    fn holds_function_value(ty: &TypedType) -> bool {
+        match ty {
+            TypedType::Function { .. } => true,
+            TypedType::Option(inner)
+            | TypedType::List(inner)
+            | TypedType::Array(inner, _)
+            | TypedType::Temporal {
+                base_type: inner, ..
+            } => Self::holds_function_value(inner),
+            TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
+                Self::holds_function_value(ok) || Self::holds_function_value(err)
+            }
+            TypedType::Record { type_args, .. } => type_args.iter().any(Self::holds_function_value),
+            _ => false,
+        }
+    }
+
     fn is_arena_scalar_result(ty: &TypedType) -> bool {
         match ty {
             TypedType::Int32
             | TypedType::Int64
             | TypedType::Float64
+            | TypedType::Decimal
             | TypedType::Boolean
             | TypedType::Char
             | TypedType::Unit => true,
@@ -7630,8 +10709,19 @@ impl TypeChecker {
         }
     }
 
-    fn _is_context_available(&self, name: &str) -> bool {
-        self._contexts.contains(&name.to_string())
+    fn is_context_active(&self, name: &str) -> bool {
+        self.active_contexts.iter().any(|active| active == name)
+    }
+
+    /// Reject a host builtin used outside the `with` block of the capability
+    /// context that grants it.
+    fn check_capability(&self, function: &str) -> Result<(), TypeError> {
+        match capability_context(function) {
+            Some(context) if !self.is_context_active(context) => {
+                Err(TypeError::UnavailableContext(context.to_string()))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Extract lifetime from AsyncRuntime<~lifetime> syntax
@@ -7689,6 +10779,86 @@ impl TypeChecker {
         inner == outer
     }
 
+    /// Check if `outer` outlives `inner`, following chains of `within` constraints.
+    fn lifetime_outlives(&self, outer: &str, inner: &str) -> bool {
+        let mut pending = vec![inner.to_string()];
+        let mut visited = HashSet::new();
+        while let Some(current) = pending.pop() {
+            if self.is_lifetime_within(&current, outer) {
+                return true;
+            }
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+            pending.extend(
+                self.temporal_context
+                    .constraints
+                    .iter()
+                    .filter(|constraint| constraint.inner == current)
+                    .map(|constraint| constraint.outer.clone()),
+            );
+        }
+        false
+    }
+
+    /// Rejects a declaration whose own `where` constraints form a cycle,
+    /// such as `~a within ~b, ~b within ~a`: no pair of lifetimes can each
+    /// end inside the other. The error lists the constraints on the cycle.
+    fn reject_cyclic_temporal_constraints(
+        constraints: &[crate::ast::TemporalConstraint],
+    ) -> Result<(), TypeError> {
+        fn find_cycle<'a>(
+            node: &'a str,
+            outers: &HashMap<&'a str, Vec<&'a str>>,
+            path: &mut Vec<&'a str>,
+            finished: &mut HashSet<&'a str>,
+        ) -> Option<Vec<&'a str>> {
+            if let Some(start) = path.iter().position(|seen| *seen == node) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(node);
+                return Some(cycle);
+            }
+            if finished.contains(node) {
+                return None;
+            }
+            path.push(node);
+            for outer in outers.get(node).into_iter().flatten() {
+                if let Some(cycle) = find_cycle(outer, outers, path, finished) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            finished.insert(node);
+            None
+        }
+
+        let mut outers: HashMap<&str, Vec<&str>> = HashMap::new();
+        for constraint in constraints {
+            outers
+                .entry(constraint.inner.as_str())
+                .or_default()
+                .push(constraint.outer.as_str());
+        }
+
+        let mut finished = HashSet::new();
+        for constraint in constraints {
+            let Some(cycle) =
+                find_cycle(&constraint.inner, &outers, &mut Vec::new(), &mut finished)
+            else {
+                continue;
+            };
+            let links = cycle
+                .windows(2)
+                .map(|pair| format!("~{} within ~{}", pair[0], pair[1]))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(TypeError::TemporalConstraintViolation(format!(
+                "Contradictory temporal constraints: {links}"
+            )));
+        }
+        Ok(())
+    }
+
     /// Validate temporal constraints when creating temporal types.
     fn validate_temporal_constraints(&self, temporals: &[String]) -> Result<(), TypeError> {
         // Check that all temporals are in scope
@@ -7832,17 +11002,6 @@ impl TypeChecker {
             ));
         }
 
-        // Check the expression being spawned (should be a lambda or async function)
-        let func_type = self.check_expr(expr)?;
-
-        // Extract the return type from the function being spawned
-        let _return_type = match &func_type {
-            TypedType::Function { return_type, .. } => return_type.as_ref().clone(),
-            _ => {
-                return Err(expected_type_mismatch("function", &func_type));
-            }
-        };
-
         // Get the current async runtime lifetime
         let async_lifetime = self
             .current_async_runtime()
@@ -7851,6 +11010,45 @@ impl TypeChecker {
             })?
             .clone();
 
+        // A task may run until the runtime shuts down, so every temporal the
+        // spawned closure captures must outlive the runtime's lifetime.
+        let allowed_temporals: HashSet<String> = self
+            .temporal_context
+            .active_temporals
+            .iter()
+            .filter(|temporal| self.lifetime_outlives(temporal, &async_lifetime))
+            .cloned()
+            .collect();
+        let captured = self.collect_free_variables(expr, &HashSet::new());
+        let mut captured: Vec<_> = captured.into_iter().collect();
+        captured.sort();
+        for var_name in &captured {
+            if let Some(var_type) = self.peek_var_type(var_name) {
+                if let Err(TypeError::TemporalEscape { temporal, .. }) =
+                    self.check_temporal_escape(&var_type, &allowed_temporals)
+                {
+                    return Err(TypeError::TemporalEscape {
+                        message: format!(
+                            "Spawned task captures '{}' with lifetime ~{}, which does not outlive the AsyncRuntime lifetime ~{}",
+                            var_name, temporal, async_lifetime
+                        ),
+                        temporal,
+                    });
+                }
+            }
+        }
+
+        // Check the expression being spawned (should be a lambda or async function)
+        let func_type = self.check_expr(expr)?;
+
+        // Extract the return type from the function being spawned
+        let _return_type = match &func_type {
+            TypedType::Function { return_type, .. } => return_type.as_ref().clone(),
+            _ => {
+                return Err(expected_type_mismatch("function", &func_type));
+            }
+        };
+
         // Return Task<T, ~async> where T is the return type of the spawned function
         Ok(TypedType::Temporal {
             base_type: Box::new(TypedType::Record {
@@ -7983,15 +11181,13 @@ impl TypeChecker {
         branch_envs.push(then_env);
         branch_types.push(then_ty);
 
-        // Check else-if branches
+        // Check else-if branches. Each body starts after its own condition
+        // and every earlier one, since reaching it evaluated them all.
+        let mut condition_env = branch_base.clone();
         for (else_cond, else_block) in &then.else_ifs {
+            self.check_else_if_condition(&mut condition_env, else_cond)?;
             let (else_if_ty, else_if_env) =
-                self.check_branch_from_env(&branch_base, |checker| {
-                    let else_cond_ty = checker.check_expr(else_cond)?;
-                    if else_cond_ty != TypedType::Boolean {
-                        return Err(expected_type_mismatch("Boolean", &else_cond_ty));
-                    }
-
+                self.check_branch_from_env(&condition_env, |checker| {
                     checker.push_scope();
                     let result =
                         checker.check_block_expr_with_expected(else_block, branch_expected);
@@ -8004,7 +11200,7 @@ impl TypeChecker {
 
         // Check else branch
         if let Some(else_block) = &then.else_block {
-            let (else_ty, else_env) = self.check_branch_from_env(&branch_base, |checker| {
+            let (else_ty, else_env) = self.check_branch_from_env(&condition_env, |checker| {
                 checker.push_scope();
                 let result = checker.check_block_expr_with_expected(else_block, branch_expected);
                 checker.pop_scope();
@@ -8014,7 +11210,7 @@ impl TypeChecker {
             branch_types.push(else_ty);
         } else {
             branch_types.push(TypedType::Unit);
-            branch_envs.push(branch_base.clone());
+            branch_envs.push(condition_env);
         }
 
         let (result_ty, branch_substitution) = Self::resolve_branch_result_type(
@@ -8037,19 +11233,108 @@ impl TypeChecker {
 
         // Check body in new scope
         self.push_scope();
-        self.check_block_expr(&while_expr.body)?;
+        let result = self.check_loop_body(&while_expr.body, false, None);
         self.pop_scope();
+        result?;
 
         // While loops always return Unit
         Ok(TypedType::Unit)
     }
 
+    /// A `loop` has the type of its `break` values, or Unit when every
+    /// `break` is plain (or there is none).
+    fn check_loop_expr(
+        &mut self,
+        body: &BlockExpr,
+        expected: Option<&TypedType>,
+    ) -> Result<TypedType, TypeError> {
+        self.push_scope();
+        let result = self.check_loop_body(body, true, expected);
+        self.pop_scope();
+        Ok(result?.unwrap_or(TypedType::Unit))
+    }
+
+    /// Check a loop body as the target of the `break`s and `continue`s in
+    /// it, returning the type of its first `break`.
+    fn check_loop_body(
+        &mut self,
+        body: &BlockExpr,
+        accepts_value: bool,
+        expected: Option<&TypedType>,
+    ) -> Result<Option<TypedType>, TypeError> {
+        self.loop_frames.push(LoopFrame {
+            accepts_value,
+            expected: expected.cloned(),
+            break_type: None,
+        });
+        let result = self.check_block_expr(body);
+        let frame = self.loop_frames.pop().expect("loop frame pushed above");
+        result?;
+        Ok(frame.break_type)
+    }
+
+    /// Every `break` out of the same loop must produce the same type.
+    fn check_break(&mut self, value: Option<&Expr>) -> Result<(), TypeError> {
+        let Some(frame) = self.loop_frames.last() else {
+            return Err(TypeError::InvalidLoopControl(
+                "`break` can only be used inside a loop".to_string(),
+            ));
+        };
+        let first_break = frame.break_type.clone();
+        let ty = match value {
+            None => TypedType::Unit,
+            Some(_) if !frame.accepts_value => {
+                return Err(TypeError::InvalidLoopControl(
+                    "`break` with a value can only leave `loop`; `while` loops evaluate to ()"
+                        .to_string(),
+                ));
+            }
+            Some(value) => {
+                let hint = first_break.clone().or_else(|| frame.expected.clone());
+                self.check_expr_with_expected(value, hint.as_ref())?
+            }
+        };
+        match first_break {
+            Some(first) if !self.type_matches_expected(&first, &ty) => {
+                Err(expected_type_mismatch(format_typed_type(&first), &ty))
+            }
+            Some(_) => Ok(()),
+            None => {
+                if let Some(frame) = self.loop_frames.last_mut() {
+                    frame.break_type = Some(ty);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn check_while_let_expr(&mut self, while_let: &WhileLetExpr) -> Result<TypedType, TypeError> {
+        // The loop runs while an Option or Result matches, so the value must
+        // have one of those types; failing to match simply ends the loop.
+        let value_type = self.check_expr(&while_let.expr)?;
+        match &value_type {
+            TypedType::Option(_) | TypedType::Result(_, _) => {}
+            found => return Err(expected_type_mismatch("Option or Result", found)),
+        }
+        self.check_pattern(&while_let.pattern, &value_type)?;
+
+        self.push_scope();
+        let result = self
+            .bind_pattern_vars(&while_let.pattern, &value_type)
+            .and_then(|()| self.check_loop_body(&while_let.body, false, None));
+        self.pop_scope();
+        result?;
+
+        Ok(TypedType::Unit)
+    }
+
     fn check_match_expr_with_expected(
         &mut self,
         match_expr: &MatchExpr,
         expected: Option<&TypedType>,
     ) -> Result<TypedType, TypeError> {
         // Check the scrutinee expression
+        let shared_reads_before = self.shared_record_reads;
         let scrutinee_type = self.check_expr(&match_expr.expr)?;
 
         // Check that we have at least one arm
@@ -8082,8 +11367,15 @@ impl TypeChecker {
             let (arm_type, arm_env) = self.check_branch_from_env(&branch_base, |checker| {
                 checker.push_scope();
                 checker.bind_pattern_vars(&arm.pattern, &scrutinee_type)?;
+                let mut bound_names = HashSet::new();
+                checker.collect_pattern_bindings(&arm.pattern, &mut bound_names);
+                checker.mark_shared_bindings(&bound_names, shared_reads_before);
 
-                let result = checker.check_block_expr_with_expected(&arm.body, branch_expected);
+                let result = match &arm.guard {
+                    Some(guard) => checker.check_match_guard(guard),
+                    None => Ok(()),
+                }
+                .and_then(|()| checker.check_block_expr_with_expected(&arm.body, branch_expected));
 
                 checker.pop_scope();
                 result
@@ -8091,6 +11383,7 @@ impl TypeChecker {
             branch_envs.push(arm_env);
             branch_types.push(arm_type);
         }
+        self.warn_unreachable_arms(&match_expr.arms);
 
         // Check exhaustiveness with detailed error reporting
         if !self.is_pattern_exhaustive(&match_expr.arms, &scrutinee_type) {
@@ -8128,10 +11421,12 @@ impl TypeChecker {
         match pattern {
             Pattern::Wildcard => Ok(()),
             Pattern::Ident(_) => Ok(()), // Binds to any type
+            Pattern::Binding(_, inner) => self.check_pattern(inner, expected_type),
             Pattern::Literal(lit) => {
                 let lit_type = match lit {
                     Literal::Int(value) => self.check_int_lit(*value, Some(expected_type))?,
                     Literal::Float(_) => TypedType::Float64,
+                    Literal::Decimal(_) => TypedType::Decimal,
                     Literal::String(_) => TypedType::String,
                     Literal::Char(_) => TypedType::Char,
                     Literal::Bool(_) => TypedType::Boolean,
@@ -8143,6 +11438,25 @@ impl TypeChecker {
                 }
                 Ok(())
             }
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                if expected_type != &TypedType::Int32 {
+                    return Err(typed_type_mismatch(expected_type, &TypedType::Int32));
+                }
+                let last = if *inclusive { *end } else { end - 1 };
+                self.check_int_lit(*start, Some(expected_type))?;
+                self.check_int_lit(last, Some(expected_type))?;
+                if start > &last {
+                    return Err(TypeError::TypeMismatch {
+                        expected: "a range pattern that matches at least one value".to_string(),
+                        found: format!("{}{}{}", start, if *inclusive { "..=" } else { ".." }, end),
+                    });
+                }
+                Ok(())
+            }
             Pattern::Record(name, fields) => {
                 if matches!(
                     expected_type,
@@ -8150,7 +11464,7 @@ impl TypeChecker {
                 ) {
                     let (record_name, instantiated_fields) =
                         self.instantiated_record_fields(expected_type)?;
-                    if name != &record_name {
+                    if !name.is_empty() && name != &record_name {
                         return Err(TypeError::TypeMismatch {
                             expected: record_name.clone(),
                             found: name.clone(),
@@ -8160,8 +11474,9 @@ impl TypeChecker {
                     for (field_name, field_pattern) in fields {
                         let field_type = instantiated_fields.get(field_name).ok_or_else(|| {
                             TypeError::UnknownField {
-                                record: name.clone(),
+                                record: record_name.clone(),
                                 field: field_name.clone(),
+                                suggestion: closest_name(field_name, instantiated_fields.keys()),
                             }
                         })?;
 
@@ -8241,7 +11556,7 @@ impl TypeChecker {
                 ) {
                     let (record_name, instantiated_fields) =
                         self.instantiated_record_fields(expected_type)?;
-                    if type_name != &record_name {
+                    if !type_name.is_empty() && type_name != &record_name {
                         return Err(TypeError::TypeMismatch {
                             expected: record_name.clone(),
                             found: type_name.clone(),
@@ -8251,8 +11566,9 @@ impl TypeChecker {
                     for (field_name, field_pattern) in fields {
                         let field_type = instantiated_fields.get(field_name).ok_or_else(|| {
                             TypeError::UnknownField {
-                                record: type_name.clone(),
+                                record: record_name.clone(),
                                 field: field_name.clone(),
+                                suggestion: closest_name(field_name, instantiated_fields.keys()),
                             }
                         })?;
 
@@ -8273,7 +11589,13 @@ impl TypeChecker {
                 self.bind_var(name.clone(), ty.clone(), false)?;
                 Ok(())
             }
-            Pattern::Literal(_) => Ok(()),
+            Pattern::Binding(name, inner) => {
+                self.bind_var(name.clone(), ty.clone(), false)?;
+                self.bind_pattern_vars(inner, ty)?;
+                self.link_pattern_aliases(name, inner);
+                Ok(())
+            }
+            Pattern::Literal(_) | Pattern::Range { .. } => Ok(()),
             Pattern::Record(_, fields) => {
                 if matches!(ty, TypedType::Record { .. } | TypedType::Temporal { .. }) {
                     let (record_name, instantiated_fields) = self.instantiated_record_fields(ty)?;
@@ -8285,6 +11607,10 @@ impl TypeChecker {
                                     TypeError::UnknownField {
                                         record: record_name.clone(),
                                         field: field_name.clone(),
+                                        suggestion: closest_name(
+                                            field_name,
+                                            instantiated_fields.keys(),
+                                        ),
                                     }
                                 })?;
                             Ok((field_name.clone(), field_type.clone()))
@@ -8350,7 +11676,7 @@ impl TypeChecker {
                 // Record destructuring with spread
                 if matches!(ty, TypedType::Record { .. } | TypedType::Temporal { .. }) {
                     let (name, instantiated_fields) = self.instantiated_record_fields(ty)?;
-                    if type_name != &name {
+                    if !type_name.is_empty() && type_name != &name {
                         return Err(TypeError::TypeMismatch {
                             expected: name.clone(),
                             found: type_name.clone(),
@@ -8365,6 +11691,10 @@ impl TypeChecker {
                                     TypeError::UnknownField {
                                         record: name.clone(),
                                         field: field_name.clone(),
+                                        suggestion: closest_name(
+                                            field_name,
+                                            instantiated_fields.keys(),
+                                        ),
                                     }
                                 })?;
                             Ok((field_name.clone(), field_type.clone()))
@@ -8381,8 +11711,7 @@ impl TypeChecker {
                     // Bind rest variable if present
                     if let Some(rest_name) = rest {
                         if rest_name != "_" {
-                            let rest_type =
-                                self.ensure_residual_record_type(type_name, fields, ty)?;
+                            let rest_type = self.ensure_residual_record_type(&name, fields, ty)?;
                             self.bind_var(rest_name.clone(), rest_type, false)?;
                         }
                     }
@@ -8392,11 +11721,106 @@ impl TypeChecker {
         }
     }
 
-    fn is_pattern_exhaustive(&self, arms: &[MatchArm], ty: &TypedType) -> bool {
-        // Check for wildcard or identifier patterns first
-        let has_catch_all = arms
+    /// Check a match guard in its arm's scope. A guard that evaluates to
+    /// `false` falls through to the next arm, so it may read pattern bindings
+    /// and outer values but must not consume anything affine.
+    fn check_match_guard(&mut self, guard: &Expr) -> Result<(), TypeError> {
+        let before = self.var_env.clone();
+        let guard_ty = self.check_expr(guard)?;
+        if guard_ty != TypedType::Boolean {
+            return Err(expected_type_mismatch("Boolean", &guard_ty));
+        }
+
+        for (scope_before, scope_after) in before.iter().zip(&self.var_env) {
+            for (name, var) in scope_after {
+                let newly_used = (var.used || var.pending_inference_uses > 0)
+                    && scope_before.get(name).is_some_and(|previous| {
+                        !previous.used && previous.pending_inference_uses == 0
+                    });
+                if newly_used {
+                    return Err(TypeError::GuardConsumesAffineValue(name.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Warn about each arm whose pattern is already covered by an earlier
+    /// unguarded arm. Guarded arms may fall through, so they never shadow
+    /// later ones.
+    fn warn_unreachable_arms(&mut self, arms: &[MatchArm]) {
+        let patterns: Vec<Pattern> = arms
             .iter()
-            .any(|arm| matches!(arm.pattern, Pattern::Wildcard | Pattern::Ident(_)));
+            .map(|arm| arm.pattern.without_bindings())
+            .collect();
+        for (later, later_pattern) in patterns.iter().enumerate() {
+            let covered_by = (0..later).find(|&earlier| {
+                arms[earlier].guard.is_none()
+                    && self.pattern_subsumes(&patterns[earlier], later_pattern)
+            });
+            if let Some(earlier) = covered_by {
+                self.warn(Warning::UnreachableArm {
+                    arm: later + 1,
+                    covered_by: earlier + 1,
+                });
+            }
+        }
+    }
+
+    /// Whether every value matched by `later` is also matched by `earlier`.
+    /// Both patterns must already have their `name @` bindings stripped.
+    fn pattern_subsumes(&self, earlier: &Pattern, later: &Pattern) -> bool {
+        if self.is_irrefutable_pattern(earlier) {
+            return true;
+        }
+        match (earlier, later) {
+            (Pattern::Literal(a), Pattern::Literal(b)) => a == b,
+            (Pattern::Range { .. }, _) => {
+                match (int_pattern_bounds(earlier), int_pattern_bounds(later)) {
+                    (Some((start_a, last_a)), Some((start_b, last_b))) => {
+                        start_a <= start_b && last_b <= last_a
+                    }
+                    _ => false,
+                }
+            }
+            (Pattern::None, Pattern::None) | (Pattern::EmptyList, Pattern::EmptyList) => true,
+            (Pattern::EmptyList, Pattern::ListExact(patterns))
+            | (Pattern::ListExact(patterns), Pattern::EmptyList) => patterns.is_empty(),
+            (Pattern::Some(a), Pattern::Some(b))
+            | (Pattern::Ok(a), Pattern::Ok(b))
+            | (Pattern::Err(a), Pattern::Err(b)) => self.pattern_subsumes(a, b),
+            (Pattern::ListCons(head_a, tail_a), Pattern::ListCons(head_b, tail_b)) => {
+                self.pattern_subsumes(head_a, head_b) && self.pattern_subsumes(tail_a, tail_b)
+            }
+            (Pattern::ListExact(a), Pattern::ListExact(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| self.pattern_subsumes(a, b))
+            }
+            _ => match (record_pattern_parts(earlier), record_pattern_parts(later)) {
+                (Some((name_a, fields_a)), Some((name_b, fields_b))) => {
+                    (name_a.is_empty() || name_b.is_empty() || name_a == name_b)
+                        && fields_a.iter().all(|(field, pattern)| {
+                            match self.pattern_for_record_field(fields_b, field) {
+                                Some(later_field) => self.pattern_subsumes(pattern, later_field),
+                                None => self.is_irrefutable_pattern(pattern),
+                            }
+                        })
+                }
+                _ => false,
+            },
+        }
+    }
+
+    fn is_pattern_exhaustive(&self, arms: &[MatchArm], ty: &TypedType) -> bool {
+        // Check for wildcard or identifier patterns first. Guarded arms may
+        // fall through, so they never count toward exhaustiveness.
+        let has_catch_all = arms.iter().any(|arm| {
+            arm.guard.is_none()
+                && matches!(
+                    arm.pattern.without_bindings(),
+                    Pattern::Wildcard | Pattern::Ident(_)
+                )
+        });
 
         if has_catch_all {
             return true;
@@ -8411,8 +11835,14 @@ impl TypeChecker {
         arms: &[MatchArm],
         ty: &TypedType,
     ) -> Result<(), Vec<String>> {
-        // Build the pattern matrix from all arms
-        let pattern_matrix: Vec<&Pattern> = arms.iter().map(|arm| &arm.pattern).collect();
+        // Build the pattern matrix from all unguarded arms. `name @` bindings
+        // do not affect which values match.
+        let patterns: Vec<Pattern> = arms
+            .iter()
+            .filter(|arm| arm.guard.is_none())
+            .map(|arm| arm.pattern.without_bindings())
+            .collect();
+        let pattern_matrix: Vec<&Pattern> = patterns.iter().collect();
 
         // Check if the pattern matrix covers the entire type space
         let uncovered = self.find_uncovered_patterns(&pattern_matrix, ty);
@@ -8442,9 +11872,17 @@ impl TypeChecker {
             TypedType::Unit => self.find_uncovered_unit_patterns(patterns),
             TypedType::List(elem_ty) => self.find_uncovered_list_patterns(patterns, elem_ty),
             TypedType::Record { name, .. } => self.find_uncovered_record_patterns(patterns, name),
+            TypedType::Int32
+                if patterns
+                    .iter()
+                    .any(|pattern| matches!(pattern, Pattern::Range { .. })) =>
+            {
+                Self::find_uncovered_int32_ranges(patterns)
+            }
             TypedType::Int32
             | TypedType::Int64
             | TypedType::Float64
+            | TypedType::Decimal
             | TypedType::String
             | TypedType::Char => {
                 // Infinite types - always require wildcard unless all possible values are covered
@@ -8711,6 +12149,7 @@ impl TypeChecker {
     fn is_irrefutable_pattern(&self, pattern: &Pattern) -> bool {
         match pattern {
             Pattern::Wildcard | Pattern::Ident(_) | Pattern::Literal(Literal::Unit) => true,
+            Pattern::Binding(_, inner) => self.is_irrefutable_pattern(inner),
             Pattern::Record(_, fields) => fields
                 .iter()
                 .all(|(_, field_pattern)| self.is_irrefutable_pattern(field_pattern)),
@@ -8718,6 +12157,7 @@ impl TypeChecker {
                 .iter()
                 .all(|(_, field_pattern)| self.is_irrefutable_pattern(field_pattern)),
             Pattern::Literal(_)
+            | Pattern::Range { .. }
             | Pattern::Some(_)
             | Pattern::None
             | Pattern::Ok(_)
@@ -8749,6 +12189,39 @@ impl TypeChecker {
             .all(|(_, pattern)| self.is_irrefutable_pattern(pattern))
     }
 
+    /// Int32 arms with ranges are exhaustive when their ranges and literals
+    /// leave no gap between `i32::MIN` and `i32::MAX`; each gap is reported
+    /// as the range pattern that would fill it.
+    fn find_uncovered_int32_ranges(patterns: &[&Pattern]) -> Vec<String> {
+        let mut covered: Vec<(i64, i64)> = patterns
+            .iter()
+            .filter_map(|pattern| int_pattern_bounds(pattern))
+            .collect();
+        covered.sort_unstable();
+
+        let mut gaps = Vec::new();
+        let mut next = i64::from(i32::MIN);
+        for (start, last) in covered {
+            if start > next {
+                gaps.push((next, start - 1));
+            }
+            next = next.max(last + 1);
+        }
+        if next <= i64::from(i32::MAX) {
+            gaps.push((next, i64::from(i32::MAX)));
+        }
+
+        gaps.into_iter()
+            .map(|(start, last)| {
+                if start == last {
+                    start.to_string()
+                } else {
+                    format!("{start}..={last}")
+                }
+            })
+            .collect()
+    }
+
     fn find_uncovered_infinite_patterns(
         &self,
         _patterns: &[&Pattern],
@@ -8974,7 +12447,7 @@ impl TypeChecker {
         };
 
         // Type check the body with inferred parameter types
-        let body_result = self.check_expr_with_expected(&lambda.body, expected_return_type);
+        let body_result = self.check_lambda_body(&lambda.body, expected_return_type);
         let observed_param_types = lambda
             .params
             .iter()
@@ -9037,6 +12510,54 @@ mod tests {
         checker.check_program(&program)
     }
 
+    #[test]
+    fn test_check_program_collecting_reports_every_failed_declaration() {
+        let (_, program) = parse_program(
+            r#"
+fun first: () -> Int32 = {
+    val leaked = 1;
+    leaked + missing
+}
+
+fun second: () -> Int32 = {
+    leaked
+}
+
+fun third: () -> Int32 = {
+    "not a number"
+}
+
+fun fine: () -> Int32 = {
+    42
+}
+"#,
+        )
+        .unwrap();
+        let errors = TypeChecker::new().check_program_collecting(&program);
+
+        // `second` must not see the binding `first` left open when it failed.
+        assert_eq!(
+            errors,
+            [
+                TypeError::UndefinedVariable {
+                    name: "missing".to_string(),
+                    suggestion: None
+                },
+                TypeError::UndefinedVariable {
+                    name: "leaked".to_string(),
+                    suggestion: None
+                },
+                TypeError::TypeMismatch {
+                    expected: "Int32".to_string(),
+                    found: "String".to_string(),
+                },
+            ]
+        );
+        assert!(TypeChecker::new()
+            .check_program_collecting(&parse_program("fun ok: () -> Int32 = { 1 }").unwrap().1)
+            .is_empty());
+    }
+
     #[test]
     fn deferred_lambda_resolution_does_not_overwrite_source_facts() {
         // A mutable deferred lambda binding escapes affine single-use
@@ -9316,6 +12837,46 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
         );
     }
 
+    #[test]
+    fn test_drop_consumes_its_argument() {
+        let dropped = r#"
+            record Handle { id: Int32 }
+            fun main: () -> Int32 = {
+                val h = Handle { id: 1 };
+                (h) drop;
+                0
+            }
+        "#;
+        assert!(check_program_str(dropped).is_ok());
+
+        let used_after_drop = r#"
+            record Handle { id: Int32 }
+            fun main: () -> Int32 = {
+                val h = Handle { id: 1 };
+                h |> drop;
+                h.id
+            }
+        "#;
+        assert_eq!(
+            check_program_str(used_after_drop),
+            Err(TypeError::AffineViolation("h".to_string()))
+        );
+
+        let dropped_after_move = r#"
+            record Handle { id: Int32 }
+            fun main: () -> Int32 = {
+                val h = Handle { id: 1 };
+                val kept = h;
+                (h) drop;
+                kept.id
+            }
+        "#;
+        assert_eq!(
+            check_program_str(dropped_after_move),
+            Err(TypeError::AffineViolation("h".to_string()))
+        );
+    }
+
     #[test]
     fn test_copy_semantics_composite_types() {
         // Test basic copyable types (Int32 is copyable)
@@ -9369,6 +12930,28 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
         assert!(check_program_str(input).is_ok());
     }
 
+    #[test]
+    fn test_unknown_field_suggests_closest_field() {
+        let input = r#"
+            record User { name: String, email: String }
+            val user = User { name: "ada", email: "ada@example.com" }
+            val address = user.emial
+        "#;
+        let err = check_program_str(input).unwrap_err();
+        assert_eq!(
+            err,
+            TypeError::UnknownField {
+                record: "User".to_string(),
+                field: "emial".to_string(),
+                suggestion: Some("email".to_string()),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Unknown field emial in record User; did you mean `email`?"
+        );
+    }
+
     #[test]
     fn test_unknown_field() {
         let input = r#"
@@ -9380,7 +12963,8 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
             check_program_str(input),
             Err(TypeError::UnknownField {
                 record: "Point".to_string(),
-                field: "z".to_string()
+                field: "z".to_string(),
+                suggestion: None,
             })
         );
     }
@@ -9424,6 +13008,7 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
             RecordDef {
                 fields: HashMap::from([("x".to_string(), TypedType::Int32)]),
                 field_order: vec!["x".to_string()],
+                defaulted_fields: HashSet::new(),
                 type_params: vec![],
                 temporal_constraints: vec![],
                 hash: None,
@@ -9440,7 +13025,8 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
             checker.bind_pattern_vars(&pattern, &test_record_type("Point")),
             Err(TypeError::UnknownField {
                 record: "Point".to_string(),
-                field: "z".to_string()
+                field: "z".to_string(),
+                suggestion: None,
             })
         );
     }
@@ -9456,6 +13042,142 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
         assert!(check_program_str(input).is_ok());
     }
 
+    fn check_derived_program(input: &str) -> Result<TypeChecker, TypeError> {
+        let (_, mut program) = parse_program(input).unwrap();
+        let base_hash = program.declarations.iter().find_map(|decl| match decl {
+            TopDecl::Record(record) if record.name == "Base" => {
+                Some(TypeChecker::record_prototype_hash(record))
+            }
+            _ => None,
+        });
+        for decl in &mut program.declarations {
+            if let TopDecl::Record(record) = decl {
+                if record.name == "Child" {
+                    record.parent_hash = base_hash.clone();
+                }
+            }
+        }
+        let mut checker = TypeChecker::new();
+        checker.check_program(&program)?;
+        Ok(checker)
+    }
+
+    #[test]
+    fn test_clone_keeps_derivation_bound_of_its_base() {
+        let checker = check_derived_program(
+            r#"
+            record Base { value: Int32 }
+            record Child { value: Int32 }
+            fun base_value: <T from Base>(item: T) -> Int32 = { 0 }
+            fun main: () -> Int32 = {
+                val child = Child { value: 1 };
+                val copy = child.clone { value: 2 };
+                val again = copy.clone { value: 3 };
+                (again) base_value
+            }
+        "#,
+        )
+        .expect("a clone derives from whatever its base derived from");
+
+        // Each clone links to its base's hash one level deeper
+        let mut depths = checker
+            .record_clones
+            .values()
+            .map(|(_, depth)| *depth)
+            .collect::<Vec<_>>();
+        depths.sort();
+        assert_eq!(depths, vec![2, 3]);
+        let child_hash = checker.prototypes["Child"].0.clone();
+        assert!(checker
+            .record_clones
+            .values()
+            .any(|(base_hash, depth)| *base_hash == child_hash && *depth == 2));
+    }
+
+    #[test]
+    fn test_clones_in_both_branches_share_the_record_type() {
+        let plain = r#"
+            record P { x: Int32 }
+            fun pick: (p: P, cond: Boolean) -> P = {
+                cond then { p.clone { x: 1 } } else { p.clone { x: 2 } }
+            }
+        "#;
+        assert!(check_program_str(plain).is_ok());
+
+        let derived = check_derived_program(
+            r#"
+            record Base { value: Int32 }
+            record Child { value: Int32 }
+            fun pick: (child: Child, cond: Boolean) -> Child = {
+                cond then { child.clone { value: 1 } } else { child.clone { value: 2 } }
+            }
+        "#,
+        );
+        assert!(derived.is_ok(), "{:?}", derived.err());
+    }
+
+    #[test]
+    fn test_mut_clone_binding_accepts_a_fresh_literal() {
+        let plain = r#"
+            record P { x: Int32 }
+            fun main: () -> Int32 = {
+                val p = P { x: 0 };
+                mut val q = p.clone { x: 1 };
+                q = P { x: 5 };
+                q.x
+            }
+        "#;
+        assert!(check_program_str(plain).is_ok());
+
+        let derived = check_derived_program(
+            r#"
+            record Base { value: Int32 }
+            record Child { value: Int32 }
+            fun main: () -> Int32 = {
+                val child = Child { value: 0 };
+                mut val copy = child.clone { value: 1 };
+                copy = Child { value: 5 };
+                copy.value
+            }
+        "#,
+        );
+        assert!(derived.is_ok(), "{:?}", derived.err());
+    }
+
+    #[test]
+    fn test_plain_record_clones_do_not_count_toward_derivation_depth() {
+        let input = r#"
+            record P { x: Int32 }
+            fun main: () -> Int32 = {
+                val a = P { x: 0 };
+                val b = a.clone { x: 1 };
+                val c = b.clone { x: 2 };
+                val d = c.clone { x: 3 };
+                val e = d.clone { x: 4 };
+                e.x
+            }
+        "#;
+        assert!(check_program_str(input).is_ok());
+    }
+
+    #[test]
+    fn test_clone_respects_the_derivation_depth_limit() {
+        let result = check_derived_program(
+            r#"
+            record Base { value: Int32 }
+            record Child { value: Int32 }
+            fun main: () -> Int32 = {
+                val child = Child { value: 1 };
+                val first = child.clone { value: 2 };
+                val second = first.clone { value: 3 };
+                val third = second.clone { value: 4 };
+                third.value
+            }
+        "#,
+        );
+        assert_eq!(result.err(), Some(TypeError::DerivationTooDeep(4)));
+    }
+
     #[test]
     fn test_clone_frozen_error() {
         let input = r#"
@@ -9466,6 +13188,80 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
         assert_eq!(check_program_str(input), Err(TypeError::CloneFrozenRecord));
     }
 
+    #[test]
+    fn test_field_update_of_frozen_record_error() {
+        let input = r#"
+            record Point { x: Int32, y: Int32 }
+            fun main: () -> Int32 = {
+                mut val p = Point { x: 1, y: 2 } freeze;
+                p.x <- 3;
+                p.x
+            }
+        "#;
+        assert_eq!(
+            check_program_str(input),
+            Err(TypeError::MutationOfFrozen {
+                record: "Point".to_string(),
+                operation: "update field `x`".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_method_call_on_frozen_record_error() {
+        let prelude = r#"
+            record Point { x: Int32, y: Int32 }
+            impl Point {
+                fun norm: (self: Point) -> Int32 = { self.x + self.y }
+            }
+        "#;
+        let expected = Err(TypeError::MutationOfFrozen {
+            record: "Point".to_string(),
+            operation: "call method `norm`".to_string(),
+        });
+        for call in ["(p) norm", "p |> Point.norm"] {
+            let input = format!(
+                "{prelude}
+                fun main: () -> Int32 = {{
+                    val p = Point {{ x: 1, y: 2 }} freeze;
+                    {call}
+                }}"
+            );
+            assert_eq!(check_program_str(&input), expected, "{call}");
+        }
+    }
+
+    #[test]
+    fn test_clone_nested_field_of_frozen_record_error() {
+        let input = r#"
+            record Inner { value: Int32 }
+            record Outer { inner: Inner, label: Int32 }
+            val outer = Outer { inner: Inner { value: 1 }, label: 2 } freeze
+            val copy = outer.inner.clone { value: 3 }
+        "#;
+        assert_eq!(check_program_str(input), Err(TypeError::CloneFrozenRecord));
+
+        let deeper = r#"
+            record Leaf { value: Int32 }
+            record Branch { leaf: Leaf }
+            record Root { branch: Branch }
+            val root = Root { branch: Branch { leaf: Leaf { value: 1 } } } freeze
+            val copy = root.branch.leaf.clone { value: 2 }
+        "#;
+        assert_eq!(check_program_str(deeper), Err(TypeError::CloneFrozenRecord));
+    }
+
+    #[test]
+    fn test_clone_nested_field_of_unfrozen_record() {
+        let input = r#"
+            record Inner { value: Int32 }
+            record Outer { inner: Inner, label: Int32 }
+            val outer = Outer { inner: Inner { value: 1 }, label: 2 }
+            val copy = outer.inner.clone { value: 3 }
+        "#;
+        assert!(check_program_str(input).is_ok());
+    }
+
     #[test]
     fn test_affine_field_access() {
         let input = r#"
@@ -9490,10 +13286,310 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
     }
 
     #[test]
-    fn test_affine_in_blocks() {
-        // Test with affine record type - should fail
+    fn test_unnamed_record_destructuring_moves_record_once() {
         let input = r#"
-            record Point { x: Int32, y: Int32 }
+            record User { id: Int32, name: String }
+            val user = User { id: 1, name: "Ada" }
+            val { id, name } = user
+        "#;
+        assert!(check_program_str(input).is_ok());
+
+        let separate_accesses = r#"
+            record User { id: Int32, name: String }
+            val user = User { id: 1, name: "Ada" }
+            val name = user.name
+            val id = user.id
+        "#;
+        assert_eq!(
+            check_program_str(separate_accesses),
+            Err(TypeError::AffineViolation("user".to_string()))
+        );
+
+        let reused = r#"
+            record User { id: Int32, name: String }
+            val user = User { id: 1, name: "Ada" }
+            val { id, name } = user
+            val again = user.id
+        "#;
+        assert_eq!(
+            check_program_str(reused),
+            Err(TypeError::AffineViolation("user".to_string()))
+        );
+
+        let not_a_record = r#"
+            val { id } = 42
+        "#;
+        assert!(matches!(
+            check_program_str(not_a_record),
+            Err(TypeError::TypeMismatch { expected, .. }) if expected == "record type"
+        ));
+    }
+
+    #[test]
+    fn test_map_builtins_are_generic_over_key_and_value() {
+        let input = r#"
+            val empty = () map_new<String, Int32>
+            val one = (empty, "a", 1) map_insert
+            val two = (one, "b", 2) map_insert
+            val smaller = (two, "a") map_remove
+            val found: Option<Int32> = (smaller, "b") map_get
+        "#;
+        assert!(check_program_str(input).is_ok());
+
+        let wrong_value = r#"
+            val empty: Map<Int32, String> = () map_new
+            val one = (empty, 1, 2) map_insert
+        "#;
+        assert!(matches!(
+            check_program_str(wrong_value),
+            Err(TypeError::TypeMismatch { .. })
+        ));
+
+        let unhashable_key = r#"
+            val empty: Map<Boolean, Int32> = () map_new
+            val one = (empty, true, 1) map_insert
+        "#;
+        assert_eq!(
+            check_program_str(unhashable_key),
+            Err(TypeError::UnsupportedFeature(
+                "Type Boolean does not implement trait MapKey".to_string()
+            ))
+        );
+
+        let reused_after_insert = r#"
+            val empty: Map<Int32, Int32> = () map_new
+            val one = (empty, 1, 1) map_insert
+            val two = (empty, 2, 2) map_insert
+        "#;
+        assert_eq!(
+            check_program_str(reused_after_insert),
+            Err(TypeError::AffineViolation("empty".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_try_requires_matching_function_return() {
+        let ok = r#"
+            fun first_doubled: (values: List<Int32>) -> Option<Int32> = {
+                val first = values |> list_head?;
+                Some(first * 2)
+            }
+        "#;
+        assert_eq!(check_program_str(ok), Ok(()));
+
+        let non_option = r#"
+            fun first_or_zero: (values: List<Int32>) -> Int32 = {
+                values |> list_head?
+            }
+        "#;
+        assert_eq!(
+            check_program_str(non_option),
+            Err(TypeError::InvalidTry {
+                operand: "Option<Int32>".to_string(),
+                required: "Option<_>".to_string(),
+                found: Some("Int32".to_string()),
+            })
+        );
+
+        let other_error = r#"
+            fun parse: (n: Int32) -> Result<Int32, String> = { Ok(n) }
+            fun checked: (n: Int32) -> Result<Int32, Int32> = {
+                Ok((n) parse?)
+            }
+        "#;
+        assert!(matches!(
+            check_program_str(other_error),
+            Err(TypeError::InvalidTry { found: Some(found), .. }) if found == "Result<Int32, Int32>"
+        ));
+
+        let in_lambda = r#"
+            fun lambda_try: (value: Option<Int32>) -> Option<Int32> = {
+                val bump = |opt: Option<Int32>| opt? + 1;
+                Some((value) bump)
+            }
+        "#;
+        assert!(matches!(
+            check_program_str(in_lambda),
+            Err(TypeError::InvalidTry { found: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_contradictory_temporal_constraints_are_rejected() {
+        let direct = r#"
+            fun transfer: <~a, ~b>(x: Int32) -> Int32 where ~a within ~b, ~b within ~a = { x }
+        "#;
+        match check_program_str(direct) {
+            Err(TypeError::TemporalConstraintViolation(msg)) => {
+                assert!(msg.contains("~a within ~b"), "{msg}");
+                assert!(msg.contains("~b within ~a"), "{msg}");
+            }
+            other => panic!("expected temporal constraint violation, got {other:?}"),
+        }
+
+        let transitive = r#"
+            fun rotate: <~a, ~b, ~c>(x: Int32) -> Int32
+                where ~a within ~b, ~b within ~c, ~c within ~a = { x }
+        "#;
+        match check_program_str(transitive) {
+            Err(TypeError::TemporalConstraintViolation(msg)) => {
+                assert!(msg.contains("~a within ~b"), "{msg}");
+                assert!(msg.contains("~b within ~c"), "{msg}");
+                assert!(msg.contains("~c within ~a"), "{msg}");
+            }
+            other => panic!("expected temporal constraint violation, got {other:?}"),
+        }
+
+        let chain = r#"
+            fun nest: <~a, ~b, ~c>(x: Int32) -> Int32
+                where ~a within ~b, ~b within ~c = { x }
+        "#;
+        assert_eq!(check_program_str(chain), Ok(()));
+    }
+
+    #[test]
+    fn test_trailing_semicolon_gives_block_unit_type() {
+        let valued = "fun f: (x: Int32) -> Int32 = { x }";
+        assert_eq!(check_program_str(valued), Ok(()));
+
+        let unit = "fun f: (x: Int32) = { x; }";
+        assert_eq!(check_program_str(unit), Ok(()));
+
+        let mismatched = "fun f: (x: Int32) -> Int32 = { x; }";
+        match check_program_str(mismatched) {
+            Err(TypeError::TypeMismatch { expected, found }) => {
+                assert_eq!(expected, "Int32");
+                assert_eq!(found, "()");
+            }
+            other => panic!("expected Unit block mismatch, got {other:?}"),
+        }
+
+        let branch = "fun f: (flag: Boolean) -> Int32 = { flag then { 1; } else { 2; } }";
+        assert!(matches!(
+            check_program_str(branch),
+            Err(TypeError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_prototype_hashes_are_stable_across_checkers() {
+        let source = r#"
+            record Point { x: Int32, y: Int32 }
+            fun main: () -> Int32 = {
+                val base = Point { x: 1, y: 2 };
+                val moved = base.clone { x: 3 };
+                moved.x
+            }
+        "#;
+        let (_, program) = parse_program(source).unwrap();
+        let mut first = TypeChecker::new();
+        first.check_program(&program).unwrap();
+        let mut second = TypeChecker::new();
+        second.check_program(&program).unwrap();
+        assert_eq!(first.prototypes, second.prototypes);
+
+        // FNV-1a does not depend on the process, so the hash is pinned.
+        let point = match &program.declarations[0] {
+            TopDecl::Record(record) => record,
+            other => panic!("expected a record, got {other:?}"),
+        };
+        assert_eq!(
+            TypeChecker::record_prototype_hash(point),
+            "203470d8f14140b7"
+        );
+    }
+
+    #[test]
+    fn test_hash_requires_scalars_or_frozen_records() {
+        let accepted = r#"
+            record Point { x: Int32, y: Int32 }
+            record Tagged { label: String, at: Point }
+            fun main: () -> Int32 = {
+                val origin = Point { x: 0, y: 0 };
+                val tagged = Tagged { label: "origin", at: origin };
+                val frozen = tagged freeze;
+                (frozen |> hash) + (5 |> hash) + ("hi" |> hash) + (2.5 |> hash)
+            }
+        "#;
+        assert_eq!(check_program_str(accepted), Ok(()));
+
+        let unfrozen = r#"
+            record Point { x: Int32, y: Int32 }
+            fun main: () -> Int32 = {
+                val p = Point { x: 1, y: 2 };
+                p |> hash
+            }
+        "#;
+        let message = check_program_str(unfrozen).unwrap_err().to_string();
+        assert!(
+            message.contains("does not implement trait Hash"),
+            "{message}"
+        );
+
+        let list_field = r#"
+            record Bag { items: List<Int32> }
+            fun main: () -> Int32 = {
+                val bag = Bag { items: [1, 2] };
+                val frozen = bag freeze;
+                frozen |> hash
+            }
+        "#;
+        let message = check_program_str(list_field).unwrap_err().to_string();
+        assert!(
+            message.contains("does not implement trait Hash"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_record_field_defaults() {
+        let input = r#"
+            record Config { host: String = "localhost", port: Int32 = 8080 }
+            val defaulted = Config { port: 9090 }
+            val overridden = Config { host: "example.org", port: 1 }
+        "#;
+        assert!(check_program_str(input).is_ok());
+
+        let undefaulted_missing = r#"
+            record Config { host: String, port: Int32 = 8080 }
+            val config = Config { port: 9090 }
+        "#;
+        assert_eq!(
+            check_program_str(undefaulted_missing),
+            Err(TypeError::MissingField {
+                record: "Config".to_string(),
+                field: "host".to_string(),
+            })
+        );
+
+        let wrong_type = r#"
+            record Config { port: Int32 = "8080" }
+        "#;
+        assert!(matches!(
+            check_program_str(wrong_type),
+            Err(TypeError::TypeMismatch { expected, found }) if expected == "Int32" && found == "String"
+        ));
+
+        let captures_outer = r#"
+            val fallback = "localhost"
+            record Config { host: String = fallback }
+            val config = Config {}
+        "#;
+        assert_eq!(
+            check_program_str(captures_outer),
+            Err(TypeError::ImpureFieldDefault {
+                record: "Config".to_string(),
+                field: "host".to_string(),
+                variable: "fallback".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_affine_in_blocks() {
+        // Test with affine record type - should fail
+        let input = r#"
+            record Point { x: Int32, y: Int32 }
             val p = Point { x: 10, y: 20 }
             val y = { val z = p }
             val w = p
@@ -9513,112 +13609,426 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
     }
 
     #[test]
-    fn test_function_params_affine() {
-        let input = r#"
-            record User { id: Int32, name: String }
-            fun use_twice: (user: User) -> Int32 = {
-                val id = user.id;
-                val name = user.name;
-                val second_id = user.id;
-                second_id
+    fn test_function_params_affine() {
+        let input = r#"
+            record User { id: Int32, name: String }
+            fun use_twice: (user: User) -> Int32 = {
+                val id = user.id;
+                val name = user.name;
+                val second_id = user.id;
+                second_id
+            }
+        "#;
+        assert_eq!(
+            check_program_str(input),
+            Err(TypeError::AffineViolation("user".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_clone_field_type_mismatch() {
+        let input = r#"
+            record Point { x: Int32, y: Int32 }
+            val p1 = Point { x: 10, y: 20 }
+            val p2 = p1.clone { x: "hello" }
+        "#;
+        assert!(matches!(
+            check_program_str(input),
+            Err(TypeError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_clone_unknown_field() {
+        let input = r#"
+            record Point { x: Int32, y: Int32 }
+            val p1 = Point { x: 10, y: 20 }
+            val p2 = p1.clone { z: 30 }
+        "#;
+        assert_eq!(
+            check_program_str(input),
+            Err(TypeError::UnknownField {
+                record: "Point".to_string(),
+                field: "z".to_string(),
+                suggestion: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_function_call() {
+        let input = r#"
+            fun add: (a: Int32, b: Int32) -> Int32 = { a }
+            val result = (10, 20) add
+        "#;
+        assert!(check_program_str(input).is_ok());
+    }
+
+    #[test]
+    fn test_function_arity_mismatch() {
+        let input = r#"
+            fun add: (a: Int32, b: Int32) -> Int32 = { a }
+            val result = (10) add
+        "#;
+        assert_eq!(
+            check_program_str(input),
+            Err(TypeError::ArityMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_undefined_function() {
+        let input = r#"
+            val result = (10, 20) add
+        "#;
+        assert_eq!(
+            check_program_str(input),
+            Err(TypeError::UndefinedFunction {
+                name: "add".to_string(),
+                suggestion: Some("and".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_undefined_function_suggests_closest_builtin() {
+        let input = r#"
+            fun main: () -> Int32 = {
+                ("hi") printn;
+                0
+            }
+        "#;
+        let err = check_program_str(input).unwrap_err();
+        assert_eq!(
+            err,
+            TypeError::UndefinedFunction {
+                name: "printn".to_string(),
+                suggestion: Some("print".to_string())
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Function printn is not defined; did you mean `print`?"
+        );
+    }
+
+    #[test]
+    fn test_closest_name_ignores_distant_and_single_letter_names() {
+        assert_eq!(
+            closest_name("printn", ["print", "println", "panic"]),
+            Some("print".to_string())
+        );
+        assert_eq!(
+            closest_name("pritnln", ["print", "println"]),
+            Some("println".to_string())
+        );
+        assert_eq!(closest_name("z", ["x", "y"]), None);
+        assert_eq!(closest_name("length", ["width", "height"]), None);
+    }
+
+    #[test]
+    fn test_binary_arithmetic() {
+        let input = r#"
+            val x = 10 + 20
+            val y = 30 - 10
+            val z = 5 * 6
+            val w = 20 / 4
+        "#;
+        assert!(check_program_str(input).is_ok());
+    }
+
+    #[test]
+    fn test_binary_comparison() {
+        let input = r#"
+            val a = 10 < 20
+            val b = 30 > 10
+            val c = 5 == 5
+            val d = 10 != 20
+        "#;
+        assert!(check_program_str(input).is_ok());
+    }
+
+    #[test]
+    fn test_binary_type_mismatch() {
+        let input = r#"
+            val x = 10 + "hello"
+        "#;
+        assert!(matches!(
+            check_program_str(input),
+            Err(TypeError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_constant_zero_divisor_warns() {
+        let source = r#"
+            fun main: () -> Int32 = {
+                val ratio = 10 / 0
+                val other = 20 / 0
+                ratio + other % 3
+            }
+        "#;
+        let (_, program) = parse_program(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&program).unwrap();
+        let warnings = checker.take_warnings();
+        let divisors: Vec<_> = warnings
+            .iter()
+            .map(|warning| match warning {
+                Warning::DivisionByZero(BinaryOp::Div, Some(span)) => span.slice(source),
+                other => panic!("unexpected warning: {other:?}"),
+            })
+            .collect();
+        // Both divisions are reported, not de-duplicated into one warning
+        assert_eq!(divisors, vec![Some("0"), Some("0")]);
+        assert_ne!(warnings[0], warnings[1]);
+        assert!(checker.take_warnings().is_empty());
+    }
+
+    fn warnings_for(source: &str) -> Vec<Warning> {
+        let (_, program) = parse_program(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&program).unwrap();
+        checker.take_warnings()
+    }
+
+    #[test]
+    fn test_unused_binding_warns() {
+        let warnings = warnings_for(
+            r#"
+            fun main: () -> Int32 = {
+                val x = 1
+                val y = 2
+                x
+            }
+        "#,
+        );
+        assert_eq!(warnings, vec![Warning::UnusedBinding("y".to_string())]);
+    }
+
+    #[test]
+    fn test_underscore_binding_does_not_warn() {
+        let warnings = warnings_for(
+            r#"
+            fun main: () -> Int32 = {
+                val _z = [1, 2, 3];
+                0
+            }
+        "#,
+        );
+        assert!(warnings.is_empty(), "unexpected warnings: {warnings:?}");
+    }
+
+    #[test]
+    fn test_consumed_binding_does_not_warn() {
+        let warnings = warnings_for(
+            r#"
+            fun double: (x: Int32) -> Int32 = {
+                x * 2
+            }
+
+            fun main: () -> Int32 = {
+                val numbers = [1, 2, 3]
+                val count = numbers |> list_length
+                val twice = |value| value * 2
+                val shifted = count |> twice
+                shifted |> double
+            }
+        "#,
+        );
+        assert!(warnings.is_empty(), "unexpected warnings: {warnings:?}");
+    }
+
+    #[test]
+    fn test_inner_scope_shadowing_warns_with_previous_span() {
+        let source = r#"
+            fun main: () -> Int32 = {
+                val x = 40
+                val y = {
+                    val x = 2;
+                    x
+                }
+                x + y
+            }
+        "#;
+        let warnings = warnings_for(source);
+        assert!(
+            matches!(
+                warnings.as_slice(),
+                [Warning::Shadowed(name, Some(span))]
+                    if name == "x" && span.slice(source) == Some("40")
+            ),
+            "unexpected warnings: {warnings:?}"
+        );
+        assert_eq!(
+            warnings[0].describe(source),
+            "Binding 'x' shadows an earlier binding at line 3, column 25"
+        );
+    }
+
+    #[test]
+    fn test_same_scope_immutable_redefinition_is_rejected() {
+        let result = check_program_str(
+            r#"
+            fun main: () -> Int32 = {
+                val x = 1
+                val x = 2
+                x
+            }
+        "#,
+        );
+        assert_eq!(result, Err(TypeError::Redefinition("x".to_string())));
+    }
+
+    #[test]
+    fn test_same_scope_mutable_redefinition_warns() {
+        let warnings = warnings_for(
+            r#"
+            fun main: () -> Int32 = {
+                mut val x = 1
+                val x = x + 1
+                x
+            }
+        "#,
+        );
+        assert!(
+            matches!(warnings.as_slice(), [Warning::Shadowed(name, _)] if name == "x"),
+            "unexpected warnings: {warnings:?}"
+        );
+    }
+
+    #[test]
+    fn test_arm_after_catch_all_is_unreachable() {
+        let warnings = warnings_for(
+            r#"
+            fun main: () -> Int32 = {
+                val n = 3
+                n match {
+                    _ => { 0 }
+                    3 => { 1 }
+                }
             }
-        "#;
+        "#,
+        );
         assert_eq!(
-            check_program_str(input),
-            Err(TypeError::AffineViolation("user".to_string()))
+            warnings,
+            vec![Warning::UnreachableArm {
+                arm: 2,
+                covered_by: 1
+            }]
         );
     }
 
     #[test]
-    fn test_clone_field_type_mismatch() {
-        let input = r#"
-            record Point { x: Int32, y: Int32 }
-            val p1 = Point { x: 10, y: 20 }
-            val p2 = p1.clone { x: "hello" }
-        "#;
-        assert!(matches!(
-            check_program_str(input),
-            Err(TypeError::TypeMismatch { .. })
-        ));
+    fn test_duplicate_none_arm_is_unreachable() {
+        let warnings = warnings_for(
+            r#"
+            fun main: () -> Int32 = {
+                val score: Option<Int32> = None
+                score match {
+                    None => { 0 }
+                    Some(value) if value > 10 => { 2 }
+                    Some(value) => { value }
+                    None => { 1 }
+                }
+            }
+        "#,
+        );
+        assert_eq!(
+            warnings,
+            vec![Warning::UnreachableArm {
+                arm: 4,
+                covered_by: 1
+            }]
+        );
     }
 
     #[test]
-    fn test_clone_unknown_field() {
-        let input = r#"
-            record Point { x: Int32, y: Int32 }
-            val p1 = Point { x: 10, y: 20 }
-            val p2 = p1.clone { z: 30 }
-        "#;
-        assert_eq!(
-            check_program_str(input),
-            Err(TypeError::UnknownField {
-                record: "Point".to_string(),
-                field: "z".to_string()
-            })
+    fn test_dropped_binding_does_not_warn() {
+        let warnings = warnings_for(
+            r#"
+            record Handle { id: Int32 }
+            fun main: () -> Int32 = {
+                val h = Handle { id: 1 };
+                (h) drop;
+                0
+            }
+        "#,
         );
+        assert!(warnings.is_empty(), "unexpected warnings: {warnings:?}");
     }
 
     #[test]
-    fn test_function_call() {
+    fn test_pipe_binding() {
         let input = r#"
-            fun add: (a: Int32, b: Int32) -> Int32 = { a }
-            val result = (10, 20) add
+            val x = 42 |> doubled
+            val y = doubled
         "#;
         assert!(check_program_str(input).is_ok());
     }
 
     #[test]
-    fn test_function_arity_mismatch() {
+    fn test_pipe_function() {
         let input = r#"
-            fun add: (a: Int32, b: Int32) -> Int32 = { a }
-            val result = (10) add
+            fun inc: (x: Int32) -> Int32 = { x }
+            val result = 42 |> inc
         "#;
-        assert_eq!(
-            check_program_str(input),
-            Err(TypeError::ArityMismatch {
-                expected: 2,
-                found: 1
-            })
-        );
+        assert!(check_program_str(input).is_ok());
     }
 
     #[test]
-    fn test_undefined_function() {
+    fn test_pipe_chain_threads_each_result() {
         let input = r#"
-            val result = (10, 20) add
+            fun inc: (x: Int32) -> Int32 = { x + 1 }
+            fun double: (x: Int32) -> Int32 = { x * 2 }
+            val result: Int32 = 42 |> inc |> double
         "#;
-        assert_eq!(
-            check_program_str(input),
-            Err(TypeError::UndefinedFunction("add".to_string()))
-        );
+        assert!(check_program_str(input).is_ok());
     }
 
     #[test]
-    fn test_binary_arithmetic() {
+    fn test_pipe_into_partially_applied_call() {
         let input = r#"
-            val x = 10 + 20
-            val y = 30 - 10
-            val z = 5 * 6
-            val w = 20 / 4
+            fun add: (a: Int32, b: Int32) -> Int32 = { a + b }
+            fun double: (x: Int32) -> Int32 = { x * 2 }
+            val result: Int32 = 5 |> (1) add |> double
         "#;
         assert!(check_program_str(input).is_ok());
     }
 
     #[test]
-    fn test_binary_comparison() {
+    fn test_pipe_into_qualified_method() {
         let input = r#"
-            val a = 10 < 20
-            val b = 30 > 10
-            val c = 5 == 5
-            val d = 10 != 20
+            record Point { x: Int32, y: Int32 }
+            impl Point {
+                fun translate: (self: Point, dx: Int32) -> Point = {
+                    Point { x: self.x + dx, y: self.y }
+                }
+                fun norm: (self: Point) -> Int32 = { self.x + self.y }
+            }
+            fun main: () -> Int32 = {
+                val p = Point { x: 1, y: 2 }
+                p |> (3) Point.translate |> Point.norm
+            }
         "#;
         assert!(check_program_str(input).is_ok());
     }
 
     #[test]
-    fn test_binary_type_mismatch() {
+    fn test_pipe_into_qualified_method_rejects_other_receiver() {
         let input = r#"
-            val x = 10 + "hello"
+            record Point { x: Int32, y: Int32 }
+            record Size { w: Int32, h: Int32 }
+            impl Point {
+                fun norm: (self: Point) -> Int32 = { self.x + self.y }
+            }
+            fun main: () -> Int32 = {
+                val s = Size { w: 1, h: 2 }
+                s |> Point.norm
+            }
         "#;
         assert!(matches!(
             check_program_str(input),
@@ -9626,22 +14036,94 @@ fun main: (item: Box<Int64>) -> Box<Int64> = {
         ));
     }
 
+    const RECEIVER_METHOD_PRELUDE: &str = r#"
+        record Point { x: Int32, y: Int32 }
+        impl Point {
+            fun scaled_sum: (self: Point, k: Int32) -> Int64 = { ((self.x + self.y) * k) as Int64 }
+        }
+    "#;
+
+    #[test]
+    fn test_receiver_method_call_returns_method_type() {
+        let input = format!(
+            "{RECEIVER_METHOD_PRELUDE}
+            fun main: () -> Int64 = {{
+                val p = Point {{ x: 1, y: 2 }}
+                val total = (3) p.scaled_sum
+                total
+            }}"
+        );
+        assert!(check_program_str(&input).is_ok());
+
+        let mismatched = format!(
+            "{RECEIVER_METHOD_PRELUDE}
+            fun main: () -> Int32 = {{
+                val p = Point {{ x: 1, y: 2 }}
+                val total = (3) p.scaled_sum
+                total
+            }}"
+        );
+        assert!(matches!(
+            check_program_str(&mismatched),
+            Err(TypeError::TypeMismatch { .. })
+        ));
+    }
+
     #[test]
-    fn test_pipe_binding() {
-        let input = r#"
-            val x = 42 |> doubled
-            val y = doubled
-        "#;
-        assert!(check_program_str(input).is_ok());
+    fn test_receiver_method_call_checks_arity() {
+        let input = format!(
+            "{RECEIVER_METHOD_PRELUDE}
+            fun main: () -> Int64 = {{
+                val p = Point {{ x: 1, y: 2 }}
+                val total = (3, 4) p.scaled_sum
+                total
+            }}"
+        );
+        assert!(matches!(
+            check_program_str(&input),
+            Err(TypeError::ArityMismatch {
+                expected: 1,
+                found: 2
+            })
+        ));
     }
 
     #[test]
-    fn test_pipe_function() {
-        let input = r#"
-            fun inc: (x: Int32) -> Int32 = { x }
-            val result = 42 |> inc
-        "#;
-        assert!(check_program_str(input).is_ok());
+    fn test_receiver_method_call_rejects_unknown_method() {
+        let input = format!(
+            "{RECEIVER_METHOD_PRELUDE}
+            fun main: () -> Int64 = {{
+                val p = Point {{ x: 1, y: 2 }}
+                val total = (3) p.missing
+                total
+            }}"
+        );
+        match check_program_str(&input) {
+            Err(TypeError::UnknownMethod {
+                record,
+                method,
+                suggestion,
+            }) => {
+                assert_eq!(record, "Point");
+                assert_eq!(method, "missing");
+                assert_eq!(suggestion, None);
+            }
+            other => panic!("expected UnknownMethod, got {other:?}"),
+        }
+
+        let misspelled = format!(
+            "{RECEIVER_METHOD_PRELUDE}
+            fun main: () -> Int64 = {{
+                val p = Point {{ x: 1, y: 2 }}
+                val total = (3) p.scaled_smu
+                total
+            }}"
+        );
+        let error = check_program_str(&misspelled).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Method scaled_smu not found for record type Point; did you mean `scaled_sum`?"
+        );
     }
 
     #[test]
@@ -9681,6 +14163,120 @@ val result = with DB {
         );
     }
 
+    #[test]
+    fn test_host_builtins_need_capability_context() {
+        let env_outside = r#"
+            fun home: () -> Option<String> = { "HOME" |> env_var }
+        "#;
+        assert_eq!(
+            check_program_str(env_outside),
+            Err(TypeError::UnavailableContext("Env".to_string()))
+        );
+
+        let clock_outside = r#"
+            fun stamp: () -> Int64 = { () now }
+        "#;
+        assert_eq!(
+            check_program_str(clock_outside),
+            Err(TypeError::UnavailableContext("Clock".to_string()))
+        );
+
+        // One capability does not grant the other
+        let clock_in_env = r#"
+            fun stamp: () -> Int64 = { with Env { () now } }
+        "#;
+        assert_eq!(
+            check_program_str(clock_in_env),
+            Err(TypeError::UnavailableContext("Clock".to_string()))
+        );
+
+        let function_value = r#"
+            fun lookup: () -> (String) -> Option<String> = { env_var }
+        "#;
+        assert_eq!(
+            check_program_str(function_value),
+            Err(TypeError::UnavailableContext("Env".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_host_builtins_inside_capability_context() {
+        let input = r#"
+            fun home: () -> Option<String> = {
+                with Env { ("HOME") env_var }
+            }
+
+            fun elapsed: (start: Int64) -> Int64 = {
+                with Clock { (() now) - start }
+            }
+
+            fun both: () -> Int64 = {
+                with Env {
+                    with Clock {
+                        "TZ" |> env_var match {
+                            Some(_) => { () now }
+                            None => { 0 }
+                        }
+                    }
+                }
+            }
+        "#;
+        assert!(check_program_str(input).is_ok());
+
+        let with_field = r#"
+            fun home: () -> Option<String> = {
+                with Env { path: "HOME" } { ("HOME") env_var }
+            }
+        "#;
+        assert_eq!(
+            check_program_str(with_field),
+            Err(TypeError::UnknownField {
+                record: "Env".to_string(),
+                field: "path".to_string(),
+                suggestion: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_function_values_cannot_escape_capability_context() {
+        let as_result = r#"
+            fun main: () -> Int32 = {
+                val f = with Clock { |x: Int32| { val _t = () now; x } };
+                (5) f
+            }
+        "#;
+        assert_eq!(
+            check_program_str(as_result),
+            Err(TypeError::CapabilityEscape("Clock".to_string()))
+        );
+
+        let by_assignment = r#"
+            fun main: () -> Int32 = {
+                mut val f = |x: Int32| { x };
+                with Env {
+                    f = |x: Int32| { val _home = ("HOME") env_var; x };
+                    0
+                };
+                (5) f
+            }
+        "#;
+        assert_eq!(
+            check_program_str(by_assignment),
+            Err(TypeError::CapabilityEscape("Env".to_string()))
+        );
+
+        let used_inside = r#"
+            fun main: () -> Int32 = {
+                with Clock {
+                    val f = |x: Int32| { val _t = () now; x };
+                    (5) f
+                }
+            }
+        "#;
+        assert!(check_program_str(used_inside).is_ok());
+    }
+
     #[test]
     fn test_multiple_contexts() {
         // Test individual context declarations work
@@ -9705,6 +14301,69 @@ val result = with DB {
         "#;
         assert!(check_program_str(cache_input).is_ok());
     }
+
+    /// Sets up `~db`, `~async within ~db` and `~tx within ~async` with an
+    /// AsyncRuntime running on `~async`, binding `conn: Conn<~db>` and
+    /// `tx: Tx<~tx>`.
+    fn checker_in_async_runtime() -> TypeChecker {
+        let mut checker = TypeChecker::new();
+        for lifetime in ["db", "async", "tx"] {
+            checker
+                .temporal_context
+                .active_temporals
+                .insert(lifetime.to_string());
+        }
+        for (inner, outer) in [("async", "db"), ("tx", "async")] {
+            checker
+                .temporal_context
+                .constraints
+                .push(TemporalConstraint {
+                    inner: inner.to_string(),
+                    outer: outer.to_string(),
+                });
+        }
+        checker.enter_async_runtime("async").unwrap();
+        checker.push_scope();
+        for (name, record, lifetime) in [("conn", "Conn", "db"), ("tx", "Tx", "tx")] {
+            let ty = TypedType::Temporal {
+                base_type: Box::new(test_record_type(record)),
+                temporals: vec![lifetime.to_string()],
+            };
+            checker.bind_var(name.to_string(), ty, false).unwrap();
+        }
+        checker
+    }
+
+    fn capturing_closure(name: &str) -> Expr {
+        Expr::new(ExprKind::Lambda(LambdaExpr {
+            params: Vec::new(),
+            body: Box::new(Expr::new(ExprKind::Ident(name.to_string()))),
+        }))
+    }
+
+    #[test]
+    fn test_spawn_may_capture_values_outliving_the_runtime() {
+        let mut checker = checker_in_async_runtime();
+        let task = checker
+            .check_spawn_expr(&capturing_closure("conn"))
+            .expect("~db outlives the ~async runtime");
+        assert!(matches!(
+            task,
+            TypedType::Temporal { ref temporals, .. } if temporals == &["async".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_spawn_rejects_captures_narrower_than_the_runtime() {
+        let mut checker = checker_in_async_runtime();
+        match checker.check_spawn_expr(&capturing_closure("tx")) {
+            Err(TypeError::TemporalEscape { temporal, message }) => {
+                assert_eq!(temporal, "tx");
+                assert!(message.contains("does not outlive the AsyncRuntime lifetime ~async"));
+            }
+            other => panic!("expected a temporal escape, got {:?}", other),
+        }
+    }
 }
 
 impl TypeChecker {
@@ -9776,8 +14435,12 @@ impl TypeChecker {
             }
         }
 
-        // Also check using the hash/parent_hash from the type itself
-        if let Some(parent_hash_val) = parent_hash {
+        // Also check using the hash/parent_hash from the type itself,
+        // looking through record clones to the prototype they came from
+        if let Some(mut parent_hash_val) = parent_hash {
+            while let Some((base_hash, _)) = self.record_clones.get(parent_hash_val) {
+                parent_hash_val = base_hash;
+            }
             for (parent_name, (parent_current_hash, _, _)) in &self.prototypes {
                 if parent_current_hash == parent_hash_val {
                     return self.is_derived_from(
@@ -9794,47 +14457,79 @@ impl TypeChecker {
     }
 
     fn generate_prototype_hash(&self, record_name: &str, content: &str) -> String {
-        // Simple hash implementation (in production, use SHA-3)
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
+        stable_hash([record_name, content])
+    }
+
+    /// Prototype hash of a declared record.
+    ///
+    /// The hash covers the record name and its field declarations, so a
+    /// record's `parent_hash` can reference any other declared record.
+    pub fn record_prototype_hash(record: &RecordDecl) -> String {
+        let field_parts: Vec<String> = record
+            .fields
+            .iter()
+            .flat_map(|field| [field.name.clone(), field.ty.to_string()])
+            .collect();
+        stable_hash(
+            std::iter::once(record.name.as_str()).chain(field_parts.iter().map(String::as_str)),
+        )
+    }
 
-        let mut hasher = DefaultHasher::new();
-        record_name.hash(&mut hasher);
-        content.hash(&mut hasher);
-        format!("{:016x}", hasher.finish())
+    fn find_prototype_by_hash(&self, hash: &str) -> Option<&String> {
+        self.prototypes
+            .iter()
+            .find(|(_, (prototype_hash, _, _))| prototype_hash == hash)
+            .map(|(name, _)| name)
     }
 
     fn check_derivation_depth(&self, type_name: &str) -> Result<usize, TypeError> {
-        let mut depth = 0;
-        let mut current_type = type_name;
-
-        loop {
-            if depth > 3 {
-                return Err(TypeError::DerivationTooDeep(depth));
-            }
+        let mut chain = vec![type_name.to_string()];
 
-            if let Some((_, Some(parent_hash_val), _)) = self.prototypes.get(current_type) {
-                // Find parent by hash
-                let mut found_parent = false;
-                for (parent_name, (parent_current_hash, _, _)) in &self.prototypes {
-                    if parent_current_hash == parent_hash_val {
-                        current_type = parent_name;
-                        depth += 1;
-                        found_parent = true;
-                        break;
-                    }
-                }
-                if !found_parent {
-                    break;
-                }
-            } else {
-                break;
+        while let Some((_, Some(parent_hash_val), _)) = self.prototypes.get(&chain[chain.len() - 1])
+        {
+            let Some(parent_name) = self.find_prototype_by_hash(parent_hash_val) else {
+                return Err(TypeError::UnknownParentPrototype {
+                    record: chain[chain.len() - 1].clone(),
+                    parent_hash: parent_hash_val.clone(),
+                });
+            };
+            if let Some(start) = chain.iter().position(|name| name == parent_name) {
+                let mut cycle = chain[start..].to_vec();
+                cycle.push(parent_name.clone());
+                return Err(TypeError::CyclicDerivation(cycle));
             }
+            chain.push(parent_name.clone());
         }
 
+        let depth = chain.len() - 1;
+        if depth > 3 {
+            return Err(TypeError::DerivationTooDeep(depth));
+        }
         Ok(depth)
     }
 
+    /// Hash and derivation depth a record value of type `name` passes on to
+    /// a clone of it. Clones carry their own hash; other values derive from
+    /// the declared record's prototype.
+    fn record_derivation_link(
+        &self,
+        name: &str,
+        hash: Option<&String>,
+    ) -> Result<(String, usize), TypeError> {
+        if let Some((hash, (_, depth))) =
+            hash.and_then(|hash| self.record_clones.get(hash).map(|link| (hash, link)))
+        {
+            return Ok((hash.clone(), *depth));
+        }
+        let depth = self.check_derivation_depth(name)?;
+        let base_hash = match (hash, self.prototypes.get(name)) {
+            (Some(hash), _) => hash.clone(),
+            (None, Some((prototype_hash, _, _))) => prototype_hash.clone(),
+            (None, None) => self.generate_prototype_hash(name, ""),
+        };
+        Ok((base_hash, depth))
+    }
+
     fn check_prototype_clone_expr(
         &mut self,
         proto_clone: &PrototypeCloneExpr,
@@ -9912,6 +14607,10 @@ impl TypeChecker {
             ExprKind::FieldAccess(object, _field) => {
                 free_vars.extend(self.collect_free_variables(object, bound_vars));
             }
+            ExprKind::FieldUpdate(update) => {
+                free_vars.extend(self.collect_free_variables(&update.object, bound_vars));
+                free_vars.extend(self.collect_free_variables(&update.value, bound_vars));
+            }
             ExprKind::RecordLit(record_lit) => {
                 for field in &record_lit.fields {
                     match field {
@@ -9973,6 +14672,9 @@ impl TypeChecker {
                     // Pattern bindings create new bound variables
                     let mut arm_bound = bound_vars.clone();
                     self.collect_pattern_bindings(&arm.pattern, &mut arm_bound);
+                    if let Some(guard) = &arm.guard {
+                        free_vars.extend(self.collect_free_variables(guard, &arm_bound));
+                    }
                     // The body is a BlockExpr, so we need to handle it specially
                     free_vars.extend(self.collect_free_variables_in_block(&arm.body, &arm_bound));
                 }
@@ -9995,6 +14697,16 @@ impl TypeChecker {
                 free_vars
                     .extend(self.collect_free_variables_in_block(&while_expr.body, bound_vars));
             }
+            ExprKind::Loop(body) => {
+                free_vars.extend(self.collect_free_variables_in_block(body, bound_vars));
+            }
+            ExprKind::WhileLet(while_let) => {
+                free_vars.extend(self.collect_free_variables(&while_let.expr, bound_vars));
+                let mut body_bound = bound_vars.clone();
+                self.collect_pattern_bindings(&while_let.pattern, &mut body_bound);
+                free_vars
+                    .extend(self.collect_free_variables_in_block(&while_let.body, &body_bound));
+            }
             ExprKind::Block(block) => {
                 free_vars.extend(self.collect_free_variables_in_block(block, bound_vars));
             }
@@ -10027,8 +14739,14 @@ impl TypeChecker {
             ExprKind::Pipe(pipe_expr) => {
                 free_vars.extend(self.collect_free_variables(&pipe_expr.expr, bound_vars));
                 match &pipe_expr.target {
-                    PipeTarget::Ident(_) => {
-                        // Target identifier is a binding, not a use
+                    PipeTarget::Ident(name) => {
+                        // A callable variable target is a use; any other
+                        // target identifier is a binding.
+                        if !bound_vars.contains(name)
+                            && matches!(self.peek_var_type(name), Some(TypedType::Function { .. }))
+                        {
+                            free_vars.insert(name.clone());
+                        }
                     }
                     PipeTarget::Expr(target_expr) => {
                         free_vars.extend(self.collect_free_variables(target_expr, bound_vars));
@@ -10038,7 +14756,7 @@ impl TypeChecker {
             ExprKind::Some(expr) => {
                 free_vars.extend(self.collect_free_variables(expr, bound_vars));
             }
-            ExprKind::Ok(expr) | ExprKind::Err(expr) => {
+            ExprKind::Ok(expr) | ExprKind::Err(expr) | ExprKind::Try(expr) => {
                 free_vars.extend(self.collect_free_variables(expr, bound_vars));
             }
             ExprKind::Await(expr) => {
@@ -10050,6 +14768,7 @@ impl TypeChecker {
             // Literals and None have no free variables
             ExprKind::IntLit(_)
             | ExprKind::FloatLit(_)
+            | ExprKind::DecimalLit(_)
             | ExprKind::StringLit(_)
             | ExprKind::CharLit(_)
             | ExprKind::BoolLit(_)
@@ -10081,9 +14800,10 @@ impl TypeChecker {
                 Stmt::Assignment(assign) => {
                     free_vars.extend(self.collect_free_variables(&assign.value, &block_bound));
                 }
-                Stmt::Expr(expr) => {
+                Stmt::Expr(expr) | Stmt::Break(Some(expr)) => {
                     free_vars.extend(self.collect_free_variables(expr, &block_bound));
                 }
+                Stmt::Break(None) | Stmt::Continue => {}
             }
         }
 
@@ -10100,6 +14820,10 @@ impl TypeChecker {
             Pattern::Ident(name) => {
                 bindings.insert(name.clone());
             }
+            Pattern::Binding(name, inner) => {
+                bindings.insert(name.clone());
+                self.collect_pattern_bindings(inner, bindings);
+            }
             Pattern::Wildcard => {}
             Pattern::Record(_name, fields) => {
                 for (_, p) in fields {
@@ -10121,7 +14845,7 @@ impl TypeChecker {
                     self.collect_pattern_bindings(p, bindings);
                 }
             }
-            Pattern::Literal(_) | Pattern::None | Pattern::EmptyList => {}
+            Pattern::Literal(_) | Pattern::Range { .. } | Pattern::None | Pattern::EmptyList => {}
             Pattern::RecordDestruct { fields, rest, .. } => {
                 // Collect bindings from fields
                 for (_, p) in fields {
@@ -10170,7 +14894,7 @@ impl TypeChecker {
             TypedType::Option(ty) => {
                 self.check_temporal_escape(ty, allowed_temporals)?;
             }
-            TypedType::Result(ok_ty, err_ty) => {
+            TypedType::Result(ok_ty, err_ty) | TypedType::Map(ok_ty, err_ty) => {
                 self.check_temporal_escape(ok_ty, allowed_temporals)?;
                 self.check_temporal_escape(err_ty, allowed_temporals)?;
             }
@@ -18,7 +18,7 @@ impl fmt::Display for TypeVarId {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct TypeVarGenerator {
     next: u32,
 }
@@ -75,12 +75,27 @@ pub struct Span {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConstraintKind {
-    Argument { func_name: String, arg_index: usize },
-    ReturnAnnotation { var_name: String },
-    LambdaParam { param_name: String },
+    Argument {
+        func_name: String,
+        arg_index: usize,
+    },
+    ReturnAnnotation {
+        var_name: String,
+    },
+    LambdaParam {
+        param_name: String,
+    },
     LambdaReturn,
-    FormBound { type_param: String },
-    AssocTypeProjection { assoc_name: String },
+    FormBound {
+        type_param: String,
+    },
+    TypeArgument {
+        func_name: String,
+        type_param: String,
+    },
+    AssocTypeProjection {
+        assoc_name: String,
+    },
     Apply,
 }
 
@@ -138,6 +153,10 @@ pub fn substitute_type_params(ty: &TypedType, type_vars: &HashMap<String, TypedT
             Box::new(substitute_type_params(ok, type_vars)),
             Box::new(substitute_type_params(err, type_vars)),
         ),
+        TypedType::Map(key, value) => TypedType::Map(
+            Box::new(substitute_type_params(key, type_vars)),
+            Box::new(substitute_type_params(value, type_vars)),
+        ),
         TypedType::Array(inner, size) => {
             TypedType::Array(Box::new(substitute_type_params(inner, type_vars)), *size)
         }
@@ -702,6 +721,10 @@ fn constraint_origin_context(origin: &ConstraintOrigin) -> Option<String> {
         }
         ConstraintKind::LambdaReturn => Some("lambda return".to_string()),
         ConstraintKind::FormBound { type_param } => Some(format!("form bound of {}", type_param)),
+        ConstraintKind::TypeArgument {
+            func_name,
+            type_param,
+        } => Some(format!("type argument {} of {}", type_param, func_name)),
         ConstraintKind::AssocTypeProjection { assoc_name } => {
             Some(format!("associated type projection {}", assoc_name))
         }
@@ -736,6 +759,7 @@ pub fn unify(
         (TypedType::Int32, TypedType::Int32)
         | (TypedType::Int64, TypedType::Int64)
         | (TypedType::Float64, TypedType::Float64)
+        | (TypedType::Decimal, TypedType::Decimal)
         | (TypedType::Boolean, TypedType::Boolean)
         | (TypedType::String, TypedType::String)
         | (TypedType::Char, TypedType::Char)
@@ -743,7 +767,8 @@ pub fn unify(
         (TypedType::TypeParam(left), TypedType::TypeParam(right)) if left == right => Ok(()),
         (TypedType::List(left), TypedType::List(right))
         | (TypedType::Option(left), TypedType::Option(right)) => unify(left, right, substitution),
-        (TypedType::Result(left_ok, left_err), TypedType::Result(right_ok, right_err)) => {
+        (TypedType::Result(left_ok, left_err), TypedType::Result(right_ok, right_err))
+        | (TypedType::Map(left_ok, left_err), TypedType::Map(right_ok, right_err)) => {
             unify(left_ok, right_ok, substitution)?;
             unify(left_err, right_err, substitution)
         }
@@ -771,26 +796,24 @@ pub fn unify(
             }
             unify(left_return, right_return, substitution)
         }
+        // Hashes only record which value a clone derives from; prototype
+        // clones get a record name of their own
         (
             TypedType::Record {
                 name: left_name,
                 type_args: left_type_args,
                 frozen: left_frozen,
-                hash: left_hash,
-                parent_hash: left_parent_hash,
+                ..
             },
             TypedType::Record {
                 name: right_name,
                 type_args: right_type_args,
                 frozen: right_frozen,
-                hash: right_hash,
-                parent_hash: right_parent_hash,
+                ..
             },
         ) if left_name == right_name
             && left_type_args.len() == right_type_args.len()
-            && left_frozen == right_frozen
-            && left_hash == right_hash
-            && left_parent_hash == right_parent_hash =>
+            && left_frozen == right_frozen =>
         {
             for (left_arg, right_arg) in left_type_args.iter().zip(right_type_args.iter()) {
                 unify(left_arg, right_arg, substitution)?;
@@ -838,6 +861,10 @@ pub fn zonk(ty: &TypedType, substitution: &Substitution) -> Result<TypedType, Ty
             Box::new(zonk(ok, substitution)?),
             Box::new(zonk(err, substitution)?),
         )),
+        TypedType::Map(key, value) => Ok(TypedType::Map(
+            Box::new(zonk(key, substitution)?),
+            Box::new(zonk(value, substitution)?),
+        )),
         TypedType::Array(inner, size) => Ok(TypedType::Array(
             Box::new(zonk(inner, substitution)?),
             *size,
@@ -910,7 +937,9 @@ pub fn contains_infer_var(ty: &TypedType) -> bool {
         TypedType::List(inner) | TypedType::Option(inner) | TypedType::Array(inner, _) => {
             contains_infer_var(inner)
         }
-        TypedType::Result(ok, err) => contains_infer_var(ok) || contains_infer_var(err),
+        TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
+            contains_infer_var(ok) || contains_infer_var(err)
+        }
         TypedType::Function {
             params,
             return_type,
@@ -930,7 +959,9 @@ pub fn contains_projection(ty: &TypedType) -> bool {
         TypedType::List(inner) | TypedType::Option(inner) | TypedType::Array(inner, _) => {
             contains_projection(inner)
         }
-        TypedType::Result(ok, err) => contains_projection(ok) || contains_projection(err),
+        TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
+            contains_projection(ok) || contains_projection(err)
+        }
         TypedType::Function {
             params,
             return_type,
@@ -968,7 +999,7 @@ fn occurs_in(
         TypedType::List(inner) | TypedType::Option(inner) | TypedType::Array(inner, _) => {
             occurs_in(id, inner, substitution)?
         }
-        TypedType::Result(ok, err) => {
+        TypedType::Result(ok, err) | TypedType::Map(ok, err) => {
             occurs_in(id, ok, substitution)? || occurs_in(id, err, substitution)?
         }
         TypedType::Function {
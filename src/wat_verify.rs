@@ -0,0 +1,57 @@
+//! # WAT Verification
+//!
+//! `--verify` assembles the WAT produced by [`WasmCodeGen`] with the `wat`
+//! crate and validates the binary with `wasmparser`, so a codegen regression
+//! fails the build instead of producing a module that hosts reject.
+//!
+//! Assembly errors already carry a line, column, and source excerpt.
+//! Validation errors only carry a byte offset into the binary, so the offset
+//! is mapped back to the function body containing it and the error quotes
+//! that function's `(func` line.
+//!
+//! [`WasmCodeGen`]: crate::codegen::WasmCodeGen
+
+use crate::codegen::CodeGenError;
+use wasmparser::{Parser, Payload, Validator};
+
+/// Assemble and validate `wat`, reporting the first problem found.
+pub fn verify_wat(wat: &str) -> Result<(), CodeGenError> {
+    let wasm = wat::parse_str(wat).map_err(|err| CodeGenError::InvalidWat(err.to_string()))?;
+    let Err(err) = Validator::new().validate_all(&wasm) else {
+        return Ok(());
+    };
+
+    let message = match defined_function_at(&wasm, err.offset())
+        .and_then(|index| defined_function_line(wat, index))
+    {
+        Some((line, text)) => format!("{}\n  --> line {}\n   | {}", err.message(), line, text),
+        None => format!("{} (at byte offset {:#x})", err.message(), err.offset()),
+    };
+    Err(CodeGenError::InvalidWat(message))
+}
+
+/// Index among the module's defined (non-imported) functions of the body
+/// that contains `offset`.
+fn defined_function_at(wasm: &[u8], offset: usize) -> Option<usize> {
+    let mut index = 0;
+    for payload in Parser::new(0).parse_all(wasm) {
+        if let Ok(Payload::CodeSectionEntry(body)) = payload {
+            if body.range().contains(&offset) {
+                return Some(index);
+            }
+            index += 1;
+        }
+    }
+    None
+}
+
+/// 1-based line number and text of the `index`-th function definition.
+/// Imported functions are declared inside `(import ...)`, so only defined
+/// functions start a line with `(func`.
+fn defined_function_line(wat: &str, index: usize) -> Option<(usize, &str)> {
+    wat.lines()
+        .enumerate()
+        .filter(|(_, line)| line.trim_start().starts_with("(func "))
+        .nth(index)
+        .map(|(line_index, line)| (line_index + 1, line.trim()))
+}
@@ -1,6 +1,9 @@
-use crate::diagnostics::{format_lex_error, format_parse_error};
+use crate::diagnostics::{
+    format_lex_error, format_parse_error, line_column, nom_error_span, span_to_line_col, NomError,
+};
+use crate::lint::lint_program;
 use crate::module::resolve_program_imports_with_module_source_map;
-use crate::{lex, parse_program, TypeChecker, WasmCodeGen};
+use crate::{lex, lex_spanned, parse_program, Program, Span, TypeChecker, WasmCodeGen};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
@@ -40,6 +43,270 @@ pub fn compile_restrict_lang_with_modules(source: &str, modules: JsValue) -> JsV
     serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
 }
 
+/// A single structured diagnostic reported by the playground bindings.
+///
+/// Diagnostics with a source span carry 1-based `line`/`column` and
+/// `endLine`/`endColumn` positions, with columns in UTF-16 code units so
+/// editors can underline the range directly. They are `null` for diagnostics
+/// that have no span yet (type and codegen errors).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDiagnostic {
+    /// `"error"` or `"warning"`.
+    pub severity: String,
+    /// Pipeline stage that produced the diagnostic: `lex`, `parse`, `import`,
+    /// `type`, or `codegen`.
+    pub stage: String,
+    pub message: String,
+    /// Stable error code for type errors, explained by `--explain`.
+    pub code: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub end_line: Option<usize>,
+    pub end_column: Option<usize>,
+}
+
+impl WebDiagnostic {
+    fn error(stage: &str, message: String) -> Self {
+        Self::new("error", stage, message)
+    }
+
+    fn warning(stage: &str, message: String) -> Self {
+        Self::new("warning", stage, message)
+    }
+
+    fn new(severity: &str, stage: &str, message: String) -> Self {
+        Self {
+            severity: severity.to_string(),
+            stage: stage.to_string(),
+            message,
+            code: None,
+            line: None,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    /// Diagnostic for a lex or parse error, positioned at the input it
+    /// points at when that input is part of `source`.
+    fn from_nom(stage: &str, source: &str, error: NomError<'_>) -> Self {
+        let span = nom_error_span(source, &error);
+        let message = match stage {
+            "lex" => format_lex_error(source, error),
+            _ => format_parse_error(source, error),
+        };
+        let diagnostic = Self::error(stage, message);
+        match span {
+            Some(span) => diagnostic.with_span(source, span),
+            None => diagnostic,
+        }
+    }
+
+    fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    fn with_span(mut self, source: &str, span: Span) -> Self {
+        let (start, end) = span_to_line_col(source, span);
+        self.line = Some(start.line);
+        self.column = Some(start.column);
+        self.end_line = Some(end.line);
+        self.end_column = Some(end.column);
+        self
+    }
+}
+
+/// Result of [`compile_to_wat`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WatResult {
+    pub ok: bool,
+    pub wat: Option<String>,
+    pub diagnostics: Vec<WebDiagnostic>,
+}
+
+/// Result of [`check`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub ok: bool,
+    pub diagnostics: Vec<WebDiagnostic>,
+}
+
+/// A lexed token with its byte range and 1-based start position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebToken {
+    pub kind: String,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Result of [`tokens`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokensResult {
+    pub ok: bool,
+    pub tokens: Vec<WebToken>,
+    pub diagnostics: Vec<WebDiagnostic>,
+}
+
+/// Runs the full pipeline and returns `{ ok, wat, diagnostics }`.
+#[wasm_bindgen]
+pub fn compile_to_wat(source: &str) -> JsValue {
+    let result = compile_to_wat_internal(source);
+    serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+}
+
+/// Lexes, parses, and type checks without generating code, returning
+/// `{ ok, diagnostics }`.
+#[wasm_bindgen]
+pub fn check(source: &str) -> JsValue {
+    let result = check_internal(source);
+    serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+}
+
+/// Lexes `source` and returns `{ ok, tokens, diagnostics }`.
+#[wasm_bindgen]
+pub fn tokens(source: &str) -> JsValue {
+    let result = tokens_internal(source);
+    serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+}
+
+fn compile_to_wat_internal(source: &str) -> WatResult {
+    let (program, mut diagnostics) = analyze(source);
+    let mut codegen = WasmCodeGen::new();
+    codegen.set_source(source);
+    let wat = program.and_then(|program| match codegen.generate(&program) {
+        Ok(wat) => Some(wat),
+        Err(e) => {
+            diagnostics.push(WebDiagnostic::error(
+                "codegen",
+                format!("Code generation error: {}", e),
+            ));
+            None
+        }
+    });
+
+    WatResult {
+        ok: wat.is_some(),
+        wat,
+        diagnostics,
+    }
+}
+
+fn check_internal(source: &str) -> CheckResult {
+    let (program, diagnostics) = analyze(source);
+    CheckResult {
+        ok: program.is_some(),
+        diagnostics,
+    }
+}
+
+fn tokens_internal(source: &str) -> TokensResult {
+    let (tokens, diagnostics) = match lex_spanned(source) {
+        Ok((remaining, tokens)) => {
+            let diagnostics = if remaining.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec![WebDiagnostic::from_nom(
+                    "lex",
+                    source,
+                    nom_error_at(remaining),
+                )]
+            };
+            (tokens, diagnostics)
+        }
+        Err(e) => (Vec::new(), vec![WebDiagnostic::from_nom("lex", source, e)]),
+    };
+
+    let tokens = tokens
+        .into_iter()
+        .map(|(token, span)| {
+            let (line, column) = line_column(source, span.start);
+            WebToken {
+                kind: format!("{:?}", token),
+                start: span.start,
+                end: span.end,
+                line,
+                column,
+            }
+        })
+        .collect();
+
+    TokensResult {
+        ok: diagnostics.is_empty(),
+        tokens,
+        diagnostics,
+    }
+}
+
+/// Runs the front end (lex, parse, type check) shared by [`check`] and
+/// [`compile_to_wat`]. Returns the checked program only when no error was
+/// reported; type checker warnings are included either way.
+fn analyze(source: &str) -> (Option<Program>, Vec<WebDiagnostic>) {
+    match lex(source) {
+        Ok((remaining, _)) if !remaining.is_empty() => {
+            return (
+                None,
+                vec![WebDiagnostic::from_nom(
+                    "lex",
+                    source,
+                    nom_error_at(remaining),
+                )],
+            );
+        }
+        Ok(_) => {}
+        Err(e) => return (None, vec![WebDiagnostic::from_nom("lex", source, e)]),
+    }
+
+    let program = match parse_program(source) {
+        Ok((remaining, _)) if !remaining.is_empty() => {
+            return (
+                None,
+                vec![WebDiagnostic::from_nom(
+                    "parse",
+                    source,
+                    nom_error_at(remaining),
+                )],
+            );
+        }
+        Ok((_, program)) => program,
+        Err(e) => return (None, vec![WebDiagnostic::from_nom("parse", source, e)]),
+    };
+
+    if !program.imports.is_empty() {
+        return (
+            None,
+            vec![WebDiagnostic::error(
+                "import",
+                "Import resolution error: source-level imports require module sources in the browser compiler".to_string(),
+            )],
+        );
+    }
+
+    let mut type_checker = TypeChecker::new();
+    let checked = type_checker.check_program(&program);
+    let mut diagnostics: Vec<WebDiagnostic> = type_checker
+        .take_warnings()
+        .into_iter()
+        .chain(lint_program(&program))
+        .map(|warning| {
+            WebDiagnostic::warning("type", format!("Warning: {}", warning.describe(source)))
+        })
+        .collect();
+
+    match checked {
+        Ok(()) => (Some(program), diagnostics),
+        Err(e) => {
+            diagnostics.push(
+                WebDiagnostic::error("type", format!("Type error: {}", e)).with_code(e.code()),
+            );
+            (None, diagnostics)
+        }
+    }
+}
+
 fn compile_internal(
     source: &str,
     module_sources: Option<HashMap<String, String>>,
@@ -144,6 +411,7 @@ fn compile_internal(
 
     // Step 4: Code generation
     let mut codegen = WasmCodeGen::new();
+    codegen.set_source(source);
     let wat = match codegen.generate(&ast) {
         Ok(wat) => wat,
         Err(e) => {
@@ -293,12 +561,12 @@ mod tests {
 
     #[test]
     fn lex_only_formats_leftover_input_without_debug_remaining() {
-        let result = lex_only_internal("val x = 1\nval y = @");
+        let result = lex_only_internal("val x = 1\nval y = $");
         let message = result.error.expect("unknown token should fail");
 
         assert!(!result.success);
         assert!(message.contains("Lexing error at line 2, column 9"));
-        assert!(message.contains("unexpected input near `@`"));
+        assert!(message.contains("unexpected input near `$`"));
         assert_no_raw_nom_debug(&message);
     }
 
@@ -313,6 +581,101 @@ mod tests {
         assert_no_raw_nom_debug(&message);
     }
 
+    #[test]
+    fn compile_to_wat_returns_wat_for_a_valid_program() {
+        let result = compile_to_wat_internal("fun main: () -> Int32 = {\n    42\n}\n");
+
+        assert!(result.ok);
+        assert!(result
+            .wat
+            .expect("valid source should produce WAT")
+            .contains("(module"));
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn check_reports_type_errors_as_structured_diagnostics() {
+        let result = check_internal("fun main: () -> Int32 = {\n    true\n}\n");
+
+        assert!(!result.ok);
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(diagnostic.severity, "error");
+        assert_eq!(diagnostic.stage, "type");
+        assert!(diagnostic.message.starts_with("Type error: "));
+        assert_eq!(diagnostic.code.as_deref(), Some("E0003"));
+    }
+
+    #[test]
+    fn check_keeps_type_checker_warnings_on_success() {
+        let result = check_internal("fun main: () -> Int32 = {\n    10 / 0\n}\n");
+
+        assert!(result.ok);
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == "warning" && diagnostic.stage == "type"));
+    }
+
+    #[test]
+    fn tokens_report_kinds_and_positions() {
+        let result = tokens_internal("val x = 1\nx");
+
+        assert!(result.ok);
+        assert_eq!(result.tokens.len(), 5);
+        assert_eq!(result.tokens[0].kind, "Val");
+        let last = result.tokens.last().expect("identifier token");
+        assert_eq!(
+            (last.start, last.end, last.line, last.column),
+            (10, 11, 2, 1)
+        );
+    }
+
+    #[test]
+    fn tokens_report_lex_errors_without_panicking() {
+        let result = tokens_internal("val x = $");
+
+        assert!(!result.ok);
+        assert_eq!(result.tokens.len(), 3);
+        assert_eq!(result.diagnostics[0].stage, "lex");
+        assert_no_raw_nom_debug(&result.diagnostics[0].message);
+    }
+
+    #[test]
+    fn check_positions_parse_errors_for_editor_underlines() {
+        let result = check_internal("fun main: () -> Int32 = {\n    val answer =\n}\n");
+        let diagnostic = &result.diagnostics[0];
+        let (line, column) = (diagnostic.line.unwrap(), diagnostic.column.unwrap());
+
+        assert_eq!(diagnostic.stage, "parse");
+        assert!(diagnostic
+            .message
+            .contains(&format!("at line {line}, column {column}")));
+        assert_eq!(diagnostic.end_line, Some(line));
+        assert!(diagnostic.end_column.unwrap() > column);
+    }
+
+    #[test]
+    fn check_positions_use_utf16_columns_after_multibyte_text() {
+        let result = check_internal("fun main: () -> Int32 = {\n    val s = \"日本😀\" $\n}\n");
+        let diagnostic = &result.diagnostics[0];
+
+        assert_eq!(diagnostic.stage, "lex");
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(2), Some(20)));
+        assert_eq!(
+            (diagnostic.end_line, diagnostic.end_column),
+            (Some(2), Some(21))
+        );
+    }
+
+    #[test]
+    fn check_leaves_positions_empty_for_unspanned_type_errors() {
+        let result = check_internal("fun main: () -> Int32 = {\n    true\n}\n");
+
+        assert_eq!(result.diagnostics[0].line, None);
+        assert_eq!(result.diagnostics[0].end_column, None);
+    }
+
     fn assert_no_raw_nom_debug(message: &str) {
         for internal in ["Error(", "Failure(", "ErrorKind", "nom"] {
             assert!(
@@ -5,12 +5,15 @@
 // Keep this file as a canonical, source-adjacent index for readers and tests.
 //
 // Current compiler-registered surface:
-// - println: (String) -> ()
+// - println: <T: Display>(T) -> ()
 // - print: (String) -> ()
 // - print_int: (Int32) -> ()
 // - print_float: (Float64) -> ()
 // - eprint: (String) -> ()
 // - eprintln: (String) -> ()
+// - args: () -> List<String>
+// - env_var: (String) -> Option<String>, inside `with Env { ... }`
+// - now: () -> Int64, inside `with Clock { ... }`
 //
 // Canonical call shapes:
 // - "hello" |> println
@@ -18,5 +21,8 @@
 // - 42 |> print_int
 // - 3.14 |> print_float
 // - "error" |> eprintln
+// - () args
+// - with Env { "HOME" |> env_var }
+// - with Clock { () now }
 //
 // Input-reading and file APIs are absent from the compiler-registered v0.0.1 surface.
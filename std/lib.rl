@@ -12,6 +12,7 @@
 // - math: compiler-registered numeric helpers
 // - list: compiler-registered list helpers
 // - option: compiler-registered Option helpers
+// - map: compiler-registered Map helpers
 //
 // The compiler-registered v0.0.1 surface has no parseable source import/export
 // aggregator. This file is an index of the current reference modules.
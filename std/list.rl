@@ -14,6 +14,15 @@
 // - list_append: <T>(List<T>, T) -> List<T>
 // - list_concat: <T>(List<T>, List<T>) -> List<T>
 // - list_count: <T>(List<T>) -> Int32
+// - list_map: <T, U>((T) -> U, List<T>) -> List<U>
+// - list_filter: <T>((T) -> Boolean, List<T>) -> List<T>
+// - list_fold: <T, A>((A, T) -> A, A, List<T>) -> A
+// - list_sort: <T>((T, T) -> Int32, List<T>) -> List<T>
+// - list_contains: <T: Eq>(List<T>, T) -> Boolean
+// - list_index_of: <T: Eq>(List<T>, T) -> Option<Int32>
+// - list_take: <T>(List<T>, Int32) -> List<T>
+// - list_drop: <T>(List<T>, Int32) -> List<T>
+// - list_slice: <T>(List<T>, Int32, Int32) -> List<T>
 //
 // Compiler list builtins that are also source-callable:
 // - list_length: <T>(List<T>) -> Int32
@@ -30,7 +39,19 @@
 // - values |> list_count
 // - values |> list_length
 // - (values, index) list_get
+// - (|value| value + 1, values) list_map
+// - (|value| value > 0, values) list_filter
+// - (|total, value| total + value, 0, values) list_fold
+// - (|left, right| left - right, values) list_sort
+// - (values, item) list_contains
+// - (values, item) list_index_of
+// - (values, count) list_take
+// - (values, count) list_drop
+// - (values, start, end) list_slice
 //
-// Higher-order helpers such as list_map, list_filter, and list_fold_left are
-// intentionally absent. The current compiler-registered v0.0.1 surface exposes
-// generic container map, filter, and fold through the prelude instead.
+// list_map, list_filter, and list_fold take the callable first and share the
+// lowering of the container-first prelude map, filter, and fold builtins.
+// list_sort takes a comparator first and returns a fresh, stably sorted list.
+// list_contains and list_index_of compare elements with ==, so T must be Eq.
+// list_take, list_drop, and list_slice clamp their indices to the list and
+// copy the range into a new list.
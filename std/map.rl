@@ -0,0 +1,24 @@
+// Standard Library: Map reference surface
+//
+// This file is not the runtime implementation. The current compiler registers
+// map symbols directly in the Rust type checker and WebAssembly codegen.
+// Keep this file as a canonical v0.0.1, source-adjacent index for readers and
+// tests.
+//
+// Current compiler-registered surface:
+// - map_new: <K: MapKey, V>() -> Map<K, V>
+// - map_insert: <K: MapKey, V>(Map<K, V>, K, V) -> Map<K, V>
+// - map_get: <K: MapKey, V>(Map<K, V>, K) -> Option<V>
+// - map_remove: <K: MapKey, V>(Map<K, V>, K) -> Map<K, V>
+//
+// MapKey is implemented by Int32 and String. Strings are compared by content.
+//
+// Canonical call shapes:
+// - val empty: Map<String, Int32> = () map_new
+// - () map_new<String, Int32>
+// - (scores, "ada", 42) map_insert
+// - (scores, "ada") map_get
+// - (scores, "ada") map_remove
+//
+// Every operation consumes the map. map_insert and map_remove return the
+// updated map, so bind the result and keep using that binding.
@@ -7,13 +7,31 @@
 //
 // Current compiler-registered surface:
 // - abs: (Int32) -> Int32
-// - max: (Int32, Int32) -> Int32
-// - min: (Int32, Int32) -> Int32
+// - max: <T: Ord>(T, T) -> T
+// - min: <T: Ord>(T, T) -> T
 // - pow: (Int32, Int32) -> Int32
 // - factorial: (Int32) -> Int32
+// - sign: (Int32) -> Int32
+// - gcd: (Int32, Int32) -> Int32
+// - lcm: (Int32, Int32) -> Int32
+// - wrapping_add: (Int32, Int32) -> Int32
+// - wrapping_mul: (Int32, Int32) -> Int32
+// - saturating_add: (Int32, Int32) -> Int32
+// - saturating_sub: (Int32, Int32) -> Int32
+// - checked_add: (Int32, Int32) -> Option<Int32>
+// - checked_sub: (Int32, Int32) -> Option<Int32>
+// - checked_mul: (Int32, Int32) -> Option<Int32>
+// - checked_div: (Int32, Int32) -> Option<Int32>
+// - clamp: (Int32, Int32, Int32) -> Int32
+// - checked: (Int32, Int32, Int32) -> Int32
+// - to_i64: (Int32) -> Int64
+// - to_i32: (Int64) -> Int32
+// - decimal_div_half_even: (Decimal, Decimal) -> Decimal
+// - decimal_div_half_up: (Decimal, Decimal) -> Decimal
 // - abs_f: (Float64) -> Float64
 // - max_f: (Float64, Float64) -> Float64
 // - min_f: (Float64, Float64) -> Float64
+// - hash: <T: Hash>(T) -> Int32
 //
 // Canonical call shapes:
 // - value |> abs
@@ -21,9 +39,25 @@
 // - (left, right) min
 // - (base, exponent) pow
 // - value |> factorial
+// - value |> sign
+// - (left, right) gcd
+// - (left, right) lcm
+// - (left, right) wrapping_add
+// - (left, right) wrapping_mul
+// - (left, right) saturating_add
+// - (left, right) saturating_sub
+// - (left, right) checked_add
+// - (dividend, divisor) checked_div
+// - (value, min, max) clamp
+// - (value, min, max) checked
+// - value |> to_i64
+// - value |> to_i32
+// - (dividend, divisor) decimal_div_half_even
+// - (dividend, divisor) decimal_div_half_up
 // - value |> abs_f
 // - (left, right) max_f
 // - (left, right) min_f
+// - value |> hash
 //
 // Additional numeric helpers are absent from the compiler-registered v0.0.1
 // surface.
@@ -9,6 +9,11 @@
 // - option_is_some: <T>(Option<T>) -> Boolean
 // - option_is_none: <T>(Option<T>) -> Boolean
 // - option_unwrap_or: <T>(Option<T>, T) -> T
+// - option_unwrap: <T>(Option<T>) -> T (traps on None)
+// - option_map: <T, U>((T) -> U, Option<T>) -> Option<U>
+// - option_and_then: <T, U>((T) -> Option<U>, Option<T>) -> Option<U>
+// - option_ok_or: <T, E>(Option<T>, E) -> Result<T, E>
+// - option_flatten: <T>(Option<Option<T>>) -> Option<T>
 //
 // Source-level constructors:
 // - Some(value): Option<T> constructor syntax
@@ -18,7 +23,11 @@
 // - maybe_value |> option_is_some
 // - maybe_value |> option_is_none
 // - (maybe_value, fallback) option_unwrap_or
+// - maybe_value |> option_unwrap
+// - (|value| value + 1, maybe_value) option_map
+// - (|value| Some(value * 2), maybe_value) option_and_then
+// - (maybe_value, error) option_ok_or
+// - nested_value |> option_flatten
 //
-// Higher-order helpers such as option_map, option_flatten, option_and_then,
-// option_zip, and option_to_list are absent from the compiler-registered
-// v0.0.1 surface.
+// Helpers such as option_zip and option_to_list are absent from the
+// compiler-registered v0.0.1 surface.
@@ -6,6 +6,7 @@
 //
 // Current compiler-registered surface:
 // - identity: <T>(T) -> T
+// - drop: <T>(T) -> (), consumes and discards its argument
 // - map: generic container mapping builtin
 // - filter: generic container filtering builtin
 // - fold: generic List reduction builtin
@@ -13,14 +14,22 @@
 // - and: (Boolean, Boolean) -> Boolean
 // - or: (Boolean, Boolean) -> Boolean
 // - assert: (Boolean, String) -> ()
+// - assert_that: (Boolean) -> (), panics with the condition's source text
+// - assert_eq: <T>(T, T, String) -> ()
+// - assert_ne: <T>(T, T, String) -> ()
 // - panic: (String) -> ()
+// - debug_assert: (Boolean, String) -> (), removed in release builds
+// - debug_print: (String) -> (), removed in release builds
 //
 // Canonical call shapes:
 // - value |> identity
+// - value |> drop
 // - value |> mapper |> next_mapper
 // - condition |> not
 // - (left, right) and
 // - (condition, "expected condition to hold") assert
+// - (total > 0) assert_that
+// - (actual, expected, "values should match") assert_eq
 //
-// Helpers such as xor, eq, ne, when, and debug_assert are absent from the
+// Helpers such as xor, eq, ne, and when are absent from the
 // compiler-registered v0.0.1 surface.
@@ -9,11 +9,13 @@
 // - Ok(value): Result<T, E> success constructor syntax
 // - Err(value): Result<T, E> error constructor syntax
 // - match arms over Ok(value) and Err(value)
+// - result_ok: <T, E>(Result<T, E>) -> Option<T>
 //
 // Canonical expression shapes:
 // - Ok(42)
 // - Err(7)
 // - result match { Ok(value) => { value } Err(code) => { code } }
+// - result |> result_ok
 //
-// Higher-order Result helpers are outside the compiler-registered v0.0.1
-// surface.
+// Higher-order Result helpers such as result_map are outside the
+// compiler-registered v0.0.1 surface.
@@ -10,6 +10,31 @@
 // - a == b: compare String contents
 // - a != b: compare String contents and negate the result
 //
+// Compiler-registered conversions to String:
+// - int_to_string: (Int32) -> String
+// - float_to_string: (Float64) -> String
+// - bool_to_string: (Boolean) -> String
+//
+// Compiler-registered parsing, None on malformed or out-of-range text:
+// - parse_int: (String) -> Option<Int32>
+// - parse_float: (String) -> Option<Float64>
+//
+// Compiler-registered inspection helpers (byte offsets into UTF-8):
+// - string_length: (String) -> Int32
+// - string_char_at: (String, Int32) -> Option<Char>
+// - string_from_char: (Char) -> String
+//
+// Compiler-registered splitting and joining; each delimiter separates two
+// parts, so "a,b," splits into "a", "b", and "":
+// - string_split: (String, Char) -> List<String>
+// - string_join: (List<String>, String) -> String
+//
+// Compiler-registered Char helpers:
+// - char_to_int: (Char) -> Int32
+// - int_to_char: (Int32) -> Char, trapping on non-scalar values
+// - is_digit: (Char) -> Boolean
+// - is_alpha: (Char) -> Boolean
+//
 // Lowered runtime helpers:
 // - string_concat: (String, String) -> String
 // - string_eq: (String, String) -> Boolean
@@ -18,6 +43,7 @@
 // - first + second
 // - first == second
 // - first != second
+// - "count: " + (count |> int_to_string)
 //
-// Length, parsing, and formatting helpers are absent from the
-// compiler-registered v0.0.1 surface.
+// Other formatting helpers are absent from the compiler-registered v0.0.1
+// surface.
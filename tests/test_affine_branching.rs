@@ -163,3 +163,209 @@ fun sum: (point: Point) -> Int32 = {
 
     type_check(source).expect("copyable fields should not consume the parent record");
 }
+
+#[test]
+fn else_if_conditions_consume_sequentially() {
+    let source = r#"
+fun is_long: (text: String) -> Boolean = {
+    text == "long"
+}
+
+fun is_short: (text: String) -> Boolean = {
+    text == "s"
+}
+
+fun bad: (a: Boolean, value: String) -> Int32 = {
+    a then {
+        1
+    } else (value) is_long then {
+        2
+    } else (value) is_short then {
+        3
+    } else {
+        4
+    }
+}
+"#;
+
+    let err = type_check(source)
+        .expect_err("a later else-if condition runs after the earlier one consumed value");
+    assert!(
+        err.contains("value") && err.contains("already been used"),
+        "error should report the affine value reused across conditions, got: {}",
+        err
+    );
+}
+
+#[test]
+fn else_if_condition_cannot_reuse_value_consumed_by_first_condition() {
+    let source = r#"
+fun is_long: (text: String) -> Boolean = {
+    text == "long"
+}
+
+fun is_short: (text: String) -> Boolean = {
+    text == "s"
+}
+
+fun bad: (value: String) -> Int32 = {
+    (value) is_long then {
+        1
+    } else (value) is_short then {
+        2
+    } else {
+        3
+    }
+}
+"#;
+
+    let err = type_check(source).expect_err("the else-if condition runs after the first one");
+    assert!(
+        err.contains("value") && err.contains("already been used"),
+        "error should report the affine value reused across conditions, got: {}",
+        err
+    );
+}
+
+#[test]
+fn else_if_bodies_may_each_move_the_same_affine_value() {
+    let source = r#"
+fun choose: (a: Boolean, b: Boolean, value: String) -> String = {
+    a then {
+        value
+    } else b then {
+        value
+    } else {
+        value
+    }
+}
+"#;
+
+    type_check(source).expect("exclusive else-if bodies may each move the same affine value");
+}
+
+#[test]
+fn else_if_body_cannot_reuse_value_consumed_by_its_condition() {
+    let source = r#"
+fun is_long: (text: String) -> Boolean = {
+    text == "long"
+}
+
+fun is_short: (text: String) -> Boolean = {
+    text == "s"
+}
+
+fun bad: (a: Boolean, value: String) -> String = {
+    a then {
+        "first"
+    } else (value) is_short then {
+        value
+    } else {
+        "other"
+    }
+}
+"#;
+
+    let err = type_check(source).expect_err("the branch body runs after its condition");
+    assert!(
+        err.contains("value") && err.contains("already been used"),
+        "error should report the affine value consumed by the condition, got: {}",
+        err
+    );
+}
+
+#[test]
+fn else_body_cannot_reuse_value_consumed_by_an_else_if_condition() {
+    let source = r#"
+fun is_long: (text: String) -> Boolean = {
+    text == "long"
+}
+
+fun is_short: (text: String) -> Boolean = {
+    text == "s"
+}
+
+fun bad: (a: Boolean, value: String) -> String = {
+    a then {
+        "first"
+    } else (value) is_short then {
+        "second"
+    } else {
+        value
+    }
+}
+"#;
+
+    let err = type_check(source).expect_err("the else body runs after every condition");
+    assert!(
+        err.contains("value") && err.contains("already been used"),
+        "error should report the affine value consumed by the condition, got: {}",
+        err
+    );
+}
+
+#[test]
+fn match_guard_may_read_copyable_bindings_before_the_arm_moves_affine_values() {
+    let source = r#"
+fun label: (count: Int32, name: String) -> String = {
+    count match {
+        n if n > 1 => { name }
+        _ => { name }
+    }
+}
+"#;
+
+    type_check(source).expect("a guard over a copyable binding consumes nothing");
+}
+
+#[test]
+fn match_guard_rejects_consuming_an_affine_binding() {
+    let source = r#"
+fun bad: (value: Option<String>) -> Int32 = {
+    value match {
+        Some(s) if (s |> string_length) > 0 => { 1 }
+        _ => { 0 }
+    }
+}
+"#;
+
+    let err = type_check(source).expect_err("a failed guard would leave s already moved");
+    assert!(
+        err.contains("Match guard cannot consume s"),
+        "error should report the consumed guard binding, got: {}",
+        err
+    );
+}
+
+#[test]
+fn at_binding_shares_ownership_with_its_destructured_parts() {
+    let source = r#"
+fun keep_whole: (value: Option<String>) -> Option<String> = {
+    value match {
+        whole @ Some(_) => { whole }
+        None => { None }
+    }
+}
+"#;
+
+    type_check(source).expect("using only the whole value is a single move");
+
+    let source = r#"
+fun both: (value: Option<String>) -> Int32 = {
+    value match {
+        whole @ Some(s) => {
+            val kept = whole;
+            s |> string_length
+        }
+        None => { 0 }
+    }
+}
+"#;
+
+    let err = type_check(source).expect_err("whole and s alias the same affine value");
+    assert!(
+        err.contains("Variable s has already been used"),
+        "error should report the double use, got: {}",
+        err
+    );
+}
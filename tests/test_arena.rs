@@ -201,3 +201,91 @@ fn test_arena_string_result_escape_rejected() {
 
     assert_arena_escape_rejected(source);
 }
+
+#[test]
+fn test_arena_record_used_inside_block() {
+    let source = r#"
+        record Point { x: Int32, y: Int32 }
+
+        fun main: () -> Int32 = {
+            with Arena {
+                val p = Point { x: 3, y: 4 };
+                p.x + p.y
+            }
+        }
+    "#;
+
+    let wat = compile(source).expect("arena-local record should compile");
+    assert!(wat.contains("call $arena_init"));
+    assert!(wat.contains("call $arena_reset"));
+}
+
+#[test]
+fn test_arena_record_result_escape_rejected() {
+    let source = r#"
+        record Point { x: Int32, y: Int32 }
+
+        fun main: () -> Point = {
+            with Arena {
+                Point { x: 3, y: 4 }
+            }
+        }
+    "#;
+
+    assert_arena_escape_rejected(source);
+}
+
+#[test]
+fn test_arena_record_assignment_escape_rejected() {
+    let source = r#"
+        record Point { x: Int32, y: Int32 }
+
+        fun main: () -> Int32 = {
+            mut val kept = Point { x: 0, y: 0 };
+            val n = with Arena {
+                kept = Point { x: 3, y: 4 };
+                0
+            };
+            kept.x + n
+        }
+    "#;
+
+    assert_arena_escape_rejected(source);
+}
+
+#[test]
+fn test_arena_assignment_inside_block_allowed() {
+    let source = r#"
+        fun main: () -> Int32 = {
+            mut val total = 0;
+            with Arena {
+                mut val values = [1, 2];
+                values = [3, 4, 5];
+                total = values |> list_count;
+                total
+            }
+        }
+    "#;
+
+    let result = type_check(source);
+    assert!(result.is_ok(), "got: {:?}", result);
+}
+
+#[test]
+fn test_arena_nested_field_update_escape_rejected() {
+    let source = r#"
+        record Label { text: String }
+        record Card { label: Label }
+
+        fun main: () -> Int32 = {
+            mut val card = Card { label: Label { text: "a" } };
+            val n = with Arena {
+                card.label.text <- (7) int_to_string;
+                0
+            };
+            n
+        }
+    "#;
+
+    assert_arena_escape_rejected(source);
+}
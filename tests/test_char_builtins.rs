@@ -0,0 +1,137 @@
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn check(source: &str) -> Result<restrict_lang::ast::Program, String> {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .map_err(|err| err.to_string())?;
+    Ok(ast)
+}
+
+fn compile(source: &str) -> String {
+    let ast = check(source).expect("source should type check");
+    WasmCodeGen::new()
+        .generate(&ast)
+        .expect("source should compile")
+}
+
+fn instantiate(source: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let wasm = wat::parse_str(compile(source))?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+#[test]
+fn char_builtins_type_check() {
+    check(
+        r#"
+fun main: () -> Int32 = {
+    val code: Int32 = 'A' |> char_to_int;
+    val letter: Char = 98 |> int_to_char;
+    val digit: Boolean = '5' |> is_digit;
+    val alpha: Boolean = letter |> is_alpha;
+    code
+}
+"#,
+    )
+    .expect("char builtins should type check");
+}
+
+#[test]
+fn char_builtins_reject_the_wrong_argument_type() {
+    let message = check("fun main: () -> Boolean = {\n    5 |> is_digit\n}\n")
+        .expect_err("is_digit takes a Char");
+    assert!(message.contains("Char"), "{message}");
+
+    let message = check("fun main: () -> Char = {\n    'a' |> int_to_char\n}\n")
+        .expect_err("int_to_char takes an Int32");
+    assert!(message.contains("Int32"), "{message}");
+}
+
+#[test]
+fn is_digit_emits_the_ascii_digit_range_check() {
+    let wat = compile("fun main: () -> Boolean = {\n    '5' |> is_digit\n}\n");
+
+    let start = wat.find("(func $is_digit ").expect("is_digit helper");
+    let helper = &wat[start..start + wat[start..].find("\n  )").unwrap()];
+    assert!(
+        helper.contains("i32.const 48\n    i32.ge_u"),
+        "is_digit should check the lower bound '0': {helper}"
+    );
+    assert!(
+        helper.contains("i32.const 57\n    i32.le_u"),
+        "is_digit should check the upper bound '9': {helper}"
+    );
+
+    let main = &wat[wat.find("(func $main").unwrap()..];
+    assert!(main.contains("i32.const 53\n    call $is_digit"), "{main}");
+}
+
+#[test]
+fn char_builtins_run() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(
+        r#"
+export fun digit: (code: Int32) -> Boolean = {
+    code |> int_to_char |> is_digit
+}
+
+export fun alpha: (code: Int32) -> Boolean = {
+    code |> int_to_char |> is_alpha
+}
+
+export fun round_trip: (code: Int32) -> Int32 = {
+    code |> int_to_char |> char_to_int
+}
+"#,
+    )?;
+    let digit = instance.get_typed_func::<i32, i32>(&store, "digit")?;
+    let alpha = instance.get_typed_func::<i32, i32>(&store, "alpha")?;
+    let round_trip = instance.get_typed_func::<i32, i32>(&store, "round_trip")?;
+
+    for (code, is_digit, is_alpha) in [
+        ('0', 1, 0),
+        ('9', 1, 0),
+        ('/', 0, 0),
+        (':', 0, 0),
+        ('a', 0, 1),
+        ('Z', 0, 1),
+        ('@', 0, 0),
+        ('[', 0, 0),
+        ('é', 0, 0),
+    ] {
+        assert_eq!(digit.call(&mut store, code as i32)?, is_digit, "{code:?}");
+        assert_eq!(alpha.call(&mut store, code as i32)?, is_alpha, "{code:?}");
+    }
+
+    assert_eq!(round_trip.call(&mut store, 0x1F600)?, 0x1F600);
+    for invalid in [-1, 0xD800, 0xDFFF, 0x110000] {
+        assert!(
+            round_trip.call(&mut store, invalid).is_err(),
+            "{invalid:#x} is not a Unicode scalar value"
+        );
+    }
+    Ok(())
+}
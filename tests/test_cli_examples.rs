@@ -1,5 +1,6 @@
 use std::fs;
-use std::process::{Command, Output};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
 
 use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
 
@@ -177,8 +178,15 @@ fn cli_help_reports_release_usage_on_stdout() {
         "--version",
         "--check",
         "--ast",
+        "--lib",
+        "--optimize",
+        "--release",
+        "--tokens",
+        "--tokens-json",
         "--verbose",
+        "--time",
         "--lsp",
+        "--repl",
         "--help",
     ] {
         assert!(
@@ -192,6 +200,187 @@ fn cli_help_reports_release_usage_on_stdout() {
     );
 }
 
+fn run_timed_temp_source(stem: &str, extra_args: &[&str]) -> (String, String) {
+    let source_path = std::env::temp_dir().join(format!(
+        "restrict_lang_cli_time_{}_{}.rl",
+        stem,
+        std::process::id()
+    ));
+    let output_path = source_path.with_extension("wat");
+    fs::write(
+        &source_path,
+        "fun main: () -> Int32 = {\n    val x = 40;\n    x + 2\n}\n",
+    )
+    .expect("timed source should be writable");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
+        .arg("--time")
+        .args(extra_args)
+        .arg(&source_path)
+        .arg(&output_path)
+        .output()
+        .expect("restrict_lang binary should run");
+    let _ = fs::remove_file(&source_path);
+    let _ = fs::remove_file(&output_path);
+
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "--time failed, stderr: {stderr}");
+    (stdout, stderr)
+}
+
+fn timing_ms(stdout: &str, label: &str) -> Option<f64> {
+    stdout.lines().find_map(|line| {
+        let value = line.strip_prefix(label)?.trim();
+        Some(
+            value
+                .parse::<f64>()
+                .unwrap_or_else(|_| panic!("{label} timing should be a number, line: {line:?}")),
+        )
+    })
+}
+
+#[test]
+fn cli_time_reports_each_phase_and_total() {
+    let (stdout, _) = run_timed_temp_source("full", &[]);
+    assert!(stdout.contains("Successfully compiled to"), "{stdout}");
+    for label in ["lexing", "parsing", "type check", "codegen", "total"] {
+        let ms = timing_ms(&stdout, label)
+            .unwrap_or_else(|| panic!("--time should report {label}, stdout: {stdout}"));
+        assert!(ms >= 0.0, "{label} took {ms} ms, stdout: {stdout}");
+    }
+}
+
+#[test]
+fn cli_time_with_check_stops_after_type_checking() {
+    let (stdout, _) = run_timed_temp_source("check", &["--check"]);
+    assert!(stdout.starts_with("OK "), "{stdout}");
+    for label in ["lexing", "parsing", "type check", "total"] {
+        assert!(
+            timing_ms(&stdout, label).is_some_and(|ms| ms >= 0.0),
+            "--check --time should report {label}, stdout: {stdout}"
+        );
+    }
+    assert!(
+        timing_ms(&stdout, "codegen").is_none(),
+        "--check --time should not time codegen, stdout: {stdout}"
+    );
+}
+
+fn run_tokens_temp_source(stem: &str, flag: &str, source: &str) -> Output {
+    let source_path = std::env::temp_dir().join(format!(
+        "restrict_lang_cli_tokens_{}_{}.rl",
+        stem,
+        std::process::id()
+    ));
+    fs::write(&source_path, source).expect("token source should be writable");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
+        .arg(flag)
+        .arg(&source_path)
+        .output()
+        .expect("restrict_lang binary should run");
+    let _ = fs::remove_file(&source_path);
+    output
+}
+
+#[test]
+fn cli_repl_reports_types_from_stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
+        .arg("--repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("restrict_lang binary should run");
+    child
+        .stdin
+        .take()
+        .expect("stdin should be piped")
+        .write_all(b"val answer = 42\nanswer + 1\n:quit\n")
+        .expect("REPL input should be writable");
+    let output = child.wait_with_output().expect("REPL should exit");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "--repl failed: {stdout}");
+    assert!(stdout.contains("answer: Int32"), "stdout: {stdout}");
+    assert!(stdout.contains("> Int32"), "stdout: {stdout}");
+}
+
+#[test]
+fn cli_lib_flag_skips_start_wrapper() {
+    let source = "export fun add: (a: Int32, b: Int32) -> Int32 = { a + b }\n\nfun main: () -> () = { () }\n";
+    let source_path =
+        std::env::temp_dir().join(format!("restrict_lang_cli_lib_{}.rl", std::process::id()));
+    let output_path = source_path.with_extension("wat");
+    fs::write(&source_path, source).expect("library source should be writable");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
+        .arg("--lib")
+        .arg(&source_path)
+        .arg(&output_path)
+        .output()
+        .expect("restrict_lang binary should run");
+    assert_success_streams("--lib", &output);
+
+    let wat = fs::read_to_string(&output_path).expect("library WAT should be written");
+    let _ = fs::remove_file(&source_path);
+    let _ = fs::remove_file(&output_path);
+    assert!(wat.contains("(export \"add\""), "{wat}");
+    assert!(!wat.contains("(export \"_start\""), "{wat}");
+    assert_instantiable_wat("--lib", &wat);
+}
+
+#[test]
+fn cli_tokens_prints_one_line_per_token() {
+    let source = "fun main = {\n    val s = \"a\\nb\"\n    (s) print // show it\n}\n";
+    let output = run_tokens_temp_source("lines", "--tokens", source);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "--tokens failed: {stderr}");
+    assert!(stderr.trim().is_empty(), "unexpected stderr: {stderr}");
+
+    let (_, tokens) = restrict_lang::lex(source).expect("source should lex");
+    assert_eq!(stdout.lines().count(), tokens.len(), "stdout: {stdout}");
+    assert_eq!(stdout.lines().next(), Some("1:1\tFun\t\"fun\""));
+    assert!(stdout.contains("2:13\tStringLit\t\"\\\"a\\\\nb\\\"\""));
+    assert!(
+        !stdout.contains("=== Lexing ==="),
+        "--tokens should print only tokens, stdout: {stdout}"
+    );
+}
+
+#[test]
+fn cli_tokens_json_reports_kinds_and_spans() {
+    let source = "val total = (a, 10) add";
+    let output = run_tokens_temp_source("json", "--tokens-json", source);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "--tokens-json failed: {stderr}");
+
+    let tokens: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("--tokens-json should print JSON");
+    let tokens = tokens.as_array().expect("token dump should be an array");
+    assert_eq!(tokens.len(), 9);
+    assert_eq!(tokens[1]["kind"], "Ident");
+    assert_eq!(tokens[1]["text"], "total");
+    assert_eq!(tokens[1]["start"], 4);
+    assert_eq!(tokens[1]["end"], 9);
+    assert_eq!(tokens[6]["kind"], "IntLit");
+    assert_eq!(tokens[6]["column"], 17);
+}
+
+#[test]
+fn cli_tokens_warns_about_unlexed_input() {
+    let output = run_tokens_temp_source("leftover", "--tokens", "val x = $ 1\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(stdout.lines().count(), 3, "stdout: {stdout}");
+    assert!(
+        stderr.contains("Warning: Lexer unparsed input remaining at line 1, column 9"),
+        "stderr: {stderr}"
+    );
+}
+
 #[test]
 fn cli_version_reports_package_version_on_stdout() {
     let output = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
@@ -215,6 +404,147 @@ fn cli_version_reports_package_version_on_stdout() {
     );
 }
 
+#[test]
+fn cli_explain_prints_error_code_description() {
+    let output = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
+        .args(["--explain", "E0001"])
+        .output()
+        .expect("restrict_lang binary should run");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "--explain E0001 should succeed");
+    assert!(stdout.starts_with("E0001: value used after it was consumed"));
+    assert!(stdout.contains("Erroneous example:"), "stdout: {stdout}");
+
+    let unknown = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
+        .args(["--explain", "E9999"])
+        .output()
+        .expect("restrict_lang binary should run");
+    assert!(!unknown.status.success());
+    assert!(String::from_utf8_lossy(&unknown.stderr).contains("Unknown error code: E9999"));
+}
+
+#[test]
+fn cli_type_errors_report_their_error_code() {
+    let (source_path, output_path, output) = run_compile_temp_source(
+        "affine_code",
+        "fun twice: (name: String) -> String = {\n    val first = name;\n    name\n}\n",
+    );
+    let _ = fs::remove_file(&source_path);
+    let _ = fs::remove_file(&output_path);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("Type error[E0001]: Variable name has already been used"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("--explain E0001"),
+        "stderr should point at --explain: {stderr}"
+    );
+}
+
+fn run_check_with_many_errors(stem: &str, count: usize, extra_args: &[&str]) -> Output {
+    let source: String = (0..count)
+        .map(|i| format!("fun broken_{i}: () -> Int32 = {{ missing_{i} }}\n"))
+        .collect();
+    let source_path = std::env::temp_dir().join(format!(
+        "restrict_lang_cli_many_errors_{}_{}.rl",
+        stem,
+        std::process::id()
+    ));
+    fs::write(&source_path, source).expect("source should be writable");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
+        .args(extra_args)
+        .arg("--check")
+        .arg(&source_path)
+        .output()
+        .expect("restrict_lang binary should run");
+    let _ = fs::remove_file(&source_path);
+    output
+}
+
+#[test]
+fn cli_caps_type_errors_at_twenty_by_default() {
+    let output = run_check_with_many_errors("default", 25, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success());
+    let reported: Vec<_> = stderr
+        .lines()
+        .filter(|line| line.starts_with("Type error["))
+        .collect();
+    assert_eq!(reported.len(), 20, "stderr: {stderr}");
+    assert_eq!(
+        reported[0],
+        "Type error[E0002]: Undefined variable: missing_0"
+    );
+    assert_eq!(
+        reported[19],
+        "Type error[E0002]: Undefined variable: missing_19"
+    );
+    assert!(
+        stderr.contains("... and 5 more errors\n"),
+        "stderr: {stderr}"
+    );
+    assert!(stderr.contains("--explain E0002"), "stderr: {stderr}");
+}
+
+#[test]
+fn cli_max_errors_sets_the_cap() {
+    let output = run_check_with_many_errors("capped", 4, &["--max-errors", "3"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("Type error[").count(), 3, "stderr: {stderr}");
+    assert!(
+        stderr.contains("... and 1 more error\n"),
+        "stderr: {stderr}"
+    );
+
+    let output = run_check_with_many_errors("uncapped", 4, &["--max-errors", "4"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("Type error[").count(), 4, "stderr: {stderr}");
+    assert!(!stderr.contains("more error"), "stderr: {stderr}");
+
+    let output = run_check_with_many_errors("invalid", 1, &["--max-errors", "many"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--max-errors requires a number"));
+}
+
+#[test]
+fn cli_dump_typed_ast_annotates_expressions_with_inferred_types() {
+    let source_path = std::env::temp_dir().join(format!(
+        "restrict_lang_cli_typed_ast_{}.rl",
+        std::process::id()
+    ));
+    fs::write(
+        &source_path,
+        "fun main: () -> Int32 = {\n    val names = [\"a\", \"b\"];\n    41 + 1\n}\n",
+    )
+    .expect("source should be writable");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_restrict_lang"))
+        .arg("--dump-typed-ast")
+        .arg(&source_path)
+        .output()
+        .expect("restrict_lang binary should run");
+    let _ = fs::remove_file(&source_path);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Function main"), "stdout: {stdout}");
+    assert!(stdout.contains("IntLit 41 : Int32"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("ListLit : List<String>"),
+        "stdout: {stdout}"
+    );
+}
+
 #[test]
 fn cli_compiles_release_example_to_valid_wat() {
     let output_path = std::env::temp_dir().join(format!(
@@ -796,7 +1126,7 @@ fn cli_compile_unresolved_inference_stops_before_codegen() {
         assert!(!output.status.success(), "{stem} compile should fail");
         let stderr = String::from_utf8_lossy(&output.stderr);
         assert!(
-            stderr.contains("Type error:"),
+            stderr.contains("Type error["),
             "{stem} should fail in type checking before codegen, got: {stderr}"
         );
         assert!(
@@ -44,6 +44,7 @@ fn record_decl(name: &str, fields: Vec<(&str, Type)>) -> TopDecl {
             .map(|(name, ty)| FieldDecl {
                 name: name.to_string(),
                 ty,
+                default: None,
             })
             .collect(),
         frozen: false,
@@ -66,7 +67,9 @@ fun main: () -> Int32 = {
 
     let err = compile_to_wat(source).expect_err("exported generic function should need an ABI");
     assert!(
-        err.contains("Codegen error: Unsupported feature: Exported generic function 'keep'"),
+        err.contains(
+            "Codegen error: Exported function 'keep' has a non-exportable signature: parameter 'value' type T is an un-monomorphized type parameter"
+        ),
         "error should explain exported generic ABI limitation, got: {err}"
     );
     assert!(
@@ -75,6 +78,113 @@ fun main: () -> Int32 = {
     );
 }
 
+#[test]
+fn exported_scalar_signature_is_exportable() {
+    let source = r#"
+export fun scale: (value: Int32, factor: Float64) -> Boolean = {
+    value > 0
+}
+
+fun main: () -> Int32 = {
+    0
+}
+"#;
+
+    let wat = compile_to_wat(source).expect("scalar export should compile");
+    assert!(wat.contains("(export \"scale\" (func $scale))"), "{wat}");
+}
+
+#[test]
+fn exported_function_returning_closure_is_non_exportable() {
+    let source = r#"
+export fun adder: (base: Int32) -> (Int32) -> Int32 = {
+    |value| base + value
+}
+
+fun main: () -> Int32 = {
+    0
+}
+"#;
+
+    let err = compile_to_wat(source).expect_err("closure return should not cross the ABI");
+    assert!(
+        err.contains(
+            "Exported function 'adder' has a non-exportable signature: return type (Int32) -> Int32 is a function type"
+        ),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn exported_function_with_temporal_parameter_is_non_exportable() {
+    let source = r#"
+record Handle<~h> {
+    id: Int32
+}
+
+export fun handle_id: <~h>(handle: Handle<~h>) -> Int32 = {
+    handle.id
+}
+
+fun main: () -> Int32 = {
+    0
+}
+"#;
+
+    // Temporal signatures are outside the checker's v0.0.1 surface, so this
+    // exercises codegen directly.
+    let program = parse_complete(source);
+    let err = WasmCodeGen::new()
+        .generate(&program)
+        .expect_err("temporal parameter should not cross the ABI")
+        .to_string();
+    assert!(
+        err.contains(
+            "Exported function 'handle_id' has a non-exportable signature: parameter 'handle' type Handle<~h> carries temporal parameters"
+        ),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn exported_function_with_nested_type_parameter_is_non_exportable() {
+    let source = r#"
+export fun first_or: <T>(items: List<T>, fallback: T) -> T = {
+    fallback
+}
+
+fun main: () -> Int32 = {
+    0
+}
+"#;
+
+    let err = compile_to_wat(source).expect_err("generic list should not cross the ABI");
+    assert!(
+        err.contains(
+            "Exported function 'first_or' has a non-exportable signature: parameter 'items' type List<T> contains T, which is an un-monomorphized type parameter"
+        ),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn exported_composite_signatures_reach_the_host_abi_check() {
+    for (signature, body) in [
+        ("(label: String) -> Int32", "0"),
+        ("(scores: List<Int32>) -> Int32", "0"),
+        ("(value: Int32) -> Option<Int32>", "Some(value)"),
+    ] {
+        let source = format!(
+            "export fun public_value: {signature} = {{\n    {body}\n}}\n\nfun main: () -> Int32 = {{\n    0\n}}\n"
+        );
+        let err = compile_to_wat(&source).expect_err("composite exports need a host ABI");
+        assert!(
+            !err.contains("non-exportable signature") && err.contains("composite host ABI"),
+            "{signature} should be representable but gated by the host ABI: {err}"
+        );
+    }
+}
+
 #[test]
 fn unsupported_export_kind_lists_current_codegen_export_surface() {
     let source = r#"
@@ -175,6 +285,7 @@ fn prototype_identity_clone_rejects_with_explicit_codegen_error() {
             TopDecl::Function(FunDecl {
                 name: "main".to_string(),
                 is_async: false,
+                is_init: false,
                 type_params: Vec::new(),
                 temporal_constraints: Vec::new(),
                 params: Vec::new(),
@@ -889,8 +1000,13 @@ fun main: () -> List<Int32> = {
 fn println_rejects_unsupported_argument_type_instead_of_string_fallback() {
     let program = parse_complete(
         r#"
+record Point {
+    x: Int32,
+    y: Int32
+}
+
 fun main: () -> () = {
-    3.14 |> println
+    Point { x: 1, y: 2 } |> println
 }
 "#,
     );
@@ -902,7 +1018,51 @@ fun main: () -> () = {
     let message = err.to_string();
 
     assert!(
-        message.contains("println does not support argument type Float64"),
+        message.contains("println does not support argument type Point"),
         "error should identify the unsupported println argument type, got: {message}"
     );
 }
+
+#[test]
+fn constant_zero_divisor_is_a_codegen_error() {
+    for (source, op) in [
+        ("fun main: () -> Int32 = {\n    10 / 0\n}\n", "/"),
+        ("fun main: () -> Int32 = {\n    10 % 0\n}\n", "%"),
+        ("fun ratio: (a: Int64) -> Int64 = {\n    a / 0\n}\n", "/"),
+        ("fun main: () -> Decimal = {\n    1.5d / 0.0d\n}\n", "/"),
+    ] {
+        let program = parse_complete(source);
+        let mut codegen = WasmCodeGen::new();
+        let err = codegen
+            .generate(&program)
+            .expect_err("literal zero divisor should be rejected at compile time");
+        assert!(
+            matches!(&err, restrict_lang::CodeGenError::DivisionByZero(found) if found == op),
+            "expected DivisionByZero for '{op}', got: {err}"
+        );
+    }
+}
+
+#[test]
+fn runtime_integer_division_emits_zero_divisor_guard() {
+    let wat = compile_to_wat(
+        r#"
+fun ratio: (a: Int32, b: Int32) -> Int32 = {
+    a / b
+}
+
+fun main: () -> Int32 = {
+    (10, 2) ratio
+}
+"#,
+    )
+    .expect("runtime division should compile");
+
+    assert!(
+        wat.contains(";; runtime guard: integer division by zero panics"),
+        "generated WAT should annotate the divisor guard:\n{wat}"
+    );
+    assert!(wat.contains("local.tee $div_rhs_i32"), "{wat}");
+    assert!(wat.contains("call $panic"), "{wat}");
+    assert!(wat.contains("division by zero"), "{wat}");
+}
@@ -0,0 +1,116 @@
+use restrict_lang::ast::{ExprKind, Program, Stmt, TopDecl};
+use restrict_lang::const_eval::fold_constants;
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+
+fn parse(source: &str) -> Program {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    ast
+}
+
+fn check_err(source: &str) -> String {
+    TypeChecker::new()
+        .check_program(&parse(source))
+        .expect_err("source should be rejected")
+        .to_string()
+}
+
+/// The folded value of each `val` in `main`, in order.
+fn folded_bindings(source: &str) -> Vec<ExprKind> {
+    let program = fold_constants(&parse(source));
+    let TopDecl::Function(main) = &program.declarations[0] else {
+        panic!("expected main to be the first declaration");
+    };
+    main.body
+        .statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Binding(binding) => Some(binding.value.kind.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn integer_arithmetic_folds_to_a_literal() {
+    assert_eq!(
+        folded_bindings("fun main: () -> Int32 = {\n    val x = 2 + 3 * 4;\n    x\n}\n"),
+        vec![ExprKind::IntLit(14)]
+    );
+}
+
+#[test]
+fn float_and_boolean_constants_fold() {
+    assert_eq!(
+        folded_bindings(
+            r#"
+fun main: () -> Int32 = {
+    val area = 1.5 * 2.0;
+    val flag = !(1 < 2) || true;
+    val negative = -(7 - 10);
+    0
+}
+"#
+        ),
+        vec![
+            ExprKind::FloatLit(3.0),
+            ExprKind::BoolLit(true),
+            ExprKind::IntLit(3),
+        ]
+    );
+}
+
+#[test]
+fn non_constant_operands_are_left_alone() {
+    let folded = folded_bindings(
+        "fun main: () -> Int32 = {\n    val a = 1;\n    val b = a + 2 * 3;\n    b\n}\n",
+    );
+    let ExprKind::Binary(binary) = &folded[1] else {
+        panic!("a + 6 should stay a binary expression: {:?}", folded[1]);
+    };
+    assert_eq!(binary.right.kind, ExprKind::IntLit(6));
+}
+
+#[test]
+fn constant_overflow_is_a_type_error() {
+    let message = check_err("fun main: () -> Int32 = {\n    val x = 2147483647 + 1;\n    x\n}\n");
+    assert!(
+        message.contains("2147483647 + 1 overflows Int32"),
+        "{message}"
+    );
+
+    let message = check_err("fun main: () -> Int64 = {\n    9223372036854775807L + 1L\n}\n");
+    assert!(
+        message.contains("9223372036854775807 + 1 overflows Int64"),
+        "{message}"
+    );
+}
+
+#[test]
+fn int64_context_widens_constant_arithmetic() {
+    let source = "fun main: () -> Int64 = {\n    val x: Int64 = 2147483647 + 1;\n    x\n}\n";
+    TypeChecker::new()
+        .check_program(&parse(source))
+        .expect("the sum fits Int64");
+
+    // Folding cannot know the width, so the sum is left for Int64 codegen.
+    assert!(matches!(folded_bindings(source)[0], ExprKind::Binary(_)));
+}
+
+#[test]
+fn codegen_emits_folded_constants() {
+    let ast = parse("fun main: () -> Int32 = {\n    val x = 2 + 3 * 4;\n    x\n}\n");
+    TypeChecker::new()
+        .check_program(&ast)
+        .expect("source should type check");
+    let wat = WasmCodeGen::new()
+        .generate(&ast)
+        .expect("source should compile");
+    let main = &wat[wat.find("(func $main").expect("main should be emitted")..];
+    let main = &main[..main.find("\n  )").unwrap()];
+    assert!(main.contains("i32.const 14"), "{main}");
+    assert!(!main.contains("i32.mul"), "{main}");
+}
@@ -85,3 +85,123 @@ fun main: () -> Int32 = {
     let err = type_check(input).expect_err("context field bindings should be type checked");
     assert!(err.contains("Type mismatch"), "unexpected error: {err}");
 }
+
+#[test]
+fn qualified_context_field_reads_the_with_binding() {
+    let input = r#"
+context DB {
+    host: String,
+    port: Int32
+}
+
+fun main: () -> Int32 = {
+    with DB { host: "localhost", port: 5432 } {
+        DB.port + 1
+    }
+}
+"#;
+
+    type_check(input).expect("DB.port should read the field bound by the enclosing with");
+}
+
+#[test]
+fn qualified_context_field_is_affine_like_the_bare_binding() {
+    let input = r#"
+context DB {
+    host: String
+}
+
+fun main: () -> String = {
+    with DB { host: "localhost" } {
+        val first = DB.host;
+        host
+    }
+}
+"#;
+
+    let err = type_check(input).expect_err("DB.host and host are the same affine binding");
+    assert!(err.contains("already been used"), "unexpected error: {err}");
+}
+
+#[test]
+fn qualified_context_field_outside_with_is_unavailable() {
+    let input = r#"
+context DB {
+    port: Int32
+}
+
+fun main: () -> Int32 = {
+    DB.port
+}
+"#;
+
+    let err = type_check(input).expect_err("DB.port needs an enclosing with DB");
+    assert!(
+        err.contains("Context DB is not available in this scope"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn qualified_context_field_rejects_unknown_field() {
+    let input = r#"
+context DB {
+    port: Int32
+}
+
+fun main: () -> Int32 = {
+    with DB { port: 5432 } {
+        DB.timeout
+    }
+}
+"#;
+
+    let err = type_check(input).expect_err("DB.timeout is not a declared context field");
+    assert!(
+        err.contains("Unknown field timeout in record DB"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn named_context_instance_binds_fields() {
+    let input = r#"
+context DB {
+    host: String,
+    port: Int32
+}
+
+fun main: () -> Int32 = {
+    with DB = DB { host: "x", port: 5 } {
+        DB.port
+    }
+}
+"#;
+
+    type_check(input).expect("with DB = DB { ... } should bind the context's fields");
+}
+
+#[test]
+fn named_context_instance_must_match_the_context() {
+    let input = r#"
+context DB {
+    port: Int32
+}
+
+context Cache {
+    port: Int32
+}
+
+fun main: () -> Int32 = {
+    with DB = Cache { port: 5 } {
+        DB.port
+    }
+}
+"#;
+
+    let err = type_check(input).expect_err("the instance must be built from the entered context");
+    assert!(
+        err.contains("must build an instance of the same context"),
+        "unexpected error: {err}"
+    );
+}
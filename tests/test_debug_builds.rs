@@ -0,0 +1,91 @@
+use restrict_lang::{parse_program, CodeGenError, TypeChecker, WasmCodeGen};
+
+fn compile(source: &str, release: bool) -> Result<String, CodeGenError> {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .expect("source should type check");
+    let mut codegen = WasmCodeGen::new();
+    codegen.release(release);
+    codegen.generate(&ast)
+}
+
+fn function_body<'a>(wat: &'a str, name: &str) -> &'a str {
+    let start = wat
+        .find(&format!("(func ${name} "))
+        .unwrap_or_else(|| panic!("{name} should be emitted"));
+    &wat[start..start + wat[start..].find("\n  )").unwrap()]
+}
+
+const CHECKED_DIVIDE: &str = r#"
+fun divide: (a: Int32, b: Int32) -> Int32 = {
+    (b != 0, "divisor must be non-zero") debug_assert;
+    "dividing" |> debug_print;
+    a / b
+}
+
+fun main: () -> Int32 = {
+    (84, 2) divide
+}
+"#;
+
+#[test]
+fn debug_builds_compile_debug_calls_to_assert_and_println() {
+    let wat = compile(CHECKED_DIVIDE, false).expect("debug build should compile");
+    let divide = function_body(&wat, "divide");
+    assert!(divide.contains("call $assert"), "{divide}");
+    assert!(divide.contains("call $println"), "{divide}");
+}
+
+#[test]
+fn release_builds_omit_debug_calls() {
+    let wat = compile(CHECKED_DIVIDE, true).expect("release build should compile");
+    let divide = function_body(&wat, "divide");
+    assert!(!divide.contains("call $assert"), "{divide}");
+    assert!(!divide.contains("call $println"), "{divide}");
+    assert!(divide.contains("i32.div_s"), "{divide}");
+    assert!(
+        !wat.contains("divisor must be non-zero"),
+        "the elided message should not reach the data section"
+    );
+}
+
+#[test]
+fn release_builds_accept_pure_std_helpers_in_debug_arguments() {
+    let source = r#"
+fun main: () -> Int32 = {
+    val total = 40 + 2;
+    ("total: " + (total |> int_to_string)) |> debug_print;
+    total
+}
+"#;
+    let wat = compile(source, true).expect("pure arguments may be dropped");
+    assert!(!function_body(&wat, "main").contains("call $println"));
+}
+
+#[test]
+fn release_builds_reject_side_effecting_debug_arguments() {
+    let source = r#"
+fun log_and_check: (value: Int32) -> Boolean = {
+    "checking" |> println;
+    value > 0
+}
+
+fun main: () -> Int32 = {
+    (7 |> log_and_check, "value must be positive") debug_assert;
+    7
+}
+"#;
+    compile(source, false).expect("debug builds keep the effect");
+
+    let err = compile(source, true).expect_err("release would drop the println");
+    assert!(
+        matches!(&err, CodeGenError::EffectfulDebugArgument(name) if name == "debug_assert"),
+        "{err}"
+    );
+    assert!(err.to_string().contains("side effects"), "{err}");
+}
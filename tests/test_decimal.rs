@@ -0,0 +1,204 @@
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn check(source: &str) -> Result<(), String> {
+    let (remaining, ast) = parse_program(source).map_err(|error| format!("{error:?}"))?;
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .map_err(|error| error.to_string())
+}
+
+fn compile_to_wat(source: &str) -> String {
+    check(source).expect("source should type check");
+    let (_, ast) = parse_program(source).expect("source should parse");
+    let mut codegen = WasmCodeGen::new();
+    codegen.generate(&ast).expect("source should generate WAT")
+}
+
+fn instantiate(source: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let wat = compile_to_wat(source);
+    let wasm = wat::parse_str(&wat)?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+#[test]
+fn decimal_literals_and_arithmetic_type_check() {
+    check(
+        r#"
+record Invoice { net: Decimal, rate: Decimal }
+
+fun gross: (invoice: Invoice) -> Decimal = {
+    val net = invoice.net;
+    net + net * invoice.rate
+}
+
+fun cheaper: (price: Decimal) -> Boolean = {
+    -price < 0d and price / 2.5d <= 19.99d
+}
+"#,
+    )
+    .expect("decimal arithmetic should type check");
+}
+
+#[test]
+fn decimal_does_not_mix_with_other_numbers() {
+    for (expr, found) in [
+        ("1.5d + 1", "Decimal and Int32"),
+        ("1.5d * 1.5", "Decimal and Float64"),
+    ] {
+        let error = check(&format!("fun main: () -> Decimal = {{ {expr} }}"))
+            .expect_err("mixed arithmetic should be rejected");
+        assert!(error.contains(found), "{expr}: {error}");
+    }
+}
+
+#[test]
+fn decimal_literals_keep_at_most_four_places() {
+    assert!(check("fun main: () -> Decimal = { 1.2345d }").is_ok());
+    assert!(check("fun main: () -> Decimal = { 1.23456d }").is_err());
+    assert!(check("fun main: () -> Decimal = { 0.00001d }").is_err());
+}
+
+#[test]
+fn decimal_codegen_uses_scaled_i64() {
+    let wat = compile_to_wat(
+        r#"
+export fun price: () -> Decimal = {
+    1.99d
+}
+"#,
+    );
+    assert!(wat.contains("(result i64)"), "{wat}");
+    assert!(wat.contains("i64.const 19900"), "{wat}");
+}
+
+// One export keeps the entry-point arenas inside a single page.
+const SOURCE: &str = r#"
+export fun decimal_case: (index: Int32, a: Decimal, b: Decimal) -> Decimal = {
+    index match {
+        0 => { a + b }
+        1 => { a - b }
+        2 => { a * b }
+        3 => { a / b }
+        4 => { (a, b) decimal_div_half_up }
+        5 => { (a, b) decimal_div_half_even }
+        _ => { (a, b) max }
+    }
+}
+
+export fun tenths_add_up: () -> Boolean = {
+    0.1d + 0.2d == 0.3d
+}
+"#;
+
+#[test]
+fn decimal_sums_are_exact() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(SOURCE)?;
+    let tenths_add_up = instance.get_typed_func::<(), i32>(&store, "tenths_add_up")?;
+    assert_eq!(tenths_add_up.call(&mut store, ())?, 1);
+    assert_ne!(0.1_f64 + 0.2_f64, 0.3_f64);
+    Ok(())
+}
+
+#[test]
+fn decimal_arithmetic_rescales_and_rounds() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(SOURCE)?;
+    let decimal_case = instance.get_typed_func::<(i32, i64, i64), i64>(&store, "decimal_case")?;
+    let mut run = |index, a, b| decimal_case.call(&mut store, (index, a, b));
+
+    // Values are scaled by 10_000: 19_900 is 1.99.
+    assert_eq!(run(0, 19_900, 1)?, 19_901);
+    assert_eq!(run(1, 10_000, 25_000)?, -15_000);
+    // 1.99 * 3 = 5.97 and 0.0005 * 0.5 = 0.00025, which ties to 0.0002.
+    assert_eq!(run(2, 19_900, 30_000)?, 59_700);
+    assert_eq!(run(2, 5, 5_000)?, 2);
+    assert_eq!(run(2, -5, 5_000)?, -2);
+    // 1_000_000 * 1_000_000 stays exact although the scaled a * b would
+    // overflow i64.
+    let million = 1_000_000 * 10_000;
+    assert_eq!(run(2, million, million)?, million * 1_000_000);
+    // 1 / 3 = 0.3333 and 2 / 3 = 0.6667.
+    assert_eq!(run(3, 10_000, 30_000)?, 3_333);
+    assert_eq!(run(3, 20_000, 30_000)?, 6_667);
+    // 0.0001 / 2 is exactly half a unit: `/` rounds to even, half-up away
+    // from zero.
+    assert_eq!(run(3, 1, 20_000)?, 0);
+    assert_eq!(run(3, 3, 20_000)?, 2);
+    assert_eq!(run(4, 1, 20_000)?, 1);
+    assert_eq!(run(4, -1, 20_000)?, -1);
+    assert_eq!(run(5, 1, 20_000)?, 0);
+    assert_eq!(run(5, 1, -20_000)?, 0);
+    assert_eq!(run(5, 3, -20_000)?, -2);
+    assert_eq!(run(6, -19_900, 5)?, 5);
+    Ok(())
+}
+
+#[test]
+fn decimal_multiplication_rounds_the_exact_product_once() -> Result<(), Box<dyn std::error::Error>>
+{
+    let (mut store, instance) = instantiate(SOURCE)?;
+    let decimal_case = instance.get_typed_func::<(i32, i64, i64), i64>(&store, "decimal_case")?;
+    let mut mul = |a, b| decimal_case.call(&mut store, (2, a, b));
+
+    // 1.5 * 0.0001 = 0.00015 ties to the even 0.0002, and 2.5 * 0.0001 =
+    // 0.00025 ties down to 0.0002.
+    assert_eq!(mul(15_000, 1)?, 2);
+    assert_eq!(mul(25_000, 1)?, 2);
+    assert_eq!(mul(-15_000, 1)?, -2);
+    assert_eq!(mul(15_000, -1)?, -2);
+    // 0.5 * 0.0003 = 0.00015 and 0.0003 * 0.5, in both operand orders.
+    assert_eq!(mul(5_000, 3)?, 2);
+    assert_eq!(mul(3, 5_000)?, 2);
+    // Above and below the half round to nearest.
+    assert_eq!(mul(15_001, 1)?, 2);
+    assert_eq!(mul(14_999, 1)?, 1);
+    // 1.2345 * 6.789 = 8.3810205 rounds using every dropped digit.
+    assert_eq!(mul(12_345, 67_890)?, 83_810);
+    Ok(())
+}
+
+#[test]
+fn decimal_multiplication_overflow_panics() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(SOURCE)?;
+    let decimal_case = instance.get_typed_func::<(i32, i64, i64), i64>(&store, "decimal_case")?;
+    let big = i64::MAX / 2;
+    assert!(decimal_case.call(&mut store, (2, big, 30_000)).is_err());
+    assert!(decimal_case
+        .call(&mut store, (2, i64::MIN, 10_000))
+        .is_err());
+    assert_eq!(decimal_case.call(&mut store, (2, big, 10_000))?, big);
+    Ok(())
+}
+
+#[test]
+fn decimal_division_by_zero_panics() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(SOURCE)?;
+    let decimal_case = instance.get_typed_func::<(i32, i64, i64), i64>(&store, "decimal_case")?;
+    assert!(decimal_case.call(&mut store, (3, 10_000, 0)).is_err());
+    Ok(())
+}
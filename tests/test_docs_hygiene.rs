@@ -66,7 +66,8 @@ const WARDER_PUBLIC_DOCS: &[&str] = &[
 ];
 
 const SUPPORTED_WARDER_SUBCOMMANDS: &[&str] = &[
-    "new", "init", "add", "remove", "build", "run", "test", "publish", "wrap", "unwrap", "doctor",
+    "new", "init", "add", "remove", "build", "clean", "run", "test", "bench", "publish", "wrap",
+    "unwrap", "doctor",
 ];
 
 const KNOWN_EXPERIMENTAL_OR_STALE_EXAMPLES: &[&str] = &[
@@ -395,18 +396,6 @@ fn unsupported_warder_public_doc_claims(markdown: &str) -> Vec<String> {
                 ));
                 continue;
             }
-
-            if subcommand == "build"
-                && invocation
-                    .tokens
-                    .iter()
-                    .any(|token| token == "--target" || token.starts_with("--target="))
-            {
-                failures.push(format!(
-                    "line {line_number}: `warder build --target` is not implemented by warder/src/main.rs: {}",
-                    line.trim()
-                ));
-            }
         }
 
         if line_claims_target_warder_artifact(line) {
@@ -586,9 +575,14 @@ fn assert_no_host_network_io_symbols(label: &str, source: &str) {
 }
 
 fn assert_no_removed_v001_syntax_patterns(label: &str, source: &str) {
+    // `if` survives only as a match guard between a pattern and `=>`.
     let code_only = source
         .lines()
         .map(strip_line_comment)
+        .map(|line| match (line.find(" if "), line.find("=>")) {
+            (Some(guard), Some(arrow)) if guard < arrow => line.replacen(" if ", " ", 1),
+            _ => line.to_string(),
+        })
         .collect::<Vec<_>>()
         .join("\n");
     let stale_substrings = [
@@ -598,9 +592,7 @@ fn assert_no_removed_v001_syntax_patterns(label: &str, source: &str) {
         ("for loop", "for "),
         ("use import", "use "),
         ("unsupported test declaration", "test \""),
-        ("lowercase i32", "i32"),
         ("lowercase f64", "f64"),
-        ("lowercase bool", "bool"),
         ("user enum declaration", "enum "),
         ("bracket-bar array literal", "[|"),
     ];
@@ -612,7 +604,11 @@ fn assert_no_removed_v001_syntax_patterns(label: &str, source: &str) {
         );
     }
 
+    // `i32` and `bool` are matched as whole words so builtins such as
+    // `to_i32` and `bool_to_string` are allowed.
     for (description, word) in [
+        ("lowercase i32", "i32"),
+        ("lowercase bool", "bool"),
         ("legacy Unit spelling", "Unit"),
         ("legacy Bool spelling", "Bool"),
         ("legacy Int spelling", "Int"),
@@ -1259,6 +1255,12 @@ fn update_record_context_stack(line: &str, stack: &mut Vec<bool>) {
 fn opens_record_context(prefix: &str) -> bool {
     let trimmed = prefix.trim_end();
 
+    // A brace right after a closed group, as in `with Ctx { } { body }`,
+    // opens a block body rather than a record initializer.
+    if trimmed.ends_with('}') {
+        return false;
+    }
+
     if trimmed.ends_with("=>")
         || trimmed.ends_with("then")
         || trimmed.ends_with("else")
@@ -178,3 +178,30 @@ export fun literal_profile_score: () -> Int32 = {
         1333
     );
 }
+
+#[test]
+fn scientific_float_literal_type_checks_as_float64_and_lowers_to_f64_const() {
+    let source = r#"
+export fun hundred: () -> Float64 = {
+    val value: Float64 = 1.0e2;
+    value
+}
+"#;
+    type_check_source(source).expect("1.0e2 should type-check as Float64");
+    let wat = compile_to_wat(source).expect("scientific float literal should compile to WAT");
+    assert!(
+        wat.contains("f64.const 100"),
+        "1.0e2 should lower to an f64 constant:\n{wat}"
+    );
+
+    let (mut store, instance) = instantiate_wat("scientific float literal", &wat);
+    let hundred = instance
+        .get_typed_func::<(), f64>(&store, "hundred")
+        .expect("hundred should be host-callable");
+    assert_eq!(
+        hundred
+            .call(&mut store, ())
+            .expect("hundred should execute"),
+        100.0
+    );
+}
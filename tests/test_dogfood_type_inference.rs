@@ -192,7 +192,8 @@ fn dogfood_generic_export_gap_is_rejected_by_codegen() {
     let err = compile_to_wat(source)
         .expect_err("exported generic dogfood should still need a concrete ABI");
     assert!(
-        err.contains("Exported generic function 'select_override' requires a concrete ABI"),
+        err.contains("Exported function 'select_override' has a non-exportable signature")
+            && err.contains("requires a concrete ABI"),
         "generic export gap should be visible as an explicit codegen rejection, got: {err}"
     );
 }
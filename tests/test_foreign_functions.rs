@@ -0,0 +1,92 @@
+use restrict_lang::{parse_program, ForeignFunction, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Linker, Module, Store};
+
+const SCALE: &str = "geometry.scale=(Int32, Int32) -> Int32";
+
+fn check(source: &str, foreign: &[&str]) -> Result<(), String> {
+    let (_, program) = parse_program(source).map_err(|e| format!("{e:?}"))?;
+    let mut checker = TypeChecker::new();
+    for spec in foreign {
+        checker
+            .declare_foreign_function(&ForeignFunction::parse(spec)?)
+            .map_err(|e| e.to_string())?;
+    }
+    checker.check_program(&program).map_err(|e| e.to_string())
+}
+
+#[test]
+fn foreign_functions_are_checked_like_program_functions() {
+    let source = "fun area: (w: Int32, h: Int32) -> Int32 = {\n    (w, h) scale\n}\n";
+    assert!(check(source, &[]).unwrap_err().contains("scale"));
+    check(source, &[SCALE]).unwrap();
+
+    let source = "fun area: (w: Float64) -> Int32 = {\n    (w, 2) scale\n}\n";
+    assert!(check(source, &[SCALE]).is_err());
+
+    let source = "fun area: (w: Int32) -> Boolean = {\n    (w, 2) scale\n}\n";
+    assert!(check(source, &[SCALE]).is_err());
+}
+
+#[test]
+fn foreign_functions_cannot_shadow_builtins() {
+    let err = check("", &["host.println=(Int32)"]).unwrap_err();
+    assert!(err.contains("shadow"), "{}", err);
+}
+
+#[test]
+fn foreign_functions_are_imported_from_the_host() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun area: (w: Int32, h: Int32) -> Int32 = {
+    (w, h) scale + 1
+}
+"#;
+    let (_, program) = parse_program(source).map_err(|e| format!("{e:?}"))?;
+    let scale = ForeignFunction::parse(SCALE)?;
+    let mut checker = TypeChecker::new();
+    checker.declare_foreign_function(&scale)?;
+    checker.check_program(&program)?;
+
+    let mut codegen = WasmCodeGen::new();
+    codegen.set_library(true);
+    codegen.declare_foreign_function(scale);
+    let wat = codegen.generate(&program)?;
+    assert!(wat.contains(
+        "(import \"geometry\" \"scale\" (func $scale (param i32) (param i32) (result i32)))"
+    ));
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wat::parse_str(&wat)?[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    linker.func_wrap("geometry", "scale", |a: i32, b: i32| -> i32 { a * b })?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+
+    let area = instance.get_typed_func::<(i32, i32), i32>(&store, "area")?;
+    assert_eq!(area.call(&mut store, (6, 7))?, 43);
+    Ok(())
+}
+
+#[test]
+fn foreign_functions_cannot_share_a_name_with_program_functions() {
+    let source = "fun scale: (a: Int32, b: Int32) -> Int32 = {\n    a\n}\n";
+    let (_, program) = parse_program(source).unwrap();
+    let mut codegen = WasmCodeGen::new();
+    codegen.set_library(true);
+    codegen.declare_foreign_function(ForeignFunction::parse(SCALE).unwrap());
+    let err = codegen.generate(&program).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("function 'scale' is declared by the program"));
+}
@@ -373,11 +373,7 @@ impl TestSummary {
         println!(
             "Passed: {} ({}%)",
             self.passed,
-            if self.total > 0 {
-                self.passed * 100 / self.total
-            } else {
-                0
-            }
+            (self.passed * 100).checked_div(self.total).unwrap_or(0)
         );
         println!("Failed: {}", self.failed);
 
@@ -1191,3 +1191,25 @@ fn release_readiness_example_compiles_captured_lambdas_to_wat() {
     assert!(wat.contains("$visibility_penalty_captured"));
     assert!(wat.contains("call_indirect"));
 }
+
+#[test]
+fn explicit_type_args_select_generic_specialization() {
+    let source = r#"
+fun keep: <T>(value: T) -> T = {
+    value
+}
+
+fun main: () -> Int32 = {
+    val wide = (41) keep<Int64>
+    0
+}
+"#;
+
+    let wat = compile_to_wat(source).expect("explicit Int64 instantiation should compile");
+
+    assert_specialized_function_and_call(&wat, "keep__Int64");
+    assert!(
+        !wat.contains("(func $keep__Int32"),
+        "explicit type arguments must override the literal's default type:\n{wat}"
+    );
+}
@@ -789,3 +789,59 @@ fun main: () -> List<String> = {
         err
     );
 }
+
+#[test]
+fn explicit_type_args_instantiate_generic_function() {
+    let input = r#"
+fun identity_local: <T>(value: T) -> T = {
+    value
+}
+
+fun main: () -> String = {
+    ("x") identity_local<String>
+}
+"#;
+
+    type_check(input).expect("explicit String type argument should match the String argument");
+}
+
+#[test]
+fn explicit_type_args_conflicting_with_inferred_type_are_rejected() {
+    let input = r#"
+fun identity_local: <T>(value: T) -> T = {
+    value
+}
+
+fun main: () -> Int32 = {
+    val text = (1) identity_local<String>
+    0
+}
+"#;
+
+    let err = type_check(input).expect_err("explicit String must not accept an Int32 argument");
+    assert!(
+        err.contains("Type mismatch: expected String, found Int32"),
+        "error should report the explicit/inferred conflict, got: {}",
+        err
+    );
+}
+
+#[test]
+fn explicit_type_args_must_match_type_parameter_count() {
+    let input = r#"
+fun identity_local: <T>(value: T) -> T = {
+    value
+}
+
+fun main: () -> Int32 = {
+    (1) identity_local<Int32, String>
+}
+"#;
+
+    let err = type_check(input).expect_err("two type arguments for one type parameter");
+    assert!(
+        err.contains("Wrong number of type arguments for identity_local: expected 1, found 2"),
+        "error should report the type argument count, got: {}",
+        err
+    );
+}
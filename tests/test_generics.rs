@@ -178,6 +178,68 @@ fun main: () -> Int32 = {
     }
 }
 
+#[test]
+fn test_where_clause_bound_is_enforced_like_inline_bound() {
+    for signature in [
+        "fun largest: <T: Ord>(a: T, b: T) -> T",
+        "fun largest: <T>(a: T, b: T) -> T where T: Ord",
+    ] {
+        let accepted = format!(
+            "{signature} = {{\n    a\n}}\n\nfun main: () -> Int32 = {{\n    (1, 2) largest\n}}\n"
+        );
+        type_check(&accepted).unwrap_or_else(|e| panic!("{signature}: {e}"));
+
+        let rejected = format!(
+            "{signature} = {{\n    a\n}}\n\nfun main: () -> Boolean = {{\n    (true, false) largest\n}}\n"
+        );
+        let err = type_check(&rejected).expect_err("Boolean does not implement Ord");
+        assert!(
+            err.contains("does not implement trait Ord"),
+            "{signature}: {err}"
+        );
+    }
+}
+
+#[test]
+fn test_where_clause_merges_with_inline_bounds() {
+    let input = r#"
+fun show_largest: <T: Display, U>(a: T, b: U) -> T where T: Ord + Display, U: Clone = {
+    a
+}
+"#;
+    let (_, program) = parse_program(input).expect("where clause should parse");
+    let restrict_lang::ast::TopDecl::Function(func) = &program.declarations[0] else {
+        panic!("expected a function declaration");
+    };
+    let bounds: Vec<Vec<&str>> = func
+        .type_params
+        .iter()
+        .map(|param| {
+            param
+                .bounds
+                .iter()
+                .map(|bound| bound.trait_name.as_str())
+                .collect()
+        })
+        .collect();
+    assert_eq!(bounds, vec![vec!["Display", "Ord"], vec!["Clone"]]);
+
+    let rejected =
+        format!("{input}\nfun main: () -> Boolean = {{\n    (true, 1) show_largest\n}}\n");
+    let err = type_check(&rejected).expect_err("the where-clause Ord bound still applies");
+    assert!(err.contains("does not implement trait Ord"), "{err}");
+}
+
+#[test]
+fn test_where_clause_rejects_unknown_type_parameter() {
+    let input = r#"
+fun keep: <T>(x: T) -> T where U: Display = {
+    x
+}
+"#;
+    assert!(type_check(input).is_err());
+}
+
 // =============================================================================
 // Phase 4: Generic Records
 // =============================================================================
@@ -0,0 +1,104 @@
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn instantiate(source: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .expect("source should type check");
+    let wat = WasmCodeGen::new()
+        .generate(&ast)
+        .expect("source should compile");
+    let wasm = wat::parse_str(wat)?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+fn fnv1a(bytes: &[u8]) -> i32 {
+    bytes.iter().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(16_777_619)
+    }) as i32
+}
+
+// One export keeps the entry-point arenas inside a single page.
+const SOURCE: &str = r#"
+record Point { x: Int32, y: Int32 }
+record Reading { label: String, value: Float64, at: Int64, origin: Point }
+
+fun point_hash: (x: Int32, y: Int32) -> Int32 = {
+    val point = Point { x: x, y: y };
+    val frozen = point freeze;
+    frozen |> hash
+}
+
+fun reading_hash: (label: String, value: Float64) -> Int32 = {
+    val origin = Point { x: 0, y: 0 };
+    val at: Int64 = 4294967296;
+    val reading = Reading { label: label, value: value, at: at, origin: origin };
+    val frozen = reading freeze;
+    frozen |> hash
+}
+
+export fun hash_case: (index: Int32) -> Int32 = {
+    index match {
+        0 => { (1, 2) point_hash }
+        1 => { (2, 1) point_hash }
+        2 => { ("north", 1.5) reading_hash }
+        3 => { ("south", 1.5) reading_hash }
+        4 => { 0.0 |> hash }
+        5 => { -0.0 |> hash }
+        6 => { 5 |> hash }
+        _ => { "hi" |> hash }
+    }
+}
+"#;
+
+#[test]
+fn structurally_equal_frozen_records_hash_equally() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(SOURCE)?;
+    let hash_case = instance.get_typed_func::<i32, i32>(&store, "hash_case")?;
+    let mut case = |index| hash_case.call(&mut store, index);
+
+    assert_eq!(case(0)?, case(0)?);
+    assert_ne!(case(0)?, case(1)?, "field order matters");
+    assert_eq!(case(2)?, case(2)?);
+    assert_ne!(case(2)?, case(3)?, "String fields are hashed by content");
+    assert_eq!(case(4)?, case(5)?, "0.0 and -0.0 compare equal");
+    Ok(())
+}
+
+#[test]
+fn scalar_hashes_are_fnv1a() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(SOURCE)?;
+    let hash_case = instance.get_typed_func::<i32, i32>(&store, "hash_case")?;
+
+    assert_eq!(hash_case.call(&mut store, 6)?, fnv1a(&5_i32.to_le_bytes()));
+    assert_eq!(hash_case.call(&mut store, 7)?, fnv1a(b"hi"));
+    assert_eq!(
+        hash_case.call(&mut store, 4)?,
+        fnv1a(&0.0_f64.to_bits().to_le_bytes())
+    );
+    Ok(())
+}
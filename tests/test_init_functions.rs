@@ -0,0 +1,110 @@
+use restrict_lang::{parse_program, TypeChecker, TypeError, WasmCodeGen};
+
+fn compile(source: &str, configure: impl FnOnce(&mut WasmCodeGen)) -> Result<String, String> {
+    let (_, ast) = parse_program(source).map_err(|e| format!("Parse error: {:?}", e))?;
+
+    let mut type_checker = TypeChecker::new();
+    type_checker
+        .check_program(&ast)
+        .map_err(|e| format!("Type error: {}", e))?;
+
+    let mut codegen = WasmCodeGen::new();
+    configure(&mut codegen);
+    let wat = codegen
+        .generate(&ast)
+        .map_err(|e| format!("Codegen error: {}", e))?;
+    wat::parse_str(&wat).map_err(|e| format!("Invalid generated WAT: {e}\n\n{wat}"))?;
+    Ok(wat)
+}
+
+fn check(source: &str) -> Result<(), TypeError> {
+    let (_, ast) = parse_program(source).expect("source should parse");
+    TypeChecker::new().check_program(&ast)
+}
+
+const INIT_PROGRAM: &str = r#"
+@init
+fun load_defaults: () -> () = {
+    "defaults" |> println
+}
+
+@init
+fun open_log: () -> () = {
+    "log" |> println
+}
+
+fun main: () -> Int32 = {
+    42
+}
+"#;
+
+#[test]
+fn start_section_calls_init_functions_in_declaration_order() {
+    let wat = compile(INIT_PROGRAM, |codegen| codegen.set_start_section(true)).unwrap();
+
+    assert!(wat.contains("(start $__restrict_init)"));
+    let init = &wat[wat.find("(func $__restrict_init").unwrap()..];
+    let init = &init[..init.find("\n  )").unwrap()];
+    let defaults = init.find("call $load_defaults").unwrap();
+    let log = init.find("call $open_log").unwrap();
+    assert!(defaults < log);
+
+    // The start section already ran them; `_start` only runs `main`.
+    let start = &wat[wat.find("(func $__restrict_start").unwrap()..];
+    assert!(!start[..start.find("\n  )").unwrap()].contains("$__restrict_init"));
+}
+
+#[test]
+fn init_functions_run_from_start_wrapper_by_default() {
+    let wat = compile(INIT_PROGRAM, |_| {}).unwrap();
+
+    assert!(!wat.contains("(start $"));
+    let start = &wat[wat.find("(func $__restrict_start").unwrap()..];
+    let init = start.find("call $__restrict_init").unwrap();
+    let main = start.find("call $main").unwrap();
+    assert!(init < main);
+}
+
+#[test]
+fn library_without_start_section_exports_initialize() {
+    let source = r#"
+@init
+fun setup: () -> () = {
+    "ready" |> println
+}
+
+export fun answer: () -> Int32 = {
+    42
+}
+"#;
+    let wat = compile(source, |codegen| codegen.set_library(true)).unwrap();
+
+    assert!(wat.contains("(export \"_initialize\" (func $__restrict_init))"));
+    assert!(!wat.contains("(start $"));
+}
+
+#[test]
+fn programs_without_init_functions_have_no_init_wrapper() {
+    let wat = compile("fun main: () -> Int32 = { 42 }", |codegen| {
+        codegen.set_start_section(true)
+    })
+    .unwrap();
+
+    assert!(!wat.contains("$__restrict_init"));
+    assert!(!wat.contains("(start $"));
+}
+
+#[test]
+fn init_functions_must_take_no_arguments_and_return_unit() {
+    for source in [
+        "@init fun setup: (level: Int32) -> () = { () }",
+        "@init fun setup: () -> Int32 = { 1 }",
+        "@init fun setup: <T>() -> () = { () }",
+    ] {
+        assert_eq!(
+            check(source),
+            Err(TypeError::InvalidInitFunction("setup".to_string())),
+            "{source}"
+        );
+    }
+}
@@ -115,3 +115,64 @@ export fun over_limit: (value: Int64) -> Boolean = {
     assert_eq!(over_limit.call(&mut store, 5_000_000_000)?, 0);
     Ok(())
 }
+
+#[test]
+fn suffixed_literal_is_int64_without_annotation() {
+    let wat = compile_to_wat(
+        r#"
+export fun total: () -> Int64 = {
+    val big = 9_000_000_000L
+    big + 7L
+}
+"#,
+    );
+
+    assert!(wat.contains("i64.const 9000000000"));
+    assert!(wat.contains("i64.const 7"));
+    assert!(wat.contains("i64.add"));
+    assert!(!wat.contains("i32.const 7\n    i64.extend_i32_s"));
+}
+
+#[test]
+fn mixed_int32_int64_arithmetic_is_rejected() {
+    let source = r#"
+fun main: () -> Int64 = {
+    val big = 5L
+    val small: Int32 = 1
+    big + small
+}
+"#;
+    let (_, ast) = parse_program(source).expect("source should parse");
+    let error = TypeChecker::new()
+        .check_program(&ast)
+        .expect_err("mixed-width arithmetic should be rejected");
+
+    assert!(
+        error.to_string().contains("found Int64 and Int32"),
+        "{error}"
+    );
+}
+
+#[test]
+fn width_conversion_builtins_execute() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun widen_add: (big: Int64, small: Int32) -> Int64 = {
+    big + (small |> to_i64)
+}
+
+export fun low_bits: (big: Int64) -> Int32 = {
+    big |> to_i32
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let widen_add = instance.get_typed_func::<(i64, i32), i64>(&store, "widen_add")?;
+    let low_bits = instance.get_typed_func::<i64, i32>(&store, "low_bits")?;
+
+    assert_eq!(
+        widen_add.call(&mut store, (5_000_000_000, -1))?,
+        4_999_999_999
+    );
+    assert_eq!(low_bits.call(&mut store, 4_294_967_298)?, 2);
+    Ok(())
+}
@@ -85,3 +85,21 @@ fn test_list_in_expression() {
     assert!(wat.contains("i32.const 20 ;; list size"));
     assert!(wat.contains("i32.add")); // for x + 1 and x + 2
 }
+
+#[test]
+fn test_list_map_invokes_closure_indirectly() {
+    let source = r#"
+        fun main: () -> List<Int32> = {
+            val offset = 10;
+            val list = [1, 2, 3];
+            (|value| value + offset, list) list_map
+        }
+    "#;
+
+    let wat = compile(source).expect("list_map should compile");
+
+    // The mapper is a closure invoked through the function table
+    assert!(wat.contains("call_indirect"), "{wat}");
+    assert!(wat.contains("(table "), "{wat}");
+    assert!(!wat.contains("call $list_map"), "{wat}");
+}
@@ -0,0 +1,254 @@
+use restrict_lang::ast::{Expr, ExprKind, Stmt, TopDecl};
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn check(source: &str) -> Result<restrict_lang::ast::Program, String> {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .map_err(|err| err.to_string())?;
+    Ok(ast)
+}
+
+fn instantiate(source: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let ast = check(source).expect("source should type check");
+    let wat = WasmCodeGen::new()
+        .generate(&ast)
+        .expect("source should compile");
+    let wasm = wat::parse_str(wat)?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+#[test]
+fn loop_parses_with_break_and_continue_statements() {
+    let (_, program) = parse_program(
+        r#"
+fun spin: () -> Int32 = {
+    mut val n = 0;
+    loop {
+        n = n + 1;
+        n < 3 then { continue } else { () };
+        break n
+    }
+}
+"#,
+    )
+    .unwrap();
+    let TopDecl::Function(func) = &program.declarations[0] else {
+        panic!("expected a function");
+    };
+    let Some(expr) = &func.body.expr else {
+        panic!("expected the loop as the function result");
+    };
+    let ExprKind::Loop(body) = &expr.kind else {
+        panic!("expected a loop, got {:?}", expr.kind);
+    };
+    assert!(matches!(&body.statements[2], Stmt::Break(Some(value))
+        if **value == Expr::new(ExprKind::Ident("n".to_string()))));
+    assert!(body.expr.is_none());
+}
+
+// One export keeps the entry-point arenas inside a single page.
+const SOURCE: &str = r#"
+fun first_square_over: (limit: Int32) -> Int32 = {
+    mut val n = 0;
+    loop {
+        n = n + 1;
+        n * n > limit then { break n * n } else { () }
+    }
+}
+
+fun sum_skipping_multiples_of_three: (count: Int32) -> Int32 = {
+    mut val i = 0;
+    mut val total = 0;
+    i < count while {
+        i = i + 1;
+        i % 3 == 0 then { continue } else { () };
+        total = total + i
+    }
+    total
+}
+
+fun count_until_ten: () -> Int32 = {
+    mut val n = 0;
+    loop {
+        n = n + 1;
+        n == 10 then { break } else { () }
+    }
+    n
+}
+
+fun nested_pairs: (size: Int32) -> Int32 = {
+    mut val pairs = 0;
+    mut val outer = 0;
+    outer < size while {
+        outer = outer + 1;
+        mut val inner = 0;
+        loop {
+            inner = inner + 1;
+            inner > outer then { break } else { () };
+            pairs = pairs + 1
+        }
+    }
+    pairs
+}
+
+export fun loop_case: (index: Int32, arg: Int32) -> Int32 = {
+    index match {
+        0 => { arg |> first_square_over }
+        1 => { arg |> sum_skipping_multiples_of_three }
+        2 => { () count_until_ten }
+        _ => { arg |> nested_pairs }
+    }
+}
+"#;
+
+#[test]
+fn loops_break_with_values_and_continue_skips() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(SOURCE)?;
+    let loop_case = instance.get_typed_func::<(i32, i32), i32>(&store, "loop_case")?;
+
+    assert_eq!(loop_case.call(&mut store, (0, 50))?, 64);
+    // 1 + 2 + 4 + 5 + 7 + 8 + 10
+    assert_eq!(loop_case.call(&mut store, (1, 10))?, 37);
+    assert_eq!(loop_case.call(&mut store, (2, 0))?, 10);
+    // A break in the inner loop leaves only that loop: 1 + 2 + 3 + 4
+    assert_eq!(loop_case.call(&mut store, (3, 4))?, 10);
+    Ok(())
+}
+
+#[test]
+fn break_and_continue_inside_with_arena_restore_the_outer_arena(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+fun break_once: (x: String) -> Int32 = {
+    loop {
+        with Arena {
+            val t = x + "0123456789";
+            t |> string_length;
+            break
+        }
+    };
+    0
+}
+
+fun continue_once: (x: String) -> Int32 = {
+    mut val i = 0;
+    i < 2 while {
+        i = i + 1;
+        with Arena {
+            val t = x + "0123456789";
+            t |> string_length;
+            continue
+        }
+    };
+    0
+}
+
+export fun after_break: () -> Boolean = {
+    ("a") break_once;
+    val s = "hello" + "world";
+    ("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzz") break_once;
+    s == "helloworld"
+}
+
+export fun after_continue: () -> Boolean = {
+    ("a") continue_once;
+    val s = "hello" + "world";
+    ("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzz") continue_once;
+    s == "helloworld"
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let after_break = instance.get_typed_func::<(), i32>(&store, "after_break")?;
+    let after_continue = instance.get_typed_func::<(), i32>(&store, "after_continue")?;
+
+    assert_eq!(after_break.call(&mut store, ())?, 1);
+    assert_eq!(after_continue.call(&mut store, ())?, 1);
+    Ok(())
+}
+
+#[test]
+fn loop_control_outside_a_loop_is_rejected() {
+    let message = check("fun main: () -> Int32 = {\n    break 1\n}\n")
+        .expect_err("break needs an enclosing loop");
+    assert!(
+        message.contains("`break` can only be used inside a loop"),
+        "{message}"
+    );
+
+    let message = check(
+        r#"
+fun main: () -> Int32 = {
+    loop {
+        val next = |x: Int32| {
+            continue;
+            x
+        };
+        break 1
+    }
+}
+"#,
+    )
+    .expect_err("a lambda body cannot continue the loop around it");
+    assert!(
+        message.contains("`continue` can only be used inside a loop"),
+        "{message}"
+    );
+}
+
+#[test]
+fn break_values_must_agree_and_need_a_loop() {
+    let message = check(
+        r#"
+fun main: (flag: Boolean) -> Int32 = {
+    loop {
+        flag then { break 1 } else { break true }
+    }
+}
+"#,
+    )
+    .expect_err("break values disagree");
+    assert!(message.contains("Type mismatch"), "{message}");
+
+    let message = check(
+        r#"
+fun main: () -> Int32 = {
+    mut val n = 0;
+    n < 3 while {
+        break n
+    }
+    n
+}
+"#,
+    )
+    .expect_err("while loops have no result value");
+    assert!(
+        message.contains("`break` with a value can only leave `loop`"),
+        "{message}"
+    );
+}
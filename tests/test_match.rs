@@ -176,3 +176,55 @@ fn test_match_in_function() {
     }
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_match_guard_tests_condition_after_pattern() {
+    let source = r#"
+        fun classify: (x: Int32) -> Int32 = {
+            x match {
+                n if n > 0 => { 1 }
+                n if n < 0 => { -1 }
+                _ => { 0 }
+            }
+        }
+    "#;
+
+    let wat = compile_to_wat(source).unwrap();
+
+    assert_eq!(wat.matches(";; match guard").count(), 2);
+    assert!(wat.contains("i32.gt_s"));
+    assert!(wat.contains("i32.lt_s"));
+    wat::parse_str(&wat).expect("guarded match should produce valid WAT");
+}
+
+#[test]
+fn test_match_at_binding_stores_scrutinee_before_destructuring() {
+    let source = r#"
+        fun whole: (x: Option<Int32>) -> Int32 = {
+            x match {
+                w @ Some(n) => { (w |> option_unwrap) + n }
+                None => { 0 }
+            }
+        }
+    "#;
+
+    let wat = compile_to_wat(source).unwrap();
+
+    assert!(wat.contains("local.tee $w ;; @ binding"));
+    wat::parse_str(&wat).expect("@-binding match should produce valid WAT");
+}
+
+#[test]
+fn test_match_guard_must_be_boolean() {
+    let source = r#"
+        fun classify: (x: Int32) -> Int32 = {
+            x match {
+                n if n => { 1 }
+                _ => { 0 }
+            }
+        }
+    "#;
+
+    let err = compile_to_wat(source).expect_err("an Int32 guard should be rejected");
+    assert!(err.contains("Boolean"), "unexpected error: {}", err);
+}
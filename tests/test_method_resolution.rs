@@ -196,6 +196,81 @@ fun main: () -> String = {
     type_check(input).expect("generic method calls should infer type params from OSV arguments");
 }
 
+#[test]
+fn generic_record_methods_see_record_type_params() {
+    let input = r#"
+record Box<T> {
+    value: T
+}
+
+impl Box {
+    fun get: (self: Box<T>) -> T = {
+        self.value
+    }
+}
+
+fun main: () -> String = {
+    val box: Box<String> = Box { value: "ok" };
+    (box) get
+}
+"#;
+
+    type_check(input).expect("a method on Box<T> should return the receiver's T");
+
+    let mismatch = input.replace("-> String = {\n    val box", "-> Int32 = {\n    val box");
+    let err = type_check(&mismatch).expect_err("Box<String>.get should not return Int32");
+    assert!(
+        err.contains("Type mismatch"),
+        "error should report the substituted return type, got: {}",
+        err
+    );
+}
+
+#[test]
+fn generic_record_method_infers_method_local_type_params() {
+    let input = r#"
+record Box<T> {
+    value: T
+}
+
+impl Box {
+    fun transform: <U>(self: Box<T>, f: (T) -> U) -> Box<U> = {
+        Box { value: (self.value) f }
+    }
+}
+
+fun main: () -> Boolean = {
+    val box: Box<Int32> = Box { value: 1 };
+    val flagged: Box<Boolean> = (box, |n| n > 0) transform;
+    flagged.value
+}
+"#;
+
+    type_check(input).expect("U should be inferred from the lambda while T comes from self");
+}
+
+#[test]
+fn method_type_params_cannot_shadow_record_type_params() {
+    let input = r#"
+record Box<T> {
+    value: T
+}
+
+impl Box {
+    fun replace: <T>(self: Box<T>, value: T) -> T = {
+        value
+    }
+}
+"#;
+
+    let err = type_check(input).expect_err("method <T> should not shadow Box<T>");
+    assert!(
+        err.contains("redeclares type parameter 'T' of record 'Box'"),
+        "error should name the shadowed record type parameter, got: {}",
+        err
+    );
+}
+
 #[test]
 fn impl_method_calls_are_declaration_order_independent() {
     let input = r#"
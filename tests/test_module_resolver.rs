@@ -5,7 +5,7 @@ use restrict_lang::module::{
     parse_module_source_key, resolve_program_imports_for_file,
     resolve_program_imports_with_module_source_map, ModuleResolver,
 };
-use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use restrict_lang::{parse_program, TypeChecker, TypeError, WasmCodeGen};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -1336,3 +1336,226 @@ export fun widen: (value: Int32) -> Int64 = {
         "range endpoints must follow module renaming"
     );
 }
+
+#[test]
+fn type_checker_loads_imported_record_and_function_through_resolver() {
+    let root = parse_complete(
+        r#"
+import geometry.{Point, shift}
+
+fun main: () -> Int32 = {
+    val point = Point { x: 1, y: 2 }
+    val moved = (point, 40) shift
+    moved.x
+}
+"#,
+    );
+
+    let mut resolver = ModuleResolver::new();
+    resolver.add_module_source(
+        vec!["geometry".to_string()],
+        r#"
+export record Point {
+    x: Int32,
+    y: Int32
+}
+
+export fun shift: (point: Point, by: Int32) -> Point = {
+    val Point { x, y } = point
+    Point { x: x + by, y: y }
+}
+"#
+        .to_string(),
+    );
+
+    let mut checker = TypeChecker::new();
+    checker.set_module_resolver(resolver.clone());
+    checker
+        .check_program(&root)
+        .expect("imported record and function should type check");
+
+    let misuse = parse_complete(
+        r#"
+import geometry.{Point, shift}
+
+fun main: () -> Int32 = {
+    val moved = (1, 40) shift
+    moved.x
+}
+"#,
+    );
+    let mut checker = TypeChecker::new();
+    checker.set_module_resolver(resolver);
+    let err = checker
+        .check_program(&misuse)
+        .expect_err("imported signature should be enforced");
+    assert!(err.to_string().contains("Point"), "{err}");
+}
+
+#[test]
+fn type_checker_reports_cyclic_module_imports() {
+    let root = parse_complete(
+        r#"
+import ping.{ping}
+
+fun main: () -> Int32 = {
+    1 |> ping
+}
+"#,
+    );
+
+    let mut resolver = ModuleResolver::new();
+    resolver.add_module_source(
+        vec!["ping".to_string()],
+        r#"
+import pong.{pong}
+
+export fun ping: (value: Int32) -> Int32 = {
+    value |> pong
+}
+"#
+        .to_string(),
+    );
+    resolver.add_module_source(
+        vec!["pong".to_string()],
+        r#"
+import ping.{ping}
+
+export fun pong: (value: Int32) -> Int32 = {
+    value |> ping
+}
+"#
+        .to_string(),
+    );
+
+    let mut checker = TypeChecker::new();
+    checker.set_module_resolver(resolver);
+    let err = checker
+        .check_program(&root)
+        .expect_err("cyclic imports should be rejected");
+    assert!(
+        err.to_string()
+            .contains("Cyclic module import detected: ping -> pong -> ping"),
+        "{err}"
+    );
+}
+
+#[test]
+fn source_file_resolver_loads_modules_next_to_the_source() {
+    let dir = temp_module_dir("source_dir_resolver");
+    fs::write(
+        dir.join("restrict_resolver_probe.rl"),
+        r#"
+export fun probe: (value: Int32) -> Int32 = {
+    value + 7
+}
+"#,
+    )
+    .expect("module source should be written");
+
+    let root = parse_complete(
+        r#"
+import restrict_resolver_probe.{probe}
+
+fun main: () -> Int32 = {
+    1 |> probe
+}
+"#,
+    );
+
+    let mut checker = TypeChecker::new();
+    checker.set_module_resolver(ModuleResolver::for_source_file(&dir.join("main.rl")));
+    let resolved = checker
+        .resolve_imports(root)
+        .expect("import next to the source file should resolve");
+    assert!(resolved.imports.is_empty());
+    checker
+        .check_program(&resolved)
+        .expect("resolved program should type check");
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn ledger_resolver() -> ModuleResolver {
+    let mut resolver = ModuleResolver::new();
+    resolver.add_module_source(
+        vec!["ledger".to_string()],
+        r#"
+record Entry {
+    amount: Int32
+}
+
+fun fee: (amount: Int32) -> Int32 = {
+    amount / 10
+}
+
+export fun net: (amount: Int32) -> Int32 = {
+    val entry = Entry { amount: amount }
+    entry.amount - (entry.amount |> fee)
+}
+"#
+        .to_string(),
+    );
+    resolver
+}
+
+#[test]
+fn importing_an_exported_function_type_checks() {
+    let root = parse_complete(
+        r#"
+import ledger.{net}
+
+fun main: () -> Int32 = {
+    100 |> net
+}
+"#,
+    );
+
+    let mut checker = TypeChecker::new();
+    checker.set_module_resolver(ledger_resolver());
+    checker
+        .check_program(&root)
+        .expect("exported function should be importable");
+}
+
+#[test]
+fn importing_a_private_function_or_record_is_rejected() {
+    for (import, use_site) in [("fee", "100 |> fee"), ("Entry", "0")] {
+        let root = parse_complete(&format!(
+            "import ledger.{{{import}}}\n\nfun main: () -> Int32 = {{\n    {use_site}\n}}\n"
+        ));
+
+        let mut checker = TypeChecker::new();
+        checker.set_module_resolver(ledger_resolver());
+        assert_eq!(
+            checker.check_program(&root),
+            Err(TypeError::NotExported {
+                module: "ledger".to_string(),
+                name: import.to_string(),
+            })
+        );
+    }
+}
+
+#[test]
+fn importing_an_unknown_name_is_a_resolution_error() {
+    let root = parse_complete(
+        r#"
+import ledger.{missing}
+
+fun main: () -> Int32 = {
+    0
+}
+"#,
+    );
+
+    let mut checker = TypeChecker::new();
+    checker.set_module_resolver(ledger_resolver());
+    let err = checker
+        .check_program(&root)
+        .expect_err("unknown imports should be rejected");
+    assert!(
+        matches!(&err, TypeError::ModuleResolution(message) if message.contains("Export 'missing' not found")),
+        "{err}"
+    );
+}
@@ -135,3 +135,214 @@ fn test_mutable_record_field() {
     let _wat = compile(input).unwrap();
     // Should compile successfully
 }
+
+#[test]
+fn test_field_update_on_mutable_record() {
+    let input = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val p = Point { x: 10, y: 20 }
+            p.x <- p.x + 1
+            p.x + p.y
+        }
+    }"#;
+    let wat = compile(input).unwrap();
+    assert!(wat.contains("i32.store"));
+}
+
+#[test]
+fn test_field_update_rejects_immutable_binding() {
+    let input = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            val p = Point { x: 10, y: 20 }
+            p.x <- 11
+            p.x
+        }
+    }"#;
+    let err = compile(input).unwrap_err();
+    assert!(
+        err.contains("Cannot update a field of immutable variable p"),
+        "Expected immutable field update error but got: {}",
+        err
+    );
+}
+
+#[test]
+fn test_field_update_rejects_frozen_record() {
+    let input = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val p = Point { x: 10, y: 20 } freeze
+            p.x <- 11
+            p.x
+        }
+    }"#;
+    let err = compile(input).unwrap_err();
+    assert!(
+        err.contains("Cannot update field `x` of frozen record Point"),
+        "Expected frozen field update error but got: {}",
+        err
+    );
+}
+
+#[test]
+fn test_freeze_rejects_mutable_binding() {
+    let input = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val p = Point { x: 10, y: 20 }
+            val frozen = p freeze
+            p.x <- 11
+            frozen.x
+        }
+    }"#;
+    let err = compile(input).unwrap_err();
+    assert!(
+        err.contains("Cannot freeze a record reached from mutable variable p"),
+        "Expected mutable freeze error but got: {}",
+        err
+    );
+}
+
+#[test]
+fn test_field_update_rejects_moved_binding() {
+    let aliased = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val p = Point { x: 10, y: 20 }
+            val q = p
+            p.x <- 99
+            q.x
+        }
+    }"#;
+    let err = compile(aliased).unwrap_err();
+    assert!(
+        err.contains("Variable p has already been used"),
+        "Expected use-after-move error but got: {}",
+        err
+    );
+
+    let passed = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun read_x: (p: Point) -> Int32 = { p.x }
+
+    fun test = {
+        with Arena {
+            mut val p = Point { x: 10, y: 20 }
+            val n = (p) read_x
+            p.x <- 99
+            n
+        }
+    }"#;
+    let err = compile(passed).unwrap_err();
+    assert!(
+        err.contains("Variable p has already been used"),
+        "Expected use-after-move error but got: {}",
+        err
+    );
+
+    // Reassigning a fresh record makes the binding updatable again
+    let reassigned = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val p = Point { x: 10, y: 20 }
+            val q = p
+            p = Point { x: 1, y: 2 }
+            p.x <- 99
+            q.x + p.x
+        }
+    }"#;
+    assert!(compile(reassigned).is_ok());
+}
+
+#[test]
+fn test_moved_mut_record_cannot_be_read_through_old_binding() {
+    let aliased = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val q = Point { x: 10, y: 20 }
+            mut val p = q
+            p.x <- 99
+            q.x
+        }
+    }"#;
+    let err = compile(aliased).unwrap_err();
+    assert!(
+        err.contains("Variable q has already been used"),
+        "Expected use-after-move error but got: {}",
+        err
+    );
+
+    // A record taken out of a mutable list stays reachable through the list
+    let from_list = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val points = [Point { x: 10, y: 20 }]
+            mut val first = points |> list_head |> option_unwrap
+            first.x <- 99
+            (points |> list_head |> option_unwrap).x
+        }
+    }"#;
+    let err = compile(from_list).unwrap_err();
+    assert!(
+        err.contains("Cannot update a field of first"),
+        "Expected shared field update error but got: {}",
+        err
+    );
+}
+
+#[test]
+fn test_clone_and_field_reads_do_not_move_mut_record() {
+    let input = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val p = Point { x: 10, y: 20 }
+            val q = p.clone { }
+            val y = p.y
+            p.x <- 5
+            p.x + q.x + y
+        }
+    }"#;
+    let result = compile(input);
+    assert!(result.is_ok(), "got: {:?}", result);
+}
+
+#[test]
+fn test_field_update_checks_field_type() {
+    let input = r#"
+    record Point { x: Int32, y: Int32 }
+
+    fun test = {
+        with Arena {
+            mut val p = Point { x: 10, y: 20 }
+            p.x <- true
+            p.x
+        }
+    }"#;
+    let err = compile(input).unwrap_err();
+    assert!(
+        err.contains("expected Int32, found Boolean"),
+        "Expected field type mismatch but got: {}",
+        err
+    );
+}
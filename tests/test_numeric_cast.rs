@@ -86,6 +86,30 @@ fun bad: (s: String) -> Float64 = {
     );
 }
 
+#[test]
+fn char_casts_type_check_and_strings_are_rejected() {
+    type_check_source(
+        r#"
+fun code: (c: Char) -> Int32 = {
+    c as Int32
+}
+
+fun letter: () -> Char = {
+    97 as Char
+}
+"#,
+    )
+    .expect("Char and Int32 should convert both ways");
+
+    for source in [
+        "fun bad: () -> Int32 = {\n    \"x\" as Int32\n}\n",
+        "fun bad: (c: Char) -> Float64 = {\n    c as Float64\n}\n",
+    ] {
+        let err = type_check_source(source).expect_err("cast should be rejected");
+        assert!(err.contains("numeric cast"), "{err}");
+    }
+}
+
 #[test]
 fn numeric_cast_codegen_emits_wasm_conversions() {
     let wat = compile_to_wat(
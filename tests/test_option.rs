@@ -199,3 +199,67 @@ fun main: () -> Option<String> = {
         .validate_all(&wasm)
         .unwrap_or_else(|err| panic!("Some(String) Wasm should validate: {err}\n\n{wat}"));
 }
+
+#[test]
+fn option_combinators_type_check_generic_signatures() {
+    let source = r#"
+fun main: () -> Boolean = {
+    val count: Option<Int32> = Some(3);
+    val positive: Option<Boolean> = (|n| n > 0, count) option_map;
+    val doubled: Option<Int32> = (|n| Some(n * 2), Some(4)) option_and_then;
+    val value: Int32 = doubled |> option_unwrap;
+    positive |> option_unwrap
+}
+"#;
+
+    type_check(source).expect("option combinators should infer T and U");
+}
+
+#[test]
+fn option_combinators_reject_mismatched_types() {
+    let and_then_without_option = r#"
+fun main: () -> Option<Int32> = {
+    (|n| n + 1, Some(1)) option_and_then
+}
+"#;
+    assert!(
+        type_check(and_then_without_option).is_err(),
+        "option_and_then requires a function returning Option"
+    );
+
+    let unwrap_wrong_type = r#"
+fun main: () -> String = {
+    Some(1) |> option_unwrap
+}
+"#;
+    assert!(
+        type_check(unwrap_wrong_type).is_err(),
+        "option_unwrap returns the payload type"
+    );
+}
+
+#[test]
+fn option_map_branches_on_the_option_tag() {
+    let source = r#"
+fun main: () -> Option<Int32> = {
+    val count: Option<Int32> = Some(41);
+    (|n| n + 1, count) option_map
+}
+"#;
+
+    let wat = compile(source).expect("option_map should compile");
+    let branch = wat
+        .find(";; map(option, mapper)")
+        .map(|start| &wat[start..])
+        .expect("option_map should emit the Option map branch");
+    assert!(branch.contains("i32.load ;; load Option tag"));
+    assert!(branch.contains("i32.const 1 ;; Some tag"));
+    assert!(branch.contains("(if (result i32)"));
+    assert!(branch.contains("call_indirect"));
+
+    let wasm = wat::parse_str(&wat)
+        .unwrap_or_else(|err| panic!("option_map WAT should parse: {err}\n\n{wat}"));
+    wasmparser::Validator::new()
+        .validate_all(&wasm)
+        .unwrap_or_else(|err| panic!("option_map Wasm should validate: {err}\n\n{wat}"));
+}
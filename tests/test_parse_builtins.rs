@@ -0,0 +1,161 @@
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn check(source: &str) -> Result<restrict_lang::ast::Program, String> {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .map_err(|err| err.to_string())?;
+    Ok(ast)
+}
+
+fn instantiate(source: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let ast = check(source).expect("source should type check");
+    let wat = WasmCodeGen::new()
+        .generate(&ast)
+        .expect("source should compile");
+    let wasm = wat::parse_str(wat)?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+#[test]
+fn parse_builtins_type_check() {
+    check(
+        r#"
+fun main: () -> Int32 = {
+    val count: Option<Int32> = "42" |> parse_int;
+    val ratio: Option<Float64> = "0.5" |> parse_float;
+    count match {
+        Some(n) => { n }
+        None => { 0 }
+    }
+}
+"#,
+    )
+    .expect("parse builtins should type check");
+}
+
+#[test]
+fn parse_builtins_reject_the_wrong_types() {
+    let message = check("fun main: () -> Option<Int32> = {\n    42 |> parse_int\n}\n")
+        .expect_err("parse_int takes a String");
+    assert!(message.contains("String"), "{message}");
+
+    let message = check("fun main: () -> Int32 = {\n    \"42\" |> parse_int\n}\n")
+        .expect_err("parse_int returns an Option");
+    assert!(message.contains("Option"), "{message}");
+
+    let message = check("fun main: () -> Option<Int32> = {\n    \"4.2\" |> parse_float\n}\n")
+        .expect_err("parse_float returns Option<Float64>");
+    assert!(message.contains("Float64"), "{message}");
+}
+
+// One export per test keeps the entry-point arenas inside a single page.
+#[test]
+fn parse_int_accepts_signed_digits_and_rejects_the_rest() -> Result<(), Box<dyn std::error::Error>>
+{
+    let cases = [
+        ("123", 123),
+        ("12x", -1),
+        ("2147483648", -1),
+        ("99999999999999999999", -1),
+        ("-42", -42),
+        ("+7", 7),
+        ("2147483647", i32::MAX),
+        ("-2147483648", i32::MIN),
+        ("-2147483649", -1),
+        ("", -1),
+        ("-", -1),
+    ];
+    let (mut store, instance) = instantiate(&format!(
+        r#"
+export fun parse_case: (index: Int32) -> Int32 = {{
+    val text = index match {{
+{}
+        _ => {{ "" }}
+    }};
+    text |> parse_int match {{
+        Some(n) => {{ n }}
+        None => {{ -1 }}
+    }}
+}}
+"#,
+        case_arms(cases.iter().map(|(text, _)| *text))
+    ))?;
+    let parse_case = instance.get_typed_func::<i32, i32>(&store, "parse_case")?;
+    for (index, (text, expected)) in cases.into_iter().enumerate() {
+        assert_eq!(
+            parse_case.call(&mut store, index as i32)?,
+            expected,
+            "{text:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn parse_float_reads_one_decimal_point() -> Result<(), Box<dyn std::error::Error>> {
+    let cases = [
+        ("3.25", 3.25),
+        ("-0.5", -0.5),
+        ("42", 42.0),
+        ("7.", 7.0),
+        ("1.2.3", -1.0),
+        (".", -1.0),
+        ("1e5", -1.0),
+    ];
+    let (mut store, instance) = instantiate(&format!(
+        r#"
+export fun parse_case: (index: Int32) -> Float64 = {{
+    val text = index match {{
+{}
+        _ => {{ "" }}
+    }};
+    text |> parse_float match {{
+        Some(x) => {{ x }}
+        None => {{ -1.0 }}
+    }}
+}}
+"#,
+        case_arms(cases.iter().map(|(text, _)| *text))
+    ))?;
+    let parse_case = instance.get_typed_func::<i32, f64>(&store, "parse_case")?;
+    for (index, (text, expected)) in cases.into_iter().enumerate() {
+        assert_eq!(
+            parse_case.call(&mut store, index as i32)?,
+            expected,
+            "{text:?}"
+        );
+    }
+    Ok(())
+}
+
+fn case_arms<'a>(texts: impl Iterator<Item = &'a str>) -> String {
+    texts
+        .enumerate()
+        .map(|(index, text)| format!("        {index} => {{ \"{text}\" }}\n"))
+        .collect()
+}
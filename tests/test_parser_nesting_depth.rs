@@ -0,0 +1,83 @@
+use restrict_lang::{parse_program, parse_program_with_max_depth, ParseError};
+
+fn nested_parens(depth: usize) -> String {
+    format!(
+        "fun main: () -> Int32 = {{\n    {}1{}\n}}\n",
+        "(".repeat(depth),
+        ")".repeat(depth)
+    )
+}
+
+fn assert_nesting_too_deep(source: &str, max_depth: usize) {
+    let err = parse_program_with_max_depth(source, max_depth)
+        .expect_err("deep nesting should be rejected");
+    assert_eq!(ParseError::from_nom(&err), Some(ParseError::NestingTooDeep));
+}
+
+#[test]
+fn test_ten_thousand_nested_parens_is_a_parse_error() {
+    let source = nested_parens(10_000);
+    let err = parse_program(&source).expect_err("deep nesting should be rejected");
+    assert_eq!(ParseError::from_nom(&err), Some(ParseError::NestingTooDeep));
+}
+
+#[test]
+fn test_deeply_nested_unary_types_patterns_and_blocks_are_rejected() {
+    let depth = 10_000;
+    let sources = [
+        format!(
+            "fun main: () -> Int32 = {{\n    {}1\n}}\n",
+            "-".repeat(depth)
+        ),
+        format!(
+            "fun f: (x: {}Int32{}) -> Int32 = {{\n    0\n}}\n",
+            "List<".repeat(depth),
+            ">".repeat(depth)
+        ),
+        format!(
+            "fun main: () -> Int32 = {{\n    val x = Some(1);\n    x match {{\n        {}v{} => {{ 0 }}\n        _ => {{ 1 }}\n    }}\n}}\n",
+            "Some(".repeat(depth),
+            ")".repeat(depth)
+        ),
+        format!(
+            "fun main: () -> Int32 = {{\n    {}1{}\n}}\n",
+            "{ ".repeat(depth),
+            " }".repeat(depth)
+        ),
+    ];
+    for source in &sources {
+        let err = parse_program(source).expect_err("deep nesting should be rejected");
+        assert_eq!(ParseError::from_nom(&err), Some(ParseError::NestingTooDeep));
+    }
+}
+
+#[test]
+fn test_max_nesting_depth_is_configurable() {
+    let source = nested_parens(4);
+    assert!(parse_program_with_max_depth(&source, 16).is_ok());
+    assert_nesting_too_deep(&source, 4);
+}
+
+#[test]
+fn test_nesting_limit_is_reset_between_parses() {
+    assert_nesting_too_deep(&nested_parens(8), 4);
+    let source = nested_parens(4);
+    let (rest, program) = parse_program(&source).expect("shallow nesting should parse");
+    assert!(rest.trim().is_empty());
+    assert_eq!(program.declarations.len(), 1);
+}
+
+#[test]
+fn test_long_operator_chains_count_toward_the_nesting_limit() {
+    let in_main = |body: String| format!("fun main: () -> Int32 = {{\n    {}\n}}\n", body);
+    let sums = in_main(vec!["1"; 1000].join(" + "));
+    let pipes = in_main(format!("1{}", " |> id".repeat(1000)));
+    for source in [sums, pipes] {
+        let err = parse_program(&source).expect_err("long chains should be rejected");
+        assert_eq!(ParseError::from_nom(&err), Some(ParseError::NestingTooDeep));
+    }
+
+    let short = "fun main: () -> Int32 = {\n    1 + 2 * 3 - 4 + 5\n}\n";
+    let (rest, _) = parse_program(short).expect("short chains should parse");
+    assert!(rest.trim().is_empty());
+}
@@ -448,3 +448,130 @@ fn error_message_includes_suggestion() {
         }
     }
 }
+
+#[test]
+fn guarded_arms_do_not_count_toward_exhaustiveness() {
+    let source = create_match_program(
+        "Option<Int32>",
+        "Int32",
+        r#"
+        Some(n) if n > 0 => { n }
+        None => { 0 }
+"#,
+    );
+    expect_non_exhaustive_error(&source, "Some");
+}
+
+#[test]
+fn guarded_catch_all_needs_an_unguarded_fallthrough() {
+    let guarded_only = create_match_program(
+        "Int32",
+        "Int32",
+        r#"
+        n if n > 0 => { 1 }
+        n if n < 0 => { -1 }
+"#,
+    );
+    expect_non_exhaustive_error(&guarded_only, "_");
+
+    let with_fallthrough = create_match_program(
+        "Int32",
+        "Int32",
+        r#"
+        n if n > 0 => { 1 }
+        n if n < 0 => { -1 }
+        _ => { 0 }
+"#,
+    );
+    expect_exhaustive(&with_fallthrough);
+}
+
+#[test]
+fn guarded_arm_with_structural_fallthrough_is_exhaustive() {
+    let source = create_match_program(
+        "Option<Int32>",
+        "Int32",
+        r#"
+        Some(n) if n > 10 => { 10 }
+        Some(n) => { n }
+        None => { 0 }
+"#,
+    );
+    expect_exhaustive(&source);
+}
+
+#[test]
+fn at_bindings_are_checked_by_their_inner_pattern() {
+    let partial = create_match_program(
+        "Option<Int32>",
+        "Int32",
+        r#"
+        whole @ Some(_) => { whole |> option_unwrap }
+"#,
+    );
+    expect_non_exhaustive_error(&partial, "None");
+
+    let catch_all = create_match_program(
+        "Option<Int32>",
+        "Int32",
+        r#"
+        Some(n) => { n }
+        rest @ _ => { 0 }
+"#,
+    );
+    expect_exhaustive(&catch_all);
+}
+
+#[test]
+fn int_ranges_covering_every_int32_are_exhaustive() {
+    let source = create_match_program(
+        "Int32",
+        "Int32",
+        r#"
+        -2147483648..0 => { -1 }
+        0 => { 0 }
+        1..=2147483647 => { 1 }
+"#,
+    );
+    expect_exhaustive(&source);
+}
+
+#[test]
+fn int_ranges_with_a_gap_report_the_gap() {
+    let source = create_match_program(
+        "Int32",
+        "Int32",
+        r#"
+        -2147483648..=-1 => { -1 }
+        1..=2147483647 => { 1 }
+"#,
+    );
+    expect_non_exhaustive_error(&source, "0");
+
+    let source = create_match_program(
+        "Int32",
+        "Int32",
+        r#"
+        0..=9 => { 1 }
+        10..100 => { 2 }
+"#,
+    );
+    expect_non_exhaustive_error(&source, "100..=2147483647");
+}
+
+#[test]
+fn int_range_pattern_requires_int32_scrutinee() {
+    let source = create_match_program(
+        "String",
+        "Int32",
+        r#"
+        0..=9 => { 1 }
+        _ => { 0 }
+"#,
+    );
+    let (_, program) = parse_program(&source).expect("parse should succeed");
+    let err = TypeChecker::new()
+        .check_program(&program)
+        .expect_err("a range cannot match a String");
+    assert!(matches!(err, TypeError::TypeMismatch { .. }), "{err:?}");
+}
@@ -0,0 +1,109 @@
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Linker, Module, Store};
+
+const SAMPLE: &str = r#"
+export fun scaled: (a: Int32) -> Int32 = {
+    val offset = a + 0;
+    val factor = a * 1;
+    offset * factor
+}
+"#;
+
+fn compile(source: &str, optimize: bool) -> String {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(remaining.trim().is_empty(), "remaining: {remaining:?}");
+    TypeChecker::new()
+        .check_program(&ast)
+        .expect("source should type check");
+    let mut codegen = WasmCodeGen::new();
+    codegen.optimize(optimize);
+    codegen.generate(&ast).expect("source should compile")
+}
+
+/// Plain instruction lines in `$name`'s body, skipping `(local ...)`
+/// declarations and comments.
+fn function_body<'a>(wat: &'a str, name: &str) -> Vec<&'a str> {
+    let header = format!("  (func ${name} ");
+    wat.lines()
+        .skip_while(|line| !line.starts_with(&header))
+        .skip(1)
+        .take_while(|line| !line.starts_with("  )") && !line.starts_with("  ("))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('(') && !line.starts_with(";;"))
+        .collect()
+}
+
+fn instruction_count(wat: &str, name: &str) -> usize {
+    function_body(wat, name).len()
+}
+
+fn call_scaled(wat: &str, arg: i32) -> Result<i32, Box<dyn std::error::Error>> {
+    let wasm = wat::parse_str(wat)?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    let scaled = instance.get_typed_func::<i32, i32>(&store, "scaled")?;
+    Ok(scaled.call(&mut store, arg)?)
+}
+
+#[test]
+fn optimize_shrinks_the_sample_function() {
+    let plain = compile(SAMPLE, false);
+    let optimized = compile(SAMPLE, true);
+
+    let before = instruction_count(&plain, "scaled");
+    let after = instruction_count(&optimized, "scaled");
+    // `a + 0` loses its `i32.const 0` / `i32.add`, and `a * 1` its
+    // `i32.const 1` / `i32.mul`.
+    assert_eq!(before - after, 4, "{plain}\n{optimized}");
+    let body = function_body(&optimized, "scaled");
+    assert!(!body.contains(&"i32.const 0"), "{body:?}");
+    assert!(!body.contains(&"i32.const 1"), "{body:?}");
+}
+
+#[test]
+fn optimize_is_off_by_default_and_preserves_behavior() -> Result<(), Box<dyn std::error::Error>> {
+    let (remaining, ast) = parse_program(SAMPLE).expect("source should parse");
+    assert!(remaining.trim().is_empty());
+    let default = WasmCodeGen::new().generate(&ast)?;
+    assert_eq!(default, compile(SAMPLE, false));
+
+    let optimized = compile(SAMPLE, true);
+    for arg in [-7, 0, 5, i32::MAX] {
+        assert_eq!(call_scaled(&default, arg)?, call_scaled(&optimized, arg)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn single_use_temporaries_collapse_onto_the_stack() {
+    let source = r#"
+fun first: (a: Int32) -> Int32 = {
+    val b = a * 2;
+    b
+}
+"#;
+    let optimized = compile(source, true);
+    let body = function_body(&optimized, "first");
+    assert!(!body.contains(&"local.set $b"), "{body:?}");
+    assert!(!body.contains(&"local.get $b"), "{body:?}");
+    assert!(
+        instruction_count(&optimized, "first")
+            < instruction_count(&compile(source, false), "first")
+    );
+}
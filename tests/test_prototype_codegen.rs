@@ -35,6 +35,7 @@ fn record_decl(name: &str, fields: Vec<(&str, Type)>) -> TopDecl {
             .map(|(name, ty)| FieldDecl {
                 name: name.to_string(),
                 ty,
+                default: None,
             })
             .collect(),
         frozen: false,
@@ -43,8 +44,20 @@ fn record_decl(name: &str, fields: Vec<(&str, Type)>) -> TopDecl {
     })
 }
 
+/// The WAT text of `(func $name ...)`, up to the next function.
+fn function_body<'a>(wat: &'a str, name: &str) -> &'a str {
+    let start = wat
+        .find(&format!("(func ${name} "))
+        .unwrap_or_else(|| panic!("missing function {name}:\n{wat}"));
+    let rest = &wat[start..];
+    let end = rest[1..]
+        .find("\n  (func ")
+        .map_or(rest.len(), |end| end + 1);
+    &rest[..end]
+}
+
 #[test]
-fn freeze_uses_registered_record_layout_size() {
+fn freeze_passes_record_through_without_copying() {
     let source = r#"
 record Snapshot {
     a: Float64,
@@ -52,39 +65,104 @@ record Snapshot {
     c: Float64
 }
 
+fun freeze_snapshot: (base: Snapshot) -> Float64 = {
+    val frozen = base freeze;
+    frozen.c
+}
+
 fun main: () -> Float64 = {
     val base = Snapshot {
         a: 1.0,
         b: 2.0,
         c: 3.0
     };
-    val frozen = base freeze;
-    frozen.c
+    base |> freeze_snapshot
 }
 "#;
 
     let wat = compile_to_wat(source).expect("freeze should compile");
+    let body = function_body(&wat, "freeze_snapshot");
+
+    assert!(
+        body.contains(";; Freeze Snapshot (type-level only)"),
+        "freeze should be marked as a type-level operation:\n{body}"
+    );
+    for op in ["call $allocate", "memory.copy", ".store"] {
+        assert!(
+            !body.contains(op),
+            "freeze should pass the record pointer through without `{op}`:\n{body}"
+        );
+    }
+
+    let wasm = wat::parse_str(&wat).unwrap_or_else(|err| {
+        panic!("freeze generated invalid WAT: {err}\n\n{wat}");
+    });
+    wasmparser::Validator::new()
+        .validate_all(&wasm)
+        .unwrap_or_else(|err| {
+            panic!("freeze generated invalid Wasm binary: {err}\n\n{wat}");
+        });
+}
+
+#[test]
+fn clone_copies_base_then_overwrites_updated_fields() {
+    let source = r#"
+record Point {
+    x: Int32,
+    y: Int32,
+    z: Int32
+}
 
+fun move_y: (base: Point, next_y: Int32) -> Point = {
+    base.clone { y: next_y }
+}
+
+fun main: () -> Int32 = {
+    val base = Point { x: 1, y: 2, z: 3 };
+    val moved = (base, 5) move_y;
+    moved.y
+}
+"#;
+
+    let wat = compile_to_wat(source).expect("clone should compile");
+    let body = function_body(&wat, "move_y");
+
+    let allocate = body.find("call $allocate").expect("clone should allocate");
+    let copy = body
+        .find("memory.copy")
+        .expect("clone should copy the base");
+    let update = body
+        .find(";; field offset for y")
+        .expect("clone should overwrite the updated field");
     assert!(
-        wat.contains("i32.const 24 ;; frozen record size"),
-        "freeze should allocate the registered 24-byte record layout:\n{wat}"
+        allocate < copy && copy < update,
+        "clone should allocate, copy the base, then overwrite fields:\n{body}"
     );
     assert!(
-        !wat.contains("i32.const 20 ;; record size"),
-        "freeze should not use the old hardcoded 20-byte placeholder:\n{wat}"
+        body.contains("i32.const 12 ;; size"),
+        "clone should copy the whole 12-byte record:\n{body}"
     );
     assert!(
-        !wat.contains("frozen flag"),
-        "freeze codegen should not write metadata into field storage:\n{wat}"
+        body.contains("local.get $base_tmp ;; source"),
+        "the base record should only be read as the copy source:\n{body}"
+    );
+    assert!(
+        !body.contains("local.get $base_tmp\n    i32.const"),
+        "the base record should not be written through:\n{body}"
+    );
+    assert_eq!(
+        body.matches("i32.store").count(),
+        1,
+        "only the updated field should be stored:\n{body}"
     );
 
     let wasm = wat::parse_str(&wat).unwrap_or_else(|err| {
-        panic!("freeze generated invalid WAT: {err}\n\n{wat}");
+        panic!("clone generated invalid WAT: {err}\n\n{wat}");
     });
     wasmparser::Validator::new()
         .validate_all(&wasm)
         .unwrap_or_else(|err| {
-            panic!("freeze generated invalid Wasm binary: {err}\n\n{wat}");
+            panic!("clone generated invalid Wasm binary: {err}\n\n{wat}");
         });
 }
 
@@ -134,7 +212,7 @@ fun main: () -> Int32 = {
 
     let wat = compile_to_wat(source).expect("generic record freeze should compile");
     assert!(
-        wat.contains(";; Freeze Box by copying record layout"),
+        wat.contains(";; Freeze Box (type-level only)"),
         "freeze should use the underlying generic record layout:\n{wat}"
     );
 
@@ -208,6 +286,7 @@ fn prototype_clone_codegen_rejects_placeholder_identity_metadata() {
             TopDecl::Function(FunDecl {
                 name: "main".to_string(),
                 is_async: false,
+                is_init: false,
                 type_params: Vec::new(),
                 temporal_constraints: Vec::new(),
                 params: Vec::new(),
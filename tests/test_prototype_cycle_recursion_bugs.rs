@@ -1,4 +1,5 @@
-use restrict_lang::{parse_program, TypeChecker};
+use restrict_lang::ast::{FieldDecl, Program, RecordDecl, TopDecl, Type};
+use restrict_lang::{parse_program, TypeChecker, TypeError};
 
 /// Prototype cycle and recursion edge-case probes.
 ///
@@ -477,3 +478,77 @@ fn test_affine_cycle_paradox() {
 
     assert_rejected_before_codegen("affine cycle paradox probe", input);
 }
+
+fn prototype_record(name: &str, parent_hash: Option<String>) -> RecordDecl {
+    RecordDecl {
+        name: name.to_string(),
+        type_params: Vec::new(),
+        temporal_constraints: Vec::new(),
+        fields: vec![FieldDecl {
+            name: format!("{}_value", name.to_lowercase()),
+            ty: Type::Named("Int32".to_string()),
+            default: None,
+        }],
+        frozen: false,
+        sealed: false,
+        parent_hash,
+    }
+}
+
+fn check_records(records: Vec<RecordDecl>) -> Result<(), TypeError> {
+    let program = Program {
+        imports: Vec::new(),
+        declarations: records.into_iter().map(TopDecl::Record).collect(),
+    };
+    TypeChecker::new().check_program(&program)
+}
+
+/// Build `names[0]` derives from `names[1]` derives from ... `names[n-1]`.
+fn prototype_chain(names: &[&str]) -> Vec<RecordDecl> {
+    let mut records: Vec<RecordDecl> = Vec::new();
+    for name in names.iter().rev() {
+        let parent_hash = records.last().map(TypeChecker::record_prototype_hash);
+        records.push(prototype_record(name, parent_hash));
+    }
+    records
+}
+
+#[test]
+fn prototype_chain_within_depth_is_accepted() {
+    check_records(prototype_chain(&["D", "C", "B", "A"])).expect("depth 3 chain is allowed");
+}
+
+#[test]
+fn prototype_chain_four_deep_is_too_deep() {
+    let err = check_records(prototype_chain(&["E", "D", "C", "B", "A"]))
+        .expect_err("depth 4 chain should be rejected");
+    assert_eq!(err, TypeError::DerivationTooDeep(4));
+}
+
+#[test]
+fn two_node_prototype_cycle_is_reported_distinctly() {
+    let a_hash = TypeChecker::record_prototype_hash(&prototype_record("A", None));
+    let b = prototype_record("B", Some(a_hash));
+    let a = prototype_record("A", Some(TypeChecker::record_prototype_hash(&b)));
+
+    let err = check_records(vec![a, b]).expect_err("A -> B -> A should be rejected");
+    assert_eq!(
+        err,
+        TypeError::CyclicDerivation(vec!["A".to_string(), "B".to_string(), "A".to_string()])
+    );
+    assert_eq!(err.to_string(), "Cyclic prototype derivation: A -> B -> A");
+}
+
+#[test]
+fn dangling_parent_hash_is_reported() {
+    let orphan = prototype_record("Orphan", Some("deadbeefdeadbeef".to_string()));
+
+    let err = check_records(vec![orphan]).expect_err("unknown parent hash should be rejected");
+    assert_eq!(
+        err,
+        TypeError::UnknownParentPrototype {
+            record: "Orphan".to_string(),
+            parent_hash: "deadbeefdeadbeef".to_string(),
+        }
+    );
+}
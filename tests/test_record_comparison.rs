@@ -0,0 +1,165 @@
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn check(source: &str) -> Result<restrict_lang::ast::Program, String> {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .map_err(|err| err.to_string())?;
+    Ok(ast)
+}
+
+fn instantiate(source: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let ast = check(source).expect("source should type check");
+    let wat = WasmCodeGen::new()
+        .generate(&ast)
+        .expect("source should compile");
+    let wasm = wat::parse_str(wat)?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+const RECORDS: &str = r#"
+record Point { x: Int32, y: Int32 }
+record Label { text: String, at: Point, weight: Float64 }
+record Version { major: Int32, minor: Int32 }
+record Tagged { id: Int32, note: String }
+
+impl Version {
+    fun cmp: (self: Version, other: Version) -> Int32 = {
+        val Version { major: a, minor: b } = self
+        val Version { major: c, minor: d } = other
+        a == c then { b - d } else { a - c }
+    }
+}
+
+impl Tagged {
+    fun eq: (self: Tagged, other: Tagged) -> Boolean = {
+        self.id == other.id
+    }
+}
+
+fun version: (major: Int32, minor: Int32) -> Version = {
+    Version { major: major, minor: minor }
+}
+
+fun label: (text: String, x: Int32) -> Label = {
+    Label { text: text, at: Point { x: x, y: 0 }, weight: 1.5 }
+}
+
+fun tagged: (id: Int32, note: String) -> Tagged = {
+    Tagged { id: id, note: note }
+}
+"#;
+
+// One export keeps the entry-point arenas inside a single page.
+#[test]
+fn record_comparisons_use_fields_or_impl_methods() -> Result<(), Box<dyn std::error::Error>> {
+    let cases = [
+        ("Point { x: 1, y: 2 } == Point { x: 1, y: 2 }", true),
+        ("Point { x: 1, y: 2 } == Point { x: 1, y: 3 }", false),
+        ("Point { x: 1, y: 2 } != Point { x: 1, y: 3 }", true),
+        ("((\"a\", 4) label) == ((\"a\", 4) label)", true),
+        ("((\"a\", 4) label) == ((\"b\", 4) label)", false),
+        ("((\"a\", 4) label) == ((\"a\", 5) label)", false),
+        ("((1, 2) version) < ((1, 3) version)", true),
+        ("((2, 0) version) <= ((1, 9) version)", false),
+        ("((2, 0) version) > ((1, 9) version)", true),
+        ("((1, 1) version) >= ((1, 1) version)", true),
+        ("((7, \"x\") tagged) == ((7, \"y\") tagged)", true),
+        ("((7, \"x\") tagged) != ((8, \"x\") tagged)", true),
+    ];
+    let arms: String = cases
+        .iter()
+        .enumerate()
+        .map(|(index, (expr, _))| format!("        {index} => {{ {expr} }}\n"))
+        .collect();
+    let (mut store, instance) = instantiate(&format!(
+        r#"{RECORDS}
+export fun compare_case: (index: Int32) -> Int32 = {{
+    val result = index match {{
+{arms}        _ => {{ false }}
+    }};
+    result then {{ 1 }} else {{ 0 }}
+}}
+"#
+    ))?;
+    let compare_case = instance.get_typed_func::<i32, i32>(&store, "compare_case")?;
+    for (index, (expr, expected)) in cases.into_iter().enumerate() {
+        assert_eq!(
+            compare_case.call(&mut store, index as i32)? == 1,
+            expected,
+            "{expr}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn ordering_records_without_cmp_is_rejected() {
+    let message = check(&format!(
+        "{RECORDS}\nfun main: () -> Boolean = {{\n    Point {{ x: 1, y: 2 }} < Point {{ x: 2, y: 1 }}\n}}\n"
+    ))
+    .expect_err("Point has no cmp method");
+    assert!(
+        message.contains("Type Point does not implement trait Ord"),
+        "{message}"
+    );
+}
+
+#[test]
+fn equality_needs_eq_fields_or_an_eq_method() {
+    let message = check(
+        r#"
+record Bag { items: List<Int32> }
+
+fun main: () -> Boolean = {
+    Bag { items: [1] } == Bag { items: [1] }
+}
+"#,
+    )
+    .expect_err("List fields have no structural equality");
+    assert!(
+        message.contains("Type Bag does not implement trait Eq"),
+        "{message}"
+    );
+
+    check(
+        r#"
+record Bag { items: List<Int32>, id: Int32 }
+
+impl Bag {
+    fun eq: (self: Bag, other: Bag) -> Boolean = {
+        self.id == other.id
+    }
+}
+
+fun main: () -> Boolean = {
+    Bag { items: [1], id: 1 } == Bag { items: [2], id: 1 }
+}
+"#,
+    )
+    .expect("an eq method makes any record comparable");
+}
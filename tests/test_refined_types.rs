@@ -0,0 +1,262 @@
+use restrict_lang::ast::{RangeBound, Type};
+use restrict_lang::{parse_program, TypeChecker, TypeError, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn parse(source: &str) -> restrict_lang::ast::Program {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    ast
+}
+
+fn check(source: &str) -> Result<restrict_lang::ast::Program, TypeError> {
+    let ast = parse(source);
+    TypeChecker::new().check_program(&ast)?;
+    Ok(ast)
+}
+
+fn check_err(source: &str) -> String {
+    check(source)
+        .expect_err("source should be rejected")
+        .to_string()
+}
+
+fn instantiate(source: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let ast = check(source).expect("source should type check");
+    let wat = WasmCodeGen::new().generate(&ast)?;
+    let wasm = wat::parse_str(&wat)?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+#[test]
+fn refined_annotation_parses_into_range_bound() {
+    let ast = parse(
+        r#"
+fun offset: (delta: Int32 in -10..10) -> Int32 in -10..=10 = {
+    delta
+}
+"#,
+    );
+    let restrict_lang::ast::TopDecl::Function(func) = &ast.declarations[0] else {
+        panic!("expected a function declaration");
+    };
+    assert_eq!(
+        func.params[0].ty,
+        Type::Refined(
+            Box::new(Type::Named("Int32".to_string())),
+            RangeBound { min: -10, max: 9 }
+        )
+    );
+    assert_eq!(func.params[0].ty.to_string(), "Int32 in -10..=9");
+    assert_eq!(
+        func.return_type,
+        Some(Type::Refined(
+            Box::new(Type::Named("Int32".to_string())),
+            RangeBound { min: -10, max: 10 }
+        ))
+    );
+}
+
+#[test]
+fn in_range_literal_is_accepted() {
+    check(
+        r#"
+fun main: () -> Int32 = {
+    val percent: Int32 in 0..100 = 42;
+    percent
+}
+"#,
+    )
+    .expect("42 fits 0..100");
+}
+
+#[test]
+fn out_of_range_literal_is_rejected() {
+    let message = check_err(
+        r#"
+fun main: () -> Int32 = {
+    val percent: Int32 in 0..=100 = 101;
+    percent
+}
+"#,
+    );
+    assert!(
+        message.contains("expected a value in 0..=100, found 101"),
+        "{message}"
+    );
+}
+
+#[test]
+fn exclusive_range_excludes_its_end() {
+    let message = check_err(
+        r#"
+fun main: () -> Int32 = {
+    val percent: Int32 in 0..100 = 100;
+    percent
+}
+"#,
+    );
+    assert!(
+        message.contains("expected a value in 0..=99, found 100"),
+        "{message}"
+    );
+
+    check(
+        r#"
+fun main: () -> Int32 = {
+    val percent: Int32 in 0..=100 = 100;
+    percent
+}
+"#,
+    )
+    .expect("..= includes its end");
+}
+
+#[test]
+fn widening_assignment_requires_conversion() {
+    let message = check_err(
+        r#"
+fun to_percent: (value: Int32) -> Int32 in 0..100 = {
+    value
+}
+"#,
+    );
+    assert!(message.contains("no static bound"), "{message}");
+    assert!(message.contains("`clamp` or `checked`"), "{message}");
+
+    check(
+        r#"
+fun to_percent: (value: Int32) -> Int32 in 0..=100 = {
+    (value, 0, 100) clamp
+}
+
+fun exact_percent: (value: Int32) -> Int32 in 0..=100 = {
+    (value, 0, 100) checked
+}
+"#,
+    )
+    .expect("clamp and checked narrow to their bounds");
+}
+
+#[test]
+fn arithmetic_on_bounded_values_narrows_statically() {
+    check(
+        r#"
+fun double: (value: Int32 in 0..=100) -> Int32 in 0..=200 = {
+    value * 2
+}
+
+fun main: () -> Int32 = {
+    val base: Int32 in 0..=100 = 40;
+    val offset = 2;
+    val doubled: Int32 in 0..=200 = base |> double;
+    val total: Int32 in 0..=302 = base + doubled + offset;
+    total
+}
+"#,
+    )
+    .expect("interval arithmetic proves the bounds");
+
+    let message = check_err(
+        r#"
+fun main: () -> Int32 = {
+    val base: Int32 in 0..=100 = 40;
+    val total: Int32 in 0..=100 = base + 1;
+    total
+}
+"#,
+    );
+    assert!(message.contains("found a value in 1..=101"), "{message}");
+}
+
+#[test]
+fn call_arguments_are_checked_against_parameter_ranges() {
+    let message = check_err(
+        r#"
+fun percent: (value: Int32 in 0..100) -> Int32 = {
+    value
+}
+
+fun main: () -> Int32 = {
+    150 |> percent
+}
+"#,
+    );
+    assert!(message.contains("found 150"), "{message}");
+}
+
+#[test]
+fn assignments_keep_the_declared_range() {
+    let message = check_err(
+        r#"
+fun main: () -> Int32 = {
+    mut val level: Int32 in 0..=10 = 0;
+    level = level + 1;
+    level
+}
+"#,
+    );
+    assert!(message.contains("found a value in 1..=11"), "{message}");
+}
+
+#[test]
+fn refinements_require_integer_bases_and_non_empty_ranges() {
+    assert!(check_err(
+        "fun main: () -> Int32 = {\n    val flag: Boolean in 0..1 = true;\n    0\n}\n"
+    )
+    .contains("only Int32 and Int64"));
+    assert!(check_err(
+        "fun main: () -> Int32 = {\n    val level: Int32 in 5..=1 = 3;\n    level\n}\n"
+    )
+    .contains("5..=1 is empty"));
+    assert!(check_err(
+        "fun main: () -> Int32 = {\n    val level: Int32 in 5..5 = 5;\n    level\n}\n"
+    )
+    .contains("is empty"));
+}
+
+#[test]
+fn refined_values_compile_as_plain_i32() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun to_percent: (value: Int32) -> Int32 in 0..=100 = {
+    (value, 0, 100) clamp
+}
+
+export fun exact_percent: (value: Int32) -> Int32 in 0..=100 = {
+    (value, 0, 100) checked
+}
+"#;
+    let (mut store, instance) = instantiate(source)?;
+    let to_percent = instance.get_typed_func::<i32, i32>(&store, "to_percent")?;
+    let exact_percent = instance.get_typed_func::<i32, i32>(&store, "exact_percent")?;
+
+    assert_eq!(to_percent.call(&mut store, 150)?, 100);
+    assert_eq!(to_percent.call(&mut store, -3)?, 0);
+    assert_eq!(to_percent.call(&mut store, 42)?, 42);
+    assert_eq!(exact_percent.call(&mut store, 42)?, 42);
+    assert!(exact_percent.call(&mut store, 101).is_err());
+    Ok(())
+}
@@ -5,9 +5,20 @@ use wasmi::{Caller, Engine, Instance, Linker, Memory, Module, Store};
 struct CapturedIo {
     stdout: Vec<u8>,
     stderr: Vec<u8>,
+    /// WASI argument vector, program name included.
+    args: Vec<String>,
+    /// WASI environment as `KEY=VALUE` entries.
+    env: Vec<String>,
 }
 
+/// Nanoseconds reported by the stub `clock_time_get`.
+const CLOCK_NANOS: i64 = 1_700_000_000_123_456_789;
+
 fn compile_to_wasm(source: &str) -> Result<Vec<u8>, String> {
+    compile_with(source, |_| {})
+}
+
+fn compile_with(source: &str, configure: impl FnOnce(&mut WasmCodeGen)) -> Result<Vec<u8>, String> {
     let (remaining, ast) = parse_program(source).map_err(|e| format!("Parse error: {e:?}"))?;
     if !remaining.trim().is_empty() {
         return Err(format!("Unparsed input remaining: {remaining:?}"));
@@ -19,6 +30,7 @@ fn compile_to_wasm(source: &str) -> Result<Vec<u8>, String> {
         .map_err(|e| format!("Type error: {e}"))?;
 
     let mut codegen = WasmCodeGen::new();
+    configure(&mut codegen);
     let wat = codegen
         .generate(&ast)
         .map_err(|e| format!("Codegen error: {e}"))?;
@@ -88,13 +100,136 @@ fn capture_fd_write(
     0
 }
 
+/// WASI `args_sizes_get`: argument count and total NUL-terminated size.
+fn args_sizes_get(caller: Caller<'_, CapturedIo>, argc: i32, buf_size: i32) -> i32 {
+    let args = caller.data().args.clone();
+    write_string_sizes(caller, &args, argc, buf_size)
+}
+
+/// WASI `args_get`: pointers into `argv`, NUL-terminated bytes into `buf`.
+fn args_get(caller: Caller<'_, CapturedIo>, argv: i32, buf: i32) -> i32 {
+    let args = caller.data().args.clone();
+    write_strings(caller, &args, argv, buf)
+}
+
+/// WASI `environ_sizes_get`, laid out like `args_sizes_get`.
+fn environ_sizes_get(caller: Caller<'_, CapturedIo>, count: i32, buf_size: i32) -> i32 {
+    let env = caller.data().env.clone();
+    write_string_sizes(caller, &env, count, buf_size)
+}
+
+/// WASI `environ_get`, laid out like `args_get`.
+fn environ_get(caller: Caller<'_, CapturedIo>, environ: i32, buf: i32) -> i32 {
+    let env = caller.data().env.clone();
+    write_strings(caller, &env, environ, buf)
+}
+
+/// WASI `clock_time_get`: a fixed realtime reading.
+fn clock_time_get(
+    mut caller: Caller<'_, CapturedIo>,
+    clock: i32,
+    _precision: i64,
+    time: i32,
+) -> i32 {
+    let Some(memory) = caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+    else {
+        return 1;
+    };
+    if clock != 0 {
+        return 28;
+    }
+    match memory.write(&mut caller, time as usize, &CLOCK_NANOS.to_le_bytes()) {
+        Ok(()) => 0,
+        Err(_) => 1,
+    }
+}
+
+fn write_string_sizes(
+    mut caller: Caller<'_, CapturedIo>,
+    strings: &[String],
+    count_ptr: i32,
+    buf_size: i32,
+) -> i32 {
+    let Some(memory) = caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+    else {
+        return 1;
+    };
+    let count = strings.len() as i32;
+    let size: usize = strings.iter().map(|arg| arg.len() + 1).sum();
+    let wrote_count = memory.write(&mut caller, count_ptr as usize, &count.to_le_bytes());
+    let wrote_size = memory.write(&mut caller, buf_size as usize, &(size as i32).to_le_bytes());
+    if wrote_count.is_err() || wrote_size.is_err() {
+        return 1;
+    }
+    0
+}
+
+fn write_strings(
+    mut caller: Caller<'_, CapturedIo>,
+    strings: &[String],
+    pointers: i32,
+    buf: i32,
+) -> i32 {
+    let Some(memory) = caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+    else {
+        return 1;
+    };
+    let mut offset = buf as usize;
+    for (i, arg) in strings.iter().enumerate() {
+        let mut bytes = arg.clone().into_bytes();
+        bytes.push(0);
+        let pointer = (offset as i32).to_le_bytes();
+        if memory
+            .write(&mut caller, pointers as usize + i * 4, &pointer)
+            .is_err()
+            || memory.write(&mut caller, offset, &bytes).is_err()
+        {
+            return 1;
+        }
+        offset += bytes.len();
+    }
+    0
+}
+
 fn instantiate(source: &str) -> Result<(Store<CapturedIo>, Instance), Box<dyn std::error::Error>> {
-    let wasm = compile_to_wasm(source)?;
-    wasmparser::Validator::new().validate_all(&wasm)?;
+    instantiate_with_args(source, &[])
+}
+
+fn instantiate_with_args(
+    source: &str,
+    args: &[&str],
+) -> Result<(Store<CapturedIo>, Instance), Box<dyn std::error::Error>> {
+    instantiate_module(&compile_to_wasm(source)?, args)
+}
+
+fn instantiate_module(
+    wasm: &[u8],
+    args: &[&str],
+) -> Result<(Store<CapturedIo>, Instance), Box<dyn std::error::Error>> {
+    instantiate_with_io(
+        wasm,
+        CapturedIo {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..CapturedIo::default()
+        },
+    )
+}
+
+fn instantiate_with_io(
+    wasm: &[u8],
+    io: CapturedIo,
+) -> Result<(Store<CapturedIo>, Instance), Box<dyn std::error::Error>> {
+    wasmparser::Validator::new().validate_all(wasm)?;
 
     let engine = Engine::default();
-    let module = Module::new(&engine, &wasm[..])?;
-    let mut store = Store::new(&engine, CapturedIo::default());
+    let module = Module::new(&engine, wasm)?;
+    let mut store = Store::new(&engine, io);
     let mut linker = Linker::new(&engine);
 
     linker.func_wrap("wasi_snapshot_preview1", "fd_write", capture_fd_write)?;
@@ -103,6 +238,15 @@ fn instantiate(source: &str) -> Result<(Store<CapturedIo>, Instance), Box<dyn st
         "proc_exit",
         |_caller: Caller<'_, CapturedIo>, _code: i32| {},
     )?;
+    linker.func_wrap("wasi_snapshot_preview1", "args_sizes_get", args_sizes_get)?;
+    linker.func_wrap("wasi_snapshot_preview1", "args_get", args_get)?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "environ_sizes_get",
+        environ_sizes_get,
+    )?;
+    linker.func_wrap("wasi_snapshot_preview1", "environ_get", environ_get)?;
+    linker.func_wrap("wasi_snapshot_preview1", "clock_time_get", clock_time_get)?;
 
     let instance = linker.instantiate_and_start(&mut store, &module)?;
     Ok((store, instance))
@@ -130,3 +274,324 @@ export fun std_io_smoke: () -> () = {
     assert_eq!(store.data().stderr, b"warn: check\n");
     Ok(())
 }
+
+#[test]
+fn assert_eq_failures_report_both_operands() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+record Point { x: Int32 }
+
+export fun int_mismatch: () -> () = {
+    ((2, 2) add, 5, "sum") assert_eq
+}
+
+export fun string_match: () -> () = {
+    ("left", "left", "labels") assert_ne
+}
+
+export fun int64_match: () -> () = {
+    val big: Int64 = 7;
+    (big, 7, "big") assert_ne
+}
+
+export fun record_mismatch: () -> () = {
+    (Point { x: 1 }, Point { x: 2 }, "points") assert_eq
+}
+
+fun add: (a: Int32, b: Int32) -> Int32 = {
+    a + b
+}
+"#;
+
+    for (name, expected) in [
+        ("int_mismatch", &b"sum: left = 4, right = 5\n"[..]),
+        ("string_match", b"labels: left = left, right = left\n"),
+        ("int64_match", b"big: left = 7, right = 7\n"),
+        ("record_mismatch", b"points\n"),
+    ] {
+        let (mut store, instance) = instantiate(source)?;
+        let function = instance.get_typed_func::<(), ()>(&store, name)?;
+        function
+            .call(&mut store, ())
+            .expect_err("a failed assertion should trap");
+        assert_eq!(
+            String::from_utf8_lossy(&store.data().stderr),
+            String::from_utf8_lossy(expected),
+            "{name}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn option_unwrap_on_none_panics_with_a_message() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun unwrap_none: () -> Int32 = {
+    val none: Option<Int32> = None;
+    none |> option_unwrap
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let unwrap_none = instance.get_typed_func::<(), i32>(&store, "unwrap_none")?;
+
+    unwrap_none
+        .call(&mut store, ())
+        .expect_err("option_unwrap should trap on None");
+    assert_eq!(store.data().stderr, b"unwrap on None\n");
+    Ok(())
+}
+
+#[test]
+fn to_string_builtins_return_printable_strings() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun convert_smoke: () -> () = {
+    val count = "count: " + (-42 |> int_to_string);
+    val zero = 0 |> int_to_string;
+    val ratio = 3.14 |> float_to_string;
+    val yes = true |> bool_to_string;
+    val no = false |> bool_to_string;
+    count |> println;
+    zero |> println;
+    ratio |> println;
+    yes |> println;
+    no |> println
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let convert_smoke = instance.get_typed_func::<(), ()>(&store, "convert_smoke")?;
+
+    convert_smoke.call(&mut store, ())?;
+
+    assert_eq!(
+        store.data().stdout,
+        b"count: -42\n0\n3.14\ntrue\nfalse\n".to_vec()
+    );
+    Ok(())
+}
+
+#[test]
+fn println_dispatches_on_display_argument_type() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun println_smoke: () -> () = {
+    (42) println;
+    ("x") println;
+    -7 |> println;
+    3.14 |> println;
+    true |> println;
+    'c' |> println
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let println_smoke = instance.get_typed_func::<(), ()>(&store, "println_smoke")?;
+
+    println_smoke.call(&mut store, ())?;
+
+    assert_eq!(store.data().stdout, b"42\nx\n-7\n3.14\ntrue\nc\n".to_vec());
+    Ok(())
+}
+
+#[test]
+fn println_prints_every_display_type() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun println_display_types: () -> () = {
+    (-42) println;
+    (-9223372036854775807L - 1L) println;
+    ("text") println;
+    (false) println;
+    (-0.5) println;
+    (-19.9d) println;
+    (0.0012d) println;
+    (5d) println;
+    ('z') println;
+    () |> println
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let println_display_types =
+        instance.get_typed_func::<(), ()>(&store, "println_display_types")?;
+
+    println_display_types.call(&mut store, ())?;
+
+    assert_eq!(
+        String::from_utf8(store.data().stdout.clone())?,
+        "-42\n-9223372036854775808\ntext\nfalse\n-0.50\n-19.9\n0.0012\n5\nz\n()\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn println_rejects_record_without_display() {
+    let source = r#"
+record Point {
+    x: Int32,
+    y: Int32
+}
+
+fun main: () -> () = {
+    val somePoint = Point { x: 1, y: 2 };
+    (somePoint) println
+}
+"#;
+
+    let err = compile_to_wasm(source).expect_err("Point does not implement Display");
+    assert!(
+        err.contains("Type error") && err.contains("does not implement trait Display"),
+        "println should enforce its Display bound, got: {err}"
+    );
+}
+
+#[test]
+fn args_reads_the_wasi_argument_vector() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+fun main: () -> () = {
+    val given = () args;
+    val first = given |> list_head match {
+        Some(arg) => { arg }
+        None => { "no arguments" }
+    };
+    first |> println
+}
+"#;
+
+    let (mut store, instance) = instantiate_with_args(source, &["echo.wasm", "hello", "world"])?;
+    let start = instance.get_typed_func::<(), ()>(&store, "_start")?;
+    start.call(&mut store, ())?;
+    assert_eq!(store.data().stdout, b"hello\n");
+
+    let (mut store, instance) = instantiate_with_args(source, &["echo.wasm"])?;
+    let start = instance.get_typed_func::<(), ()>(&store, "_start")?;
+    start.call(&mut store, ())?;
+    assert_eq!(store.data().stdout, b"no arguments\n");
+    Ok(())
+}
+
+#[test]
+fn programs_without_args_do_not_import_wasi_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let wasm = compile_to_wasm("fun main: () -> () = { \"hi\" |> println }")?;
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let imports: Vec<_> = module
+        .imports()
+        .map(|import| import.name().to_string())
+        .collect();
+    assert_eq!(imports, ["fd_write", "proc_exit"]);
+    Ok(())
+}
+
+#[test]
+fn env_var_reads_the_wasi_environment() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+fun show: (name: String) -> () = {
+    val found = with Env { name |> env_var };
+    found match {
+        Some(value) => { value |> println }
+        None => { "unset" |> println }
+    }
+}
+
+fun main: () -> () = {
+    "HOME" |> show;
+    "HOM" |> show;
+    "EMPTY" |> show;
+    "MISSING" |> show
+}
+"#;
+
+    let wasm = compile_to_wasm(source)?;
+    let io = CapturedIo {
+        env: ["HOMEPAGE=example.org", "HOME=/home/restrict", "EMPTY="]
+            .map(String::from)
+            .to_vec(),
+        ..CapturedIo::default()
+    };
+    let (mut store, instance) = instantiate_with_io(&wasm, io)?;
+    let start = instance.get_typed_func::<(), ()>(&store, "_start")?;
+    start.call(&mut store, ())?;
+    assert_eq!(store.data().stdout, b"/home/restrict\nunset\n\nunset\n");
+    Ok(())
+}
+
+#[test]
+fn now_reads_the_wasi_realtime_clock() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun stamp: () -> Int64 = {
+    with Clock { () now }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let stamp = instance.get_typed_func::<(), i64>(&store, "stamp")?;
+    assert_eq!(stamp.call(&mut store, ())?, CLOCK_NANOS);
+    Ok(())
+}
+
+#[test]
+fn host_builtins_are_rejected_outside_their_context() {
+    let err = compile_to_wasm("fun main: () -> () = { (() now) |> println }")
+        .expect_err("now needs with Clock");
+    assert!(err.contains("Context Clock is not available"), "got: {err}");
+}
+
+const INIT_PROGRAM: &str = r#"
+@init
+fun announce: () -> () = {
+    "init" |> println
+}
+
+fun main: () -> () = {
+    "main" |> println
+}
+"#;
+
+#[test]
+fn start_section_runs_init_functions_at_instantiation() -> Result<(), Box<dyn std::error::Error>> {
+    let wasm = compile_with(INIT_PROGRAM, |codegen| codegen.set_start_section(true))?;
+    let (mut store, instance) = instantiate_module(&wasm, &[])?;
+    assert_eq!(store.data().stdout, b"init\n");
+
+    let start = instance.get_typed_func::<(), ()>(&store, "_start")?;
+    start.call(&mut store, ())?;
+
+    assert_eq!(store.data().stdout, b"init\nmain\n");
+    Ok(())
+}
+
+#[test]
+fn start_wrapper_runs_init_functions_before_main() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(INIT_PROGRAM)?;
+    assert!(store.data().stdout.is_empty());
+
+    let start = instance.get_typed_func::<(), ()>(&store, "_start")?;
+    start.call(&mut store, ())?;
+
+    assert_eq!(store.data().stdout, b"init\nmain\n");
+    Ok(())
+}
+
+#[test]
+fn assert_that_panics_with_the_condition_source_text() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun failing_check: () -> () = {
+    (1 > 2) assert_that
+}
+
+export fun passing_check: () -> () = {
+    (2 > 1) |> assert_that
+}
+"#;
+
+    let wasm = compile_with(source, |codegen| codegen.set_source(source))?;
+    let (mut store, instance) = instantiate_module(&wasm, &[])?;
+    let passing_check = instance.get_typed_func::<(), ()>(&store, "passing_check")?;
+    let failing_check = instance.get_typed_func::<(), ()>(&store, "failing_check")?;
+
+    passing_check.call(&mut store, ())?;
+    assert!(store.data().stderr.is_empty());
+
+    assert!(failing_check.call(&mut store, ()).is_err());
+    assert_eq!(store.data().stderr, b"assertion failed: 1 > 2\n");
+    Ok(())
+}
@@ -91,6 +91,63 @@ fun test_prelude: () -> Boolean = {
     check_program_str(input).expect("prelude functions should type check");
 }
 
+#[test]
+fn assert_eq_and_assert_ne_unify_their_operands() {
+    let input = r#"
+fun test_asserts: () -> Int32 = {
+    val total = (2, 3) add_values;
+    (total, 5, "sum should be five") assert_eq;
+    ("left", "right", "labels should differ") assert_ne;
+    (1.5, 1.5, "floats should match") assert_eq;
+    total
+}
+
+fun add_values: (a: Int32, b: Int32) -> Int32 = {
+    a + b
+}
+"#;
+
+    check_program_str(input).expect("matching assert operands should type check");
+
+    let mismatched = r#"
+fun test_asserts: () -> () = {
+    (1, "one", "mixed operands") assert_eq
+}
+"#;
+    let err = check_program_str(mismatched).expect_err("mismatched operands should be rejected");
+    assert!(
+        err.contains("Int32") && err.contains("String"),
+        "unexpected error: {err}"
+    );
+
+    let missing_message = r#"
+fun test_asserts: () -> () = {
+    (1, 2, 3) assert_ne
+}
+"#;
+    check_program_str(missing_message).expect_err("the message must be a String");
+
+    let not_comparable = r#"
+fun test_asserts: () -> () = {
+    ([1], [1], "lists") assert_eq
+}
+"#;
+    let err = check_program_str(not_comparable).expect_err("operands must be Eq");
+    assert!(
+        err.contains("does not implement trait Eq"),
+        "unexpected error: {err}"
+    );
+
+    let records = r#"
+record Point { x: Int32, y: Int32 }
+
+fun test_asserts: () -> () = {
+    (Point { x: 1, y: 2 }, Point { x: 1, y: 2 }, "points") assert_eq
+}
+"#;
+    check_program_str(records).expect("records with Eq fields compare structurally");
+}
+
 #[test]
 fn generic_list_functions_infer_element_types() {
     let input = r#"
@@ -128,6 +185,49 @@ fun test_generic_options: () -> Int32 = {
     check_program_str(input).expect("generic option functions should infer payload types");
 }
 
+#[test]
+fn option_result_conversions_infer_generic_signatures() {
+    let input = r#"
+fun test_conversions: () -> Int32 = {
+    val present: Result<Int32, String> = (Some(1), "missing") option_ok_or;
+    val done: Result<Int32, Boolean> = Ok(2);
+    val ok_value: Option<Int32> = done |> result_ok;
+    val nested: Option<Option<Int32>> = Some(Some(3));
+    val flat: Option<Int32> = nested |> option_flatten;
+    val named = (Some("ada"), 0) option_ok_or;
+    val name: Option<String> = named |> result_ok;
+    (flat, 0) option_unwrap_or
+}
+"#;
+
+    check_program_str(input).expect("option and result conversions should type check");
+
+    let mismatched_error = r#"
+fun test_conversions: () -> Int32 = {
+    val converted: Result<Int32, String> = (Some(1), 0) option_ok_or;
+    0
+}
+"#;
+    let err = check_program_str(mismatched_error)
+        .expect_err("the error type comes from the second argument");
+    assert!(
+        err.contains("expected String, found Int32 (argument 2 of option_ok_or)"),
+        "unexpected error: {err}"
+    );
+
+    let flat_option = r#"
+fun test_conversions: () -> Int32 = {
+    val flat: Option<Int32> = Some(3) |> option_flatten;
+    0
+}
+"#;
+    let err = check_program_str(flat_option).expect_err("option_flatten needs a nested option");
+    assert!(
+        err.contains("expected Option<Int32>, found Int32 (argument 1 of option_flatten)"),
+        "unexpected error: {err}"
+    );
+}
+
 #[test]
 fn float_math_functions_type_check() {
     let input = r#"
@@ -142,6 +242,157 @@ fun test_float_math: () -> Float64 = {
     check_program_str(input).expect("float math functions should type check");
 }
 
+#[test]
+fn min_and_max_accept_any_ord_type() {
+    let input = r#"
+fun test_ord_math: () -> Int32 = {
+    val small: Int32 = (1, 2) min;
+    val large: Float64 = (1.0, 2.0) max;
+    val first: String = ("a", "b") min;
+    val low: Int64 = 3;
+    val high: Int64 = 4;
+    val wide: Int64 = (low, high) max;
+    small
+}
+"#;
+
+    check_program_str(input).expect("min and max should accept any Ord type");
+
+    let mismatched = r#"
+fun test_ord_math: () -> Int32 = {
+    (1, 2.0) min
+}
+"#;
+
+    assert!(
+        check_program_str(mismatched).is_err(),
+        "min must reject operands of different types"
+    );
+}
+
+#[test]
+fn min_rejects_records_without_ord() {
+    let input = r#"
+record Point { x: Int32, y: Int32 }
+
+fun test_point_min: () -> Int32 = {
+    val some_point = Point { x: 1, y: 2 };
+    val other = Point { x: 3, y: 4 };
+    val smaller = (some_point, other) min;
+    smaller.x
+}
+"#;
+
+    let err = check_program_str(input).expect_err("records do not implement Ord");
+    assert!(
+        err.contains("does not implement trait Ord"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn overflow_math_functions_return_int32() {
+    let input = r#"
+fun test_overflow_math: () -> Int32 = {
+    val a: Int32 = (2147483647, 1) wrapping_add;
+    val b: Int32 = (65536, 65536) wrapping_mul;
+    val c: Int32 = (2147483647, 1) saturating_add;
+    val d: Int32 = (-2147483647, 2) saturating_sub;
+    a + b + c + d
+}
+"#;
+
+    check_program_str(input).expect("overflow math functions should type check");
+
+    let mismatched = r#"
+fun test_overflow_math: () -> Int32 = {
+    (1, true) saturating_add
+}
+"#;
+    let err = check_program_str(mismatched).expect_err("Boolean operand should be rejected");
+    assert!(
+        err.contains("expected Int32, found Boolean"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn checked_math_functions_return_option_int32() {
+    let input = r#"
+fun test_checked_math: () -> Int32 = {
+    val sum: Option<Int32> = (2147483647, 1) checked_add;
+    val difference: Option<Int32> = (5, 3) checked_sub;
+    val product: Option<Int32> = (65536, 65536) checked_mul;
+    val quotient: Option<Int32> = (10, 0) checked_div;
+    sum match {
+        Some(value) => { value }
+        None => { 0 }
+    }
+}
+"#;
+
+    check_program_str(input).expect("checked math functions should type check");
+
+    let unwrapped = r#"
+fun test_checked_math: () -> Int32 = {
+    (2, 3) checked_add
+}
+"#;
+    let err = check_program_str(unwrapped).expect_err("the result is an Option, not an Int32");
+    assert!(err.contains("Option<Int32>"), "unexpected error: {err}");
+}
+
+#[test]
+fn integer_math_helpers_return_int32() {
+    let input = r#"
+fun test_integer_math: () -> Int32 = {
+    val a: Int32 = (15, 0, 10) clamp;
+    val b: Int32 = -7 |> sign;
+    val c: Int32 = (12, 18) gcd;
+    val d: Int32 = (4, 6) lcm;
+    a + b + c + d
+}
+"#;
+
+    check_program_str(input).expect("integer math helpers should type check");
+
+    let mismatched = r#"
+fun test_integer_math: () -> Int32 = {
+    (12, 1.5) gcd
+}
+"#;
+    let err = check_program_str(mismatched).expect_err("Float64 operand should be rejected");
+    assert!(
+        err.contains("expected Int32, found Float64"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn to_string_conversions_return_string() {
+    let input = r#"
+fun test_convert: () -> String = {
+    val count: String = 42 |> int_to_string;
+    val ratio: String = 0.5 |> float_to_string;
+    val flag: String = true |> bool_to_string;
+    count + ", " + ratio + ", " + flag
+}
+"#;
+
+    check_program_str(input).expect("to_string conversions should type check");
+
+    let mismatched = r#"
+fun test_convert: () -> String = {
+    1.5 |> int_to_string
+}
+"#;
+    let err = check_program_str(mismatched).expect_err("Float64 argument should be rejected");
+    assert!(
+        err.contains("expected Int32, found Float64"),
+        "unexpected error: {err}"
+    );
+}
+
 #[test]
 fn list_operations_compose_with_osv_calls() {
     let input = r#"
@@ -187,3 +438,177 @@ fun comprehensive_test: () -> Boolean = {
 
     check_program_str(input).expect("comprehensive stdlib flow should type check");
 }
+
+#[test]
+fn list_map_type_checks_callable_first() {
+    let input = r#"
+fun test_list_map: () -> List<Boolean> = {
+    val numbers = [1, 2, 3];
+    (|value| value > 1, numbers) list_map
+}
+"#;
+
+    check_program_str(input).expect("list_map should infer its mapper from the list");
+}
+
+#[test]
+fn list_filter_type_checks_with_captured_threshold() {
+    let input = r#"
+fun test_list_filter: (threshold: Int32) -> List<Int32> = {
+    val numbers = [1, 2, 3];
+    (|value| value > threshold, numbers) list_filter
+}
+"#;
+
+    check_program_str(input).expect("list_filter should accept a capturing predicate");
+}
+
+#[test]
+fn list_fold_type_checks_accumulator_type() {
+    let input = r#"
+fun test_list_fold: () -> Float64 = {
+    val numbers = [1, 2, 3];
+    (|total, value| total + (value as Float64), 0.5, numbers) list_fold
+}
+"#;
+
+    check_program_str(input).expect("list_fold should accept a distinct accumulator type");
+}
+
+#[test]
+fn list_filter_rejects_non_boolean_predicate() {
+    let input = r#"
+fun test_bad_filter: () -> List<Int32> = {
+    val numbers = [1, 2, 3];
+    (|value| value + 1, numbers) list_filter
+}
+"#;
+
+    let err = check_program_str(input).expect_err("predicate must return Boolean");
+    assert!(err.contains("Type error"), "unexpected error: {err}");
+}
+
+#[test]
+fn list_sort_type_checks_comparator() {
+    let input = r#"
+fun test_list_sort: () -> List<Float64> = {
+    val readings = [2.5, 0.5, 1.5];
+    (|a, b| a < b then { -1 } else { 1 }, readings) list_sort
+}
+"#;
+
+    check_program_str(input).expect("list_sort should infer its comparator from the list");
+}
+
+#[test]
+fn list_sort_rejects_non_int_comparator() {
+    let input = r#"
+fun test_bad_sort: () -> List<Int32> = {
+    val numbers = [3, 1, 2];
+    (|a, b| a < b, numbers) list_sort
+}
+"#;
+
+    let err = check_program_str(input).expect_err("comparator must return Int32");
+    assert!(err.contains("Type error"), "unexpected error: {err}");
+}
+
+#[test]
+fn list_sort_consumes_its_input() {
+    let input = r#"
+fun test_sort_reuse: () -> Int32 = {
+    val numbers = [3, 1, 2];
+    val sorted = (|a, b| a - b, numbers) list_sort;
+    numbers |> list_length
+}
+"#;
+
+    let err = check_program_str(input).expect_err("sorted list must not be reused");
+    assert!(err.contains("already been used"), "unexpected error: {err}");
+}
+
+#[test]
+fn list_sublist_builtins_type_check() {
+    let input = r#"
+fun test_sublists: () -> Int32 = {
+    val first_two: List<Int32> = ([1, 2, 3, 4], 2) list_take;
+    val names: List<String> = (["ada", "grace", "alan"], 1) list_drop;
+    val middle: List<Float64> = ([0.5, 1.5, 2.5], 1, 2) list_slice;
+    (first_two |> list_length) + (names |> list_length) + (middle |> list_length)
+}
+"#;
+
+    check_program_str(input).expect("sublist builtins should accept any element type");
+}
+
+#[test]
+fn list_take_consumes_its_input() {
+    let input = r#"
+fun test_take_reuse: () -> Int32 = {
+    val numbers = [3, 1, 2];
+    val first = (numbers, 1) list_take;
+    numbers |> list_length
+}
+"#;
+
+    let err = check_program_str(input).expect_err("taken-from list must not be reused");
+    assert!(err.contains("already been used"), "unexpected error: {err}");
+}
+
+#[test]
+fn list_slice_rejects_non_integer_bounds() {
+    let input = r#"
+fun test_bad_slice: () -> List<Int32> = {
+    ([1, 2, 3], 0, "2") list_slice
+}
+"#;
+
+    let err = check_program_str(input).expect_err("slice bounds must be Int32");
+    assert!(err.contains("Type error"), "unexpected error: {err}");
+}
+
+#[test]
+fn list_search_builtins_type_check_for_eq_elements() {
+    let input = r#"
+fun test_list_search: () -> Int32 = {
+    val names = ["ada", "grace"];
+    val has_ada: Boolean = (names, "ada") list_contains;
+    val position: Option<Int32> = ([3, 5, 8], 5) list_index_of;
+    position match {
+        Some(index) => { index }
+        None => { 0 }
+    }
+}
+"#;
+
+    check_program_str(input).expect("list search should accept Eq element types");
+}
+
+#[test]
+fn list_contains_rejects_mismatched_item() {
+    let input = r#"
+fun test_bad_search: () -> Boolean = {
+    ([1, 2, 3], "two") list_contains
+}
+"#;
+
+    let err = check_program_str(input).expect_err("item must match the element type");
+    assert!(err.contains("Type error"), "unexpected error: {err}");
+}
+
+#[test]
+fn list_search_rejects_elements_without_eq() {
+    let input = r#"
+fun test_function_search: () -> Boolean = {
+    val inc = |x: Int32| x + 1;
+    val handlers = [inc];
+    (handlers, |x: Int32| x) list_contains
+}
+"#;
+
+    let err = check_program_str(input).expect_err("functions are not equality-comparable");
+    assert!(
+        err.contains("does not implement trait Eq"),
+        "unexpected error: {err}"
+    );
+}
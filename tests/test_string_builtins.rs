@@ -0,0 +1,246 @@
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn check(source: &str) -> Result<restrict_lang::ast::Program, String> {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .map_err(|err| err.to_string())?;
+    Ok(ast)
+}
+
+fn compile(source: &str) -> String {
+    let ast = check(source).expect("source should type check");
+    WasmCodeGen::new()
+        .generate(&ast)
+        .expect("source should compile")
+}
+
+fn instantiate(source: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let wasm = wat::parse_str(compile(source))?;
+    wasmparser::Validator::new().validate_all(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+#[test]
+fn string_builtins_type_check() {
+    check(
+        r#"
+fun main: () -> Int32 = {
+    val length: Int32 = "abc" |> string_length;
+    val first: Option<Char> = ("abc", 0) string_char_at;
+    val text: String = 'x' |> string_from_char;
+    length
+}
+"#,
+    )
+    .expect("string builtins should type check");
+}
+
+#[test]
+fn string_builtins_reject_the_wrong_argument_types() {
+    let message = check("fun main: () -> Int32 = {\n    'a' |> string_length\n}\n")
+        .expect_err("string_length takes a String");
+    assert!(message.contains("String"), "{message}");
+
+    let message = check("fun main: () -> Option<Char> = {\n    (\"abc\", 'a') string_char_at\n}\n")
+        .expect_err("string_char_at takes an Int32 index");
+    assert!(message.contains("Int32"), "{message}");
+
+    let message = check("fun main: () -> Char = {\n    (\"abc\", 0) string_char_at\n}\n")
+        .expect_err("string_char_at returns an Option");
+    assert!(message.contains("Option"), "{message}");
+}
+
+#[test]
+fn string_length_reads_the_length_prefix() {
+    let wat = compile("fun main: () -> Int32 = {\n    \"abc\" |> string_length\n}\n");
+
+    let start = wat
+        .find("(func $string_length ")
+        .expect("string_length helper");
+    let helper = &wat[start..start + wat[start..].find("\n  )").unwrap()];
+    assert!(
+        helper.ends_with("local.get $str\n    i32.load"),
+        "string_length should load the 4-byte prefix: {helper}"
+    );
+    let main = &wat[wat.find("(func $main").unwrap()..];
+    assert!(main.contains("call $string_length"), "{main}");
+}
+
+#[test]
+fn string_builtins_run() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, instance) = instantiate(
+        r#"
+export fun sample_length: () -> Int32 = {
+    "aé😀" |> string_length
+}
+
+export fun code_at: (index: Int32) -> Int32 = {
+    val found = ("aé😀", index) string_char_at;
+    found match {
+        Some(ch) => { ch |> char_to_int }
+        None => { -1 }
+    }
+}
+
+export fun encoded_length: (code: Int32) -> Int32 = {
+    code |> int_to_char |> string_from_char |> string_length
+}
+
+export fun round_trip: (code: Int32) -> Int32 = {
+    val text = code |> int_to_char |> string_from_char;
+    val found = (text, 0) string_char_at;
+    found match {
+        Some(ch) => { ch |> char_to_int }
+        None => { -1 }
+    }
+}
+"#,
+    )?;
+    let sample_length = instance.get_typed_func::<(), i32>(&store, "sample_length")?;
+    let code_at = instance.get_typed_func::<i32, i32>(&store, "code_at")?;
+    let encoded_length = instance.get_typed_func::<i32, i32>(&store, "encoded_length")?;
+    let round_trip = instance.get_typed_func::<i32, i32>(&store, "round_trip")?;
+
+    // 'a' is one byte, 'é' two, and '😀' four.
+    assert_eq!(sample_length.call(&mut store, ())?, 7);
+    assert_eq!(code_at.call(&mut store, 0)?, 'a' as i32);
+    assert_eq!(code_at.call(&mut store, 1)?, 'é' as i32);
+    assert_eq!(code_at.call(&mut store, 2)?, -1, "inside 'é'");
+    assert_eq!(code_at.call(&mut store, 3)?, '😀' as i32);
+    assert_eq!(code_at.call(&mut store, 7)?, -1, "past the end");
+    assert_eq!(code_at.call(&mut store, -1)?, -1, "negative index");
+
+    for ch in ['a', 'é', '€', '😀'] {
+        assert_eq!(
+            encoded_length.call(&mut store, ch as i32)?,
+            ch.len_utf8() as i32
+        );
+        assert_eq!(round_trip.call(&mut store, ch as i32)?, ch as i32);
+    }
+    Ok(())
+}
+
+#[test]
+fn raw_multiline_strings_compile_to_their_dedented_bytes() -> Result<(), Box<dyn std::error::Error>>
+{
+    let (mut store, instance) = instantiate(
+        r#"
+export fun raw_matches_escaped: () -> Boolean = {
+    val raw = """
+        line "one"
+          C:\path
+        """;
+    raw == "line \"one\"\n  C:\\path\n"
+}
+
+export fun raw_length: () -> Int32 = {
+    """
+        line "one"
+          C:\path
+        """ |> string_length
+}
+"#,
+    )?;
+    let raw_matches_escaped = instance.get_typed_func::<(), i32>(&store, "raw_matches_escaped")?;
+    let raw_length = instance.get_typed_func::<(), i32>(&store, "raw_length")?;
+
+    assert_eq!(raw_matches_escaped.call(&mut store, ())?, 1);
+    assert_eq!(raw_length.call(&mut store, ())?, 21);
+    Ok(())
+}
+
+#[test]
+fn string_split_and_join_type_check() {
+    check(
+        r#"
+fun main: () -> String = {
+    val parts: List<String> = ("a,b,c", ',') string_split;
+    (parts, "-") string_join
+}
+"#,
+    )
+    .expect("string_split and string_join should type check");
+
+    let message = check("fun main: () -> List<String> = {\n    (\"a,b\", \",\") string_split\n}\n")
+        .expect_err("string_split takes a Char delimiter");
+    assert!(message.contains("Char"), "{message}");
+
+    let message = check("fun main: () -> String = {\n    ([1, 2], \"-\") string_join\n}\n")
+        .expect_err("string_join takes a List<String>");
+    assert!(message.contains("expected String"), "{message}");
+}
+
+#[test]
+fn string_split_and_join_run() -> Result<(), Box<dyn std::error::Error>> {
+    // One export keeps the entry-point arenas inside a single page.
+    let (mut store, instance) = instantiate(
+        r#"
+fun split_case: (index: Int32) -> Int32 = {
+    index match {
+        0 => { ("a,b,c", ',') string_split |> list_count }
+        1 => { ("abc", ';') string_split |> list_count }
+        2 => { ("a,b,", ',') string_split |> list_count }
+        3 => { ("", ',') string_split |> list_count }
+        _ => { ("x😀y😀z", '😀') string_split |> list_count }
+    }
+}
+
+fun join_case: (index: Int32) -> Boolean = {
+    index match {
+        0 => { (["a", "b"], "-") string_join == "a-b" }
+        1 => { (("a,b,c", ',') string_split, " | ") string_join == "a | b | c" }
+        2 => { (("a,b,", ',') string_split, "+") string_join == "a+b+" }
+        3 => { (["only"], ", ") string_join == "only" }
+        _ => { (("x😀y😀z", '😀') string_split, "") string_join == "xyz" }
+    }
+}
+
+export fun text_case: (kind: Int32, index: Int32) -> Int32 = {
+    kind == 0 then { index |> split_case } else { (index |> join_case) then { 1 } else { 0 } }
+}
+"#,
+    )?;
+    let text_case = instance.get_typed_func::<(i32, i32), i32>(&store, "text_case")?;
+
+    assert_eq!(text_case.call(&mut store, (0, 0))?, 3);
+    assert_eq!(text_case.call(&mut store, (0, 1))?, 1, "no delimiter");
+    assert_eq!(text_case.call(&mut store, (0, 2))?, 3, "trailing delimiter");
+    assert_eq!(text_case.call(&mut store, (0, 3))?, 1, "empty text");
+    assert_eq!(
+        text_case.call(&mut store, (0, 4))?,
+        3,
+        "multi-byte delimiter"
+    );
+    for index in 0..5 {
+        assert_eq!(
+            text_case.call(&mut store, (1, index))?,
+            1,
+            "join case {index}"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use wasmi::{Caller, Engine, Instance, Linker, Module, Store};
+
+fn compile(source: &str, tail_calls: bool) -> String {
+    let (remaining, ast) = parse_program(source).expect("source should parse");
+    assert!(
+        remaining.trim().is_empty(),
+        "source should parse completely, remaining: {remaining:?}"
+    );
+    TypeChecker::new()
+        .check_program(&ast)
+        .expect("source should type check");
+    let mut codegen = WasmCodeGen::new();
+    codegen.tail_calls(tail_calls);
+    codegen.verify(true);
+    codegen.generate(&ast).expect("source should compile")
+}
+
+/// The body of the generated `$name` function.
+fn function_body<'a>(wat: &'a str, name: &str) -> &'a str {
+    let start = wat
+        .find(&format!("(func ${} ", name))
+        .unwrap_or_else(|| panic!("missing function {name}"));
+    let end = wat[start + 1..]
+        .find("\n  (func ")
+        .map_or(wat.len(), |offset| start + 1 + offset);
+    &wat[start..end]
+}
+
+fn instantiate(wat: &str) -> Result<(Store<()>, Instance), Box<dyn std::error::Error>> {
+    let wasm = wat::parse_str(wat)?;
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..])?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "fd_write",
+        |_caller: Caller<'_, ()>, _fd: i32, _iovs: i32, _iovs_len: i32, _nwritten: i32| -> i32 {
+            0
+        },
+    )?;
+    linker.func_wrap(
+        "wasi_snapshot_preview1",
+        "proc_exit",
+        |_caller: Caller<'_, ()>, _code: i32| {},
+    )?;
+    let instance = linker.instantiate_and_start(&mut store, &module)?;
+    Ok((store, instance))
+}
+
+// One export keeps the entry-point arenas inside a single page.
+const SOURCE: &str = r#"
+fun sum_to: (value: Int32, total: Int32) -> Int32 = {
+    value == 0 then {
+        total
+    } else {
+        (value - 1, total + 2) sum_to
+    }
+}
+
+fun is_even: (value: Int32) -> Boolean = {
+    value match {
+        0 => { true }
+        1 => { false }
+        _ => { (value - 2) |> is_even }
+    }
+}
+
+fun product_to: (value: Int32) -> Int32 = {
+    value <= 1 then { 1 } else { value * ((value - 1) |> product_to) }
+}
+
+export fun tail_case: (index: Int32, arg: Int32) -> Int32 = {
+    index match {
+        0 => { (arg, 0) sum_to }
+        1 => { (arg |> is_even) then { 1 } else { 0 } }
+        _ => { arg |> product_to }
+    }
+}
+"#;
+
+#[test]
+fn self_tail_calls_lower_to_a_loop_by_default() -> Result<(), Box<dyn std::error::Error>> {
+    let wat = compile(SOURCE, false);
+    for name in ["sum_to", "is_even"] {
+        let body = function_body(&wat, name);
+        assert!(body.contains("(loop $tail_recur"), "{body}");
+        assert!(body.contains("br $tail_recur"), "{body}");
+        assert!(!body.contains(&format!("call ${name}")), "{body}");
+    }
+    // Multiplying after the call returns keeps it out of tail position.
+    let product_to = function_body(&wat, "product_to");
+    assert!(product_to.contains("call $product_to"), "{product_to}");
+    assert!(!product_to.contains("tail_recur"), "{product_to}");
+
+    let (mut store, instance) = instantiate(&wat)?;
+    let tail_case = instance.get_typed_func::<(i32, i32), i32>(&store, "tail_case")?;
+    // Deep enough to exhaust the call stack if every step took a frame.
+    assert_eq!(tail_case.call(&mut store, (0, 1_000_000))?, 2_000_000);
+    assert_eq!(tail_case.call(&mut store, (1, 1_000_001))?, 0);
+    assert_eq!(tail_case.call(&mut store, (2, 5))?, 120);
+    Ok(())
+}
+
+#[test]
+fn tail_calls_option_emits_return_call() -> Result<(), Box<dyn std::error::Error>> {
+    let wat = compile(SOURCE, true);
+    for name in ["sum_to", "is_even"] {
+        let body = function_body(&wat, name);
+        assert!(body.contains(&format!("return_call ${name}")), "{body}");
+        assert!(!body.contains(&format!("    call ${name}")), "{body}");
+        assert!(!body.contains("tail_recur"), "{body}");
+    }
+
+    let (mut store, instance) = instantiate(&wat)?;
+    let tail_case = instance.get_typed_func::<(i32, i32), i32>(&store, "tail_case")?;
+    assert_eq!(tail_case.call(&mut store, (0, 1_000_000))?, 2_000_000);
+    assert_eq!(tail_case.call(&mut store, (1, 1_000_000))?, 1);
+    Ok(())
+}
+
+#[test]
+fn exported_tail_calls_keep_the_loop_to_reset_their_arena() {
+    let wat = compile(
+        r#"
+export fun count_down: (value: Int32) -> Int32 = {
+    value <= 0 then { 0 } else { (value - 1) |> count_down }
+}
+"#,
+        true,
+    );
+    let body = function_body(&wat, "count_down");
+    assert!(body.contains("br $tail_recur"), "{body}");
+    assert!(!body.contains("return_call"), "{body}");
+    assert!(body.contains("call $arena_reset"), "{body}");
+}
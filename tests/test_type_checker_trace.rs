@@ -0,0 +1,84 @@
+use restrict_lang::{parse_program, CheckPhase, TraceEvent, TypeChecker, TypedType};
+use std::sync::{Arc, Mutex};
+
+fn trace_program(source: &str) -> Vec<TraceEvent> {
+    let (remaining, program) = parse_program(source).expect("source should parse");
+    assert!(remaining.trim().is_empty(), "unparsed input: {remaining:?}");
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let mut checker = TypeChecker::new();
+    checker.set_tracer(Box::new(move |event| sink.lock().unwrap().push(event)));
+    checker
+        .check_program(&program)
+        .expect("traced program should type check");
+
+    let events = events.lock().unwrap().clone();
+    events
+}
+
+#[test]
+fn tracing_reports_phases_functions_generics_and_consumption() {
+    let events = trace_program(
+        r#"
+record Point { x: Int32, y: Int32 }
+
+fun keep: <T>(value: T) -> T = {
+    value
+}
+
+fun main: () -> Int32 = {
+    val p = Point { x: 1, y: 2 };
+    val q = p |> keep;
+    q.x
+}
+"#,
+    );
+
+    let phases: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Phase(phase) => Some(*phase),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        phases,
+        [
+            CheckPhase::Declarations,
+            CheckPhase::Signatures,
+            CheckPhase::ImplBodies,
+            CheckPhase::ReturnInference,
+            CheckPhase::Bodies,
+        ]
+    );
+
+    let position = |wanted: &TraceEvent| {
+        events
+            .iter()
+            .position(|event| event == wanted)
+            .unwrap_or_else(|| panic!("missing {wanted}: {events:?}"))
+    };
+    let bodies = position(&TraceEvent::Phase(CheckPhase::Bodies));
+    let checking_keep = position(&TraceEvent::CheckingFunction("keep".to_string()));
+    let checking_main = position(&TraceEvent::CheckingFunction("main".to_string()));
+    let consumed_p = position(&TraceEvent::AffineConsumed("p".to_string()));
+    let resolved_t = position(&TraceEvent::GenericInstantiated {
+        function: "keep".to_string(),
+        param: "T".to_string(),
+        ty: TypedType::Record {
+            name: "Point".to_string(),
+            type_args: Vec::new(),
+            frozen: false,
+            hash: None,
+            parent_hash: None,
+        },
+    });
+
+    assert!(bodies < checking_keep && checking_keep < checking_main);
+    assert!(checking_main < consumed_p && consumed_p < resolved_t);
+    assert_eq!(
+        events[resolved_t].to_string(),
+        "resolved generic T=Point in keep"
+    );
+}
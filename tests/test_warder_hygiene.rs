@@ -76,12 +76,12 @@ fn checked_in_warder_fixture_sources_use_current_v001_syntax() {
 }
 
 #[test]
-fn warder_test_uses_current_cli_check_mode() {
+fn warder_test_runs_tests_in_process() {
     let source = read_workspace_file("warder/src/commands/test.rs");
 
     assert!(
-        source.contains(".arg(\"--check\")"),
-        "`warder test` should use the current compiler type-check mode"
+        source.contains("WasmCodeGen") && source.contains("wasmi"),
+        "`warder test` should compile and run test functions itself"
     );
     assert!(
         !source.contains(".arg(\"--test\")"),
@@ -93,15 +93,13 @@ fn warder_test_uses_current_cli_check_mode() {
 fn warder_build_unimplemented_modes_are_release_scoped() {
     let source = read_workspace_file("warder/src/commands/build.rs");
 
-    for anchor in [
-        "Watch mode",
-        "Release optimizations",
-        "WASM Component output",
-        "Deterministic build mode",
-        "Signature verification",
-    ] {
+    for anchor in ["WASM Component output", "Signature verification"] {
         assert_release_readiness_message(&source, anchor);
     }
+    assert!(
+        source.contains("cmd.arg(\"--release\")"),
+        "`warder build --release` should ask the compiler for a release build"
+    );
 }
 
 #[test]
@@ -506,6 +506,52 @@ export fun context_score: (input: Int32, adjustment: Int32) -> Int32 = {
     Ok(())
 }
 
+#[test]
+fn qualified_context_field_access_executes() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+context RuntimeScale {
+    factor: Int32,
+    offset: Int32
+}
+
+export fun qualified_context_score: (input: Int32, adjustment: Int32) -> Int32 = {
+    with RuntimeScale { factor: input, offset: adjustment } {
+        RuntimeScale.factor * 10 + RuntimeScale.offset
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let score = instance.get_typed_func::<(i32, i32), i32>(&store, "qualified_context_score")?;
+
+    assert_eq!(score.call(&mut store, (4, 2))?, 42);
+    Ok(())
+}
+
+#[test]
+fn qualified_context_field_ignores_body_locals_with_the_field_name(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+context DB {
+    host: String,
+    port: Int32
+}
+
+export fun context_port: () -> Int32 = {
+    with DB = DB { host: "x", port: 5 } {
+        val port = 7;
+        DB.port * 10 + port
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let context_port = instance.get_typed_func::<(), i32>(&store, "context_port")?;
+
+    assert_eq!(context_port.call(&mut store, ())?, 57);
+    Ok(())
+}
+
 #[test]
 fn exported_list_map_filter_fold_pipeline_executes() -> Result<(), Box<dyn std::error::Error>> {
     let source = r#"
@@ -527,6 +573,259 @@ export fun scored_iterator_pipeline: () -> Int32 = {
     Ok(())
 }
 
+#[test]
+fn exported_callable_first_list_combinators_execute() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun callable_first_pipeline: (offset: Int32) -> Int32 = {
+    with Arena {
+        val values = [1, 2, 3];
+        val shifted = (|value| value + offset, values) list_map;
+        val kept = (|value| value > 11, shifted) list_filter;
+        (|total, value| total + value, 0, kept) list_fold
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let callable_first_pipeline =
+        instance.get_typed_func::<i32, i32>(&store, "callable_first_pipeline")?;
+
+    assert_eq!(callable_first_pipeline.call(&mut store, 10)?, 25);
+    Ok(())
+}
+
+#[test]
+fn exported_method_and_partial_application_pipes_execute() -> Result<(), Box<dyn std::error::Error>>
+{
+    let source = r#"
+record Point { x: Int32, y: Int32 }
+
+impl Point {
+    fun translate: (self: Point, dx: Int32) -> Point = {
+        Point { x: self.x + dx, y: self.y }
+    }
+
+    fun shifted: (self: Point) -> Point = {
+        Point { x: self.x + 1, y: self.y + 1 }
+    }
+}
+
+fun add: (a: Int32, b: Int32) -> Int32 = { a + b }
+fun double: (a: Int32) -> Int32 = { a * 2 }
+
+export fun piped: (seed: Int32) -> Int32 = {
+    with Arena {
+        val p = Point { x: seed, y: 2 };
+        val q = p |> Point.shifted |> (10) Point.translate;
+        val r = seed |> (4) add |> double;
+        q.x + r
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let piped = instance.get_typed_func::<i32, i32>(&store, "piped")?;
+
+    assert_eq!(piped.call(&mut store, 1)?, 22);
+    Ok(())
+}
+
+#[test]
+fn partial_application_pipe_calls_a_local_that_shadows_a_function(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+fun add: (a: Int32, b: Int32) -> Int32 = { a + b }
+
+export fun shadowed: () -> Int32 = {
+    val add: (Int32) -> (Int32) -> Int32 = |x: Int32| { |y: Int32| { x * y } };
+    5 |> (3) add
+}
+
+export fun global: () -> Int32 = {
+    5 |> (3) add
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let shadowed = instance.get_typed_func::<(), i32>(&store, "shadowed")?;
+    let global = instance.get_typed_func::<(), i32>(&store, "global")?;
+
+    assert_eq!(shadowed.call(&mut store, ())?, 15);
+    assert_eq!(global.call(&mut store, ())?, 8);
+    Ok(())
+}
+
+#[test]
+fn exported_receiver_method_calls_execute() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+record Point { x: Int32, y: Int32 }
+
+impl Point {
+    fun scaled_sum: (self: Point, k: Int32) -> Int32 = { (self.x + self.y) * k }
+}
+
+export fun run: (k: Int32) -> Int32 = {
+    with Arena {
+        val p = Point { x: 2, y: 5 }
+        val total = (k) p.scaled_sum
+        total + 1
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let run = instance.get_typed_func::<i32, i32>(&store, "run")?;
+
+    assert_eq!(run.call(&mut store, 3)?, 22);
+    Ok(())
+}
+
+#[test]
+fn drop_discards_values_in_calls_and_pipes() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+record Handle { id: Int32 }
+
+fun close: (h: Handle) -> Int32 = { h.id }
+
+fun release: (h: Handle) -> Int32 = {
+    h |> drop;
+    1
+}
+
+export fun run: (n: Int32) -> Int32 = {
+    with Arena {
+        val h = Handle { id: n };
+        val status = Handle { id: 3 } |> close;
+        (status) drop;
+        (h) drop;
+        (Handle { id: n }) release + n
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let run = instance.get_typed_func::<i32, i32>(&store, "run")?;
+
+    assert_eq!(run.call(&mut store, 41)?, 42);
+    Ok(())
+}
+
+#[test]
+fn while_let_drains_option_and_result_producers() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+fun next_below: (n: Int32, limit: Int32) -> Option<Int32> = {
+    n < limit then { Some(n) } else { None }
+}
+
+fun checked_step: (n: Int32) -> Result<Int32, String> = {
+    n < 100 then { Ok(n * 2) } else { Err("overflow") }
+}
+
+export fun drain: (limit: Int32) -> Int32 = {
+    mut val i = 0;
+    mut val total = 0;
+    (i, limit) next_below while Some(x) {
+        total = total + x;
+        i = i + 1;
+    }
+    total
+}
+
+export fun double_until_overflow: (seed: Int32) -> Int32 = {
+    mut val current = seed;
+    current |> checked_step while Ok(next) {
+        current = next;
+    }
+    current
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let drain = instance.get_typed_func::<i32, i32>(&store, "drain")?;
+    let double_until_overflow =
+        instance.get_typed_func::<i32, i32>(&store, "double_until_overflow")?;
+
+    assert_eq!(drain.call(&mut store, 5)?, 10);
+    assert_eq!(drain.call(&mut store, 0)?, 0);
+    assert_eq!(double_until_overflow.call(&mut store, 3)?, 192);
+    Ok(())
+}
+
+#[test]
+fn exported_field_updates_write_through_mutable_records() -> Result<(), Box<dyn std::error::Error>>
+{
+    let source = r#"
+record Counter { hits: Int32, total: Int64 }
+
+export fun bump: (n: Int32) -> Int32 = {
+    with Arena {
+        mut val c = Counter { hits: 0, total: 0 as Int64 }
+        mut val i = 0
+        (i < n) while {
+            c.hits <- c.hits + 1
+            c.total <- (i as Int64) + c.total
+            i = i + 1
+        }
+        c.hits + (c.total as Int32)
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let bump = instance.get_typed_func::<i32, i32>(&store, "bump")?;
+
+    assert_eq!(bump.call(&mut store, 4)?, 10);
+    Ok(())
+}
+
+#[test]
+fn field_updates_cannot_store_arena_values_in_outer_records(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let leak = r#"
+record Label { text: String, size: Int32 }
+
+export fun leak: () -> Int32 = {
+    mut val label = Label { text: "a", size: 0 };
+    mut val n = 1111;
+    n <= 2222 while {
+        with Arena {
+            val fresh = (n) int_to_string;
+            label.text <- fresh;
+            0
+        };
+        n = n + 1111
+    };
+    label.text |> string_length
+}
+"#;
+    let err = compile_to_wasm(leak).expect_err("arena string stored in an outer record");
+    assert!(err.contains("Arena result cannot escape"), "got: {err}");
+
+    let kept = r#"
+record Label { text: String, size: Int32 }
+
+export fun kept: () -> Int32 = {
+    mut val label = Label { text: "a", size: 0 };
+    mut val n = 1111;
+    n <= 2222 while {
+        with Arena {
+            val fresh = (n) int_to_string;
+            label.size <- fresh |> string_length;
+            0
+        };
+        label.text <- (n) int_to_string;
+        n = n + 1111
+    };
+    (label.text == "2222") then { label.size } else { 0 - 1 }
+}
+"#;
+    let (mut store, instance) = instantiate(kept)?;
+    let kept = instance.get_typed_func::<(), i32>(&store, "kept")?;
+
+    assert_eq!(kept.call(&mut store, ())?, 4);
+    Ok(())
+}
+
 #[test]
 fn exported_named_function_iterators_execute() -> Result<(), Box<dyn std::error::Error>> {
     let source = r#"
@@ -801,6 +1100,108 @@ export fun literal_pattern_score: () -> Int32 = {
     Ok(())
 }
 
+#[test]
+fn exported_overflow_math_functions_execute() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun wrap_add: (a: Int32, b: Int32) -> Int32 = { (a, b) wrapping_add }
+export fun wrap_mul: (a: Int32, b: Int32) -> Int32 = { (a, b) wrapping_mul }
+export fun sat_add: (a: Int32, b: Int32) -> Int32 = { (a, b) saturating_add }
+export fun sat_sub: (a: Int32, b: Int32) -> Int32 = { (a, b) saturating_sub }
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let wrap_add = instance.get_typed_func::<(i32, i32), i32>(&store, "wrap_add")?;
+    let wrap_mul = instance.get_typed_func::<(i32, i32), i32>(&store, "wrap_mul")?;
+    let sat_add = instance.get_typed_func::<(i32, i32), i32>(&store, "sat_add")?;
+    let sat_sub = instance.get_typed_func::<(i32, i32), i32>(&store, "sat_sub")?;
+
+    assert_eq!(wrap_add.call(&mut store, (i32::MAX, 1))?, i32::MIN);
+    assert_eq!(wrap_mul.call(&mut store, (65536, 65536))?, 0);
+    assert_eq!(sat_add.call(&mut store, (i32::MAX, 1))?, i32::MAX);
+    assert_eq!(sat_add.call(&mut store, (i32::MIN, -1))?, i32::MIN);
+    assert_eq!(sat_add.call(&mut store, (40, 2))?, 42);
+    assert_eq!(sat_sub.call(&mut store, (i32::MIN, 1))?, i32::MIN);
+    assert_eq!(sat_sub.call(&mut store, (i32::MAX, -1))?, i32::MAX);
+    assert_eq!(sat_sub.call(&mut store, (50, 8))?, 42);
+    Ok(())
+}
+
+#[test]
+fn checked_math_returns_none_on_overflow() -> Result<(), Box<dyn std::error::Error>> {
+    // `None` maps to a value no Int32 result can take.
+    let source = r#"
+export fun checked_case: (op: Int32, a: Int32, b: Int32) -> Int64 = {
+    val result = op match {
+        0 => { (a, b) checked_add }
+        1 => { (a, b) checked_sub }
+        2 => { (a, b) checked_mul }
+        _ => { (a, b) checked_div }
+    };
+    result match {
+        Some(value) => { value |> to_i64 }
+        None => { 4294967296L }
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let checked_case = instance.get_typed_func::<(i32, i32, i32), i64>(&store, "checked_case")?;
+    let none = 1_i64 << 32;
+    let (add, sub, mul, div) = (0, 1, 2, 3);
+
+    assert_eq!(checked_case.call(&mut store, (add, i32::MAX, 1))?, none);
+    assert_eq!(checked_case.call(&mut store, (add, i32::MIN, -1))?, none);
+    assert_eq!(checked_case.call(&mut store, (add, 2, 3))?, 5);
+    assert_eq!(
+        checked_case.call(&mut store, (add, i32::MAX, 0))?,
+        i64::from(i32::MAX)
+    );
+    assert_eq!(checked_case.call(&mut store, (sub, i32::MIN, 1))?, none);
+    assert_eq!(checked_case.call(&mut store, (sub, 50, 8))?, 42);
+    assert_eq!(checked_case.call(&mut store, (mul, 65536, 65536))?, none);
+    assert_eq!(checked_case.call(&mut store, (mul, -6, 7))?, -42);
+    assert_eq!(checked_case.call(&mut store, (div, 10, 0))?, none);
+    assert_eq!(checked_case.call(&mut store, (div, i32::MIN, -1))?, none);
+    assert_eq!(checked_case.call(&mut store, (div, -7, 2))?, -3);
+    Ok(())
+}
+
+#[test]
+fn exported_integer_math_helpers_execute() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun math_case: (op: Int32, a: Int32, b: Int32) -> Int32 = {
+    op match {
+        0 => { (a, 0, b) clamp }
+        1 => { a |> sign }
+        2 => { (a, b) gcd }
+        _ => { (a, b) lcm }
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let math_case = instance.get_typed_func::<(i32, i32, i32), i32>(&store, "math_case")?;
+    let mut call = |op, a, b| math_case.call(&mut store, (op, a, b));
+
+    assert_eq!(call(0, 15, 10)?, 10);
+    assert_eq!(call(0, -3, 10)?, 0);
+    assert_eq!(call(0, 7, 10)?, 7);
+    assert_eq!(call(1, -42, 0)?, -1);
+    assert_eq!(call(1, 0, 0)?, 0);
+    assert_eq!(call(1, i32::MAX, 0)?, 1);
+    assert_eq!(call(2, 12, 18)?, 6);
+    assert_eq!(call(2, -12, 18)?, 6);
+    assert_eq!(call(2, 0, 0)?, 0);
+    assert_eq!(call(2, 0, -5)?, 5);
+    assert_eq!(call(2, i32::MIN, 6)?, 2);
+    assert!(call(2, i32::MIN, 0).is_err(), "2^31 does not fit in Int32");
+    assert_eq!(call(3, 4, 6)?, 12);
+    assert_eq!(call(3, -4, 6)?, 12);
+    assert_eq!(call(3, 0, 6)?, 0);
+    assert!(call(3, 65537, 65536).is_err(), "lcm overflow traps");
+    Ok(())
+}
+
 #[test]
 fn exported_stdlib_value_functions_execute() -> Result<(), Box<dyn std::error::Error>> {
     let source = r#"
@@ -996,95 +1397,827 @@ export fun write_index_at_length: () -> Int32 = {
 }
 
 #[test]
-fn specialized_int64_list_runtime_abi_executes() -> Result<(), Box<dyn std::error::Error>> {
+fn match_guards_fall_through_to_later_arms() -> Result<(), Box<dyn std::error::Error>> {
     let source = r#"
-export fun update_int64_list: () -> Int64 = {
-    with Arena {
-        val base: List<Int64> = [10000000000, 20000000000];
-        val appended = (base, 30000000000) list_append;
-        val prepended = (40000000000, appended) list_prepend;
-        val reversed = prepended |> list_reverse;
-        (reversed, 0) list_get
+fun bucket: (value: Option<Int32>) -> Int32 = {
+    value match {
+        Some(n) if n > 100 => { 3 }
+        Some(n) if n > 10 => { 2 }
+        Some(n) => { 1 }
+        None => { 0 }
     }
 }
+
+export fun guard_score: () -> Int32 = {
+    (Some(500) |> bucket) * 1000
+        + (Some(50) |> bucket) * 100
+        + (Some(5) |> bucket) * 10
+        + (None |> bucket)
+}
 "#;
 
     let (mut store, instance) = instantiate(source)?;
-    let update_int64_list = instance.get_typed_func::<(), i64>(&store, "update_int64_list")?;
+    let guard_score = instance.get_typed_func::<(), i32>(&store, "guard_score")?;
 
-    assert_eq!(update_int64_list.call(&mut store, ())?, 30000000000);
+    assert_eq!(guard_score.call(&mut store, ())?, 3210);
     Ok(())
 }
 
 #[test]
-fn small_int64_literals_runtime_abi_executes() -> Result<(), Box<dyn std::error::Error>> {
+fn at_bindings_keep_the_whole_value_alongside_destructured_parts(
+) -> Result<(), Box<dyn std::error::Error>> {
     let source = r#"
-export fun update_small_int64_array: () -> Int64 = {
-    with Arena {
-        mut val counters: Array<Int64, 2> = [1, 2];
-        val done = (counters, 1, 3) array_set;
-        (counters, 1) array_get
+fun outer: (value: Option<Int32>) -> Int32 = {
+    value match {
+        whole @ Some(n) => { (whole |> option_unwrap) * 100 + n }
+        None => { 0 }
     }
 }
 
-export fun update_small_int64_list: () -> Int64 = {
-    with Arena {
-        val base: List<Int64> = [1, 2];
-        val appended = (base, 3) list_append;
-        (appended, 2) list_get
+fun nested: (value: Option<Option<Int32>>) -> Int32 = {
+    value match {
+        Some(inner @ Some(n)) => { (inner |> option_unwrap) + n }
+        Some(None) => { 1 }
+        None => { 0 }
     }
 }
+
+export fun at_binding_score: () -> Int32 = {
+    (Some(7) |> outer) * 100 + (Some(Some(4)) |> nested) * 10 + (Some(None) |> nested)
+}
 "#;
 
     let (mut store, instance) = instantiate(source)?;
-    let update_small_int64_array =
-        instance.get_typed_func::<(), i64>(&store, "update_small_int64_array")?;
-    let update_small_int64_list =
-        instance.get_typed_func::<(), i64>(&store, "update_small_int64_list")?;
+    let at_binding_score = instance.get_typed_func::<(), i32>(&store, "at_binding_score")?;
 
-    assert_eq!(update_small_int64_array.call(&mut store, ())?, 3);
-    assert_eq!(update_small_int64_list.call(&mut store, ())?, 3);
+    assert_eq!(at_binding_score.call(&mut store, ())?, 70781);
     Ok(())
 }
 
 #[test]
-fn exported_allocation_without_explicit_arena_uses_export_arena(
+fn generic_record_methods_specialize_record_and_method_type_params(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let source = r#"
-export fun unscoped_allocating_export: () -> Int32 = {
-    val values = [1, 2];
-    values |> list_count
+record Box<T> {
+    value: T
+}
+
+impl Box {
+    fun get: (self: Box<T>) -> T = {
+        self.value
+    }
+
+    fun transform: <U>(self: Box<T>, f: (T) -> U) -> Box<U> = {
+        Box { value: (self.value) f }
+    }
+}
+
+export fun generic_method_score: () -> Int32 = {
+    val box: Box<Int32> = Box { value: 20 };
+    val wide = (box, |n| (n as Int64) * 2) transform;
+    val narrow = (wide, |n| (n + 2) as Int32) transform;
+    (narrow) get
 }
 "#;
 
     let (mut store, instance) = instantiate(source)?;
-    let unscoped_allocating_export =
-        instance.get_typed_func::<(), i32>(&store, "unscoped_allocating_export")?;
+    let generic_method_score =
+        instance.get_typed_func::<(), i32>(&store, "generic_method_score")?;
 
-    assert_eq!(unscoped_allocating_export.call(&mut store, ())?, 2);
+    assert_eq!(generic_method_score.call(&mut store, ())?, 42);
     Ok(())
 }
 
 #[test]
-fn exported_function_preserves_caller_arena_after_calling_exported_allocator(
-) -> Result<(), Box<dyn std::error::Error>> {
+fn option_result_conversions_execute() -> Result<(), Box<dyn std::error::Error>> {
     let source = r#"
-export fun inner_allocating_export: () -> Int32 = {
-    val inner_values = [1, 2];
-    inner_values |> list_count
+export fun some_to_ok: () -> Int32 = {
+    val converted = (Some(1), 99) option_ok_or;
+    converted match {
+        Ok(value) => { value }
+        Err(code) => { code }
+    }
 }
 
-export fun outer_allocating_export: () -> Int32 = {
-    val inner_count = () inner_allocating_export;
-    val outer_values = [3, 4];
-    inner_count + (outer_values |> list_count)
+export fun none_to_err: () -> Float64 = {
+    val missing: Option<Int32> = None;
+    val converted = (missing, 2.5) option_ok_or;
+    converted match {
+        Ok(value) => { value as Float64 }
+        Err(code) => { code }
+    }
 }
-"#;
 
-    let (mut store, instance) = instantiate(source)?;
-    let outer_allocating_export =
-        instance.get_typed_func::<(), i32>(&store, "outer_allocating_export")?;
+export fun result_to_option: () -> Int32 = {
+    val done: Result<Int32, String> = Ok(7);
+    val failed: Result<Int32, String> = Err("boom");
+    val kept = done |> result_ok;
+    val dropped = failed |> result_ok;
+    (dropped |> option_is_none) then {
+        (kept, 0) option_unwrap_or
+    } else {
+        -1
+    }
+}
 
-    assert_eq!(outer_allocating_export.call(&mut store, ())?, 4);
+export fun flatten_nested: () -> Int32 = {
+    val nested: Option<Option<Int32>> = Some(Some(2));
+    val empty_inner: Option<Option<Int32>> = Some(None);
+    val empty_outer: Option<Option<Int32>> = None;
+    val inner_missing = (empty_inner |> option_flatten) |> option_is_none;
+    val outer_missing = (empty_outer |> option_flatten) |> option_is_none;
+    (inner_missing && outer_missing) then {
+        (nested |> option_flatten) match {
+            Some(value) => { value }
+            None => { 0 }
+        }
+    } else {
+        -1
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let some_to_ok = instance.get_typed_func::<(), i32>(&store, "some_to_ok")?;
+    let none_to_err = instance.get_typed_func::<(), f64>(&store, "none_to_err")?;
+    let result_to_option = instance.get_typed_func::<(), i32>(&store, "result_to_option")?;
+    let flatten_nested = instance.get_typed_func::<(), i32>(&store, "flatten_nested")?;
+
+    assert_eq!(some_to_ok.call(&mut store, ())?, 1);
+    assert_eq!(none_to_err.call(&mut store, ())?, 2.5);
+    assert_eq!(result_to_option.call(&mut store, ())?, 7);
+    assert_eq!(flatten_nested.call(&mut store, ())?, 2);
+    Ok(())
+}
+
+#[test]
+fn option_combinators_execute_and_unwrap_traps_on_none() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun option_combinator_score: () -> Int32 = {
+    val some: Option<Int32> = Some(20);
+    val none: Option<Int32> = None;
+    val mapped = (|n| n + 1, some) option_map;
+    val chained = (|n| Some(n * 2), mapped) option_and_then;
+    val skipped = (|n| Some(n * 2), none) option_and_then;
+    val ratio = (|n| n as Float64, Some(3)) option_map;
+    val missing = skipped |> option_is_none;
+
+    missing then {
+        (chained |> option_unwrap) + ((ratio |> option_unwrap) as Int32)
+    } else {
+        0
+    }
+}
+
+export fun unwrap_none: () -> Int32 = {
+    val none: Option<Int32> = None;
+    none |> option_unwrap
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let option_combinator_score =
+        instance.get_typed_func::<(), i32>(&store, "option_combinator_score")?;
+    let unwrap_none = instance.get_typed_func::<(), i32>(&store, "unwrap_none")?;
+
+    assert_eq!(option_combinator_score.call(&mut store, ())?, 45);
+    let err = unwrap_none
+        .call(&mut store, ())
+        .expect_err("option_unwrap should trap on None");
+    assert_eq!(err.as_trap_code(), Some(TrapCode::UnreachableCodeReached));
+    Ok(())
+}
+
+#[test]
+fn specialized_int64_list_runtime_abi_executes() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun update_int64_list: () -> Int64 = {
+    with Arena {
+        val base: List<Int64> = [10000000000, 20000000000];
+        val appended = (base, 30000000000) list_append;
+        val prepended = (40000000000, appended) list_prepend;
+        val reversed = prepended |> list_reverse;
+        (reversed, 0) list_get
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let update_int64_list = instance.get_typed_func::<(), i64>(&store, "update_int64_list")?;
+
+    assert_eq!(update_int64_list.call(&mut store, ())?, 30000000000);
+    Ok(())
+}
+
+#[test]
+fn small_int64_literals_runtime_abi_executes() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun update_small_int64_array: () -> Int64 = {
+    with Arena {
+        mut val counters: Array<Int64, 2> = [1, 2];
+        val done = (counters, 1, 3) array_set;
+        (counters, 1) array_get
+    }
+}
+
+export fun update_small_int64_list: () -> Int64 = {
+    with Arena {
+        val base: List<Int64> = [1, 2];
+        val appended = (base, 3) list_append;
+        (appended, 2) list_get
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let update_small_int64_array =
+        instance.get_typed_func::<(), i64>(&store, "update_small_int64_array")?;
+    let update_small_int64_list =
+        instance.get_typed_func::<(), i64>(&store, "update_small_int64_list")?;
+
+    assert_eq!(update_small_int64_array.call(&mut store, ())?, 3);
+    assert_eq!(update_small_int64_list.call(&mut store, ())?, 3);
+    Ok(())
+}
+
+#[test]
+fn exported_allocation_without_explicit_arena_uses_export_arena(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun unscoped_allocating_export: () -> Int32 = {
+    val values = [1, 2];
+    values |> list_count
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let unscoped_allocating_export =
+        instance.get_typed_func::<(), i32>(&store, "unscoped_allocating_export")?;
+
+    assert_eq!(unscoped_allocating_export.call(&mut store, ())?, 2);
+    Ok(())
+}
+
+#[test]
+fn exported_function_preserves_caller_arena_after_calling_exported_allocator(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun inner_allocating_export: () -> Int32 = {
+    val inner_values = [1, 2];
+    inner_values |> list_count
+}
+
+export fun outer_allocating_export: () -> Int32 = {
+    val inner_count = () inner_allocating_export;
+    val outer_values = [3, 4];
+    inner_count + (outer_values |> list_count)
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let outer_allocating_export =
+        instance.get_typed_func::<(), i32>(&store, "outer_allocating_export")?;
+
+    assert_eq!(outer_allocating_export.call(&mut store, ())?, 4);
+    Ok(())
+}
+
+#[test]
+fn exported_division_by_runtime_zero_panics() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun safe_ratio: (a: Int32, b: Int32) -> Int32 = {
+    a / b
+}
+
+export fun wide_remainder: (a: Int64, b: Int64) -> Int64 = {
+    a % b
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let safe_ratio = instance.get_typed_func::<(i32, i32), i32>(&store, "safe_ratio")?;
+    let wide_remainder = instance.get_typed_func::<(i64, i64), i64>(&store, "wide_remainder")?;
+
+    assert_eq!(safe_ratio.call(&mut store, (84, 2))?, 42);
+    assert_eq!(wide_remainder.call(&mut store, (17, 5))?, 2);
+
+    let err = safe_ratio
+        .call(&mut store, (1, 0))
+        .expect_err("zero divisor should panic");
+    assert_eq!(err.as_trap_code(), Some(TrapCode::UnreachableCodeReached));
+
+    let err = wide_remainder
+        .call(&mut store, (1, 0))
+        .expect_err("zero Int64 divisor should panic");
+    assert_eq!(err.as_trap_code(), Some(TrapCode::UnreachableCodeReached));
+    Ok(())
+}
+
+#[test]
+fn constant_float_division_by_zero_follows_ieee() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun overflow: (x: Float64) -> Float64 = {
+    x / 0.0
+}
+
+export fun remainder: (x: Float64) -> Float64 = {
+    x % -0.0
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let overflow = instance.get_typed_func::<f64, f64>(&store, "overflow")?;
+    let remainder = instance.get_typed_func::<f64, f64>(&store, "remainder")?;
+
+    assert_eq!(overflow.call(&mut store, 1.5)?, f64::INFINITY);
+    assert_eq!(overflow.call(&mut store, -1.5)?, f64::NEG_INFINITY);
+    assert!(remainder.call(&mut store, 1.5)?.is_nan());
+    Ok(())
+}
+
+#[test]
+fn assert_eq_and_assert_ne_trap_only_on_failure() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun checked_double: (value: Int32) -> Int32 = {
+    val doubled = value * 2;
+    (doubled, value + value, "doubling should match addition") assert_eq;
+    (doubled, 10, "ten is rejected") assert_ne;
+    doubled
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let checked_double = instance.get_typed_func::<i32, i32>(&store, "checked_double")?;
+
+    assert_eq!(checked_double.call(&mut store, 4)?, 8);
+    let err = checked_double
+        .call(&mut store, 5)
+        .expect_err("assert_ne should trap when the operands are equal");
+    assert_eq!(err.as_trap_code(), Some(TrapCode::UnreachableCodeReached));
+    Ok(())
+}
+
+#[test]
+fn arena_overflow_traps_instead_of_corrupting_memory() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+record Point { x: Int32, y: Int32 }
+
+export fun fill_arena: (count: Int32) -> Int32 = {
+    mut val i = 0
+    mut val total = 0
+    (i < count) while {
+        val p = Point { x: i, y: 1 }
+        total = total + p.y
+        i = i + 1
+    }
+    total
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let fill_arena = instance.get_typed_func::<i32, i32>(&store, "fill_arena")?;
+
+    assert_eq!(fill_arena.call(&mut store, 100)?, 100);
+
+    let err = fill_arena
+        .call(&mut store, 1000)
+        .expect_err("allocating past the arena end should trap");
+    assert_eq!(err.as_trap_code(), Some(TrapCode::UnreachableCodeReached));
+    Ok(())
+}
+
+#[test]
+fn many_arena_functions_get_enough_memory_pages() -> Result<(), Box<dyn std::error::Error>> {
+    // Each function's arenas get their own static 4KiB slot, so sixteen
+    // `with Arena` functions lay arenas out well past the first page.
+    let source: String = (0..16)
+        .map(|index| {
+            format!(
+                r#"
+export fun arena_{index}: () -> Int32 = {{
+    with Arena {{
+        val values = [{index}, 1, 2];
+        values |> list_count
+    }}
+}}
+"#
+            )
+        })
+        .collect();
+
+    let (mut store, instance) = instantiate(&source)?;
+    for index in 0..16 {
+        let arena = instance.get_typed_func::<(), i32>(&store, &format!("arena_{index}"))?;
+        assert_eq!(arena.call(&mut store, ())?, 3, "arena_{index}");
+    }
+    let memory = instance
+        .get_memory(&store, "memory")
+        .expect("module exports its memory");
+    assert!(memory.size(&store) > 1);
+    Ok(())
+}
+
+#[test]
+fn list_take_drop_and_slice_copy_clamped_ranges() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+fun digits: (values: List<Int32>) -> Int32 = {
+    (|total, value| total * 10 + value, 0, values) list_fold
+}
+
+export fun take: (count: Int32) -> Int32 = {
+    with Arena {
+        ([1, 2, 3, 4], count) list_take |> digits
+    }
+}
+
+export fun drop: (count: Int32) -> Int32 = {
+    with Arena {
+        ([1, 2, 3, 4], count) list_drop |> digits
+    }
+}
+
+export fun slice: (start: Int32, end: Int32) -> Int32 = {
+    with Arena {
+        ([1, 2, 3, 4], start, end) list_slice |> digits
+    }
+}
+
+export fun wide_slice_length: (start: Int32, end: Int32) -> Int32 = {
+    with Arena {
+        val values = [10 as Int64, 20 as Int64, 30 as Int64];
+        val middle = (values, start, end) list_slice;
+        middle |> list_length
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let take = instance.get_typed_func::<i32, i32>(&store, "take")?;
+    let drop = instance.get_typed_func::<i32, i32>(&store, "drop")?;
+    let slice = instance.get_typed_func::<(i32, i32), i32>(&store, "slice")?;
+    let wide_slice_length =
+        instance.get_typed_func::<(i32, i32), i32>(&store, "wide_slice_length")?;
+
+    assert_eq!(take.call(&mut store, 2)?, 12);
+    assert_eq!(take.call(&mut store, 9)?, 1234);
+    assert_eq!(take.call(&mut store, -1)?, 0);
+    assert_eq!(drop.call(&mut store, 1)?, 234);
+    assert_eq!(drop.call(&mut store, 5)?, 0);
+    assert_eq!(slice.call(&mut store, (1, 3))?, 23);
+    assert_eq!(slice.call(&mut store, (-2, 2))?, 12);
+    assert_eq!(slice.call(&mut store, (3, 1))?, 0);
+    assert_eq!(wide_slice_length.call(&mut store, (1, 10))?, 2);
+    Ok(())
+}
+
+#[test]
+fn list_contains_and_index_of_find_and_miss_elements() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun position_of: (needle: Int32) -> Int32 = {
+    with Arena {
+        val values = [3, 5, 8, 13];
+        (values, needle) list_index_of match {
+            Some(index) => { index }
+            None => { -1 }
+        }
+    }
+}
+
+export fun has_wide: (needle: Int64) -> Boolean = {
+    with Arena {
+        val values = [10 as Int64, 20 as Int64];
+        (values, needle) list_contains
+    }
+}
+
+export fun has_name: (pick: Int32) -> Boolean = {
+    with Arena {
+        val names = ["ada", "grace"];
+        val needle = "gr" + ((pick == 0) then { "ace" } else { "ant" });
+        (names, needle) list_contains
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let position_of = instance.get_typed_func::<i32, i32>(&store, "position_of")?;
+    let has_wide = instance.get_typed_func::<i64, i32>(&store, "has_wide")?;
+    let has_name = instance.get_typed_func::<i32, i32>(&store, "has_name")?;
+
+    assert_eq!(position_of.call(&mut store, 8)?, 2);
+    assert_eq!(position_of.call(&mut store, 4)?, -1);
+    assert_eq!(has_wide.call(&mut store, 20)?, 1);
+    assert_eq!(has_wide.call(&mut store, 30)?, 0);
+    assert_eq!(has_name.call(&mut store, 0)?, 1);
+    assert_eq!(has_name.call(&mut store, 1)?, 0);
+    Ok(())
+}
+
+#[test]
+fn generic_min_and_max_compare_by_operand_type() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun wide_max: (a: Int64, b: Int64) -> Int64 = {
+    (a, b) max
+}
+
+export fun float_min: (a: Float64, b: Float64) -> Float64 = {
+    (a, b) min
+}
+
+export fun int_max: (a: Int32, b: Int32) -> Int32 = {
+    (a, b) max
+}
+
+export fun first_name_length: (pick: Int32) -> Int32 = {
+    val left = (pick == 0) then { "grace" } else { "gr" };
+    (left, "grapefruit") min |> string_length
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let wide_max = instance.get_typed_func::<(i64, i64), i64>(&store, "wide_max")?;
+    let float_min = instance.get_typed_func::<(f64, f64), f64>(&store, "float_min")?;
+    let int_max = instance.get_typed_func::<(i32, i32), i32>(&store, "int_max")?;
+    let first_name_length = instance.get_typed_func::<i32, i32>(&store, "first_name_length")?;
+
+    assert_eq!(wide_max.call(&mut store, (1 << 40, 7))?, 1 << 40);
+    assert_eq!(float_min.call(&mut store, (2.5, -1.5))?, -1.5);
+    assert_eq!(int_max.call(&mut store, (-3, -9))?, -3);
+    // "grace" < "grapefruit" by content; "gr" orders first as a prefix
+    assert_eq!(first_name_length.call(&mut store, 0)?, 5);
+    assert_eq!(first_name_length.call(&mut store, 1)?, 2);
+    Ok(())
+}
+
+#[test]
+fn shadowing_binding_keeps_outer_value_intact() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun inner_shadow: () -> Int32 = {
+    val x = 40;
+    val y = {
+        val x = 2;
+        x
+    };
+    x + y
+}
+
+export fun rebind_from_self: (n: Int32) -> Int32 = {
+    mut val x = n;
+    val x = x * 10;
+    x + 1
+}
+
+export fun shadow_in_operand: () -> Int32 = {
+    val x = 1;
+    ({ val x = 20; x }) + x
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let inner_shadow = instance.get_typed_func::<(), i32>(&store, "inner_shadow")?;
+    let rebind_from_self = instance.get_typed_func::<i32, i32>(&store, "rebind_from_self")?;
+    let shadow_in_operand = instance.get_typed_func::<(), i32>(&store, "shadow_in_operand")?;
+
+    assert_eq!(inner_shadow.call(&mut store, ())?, 42);
+    assert_eq!(rebind_from_self.call(&mut store, 4)?, 41);
+    assert_eq!(shadow_in_operand.call(&mut store, ())?, 21);
+    Ok(())
+}
+
+#[test]
+fn unnamed_record_destructuring_moves_every_field() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+record User { id: Int32, name: String }
+record Point { x: Int32, y: Int32, z: Int32 }
+
+fun describe: (user: User) -> Int32 = {
+    val { id, name } = user;
+    id + (name |> string_length)
+}
+
+export fun user_score: () -> Int32 = {
+    User { id: 40, name: "ab" } |> describe
+}
+
+export fun point_sum: (x: Int32) -> Int32 = {
+    val p = Point { x: x, y: 20, z: 300 };
+    val { x: first, ...rest } = p;
+    first + rest.y + rest.z
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let user_score = instance.get_typed_func::<(), i32>(&store, "user_score")?;
+    let point_sum = instance.get_typed_func::<i32, i32>(&store, "point_sum")?;
+
+    assert_eq!(user_score.call(&mut store, ())?, 42);
+    assert_eq!(point_sum.call(&mut store, 1)?, 321);
+    Ok(())
+}
+
+#[test]
+fn record_field_defaults_fill_omitted_fields_and_yield_to_overrides(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+record Config {
+    host: String = "localhost"
+    port: Int32 = 8080
+    retries: Int32 = 2 * 3
+}
+
+export fun default_port: () -> Int32 = {
+    val config = Config { host: "example.org" };
+    config.port + config.retries
+}
+
+export fun overridden_port: (port: Int32) -> Int32 = {
+    val config = Config { port: port };
+    config.port + (config.host |> string_length)
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let default_port = instance.get_typed_func::<(), i32>(&store, "default_port")?;
+    let overridden_port = instance.get_typed_func::<i32, i32>(&store, "overridden_port")?;
+
+    assert_eq!(default_port.call(&mut store, ())?, 8086);
+    assert_eq!(overridden_port.call(&mut store, 9090)?, 9099);
+    Ok(())
+}
+
+#[test]
+fn map_insert_then_get_finds_keys_across_growth_and_removal(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+fun fill: (map: Map<Int32, Int32>, n: Int32) -> Map<Int32, Int32> = {
+    (n == 0) then {
+        map
+    } else {
+        val grown = (map, n, n * 10) map_insert;
+        (grown, n - 1) fill
+    }
+}
+
+export fun int_lookup: (key: Int32) -> Int32 = {
+    val empty: Map<Int32, Int32> = () map_new;
+    val filled = (empty, 40) fill;
+    val without_seven = (filled, 7) map_remove;
+    val found = (without_seven, key) map_get;
+    found match {
+        Some(value) => { value }
+        None => { -1 }
+    }
+}
+
+export fun string_lookup: () -> Int32 = {
+    val empty: Map<String, Int32> = () map_new;
+    val one = (empty, "alpha", 1) map_insert;
+    val two = (one, "beta", 2) map_insert;
+    val replaced = (two, "alpha", 3) map_insert;
+    val found = (replaced, "alp" + "ha") map_get;
+    found match {
+        Some(value) => { value }
+        None => { -1 }
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let int_lookup = instance.get_typed_func::<i32, i32>(&store, "int_lookup")?;
+    let string_lookup = instance.get_typed_func::<(), i32>(&store, "string_lookup")?;
+
+    assert_eq!(int_lookup.call(&mut store, 1)?, 10);
+    assert_eq!(int_lookup.call(&mut store, 40)?, 400);
+    assert_eq!(int_lookup.call(&mut store, 7)?, -1);
+    assert_eq!(int_lookup.call(&mut store, 41)?, -1);
+    assert_eq!(string_lookup.call(&mut store, ())?, 3);
+    Ok(())
+}
+
+#[test]
+fn try_operator_unwraps_some_and_returns_none_early() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+fun positive: (n: Int32) -> Option<Int32> = {
+    (n > 0) then { Some(n) } else { None }
+}
+
+fun halve: (n: Int32) -> Result<Int32, Int32> = {
+    (n % 2 == 0) then { Ok(n / 2) } else { Err(n) }
+}
+
+fun doubled_positive: (n: Int32) -> Option<Int32> = {
+    val value = (n) positive?;
+    Some(value * 2)
+}
+
+fun quarter: (n: Int32) -> Result<Int32, Int32> = {
+    val half = (n) halve?;
+    Ok((half) halve? + 100)
+}
+
+export fun try_option: (n: Int32) -> Int32 = {
+    (n) doubled_positive match {
+        Some(value) => { value }
+        None => { -1 }
+    }
+}
+
+export fun try_result: (n: Int32) -> Int32 = {
+    (n) quarter match {
+        Ok(value) => { value }
+        Err(odd) => { 0 - odd }
+    }
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let try_option = instance.get_typed_func::<i32, i32>(&store, "try_option")?;
+    let try_result = instance.get_typed_func::<i32, i32>(&store, "try_result")?;
+
+    assert_eq!(try_option.call(&mut store, 21)?, 42);
+    assert_eq!(try_option.call(&mut store, -3)?, -1);
+    assert_eq!(try_result.call(&mut store, 12)?, 103);
+    assert_eq!(try_result.call(&mut store, 6)?, -3);
+    assert_eq!(try_result.call(&mut store, 7)?, -7);
+    Ok(())
+}
+
+#[test]
+fn casts_truncate_toward_zero_and_convert_chars() -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun three: () -> Float64 = {
+    3 as Float64
+}
+
+export fun truncate: (value: Float64) -> Int32 = {
+    value as Int32
+}
+
+export fun code_point: (value: Char) -> Int32 = {
+    value as Int32
+}
+
+export fun from_code_point: (value: Int32) -> Char = {
+    value as Char
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let three = instance.get_typed_func::<(), f64>(&store, "three")?;
+    let truncate = instance.get_typed_func::<f64, i32>(&store, "truncate")?;
+    let code_point = instance.get_typed_func::<i32, i32>(&store, "code_point")?;
+    let from_code_point = instance.get_typed_func::<i32, i32>(&store, "from_code_point")?;
+
+    assert_eq!(three.call(&mut store, ())?, 3.0);
+    assert_eq!(truncate.call(&mut store, 3.9)?, 3);
+    assert_eq!(truncate.call(&mut store, -3.9)?, -3);
+    assert_eq!(code_point.call(&mut store, 'A' as i32)?, 65);
+    assert_eq!(from_code_point.call(&mut store, 97)?, 'a' as i32);
+    let err = from_code_point
+        .call(&mut store, 0xD800)
+        .expect_err("a surrogate is not a Char");
+    assert_eq!(err.as_trap_code(), Some(TrapCode::UnreachableCodeReached));
+    Ok(())
+}
+
+#[test]
+fn range_patterns_match_inside_and_respect_inclusive_bounds(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = r#"
+export fun digit_class: (n: Int32) -> Int32 = {
+    n match {
+        0..=9 => { 1 }
+        10..20 => { 2 }
+        _ => { 0 }
+    }
+}
+
+fun signed_class: (n: Option<Int32>) -> Int32 = {
+    n match {
+        Some(-5..=-1) => { -1 }
+        Some(_) => { 1 }
+        None => { 0 }
+    }
+}
+
+export fun classify_some: (n: Int32) -> Int32 = {
+    (Some(n)) signed_class
+}
+"#;
+
+    let (mut store, instance) = instantiate(source)?;
+    let digit_class = instance.get_typed_func::<i32, i32>(&store, "digit_class")?;
+    let classify_some = instance.get_typed_func::<i32, i32>(&store, "classify_some")?;
+
+    assert_eq!(digit_class.call(&mut store, 5)?, 1);
+    assert_eq!(digit_class.call(&mut store, 9)?, 1);
+    assert_eq!(digit_class.call(&mut store, 10)?, 2);
+    assert_eq!(digit_class.call(&mut store, 19)?, 2);
+    assert_eq!(digit_class.call(&mut store, 20)?, 0);
+    assert_eq!(digit_class.call(&mut store, -1)?, 0);
+    assert_eq!(classify_some.call(&mut store, -5)?, -1);
+    assert_eq!(classify_some.call(&mut store, -1)?, -1);
+    assert_eq!(classify_some.call(&mut store, 0)?, 1);
+    assert_eq!(classify_some.call(&mut store, -6)?, 1);
     Ok(())
 }
@@ -1,4 +1,4 @@
-use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+use restrict_lang::{parse_program, CodeGenError, Target, TypeChecker, WasmCodeGen};
 
 fn compile_to_wat(source: &str) -> Result<String, String> {
     let (remaining, ast) = parse_program(source).map_err(|e| format!("Parse error: {:?}", e))?;
@@ -377,6 +377,121 @@ fn practical_examples_generate_valid_wat() {
     );
 }
 
+#[test]
+fn int_to_string_builds_an_arena_string_instead_of_printing() {
+    let wat = assert_valid_wat(
+        "int_to_string",
+        r#"
+fun describe: (value: Int32) -> String = {
+    "value: " + (value |> int_to_string)
+}
+
+fun main: () -> () = {
+    (7) describe |> println
+}
+"#,
+    );
+
+    let start = wat
+        .find("(func $int_to_string")
+        .expect("int_to_string should be emitted");
+    let body = &wat[start..];
+    let body = &body[..body[1..].find("(func ").map_or(body.len(), |end| end + 1)];
+    assert!(body.contains("(result i32)"), "{body}");
+    assert!(body.contains("call $allocate"), "{body}");
+    assert!(body.contains("memory.copy"), "{body}");
+    assert!(!body.contains("fd_write"), "{body}");
+    assert!(wat.contains("call $int_to_string"));
+}
+
+#[test]
+fn assert_eq_compares_operands_and_panics_with_the_message() {
+    let wat = assert_valid_wat(
+        "assert_eq",
+        r#"
+fun check: (count: Int32, label: String) -> () = {
+    (count, 3, "count should be three") assert_eq;
+    (label, "other", "label should not be other") assert_ne
+}
+
+fun main: () -> () = {
+    (3, "name") check
+}
+"#,
+    );
+
+    let start = wat.find("(func $check ").expect("check should be emitted");
+    let body = &wat[start..];
+    let body = &body[..body[1..].find("(func ").map_or(body.len(), |end| end + 1)];
+    let int_compare = body
+        .find("call $assert_compare_int32")
+        .expect("Int32 operands use the Int32 helper");
+    let string_compare = body
+        .find("call $assert_compare_string")
+        .expect("String operands use the String helper");
+    assert!(int_compare < string_compare, "{body}");
+
+    let helper_start = wat
+        .find("(func $assert_compare_string")
+        .expect("the String helper should be emitted");
+    let helper_body = &wat[helper_start..];
+    let helper_body = &helper_body[..helper_body[1..]
+        .find("(func ")
+        .map_or(helper_body.len(), |end| end + 1)];
+    assert!(helper_body.contains("call $string_eq"), "{helper_body}");
+    assert!(helper_body.contains("call $assert_failed"), "{helper_body}");
+
+    let failed_start = wat
+        .find("(func $assert_failed")
+        .expect("assert_failed should be emitted");
+    let failed_body = &wat[failed_start..];
+    let failed_body = &failed_body[..failed_body[1..]
+        .find("(func ")
+        .map_or(failed_body.len(), |end| end + 1)];
+    assert!(failed_body.contains("call $string_concat"), "{failed_body}");
+    assert!(failed_body.contains("call $panic"), "{failed_body}");
+}
+
+#[test]
+fn panic_writes_message_to_stderr_and_exits() {
+    let wat = assert_valid_wat(
+        "panic",
+        r#"
+fun main: () -> () = {
+    "boom" |> panic
+}
+"#,
+    );
+
+    let function_body = |name: &str| {
+        let start = wat
+            .find(&format!("(func ${name}"))
+            .unwrap_or_else(|| panic!("{name} should be emitted:\n{wat}"));
+        let body = &wat[start..];
+        &body[..body[1..].find("(func ").map_or(body.len(), |end| end + 1)]
+    };
+
+    assert!(function_body("main").contains("call $panic"), "{wat}");
+
+    let panic_body = function_body("panic");
+    let write = panic_body
+        .find("call $eprintln")
+        .expect("panic should print its message");
+    let exit = panic_body
+        .find("i32.const 1\n    call $proc_exit")
+        .expect("panic should exit with status 1");
+    assert!(write < exit, "{panic_body}");
+
+    let stderr_body = function_body("eprintln");
+    let fd = stderr_body
+        .find("i32.const 2\n    i32.const 0")
+        .expect("eprintln should write to fd 2");
+    let fd_write = stderr_body
+        .find("call $fd_write")
+        .expect("eprintln should call fd_write");
+    assert!(fd < fd_write, "{stderr_body}");
+}
+
 #[test]
 fn source_imports_are_rejected_before_type_checking() {
     let source = r#"
@@ -986,7 +1101,7 @@ fun main: () -> Int32 = {
 
     let err = compile_to_wat(source).expect_err("exported generic function should need an ABI");
     assert!(
-        err.contains("Exported generic function"),
+        err.contains("un-monomorphized type parameter"),
         "error should explain exported generic ABI limitation, got: {err}"
     );
 }
@@ -1070,6 +1185,49 @@ fun main: () -> Int32 = {
     assert_valid_wat("std_math_functions", source);
 }
 
+#[test]
+fn overflow_math_functions_generate_clamped_wat() {
+    let source = r#"
+fun main: () -> Int32 = {
+    val a = (1, 2) wrapping_add;
+    val b = (3, 4) wrapping_mul;
+    val c = (5, 6) saturating_add;
+    val d = (7, 8) saturating_sub;
+
+    a + b + c + d
+}
+"#;
+
+    let wat = assert_valid_wat("overflow_math_functions", source);
+    let function_body = |name: &str| {
+        let start = wat
+            .find(&format!("(func ${name} "))
+            .unwrap_or_else(|| panic!("missing ${name} in WAT:\n{wat}"));
+        let rest = &wat[start + 1..];
+        let end = rest.find("(func $").unwrap_or(rest.len());
+        rest[..end].to_string()
+    };
+
+    assert!(function_body("wrapping_add").contains("i32.add"));
+    assert!(function_body("wrapping_mul").contains("i32.mul"));
+    for (name, op) in [("saturating_add", "i64.add"), ("saturating_sub", "i64.sub")] {
+        let body = function_body(name);
+        for expected in [
+            op,
+            "i64.const 2147483647",
+            "i64.gt_s",
+            "i64.const -2147483648",
+            "i64.lt_s",
+            "i32.wrap_i64",
+        ] {
+            assert!(
+                body.contains(expected),
+                "${name} should contain `{expected}`:\n{body}"
+            );
+        }
+    }
+}
+
 #[test]
 fn float_math_functions_generate_valid_wat() {
     let source = r#"
@@ -1383,6 +1541,16 @@ fun main: () -> Option<Float64> = {
 "#,
     );
 
+    assert_valid_wat(
+        "float_list_sort",
+        r#"
+fun main: () -> List<Float64> = {
+    val readings = [2.5, 0.5, 1.5];
+    (|a, b| a < b then { -1 } else { 1 }, readings) list_sort
+}
+"#,
+    );
+
     assert_valid_wat(
         "float_option_filter",
         r#"
@@ -1404,6 +1572,25 @@ fun main: () -> Float64 = {
     );
 }
 
+#[test]
+fn list_sort_calls_comparator_indirectly() {
+    let wat = assert_valid_wat(
+        "int_list_sort",
+        r#"
+fun main: () -> List<Int32> = {
+    val numbers = [3, 1, 2];
+    (|a, b| a - b, numbers) list_sort
+}
+"#,
+    );
+
+    assert!(wat.contains(";; list_sort(compare, list)"), "{wat}");
+    assert!(
+        wat.contains("call_indirect (type $closure_call_2)"),
+        "comparator should be invoked through the closure table:\n{wat}"
+    );
+}
+
 #[test]
 fn named_function_iterators_generate_valid_wat() {
     let source = r#"
@@ -2178,6 +2365,74 @@ fun main: () -> Int32 = {
     assert_valid_wat("arena_allocator_bounds_checked", source);
 }
 
+#[test]
+fn memory_pages_size_linear_memory_and_arena_end() {
+    let source = r#"
+fun main: () -> Int32 = {
+    val readings = [1, 2, 3];
+    readings |> list_count
+}
+"#;
+
+    let default_wat = compile_to_wat(source).expect("default memory layout should compile");
+    assert!(default_wat.contains("(memory 1)"), "{default_wat}");
+    assert!(
+        default_wat.contains(";; Arena bounds check against the end stored in the header"),
+        "arena_alloc should compare against the header's end address:\n{default_wat}"
+    );
+    assert!(default_wat.contains("call $arena_oom"), "{default_wat}");
+
+    let mut codegen = WasmCodeGen::new();
+    codegen.memory_pages(4);
+    let wat = codegen
+        .generate(&parse_source(source))
+        .expect("larger memory layout should compile");
+    assert!(wat.contains("(memory 4)"), "{wat}");
+    assert!(
+        wat.contains("    ;; Store end address at offset 8\n    local.get $start\n    i32.const 8\n    i32.add\n    local.get $start\n    i32.const 16384\n"),
+        "four pages should give each arena 16KiB:\n{wat}"
+    );
+    let wasm = wat::parse_str(&wat).expect("larger memory layout should be valid WAT");
+    wasmparser::Validator::new()
+        .validate_all(&wasm)
+        .expect("larger memory layout should validate");
+}
+
+#[test]
+fn freestanding_target_emits_no_wasi_imports() {
+    let source = r#"
+fun main: () -> Int32 = {
+    val readings = [1, 2, 3];
+    val total = readings |> list_count;
+    (total > 0, "empty readings") assert;
+    total
+}
+"#;
+
+    let mut codegen = WasmCodeGen::new();
+    codegen.target(Target::Freestanding);
+    let wat = codegen
+        .generate(&parse_source(source))
+        .expect("freestanding program without I/O should compile");
+    assert!(!wat.contains("wasi_snapshot_preview1"), "{wat}");
+    let wasm = wat::parse_str(&wat).expect("freestanding module should be valid WAT");
+    wasmparser::Validator::new()
+        .validate_all(&wasm)
+        .expect("freestanding module should validate");
+
+    let mut codegen = WasmCodeGen::new();
+    codegen.target(Target::Freestanding);
+    let err = codegen
+        .generate(&parse_source(
+            "fun main: () -> Int32 = {\n    \"hi\" |> println;\n    0\n}\n",
+        ))
+        .expect_err("println needs fd_write");
+    assert!(
+        err.to_string().contains("'println' needs WASI imports"),
+        "{err}"
+    );
+}
+
 #[test]
 fn logical_boolean_ops_generate_valid_wat() {
     let source = r#"
@@ -2854,3 +3109,44 @@ fun main: () -> Int64 = {
     assert!(wat.contains("call $list_get_i64"));
     assert!(wat.contains("call $list_head_i64"));
 }
+
+#[test]
+fn verify_passes_well_formed_output_and_catches_injected_wat() {
+    let source =
+        "fun main: () -> Int32 = {\n    val values = [1, 2, 3];\n    values |> list_count\n}\n";
+
+    let mut codegen = WasmCodeGen::new();
+    codegen.verify(true);
+    codegen
+        .generate(&parse_source(source))
+        .expect("verified output should be valid");
+
+    // Does not assemble: the error quotes the offending line.
+    let mut codegen = WasmCodeGen::new();
+    codegen.verify(true);
+    codegen.inject_wat("  (func $broken\n    i32.bogus\n  )\n");
+    let err = codegen
+        .generate(&parse_source(source))
+        .expect_err("unknown instruction should fail verification");
+    assert!(matches!(err, CodeGenError::InvalidWat(_)), "{err:?}");
+    assert!(err.to_string().contains("i32.bogus"), "{err}");
+
+    // Assembles but does not validate: the error names the function line.
+    let mut codegen = WasmCodeGen::new();
+    codegen.verify(true);
+    codegen.inject_wat("  (func $broken (result i32)\n    f32.const 1\n  )\n");
+    let err = codegen
+        .generate(&parse_source(source))
+        .expect_err("mismatched result type should fail verification");
+    let message = err.to_string();
+    assert!(message.contains("type mismatch"), "{message}");
+    assert!(message.contains("(func $broken (result i32)"), "{message}");
+
+    // Without --verify the same output is returned unchecked.
+    let mut codegen = WasmCodeGen::new();
+    codegen.inject_wat("  (func $broken (result i32)\n    f32.const 1\n  )\n");
+    let wat = codegen
+        .generate(&parse_source(source))
+        .expect("unverified output is returned as is");
+    assert!(wat.contains("(func $broken"));
+}
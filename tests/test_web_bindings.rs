@@ -0,0 +1,66 @@
+#![cfg(target_arch = "wasm32")]
+
+use restrict_lang::web::{check, compile_to_wat, tokens, CheckResult, TokensResult, WatResult};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const GOOD_PROGRAM: &str = "fun main: () -> Int32 = {\n    42\n}\n";
+const BAD_PROGRAM: &str = "fun main: () -> Int32 = {\n    true\n}\n";
+
+#[wasm_bindgen_test]
+fn compile_to_wat_returns_wat_for_a_good_program() {
+    let result: WatResult = serde_wasm_bindgen::from_value(compile_to_wat(GOOD_PROGRAM))
+        .expect("compile_to_wat should return a structured result");
+
+    assert!(result.ok);
+    assert!(result.wat.expect("WAT output").contains("(module"));
+    assert!(result.diagnostics.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn compile_to_wat_returns_diagnostics_for_a_bad_program() {
+    let result: WatResult = serde_wasm_bindgen::from_value(compile_to_wat(BAD_PROGRAM))
+        .expect("compile_to_wat should return a structured result");
+
+    assert!(!result.ok);
+    assert!(result.wat.is_none());
+    assert_eq!(result.diagnostics[0].stage, "type");
+}
+
+#[wasm_bindgen_test]
+fn check_returns_diagnostics_only() {
+    let good: CheckResult = serde_wasm_bindgen::from_value(check(GOOD_PROGRAM))
+        .expect("check should return a structured result");
+    let bad: CheckResult = serde_wasm_bindgen::from_value(check(BAD_PROGRAM))
+        .expect("check should return a structured result");
+
+    assert!(good.ok);
+    assert!(good.diagnostics.is_empty());
+    assert!(!bad.ok);
+    assert_eq!(bad.diagnostics[0].severity, "error");
+}
+
+#[wasm_bindgen_test]
+fn tokens_returns_spanned_tokens_and_lex_diagnostics() {
+    let good: TokensResult = serde_wasm_bindgen::from_value(tokens("val x = 1"))
+        .expect("tokens should return a structured result");
+    let bad: TokensResult = serde_wasm_bindgen::from_value(tokens("val x = $"))
+        .expect("tokens should return a structured result");
+
+    assert!(good.ok);
+    assert_eq!(good.tokens.len(), 4);
+    assert!(!bad.ok);
+    assert_eq!(bad.diagnostics[0].stage, "lex");
+}
+
+#[wasm_bindgen_test]
+fn check_positions_lex_errors_in_utf16_columns() {
+    let result: CheckResult = serde_wasm_bindgen::from_value(check("val s = \"日本😀\" $"))
+        .expect("check should return a structured result");
+    let diagnostic = &result.diagnostics[0];
+
+    assert_eq!((diagnostic.line, diagnostic.column), (Some(1), Some(16)));
+    assert_eq!(
+        (diagnostic.end_line, diagnostic.end_column),
+        (Some(1), Some(17))
+    );
+}
@@ -0,0 +1,120 @@
+use restrict_lang::ast::{ExprKind, Pattern, Stmt, TopDecl};
+use restrict_lang::{parse_program, TypeChecker, WasmCodeGen};
+
+fn compile_to_wat(source: &str) -> Result<String, String> {
+    let (remaining, ast) = parse_program(source).map_err(|e| format!("Parse error: {:?}", e))?;
+    if !remaining.trim().is_empty() {
+        return Err(format!("Unparsed input remaining: {:?}", remaining));
+    }
+
+    let mut type_checker = TypeChecker::new();
+    type_checker
+        .check_program(&ast)
+        .map_err(|e| format!("Type error: {}", e))?;
+
+    let mut codegen = WasmCodeGen::new();
+    codegen
+        .generate(&ast)
+        .map_err(|e| format!("Codegen error: {}", e))
+}
+
+const COUNTER: &str = r#"
+    fun next_below: (n: Int32, limit: Int32) -> Option<Int32> = {
+        n < limit then { Some(n) } else { None }
+    }
+"#;
+
+#[test]
+fn while_let_parses_pattern_between_while_and_body() {
+    let source = r#"
+        fun drain: (limit: Int32) -> Int32 = {
+            mut val i = 0;
+            (i, limit) next_below while Some(x) {
+                i = i + 1;
+            }
+            i
+        }
+    "#;
+
+    let (_, program) = parse_program(source).unwrap();
+    let TopDecl::Function(func) = &program.declarations[0] else {
+        panic!("expected a function");
+    };
+    let Stmt::Expr(expr) = &func.body.statements[1] else {
+        panic!("expected the loop statement");
+    };
+    let ExprKind::WhileLet(while_let) = &expr.kind else {
+        panic!("expected a while-let loop, got {:?}", expr.kind);
+    };
+    assert!(
+        matches!(&while_let.pattern, Pattern::Some(inner) if **inner == Pattern::Ident("x".to_string()))
+    );
+    assert!(matches!(while_let.expr.kind, ExprKind::Call(_)));
+    assert_eq!(while_let.body.statements.len(), 1);
+}
+
+#[test]
+fn while_let_exits_without_a_none_arm() {
+    let source = format!(
+        "{COUNTER}{}",
+        r#"
+        fun drain: (limit: Int32) -> Int32 = {
+            mut val i = 0;
+            mut val total = 0;
+            (i, limit) next_below while Some(x) {
+                total = total + x;
+                i = i + 1;
+            }
+            total
+        }
+    "#
+    );
+
+    let wat = compile_to_wat(&source).unwrap();
+
+    assert!(wat.contains("(loop $while_let_loop"));
+    assert!(wat.contains("br_if $while_let_exit"));
+    wat::parse_str(&wat).expect("while-let should produce valid WAT");
+}
+
+#[test]
+fn while_let_requires_option_or_result() {
+    let source = r#"
+        fun spin: (n: Int32) -> Int32 = {
+            n while Some(x) {
+                x;
+            }
+            n
+        }
+    "#;
+
+    let err = compile_to_wat(source).expect_err("an Int32 loop value should be rejected");
+    assert!(
+        err.contains("Option or Result"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn while_let_bindings_are_scoped_to_the_body() {
+    let source = format!(
+        "{COUNTER}{}",
+        r#"
+        fun last: (limit: Int32) -> Int32 = {
+            mut val i = 0;
+            (i, limit) next_below while Some(x) {
+                i = i + 1;
+            }
+            x
+        }
+    "#
+    );
+
+    let err = compile_to_wat(&source).expect_err("x is only bound inside the loop");
+    assert!(
+        err.contains("Undefined variable"),
+        "unexpected error: {}",
+        err
+    );
+}
@@ -171,7 +171,7 @@ impl Cage {
     }
 }
 
-fn update_hash_field(hasher: &mut Sha256, label: &str, value: &[u8]) {
+pub(crate) fn update_hash_field(hasher: &mut Sha256, label: &str, value: &[u8]) {
     hasher.update(label.as_bytes());
     hasher.update([0]);
     hasher.update((value.len() as u64).to_le_bytes());
@@ -1,5 +1,10 @@
-use super::{load_manifest, print_info, print_success, save_manifest};
+use super::{
+    find_project_root, load_manifest, print_info, print_success, save_manifest, update_lockfile,
+};
 use crate::manifest::Dependency;
+use crate::registry::fetch_wit;
+use crate::vault::{cache_wit, checkout_git, GitReference};
+use crate::wit::parse_wit;
 use anyhow::{bail, Result};
 use semver::VersionReq;
 
@@ -7,6 +12,7 @@ pub async fn add_dependency(
     dep_spec: &str,
     path: Option<String>,
     git: Option<String>,
+    reference: GitReference,
     wasm: Option<String>,
     wit: Option<String>,
 ) -> Result<()> {
@@ -19,7 +25,7 @@ pub async fn add_dependency(
         (name, Dependency::Local { path })
     } else if let Some(git) = git {
         // Git dependency
-        parse_git_dep(dep_spec, git)?
+        parse_git_dep(dep_spec, git, reference)?
     } else if let (Some(wasm), Some(wit)) = (wasm, wit) {
         // Foreign WASM dependency
         let name = extract_name_from_spec(dep_spec)?;
@@ -34,9 +40,28 @@ pub async fn add_dependency(
         bail!("Dependency '{}' already exists", name);
     }
 
+    // Fetch and validate the WIT interface before recording a foreign dependency
+    if let Dependency::Foreign { wit, .. } = &dependency {
+        validate_foreign_wit(&name, wit).await?;
+    }
+
+    // Check out a Git dependency before recording it, so the lockfile pins
+    // the resolved commit
+    if let Dependency::Git {
+        git,
+        branch,
+        tag,
+        rev,
+    } = &dependency
+    {
+        let reference = GitReference::new(branch.as_ref(), tag.as_ref(), rev.as_ref());
+        checkout_git_dependency(&name, git, &reference)?;
+    }
+
     // Add dependency
     manifest.add_dependency(name.clone(), dependency);
     save_manifest(&manifest)?;
+    update_lockfile(&manifest)?;
 
     print_success(&format!("Added dependency '{}'", name));
     print_info("Run 'warder build' to download and build dependencies");
@@ -52,6 +77,7 @@ pub fn remove_dependency(name: &str) -> Result<()> {
     }
 
     save_manifest(&manifest)?;
+    update_lockfile(&manifest)?;
 
     print_success(&format!("Removed dependency '{}'", name));
     print_info("Run 'warder build' to update the project");
@@ -59,23 +85,77 @@ pub fn remove_dependency(name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Fetch the WIT interface at `location`, reject it if it does not parse, and
+/// cache it for lockfile resolution.
+async fn validate_foreign_wit(name: &str, location: &str) -> Result<()> {
+    let root = find_project_root()?;
+    let content = fetch_wit(&root, location).await?;
+    let document = match parse_wit(&content) {
+        Ok(document) => document,
+        Err(err) => bail!("Invalid WIT interface '{}': {}", location, err),
+    };
+    cache_wit(&root, name, &content)?;
+
+    print_info(&format!(
+        "Validated WIT interface '{}' ({} exported function{})",
+        location,
+        document.exports.len(),
+        if document.exports.len() == 1 { "" } else { "s" }
+    ));
+    Ok(())
+}
+
+/// Clone or fetch `url` into the Git cache and check out `reference`.
+fn checkout_git_dependency(name: &str, url: &str, reference: &GitReference) -> Result<()> {
+    let root = find_project_root()?;
+    let checkout = checkout_git(&root, name, url, reference)?;
+    if checkout.reused {
+        print_info(&format!("Fetched {} into the cached clone", url));
+    }
+    print_info(&format!(
+        "Checked out {} of {} at {}",
+        reference, url, checkout.commit
+    ));
+    Ok(())
+}
+
 fn extract_name_from_spec(spec: &str) -> Result<String> {
     // Extract name from spec like "name@version" or just "name"
     Ok(spec.split('@').next().unwrap_or(spec).to_string())
 }
 
-fn parse_git_dep(spec: &str, git: String) -> Result<(String, Dependency)> {
-    let (name, tag) = if let Some((name, tag)) = spec.split_once('@') {
+fn parse_git_dep(spec: &str, git: String, reference: GitReference) -> Result<(String, Dependency)> {
+    let (name, reference) = if let Some((name, tag)) = spec.split_once('@') {
         if tag.is_empty() {
             bail!("Invalid git tag specification '{}'. Use name@tag", spec);
         }
+        if reference != GitReference::DefaultBranch {
+            bail!(
+                "'{}' already names a tag; drop --branch, --tag, or --rev",
+                spec
+            );
+        }
 
-        (name.to_string(), Some(tag.to_string()))
+        (name.to_string(), GitReference::Tag(tag.to_string()))
     } else {
-        (spec.to_string(), None)
+        (spec.to_string(), reference)
     };
 
-    Ok((name, Dependency::Git { git, tag }))
+    let (branch, tag, rev) = match reference {
+        GitReference::Branch(branch) => (Some(branch), None, None),
+        GitReference::Tag(tag) => (None, Some(tag), None),
+        GitReference::Rev(rev) => (None, None, Some(rev)),
+        GitReference::DefaultBranch => (None, None, None),
+    };
+    Ok((
+        name,
+        Dependency::Git {
+            git,
+            branch,
+            tag,
+            rev,
+        },
+    ))
 }
 
 fn parse_registry_dep(spec: &str) -> Result<(String, Dependency)> {
@@ -115,12 +195,16 @@ mod tests {
 
     #[test]
     fn parse_git_dep_reads_tag_from_spec() {
-        let (name, dependency) =
-            parse_git_dep("json@v1.2.3", "https://example.com/json.git".to_string()).unwrap();
+        let (name, dependency) = parse_git_dep(
+            "json@v1.2.3",
+            "https://example.com/json.git".to_string(),
+            GitReference::DefaultBranch,
+        )
+        .unwrap();
 
         assert_eq!(name, "json");
         match dependency {
-            Dependency::Git { git, tag } => {
+            Dependency::Git { git, tag, .. } => {
                 assert_eq!(git, "https://example.com/json.git");
                 assert_eq!(tag.as_deref(), Some("v1.2.3"));
             }
@@ -130,13 +214,40 @@ mod tests {
 
     #[test]
     fn parse_git_dep_without_tag_leaves_tag_empty() {
-        let (name, dependency) =
-            parse_git_dep("json", "https://example.com/json.git".to_string()).unwrap();
+        let (name, dependency) = parse_git_dep(
+            "json",
+            "https://example.com/json.git".to_string(),
+            GitReference::DefaultBranch,
+        )
+        .unwrap();
 
         assert_eq!(name, "json");
         match dependency {
-            Dependency::Git { git, tag } => {
+            Dependency::Git {
+                git,
+                branch,
+                tag,
+                rev,
+            } => {
                 assert_eq!(git, "https://example.com/json.git");
+                assert_eq!((branch, tag, rev), (None, None, None));
+            }
+            _ => panic!("expected git dependency"),
+        }
+    }
+
+    #[test]
+    fn parse_git_dep_records_requested_branch() {
+        let (_, dependency) = parse_git_dep(
+            "json",
+            "https://example.com/json.git".to_string(),
+            GitReference::Branch("stable".to_string()),
+        )
+        .unwrap();
+
+        match dependency {
+            Dependency::Git { branch, tag, .. } => {
+                assert_eq!(branch.as_deref(), Some("stable"));
                 assert_eq!(tag, None);
             }
             _ => panic!("expected git dependency"),
@@ -145,8 +256,25 @@ mod tests {
 
     #[test]
     fn parse_git_dep_rejects_empty_tag() {
-        let err = parse_git_dep("json@", "https://example.com/json.git".to_string()).unwrap_err();
+        let err = parse_git_dep(
+            "json@",
+            "https://example.com/json.git".to_string(),
+            GitReference::DefaultBranch,
+        )
+        .unwrap_err();
 
         assert!(err.to_string().contains("Invalid git tag specification"));
     }
+
+    #[test]
+    fn parse_git_dep_rejects_spec_tag_with_ref_flag() {
+        let err = parse_git_dep(
+            "json@v1.2.3",
+            "https://example.com/json.git".to_string(),
+            GitReference::Rev("abc123".to_string()),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("already names a tag"));
+    }
 }
@@ -0,0 +1,213 @@
+use super::test::{
+    call_export, compile_test_module, instantiate_export, load_test_program, InvokeResult,
+};
+use super::{find_project_root, print_error, print_info, print_success};
+use anyhow::Result;
+use restrict_lang::{Program, TopDecl};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+use wasmi::{Func, Store};
+
+/// Prefix that marks a zero-argument function as a benchmark.
+const BENCH_PREFIX: &str = "bench_";
+
+/// Timings of one benchmark, one sample per run.
+struct BenchTimings {
+    samples: Vec<Duration>,
+}
+
+impl BenchTimings {
+    fn mean(&self) -> Duration {
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+
+    fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        }
+    }
+
+    fn min(&self) -> Duration {
+        self.samples.iter().copied().min().unwrap_or_default()
+    }
+}
+
+pub async fn bench_project(filter: Option<String>, iterations: u32) -> Result<()> {
+    let root = find_project_root()?;
+    let benches_dir = root.join("benches");
+
+    if !benches_dir.exists() {
+        print_info("No benches directory found");
+        return Ok(());
+    }
+
+    let mut bench_files = Vec::new();
+    for entry in WalkDir::new(&benches_dir).sort_by_file_name() {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.extension().and_then(|s| s.to_str()) == Some("rl") {
+            bench_files.push(path.to_path_buf());
+        }
+    }
+
+    if bench_files.is_empty() {
+        print_info("No bench files found");
+        return Ok(());
+    }
+
+    let mut measured = 0;
+    let mut failed = 0;
+    let mut filtered_out = 0;
+
+    for bench_file in bench_files {
+        let display = bench_file
+            .strip_prefix(&root)
+            .unwrap_or(&bench_file)
+            .display()
+            .to_string();
+        let file_name = bench_file
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("");
+        let file_matches = filter
+            .as_deref()
+            .is_some_and(|filter| file_name.contains(filter));
+
+        let program = match load_test_program(&bench_file) {
+            Ok(program) => program,
+            Err(e) => {
+                println!("bench {} ... FAILED", display);
+                print_error(&format!("  {}", e));
+                failed += 1;
+                continue;
+            }
+        };
+
+        for name in discover_benches(&program) {
+            let selected = match &filter {
+                Some(filter) => file_matches || name.contains(filter.as_str()),
+                None => true,
+            };
+            if !selected {
+                filtered_out += 1;
+                continue;
+            }
+
+            match run_bench(&program, &name, iterations) {
+                Ok(timings) => {
+                    println!(
+                        "bench {}::{} ... {} runs, mean {:.2?}, median {:.2?}, min {:.2?}",
+                        display,
+                        name,
+                        timings.samples.len(),
+                        timings.mean(),
+                        timings.median(),
+                        timings.min()
+                    );
+                    measured += 1;
+                }
+                Err(e) => {
+                    println!("bench {}::{} ... FAILED", display, name);
+                    print_error(&format!("  {}", e));
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!();
+    let summary = format!(
+        "{} measured, {} failed, {} filtered out",
+        measured, failed, filtered_out
+    );
+    if failed == 0 {
+        print_success(&format!("bench result: ok. {}", summary));
+    } else {
+        print_error(&format!("bench result: FAILED. {}", summary));
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Zero-argument functions named `bench_*`, in declaration order.
+fn discover_benches(program: &Program) -> Vec<String> {
+    program
+        .declarations
+        .iter()
+        .filter_map(|decl| match decl {
+            TopDecl::Function(func) => Some(func),
+            TopDecl::Export(export) => match &*export.item {
+                TopDecl::Function(func) => Some(func),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|func| func.name.starts_with(BENCH_PREFIX) && func.params.is_empty())
+        .map(|func| func.name.clone())
+        .collect()
+}
+
+/// Compile once, then time `iterations` calls of the export.
+fn run_bench(
+    program: &Program,
+    name: &str,
+    iterations: u32,
+) -> std::result::Result<BenchTimings, String> {
+    let wasm = compile_test_module(program, name)?;
+    time_export(&wasm, name, iterations, call_export)
+}
+
+/// Time `iterations` runs of `call` on the export, each in a fresh instance
+/// so arena state from one run cannot leak into the next. Only the call is
+/// timed, not instantiation.
+fn time_export(
+    wasm: &[u8],
+    name: &str,
+    iterations: u32,
+    mut call: impl FnMut(&mut Store<Vec<u8>>, Func) -> InvokeResult,
+) -> std::result::Result<BenchTimings, String> {
+    let mut samples = Vec::with_capacity(iterations as usize);
+    for run in 1..=iterations {
+        let (mut store, func) = instantiate_export(wasm, name)?;
+        let start = Instant::now();
+        let result = call(&mut store, func);
+        let elapsed = start.elapsed();
+        if let Err(trap) = result {
+            return Err(format!("panicked on run {}: {}", run, trap));
+        }
+        samples.push(elapsed);
+    }
+    Ok(BenchTimings { samples })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use restrict_lang::parse_program;
+
+    #[test]
+    fn each_iteration_calls_the_module_once() {
+        let (_, program) = parse_program("fun bench_one: () -> Int32 = {\n    1\n}\n").unwrap();
+        let wasm = compile_test_module(&program, "bench_one").unwrap();
+
+        for iterations in [1, 7] {
+            let mut calls = 0;
+            let timings = time_export(&wasm, "bench_one", iterations, |store, func| {
+                calls += 1;
+                let result = call_export(store, func);
+                assert_eq!(result, Ok(Some(1)));
+                result
+            })
+            .unwrap();
+            assert_eq!(calls, iterations);
+            assert_eq!(timings.samples.len(), iterations as usize);
+        }
+    }
+}
@@ -1,9 +1,16 @@
-use super::{find_project_root, load_manifest, print_info, print_success, print_warning};
+use super::watch::watch_sources;
+use super::{
+    find_project_root, load_manifest, print_error, print_info, print_success, print_warning,
+    write_lockfile,
+};
 use crate::cage::Cage;
-use crate::manifest::{Dependency, Manifest};
-use crate::vault::{LockSource, PackageLock, Vault};
+use crate::lockfile::{Lockfile, LOCKFILE_NAME};
+use crate::manifest::{Dependency, Manifest, PackageKind};
+use crate::registry::DEFAULT_REGISTRY_URL;
+use crate::vault::{cached_git_commit, GitReference, LockSource, PackageLock, Vault};
 use anyhow::{bail, Context, Result};
 use indicatif::{ProgressBar, ProgressStyle};
+use std::path::Path;
 use std::process::Command;
 
 pub async fn build_project(
@@ -12,21 +19,11 @@ pub async fn build_project(
     component: bool,
     verify: bool,
     repro: bool,
+    target: &str,
 ) -> Result<()> {
     let root = find_project_root()?;
     let manifest = load_manifest()?;
 
-    if watch {
-        print_warning(
-            "Watch mode is experimental and out-of-scope for v0.0.1; no watcher was started",
-        );
-        return Ok(());
-    }
-
-    // Create build directory
-    let build_dir = root.join(&manifest.build.output);
-    std::fs::create_dir_all(&build_dir)?;
-
     // Load or create vault
     let vault_path = root.join("restrict-lock.toml");
     let mut vault = if vault_path.exists() {
@@ -37,9 +34,48 @@ pub async fn build_project(
 
     // Resolve dependencies
     print_info("Resolving dependencies...");
-    resolve_dependencies(&manifest, &mut vault).await?;
+    check_lockfile(&root, &manifest, repro)?;
+    resolve_dependencies(&root, &manifest, &mut vault).await?;
     vault.save(&vault_path)?;
 
+    let options = BuildOptions {
+        release,
+        component,
+        verify,
+        target,
+    };
+    if !watch {
+        return build_entry(&root, &manifest, &options);
+    }
+
+    // A failed first build is reported and fixed under the watcher
+    if let Err(e) = build_entry(&root, &manifest, &options) {
+        print_error(&format!("{:#}", e));
+    }
+    watch_sources(&root, &manifest, || build_entry(&root, &manifest, &options))
+}
+
+/// Flags for compiling the entry module into a cage.
+struct BuildOptions<'a> {
+    release: bool,
+    component: bool,
+    verify: bool,
+    target: &'a str,
+}
+
+/// Compile the entry module and package it as a cage in the build directory.
+fn build_entry(root: &Path, manifest: &Manifest, options: &BuildOptions) -> Result<()> {
+    let BuildOptions {
+        release,
+        component,
+        verify,
+        target,
+    } = *options;
+
+    // Create build directory
+    let build_dir = root.join(&manifest.build.output);
+    std::fs::create_dir_all(&build_dir)?;
+
     // Build the project
     print_info("Building project...");
     let progress = ProgressBar::new(100);
@@ -64,13 +100,22 @@ pub async fn build_project(
     let compiler =
         std::env::var("RESTRICT_LANG_BIN").unwrap_or_else(|_| "restrict_lang".to_string());
     let mut cmd = Command::new(compiler);
-    cmd.arg(&entry_path).arg(&wat_output);
-
+    if manifest.package.kind == PackageKind::Lib {
+        // Libraries only expose their exports; no `main`/`_start` entry.
+        cmd.arg("--lib");
+    }
     if release {
-        print_warning(
-            "Release optimizations are experimental and out-of-scope for v0.0.1; building without optimizations",
-        );
+        cmd.arg("--release");
     }
+    cmd.arg("--target").arg(target);
+    for signature in foreign_signatures(root)? {
+        cmd.arg("--foreign").arg(signature);
+    }
+    if verify {
+        // The compiler assembles and validates its own output.
+        cmd.arg("--verify");
+    }
+    cmd.arg(&entry_path).arg(&wat_output);
 
     if component {
         print_warning(
@@ -78,12 +123,6 @@ pub async fn build_project(
         );
     }
 
-    if repro {
-        print_warning(
-            "Deterministic build mode is experimental and out-of-scope for v0.0.1; using the default compiler output",
-        );
-    }
-
     let output = cmd
         .output()
         .context("Failed to run restrict_lang compiler. Is it installed and in PATH?")?;
@@ -148,7 +187,67 @@ pub async fn build_project(
     Ok(())
 }
 
-async fn resolve_dependencies(manifest: &Manifest, vault: &mut Vault) -> Result<()> {
+/// `--foreign` signatures for the locked exports of foreign dependencies,
+/// so the compiler checks calls to them. Exports that need more than
+/// scalars cannot be called yet and are skipped with a warning.
+fn foreign_signatures(root: &Path) -> Result<Vec<String>> {
+    let lock_path = root.join(LOCKFILE_NAME);
+    if !lock_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut signatures = Vec::new();
+    for (name, package) in Lockfile::load(&lock_path)?.packages {
+        for function in &package.exports {
+            match function.foreign_signature(&name) {
+                Ok(signature) => signatures.push(signature),
+                Err(reason) => print_warning(&format!(
+                    "Skipping '{}' export {}: {}",
+                    name, function, reason
+                )),
+            }
+        }
+    }
+    Ok(signatures)
+}
+
+/// Compare the current resolution with `package.rl.lock`. Reproducible
+/// builds fail on any difference; other builds report it and rewrite the lock.
+fn check_lockfile(root: &Path, manifest: &Manifest, repro: bool) -> Result<()> {
+    let lock_path = root.join(LOCKFILE_NAME);
+    let resolved = Lockfile::resolve(root, manifest)?;
+
+    if !lock_path.exists() {
+        if repro {
+            bail!(
+                "Reproducible build requires {}; run 'warder build' without --repro to create it",
+                LOCKFILE_NAME
+            );
+        }
+        return write_lockfile(root, &resolved);
+    }
+
+    let differences = Lockfile::load(&lock_path)?.differences(&resolved);
+    if differences.is_empty() {
+        return Ok(());
+    }
+
+    if repro {
+        bail!(
+            "Dependency resolution differs from {}:\n  {}",
+            LOCKFILE_NAME,
+            differences.join("\n  ")
+        );
+    }
+
+    for difference in &differences {
+        print_warning(difference);
+    }
+    print_info(&format!("Updating {}", LOCKFILE_NAME));
+    resolved.save(&lock_path)
+}
+
+async fn resolve_dependencies(root: &Path, manifest: &Manifest, vault: &mut Vault) -> Result<()> {
     // TODO: Implement full dependency resolution
     // For now, just add entries to vault
 
@@ -164,7 +263,7 @@ async fn resolve_dependencies(manifest: &Manifest, vault: &mut Vault) -> Result<
                 PackageLock {
                     version: ver.clone(),
                     source: LockSource::Registry {
-                        url: "https://wardhub.restrict-lang.org".to_string(),
+                        url: DEFAULT_REGISTRY_URL.to_string(),
                     },
                     abi_hash: String::new(),
                     sha256: String::new(),
@@ -178,16 +277,25 @@ async fn resolve_dependencies(manifest: &Manifest, vault: &mut Vault) -> Result<
                 sha256: String::new(),
                 dependencies: Default::default(),
             },
-            Dependency::Git { git, tag } => PackageLock {
-                version: tag.as_ref().unwrap_or(&"latest".to_string()).clone(),
-                source: LockSource::Git {
-                    url: git.clone(),
-                    rev: tag.as_ref().unwrap_or(&"HEAD".to_string()).clone(),
-                },
-                abi_hash: String::new(),
-                sha256: String::new(),
-                dependencies: Default::default(),
-            },
+            Dependency::Git {
+                git,
+                branch,
+                tag,
+                rev,
+            } => {
+                let reference = GitReference::new(branch.as_ref(), tag.as_ref(), rev.as_ref());
+                PackageLock {
+                    version: reference.version().to_string(),
+                    source: LockSource::Git {
+                        url: git.clone(),
+                        rev: cached_git_commit(root, name, git)
+                            .unwrap_or_else(|| reference.name().to_string()),
+                    },
+                    abi_hash: String::new(),
+                    sha256: String::new(),
+                    dependencies: Default::default(),
+                }
+            }
             Dependency::Foreign { .. } => {
                 // TODO: Wrap foreign WASM
                 PackageLock {
@@ -0,0 +1,55 @@
+use super::{find_project_root, load_manifest, print_info, print_success};
+use crate::vault::CACHE_DIR;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Remove the build output directory and, with `all`, the dependency cache.
+pub fn clean_project(all: bool) -> Result<()> {
+    let root = find_project_root()?;
+    let manifest = load_manifest()?;
+
+    let mut targets = vec![root.join(&manifest.build.output)];
+    if all {
+        targets.push(root.join(CACHE_DIR));
+    }
+
+    let mut removed = false;
+    for target in targets {
+        if !target.exists() {
+            continue;
+        }
+        let target = within_project(&root, &target)?;
+        fs::remove_dir_all(&target)
+            .with_context(|| format!("Failed to remove {}", target.display()))?;
+        print_info(&format!("Removed {}", target.display()));
+        removed = true;
+    }
+
+    if removed {
+        print_success("Project cleaned");
+    } else {
+        print_info("Nothing to clean");
+    }
+    Ok(())
+}
+
+/// Resolve `path` and check that it lies strictly inside the project root, so
+/// a manifest `output` such as `..` or a symlink out of the project can never
+/// point `clean` at unrelated files.
+fn within_project(root: &Path, path: &Path) -> Result<PathBuf> {
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", root.display()))?;
+    let resolved = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    if resolved == root || !resolved.starts_with(&root) {
+        bail!(
+            "Refusing to remove {}: it is not inside the project root {}",
+            resolved.display(),
+            root.display()
+        );
+    }
+    Ok(resolved)
+}
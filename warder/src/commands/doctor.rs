@@ -1,9 +1,14 @@
 use super::{
     find_project_root, load_manifest, print_error, print_info, print_success, print_warning,
 };
+use crate::manifest::{Dependency, Manifest};
 use crate::vault::Vault;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use colored::*;
+use restrict_lang::diagnostics::{format_lex_error, format_parse_error};
+use restrict_lang::module::resolve_program_imports_for_file;
+use restrict_lang::{check_v001_release_surface, lex, parse_program, TypeChecker, WasmCodeGen};
+use std::path::{Path, PathBuf};
 
 pub async fn doctor_check() -> Result<()> {
     println!("{}", "Running project diagnostics...".bold());
@@ -17,17 +22,20 @@ pub async fn doctor_check() -> Result<()> {
         Ok(root) => root,
         Err(_) => {
             print_error("Not in a Restrict Language project (no package.rl.toml found)");
-            return Ok(());
+            bail!("Diagnostics failed: no project found");
         }
     };
 
     // Check manifest
     let manifest = match load_manifest() {
-        Ok(m) => m,
+        Ok(m) => {
+            print_success("Manifest package.rl.toml parses");
+            m
+        }
         Err(e) => {
-            issues.push(format!("Invalid manifest: {}", e));
+            print_error(&format!("Invalid manifest: {:#}", e));
             print_error("Cannot continue checks without valid manifest");
-            return Ok(());
+            bail!("Diagnostics failed: invalid manifest");
         }
     };
 
@@ -37,6 +45,12 @@ pub async fn doctor_check() -> Result<()> {
         issues.push(format!("Entry point not found: {}", entry_path.display()));
     }
 
+    // Check declared dependency paths exist
+    check_dependency_paths(&root, &manifest, &mut issues);
+
+    // Compile every source file through the real toolchain
+    check_sources_compile(&root, &manifest, &mut issues);
+
     // Check vault if it exists
     let vault_path = root.join("restrict-lock.toml");
     if vault_path.exists() {
@@ -107,6 +121,115 @@ pub async fn doctor_check() -> Result<()> {
     ));
     print_info(&format!("Dependencies: {}", manifest.dependencies.len()));
 
+    if !issues.is_empty() {
+        bail!("Diagnostics failed with {} error(s)", issues.len());
+    }
+
+    Ok(())
+}
+
+fn check_dependency_paths(root: &Path, manifest: &Manifest, issues: &mut Vec<String>) {
+    let mut names: Vec<&String> = manifest.dependencies.keys().collect();
+    names.sort();
+
+    for name in names {
+        let paths: Vec<&String> = match &manifest.dependencies[name] {
+            Dependency::Local { path } => vec![path],
+            Dependency::Foreign { wasm, wit } => vec![wasm, wit],
+            Dependency::Version(_) | Dependency::Git { .. } => continue,
+        };
+
+        for path in paths {
+            if !root.join(path).exists() {
+                issues.push(format!(
+                    "Dependency '{}' path not found: {}",
+                    name,
+                    root.join(path).display()
+                ));
+            }
+        }
+    }
+}
+
+fn check_sources_compile(root: &Path, manifest: &Manifest, issues: &mut Vec<String>) {
+    println!("{}", "Compiling sources...".bold());
+
+    let sources = collect_sources(root, manifest);
+    if sources.is_empty() {
+        print_warning("No .rl source files found");
+    }
+
+    for source in sources {
+        let display = source.strip_prefix(root).unwrap_or(&source).display();
+        match compile_source(&source) {
+            Ok(()) => print_success(&format!("{}", display)),
+            Err(e) => {
+                print_error(&format!("{}: {}", display, e));
+                issues.push(format!("{} does not compile", display));
+            }
+        }
+    }
+
+    println!();
+}
+
+pub(super) fn collect_sources(root: &Path, manifest: &Manifest) -> Vec<PathBuf> {
+    let output_dir = root.join(&manifest.build.output);
+    let mut sources: Vec<PathBuf> = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let path = entry.path();
+            if path == root {
+                return true;
+            }
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            !hidden && path != output_dir && entry.file_name() != "target"
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rl"))
+        .collect();
+    sources.sort();
+    sources
+}
+
+/// Runs a single source file through lex, parse, type check, codegen and
+/// WAT assembly, returning the first error encountered.
+pub(super) fn compile_source(path: &Path) -> std::result::Result<(), String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("cannot read file: {}", e))?;
+
+    lex(&source).map_err(|e| format_lex_error(&source, e))?;
+
+    let program = match parse_program(&source) {
+        Ok((remaining, program)) => {
+            if !remaining.trim().is_empty() {
+                return Err(format!(
+                    "Unparsed input remaining at position {}",
+                    source.len() - remaining.len()
+                ));
+            }
+            program
+        }
+        Err(e) => return Err(format_parse_error(&source, e)),
+    };
+
+    let program = resolve_program_imports_for_file(program, path)
+        .map_err(|e| format!("Import resolution error: {}", e))?;
+
+    let mut type_checker = TypeChecker::new();
+    type_checker
+        .check_program(&program)
+        .map_err(|e| format!("Type error: {}", e))?;
+    check_v001_release_surface(&program, &type_checker)
+        .map_err(|e| format!("Release surface error: {}", e))?;
+
+    let wat = WasmCodeGen::new()
+        .generate(&program)
+        .map_err(|e| format!("Code generation error: {}", e))?;
+
+    wat::parse_str(&wat).map_err(|e| format!("Emitted WAT is not valid WASM: {}", e))?;
+
     Ok(())
 }
 
@@ -1,5 +1,5 @@
 use super::{print_info, print_success, print_warning};
-use crate::manifest::Manifest;
+use crate::manifest::{Manifest, PackageKind};
 use anyhow::{bail, Result};
 use std::fs;
 
@@ -19,7 +19,7 @@ pub fn init_project() -> Result<()> {
         .to_string();
 
     // Create manifest
-    let manifest = Manifest::new(&project_name);
+    let manifest = Manifest::new(&project_name, PackageKind::Bin);
     manifest.save(&manifest_path)?;
 
     // Create src directory if it doesn't exist
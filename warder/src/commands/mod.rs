@@ -1,19 +1,24 @@
 use anyhow::{bail, Result};
 use colored::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 mod add;
+mod bench;
 mod build;
+mod clean;
 mod doctor;
 mod init;
 mod new;
 mod publish;
 mod run;
 mod test;
+mod watch;
 mod wrap;
 
 pub use add::{add_dependency, remove_dependency};
+pub use bench::bench_project;
 pub use build::build_project;
+pub use clean::clean_project;
 pub use doctor::doctor_check;
 pub use init::init_project;
 pub use new::new_project;
@@ -22,6 +27,7 @@ pub use run::run_project;
 pub use test::test_project;
 pub use wrap::{unwrap_cage, wrap_wasm};
 
+use crate::lockfile::{Lockfile, LOCKFILE_NAME};
 use crate::manifest::Manifest;
 
 pub fn find_project_root() -> Result<PathBuf> {
@@ -53,6 +59,23 @@ pub fn save_manifest(manifest: &Manifest) -> Result<()> {
     manifest.save(&manifest_path)
 }
 
+/// Re-resolve the manifest's dependencies and rewrite `package.rl.lock`.
+pub fn update_lockfile(manifest: &Manifest) -> Result<()> {
+    let root = find_project_root()?;
+    write_lockfile(&root, &Lockfile::resolve(&root, manifest)?)
+}
+
+/// Save `resolved` as the project's lock, reporting each dependency whose
+/// locked resolution changes. An unreadable old lock counts as empty.
+pub fn write_lockfile(root: &Path, resolved: &Lockfile) -> Result<()> {
+    let lock_path = root.join(LOCKFILE_NAME);
+    let previous = Lockfile::load(&lock_path).unwrap_or_else(|_| Lockfile::new());
+    for change in previous.changes(resolved) {
+        print_info(&change);
+    }
+    resolved.save(&lock_path)
+}
+
 pub fn print_success(message: &str) {
     println!("{} {}", "✓".green().bold(), message);
 }
@@ -1,10 +1,10 @@
 use super::{print_info, print_success};
-use crate::manifest::Manifest;
+use crate::manifest::{Manifest, PackageKind};
 use anyhow::{bail, Result};
 use std::fs;
 use std::path::Path;
 
-pub fn new_project(name: &str) -> Result<()> {
+pub fn new_project(name: &str, lib: bool) -> Result<()> {
     // Validate project name
     if !is_valid_project_name(name) {
         bail!("Invalid project name '{}'. Must start with a letter and contain only letters, numbers, hyphens, and underscores.", name);
@@ -22,20 +22,39 @@ pub fn new_project(name: &str) -> Result<()> {
     fs::create_dir_all(project_path.join("tests"))?;
 
     // Create manifest
-    let manifest = Manifest::new(name);
+    let kind = if lib {
+        PackageKind::Lib
+    } else {
+        PackageKind::Bin
+    };
+    let manifest = Manifest::new(name, kind);
     manifest.save(&project_path.join("package.rl.toml"))?;
 
-    // Create main.rl
-    let main_content = r#"// Welcome to Restrict Language!
+    // Create the entry source: main.rl for programs, lib.rl for libraries
+    let (entry_content, usage) = match kind {
+        PackageKind::Bin => (
+            r#"// Welcome to Restrict Language!
 
 fun main: () -> () = {
     "Hello, World!" |> println
 }
-"#;
-    fs::write(project_path.join("src/main.rl"), main_content)?;
+"#,
+            "warder build\nwarder run",
+        ),
+        PackageKind::Lib => (
+            r#"// Library entry point. Exported functions form the package API.
 
-    // Create test file. Warder currently type-checks test sources.
-    let test_content = r#"// Example type-check smoke test
+export fun add: (a: Int32, b: Int32) -> Int32 = {
+    a + b
+}
+"#,
+            "warder build\nwarder publish",
+        ),
+    };
+    fs::write(project_path.join(&manifest.package.entry), entry_content)?;
+
+    // Create test file. `warder test` runs every zero-argument `test_*` function.
+    let test_content = r#"// Example test
 
 fun test_example: () -> Boolean = {
     1 + 1 == 2
@@ -60,32 +79,40 @@ restrict-lock.toml
     let readme_content = format!(
         r#"# {}
 
-A new Restrict Language project.
+A new Restrict Language {}.
 
 ## Getting Started
 
 ```bash
-warder build
-warder run
+{}
 ```
 
 ## Testing
 
-`warder test` type-checks each `.rl` file under `tests/`.
+`warder test` runs every zero-argument `test_*` function under `tests/`.
 
 ```bash
 warder test
 ```
 "#,
-        name
+        name,
+        match kind {
+            PackageKind::Bin => "project",
+            PackageKind::Lib => "library",
+        },
+        usage
     );
     fs::write(project_path.join("README.md"), readme_content)?;
 
-    print_success(&format!("Created project '{}'", name));
+    match kind {
+        PackageKind::Bin => print_success(&format!("Created project '{}'", name)),
+        PackageKind::Lib => print_success(&format!("Created library '{}'", name)),
+    }
     print_info("Next steps:");
     println!("  cd {}", name);
-    println!("  warder build");
-    println!("  warder run");
+    for step in usage.lines() {
+        println!("  {}", step);
+    }
 
     Ok(())
 }
@@ -1,17 +1,24 @@
-use super::{find_project_root, load_manifest, print_info, print_warning};
+use super::{find_project_root, load_manifest, print_info, print_success, print_warning};
 use crate::cage::Cage;
-use anyhow::{bail, Result};
+use crate::manifest::Manifest;
+use crate::registry::DEFAULT_REGISTRY_URL;
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+use wasmi::{Engine, ExternType, Module, ValType};
 
-pub async fn publish_package(registry: Option<String>) -> Result<()> {
+pub async fn publish_package(registry: Option<String>, dry_run: bool) -> Result<()> {
     let root = find_project_root()?;
     let manifest = load_manifest()?;
 
     // Default registry
-    let registry_url = registry.unwrap_or_else(|| "https://wardhub.restrict-lang.org".to_string());
+    let registry_url = registry.unwrap_or_else(|| DEFAULT_REGISTRY_URL.to_string());
+
+    // Reject incomplete metadata before spending time on a build
+    verify_manifest_metadata(&manifest)?;
 
     // Build in release mode first
     print_info("Running publish preflight build...");
-    super::build::build_project(true, false, false, true, true).await?;
+    super::build::build_project(true, false, false, true, true, "wasi").await?;
 
     // Find the built cage
     let build_dir = root.join(&manifest.build.output);
@@ -34,11 +41,29 @@ pub async fn publish_package(registry: Option<String>) -> Result<()> {
         bail!("Package version mismatch");
     }
 
+    verify_exports(&cage.wasm_bytes)?;
+
+    // Hash the cage exactly as it would be uploaded
+    let cage_bytes = std::fs::read(&cage_path)
+        .with_context(|| format!("Failed to read cage {}", cage_path.display()))?;
+    let content_hash = hex::encode(Sha256::digest(&cage_bytes));
+
     print_info(&format!(
         "Publish preflight complete for {} v{}",
         manifest.package.name, manifest.package.version
     ));
 
+    if dry_run {
+        print_success(&format!(
+            "Dry run: {} ({} bytes) would be published to {}",
+            cage_path.display(),
+            cage_bytes.len(),
+            registry_url
+        ));
+        print_info(&format!("Content hash (sha256): {}", content_hash));
+        return Ok(());
+    }
+
     // TODO: Implement actual publishing
     // This would:
     // 1. Authenticate with the registry
@@ -55,3 +80,67 @@ pub async fn publish_package(registry: Option<String>) -> Result<()> {
 
     Ok(())
 }
+
+/// Registries index packages by name and version and need a license to
+/// redistribute them, so all three must be present before a publish.
+fn verify_manifest_metadata(manifest: &Manifest) -> Result<()> {
+    let package = &manifest.package;
+    let mut missing = Vec::new();
+    if package.name.trim().is_empty() {
+        missing.push("name");
+    }
+    if package.version.trim().is_empty() {
+        missing.push("version");
+    }
+    if package
+        .license
+        .as_deref()
+        .is_none_or(|l| l.trim().is_empty())
+    {
+        missing.push("license");
+    }
+    if !missing.is_empty() {
+        bail!(
+            "Cannot publish: package.rl.toml is missing required [package] field(s): {}",
+            missing.join(", ")
+        );
+    }
+
+    if let Err(e) = semver::Version::parse(&package.version) {
+        bail!(
+            "Cannot publish: version '{}' is not a semantic version: {}",
+            package.version,
+            e
+        );
+    }
+
+    Ok(())
+}
+
+/// Check that every exported function crosses the boundary with plain
+/// numeric Wasm values, which is all a registry consumer can link against.
+fn verify_exports(wasm_bytes: &[u8]) -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm_bytes).context("Built module is not valid WASM")?;
+
+    for export in module.exports() {
+        let ExternType::Func(func_ty) = export.ty() else {
+            continue;
+        };
+        let unrepresentable = func_ty.params().iter().chain(func_ty.results()).find(|ty| {
+            !matches!(
+                ty,
+                ValType::I32 | ValType::I64 | ValType::F32 | ValType::F64
+            )
+        });
+        if let Some(ty) = unrepresentable {
+            bail!(
+                "Cannot publish: export '{}' uses {:?}, which has no portable WASM representation",
+                export.name(),
+                ty
+            );
+        }
+    }
+
+    Ok(())
+}
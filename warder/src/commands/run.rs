@@ -1,10 +1,17 @@
 use super::{find_project_root, load_manifest};
-use anyhow::{bail, Context, Result};
+use crate::manifest::PackageKind;
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::Write;
 use std::process::Command;
+use wasmi::{Caller, Engine, Linker, Memory, Module, Store};
 
 pub async fn run_project(args: Vec<String>) -> Result<()> {
+    if load_manifest()?.package.kind == PackageKind::Lib {
+        bail!("Cannot run a library package; it has no `main` entry point. Use 'warder build' instead.");
+    }
+
     // First build the project
-    super::build::build_project(false, false, false, false, false).await?;
+    super::build::build_project(false, false, false, false, false, "wasi").await?;
 
     let root = find_project_root()?;
     let manifest = load_manifest()?;
@@ -41,8 +48,10 @@ pub async fn run_project(args: Vec<String>) -> Result<()> {
 fn run_wasm(wasm_path: &std::path::Path, args: Vec<String>) -> Result<()> {
     // Try wasmtime first
     if which::which("wasmtime").is_ok() {
+        // `--` before the module keeps program arguments that look like
+        // flags away from wasmtime's own option parser.
         let mut cmd = Command::new("wasmtime");
-        cmd.arg("run").arg(wasm_path).arg("--").args(args);
+        cmd.arg("run").arg("--").arg(wasm_path).args(args);
 
         let status = cmd.status().context("Failed to run with wasmtime")?;
 
@@ -64,8 +73,166 @@ fn run_wasm(wasm_path: &std::path::Path, args: Vec<String>) -> Result<()> {
 
         Ok(())
     } else {
-        bail!("No WASM runtime found. Please install wasmtime or wasmer.");
+        run_embedded(wasm_path, args)
+    }
+}
+
+/// Run the module in-process when no external runtime is installed. Only
+/// the WASI calls emitted by the compiler are provided: `fd_write`,
+/// `proc_exit`, `args_sizes_get`, and `args_get`.
+fn run_embedded(wasm_path: &std::path::Path, args: Vec<String>) -> Result<()> {
+    let wasm = std::fs::read(wasm_path)
+        .with_context(|| format!("Failed to read {}", wasm_path.display()))?;
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..]).map_err(|e| anyhow!("Invalid module: {}", e))?;
+
+    // WASI argv[0] is the program name, as with wasmtime.
+    let program = wasm_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let argv: Vec<String> = std::iter::once(program).chain(args).collect();
+    let mut store = Store::new(&engine, argv);
+    let mut linker = <Linker<Vec<String>>>::new(&engine);
+
+    linker
+        .func_wrap("wasi_snapshot_preview1", "fd_write", host_fd_write)
+        .and_then(|linker| {
+            linker.func_wrap(
+                "wasi_snapshot_preview1",
+                "proc_exit",
+                |_caller: Caller<'_, Vec<String>>,
+                 code: i32|
+                 -> std::result::Result<(), wasmi::Error> {
+                    Err(wasmi::Error::i32_exit(code))
+                },
+            )
+        })
+        .and_then(|linker| {
+            linker.func_wrap(
+                "wasi_snapshot_preview1",
+                "args_sizes_get",
+                host_args_sizes_get,
+            )
+        })
+        .and_then(|linker| linker.func_wrap("wasi_snapshot_preview1", "args_get", host_args_get))
+        .map_err(|e| anyhow!("Failed to link WASI imports: {}", e))?;
+
+    let instance = linker
+        .instantiate_and_start(&mut store, &module)
+        .map_err(|e| anyhow!("Failed to instantiate {}: {}", wasm_path.display(), e))?;
+    let start = instance
+        .get_typed_func::<(), ()>(&store, "_start")
+        .map_err(|_| anyhow!("{} has no `_start` entry point", wasm_path.display()))?;
+
+    let outcome = start.call(&mut store, ());
+    std::io::stdout().flush().ok();
+    match outcome {
+        Ok(()) => Ok(()),
+        Err(e) => match e.i32_exit_status() {
+            Some(0) => Ok(()),
+            Some(code) => std::process::exit(code),
+            None => bail!("Program trapped: {}", e),
+        },
+    }
+}
+
+fn guest_memory(caller: &mut Caller<'_, Vec<String>>) -> Option<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+}
+
+fn write_i32(
+    memory: Memory,
+    caller: &mut Caller<'_, Vec<String>>,
+    offset: i32,
+    value: i32,
+) -> bool {
+    memory
+        .write(caller, offset as usize, &value.to_le_bytes())
+        .is_ok()
+}
+
+/// WASI `fd_write` forwarding stdout and stderr to the host streams.
+fn host_fd_write(
+    mut caller: Caller<'_, Vec<String>>,
+    fd: i32,
+    iovs: i32,
+    iovs_len: i32,
+    nwritten: i32,
+) -> i32 {
+    let Some(memory) = guest_memory(&mut caller) else {
+        return 1;
+    };
+
+    let mut bytes = Vec::new();
+    for i in 0..iovs_len {
+        let iov = (iovs + i * 8) as usize;
+        let mut header = [0; 8];
+        if memory.read(&caller, iov, &mut header).is_err() {
+            return 1;
+        }
+        let base = i32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        let len = i32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+        let start = bytes.len();
+        bytes.resize(start + len, 0);
+        if memory.read(&caller, base, &mut bytes[start..]).is_err() {
+            return 1;
+        }
+    }
+
+    let written = match fd {
+        1 => std::io::stdout().write_all(&bytes),
+        2 => std::io::stderr().write_all(&bytes),
+        _ => return 8,
+    };
+    if written.is_err() {
+        return 29;
+    }
+    if write_i32(memory, &mut caller, nwritten, bytes.len() as i32) {
+        0
+    } else {
+        1
+    }
+}
+
+/// WASI `args_sizes_get`: the argument count and the size of the
+/// NUL-terminated argument buffer.
+fn host_args_sizes_get(mut caller: Caller<'_, Vec<String>>, argc: i32, buf_size: i32) -> i32 {
+    let Some(memory) = guest_memory(&mut caller) else {
+        return 1;
+    };
+    let count = caller.data().len() as i32;
+    let size: usize = caller.data().iter().map(|arg| arg.len() + 1).sum();
+    if write_i32(memory, &mut caller, argc, count)
+        && write_i32(memory, &mut caller, buf_size, size as i32)
+    {
+        0
+    } else {
+        1
+    }
+}
+
+/// WASI `args_get`: fills `argv` with pointers to NUL-terminated copies of
+/// each argument laid out back to back in `buf`.
+fn host_args_get(mut caller: Caller<'_, Vec<String>>, argv: i32, buf: i32) -> i32 {
+    let Some(memory) = guest_memory(&mut caller) else {
+        return 1;
+    };
+    let args = caller.data().clone();
+    let mut offset = buf;
+    for (i, arg) in args.iter().enumerate() {
+        let mut bytes = arg.as_bytes().to_vec();
+        bytes.push(0);
+        if !write_i32(memory, &mut caller, argv + (i as i32) * 4, offset)
+            || memory.write(&mut caller, offset as usize, &bytes).is_err()
+        {
+            return 1;
+        }
+        offset += bytes.len() as i32;
     }
+    0
 }
 
 fn run_native(binary_path: &std::path::Path, args: Vec<String>) -> Result<()> {
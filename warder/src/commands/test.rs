@@ -1,8 +1,39 @@
 use super::{find_project_root, print_error, print_info, print_success};
-use anyhow::{Context, Result};
-use std::path::PathBuf;
-use std::process::Command;
+use anyhow::Result;
+use restrict_lang::diagnostics::format_parse_error;
+use restrict_lang::module::resolve_program_imports_for_file;
+use restrict_lang::test_framework::{
+    check_property, Gen, PropertyResult, Value, DEFAULT_ITERATIONS,
+};
+use restrict_lang::{parse_program, ExportDecl, Program, TopDecl, Type, TypeChecker, WasmCodeGen};
+use std::path::Path;
 use walkdir::WalkDir;
+use wasmi::{Caller, Engine, Func, Linker, Memory, Module, Store, Val};
+
+/// Prefix that marks a zero-argument function as a test.
+const TEST_PREFIX: &str = "test_";
+
+/// Prefix that marks a function with parameters as a property, run against
+/// generated arguments.
+const PROPERTY_PREFIX: &str = "prop_";
+
+/// Name of the synthesized function that calls a property with one case.
+const PROPERTY_CASE: &str = "__property_case";
+
+/// A test or property function discovered in a test file.
+struct TestFunction {
+    name: String,
+    /// Parameter types; empty for plain tests.
+    params: Vec<Type>,
+    return_type: Option<Type>,
+}
+
+impl TestFunction {
+    /// Boolean tests fail when they return `false`.
+    fn returns_boolean(&self) -> bool {
+        matches!(&self.return_type, Some(Type::Named(name)) if name == "Boolean")
+    }
+}
 
 pub async fn test_project(filter: Option<String>) -> Result<()> {
     let root = find_project_root()?;
@@ -15,18 +46,11 @@ pub async fn test_project(filter: Option<String>) -> Result<()> {
 
     // Find all test files
     let mut test_files = Vec::new();
-    for entry in WalkDir::new(&tests_dir) {
+    for entry in WalkDir::new(&tests_dir).sort_by_file_name() {
         let entry = entry?;
         let path = entry.path();
 
         if path.extension().and_then(|s| s.to_str()) == Some("rl") {
-            if let Some(filter) = &filter {
-                let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-                if !file_name.contains(filter) {
-                    continue;
-                }
-            }
-
             test_files.push(path.to_path_buf());
         }
     }
@@ -36,53 +60,413 @@ pub async fn test_project(filter: Option<String>) -> Result<()> {
         return Ok(());
     }
 
-    print_info(&format!("Running {} test file(s)", test_files.len()));
-
     let mut passed = 0;
     let mut failed = 0;
+    let mut filtered_out = 0;
 
     for test_file in test_files {
-        print!("Testing {} ... ", test_file.display());
+        let display = test_file
+            .strip_prefix(&root)
+            .unwrap_or(&test_file)
+            .display()
+            .to_string();
+        let file_name = test_file.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let file_matches = filter
+            .as_deref()
+            .is_some_and(|filter| file_name.contains(filter));
 
-        match run_test_file(&test_file).await {
-            Ok(_) => {
-                println!("PASSED");
-                passed += 1;
-            }
+        let program = match load_test_program(&test_file) {
+            Ok(program) => program,
             Err(e) => {
-                println!("FAILED");
+                println!("test {} ... FAILED", display);
                 print_error(&format!("  {}", e));
                 failed += 1;
+                continue;
+            }
+        };
+
+        for test in discover_tests(&program) {
+            let selected = match &filter {
+                Some(filter) => file_matches || test.name.contains(filter.as_str()),
+                None => true,
+            };
+            if !selected {
+                filtered_out += 1;
+                continue;
+            }
+
+            match run_test(&program, &test) {
+                Ok(()) => {
+                    println!("test {}::{} ... ok", display, test.name);
+                    passed += 1;
+                }
+                Err(e) => {
+                    println!("test {}::{} ... FAILED", display, test.name);
+                    print_error(&format!("  {}", e));
+                    failed += 1;
+                }
             }
         }
     }
 
     println!();
+    let summary = format!(
+        "{} passed, {} failed, {} filtered out",
+        passed, failed, filtered_out
+    );
     if failed == 0 {
-        print_success(&format!("All tests passed! ({} total)", passed));
+        print_success(&format!("test result: ok. {}", summary));
     } else {
-        print_error(&format!("{} passed, {} failed", passed, failed));
+        print_error(&format!("test result: FAILED. {}", summary));
         std::process::exit(1);
     }
 
     Ok(())
 }
 
-async fn run_test_file(test_file: &PathBuf) -> Result<()> {
-    // v0.0.1 does not have a dedicated test declaration syntax yet.
-    // Treat test files as type-check smoke tests until the test DSL is designed.
-    let compiler =
-        std::env::var("RESTRICT_LANG_BIN").unwrap_or_else(|_| "restrict_lang".to_string());
-    let output = Command::new(compiler)
-        .arg("--check")
-        .arg(test_file)
-        .output()
-        .context("Failed to type-check test file")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Type checking failed:\n{}", stderr);
+/// Parse, resolve imports for, and type-check a test file.
+pub(super) fn load_test_program(path: &Path) -> std::result::Result<Program, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("cannot read file: {}", e))?;
+
+    let program = match parse_program(&source) {
+        Ok((remaining, program)) => {
+            if !remaining.trim().is_empty() {
+                return Err(format!(
+                    "Unparsed input remaining at position {}",
+                    source.len() - remaining.len()
+                ));
+            }
+            program
+        }
+        Err(e) => return Err(format_parse_error(&source, e)),
+    };
+
+    let program = resolve_program_imports_for_file(program, path)
+        .map_err(|e| format!("Import resolution error: {}", e))?;
+
+    TypeChecker::new()
+        .check_program(&program)
+        .map_err(|e| format!("Type error: {}", e))?;
+
+    Ok(program)
+}
+
+/// Zero-argument functions named `test_*` and functions with parameters
+/// named `prop_*`, in declaration order.
+fn discover_tests(program: &Program) -> Vec<TestFunction> {
+    program
+        .declarations
+        .iter()
+        .filter_map(|decl| match decl {
+            TopDecl::Function(func) => Some(func),
+            TopDecl::Export(export) => match &*export.item {
+                TopDecl::Function(func) => Some(func),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|func| {
+            (func.name.starts_with(TEST_PREFIX) && func.params.is_empty())
+                || (func.name.starts_with(PROPERTY_PREFIX) && !func.params.is_empty())
+        })
+        .map(|func| TestFunction {
+            name: func.name.clone(),
+            params: func.params.iter().map(|param| param.ty.clone()).collect(),
+            return_type: func.return_type.clone(),
+        })
+        .collect()
+}
+
+fn run_test(program: &Program, test: &TestFunction) -> std::result::Result<(), String> {
+    if test.params.is_empty() {
+        return run_case(program, &test.name, test.returns_boolean());
     }
 
-    Ok(())
+    let gen = Gen::tuple(
+        test.params
+            .iter()
+            .map(|ty| Gen::for_type(ty, program))
+            .collect::<std::result::Result<Vec<_>, _>>()?,
+    );
+    let result = check_property(
+        &gen,
+        |args| {
+            let case = property_case(program, test, args)?;
+            run_case(&case, PROPERTY_CASE, test.returns_boolean())
+        },
+        DEFAULT_ITERATIONS,
+    );
+
+    match result {
+        PropertyResult::Passed { .. } => Ok(()),
+        PropertyResult::Failed(counterexample) => Err(format!(
+            "{}\n  counterexample: ({}) after {} case(s) and {} shrink step(s)",
+            counterexample.message,
+            render_args(&counterexample.shrunk),
+            counterexample.iterations,
+            counterexample.shrink_steps
+        )),
+    }
+}
+
+fn render_args(args: &[Value]) -> String {
+    args.iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The program plus a zero-argument function calling the property with `args`.
+fn property_case(
+    program: &Program,
+    test: &TestFunction,
+    args: &[Value],
+) -> std::result::Result<Program, String> {
+    let return_type = test
+        .return_type
+        .as_ref()
+        .map(|ty| format!("-> {} ", ty))
+        .unwrap_or_default();
+    let source = format!(
+        "fun {}: () {}= {{\n    ({}) {}\n}}\n",
+        PROPERTY_CASE,
+        return_type,
+        render_args(args),
+        test.name
+    );
+    let (_, case) = parse_program(&source).map_err(|e| {
+        format!(
+            "Cannot build property case: {}",
+            format_parse_error(&source, e)
+        )
+    })?;
+
+    let mut program = program.clone();
+    program.declarations.extend(case.declarations);
+    Ok(program)
+}
+
+/// Compile the program, call the named export, and turn a trap or a `false`
+/// result into a failure message.
+fn run_case(
+    program: &Program,
+    name: &str,
+    returns_boolean: bool,
+) -> std::result::Result<(), String> {
+    let wasm = compile_test_module(program, name)?;
+    let (result, output) = invoke_export(&wasm, name)?;
+
+    let failure = match result {
+        Err(trap) => format!("panicked: {}", trap),
+        Ok(Some(0)) if returns_boolean => "returned false".to_string(),
+        Ok(_) => return Ok(()),
+    };
+
+    if output.is_empty() {
+        Err(failure)
+    } else {
+        Err(format!(
+            "{}\n  output:\n{}",
+            failure,
+            String::from_utf8_lossy(&output).trim_end()
+        ))
+    }
+}
+
+/// Compile the program as a library that exports the named function.
+pub(super) fn compile_test_module(
+    program: &Program,
+    name: &str,
+) -> std::result::Result<Vec<u8>, String> {
+    let mut program = program.clone();
+    program.declarations = program
+        .declarations
+        .into_iter()
+        .map(|decl| match decl {
+            TopDecl::Function(func) if func.name == name => TopDecl::Export(ExportDecl {
+                item: Box::new(TopDecl::Function(func)),
+            }),
+            decl => decl,
+        })
+        .collect();
+
+    let mut codegen = WasmCodeGen::new();
+    codegen.set_library(true);
+    let wat = codegen
+        .generate(&program)
+        .map_err(|e| format!("Code generation error: {}", e))?;
+
+    wat::parse_str(&wat).map_err(|e| format!("Emitted WAT is not valid WASM: {}", e))
+}
+
+pub(super) type InvokeResult = std::result::Result<Option<i32>, String>;
+
+/// Instantiate the module with stdout/stderr captured and call the named
+/// zero-argument export. Traps and `proc_exit` come back as `Err`.
+fn invoke_export(wasm: &[u8], name: &str) -> std::result::Result<(InvokeResult, Vec<u8>), String> {
+    let (mut store, func) = instantiate_export(wasm, name)?;
+    let result = call_export(&mut store, func);
+    Ok((result, store.into_data()))
+}
+
+/// Instantiate the module in a fresh store with stdout/stderr captured and
+/// look up the named export.
+///
+/// Tests run on the embedded wasmi interpreter, the same one `warder run`
+/// falls back to, rather than an installed wasmtime: output has to be
+/// captured per test in-process, and `warder test` must work on machines
+/// without an external runtime. Each test module is small and runs once,
+/// so an interpreter costs less than compiling it with a JIT would.
+pub(super) fn instantiate_export(
+    wasm: &[u8],
+    name: &str,
+) -> std::result::Result<(Store<Vec<u8>>, Func), String> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm).map_err(|e| format!("Invalid module: {}", e))?;
+    let mut store = Store::new(&engine, Vec::<u8>::new());
+    let mut linker = <Linker<Vec<u8>>>::new(&engine);
+
+    linker
+        .func_wrap("wasi_snapshot_preview1", "fd_write", capture_fd_write)
+        .and_then(|linker| {
+            linker.func_wrap(
+                "wasi_snapshot_preview1",
+                "proc_exit",
+                |_caller: Caller<'_, Vec<u8>>,
+                 code: i32|
+                 -> std::result::Result<(), wasmi::Error> {
+                    Err(wasmi::Error::i32_exit(code))
+                },
+            )
+        })
+        .map_err(|e| format!("Failed to link WASI imports: {}", e))?;
+
+    let instance = linker
+        .instantiate_and_start(&mut store, &module)
+        .map_err(|e| format!("Failed to instantiate test module: {}", e))?;
+    let func = instance
+        .get_func(&store, name)
+        .ok_or_else(|| format!("Test function '{}' was not exported", name))?;
+    Ok((store, func))
+}
+
+/// Call a zero-argument export. Traps and `proc_exit` come back as `Err`.
+pub(super) fn call_export(store: &mut Store<Vec<u8>>, func: Func) -> InvokeResult {
+    let mut results: Vec<Val> = func
+        .ty(&*store)
+        .results()
+        .iter()
+        .map(|ty| Val::default(*ty))
+        .collect();
+    match func.call(store, &[], &mut results) {
+        Ok(()) => Ok(results.first().and_then(Val::i32)),
+        Err(e) => Err(match e.i32_exit_status() {
+            Some(code) => format!("exited with status {}", code),
+            None => e.to_string(),
+        }),
+    }
+}
+
+fn read_i32(memory: Memory, caller: &Caller<'_, Vec<u8>>, offset: i32) -> Option<i32> {
+    let bytes = guest_bytes(memory, caller, offset, 4)?;
+    Some(i32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// The `len` bytes at `base` in guest memory, or `None` when the range does
+/// not lie within it. Both values come from the guest, so they are checked
+/// against the memory size before anything is copied.
+fn guest_bytes<'a>(
+    memory: Memory,
+    caller: &'a Caller<'_, Vec<u8>>,
+    base: i32,
+    len: i32,
+) -> Option<&'a [u8]> {
+    let start = base as u32 as usize;
+    let end = start.checked_add(len as u32 as usize)?;
+    memory.data(caller).get(start..end)
+}
+
+/// WASI `fd_write` that appends stdout and stderr to the store's buffer.
+fn capture_fd_write(
+    mut caller: Caller<'_, Vec<u8>>,
+    _fd: i32,
+    iovs: i32,
+    iovs_len: i32,
+    nwritten: i32,
+) -> i32 {
+    let Some(memory) = caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+    else {
+        return 1;
+    };
+
+    let mut captured = Vec::new();
+    for i in 0..iovs_len {
+        let iov = iovs + i * 8;
+        let (Some(base), Some(len)) = (
+            read_i32(memory, &caller, iov),
+            read_i32(memory, &caller, iov + 4),
+        ) else {
+            return 1;
+        };
+        let Some(bytes) = guest_bytes(memory, &caller, base, len) else {
+            return 1;
+        };
+        captured.extend_from_slice(bytes);
+    }
+
+    let written = (captured.len() as i32).to_le_bytes();
+    caller.data_mut().extend(captured);
+    if memory
+        .write(&mut caller, nwritten as usize, &written)
+        .is_err()
+    {
+        return 1;
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module whose `write` export hands `fd_write` one iovec of `len`
+    /// bytes starting at offset 16, where "hi" is stored.
+    fn fd_write_module(len: u32) -> Vec<u8> {
+        let len = len
+            .to_le_bytes()
+            .map(|byte| format!("\\{:02x}", byte))
+            .concat();
+        wat::parse_str(format!(
+            r#"(module
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "\10\00\00\00{len}")
+  (data (i32.const 16) "hi")
+  (func (export "write") (result i32)
+    i32.const 1
+    i32.const 0
+    i32.const 1
+    i32.const 8
+    call $fd_write))"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn fd_write_captures_guest_output() {
+        let (result, output) = invoke_export(&fd_write_module(2), "write").unwrap();
+        assert_eq!(result, Ok(Some(0)));
+        assert_eq!(output, b"hi");
+    }
+
+    #[test]
+    fn fd_write_rejects_ranges_past_the_end_of_memory() {
+        for len in [0x1_0000, i32::MAX as u32, u32::MAX] {
+            let (result, output) = invoke_export(&fd_write_module(len), "write").unwrap();
+            assert_eq!(result, Ok(Some(1)), "len {len:#x}");
+            assert!(output.is_empty());
+        }
+    }
 }
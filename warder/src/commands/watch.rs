@@ -0,0 +1,326 @@
+use super::doctor::{collect_sources, compile_source};
+use super::{print_error, print_info, print_success};
+use crate::manifest::Manifest;
+use anyhow::Result;
+use colored::*;
+use restrict_lang::parse_program;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the source tree is polled for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How long the tree must stay unchanged before a burst of saves is rebuilt.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Which project files import which, so a change can be traced to the
+/// files that must be recompiled with it.
+#[derive(Debug, Default)]
+pub(super) struct ModuleGraph {
+    /// File -> project files it imports
+    imports: BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+}
+
+impl ModuleGraph {
+    pub(super) fn scan(root: &Path, sources: &[PathBuf]) -> Self {
+        let mut graph = Self::default();
+        for source in sources {
+            graph.rescan(root, source, sources);
+        }
+        graph
+    }
+
+    /// Re-read the imports of `path`, or forget it if it no longer exists.
+    /// A file that does not parse keeps no edges until it is fixed.
+    fn rescan(&mut self, root: &Path, path: &Path, sources: &[PathBuf]) {
+        let Ok(source) = std::fs::read_to_string(path) else {
+            self.imports.remove(path);
+            return;
+        };
+        let imports = match parse_program(&source) {
+            Ok((_, program)) => program
+                .imports
+                .iter()
+                .filter_map(|import| module_file(root, path, &import.module_path, sources))
+                .collect(),
+            Err(_) => BTreeSet::new(),
+        };
+        self.imports.insert(path.to_path_buf(), imports);
+    }
+
+    /// The changed files plus every file that imports one of them, directly
+    /// or transitively, in a stable order.
+    pub(super) fn affected(&self, changed: &[PathBuf]) -> Vec<PathBuf> {
+        let mut affected: BTreeSet<PathBuf> = changed.iter().cloned().collect();
+        let mut pending: Vec<PathBuf> = changed.to_vec();
+        while let Some(path) = pending.pop() {
+            for (importer, imports) in &self.imports {
+                if imports.contains(&path) && affected.insert(importer.clone()) {
+                    pending.push(importer.clone());
+                }
+            }
+        }
+        affected.into_iter().collect()
+    }
+}
+
+/// The project file an import names, looked up next to the importing file
+/// and then at the project root, like the compiler's module resolver.
+fn module_file(
+    root: &Path,
+    importer: &Path,
+    module_path: &[String],
+    sources: &[PathBuf],
+) -> Option<PathBuf> {
+    let relative = module_path.join("/") + ".rl";
+    importer
+        .parent()
+        .into_iter()
+        .chain(std::iter::once(root))
+        .map(|dir| dir.join(&relative))
+        .find(|candidate| sources.contains(candidate))
+}
+
+/// The outcome of recompiling one file.
+#[derive(Debug)]
+pub(super) struct FileBuild {
+    pub(super) path: PathBuf,
+    pub(super) elapsed: Duration,
+    pub(super) result: std::result::Result<(), String>,
+}
+
+/// Rebuild state kept between changes: the module graph and the content of
+/// each file as of its last successful compile.
+pub(super) struct IncrementalBuild {
+    root: PathBuf,
+    sources: Vec<PathBuf>,
+    graph: ModuleGraph,
+    compiled: HashMap<PathBuf, u64>,
+}
+
+impl IncrementalBuild {
+    pub(super) fn new(root: &Path, sources: Vec<PathBuf>) -> Self {
+        let graph = ModuleGraph::scan(root, &sources);
+        Self {
+            root: root.to_path_buf(),
+            sources,
+            graph,
+            compiled: HashMap::new(),
+        }
+    }
+
+    /// Recompile `changed` and the files depending on them with `compile`.
+    /// A changed file whose content matches its last successful compile, such
+    /// as one saved without edits, triggers nothing.
+    pub(super) fn rebuild<F>(&mut self, changed: &[PathBuf], mut compile: F) -> Vec<FileBuild>
+    where
+        F: FnMut(&Path) -> std::result::Result<(), String>,
+    {
+        let mut edited = Vec::new();
+        for path in changed {
+            let exists = path.exists();
+            if exists && !self.sources.contains(path) {
+                self.sources.push(path.clone());
+                self.sources.sort();
+            } else if !exists {
+                self.sources.retain(|source| source != path);
+            }
+            let hash = content_hash(path);
+            if hash.is_none() || hash != self.compiled.get(path).copied() {
+                edited.push(path.clone());
+            }
+        }
+        for path in &edited {
+            self.graph.rescan(&self.root, path, &self.sources);
+        }
+
+        let mut builds = Vec::new();
+        for path in self.graph.affected(&edited) {
+            if !self.sources.contains(&path) {
+                // A deleted file leaves only its dependents to recompile
+                self.compiled.remove(&path);
+                continue;
+            }
+            let start = Instant::now();
+            let result = compile(&path);
+            match (&result, content_hash(&path)) {
+                (Ok(()), Some(hash)) => {
+                    self.compiled.insert(path.clone(), hash);
+                }
+                _ => {
+                    self.compiled.remove(&path);
+                }
+            }
+            builds.push(FileBuild {
+                path,
+                elapsed: start.elapsed(),
+                result,
+            });
+        }
+        builds
+    }
+}
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let content = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Modification time of every source file, for change polling.
+fn snapshot(root: &Path, manifest: &Manifest) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    collect_sources(root, manifest)
+        .into_iter()
+        .map(|path| {
+            let modified = std::fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+fn changed_paths(
+    before: &BTreeMap<PathBuf, Option<SystemTime>>,
+    after: &BTreeMap<PathBuf, Option<SystemTime>>,
+) -> BTreeSet<PathBuf> {
+    let removed = before.keys().filter(|path| !after.contains_key(*path));
+    let modified = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(*modified))
+        .map(|(path, _)| path);
+    removed.chain(modified).cloned().collect()
+}
+
+/// Poll until the sources change, then keep polling until they have been
+/// quiet for [`DEBOUNCE`], so a burst of saves is rebuilt once.
+fn wait_for_changes(
+    root: &Path,
+    manifest: &Manifest,
+    last: &mut BTreeMap<PathBuf, Option<SystemTime>>,
+) -> Vec<PathBuf> {
+    let mut changed = BTreeSet::new();
+    let mut quiet_since: Option<Instant> = None;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let current = snapshot(root, manifest);
+        let newly_changed = changed_paths(last, &current);
+        *last = current;
+        if !newly_changed.is_empty() {
+            changed.extend(newly_changed);
+            quiet_since = Some(Instant::now());
+        } else if quiet_since.is_some_and(|since| since.elapsed() >= DEBOUNCE) {
+            return changed.into_iter().collect();
+        }
+    }
+}
+
+/// Watch the project sources, recompiling each change and the files that
+/// import it, and run `build_entry` whenever the entry module was affected.
+pub(super) fn watch_sources<F>(root: &Path, manifest: &Manifest, mut build_entry: F) -> Result<()>
+where
+    F: FnMut() -> Result<()>,
+{
+    let entry = root.join(&manifest.package.entry);
+    let mut last = snapshot(root, manifest);
+    let mut build = IncrementalBuild::new(root, last.keys().cloned().collect());
+
+    // Seed the cache so the first change only recompiles what it affects
+    let all: Vec<PathBuf> = last.keys().cloned().collect();
+    report(root, &build.rebuild(&all, compile_source));
+
+    loop {
+        println!();
+        print_info("Waiting for changes... (press Ctrl-C to stop)");
+        let changed = wait_for_changes(root, manifest, &mut last);
+        let builds = build.rebuild(&changed, compile_source);
+        if builds.is_empty() {
+            continue;
+        }
+        let failed = report(root, &builds);
+        if !failed && builds.iter().any(|file| file.path == entry) {
+            if let Err(e) = build_entry() {
+                print_error(&format!("{:#}", e));
+            }
+        }
+    }
+}
+
+/// Print per-file timings, returning whether any file failed.
+fn report(root: &Path, builds: &[FileBuild]) -> bool {
+    let mut failed = false;
+    for file in builds {
+        let display = file.path.strip_prefix(root).unwrap_or(&file.path).display();
+        let timing = format!("({:.1?})", file.elapsed).dimmed();
+        match &file.result {
+            Ok(()) => print_success(&format!("{} {}", display, timing)),
+            Err(e) => {
+                failed = true;
+                print_error(&format!("{} {}: {}", display, timing, e));
+            }
+        }
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn write(dir: &Path, name: &str, source: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn a_changed_file_recompiles_only_itself_and_its_importers() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let util = write(
+            root,
+            "util.rl",
+            "export fun double: (x: Int32) -> Int32 = { x * 2 }\n",
+        );
+        let main = write(
+            root,
+            "main.rl",
+            "import util.{double}\nfun main: () -> Int32 = { (21) double }\n",
+        );
+        let other = write(root, "other.rl", "fun other: () -> Int32 = { 1 }\n");
+
+        let compiled = RefCell::new(Vec::new());
+        let compile = |path: &Path| {
+            compiled.borrow_mut().push(path.to_path_buf());
+            Ok(())
+        };
+        let mut build =
+            IncrementalBuild::new(root, vec![main.clone(), other.clone(), util.clone()]);
+        build.rebuild(&[main.clone(), other.clone(), util.clone()], compile);
+        compiled.borrow_mut().clear();
+
+        write(root, "other.rl", "fun other: () -> Int32 = { 2 }\n");
+        let builds = build.rebuild(std::slice::from_ref(&other), compile);
+        assert_eq!(*compiled.borrow(), vec![other.clone()]);
+        assert_eq!(builds.len(), 1);
+        assert!(builds[0].result.is_ok());
+        compiled.borrow_mut().clear();
+
+        write(
+            root,
+            "util.rl",
+            "export fun double: (x: Int32) -> Int32 = { x + x }\n",
+        );
+        build.rebuild(std::slice::from_ref(&util), compile);
+        assert_eq!(*compiled.borrow(), vec![main, util]);
+        compiled.borrow_mut().clear();
+
+        // Saving without edits does not recompile anything
+        build.rebuild(std::slice::from_ref(&other), compile);
+        assert!(compiled.borrow().is_empty());
+    }
+}
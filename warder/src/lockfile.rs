@@ -0,0 +1,440 @@
+use crate::cage::update_hash_field;
+use crate::manifest::{Build, Dependency, Manifest};
+use crate::registry::DEFAULT_REGISTRY_URL;
+use crate::vault::{cached_git_commit, cached_git_path, cached_wit_path, GitReference, LockSource};
+use crate::wit::{parse_wit, WitFunction};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// File name of the lockfile, written next to `package.rl.toml`.
+pub const LOCKFILE_NAME: &str = "package.rl.lock";
+
+const LOCK_HASH_FORMAT_VERSION: &str = "warder.lock.content.v1";
+
+/// Exact resolution of every manifest dependency, used by `--repro` builds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default)]
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub version: String,
+    pub source: LockSource,
+    /// SHA-256 over the dependency's content. Local paths, local WASM/WIT
+    /// files, and checked-out Git dependencies are hashed by content; other
+    /// remote sources are hashed by their pinned specification until they
+    /// are fetched.
+    pub hash: String,
+    /// Functions exported by a foreign dependency's WIT interface, read from
+    /// the local file or the copy cached by `warder add`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exports: Vec<WitFunction>,
+}
+
+impl Lockfile {
+    pub fn new() -> Self {
+        Self {
+            version: 1,
+            packages: BTreeMap::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lockfile from {:?}", path))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse lockfile from {:?}", path))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).context("Failed to serialize lockfile")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write lockfile to {:?}", path))
+    }
+
+    /// Resolve the manifest's dependencies against the project at `root`.
+    pub fn resolve(root: &Path, manifest: &Manifest) -> Result<Self> {
+        let mut lockfile = Self::new();
+        for (name, dep) in &manifest.dependencies {
+            let package = resolve_dependency(root, name, dep)
+                .with_context(|| format!("Failed to resolve dependency '{}'", name))?;
+            lockfile.packages.insert(name.clone(), package);
+        }
+        Ok(lockfile)
+    }
+
+    /// Describe every way `resolved` differs from this lock, sorted by
+    /// dependency name. An empty result means the lock is up to date.
+    pub fn differences(&self, resolved: &Lockfile) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        for (name, locked) in &self.packages {
+            let Some(current) = resolved.packages.get(name) else {
+                differences.push(format!(
+                    "Dependency '{}' is locked but no longer declared",
+                    name
+                ));
+                continue;
+            };
+
+            if locked.source != current.source {
+                differences.push(format!("Dependency '{}' source changed", name));
+            }
+            if locked.version != current.version {
+                differences.push(format!(
+                    "Dependency '{}' version changed: locked {}, resolved {}",
+                    name, locked.version, current.version
+                ));
+            }
+            if locked.hash != current.hash {
+                differences.push(format!(
+                    "Dependency '{}' content hash mismatch: locked {}, resolved {}",
+                    name, locked.hash, current.hash
+                ));
+            }
+        }
+
+        for name in resolved.packages.keys() {
+            if !self.packages.contains_key(name) {
+                differences.push(format!(
+                    "Dependency '{}' is missing from {}",
+                    name, LOCKFILE_NAME
+                ));
+            }
+        }
+
+        differences.sort();
+        differences
+    }
+
+    /// Describe what replacing this lock with `resolved` changes, one line
+    /// per added, removed, or re-resolved dependency, sorted by name.
+    pub fn changes(&self, resolved: &Lockfile) -> Vec<String> {
+        let names: BTreeSet<&String> = self
+            .packages
+            .keys()
+            .chain(resolved.packages.keys())
+            .collect();
+        names
+            .into_iter()
+            .filter_map(
+                |name| match (self.packages.get(name), resolved.packages.get(name)) {
+                    (None, Some(new)) => Some(format!("Locking {} {}", name, new.version)),
+                    (Some(old), None) => Some(format!("Removing {} {}", name, old.version)),
+                    (Some(old), Some(new)) if old.version != new.version => Some(format!(
+                        "Updating {} {} -> {}",
+                        name, old.version, new.version
+                    )),
+                    (Some(old), Some(new)) if old != new => {
+                        Some(format!("Updating {} {}", name, new.version))
+                    }
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+}
+
+fn resolve_dependency(root: &Path, name: &str, dep: &Dependency) -> Result<LockedPackage> {
+    let mut hasher = Sha256::new();
+    update_hash_field(&mut hasher, "format", LOCK_HASH_FORMAT_VERSION.as_bytes());
+    update_hash_field(&mut hasher, "name", name.as_bytes());
+
+    let mut exports = Vec::new();
+    let (version, source) = match dep {
+        Dependency::Version(version) => {
+            update_hash_field(&mut hasher, "registry.version", version.as_bytes());
+            (
+                version.clone(),
+                LockSource::Registry {
+                    url: DEFAULT_REGISTRY_URL.to_string(),
+                },
+            )
+        }
+        Dependency::Local { path } => {
+            let dep_root = root.join(path);
+            hash_path_content(&mut hasher, &dep_root, &build_output_dir(&dep_root))?;
+            let manifest_path = dep_root.join("package.rl.toml");
+            let version = if manifest_path.exists() {
+                Manifest::load(&manifest_path)?.package.version
+            } else {
+                "0.0.0".to_string()
+            };
+            (version, LockSource::Path { path: path.clone() })
+        }
+        Dependency::Git {
+            git,
+            branch,
+            tag,
+            rev,
+        } => {
+            let reference = GitReference::new(branch.as_ref(), tag.as_ref(), rev.as_ref());
+            update_hash_field(&mut hasher, "git.url", git.as_bytes());
+            // A checked-out dependency is pinned to its commit and hashed by
+            // content; until then, by the requested ref.
+            let rev = match cached_git_commit(root, name, git) {
+                Some(commit) => {
+                    update_hash_field(&mut hasher, "git.commit", commit.as_bytes());
+                    let checkout = cached_git_path(root, name);
+                    hash_path_content(&mut hasher, &checkout, &build_output_dir(&checkout))?;
+                    commit
+                }
+                None => {
+                    update_hash_field(&mut hasher, "git.rev", reference.name().as_bytes());
+                    reference.name().to_string()
+                }
+            };
+            (
+                reference.version().to_string(),
+                LockSource::Git {
+                    url: git.clone(),
+                    rev,
+                },
+            )
+        }
+        Dependency::Foreign { wasm, wit } => {
+            for (label, location) in [("foreign.wasm", wasm), ("foreign.wit", wit)] {
+                let path = root.join(location);
+                if path.is_file() {
+                    let content = std::fs::read(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?;
+                    update_hash_field(&mut hasher, label, &content);
+                } else {
+                    update_hash_field(&mut hasher, label, location.as_bytes());
+                }
+            }
+            exports = foreign_exports(root, name, wit)?;
+            (
+                "0.0.0".to_string(),
+                LockSource::Foreign {
+                    wasm: wasm.clone(),
+                    wit: wit.clone(),
+                },
+            )
+        }
+    };
+
+    Ok(LockedPackage {
+        version,
+        source,
+        hash: hex::encode(hasher.finalize()),
+        exports,
+    })
+}
+
+/// Exported functions of a foreign dependency's WIT interface. A WIT that is
+/// neither a local file nor cached yet records no exports.
+fn foreign_exports(root: &Path, name: &str, wit: &str) -> Result<Vec<WitFunction>> {
+    let local = root.join(wit);
+    let path = if local.is_file() {
+        local
+    } else {
+        let cached = cached_wit_path(root, name);
+        if !cached.is_file() {
+            return Ok(Vec::new());
+        }
+        cached
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let document =
+        parse_wit(&content).with_context(|| format!("Invalid WIT interface {}", path.display()))?;
+    Ok(document.exports)
+}
+
+/// Build output directory of the package at `root`: its manifest's
+/// `build.output`, or the default when it has no readable manifest.
+fn build_output_dir(root: &Path) -> PathBuf {
+    let output = Manifest::load(&root.join("package.rl.toml"))
+        .map(|manifest| manifest.build.output)
+        .unwrap_or_else(|_| Build::default().output);
+    root.join(output)
+}
+
+/// Hash a file, or every file under a directory in path order. The build
+/// `output` directory, the cache, and lockfiles are skipped so building a
+/// path dependency does not change its hash. A missing path hashes as empty;
+/// `warder doctor` reports it.
+fn hash_path_content(hasher: &mut Sha256, path: &Path, output: &Path) -> Result<()> {
+    let mut entries = Vec::new();
+    for entry in WalkDir::new(path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.path() != output
+                && !matches!(
+                    entry.file_name().to_str(),
+                    Some(".restrict-cache" | ".git" | LOCKFILE_NAME)
+                )
+        })
+    {
+        let Ok(entry) = entry else { continue };
+        if entry.file_type().is_file() {
+            entries.push(entry.into_path());
+        }
+    }
+
+    for entry in entries {
+        let relative = entry.strip_prefix(path).unwrap_or(&entry);
+        let content =
+            std::fs::read(&entry).with_context(|| format!("Failed to read {}", entry.display()))?;
+        update_hash_field(
+            hasher,
+            "path",
+            relative.to_string_lossy().replace('\\', "/").as_bytes(),
+        );
+        update_hash_field(hasher, "content", &content);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::PackageKind;
+    use tempdir::TempDir;
+
+    #[test]
+    fn local_dependency_hash_tracks_content() {
+        let dir = TempDir::new("warder-lock-local").unwrap();
+        std::fs::create_dir_all(dir.path().join("lib/src")).unwrap();
+        std::fs::write(
+            dir.path().join("lib/src/lib.rl"),
+            "export fun one: () -> Int32 = { 1 }\n",
+        )
+        .unwrap();
+
+        let mut manifest = Manifest::new("app", PackageKind::Bin);
+        manifest.add_dependency(
+            "lib".to_string(),
+            Dependency::Local {
+                path: "lib".to_string(),
+            },
+        );
+
+        let locked = Lockfile::resolve(dir.path(), &manifest).unwrap();
+        assert!(locked
+            .differences(&Lockfile::resolve(dir.path(), &manifest).unwrap())
+            .is_empty());
+
+        std::fs::write(
+            dir.path().join("lib/src/lib.rl"),
+            "export fun one: () -> Int32 = { 2 }\n",
+        )
+        .unwrap();
+        let changed = locked.differences(&Lockfile::resolve(dir.path(), &manifest).unwrap());
+        assert_eq!(changed.len(), 1);
+        assert!(changed[0].starts_with("Dependency 'lib' content hash mismatch"));
+    }
+
+    #[test]
+    fn local_dependency_hash_skips_its_build_output() {
+        let dir = TempDir::new("warder-lock-output").unwrap();
+        std::fs::create_dir_all(dir.path().join("lib/src")).unwrap();
+        std::fs::write(
+            dir.path().join("lib/package.rl.toml"),
+            "[package]\nname = \"lib\"\nversion = \"0.1.0\"\nentry = \"src/lib.rl\"\nedition = \"2025\"\n\n[build]\noutput = \"out/\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("lib/src/lib.rl"),
+            "export fun one: () -> Int32 = { 1 }\n",
+        )
+        .unwrap();
+
+        let mut manifest = Manifest::new("app", PackageKind::Bin);
+        manifest.add_dependency(
+            "lib".to_string(),
+            Dependency::Local {
+                path: "lib".to_string(),
+            },
+        );
+        let locked = Lockfile::resolve(dir.path(), &manifest).unwrap();
+
+        std::fs::create_dir_all(dir.path().join("lib/out")).unwrap();
+        std::fs::write(dir.path().join("lib/out/lib-0.1.0.wasm"), b"\0asm").unwrap();
+        assert!(locked
+            .differences(&Lockfile::resolve(dir.path(), &manifest).unwrap())
+            .is_empty());
+
+        // `dist` is only the default output, not a skipped name.
+        std::fs::create_dir_all(dir.path().join("lib/dist")).unwrap();
+        std::fs::write(dir.path().join("lib/dist/notes.txt"), "kept").unwrap();
+        assert_eq!(
+            locked
+                .differences(&Lockfile::resolve(dir.path(), &manifest).unwrap())
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn changes_describe_locked_additions_removals_and_updates() {
+        let package = |version: &str, hash: &str| LockedPackage {
+            version: version.to_string(),
+            source: LockSource::Path {
+                path: "lib".to_string(),
+            },
+            hash: hash.to_string(),
+            exports: Vec::new(),
+        };
+        let mut old = Lockfile::new();
+        old.packages
+            .insert("gone".to_string(), package("1.0.0", "a"));
+        old.packages
+            .insert("bumped".to_string(), package("1.0.0", "a"));
+        old.packages
+            .insert("edited".to_string(), package("1.0.0", "a"));
+        old.packages
+            .insert("same".to_string(), package("1.0.0", "a"));
+        let mut new = old.clone();
+        new.packages.remove("gone");
+        new.packages
+            .insert("bumped".to_string(), package("1.1.0", "b"));
+        new.packages
+            .insert("edited".to_string(), package("1.0.0", "b"));
+        new.packages
+            .insert("added".to_string(), package("0.2.0", "c"));
+
+        assert_eq!(
+            old.changes(&new),
+            [
+                "Locking added 0.2.0",
+                "Updating bumped 1.0.0 -> 1.1.0",
+                "Updating edited 1.0.0",
+                "Removing gone 1.0.0",
+            ]
+        );
+        assert!(new.changes(&new).is_empty());
+    }
+
+    #[test]
+    fn lockfile_round_trips() {
+        let mut lockfile = Lockfile::new();
+        lockfile.packages.insert(
+            "json".to_string(),
+            LockedPackage {
+                version: "v1.0.0".to_string(),
+                source: LockSource::Git {
+                    url: "https://example.com/json.git".to_string(),
+                    rev: "v1.0.0".to_string(),
+                },
+                hash: "abc".to_string(),
+                exports: Vec::new(),
+            },
+        );
+
+        let content = toml::to_string_pretty(&lockfile).unwrap();
+        let loaded: Lockfile = toml::from_str(&content).unwrap();
+        assert_eq!(loaded, lockfile);
+    }
+}
@@ -3,11 +3,14 @@ use clap::{Parser, Subcommand};
 
 mod cage;
 mod commands;
+mod lockfile;
 mod manifest;
 mod registry;
 mod vault;
+mod wit;
 
 use commands::*;
+use vault::GitReference;
 
 #[derive(Parser)]
 #[command(name = "warder")]
@@ -24,6 +27,9 @@ enum Commands {
     New {
         /// Project name
         name: String,
+        /// Create a library package with exported functions instead of a `main`
+        #[arg(long)]
+        lib: bool,
     },
 
     /// Initialize a Restrict Language project in current directory
@@ -39,6 +45,15 @@ enum Commands {
         /// Git repository URL
         #[arg(long)]
         git: Option<String>,
+        /// Git branch to check out
+        #[arg(long, requires = "git", conflicts_with_all = ["tag", "rev"])]
+        branch: Option<String>,
+        /// Git tag to check out
+        #[arg(long, requires = "git", conflicts_with = "rev")]
+        tag: Option<String>,
+        /// Git commit to check out
+        #[arg(long, requires = "git")]
+        rev: Option<String>,
         /// WASM module URL
         #[arg(long)]
         wasm: Option<String>,
@@ -64,17 +79,29 @@ enum Commands {
         /// Build as WASM Component
         #[arg(long)]
         component: bool,
-        /// Verify signatures of dependencies
+        /// Validate the generated Wasm and verify signatures of dependencies
         #[arg(long)]
         verify: bool,
-        /// Reproducible build
-        #[arg(long, alias = "deterministic")]
+        /// Reproducible build: fail if dependency resolution differs from
+        /// package.rl.lock instead of updating it
+        #[arg(long, visible_alias = "locked", alias = "deterministic")]
         repro: bool,
+        /// Host environment: `wasi`, or `freestanding` for no WASI imports
+        #[arg(long, default_value = "wasi", value_parser = ["wasi", "freestanding"])]
+        target: String,
+    },
+
+    /// Remove build output; with `--all`, also the dependency cache
+    Clean {
+        /// Also remove downloaded and checked-out dependencies
+        #[arg(long)]
+        all: bool,
     },
 
     /// Build and run the project
     Run {
-        /// Arguments to pass to the program
+        /// Arguments to pass to the program, usually after `--`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
 
@@ -84,11 +111,23 @@ enum Commands {
         filter: Option<String>,
     },
 
+    /// Time `bench_*` functions under `benches/`
+    Bench {
+        /// Bench filter
+        filter: Option<String>,
+        /// Runs per benchmark, each in a fresh instance
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: u32,
+    },
+
     /// Publish a package to WardHub
     Publish {
         /// Registry URL
         #[arg(long)]
         registry: Option<String>,
+        /// Build and verify the package, then print its cage hash without uploading
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Wrap external WASM into a Cage
@@ -130,8 +169,8 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::New { name } => {
-            new_project(&name)?;
+        Commands::New { name, lib } => {
+            new_project(&name, lib)?;
         }
         Commands::Init => {
             init_project()?;
@@ -140,10 +179,14 @@ async fn main() -> Result<()> {
             dep,
             path,
             git,
+            branch,
+            tag,
+            rev,
             wasm,
             wit,
         } => {
-            add_dependency(&dep, path, git, wasm, wit).await?;
+            let reference = GitReference::new(branch.as_ref(), tag.as_ref(), rev.as_ref());
+            add_dependency(&dep, path, git, reference, wasm, wit).await?;
         }
         Commands::Remove { name } => {
             remove_dependency(&name)?;
@@ -154,8 +197,12 @@ async fn main() -> Result<()> {
             component,
             verify,
             repro,
+            target,
         } => {
-            build_project(release, watch, component, verify, repro).await?;
+            build_project(release, watch, component, verify, repro, &target).await?;
+        }
+        Commands::Clean { all } => {
+            clean_project(all)?;
         }
         Commands::Run { args } => {
             run_project(args).await?;
@@ -163,8 +210,11 @@ async fn main() -> Result<()> {
         Commands::Test { filter } => {
             test_project(filter).await?;
         }
-        Commands::Publish { registry } => {
-            publish_package(registry).await?;
+        Commands::Bench { filter, iterations } => {
+            bench_project(filter, iterations).await?;
+        }
+        Commands::Publish { registry, dry_run } => {
+            publish_package(registry, dry_run).await?;
         }
         Commands::Wrap {
             wasm,
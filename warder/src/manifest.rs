@@ -18,10 +18,26 @@ pub struct Package {
     pub version: String,
     pub entry: String,
     pub edition: String,
+    #[serde(default)]
+    pub kind: PackageKind,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub authors: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// SPDX license expression; required by `warder publish`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+}
+
+/// Whether a package builds a runnable program or a library of exports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageKind {
+    /// Program with a `main` entry point, exported as `_start`.
+    #[default]
+    Bin,
+    /// Library exposing `export fun` items, with no `_start`.
+    Lib,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,10 +47,16 @@ pub enum Dependency {
     Local {
         path: String,
     },
+    /// A Git repository pinned to at most one of `branch`, `tag`, or `rev`;
+    /// with none, the remote's default branch.
     Git {
         git: String,
         #[serde(skip_serializing_if = "Option::is_none")]
+        branch: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         tag: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rev: Option<String>,
     },
     Foreign {
         wasm: String,
@@ -75,15 +97,21 @@ fn default_output() -> String {
 }
 
 impl Manifest {
-    pub fn new(name: &str) -> Self {
+    pub fn new(name: &str, kind: PackageKind) -> Self {
+        let entry = match kind {
+            PackageKind::Bin => "src/main.rl",
+            PackageKind::Lib => "src/lib.rl",
+        };
         Self {
             package: Package {
                 name: name.to_string(),
                 version: "0.1.0".to_string(),
-                entry: "src/main.rl".to_string(),
+                entry: entry.to_string(),
                 edition: "2025".to_string(),
+                kind,
                 authors: None,
                 description: None,
+                license: None,
             },
             dependencies: HashMap::new(),
             build: Build::default(),
@@ -140,3 +168,35 @@ impl CageManifest {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn library_manifest_round_trips() {
+        let manifest = Manifest::new("shapes", PackageKind::Lib);
+        let content = toml::to_string_pretty(&manifest).unwrap();
+        assert!(content.contains("kind = \"lib\""));
+        assert!(content.contains("entry = \"src/lib.rl\""));
+
+        let loaded: Manifest = toml::from_str(&content).unwrap();
+        assert_eq!(loaded.package.kind, PackageKind::Lib);
+        assert_eq!(loaded.package.entry, "src/lib.rl");
+        assert_eq!(loaded.package.name, "shapes");
+    }
+
+    #[test]
+    fn manifest_without_kind_defaults_to_bin() {
+        let loaded: Manifest = toml::from_str(
+            r#"[package]
+name = "app"
+version = "0.1.0"
+entry = "src/main.rl"
+edition = "2025"
+"#,
+        )
+        .unwrap();
+        assert_eq!(loaded.package.kind, PackageKind::Bin);
+    }
+}
@@ -1,8 +1,49 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use url::Url;
 
+/// WardHub, used when a dependency or `publish` names no registry.
+pub const DEFAULT_REGISTRY_URL: &str = "https://wardhub.restrict-lang.org";
+
+/// Read a WIT interface from an `http(s)://` or `file://` URL, or from a
+/// path relative to the project root.
+pub async fn fetch_wit(root: &Path, location: &str) -> Result<String> {
+    let url = match Url::parse(location) {
+        Ok(url) if matches!(url.scheme(), "http" | "https" | "file") => url,
+        // Plain paths, including Windows drive letters that parse as schemes.
+        _ => {
+            let path = root.join(location);
+            return std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read WIT interface {}", path.display()));
+        }
+    };
+
+    if url.scheme() == "file" {
+        let path = url
+            .to_file_path()
+            .map_err(|_| anyhow::anyhow!("Invalid file URL: {}", location))?;
+        return std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read WIT interface {}", path.display()));
+    }
+
+    let response = reqwest::get(url)
+        .await
+        .with_context(|| format!("Failed to fetch WIT interface {}", location))?;
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to fetch WIT interface {}: {}",
+            location,
+            response.status()
+        );
+    }
+    response
+        .text()
+        .await
+        .with_context(|| format!("Failed to read WIT interface {}", location))
+}
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct Registry {
@@ -1,7 +1,20 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory, relative to the project root, that holds everything warder
+/// downloads or checks out for dependencies.
+pub const CACHE_DIR: &str = ".restrict-cache";
+
+/// Directory, relative to the project root, that caches fetched WIT interfaces.
+pub const WIT_CACHE_DIR: &str = ".restrict-cache/wit";
+
+/// Directory, relative to the project root, that holds a clone of each Git
+/// dependency, checked out at its pinned commit.
+pub const GIT_CACHE_DIR: &str = ".restrict-cache/git";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum VisitState {
@@ -24,13 +37,195 @@ pub struct PackageLock {
     pub dependencies: HashMap<String, String>, // name -> version
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum LockSource {
-    Registry { url: String },
-    Path { path: String },
-    Git { url: String, rev: String },
-    Cage { path: String },
+    Registry {
+        url: String,
+    },
+    Path {
+        path: String,
+    },
+    /// `rev` is the resolved commit once the dependency is checked out, and
+    /// the requested ref until then.
+    Git {
+        url: String,
+        rev: String,
+    },
+    Cage {
+        path: String,
+    },
+    Foreign {
+        wasm: String,
+        wit: String,
+    },
+}
+
+/// Location of the cached WIT interface for foreign dependency `name`.
+pub fn cached_wit_path(root: &Path, name: &str) -> PathBuf {
+    root.join(WIT_CACHE_DIR).join(format!("{}.wit", name))
+}
+
+/// Cache a fetched WIT interface so lockfile resolution and builds can read
+/// it without fetching again.
+pub fn cache_wit(root: &Path, name: &str, content: &str) -> Result<PathBuf> {
+    let path = cached_wit_path(root, name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create WIT cache at {:?}", parent))?;
+    }
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write cached WIT to {:?}", path))?;
+    Ok(path)
+}
+
+/// The ref a Git dependency is pinned to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitReference {
+    Branch(String),
+    Tag(String),
+    Rev(String),
+    /// The remote's default branch.
+    DefaultBranch,
+}
+
+impl GitReference {
+    /// Build the reference from a manifest entry's `branch`, `tag`, and `rev`
+    /// fields, of which at most one is set.
+    pub fn new(branch: Option<&String>, tag: Option<&String>, rev: Option<&String>) -> Self {
+        match (branch, tag, rev) {
+            (Some(branch), _, _) => Self::Branch(branch.clone()),
+            (_, Some(tag), _) => Self::Tag(tag.clone()),
+            (_, _, Some(rev)) => Self::Rev(rev.clone()),
+            _ => Self::DefaultBranch,
+        }
+    }
+
+    /// The requested ref as written, or `HEAD` for the default branch.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Branch(name) | Self::Tag(name) | Self::Rev(name) => name,
+            Self::DefaultBranch => "HEAD",
+        }
+    }
+
+    /// Version recorded for the dependency: the requested ref, or `latest`
+    /// for the default branch.
+    pub fn version(&self) -> &str {
+        match self {
+            Self::DefaultBranch => "latest",
+            reference => reference.name(),
+        }
+    }
+
+    /// Revision expression naming the commit in a clone of the remote.
+    fn commit_spec(&self) -> String {
+        match self {
+            Self::Branch(branch) => format!("refs/remotes/origin/{}^{{commit}}", branch),
+            Self::Tag(tag) => format!("refs/tags/{}^{{commit}}", tag),
+            Self::Rev(rev) => format!("{}^{{commit}}", rev),
+            Self::DefaultBranch => "refs/remotes/origin/HEAD^{commit}".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for GitReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Branch(branch) => write!(f, "branch '{}'", branch),
+            Self::Tag(tag) => write!(f, "tag '{}'", tag),
+            Self::Rev(rev) => write!(f, "revision '{}'", rev),
+            Self::DefaultBranch => write!(f, "default branch"),
+        }
+    }
+}
+
+/// A Git dependency checked out in the cache.
+#[derive(Debug)]
+pub struct GitCheckout {
+    pub commit: String,
+    /// The clone already existed and was fetched instead of cloned again.
+    pub reused: bool,
+}
+
+/// Location of the cached clone of Git dependency `name`.
+pub fn cached_git_path(root: &Path, name: &str) -> PathBuf {
+    root.join(GIT_CACHE_DIR).join(name)
+}
+
+/// Clone `url` into the cache, or fetch into the clone already there, and
+/// check out the commit `reference` names.
+pub fn checkout_git(
+    root: &Path,
+    name: &str,
+    url: &str,
+    reference: &GitReference,
+) -> Result<GitCheckout> {
+    let path = cached_git_path(root, name);
+    let reused = cached_origin(&path).as_deref() == Some(url);
+    if reused {
+        run_git(&path, &["fetch", "--quiet", "--tags", "--force", "origin"])
+            .with_context(|| format!("Failed to fetch {}", url))?;
+    } else {
+        if path.exists() {
+            std::fs::remove_dir_all(&path)
+                .with_context(|| format!("Failed to clear stale Git cache at {:?}", path))?;
+        }
+        let parent = root.join(GIT_CACHE_DIR);
+        std::fs::create_dir_all(&parent)
+            .with_context(|| format!("Failed to create Git cache at {:?}", parent))?;
+        run_git(&parent, &["clone", "--quiet", "--no-checkout", url, name])
+            .with_context(|| format!("Failed to clone {}", url))?;
+    }
+
+    let commit = run_git(
+        &path,
+        &["rev-parse", "--verify", "--quiet", &reference.commit_spec()],
+    )
+    .map_err(|_| anyhow!("Git {} not found in {}", reference, url))?;
+    run_git(
+        &path,
+        &["checkout", "--quiet", "--force", "--detach", &commit],
+    )
+    .with_context(|| format!("Failed to check out {} of {}", reference, url))?;
+
+    Ok(GitCheckout { commit, reused })
+}
+
+/// Commit checked out in the cached clone of `name`, if `url` has been
+/// fetched there.
+pub fn cached_git_commit(root: &Path, name: &str, url: &str) -> Option<String> {
+    let path = cached_git_path(root, name);
+    if cached_origin(&path).as_deref() != Some(url) {
+        return None;
+    }
+    run_git(&path, &["rev-parse", "--verify", "--quiet", "HEAD"]).ok()
+}
+
+fn cached_origin(path: &Path) -> Option<String> {
+    if !path.join(".git").is_dir() {
+        return None;
+    }
+    run_git(path, &["config", "--get", "remote.origin.url"]).ok()
+}
+
+/// Run `git` in `dir` without prompting for credentials, returning its
+/// trimmed standard output.
+fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .context("Failed to run git; is it installed?")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
 impl Vault {
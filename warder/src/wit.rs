@@ -0,0 +1,377 @@
+//! WIT (WebAssembly Interface Types) reader.
+//!
+//! Foreign dependencies describe a wrapped module with a WIT package.
+//! Parsing and name resolution are done by `wit-parser`, so types are scoped
+//! per interface and must be brought in with `use`; this module only
+//! collects the exported functions, with their types rendered back as WIT,
+//! so calls into the wrapped module can be checked against them.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use wit_parser::{Handle, Resolve, Type, TypeDefKind, WorldItem, WorldKey};
+
+/// A function exported by a WIT document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitFunction {
+    /// Interface that declares the function; `None` for functions exported
+    /// directly from a world.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub params: Vec<WitParam>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+impl fmt::Display for WitFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(interface) = &self.interface {
+            write!(f, "{}.", interface)?;
+        }
+        write!(f, "{}: func(", self.name)?;
+        for (index, param) in self.params.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", param.name, param.ty)?;
+        }
+        write!(f, ")")?;
+        if let Some(result) = &self.result {
+            write!(f, " -> {}", result)?;
+        }
+        Ok(())
+    }
+}
+
+impl WitFunction {
+    /// The compiler's `--foreign` signature for calling this function, an
+    /// export of dependency `dependency`. World functions are imported from
+    /// the dependency's module and interface functions from
+    /// `dependency/interface`. Fails with the first type that has no
+    /// Restrict scalar equivalent.
+    pub fn foreign_signature(&self, dependency: &str) -> Result<String, String> {
+        let params = self
+            .params
+            .iter()
+            .map(|param| restrict_scalar(&param.ty))
+            .collect::<Result<Vec<_>, _>>()?;
+        let module = match &self.interface {
+            Some(interface) => format!("{}/{}", dependency, interface),
+            None => dependency.to_string(),
+        };
+        let mut signature = format!("{}.{}=({})", module, self.name, params.join(", "));
+        if let Some(result) = &self.result {
+            signature.push_str(&format!(" -> {}", restrict_scalar(result)?));
+        }
+        Ok(signature)
+    }
+}
+
+/// Restrict type a WIT scalar is passed as.
+fn restrict_scalar(ty: &str) -> Result<&'static str, String> {
+    match ty {
+        "s32" => Ok("Int32"),
+        "s64" => Ok("Int64"),
+        "f64" => Ok("Float64"),
+        "bool" => Ok("Boolean"),
+        "char" => Ok("Char"),
+        _ => Err(format!("WIT type '{}' has no Restrict equivalent", ty)),
+    }
+}
+
+/// A parsed WIT document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WitDocument {
+    pub package: Option<String>,
+    pub exports: Vec<WitFunction>,
+}
+
+/// A malformed WIT document, with the 1-based position of the problem.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}, column {column}: {message}")]
+pub struct WitError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Parse a WIT document and collect its exported function signatures:
+/// every function of the package's interfaces, followed by the functions
+/// and inline interfaces its worlds export.
+pub fn parse_wit(source: &str) -> Result<WitDocument, WitError> {
+    let mut resolve = Resolve::default();
+    let package_id = resolve
+        .push_str(WIT_SOURCE_NAME, source)
+        .map_err(|err| wit_error(&err))?;
+    let package = &resolve.packages[package_id];
+
+    let mut exports = Vec::new();
+    for (interface_name, interface_id) in &package.interfaces {
+        let interface = &resolve.interfaces[*interface_id];
+        for function in interface.functions.values() {
+            exports.push(wit_function(&resolve, Some(interface_name), function));
+        }
+    }
+    for world_id in package.worlds.values() {
+        for (key, item) in &resolve.worlds[*world_id].exports {
+            let WorldKey::Name(name) = key else {
+                // Named interfaces were collected above.
+                continue;
+            };
+            match item {
+                WorldItem::Function(function) => {
+                    exports.push(wit_function(&resolve, None, function));
+                }
+                WorldItem::Interface { id, .. } => {
+                    for function in resolve.interfaces[*id].functions.values() {
+                        exports.push(wit_function(&resolve, Some(name), function));
+                    }
+                }
+                WorldItem::Type(_) => {}
+            }
+        }
+    }
+
+    Ok(WitDocument {
+        package: Some(package.name.to_string()),
+        exports,
+    })
+}
+
+/// Path `wit-parser` reports positions against; see `wit_error`.
+const WIT_SOURCE_NAME: &str = "interface.wit";
+
+/// Turn a `wit-parser` error into a `WitError`. Its message is the first
+/// line of the report, and the position comes from the `--> file:line:col`
+/// line that follows when the error points into the source.
+fn wit_error(err: &anyhow::Error) -> WitError {
+    let report = format!("{:#}", err);
+    let message = report.lines().next().unwrap_or_default().trim().to_string();
+    let marker = format!("--> {}:", WIT_SOURCE_NAME);
+    let (line, column) = report
+        .lines()
+        .find_map(|line| {
+            let position = &line[line.find(&marker)? + marker.len()..];
+            let (line, column) = position.trim().split_once(':')?;
+            Some((line.parse().ok()?, column.parse().ok()?))
+        })
+        .unwrap_or((1, 1));
+    WitError {
+        line,
+        column,
+        message,
+    }
+}
+
+fn wit_function(
+    resolve: &Resolve,
+    interface: Option<&String>,
+    function: &wit_parser::Function,
+) -> WitFunction {
+    WitFunction {
+        interface: interface.cloned(),
+        name: function.name.clone(),
+        params: function
+            .params
+            .iter()
+            .map(|(name, ty)| WitParam {
+                name: name.clone(),
+                ty: render_type(resolve, ty),
+            })
+            .collect(),
+        result: function.result.as_ref().map(|ty| render_type(resolve, ty)),
+    }
+}
+
+/// Render `ty` as it is written in WIT: named types by name, anonymous ones
+/// structurally, with `, ` between type arguments.
+fn render_type(resolve: &Resolve, ty: &Type) -> String {
+    let id = match ty {
+        Type::Bool => return "bool".to_string(),
+        Type::U8 => return "u8".to_string(),
+        Type::U16 => return "u16".to_string(),
+        Type::U32 => return "u32".to_string(),
+        Type::U64 => return "u64".to_string(),
+        Type::S8 => return "s8".to_string(),
+        Type::S16 => return "s16".to_string(),
+        Type::S32 => return "s32".to_string(),
+        Type::S64 => return "s64".to_string(),
+        Type::F32 => return "f32".to_string(),
+        Type::F64 => return "f64".to_string(),
+        Type::Char => return "char".to_string(),
+        Type::String => return "string".to_string(),
+        Type::ErrorContext => return "error-context".to_string(),
+        Type::Id(id) => *id,
+    };
+
+    let def = &resolve.types[id];
+    if let Some(name) = &def.name {
+        return name.clone();
+    }
+    let render = |ty: &Type| render_type(resolve, ty);
+    let render_optional = |ty: &Option<Type>| ty.as_ref().map_or("_".to_string(), render);
+    match &def.kind {
+        TypeDefKind::List(element) => format!("list<{}>", render(element)),
+        TypeDefKind::FixedSizeList(element, len) => format!("list<{}, {}>", render(element), len),
+        TypeDefKind::Map(key, value) => format!("map<{}, {}>", render(key), render(value)),
+        TypeDefKind::Option(inner) => format!("option<{}>", render(inner)),
+        TypeDefKind::Result(result) => match (&result.ok, &result.err) {
+            (None, None) => "result".to_string(),
+            (Some(ok), None) => format!("result<{}>", render(ok)),
+            (ok, Some(err)) => format!("result<{}, {}>", render_optional(ok), render(err)),
+        },
+        TypeDefKind::Tuple(tuple) => format!(
+            "tuple<{}>",
+            tuple
+                .types
+                .iter()
+                .map(render)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        TypeDefKind::Handle(Handle::Own(resource)) => render(&Type::Id(*resource)),
+        TypeDefKind::Handle(Handle::Borrow(resource)) => {
+            format!("borrow<{}>", render(&Type::Id(*resource)))
+        }
+        TypeDefKind::Future(inner) => match inner {
+            Some(inner) => format!("future<{}>", render(inner)),
+            None => "future".to_string(),
+        },
+        TypeDefKind::Stream(inner) => match inner {
+            Some(inner) => format!("stream<{}>", render(inner)),
+            None => "stream".to_string(),
+        },
+        TypeDefKind::Type(alias) => render(alias),
+        kind => kind.as_str().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_interface_and_world_exports() {
+        let document = parse_wit(
+            r#"
+            package example:math@0.1.0;
+
+            interface ops {
+                record point { x: s32, y: s32 }
+                add: func(a: s32, b: s32) -> s32;
+                norm: func(p: point) -> f64;
+            }
+
+            world calculator {
+                import log: func(message: string);
+                export ops;
+                export version: func() -> string;
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(document.package.as_deref(), Some("example:math@0.1.0"));
+        let exports: Vec<String> = document.exports.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            exports,
+            [
+                "ops.add: func(a: s32, b: s32) -> s32",
+                "ops.norm: func(p: point) -> f64",
+                "version: func() -> string",
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_scalar_signatures_to_foreign_functions() {
+        let document = parse_wit(
+            "package example:math;\n\
+             interface ops { add: func(a: s32, b: s64) -> f64; name: func() -> string; }\n\
+             world calc { export ops; export ping: func(on: bool); }\n",
+        )
+        .unwrap();
+        let signatures: Vec<_> = document
+            .exports
+            .iter()
+            .map(|function| function.foreign_signature("math"))
+            .collect();
+        assert_eq!(
+            signatures,
+            [
+                Ok("math/ops.add=(Int32, Int64) -> Float64".to_string()),
+                Err("WIT type 'string' has no Restrict equivalent".to_string()),
+                Ok("math.ping=(Boolean)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn canonicalizes_generic_types() {
+        let document = parse_wit(
+            "package example:io;\ninterface io { read: func(len: u32) -> result<list<u8>,string>; }",
+        )
+        .unwrap();
+        assert_eq!(
+            document.exports[0].result.as_deref(),
+            Some("result<list<u8>, string>")
+        );
+    }
+
+    #[test]
+    fn reports_position_of_malformed_signature() {
+        let err = parse_wit(
+            "package example:math;\ninterface ops {\n    add: func(a: s32 b: s32) -> s32;\n}\n",
+        )
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (3, 22));
+        assert!(err.message.contains("expected"), "{}", err.message);
+    }
+
+    #[test]
+    fn rejects_unknown_types() {
+        let err =
+            parse_wit("package example:math;\ninterface ops {\n    get: func() -> widget;\n}\n")
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 20: name `widget` is not defined"
+        );
+    }
+
+    #[test]
+    fn scopes_types_to_their_interface() {
+        let source = "package example:math;\n\
+            interface types {\n    record point { x: s32, y: s32 }\n}\n\
+            interface ops {\n    origin: func() -> point;\n}\n";
+        let err = parse_wit(source).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 6, column 23: name `point` is not defined"
+        );
+
+        let document = parse_wit(
+            &source.replace("interface ops {", "interface ops {\n    use types.{point};"),
+        )
+        .unwrap();
+        assert_eq!(
+            document.exports[0].to_string(),
+            "ops.origin: func() -> point"
+        );
+    }
+
+    #[test]
+    fn rejects_unclosed_interface() {
+        let err =
+            parse_wit("package example:math;\ninterface ops {\n    ping: func();\n").unwrap_err();
+        assert!(err.message.contains("found eof"), "{}", err.message);
+    }
+}
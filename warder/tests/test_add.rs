@@ -0,0 +1,288 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempdir::TempDir;
+
+const MANIFEST: &str = r#"[package]
+name = "wit-fixture"
+version = "0.1.0"
+entry = "src/main.rl"
+edition = "2025"
+
+[dependencies]
+"#;
+
+fn write_project(root: &Path) {
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("package.rl.toml"), MANIFEST).unwrap();
+    fs::write(
+        root.join("src/main.rl"),
+        "fun main: () -> Int32 = {\n    42\n}\n",
+    )
+    .unwrap();
+}
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/wit")
+        .join(name)
+}
+
+fn warder() -> Command {
+    Command::cargo_bin("warder").unwrap()
+}
+
+#[test]
+fn add_wit_records_exported_signatures() {
+    let dir = TempDir::new("warder-add-wit").unwrap();
+    write_project(dir.path());
+    let wit_url = url::Url::from_file_path(fixture("valid.wit")).unwrap();
+
+    warder()
+        .args(["add", "geometry", "--wasm", "geometry.wasm", "--wit"])
+        .arg(wit_url.as_str())
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 exported functions"));
+
+    assert!(dir
+        .path()
+        .join(".restrict-cache/wit/geometry.wit")
+        .is_file());
+
+    let lock: toml::Table =
+        toml::from_str(&fs::read_to_string(dir.path().join("package.rl.lock")).unwrap()).unwrap();
+    let exports = lock["packages"]["geometry"]["exports"].as_array().unwrap();
+    let names: Vec<_> = exports
+        .iter()
+        .map(|export| export["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["distance", "centroid", "version"]);
+
+    let centroid = &exports[1];
+    assert_eq!(centroid["interface"].as_str(), Some("shapes"));
+    assert_eq!(centroid["params"][0]["type"].as_str(), Some("list<point>"));
+    assert_eq!(centroid["result"].as_str(), Some("option<point>"));
+}
+
+#[test]
+fn build_checks_calls_against_foreign_exports() {
+    let compiler = assert_cmd::cargo::cargo_bin("restrict_lang");
+    if !compiler.exists() {
+        eprintln!(
+            "skipping: restrict_lang compiler not built at {}",
+            compiler.display()
+        );
+        return;
+    }
+
+    let dir = TempDir::new("warder-build-foreign").unwrap();
+    write_project(dir.path());
+    fs::write(
+        dir.path().join("geometry.wit"),
+        "package example:geometry@0.1.0;\n\n\
+         interface shapes {\n    scale: func(a: s32, b: s32) -> s32;\n}\n\n\
+         world geometry {\n    export shapes;\n    export version: func() -> string;\n}\n",
+    )
+    .unwrap();
+    warder()
+        .args([
+            "add",
+            "geometry",
+            "--wasm",
+            "geometry.wasm",
+            "--wit",
+            "geometry.wit",
+        ])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    fs::write(
+        dir.path().join("src/main.rl"),
+        "fun main: () -> Int32 = {\n    (6, true) scale\n}\n",
+    )
+    .unwrap();
+    warder()
+        .arg("build")
+        .current_dir(dir.path())
+        .env("RESTRICT_LANG_BIN", &compiler)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Type mismatch"));
+
+    fs::write(
+        dir.path().join("src/main.rl"),
+        "fun main: () -> Int32 = {\n    (6, 7) scale\n}\n",
+    )
+    .unwrap();
+    warder()
+        .arg("build")
+        .current_dir(dir.path())
+        .env("RESTRICT_LANG_BIN", &compiler)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Skipping 'geometry' export version: func() -> string: WIT type 'string' has no Restrict equivalent",
+        ));
+
+    let wat = fs::read_to_string(dir.path().join("dist/wit-fixture-0.1.0.wat")).unwrap();
+    assert!(wat.contains(
+        "(import \"geometry/shapes\" \"scale\" (func $scale (param i32) (param i32) (result i32)))"
+    ));
+}
+
+#[test]
+fn add_wit_rejects_malformed_interfaces() {
+    let dir = TempDir::new("warder-add-bad-wit").unwrap();
+    write_project(dir.path());
+    fs::copy(fixture("invalid.wit"), dir.path().join("geometry.wit")).unwrap();
+
+    warder()
+        .args([
+            "add",
+            "geometry",
+            "--wasm",
+            "geometry.wasm",
+            "--wit",
+            "geometry.wit",
+        ])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid WIT interface 'geometry.wit': line 5, column 1: expected ';', found '}'",
+        ));
+
+    let manifest = fs::read_to_string(dir.path().join("package.rl.toml")).unwrap();
+    assert!(!manifest.contains("geometry"));
+    assert!(!dir.path().join("package.rl.lock").exists());
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args([
+            "-c",
+            "user.name=Fixture",
+            "-c",
+            "user.email=fixture@example.com",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// A bare repository whose `v1.0.0` tag is one commit behind `main`.
+/// Returns its `file://` URL and the tagged commit.
+fn bare_repo_fixture(root: &Path) -> (String, String) {
+    let work = root.join("json-work");
+    fs::create_dir_all(work.join("src")).unwrap();
+    git(&work, &["init", "--quiet", "--initial-branch=main"]);
+
+    fs::write(
+        work.join("src/lib.rl"),
+        "export fun one: () -> Int32 = { 1 }\n",
+    )
+    .unwrap();
+    git(&work, &["add", "."]);
+    git(&work, &["commit", "--quiet", "-m", "First release"]);
+    git(&work, &["tag", "v1.0.0"]);
+    let tagged = git(&work, &["rev-parse", "HEAD"]);
+
+    fs::write(
+        work.join("src/lib.rl"),
+        "export fun one: () -> Int32 = { 2 }\n",
+    )
+    .unwrap();
+    git(&work, &["commit", "--quiet", "-am", "Unreleased change"]);
+
+    let bare = root.join("json.git");
+    git(
+        root,
+        &["clone", "--quiet", "--bare", "json-work", "json.git"],
+    );
+    let url = url::Url::from_file_path(&bare).unwrap().to_string();
+    (url, tagged)
+}
+
+fn lockfile(root: &Path) -> toml::Table {
+    toml::from_str(&fs::read_to_string(root.join("package.rl.lock")).unwrap()).unwrap()
+}
+
+#[test]
+fn add_git_tag_records_the_tagged_commit() {
+    let dir = TempDir::new("warder-add-git").unwrap();
+    let (url, tagged) = bare_repo_fixture(dir.path());
+    let project = dir.path().join("app");
+    write_project(&project);
+
+    warder()
+        .args(["add", "json", "--tag", "v1.0.0", "--git", &url])
+        .current_dir(&project)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Checked out tag 'v1.0.0' of {} at {}",
+            url, tagged
+        )));
+
+    let manifest = fs::read_to_string(project.join("package.rl.toml")).unwrap();
+    assert!(manifest.contains("tag = \"v1.0.0\""), "{manifest}");
+    let source = &lockfile(&project)["packages"]["json"]["source"];
+    assert_eq!(source["type"].as_str(), Some("Git"));
+    assert_eq!(source["rev"].as_str(), Some(tagged.as_str()));
+    assert_eq!(
+        fs::read_to_string(project.join(".restrict-cache/git/json/src/lib.rl")).unwrap(),
+        "export fun one: () -> Int32 = { 1 }\n"
+    );
+
+    // Adding the dependency again after removing it fetches into the cached
+    // clone instead of cloning the repository again.
+    warder()
+        .args(["remove", "json"])
+        .current_dir(&project)
+        .assert()
+        .success();
+    warder()
+        .args(["add", "json", "--branch", "main", "--git", &url])
+        .current_dir(&project)
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Fetched")
+                .and(predicate::str::contains("Checked out branch 'main'")),
+        );
+    let source = &lockfile(&project)["packages"]["json"]["source"];
+    assert_ne!(source["rev"].as_str(), Some(tagged.as_str()));
+}
+
+#[test]
+fn add_git_rejects_unknown_refs() {
+    let dir = TempDir::new("warder-add-git-missing").unwrap();
+    let (url, _) = bare_repo_fixture(dir.path());
+    let project = dir.path().join("app");
+    write_project(&project);
+
+    warder()
+        .args(["add", "json", "--tag", "v9.9.9", "--git", &url])
+        .current_dir(&project)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "Git tag 'v9.9.9' not found in {}",
+            url
+        )));
+
+    let manifest = fs::read_to_string(project.join("package.rl.toml")).unwrap();
+    assert!(!manifest.contains("json"));
+    assert!(!project.join("package.rl.lock").exists());
+}
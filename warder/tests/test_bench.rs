@@ -0,0 +1,59 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+const MANIFEST: &str = r#"[package]
+name = "bench-fixture"
+version = "0.1.0"
+entry = "src/main.rl"
+edition = "2025"
+"#;
+
+const SUM_BENCH: &str = r#"fun sum_to: (n: Int32) -> Int32 = {
+    n == 0 then { 0 } else { n + ((n - 1) sum_to) }
+}
+
+fun bench_sum: () -> Int32 = {
+    (100) sum_to
+}
+
+fun helper_is_not_a_bench: () -> Int32 = {
+    1
+}
+"#;
+
+fn write_project(root: &Path) {
+    let files = [
+        ("package.rl.toml", MANIFEST),
+        ("src/main.rl", "fun main: () -> Int32 = {\n    0\n}\n"),
+        ("benches/sum_bench.rl", SUM_BENCH),
+    ];
+    for (path, contents) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+#[test]
+fn bench_times_each_bench_function_for_the_requested_runs() {
+    let dir = TempDir::new("warder-bench-run").unwrap();
+    write_project(dir.path());
+
+    Command::cargo_bin("warder")
+        .unwrap()
+        .args(["bench", "--iterations", "5"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(r"bench_sum \.\.\. 5 runs, mean \S+, median \S+, min \S+")
+                .unwrap(),
+        )
+        .stdout(predicate::str::contains("helper_is_not_a_bench").not())
+        .stdout(predicate::str::contains(
+            "1 measured, 0 failed, 0 filtered out",
+        ));
+}
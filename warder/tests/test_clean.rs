@@ -0,0 +1,118 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+const MANIFEST: &str = r#"[package]
+name = "clean-fixture"
+version = "0.1.0"
+entry = "src/main.rl"
+edition = "2025"
+
+[dependencies]
+"#;
+
+const SOURCE: &str = "fun main: () -> Int32 = {\n    42\n}\n";
+
+fn write_project(root: &Path, manifest: &str) {
+    let files = [
+        ("package.rl.toml", manifest),
+        ("src/main.rl", SOURCE),
+        ("dist/clean-fixture-0.1.0.wat", "(module)"),
+        ("dist/clean-fixture-0.1.0.wasm", "\0asm"),
+        (".restrict-cache/wit/math.wit", "package math;"),
+        (".restrict-cache/git/lib/src/lib.rl", SOURCE),
+    ];
+    for (path, contents) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+fn warder() -> Command {
+    Command::cargo_bin("warder").unwrap()
+}
+
+#[test]
+fn clean_removes_build_output_and_keeps_the_cache() {
+    let dir = TempDir::new("warder-clean").unwrap();
+    write_project(dir.path(), MANIFEST);
+
+    warder()
+        .arg("clean")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed").and(predicate::str::contains("dist")));
+
+    assert!(!dir.path().join("dist").exists());
+    assert!(dir.path().join(".restrict-cache/wit/math.wit").exists());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("src/main.rl")).unwrap(),
+        SOURCE
+    );
+    assert!(dir.path().join("package.rl.toml").exists());
+
+    warder()
+        .arg("clean")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to clean"));
+}
+
+#[test]
+fn clean_all_also_clears_the_dependency_cache() {
+    let dir = TempDir::new("warder-clean-all").unwrap();
+    write_project(dir.path(), MANIFEST);
+
+    // From a subdirectory, the project root is still found
+    warder()
+        .args(["clean", "--all"])
+        .current_dir(dir.path().join("src"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".restrict-cache"));
+
+    assert!(!dir.path().join("dist").exists());
+    assert!(!dir.path().join(".restrict-cache").exists());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("src/main.rl")).unwrap(),
+        SOURCE
+    );
+}
+
+#[test]
+fn clean_refuses_an_output_directory_outside_the_project() {
+    let dir = TempDir::new("warder-clean-outside").unwrap();
+    let project = dir.path().join("project");
+    let outside = dir.path().join("elsewhere");
+    fs::create_dir_all(&outside).unwrap();
+    fs::write(outside.join("keep.txt"), "keep").unwrap();
+    write_project(
+        &project,
+        &format!("{}\n[build]\noutput = \"../elsewhere\"\n", MANIFEST),
+    );
+
+    warder()
+        .arg("clean")
+        .current_dir(&project)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not inside the project root"));
+    assert!(outside.join("keep.txt").exists());
+
+    // The project root itself is not a removable output directory either
+    write_project(
+        &project,
+        &format!("{}\n[build]\noutput = \".\"\n", MANIFEST),
+    );
+    warder()
+        .arg("clean")
+        .current_dir(&project)
+        .assert()
+        .failure();
+    assert!(project.join("src/main.rl").exists());
+}
@@ -0,0 +1,99 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+const MANIFEST: &str = r#"[package]
+name = "doctor-fixture"
+version = "0.1.0"
+entry = "src/main.rl"
+edition = "2025"
+
+[dependencies]
+"#;
+
+const VALID_SOURCE: &str = "fun main: () -> Int32 = {\n    val x = 40;\n    x + 2\n}\n";
+
+fn write_project(root: &Path, manifest: &str, files: &[(&str, &str)]) {
+    fs::write(root.join("package.rl.toml"), manifest).unwrap();
+    for (path, contents) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+fn warder() -> Command {
+    Command::cargo_bin("warder").unwrap()
+}
+
+#[test]
+fn doctor_passes_on_a_project_that_compiles() {
+    let dir = TempDir::new("warder-doctor-ok").unwrap();
+    write_project(dir.path(), MANIFEST, &[("src/main.rl", VALID_SOURCE)]);
+
+    warder()
+        .arg("doctor")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("src/main.rl"));
+}
+
+#[test]
+fn doctor_reports_the_broken_file_and_fails() {
+    let dir = TempDir::new("warder-doctor-broken").unwrap();
+    write_project(
+        dir.path(),
+        MANIFEST,
+        &[
+            ("src/main.rl", VALID_SOURCE),
+            (
+                "src/broken.rl",
+                "fun broken: () -> Int32 = {\n    true\n}\n",
+            ),
+        ],
+    );
+
+    warder()
+        .arg("doctor")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("src/main.rl"))
+        .stderr(predicate::str::contains("src/broken.rl: Type error"));
+}
+
+#[test]
+fn doctor_fails_when_a_dependency_path_is_missing() {
+    let dir = TempDir::new("warder-doctor-dep").unwrap();
+    let manifest = format!("{}local-lib = {{ path = \"../missing-lib\" }}\n", MANIFEST);
+    write_project(dir.path(), &manifest, &[("src/main.rl", VALID_SOURCE)]);
+
+    warder()
+        .arg("doctor")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Dependency 'local-lib' path not found",
+        ));
+}
+
+#[test]
+fn doctor_fails_on_an_unparseable_manifest() {
+    let dir = TempDir::new("warder-doctor-manifest").unwrap();
+    write_project(
+        dir.path(),
+        "[package\nname = ",
+        &[("src/main.rl", VALID_SOURCE)],
+    );
+
+    warder()
+        .arg("doctor")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid manifest"));
+}
@@ -0,0 +1,124 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+const MANIFEST: &str = r#"[package]
+name = "lock-fixture"
+version = "0.1.0"
+entry = "src/main.rl"
+edition = "2025"
+
+[dependencies]
+"#;
+
+const LIB_MANIFEST: &str = r#"[package]
+name = "local-lib"
+version = "0.3.1"
+entry = "src/lib.rl"
+edition = "2025"
+kind = "lib"
+"#;
+
+fn write_project(root: &Path) {
+    let files = [
+        ("package.rl.toml", MANIFEST),
+        ("src/main.rl", "fun main: () -> Int32 = {\n    42\n}\n"),
+        ("local-lib/package.rl.toml", LIB_MANIFEST),
+        (
+            "local-lib/src/lib.rl",
+            "export fun answer: () -> Int32 = {\n    42\n}\n",
+        ),
+    ];
+    for (path, contents) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+fn warder() -> Command {
+    Command::cargo_bin("warder").unwrap()
+}
+
+fn read_lock(root: &Path) -> toml::Table {
+    toml::from_str(&fs::read_to_string(root.join("package.rl.lock")).unwrap()).unwrap()
+}
+
+#[test]
+fn add_and_remove_update_the_lockfile() {
+    let dir = TempDir::new("warder-lock-add").unwrap();
+    write_project(dir.path());
+
+    warder()
+        .args(["add", "local-lib", "--path", "local-lib"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Locking local-lib 0.3.1"));
+
+    let lock = read_lock(dir.path());
+    let package = &lock["packages"]["local-lib"];
+    assert_eq!(package["version"].as_str(), Some("0.3.1"));
+    assert_eq!(package["source"]["type"].as_str(), Some("Path"));
+    assert_eq!(package["source"]["path"].as_str(), Some("local-lib"));
+    assert_eq!(package["hash"].as_str().unwrap().len(), 64);
+
+    warder()
+        .args(["remove", "local-lib"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removing local-lib 0.3.1"));
+
+    let lock = read_lock(dir.path());
+    assert!(lock
+        .get("packages")
+        .and_then(|packages| packages.get("local-lib"))
+        .is_none());
+}
+
+#[test]
+fn repro_build_rejects_a_tampered_lock_hash() {
+    let dir = TempDir::new("warder-lock-tamper").unwrap();
+    write_project(dir.path());
+
+    warder()
+        .args(["add", "local-lib", "--path", "local-lib"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let lock_path = dir.path().join("package.rl.lock");
+    let lock = fs::read_to_string(&lock_path).unwrap();
+    let hash = read_lock(dir.path())["packages"]["local-lib"]["hash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    fs::write(&lock_path, lock.replace(&hash, &"0".repeat(64))).unwrap();
+
+    for flag in ["--repro", "--locked"] {
+        warder()
+            .args(["build", flag])
+            .current_dir(dir.path())
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Dependency 'local-lib' content hash mismatch",
+            ));
+    }
+}
+
+#[test]
+fn repro_build_requires_a_lockfile() {
+    let dir = TempDir::new("warder-lock-missing").unwrap();
+    write_project(dir.path());
+
+    warder()
+        .args(["build", "--repro"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("requires package.rl.lock"));
+}
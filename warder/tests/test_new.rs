@@ -0,0 +1,85 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempdir::TempDir;
+
+fn warder() -> Command {
+    Command::cargo_bin("warder").unwrap()
+}
+
+#[test]
+fn new_lib_scaffolds_a_library_package() {
+    let dir = TempDir::new("warder-new-lib").unwrap();
+
+    warder()
+        .args(["new", "shapes", "--lib"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created library 'shapes'"));
+
+    let project = dir.path().join("shapes");
+    let manifest: toml::Table =
+        toml::from_str(&fs::read_to_string(project.join("package.rl.toml")).unwrap()).unwrap();
+    assert_eq!(manifest["package"]["kind"].as_str(), Some("lib"));
+    assert_eq!(manifest["package"]["entry"].as_str(), Some("src/lib.rl"));
+
+    let lib_source = fs::read_to_string(project.join("src/lib.rl")).unwrap();
+    assert!(lib_source.contains("export fun"));
+    assert!(!project.join("src/main.rl").exists());
+}
+
+#[test]
+fn new_without_lib_scaffolds_a_binary_package() {
+    let dir = TempDir::new("warder-new-bin").unwrap();
+
+    warder()
+        .args(["new", "app"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let project = dir.path().join("app");
+    let manifest = fs::read_to_string(project.join("package.rl.toml")).unwrap();
+    assert!(manifest.contains("kind = \"bin\""));
+    assert!(project.join("src/main.rl").exists());
+}
+
+#[test]
+fn library_build_does_not_require_main() {
+    let compiler = assert_cmd::cargo::cargo_bin("restrict_lang");
+    if !compiler.exists() {
+        eprintln!(
+            "skipping: restrict_lang compiler not built at {}",
+            compiler.display()
+        );
+        return;
+    }
+
+    let dir = TempDir::new("warder-build-lib").unwrap();
+    warder()
+        .args(["new", "shapes", "--lib"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let project = dir.path().join("shapes");
+    warder()
+        .arg("build")
+        .current_dir(&project)
+        .env("RESTRICT_LANG_BIN", &compiler)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Built shapes v0.1.0"));
+
+    let wat = fs::read_to_string(project.join("dist/shapes-0.1.0.wat")).unwrap();
+    assert!(wat.contains("(export \"add\""));
+    assert!(!wat.contains("(export \"_start\""));
+
+    warder()
+        .arg("run")
+        .current_dir(&project)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot run a library package"));
+}
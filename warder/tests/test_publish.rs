@@ -0,0 +1,95 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+const MANIFEST: &str = r#"[package]
+name = "publish-fixture"
+version = "0.1.0"
+entry = "src/lib.rl"
+edition = "2025"
+kind = "lib"
+"#;
+
+const LIB_SOURCE: &str = "export fun add: (a: Int32, b: Int32) -> Int32 = {\n    a + b\n}\n";
+
+fn write_project(root: &Path, manifest: &str) {
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("package.rl.toml"), manifest).unwrap();
+    fs::write(root.join("src/lib.rl"), LIB_SOURCE).unwrap();
+}
+
+/// `warder publish --dry-run` with the workspace compiler, or `None` when it
+/// is not built.
+fn warder_publish_dry_run() -> Option<Command> {
+    let compiler = assert_cmd::cargo::cargo_bin("restrict_lang");
+    if !compiler.exists() {
+        eprintln!(
+            "skipping: restrict_lang compiler not built at {}",
+            compiler.display()
+        );
+        return None;
+    }
+    let mut cmd = Command::cargo_bin("warder").unwrap();
+    cmd.args(["publish", "--dry-run"])
+        .env("RESTRICT_LANG_BIN", compiler);
+    Some(cmd)
+}
+
+#[test]
+fn publish_dry_run_builds_the_cage_and_prints_its_hash() {
+    let dir = TempDir::new("warder-publish-ok").unwrap();
+    write_project(dir.path(), &format!("{}license = \"MIT\"\n", MANIFEST));
+
+    let Some(mut publish) = warder_publish_dry_run() else {
+        return;
+    };
+    // Publishing builds reproducibly, so the lockfile must already exist.
+    Command::cargo_bin("warder")
+        .unwrap()
+        .arg("build")
+        .env(
+            "RESTRICT_LANG_BIN",
+            assert_cmd::cargo::cargo_bin("restrict_lang"),
+        )
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let output = publish.current_dir(dir.path()).output().unwrap();
+    assert!(
+        output.status.success(),
+        "dry run should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let cage = fs::read(dir.path().join("dist/publish-fixture-0.1.0.rgc")).unwrap();
+    let hash = {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(&cage))
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Dry run:"), "{stdout}");
+    assert!(
+        stdout.contains(&format!("Content hash (sha256): {hash}")),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn publish_dry_run_rejects_a_manifest_without_a_license() {
+    let dir = TempDir::new("warder-publish-license").unwrap();
+    write_project(dir.path(), MANIFEST);
+
+    let Some(mut publish) = warder_publish_dry_run() else {
+        return;
+    };
+    publish
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "missing required [package] field(s): license",
+        ));
+    assert!(!dir.path().join("dist").exists());
+}
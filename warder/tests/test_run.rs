@@ -0,0 +1,72 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+const MANIFEST: &str = r#"[package]
+name = "echo-fixture"
+version = "0.1.0"
+entry = "src/main.rl"
+edition = "2025"
+"#;
+
+const ECHO_MAIN: &str = r#"fun main: () -> () = {
+    val given = () args;
+    val first = given |> list_head match {
+        Some(arg) => { arg }
+        None => { "<none>" }
+    };
+    ("echo: " + first) |> println
+}
+"#;
+
+fn write_project(root: &Path) {
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("package.rl.toml"), MANIFEST).unwrap();
+    fs::write(root.join("src/main.rl"), ECHO_MAIN).unwrap();
+}
+
+/// `warder run` with the workspace compiler, or `None` when it is not built.
+fn warder_run() -> Option<Command> {
+    let compiler = assert_cmd::cargo::cargo_bin("restrict_lang");
+    if !compiler.exists() {
+        eprintln!(
+            "skipping: restrict_lang compiler not built at {}",
+            compiler.display()
+        );
+        return None;
+    }
+    let mut cmd = Command::cargo_bin("warder").unwrap();
+    cmd.arg("run").env("RESTRICT_LANG_BIN", compiler);
+    Some(cmd)
+}
+
+#[test]
+fn run_forwards_arguments_after_double_dash() {
+    let dir = TempDir::new("warder-run-args").unwrap();
+    write_project(dir.path());
+
+    let Some(mut run) = warder_run() else {
+        return;
+    };
+    run.args(["--", "--loud", "quiet"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("echo: --loud\n"));
+}
+
+#[test]
+fn run_without_arguments_sees_only_the_program_name() {
+    let dir = TempDir::new("warder-run-no-args").unwrap();
+    write_project(dir.path());
+
+    let Some(mut run) = warder_run() else {
+        return;
+    };
+    run.current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("echo: <none>\n"));
+}
@@ -0,0 +1,124 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+const MANIFEST: &str = r#"[package]
+name = "test-fixture"
+version = "0.1.0"
+entry = "src/main.rl"
+edition = "2025"
+"#;
+
+const MATH_TEST: &str = r#"fun add: (a: Int32, b: Int32) -> Int32 = {
+    a + b
+}
+
+fun test_add_passes: () -> Boolean = {
+    (2, 3) add == 5
+}
+
+fun test_add_fails: () -> () = {
+    ((2, 3) add, 6, "2 + 3 should be 6") assert_eq
+}
+"#;
+
+fn write_project(root: &Path) {
+    let files = [
+        ("package.rl.toml", MANIFEST),
+        ("src/main.rl", "fun main: () -> Int32 = {\n    0\n}\n"),
+        ("tests/math_test.rl", MATH_TEST),
+    ];
+    for (path, contents) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+fn warder() -> Command {
+    Command::cargo_bin("warder").unwrap()
+}
+
+#[test]
+fn test_runs_each_test_function_and_reports_failures() {
+    let dir = TempDir::new("warder-test-run").unwrap();
+    write_project(dir.path());
+
+    warder()
+        .arg("test")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("test_add_passes ... ok"))
+        .stdout(predicate::str::contains("test_add_fails ... FAILED"))
+        .stderr(predicate::str::contains("1 passed, 1 failed"));
+}
+
+#[test]
+fn test_filter_selects_tests_by_name() {
+    let dir = TempDir::new("warder-test-filter").unwrap();
+    write_project(dir.path());
+
+    warder()
+        .args(["test", "passes"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test_add_passes ... ok"))
+        .stdout(predicate::str::contains("test_add_fails").not());
+}
+
+const PROPERTY_TEST: &str = r#"fun prop_reverse_twice: (xs: List<Int32>) -> Boolean = {
+    (xs |> list_reverse |> list_reverse) list_length <= 100
+}
+
+fun prop_short_lists: (xs: List<Int32>) -> Boolean = {
+    (xs) list_length < 3
+}
+"#;
+
+#[test]
+fn test_runs_properties_and_shrinks_counterexamples() {
+    let dir = TempDir::new("warder-test-property").unwrap();
+    write_project(dir.path());
+    fs::remove_file(dir.path().join("tests/math_test.rl")).unwrap();
+    fs::write(dir.path().join("tests/property_test.rl"), PROPERTY_TEST).unwrap();
+
+    warder()
+        .arg("test")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("prop_reverse_twice ... ok"))
+        .stdout(predicate::str::contains("prop_short_lists ... FAILED"))
+        .stderr(predicate::str::contains("counterexample: ([0, 0, 0])"));
+}
+
+const SORT_TEST: &str = r#"fun digits: (xs: List<Int32>) -> Int32 = {
+    (|acc, value| acc * 10 + value, 0, xs) list_fold
+}
+
+fun test_sort_ascending: () -> Boolean = {
+    val numbers = [3, 1, 2];
+    val sorted = (|a, b| a - b, numbers) list_sort;
+    val total = sorted |> digits;
+    total == 123
+}
+"#;
+
+#[test]
+fn test_runs_list_sort_with_comparator() {
+    let dir = TempDir::new("warder-test-sort").unwrap();
+    write_project(dir.path());
+    fs::remove_file(dir.path().join("tests/math_test.rl")).unwrap();
+    fs::write(dir.path().join("tests/sort_test.rl"), SORT_TEST).unwrap();
+
+    warder()
+        .arg("test")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test_sort_ascending ... ok"));
+}